@@ -35,6 +35,7 @@
 //! Don't just copy - read and understand! Every line is explained.
 
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
 /// Classifies a number using match expressions and guards.
 ///
@@ -262,4 +263,172 @@ pub fn decide_action(input: &str, game_won: bool) -> &'static str {
     todo!("Decide action from command and game state")
 }
 
+/// The result of one call to [`GuessingGame::guess`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessOutcome {
+    /// The input wasn't a number (message from `validate_guess`).
+    /// Doesn't use up an attempt.
+    Invalid(String),
+    TooLow {
+        attempts_left: u32,
+    },
+    TooHigh {
+        attempts_left: u32,
+    },
+    Correct {
+        attempts_used: u32,
+    },
+    /// The last attempt was wrong (or was already used up).
+    OutOfAttempts {
+        secret: i32,
+    },
+}
+
+/// A guessing game with a limited number of attempts.
+///
+/// This struct teaches you about:
+/// - Keeping state in a struct instead of local variables in `main`
+/// - Returning enums with data from methods
+/// - `&mut self` vs `&self` methods
+///
+/// # Example
+/// ```ignore
+/// use control_flow::{GuessingGame, GuessOutcome};
+/// let mut game = GuessingGame::new(42, 3);
+/// assert_eq!(game.guess("10"), GuessOutcome::TooLow { attempts_left: 2 });
+/// assert!(matches!(game.guess("abc"), GuessOutcome::Invalid(_)));
+/// assert_eq!(game.guess("42"), GuessOutcome::Correct { attempts_used: 2 });
+/// ```
+#[derive(Debug, Clone)]
+pub struct GuessingGame {
+    secret: i32,
+    max_attempts: u32,
+    history: Vec<i32>,
+    won: bool,
+}
+
+impl GuessingGame {
+    pub fn new(secret: i32, max_attempts: u32) -> Self {
+        GuessingGame {
+            secret,
+            max_attempts,
+            history: Vec::new(),
+            won: false,
+        }
+    }
+
+    /// Validates `input`, then compares it with the secret.
+    ///
+    /// # Hint
+    /// Handle the game-over states first (they repeat forever), then use
+    /// `validate_guess` and `compare_guess`. Only valid guesses count.
+    pub fn guess(&mut self, input: &str) -> GuessOutcome {
+        // TODO: Return the terminal outcome if the game is over, otherwise
+        // validate, record, and compare the guess.
+        let _ = input;
+        todo!("Play one guess")
+    }
+
+    pub fn is_over(&self) -> bool {
+        // TODO: Won, or no attempts left.
+        todo!("Check if the game is over")
+    }
+
+    pub fn is_won(&self) -> bool {
+        // TODO: Return whether the secret was guessed.
+        todo!("Check if the game was won")
+    }
+
+    pub fn attempts_used(&self) -> u32 {
+        // TODO: Count valid guesses.
+        todo!("Count attempts used")
+    }
+
+    pub fn attempts_left(&self) -> u32 {
+        // TODO: max_attempts minus attempts used (never below zero).
+        todo!("Count attempts left")
+    }
+
+    pub fn history(&self) -> &[i32] {
+        // TODO: Return the valid guesses made so far.
+        todo!("Return guess history")
+    }
+}
+
+/// Counts how many numbers fall into each `classify_number` category.
+///
+/// Only categories that occur get a key.
+///
+/// # Example
+/// ```ignore
+/// use control_flow::classify_all;
+/// let counts = classify_all(&[0, 3, 7, 50, -2]);
+/// assert_eq!(counts["small"], 2);
+/// assert_eq!(counts.get("large"), None);
+/// ```
+pub fn classify_all(numbers: &[i32]) -> HashMap<&'static str, usize> {
+    // TODO: Loop over the numbers and bump a counter per category.
+    // Hint: `*counts.entry(classify_number(n)).or_insert(0) += 1`.
+    let _ = numbers;
+    todo!("Count numbers per category")
+}
+
+/// How a slice of numbers splits across the `describe_number` categories.
+///
+/// "one" counts as odd; negative numbers only count as `negative`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Distribution {
+    pub even: usize,
+    pub odd: usize,
+    pub negative: usize,
+    pub zero: usize,
+}
+
+impl Distribution {
+    pub fn total(&self) -> usize {
+        // TODO: Sum the four counts.
+        todo!("Total numbers counted")
+    }
+}
+
+/// Tallies `numbers` by `describe_number` category.
+///
+/// # Example
+/// ```ignore
+/// use control_flow::{describe_distribution, Distribution};
+/// let dist = describe_distribution(&[0, 1, 2, 3, -4]);
+/// assert_eq!(dist, Distribution { even: 1, odd: 2, negative: 1, zero: 1 });
+/// ```
+pub fn describe_distribution(numbers: &[i32]) -> Distribution {
+    // TODO: Match on `describe_number(n)` and bump the matching field.
+    // Hint: The `_` arm can't happen - use `unreachable!`.
+    let _ = numbers;
+    todo!("Build the distribution")
+}
+
+/// The most numbers `fizzbuzz_range` will produce in one call.
+pub const FIZZBUZZ_MAX_LEN: usize = 10_000;
+
+/// FizzBuzz for every integer from `start` to `end` (both inclusive).
+///
+/// Multiples of 15 become "FizzBuzz", of 3 "Fizz", of 5 "Buzz"; anything
+/// else is the number itself.
+///
+/// # Returns
+/// - `Ok(vec![])` if `start > end`
+/// - `Err(message)` if the range holds more than `FIZZBUZZ_MAX_LEN` numbers
+///
+/// # Example
+/// ```ignore
+/// use control_flow::fizzbuzz_range;
+/// assert_eq!(fizzbuzz_range(9, 11).unwrap(), ["Fizz", "Buzz", "11"]);
+/// ```
+pub fn fizzbuzz_range(start: i32, end: i32) -> Result<Vec<String>, String> {
+    // TODO: Validate the range, then loop over `start..=end`.
+    // Hint: Compute the length in i64 so huge ranges can't overflow, and
+    // `match (n % 3, n % 5)` with the `(0, 0)` arm first.
+    let _ = (start, end);
+    todo!("Produce FizzBuzz lines for a range")
+}
+
 pub mod solution;
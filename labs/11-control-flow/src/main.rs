@@ -3,8 +3,10 @@
 // This program demonstrates Rust's control flow structures through a number
 // guessing game. We'll explore if/else, loops, match expressions, and functions.
 
+use control_flow::solution::{
+    classify_all, describe_distribution, fizzbuzz_range, GuessOutcome, GuessingGame,
+};
 use std::io;
-use std::cmp::Ordering;
 
 fn main() {
     println!("=== Rust Control Flow & Functions ===\n");
@@ -157,6 +159,17 @@ fn demonstrate_match() {
     // Match is EXHAUSTIVE - you must handle all possible cases
     // The compiler will error if you forget a case!
 
+    // Loops + match together: aggregate a whole slice
+    let numbers = [0, -4, 1, 8, 42, 150, 7];
+    let mut counts: Vec<_> = classify_all(&numbers).into_iter().collect();
+    counts.sort();
+    println!("Categories of {:?}: {:?}", numbers, counts);
+    println!("Distribution: {:?}", describe_distribution(&numbers));
+    match fizzbuzz_range(10, 15) {
+        Ok(lines) => println!("FizzBuzz 10..=15: {}", lines.join(" ")),
+        Err(message) => println!("FizzBuzz failed: {}", message),
+    }
+
     println!();
 }
 
@@ -209,48 +222,49 @@ fn guessing_game() {
     // In a real game, you'd use: rand::thread_rng().gen_range(1..=100)
     let secret_number = 42;
 
-    println!("I'm thinking of a number between 1 and 100.");
+    // The game state (secret, attempts, history) lives in a struct from the
+    // library; main only reads input and prints what happened.
+    let mut game = GuessingGame::new(secret_number, 7);
+
+    println!("I'm thinking of a number between 1 and 100. You have 7 tries.");
     println!("(Hint: it's 42 - but try different numbers to see the logic!)");
 
-    // Infinite loop - we'll break when they guess correctly
-    loop {
+    // Loop until the game says it's over
+    while !game.is_over() {
         println!("\nPlease input your guess:");
 
         // Create a new mutable String to store the input
         let mut guess = String::new();
 
         // Read a line from stdin
-        // read_line returns a Result<usize, Error>
-        // We use .expect() to crash if reading fails
-        io::stdin()
+        // read_line returns a Result<usize, Error>; Ok(0) means end of input
+        let bytes_read = io::stdin()
             .read_line(&mut guess)
             .expect("Failed to read line");
+        if bytes_read == 0 {
+            println!("No more input - bye!");
+            return;
+        }
 
-        // Convert the string to a number
-        // trim() removes whitespace (including the newline)
-        // parse() converts string to the type we specify
-        // This returns Result<i32, ParseIntError>
-        let guess: i32 = match guess.trim().parse() {
-            Ok(num) => num,  // Successfully parsed
-            Err(_) => {
-                println!("Please enter a valid number!");
-                continue;  // Skip to next loop iteration
+        // match on the outcome enum: every variant must be handled
+        match game.guess(&guess) {
+            GuessOutcome::Invalid(message) => println!("{}!", message),
+            GuessOutcome::TooLow { attempts_left } => {
+                println!("Too small! ({} tries left)", attempts_left)
             }
-        };
-
-        println!("You guessed: {}", guess);
-
-        // Compare the guess to the secret number
-        // cmp() returns an Ordering enum: Less, Greater, or Equal
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Equal => {
-                println!("You win! 🎉");
-                break;  // Exit the loop
+            GuessOutcome::TooHigh { attempts_left } => {
+                println!("Too big! ({} tries left)", attempts_left)
+            }
+            GuessOutcome::Correct { attempts_used } => {
+                println!("You win in {} tries! 🎉", attempts_used)
+            }
+            GuessOutcome::OutOfAttempts { secret } => {
+                println!("Out of tries! The number was {}.", secret)
             }
         }
     }
+
+    println!("Your guesses: {:?}", game.history());
 }
 
 // ============================================================================
@@ -80,11 +80,16 @@
 //! - `validate_guess`: O(n) where n = length of string (for parsing)
 //! - `count_divisions`: O(log n) where n = input number
 //! - `sum_range`: O(n) where n = number of integers in range
+//! - `classify_all`, `describe_distribution`: O(n) where n = slice length
+//! - `fizzbuzz_range`: O(n) where n = number of integers in range
 //!
 //! ## Space Complexity
-//! - All functions use O(1) space (just a few variables)
+//! - The single-number functions use O(1) space (just a few variables)
+//! - The aggregates use O(1) extra space (at most five categories), except
+//!   `fizzbuzz_range`, which returns one `String` per number
 
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
 /// Classifies a number using match expressions with pattern guards.
 ///
@@ -982,3 +987,283 @@ pub fn decide_action(input: &str, game_won: bool) -> &'static str {
         _ => "invalid command, please try again",
     }
 }
+
+/// The result of one call to [`GuessingGame::guess`].
+///
+/// ## Why an Enum?
+///
+/// Each outcome carries different data: a hint needs `attempts_left`, a win
+/// needs `attempts_used`, a loss reveals the `secret`. An enum lets every
+/// variant hold exactly the data that makes sense for it, and `match` forces
+/// the caller to handle all five cases.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessOutcome {
+    /// The input wasn't a number (message from `validate_guess`).
+    /// Doesn't use up an attempt.
+    Invalid(String),
+    TooLow {
+        attempts_left: u32,
+    },
+    TooHigh {
+        attempts_left: u32,
+    },
+    Correct {
+        attempts_used: u32,
+    },
+    /// The last attempt was wrong (or was already used up).
+    OutOfAttempts {
+        secret: i32,
+    },
+}
+
+/// A guessing game with a limited number of attempts.
+///
+/// ## What This Struct Does
+///
+/// Holds the game state so `main` only has to read input and print outcomes:
+/// ```ignore
+/// use control_flow::solution::{GuessingGame, GuessOutcome};
+///
+/// let mut game = GuessingGame::new(42, 3);
+/// assert_eq!(game.guess("10"), GuessOutcome::TooLow { attempts_left: 2 });
+/// assert!(matches!(game.guess("abc"), GuessOutcome::Invalid(_)));
+/// assert_eq!(game.guess("42"), GuessOutcome::Correct { attempts_used: 2 });
+/// assert!(game.is_over());
+/// ```
+///
+/// ## Terminal States
+///
+/// Once the game is won or out of attempts, further guesses change nothing:
+/// they return the same `Correct`/`OutOfAttempts` outcome again. That way a
+/// caller that forgets to check `is_over()` still gets a sensible answer.
+///
+/// ## Ownership & Borrowing Analysis
+///
+/// - `guess(&mut self, ...)` - MUTABLY borrows the game
+///   - It records the guess in `history`, so it must modify state
+/// - `history(&self) -> &[i32]` - returns a BORROWED slice
+///   - The caller can look at past guesses without copying the Vec
+#[derive(Debug, Clone)]
+pub struct GuessingGame {
+    secret: i32,
+    max_attempts: u32,
+    history: Vec<i32>,
+    won: bool,
+}
+
+impl GuessingGame {
+    pub fn new(secret: i32, max_attempts: u32) -> Self {
+        GuessingGame {
+            secret,
+            max_attempts,
+            history: Vec::new(),
+            won: false,
+        }
+    }
+
+    /// Validates `input`, then compares it with the secret.
+    pub fn guess(&mut self, input: &str) -> GuessOutcome {
+        // Check terminal states first, so even invalid input after the end
+        // reports the final result.
+        if self.won {
+            return GuessOutcome::Correct {
+                attempts_used: self.attempts_used(),
+            };
+        }
+        if self.attempts_left() == 0 {
+            return GuessOutcome::OutOfAttempts {
+                secret: self.secret,
+            };
+        }
+
+        let guess = match validate_guess(input) {
+            Ok(num) => num,
+            Err(message) => return GuessOutcome::Invalid(message),
+        };
+        self.history.push(guess);
+
+        let attempts_left = self.attempts_left();
+        match compare_guess(guess, self.secret) {
+            Ordering::Equal => {
+                self.won = true;
+                GuessOutcome::Correct {
+                    attempts_used: self.attempts_used(),
+                }
+            }
+            _ if attempts_left == 0 => GuessOutcome::OutOfAttempts {
+                secret: self.secret,
+            },
+            Ordering::Less => GuessOutcome::TooLow { attempts_left },
+            Ordering::Greater => GuessOutcome::TooHigh { attempts_left },
+        }
+    }
+
+    /// True once the player has won or used every attempt.
+    pub fn is_over(&self) -> bool {
+        self.won || self.attempts_left() == 0
+    }
+
+    /// True if the secret was guessed.
+    pub fn is_won(&self) -> bool {
+        self.won
+    }
+
+    /// Number of valid guesses made so far.
+    pub fn attempts_used(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    pub fn attempts_left(&self) -> u32 {
+        self.max_attempts.saturating_sub(self.attempts_used())
+    }
+
+    /// Every valid guess, in the order it was made.
+    pub fn history(&self) -> &[i32] {
+        &self.history
+    }
+}
+
+/// Counts how many numbers fall into each `classify_number` category.
+///
+/// ## What This Function Does
+///
+/// Runs every number through `classify_number` and tallies the results:
+/// ```ignore
+/// use control_flow::solution::classify_all;
+///
+/// let counts = classify_all(&[0, 3, 7, 50, -2]);
+/// assert_eq!(counts["small"], 2);
+/// assert_eq!(counts.get("large"), None); // no large numbers, no key
+/// ```
+///
+/// Only categories that actually occur get a key, so an empty slice gives
+/// an empty map.
+///
+/// ## Why `&'static str` Keys Work
+///
+/// `classify_number` returns string literals, which live for the whole
+/// program. The map can hold them directly without allocating a `String`
+/// per key.
+///
+/// ## The `entry` API
+///
+/// `counts.entry(key).or_insert(0)` returns a `&mut usize` to the existing
+/// count, or to a fresh 0 if the key is new. One lookup, no `if contains`.
+pub fn classify_all(numbers: &[i32]) -> HashMap<&'static str, usize> {
+    let mut counts = HashMap::new();
+    for &n in numbers {
+        *counts.entry(classify_number(n)).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// How a slice of numbers splits across the `describe_number` categories.
+///
+/// "one" counts as odd. Negative numbers only count as `negative`, never as
+/// even or odd, so the four fields always add up to the slice length.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Distribution {
+    pub even: usize,
+    pub odd: usize,
+    pub negative: usize,
+    pub zero: usize,
+}
+
+impl Distribution {
+    /// Total numbers counted.
+    pub fn total(&self) -> usize {
+        self.even + self.odd + self.negative + self.zero
+    }
+}
+
+/// Tallies `numbers` by `describe_number` category.
+///
+/// ## What This Function Does
+///
+/// ```ignore
+/// use control_flow::solution::{describe_distribution, Distribution};
+///
+/// let dist = describe_distribution(&[0, 1, 2, 3, -4]);
+/// assert_eq!(dist, Distribution { even: 1, odd: 2, negative: 1, zero: 1 });
+/// ```
+///
+/// ## Exhaustive Match on Strings
+///
+/// `describe_number` returns a `&str`, and the compiler can't know it only
+/// ever returns five values. The `_` arm is still required; it's
+/// `unreachable!` because hitting it would mean the two functions disagree,
+/// which is a bug, not bad input.
+pub fn describe_distribution(numbers: &[i32]) -> Distribution {
+    let mut dist = Distribution::default();
+    for &n in numbers {
+        match describe_number(n) {
+            "even" => dist.even += 1,
+            "odd" | "one" => dist.odd += 1,
+            "negative" => dist.negative += 1,
+            "zero" => dist.zero += 1,
+            other => unreachable!("describe_number returned {:?}", other),
+        }
+    }
+    dist
+}
+
+/// The most numbers `fizzbuzz_range` will produce in one call.
+pub const FIZZBUZZ_MAX_LEN: usize = 10_000;
+
+/// FizzBuzz for every integer from `start` to `end` (both inclusive).
+///
+/// ## What This Function Does
+///
+/// - multiple of 15 -> "FizzBuzz"
+/// - multiple of 3 -> "Fizz"
+/// - multiple of 5 -> "Buzz"
+/// - anything else -> the number itself
+///
+/// ```ignore
+/// use control_flow::solution::fizzbuzz_range;
+///
+/// assert_eq!(fizzbuzz_range(9, 11).unwrap(), ["Fizz", "Buzz", "11"]);
+/// assert!(fizzbuzz_range(5, 1).unwrap().is_empty());
+/// assert!(fizzbuzz_range(1, 20_000).is_err());
+/// ```
+///
+/// ## Validation
+///
+/// - `start > end` is an empty range, so the result is an empty `Vec`
+///   (the same thing `start..=end` yields in a `for` loop).
+/// - More than `FIZZBUZZ_MAX_LEN` numbers is an `Err`, so a typo like
+///   `fizzbuzz_range(i32::MIN, i32::MAX)` can't try to allocate four
+///   billion strings.
+///
+/// The length is computed in `i64`: `end - start` in `i32` overflows for
+/// exactly the huge ranges we want to reject.
+///
+/// ## Matching on a Tuple
+///
+/// `match (n % 3, n % 5)` checks both remainders at once. The `(0, 0)` arm
+/// comes first; with the arms in any other order, 15 would print "Fizz".
+pub fn fizzbuzz_range(start: i32, end: i32) -> Result<Vec<String>, String> {
+    if start > end {
+        return Ok(Vec::new());
+    }
+
+    let len = i64::from(end) - i64::from(start) + 1;
+    if len > FIZZBUZZ_MAX_LEN as i64 {
+        return Err(format!(
+            "range {}..={} has {} numbers; the limit is {}",
+            start, end, len, FIZZBUZZ_MAX_LEN
+        ));
+    }
+
+    let mut lines = Vec::with_capacity(len as usize);
+    for n in start..=end {
+        let line = match (n % 3, n % 5) {
+            (0, 0) => "FizzBuzz".to_string(),
+            (0, _) => "Fizz".to_string(),
+            (_, 0) => "Buzz".to_string(),
+            _ => n.to_string(),
+        };
+        lines.push(line);
+    }
+    Ok(lines)
+}
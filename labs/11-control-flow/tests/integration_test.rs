@@ -125,3 +125,141 @@ fn test_decide_action_lost() {
     let result = decide_action("quit", false);
     assert!(!result.is_empty(), "Should return some action");
 }
+
+#[test]
+fn test_guessing_game_win_on_last_attempt() {
+    let mut game = GuessingGame::new(42, 3);
+    assert_eq!(game.guess("10"), GuessOutcome::TooLow { attempts_left: 2 });
+    assert_eq!(game.guess("90"), GuessOutcome::TooHigh { attempts_left: 1 });
+    assert_eq!(game.guess("42"), GuessOutcome::Correct { attempts_used: 3 });
+    assert!(game.is_over(), "Game should end after a correct guess");
+    assert!(game.is_won());
+    assert_eq!(game.history(), &[10, 90, 42]);
+}
+
+#[test]
+fn test_guessing_game_out_of_attempts() {
+    let mut game = GuessingGame::new(42, 2);
+    assert_eq!(game.guess("1"), GuessOutcome::TooLow { attempts_left: 1 });
+    assert_eq!(game.guess("2"), GuessOutcome::OutOfAttempts { secret: 42 });
+    assert!(game.is_over());
+    assert!(!game.is_won());
+    assert_eq!(game.attempts_used(), 2);
+}
+
+#[test]
+fn test_guessing_game_invalid_input_is_free() {
+    let mut game = GuessingGame::new(7, 1);
+    assert_eq!(
+        game.guess("seven"),
+        GuessOutcome::Invalid("Please enter a valid number".to_string())
+    );
+    assert_eq!(
+        game.attempts_used(),
+        0,
+        "Invalid input must not use an attempt"
+    );
+    assert!(game.history().is_empty());
+    assert_eq!(
+        game.guess(" 7 "),
+        GuessOutcome::Correct { attempts_used: 1 }
+    );
+}
+
+#[test]
+fn test_guessing_game_guess_after_game_over() {
+    let mut lost = GuessingGame::new(5, 1);
+    lost.guess("4");
+    assert_eq!(lost.guess("5"), GuessOutcome::OutOfAttempts { secret: 5 });
+    assert_eq!(
+        lost.guess("junk"),
+        GuessOutcome::OutOfAttempts { secret: 5 }
+    );
+    assert_eq!(
+        lost.history(),
+        &[4],
+        "Guesses after the end are not recorded"
+    );
+
+    let mut won = GuessingGame::new(5, 3);
+    won.guess("5");
+    assert_eq!(won.guess("6"), GuessOutcome::Correct { attempts_used: 1 });
+    assert_eq!(won.attempts_used(), 1);
+}
+
+#[test]
+fn test_guessing_game_zero_attempts() {
+    let mut game = GuessingGame::new(5, 0);
+    assert!(game.is_over());
+    assert_eq!(game.guess("5"), GuessOutcome::OutOfAttempts { secret: 5 });
+}
+
+// ============================================================================
+// AGGREGATE TESTS
+// ============================================================================
+
+#[test]
+fn test_classify_all_counts_categories() {
+    let numbers = [0, -5, -1, 1, 10, 11, 100, 101, 5_000, 7, 0];
+    let counts = classify_all(&numbers);
+    assert_eq!(counts["zero"], 2);
+    assert_eq!(counts["negative"], 2);
+    assert_eq!(counts["small"], 3);
+    assert_eq!(counts["medium"], 2);
+    assert_eq!(counts["large"], 2);
+    assert_eq!(counts.values().sum::<usize>(), numbers.len());
+
+    let counts = classify_all(&[3, 4]);
+    assert_eq!(counts.len(), 1, "Only categories that occur get a key");
+    assert!(classify_all(&[]).is_empty());
+}
+
+#[test]
+fn test_describe_distribution() {
+    let dist = describe_distribution(&[0, 1, 2, 3, 4, -4, -3, 0, 99]);
+    assert_eq!(
+        dist,
+        Distribution {
+            even: 2,
+            odd: 3,
+            negative: 2,
+            zero: 2,
+        }
+    );
+    assert_eq!(dist.total(), 9);
+    assert_eq!(describe_distribution(&[]), Distribution::default());
+}
+
+#[test]
+fn test_fizzbuzz_golden_1_to_15() {
+    let expected = [
+        "1", "2", "Fizz", "4", "Buzz", "Fizz", "7", "8", "Fizz", "Buzz", "11", "Fizz", "13", "14",
+        "FizzBuzz",
+    ];
+    assert_eq!(fizzbuzz_range(1, 15).unwrap(), expected);
+}
+
+#[test]
+fn test_fizzbuzz_negative_and_single() {
+    assert_eq!(
+        fizzbuzz_range(-3, 0).unwrap(),
+        ["Fizz", "-2", "-1", "FizzBuzz"]
+    );
+    assert_eq!(fizzbuzz_range(7, 7).unwrap(), ["7"]);
+}
+
+#[test]
+fn test_fizzbuzz_empty_range() {
+    assert!(fizzbuzz_range(15, 1).unwrap().is_empty());
+    assert!(fizzbuzz_range(i32::MAX, i32::MIN).unwrap().is_empty());
+}
+
+#[test]
+fn test_fizzbuzz_cap() {
+    let max = FIZZBUZZ_MAX_LEN as i32;
+    assert_eq!(fizzbuzz_range(1, max).unwrap().len(), FIZZBUZZ_MAX_LEN);
+    assert!(fizzbuzz_range(1, max + 1).is_err());
+    // The length must not overflow i32 on the way to the error.
+    let err = fizzbuzz_range(i32::MIN, i32::MAX).unwrap_err();
+    assert!(err.contains("limit"), "{}", err);
+}
@@ -11,6 +11,7 @@
 //! - Your balance = sum of all UTXOs you can spend
 
 use std::collections::HashMap;
+use std::fmt;
 
 // ============================================================================
 // DATA STRUCTURES
@@ -145,6 +146,102 @@ pub fn apply_transaction(utxo_set: &mut UtxoSet, tx: &Transaction) -> Result<u64
     todo!("Apply transaction to UTXO set")
 }
 
+// ============================================================================
+// BATCH APPLICATION
+// ============================================================================
+
+/// Why a batch of transactions was rejected.
+///
+/// Every variant names the transaction that failed (`txid`) and, where it
+/// makes sense, the outpoint (`"txid:index"`) that caused the problem.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UtxoError {
+    /// The outpoint was already consumed by an earlier input in the same batch.
+    DoubleSpend { txid: String, outpoint: UtxoId },
+    /// The outpoint is not in the UTXO set and was not created earlier in the batch.
+    UnknownOutpoint { txid: String, outpoint: UtxoId },
+    /// The spender does not own the outpoint.
+    OwnershipViolation {
+        txid: String,
+        outpoint: UtxoId,
+        spender: Address,
+        owner: Address,
+    },
+    /// The outputs are worth more than the inputs.
+    InsufficientInputs {
+        txid: String,
+        inputs: u64,
+        outputs: u64,
+    },
+}
+
+impl fmt::Display for UtxoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UtxoError::DoubleSpend { txid, outpoint } => {
+                write!(f, "{txid}: double spend of {outpoint}")
+            }
+            UtxoError::UnknownOutpoint { txid, outpoint } => {
+                write!(f, "{txid}: UTXO {outpoint} not found")
+            }
+            UtxoError::OwnershipViolation {
+                txid,
+                outpoint,
+                spender,
+                owner,
+            } => write!(
+                f,
+                "{txid}: {spender} tried to spend {outpoint} owned by {owner}"
+            ),
+            UtxoError::InsufficientInputs {
+                txid,
+                inputs,
+                outputs,
+            } => write!(f, "{txid}: outputs ({outputs}) exceed inputs ({inputs})"),
+        }
+    }
+}
+
+impl std::error::Error for UtxoError {}
+
+/// What a successfully applied batch did to the UTXO set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AppliedSummary {
+    /// Number of UTXOs removed from the set.
+    pub consumed: usize,
+    /// Number of UTXOs added to the set.
+    pub created: usize,
+    /// Sum of `inputs - outputs` over every transaction in the batch.
+    pub total_fees: u64,
+}
+
+/// Applies a batch of transactions (think: one block) atomically.
+///
+/// Transactions are validated in order against the *evolving* set, so an
+/// output created by `txs[0]` can be spent by `txs[1]`. If any transaction
+/// is invalid, every change made by the earlier ones is rolled back and the
+/// set is left exactly as it was.
+///
+/// ## Double Spend vs Unknown Outpoint
+/// An outpoint that is missing from the set could be a typo or a coin that
+/// was spent in an earlier block. We only call it a `DoubleSpend` when we
+/// saw it consumed *within this batch* (including twice in the same
+/// transaction) because that's the case we can prove.
+///
+/// ## Rollback
+/// Instead of cloning the whole set up front (Bitcoin's would be gigabytes),
+/// we keep an undo log of `(id, previous value)` and replay it backwards on
+/// failure.
+pub fn apply_transactions(
+    utxo_set: &mut UtxoSet,
+    txs: &[Transaction],
+) -> Result<AppliedSummary, UtxoError> {
+    // TODO: Validate each tx against the evolving set (track outpoints spent in
+    // this batch), record an undo log, and roll everything back on the first error.
+    let _ = (utxo_set, txs);
+    todo!("Apply a batch of transactions atomically")
+}
+
 /// Calculate the balance of an address by summing all UTXOs they own.
 ///
 /// In the UTXO model, there's no single "account balance" variable.
@@ -171,4 +268,88 @@ pub fn create_genesis_utxo(utxo_set: &mut UtxoSet, id: &str, owner: &str, amount
     todo!("Insert a genesis UTXO")
 }
 
+// ============================================================================
+// LEDGER (PER-ADDRESS HISTORY)
+// ============================================================================
+
+/// One line of an address's history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntry {
+    /// The transaction that changed the balance.
+    pub txid: String,
+    /// Net change: outputs received minus inputs spent (change included).
+    pub delta: i64,
+    /// The address's balance once the transaction was applied.
+    pub balance_after: u64,
+    /// Caller-supplied time the transaction was applied (e.g. a block time).
+    pub timestamp: u64,
+}
+
+/// Per-address history of every transaction applied through it.
+#[derive(Debug, Clone, Default)]
+pub struct Ledger {
+    entries: HashMap<Address, Vec<LedgerEntry>>,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Ledger::default()
+    }
+
+    /// Applies `history` in order to `utxo_set`, recording each transaction.
+    pub fn replay(
+        utxo_set: &mut UtxoSet,
+        history: &[(Transaction, u64)],
+    ) -> Result<Ledger, String> {
+        // TODO: Start from an empty ledger and `apply` each transaction,
+        // stopping at the first error.
+        let _ = (utxo_set, history);
+        todo!("Replay a transaction history")
+    }
+
+    /// Like `create_genesis_utxo`, but records the new coins under the part
+    /// of `id` before the `:` (so `"genesis:0"` is recorded as `genesis`).
+    pub fn create_genesis(
+        &mut self,
+        utxo_set: &mut UtxoSet,
+        id: &str,
+        owner: &str,
+        amount: u64,
+        timestamp: u64,
+    ) {
+        let _ = (&self.entries, utxo_set, id, owner, amount, timestamp);
+        todo!("Create and record a genesis UTXO")
+    }
+
+    /// Applies `tx` with `apply_transaction` and, if it succeeds, adds one
+    /// entry for every address it touched.
+    pub fn apply(
+        &mut self,
+        utxo_set: &mut UtxoSet,
+        tx: &Transaction,
+        timestamp: u64,
+    ) -> Result<u64, String> {
+        // TODO: Net each address's inputs against its outputs BEFORE applying
+        // (spent UTXOs vanish from the set). Spending 10 and getting 4 back
+        // as change is one entry with delta -6. An address's starting balance
+        // is its last `balance_after`, or `get_balance` the first time.
+        let _ = (utxo_set, tx, timestamp);
+        todo!("Apply and record a transaction")
+    }
+
+    /// Every entry for `address`, oldest first. Empty if it never appeared.
+    pub fn for_address(&self, address: &str) -> &[LedgerEntry] {
+        let _ = address;
+        todo!("Look up an address's history")
+    }
+
+    /// The history of `address` as CSV with a
+    /// `txid,delta,balance_after,timestamp` header.
+    pub fn export_csv(&self, address: &str) -> String {
+        // TODO: Quote txids containing commas, quotes, or newlines.
+        let _ = address;
+        todo!("Export an address's history as CSV")
+    }
+}
+
 pub mod solution;
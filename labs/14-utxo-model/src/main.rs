@@ -1,6 +1,6 @@
 //! # UTXO Model Demo
 
-use utxo_model::solution::{self, Transaction, TxInput, TxOutput, UtxoSet};
+use utxo_model::solution::{self, Ledger, Transaction, TxInput, TxOutput, UtxoSet};
 
 fn main() {
     println!("=== UTXO Model Demo ===\n");
@@ -28,4 +28,63 @@ fn main() {
     println!("  Alice: {}", solution::get_balance(&utxo_set, "Alice"));
     println!("  Bob: {}", solution::get_balance(&utxo_set, "Bob"));
     println!("  Charlie: {}", solution::get_balance(&utxo_set, "Charlie"));
+
+    // A "block": tx3 spends an output that tx2 creates in the same batch.
+    let block = vec![
+        Transaction::new(
+            "tx2".to_string(),
+            vec![TxInput::new("genesis:1".to_string(), "Bob".to_string())],
+            vec![TxOutput::new("Dave".to_string(), 48)],
+        ),
+        Transaction::new(
+            "tx3".to_string(),
+            vec![TxInput::new("tx2:0".to_string(), "Dave".to_string())],
+            vec![TxOutput::new("Charlie".to_string(), 47)],
+        ),
+    ];
+    let summary = solution::apply_transactions(&mut utxo_set, &block).expect("valid block");
+    println!(
+        "
+block applied: {summary:?}"
+    );
+
+    // Spending the same coin twice in one batch is rejected and changes nothing.
+    let replay = vec![
+        Transaction::new(
+            "tx4".to_string(),
+            vec![TxInput::new("tx1:1".to_string(), "Alice".to_string())],
+            vec![TxOutput::new("Alice".to_string(), 70)],
+        ),
+        Transaction::new(
+            "tx5".to_string(),
+            vec![TxInput::new("tx1:1".to_string(), "Alice".to_string())],
+            vec![TxOutput::new("Eve".to_string(), 70)],
+        ),
+    ];
+    match solution::apply_transactions(&mut utxo_set, &replay) {
+        Ok(_) => println!("unexpected: replay accepted"),
+        Err(err) => println!("replay rejected: {err}"),
+    }
+    println!(
+        "  Alice still has: {}",
+        solution::get_balance(&utxo_set, "Alice")
+    );
+
+    // A ledger records each address's net change, so change outputs don't
+    // show up as separate "received" lines.
+    let mut utxo_set = UtxoSet::new();
+    let mut ledger = Ledger::new();
+    ledger.create_genesis(&mut utxo_set, "genesis:0", "Alice", 10, 1_700_000_000);
+    let pay_bob = Transaction::new(
+        "tx1".to_string(),
+        vec![TxInput::new("genesis:0".to_string(), "Alice".to_string())],
+        vec![
+            TxOutput::new("Bob".to_string(), 6),
+            TxOutput::new("Alice".to_string(), 4),
+        ],
+    );
+    ledger
+        .apply(&mut utxo_set, &pay_bob, 1_700_000_600)
+        .expect("valid transfer");
+    println!("\nAlice's ledger:\n{}", ledger.export_csv("Alice"));
 }
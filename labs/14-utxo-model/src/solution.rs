@@ -10,7 +10,8 @@
 //! - Once a UTXO is spent, it's removed from the "UTXO set"
 //! - Your balance = sum of all UTXOs you can spend
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 // ============================================================================
 // DATA STRUCTURES
@@ -182,6 +183,185 @@ pub fn apply_transaction(utxo_set: &mut UtxoSet, tx: &Transaction) -> Result<u64
     Ok(fee)
 }
 
+// ============================================================================
+// BATCH APPLICATION
+// ============================================================================
+
+/// Why a batch of transactions was rejected.
+///
+/// Every variant names the transaction that failed (`txid`) and, where it
+/// makes sense, the outpoint (`"txid:index"`) that caused the problem.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UtxoError {
+    /// The outpoint was already consumed by an earlier input in the same batch.
+    DoubleSpend { txid: String, outpoint: UtxoId },
+    /// The outpoint is not in the UTXO set and was not created earlier in the batch.
+    UnknownOutpoint { txid: String, outpoint: UtxoId },
+    /// The spender does not own the outpoint.
+    OwnershipViolation {
+        txid: String,
+        outpoint: UtxoId,
+        spender: Address,
+        owner: Address,
+    },
+    /// The outputs are worth more than the inputs.
+    InsufficientInputs {
+        txid: String,
+        inputs: u64,
+        outputs: u64,
+    },
+}
+
+impl fmt::Display for UtxoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UtxoError::DoubleSpend { txid, outpoint } => {
+                write!(f, "{txid}: double spend of {outpoint}")
+            }
+            UtxoError::UnknownOutpoint { txid, outpoint } => {
+                write!(f, "{txid}: UTXO {outpoint} not found")
+            }
+            UtxoError::OwnershipViolation {
+                txid,
+                outpoint,
+                spender,
+                owner,
+            } => write!(
+                f,
+                "{txid}: {spender} tried to spend {outpoint} owned by {owner}"
+            ),
+            UtxoError::InsufficientInputs {
+                txid,
+                inputs,
+                outputs,
+            } => write!(f, "{txid}: outputs ({outputs}) exceed inputs ({inputs})"),
+        }
+    }
+}
+
+impl std::error::Error for UtxoError {}
+
+/// What a successfully applied batch did to the UTXO set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AppliedSummary {
+    /// Number of UTXOs removed from the set.
+    pub consumed: usize,
+    /// Number of UTXOs added to the set.
+    pub created: usize,
+    /// Sum of `inputs - outputs` over every transaction in the batch.
+    pub total_fees: u64,
+}
+
+/// Applies a batch of transactions (think: one block) atomically.
+///
+/// Transactions are validated in order against the *evolving* set, so an
+/// output created by `txs[0]` can be spent by `txs[1]`. If any transaction
+/// is invalid, every change made by the earlier ones is rolled back and the
+/// set is left exactly as it was.
+///
+/// ## Double Spend vs Unknown Outpoint
+/// An outpoint that is missing from the set could be a typo or a coin that
+/// was spent in an earlier block. We only call it a `DoubleSpend` when we
+/// saw it consumed *within this batch* (including twice in the same
+/// transaction) because that's the case we can prove.
+///
+/// ## Rollback
+/// Instead of cloning the whole set up front (Bitcoin's would be gigabytes),
+/// we keep an undo log of `(id, previous value)` and replay it backwards on
+/// failure.
+pub fn apply_transactions(
+    utxo_set: &mut UtxoSet,
+    txs: &[Transaction],
+) -> Result<AppliedSummary, UtxoError> {
+    let mut undo_log: Vec<(UtxoId, Option<Utxo>)> = Vec::new();
+    let mut spent_in_batch: HashSet<UtxoId> = HashSet::new();
+    let mut summary = AppliedSummary::default();
+
+    for tx in txs {
+        match apply_logged(utxo_set, tx, &mut undo_log, &mut spent_in_batch) {
+            Ok(fee) => {
+                summary.consumed += tx.inputs.len();
+                summary.created += tx.outputs.len();
+                summary.total_fees += fee;
+            }
+            Err(err) => {
+                for (id, previous) in undo_log.into_iter().rev() {
+                    match previous {
+                        Some(utxo) => utxo_set.insert(id, utxo),
+                        None => utxo_set.remove(&id),
+                    };
+                }
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Validates and applies one transaction, recording every change it makes
+/// in `undo_log` so the caller can roll it back.
+fn apply_logged(
+    utxo_set: &mut UtxoSet,
+    tx: &Transaction,
+    undo_log: &mut Vec<(UtxoId, Option<Utxo>)>,
+    spent_in_batch: &mut HashSet<UtxoId>,
+) -> Result<u64, UtxoError> {
+    let mut total_input: u64 = 0;
+
+    for input in &tx.inputs {
+        let outpoint = &input.utxo_id;
+        if spent_in_batch.contains(outpoint) {
+            return Err(UtxoError::DoubleSpend {
+                txid: tx.id.clone(),
+                outpoint: outpoint.clone(),
+            });
+        }
+
+        let utxo = utxo_set
+            .get(outpoint)
+            .ok_or_else(|| UtxoError::UnknownOutpoint {
+                txid: tx.id.clone(),
+                outpoint: outpoint.clone(),
+            })?;
+
+        if utxo.owner != input.spender {
+            return Err(UtxoError::OwnershipViolation {
+                txid: tx.id.clone(),
+                outpoint: outpoint.clone(),
+                spender: input.spender.clone(),
+                owner: utxo.owner.clone(),
+            });
+        }
+
+        total_input += utxo.amount;
+        spent_in_batch.insert(outpoint.clone());
+    }
+
+    let total_output: u64 = tx.outputs.iter().map(|o| o.amount).sum();
+    if total_input < total_output {
+        return Err(UtxoError::InsufficientInputs {
+            txid: tx.id.clone(),
+            inputs: total_input,
+            outputs: total_output,
+        });
+    }
+
+    for input in &tx.inputs {
+        let previous = utxo_set.remove(&input.utxo_id);
+        undo_log.push((input.utxo_id.clone(), previous));
+    }
+
+    for (index, output) in tx.outputs.iter().enumerate() {
+        let utxo_id = format!("{}:{}", tx.id, index);
+        let utxo = Utxo::new(output.recipient.clone(), output.amount);
+        let previous = utxo_set.insert(utxo_id.clone(), utxo);
+        undo_log.push((utxo_id, previous));
+    }
+
+    Ok(total_input - total_output)
+}
+
 /// Calculate the balance of an address by summing all UTXOs they own.
 ///
 /// In the UTXO model, there's no single "account balance" variable.
@@ -209,3 +389,173 @@ pub fn get_utxos_for_address(utxo_set: &UtxoSet, address: &str) -> Vec<(UtxoId,
 pub fn create_genesis_utxo(utxo_set: &mut UtxoSet, id: &str, owner: &str, amount: u64) {
     utxo_set.insert(id.to_string(), Utxo::new(owner.to_string(), amount));
 }
+
+// ============================================================================
+// LEDGER (PER-ADDRESS HISTORY)
+// ============================================================================
+// `get_balance` answers "how much now?" but a wallet also wants "how did I
+// get here?". The UTXO set can't tell us: spent coins are gone. So the ledger
+// watches transactions as they are applied and writes one line per address
+// per transaction, like a bank statement.
+//
+// The subtle part is change. Alice spending a 10-coin UTXO to pay Bob 6 sends
+// 4 back to herself, so the transaction both takes 10 from her and gives her
+// 4. Her statement should say -6, not two separate lines, so we net inputs
+// against outputs per address before recording anything.
+
+/// One line of an address's history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntry {
+    /// The transaction that changed the balance.
+    pub txid: String,
+    /// Net change: outputs received minus inputs spent (change included).
+    pub delta: i64,
+    /// The address's balance once the transaction was applied.
+    pub balance_after: u64,
+    /// Caller-supplied time the transaction was applied (e.g. a block time).
+    pub timestamp: u64,
+}
+
+/// Per-address history of every transaction applied through it.
+///
+/// Route all changes to the UTXO set through [`Ledger::apply`] and
+/// [`Ledger::create_genesis`]; anything applied behind its back is missing
+/// from the history and leaves later `balance_after` values stale.
+#[derive(Debug, Clone, Default)]
+pub struct Ledger {
+    entries: HashMap<Address, Vec<LedgerEntry>>,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Ledger::default()
+    }
+
+    /// Applies `history` in order to `utxo_set`, recording each transaction.
+    ///
+    /// Stops at the first invalid transaction; the ones before it stay applied.
+    pub fn replay(
+        utxo_set: &mut UtxoSet,
+        history: &[(Transaction, u64)],
+    ) -> Result<Ledger, String> {
+        let mut ledger = Ledger::new();
+        for (tx, timestamp) in history {
+            ledger.apply(utxo_set, tx, *timestamp)?;
+        }
+        Ok(ledger)
+    }
+
+    /// Like [`create_genesis_utxo`], but records the new coins.
+    ///
+    /// The entry's `txid` is the part of `id` before the `:`, so
+    /// `"genesis:0"` shows up as `genesis`.
+    pub fn create_genesis(
+        &mut self,
+        utxo_set: &mut UtxoSet,
+        id: &str,
+        owner: &str,
+        amount: u64,
+        timestamp: u64,
+    ) {
+        let before = self.balance_before(utxo_set, owner);
+        create_genesis_utxo(utxo_set, id, owner, amount);
+        let txid = id.split(':').next().unwrap_or(id);
+        self.push(owner, txid, amount as i64, before + amount, timestamp);
+    }
+
+    /// Applies `tx` with [`apply_transaction`] and, if it succeeds, adds one
+    /// entry for every address it touched.
+    ///
+    /// ## Ownership & Borrowing
+    /// Deltas are computed *before* applying, because afterwards the spent
+    /// UTXOs (and so their owners and amounts) are gone from the set.
+    pub fn apply(
+        &mut self,
+        utxo_set: &mut UtxoSet,
+        tx: &Transaction,
+        timestamp: u64,
+    ) -> Result<u64, String> {
+        // A Vec rather than a HashMap keeps entries in a stable order:
+        // spenders first, then recipients, as they appear in the transaction.
+        let mut deltas: Vec<(Address, i64)> = Vec::new();
+        let mut add =
+            |address: &str, amount: i64| match deltas.iter_mut().find(|(a, _)| a == address) {
+                Some((_, delta)) => *delta += amount,
+                None => deltas.push((address.to_string(), amount)),
+            };
+        for input in &tx.inputs {
+            // Missing or foreign UTXOs are apply_transaction's to report
+            if let Some(utxo) = utxo_set.get(&input.utxo_id) {
+                add(&utxo.owner, -(utxo.amount as i64));
+            }
+        }
+        for output in &tx.outputs {
+            add(&output.recipient, output.amount as i64);
+        }
+
+        let before: Vec<u64> = deltas
+            .iter()
+            .map(|(address, _)| self.balance_before(utxo_set, address))
+            .collect();
+
+        let fee = apply_transaction(utxo_set, tx)?;
+
+        for ((address, delta), before) in deltas.into_iter().zip(before) {
+            let after = (before as i64 + delta) as u64;
+            self.push(&address, &tx.id, delta, after, timestamp);
+        }
+        Ok(fee)
+    }
+
+    /// Every entry for `address`, oldest first. Empty if it never appeared.
+    pub fn for_address(&self, address: &str) -> &[LedgerEntry] {
+        self.entries.get(address).map_or(&[], Vec::as_slice)
+    }
+
+    /// The history of `address` as CSV with a
+    /// `txid,delta,balance_after,timestamp` header, ready for lab 45's
+    /// `csv_to_json`.
+    pub fn export_csv(&self, address: &str) -> String {
+        let mut csv = String::from("txid,delta,balance_after,timestamp\n");
+        for entry in self.for_address(address) {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                csv_field(&entry.txid),
+                entry.delta,
+                entry.balance_after,
+                entry.timestamp
+            ));
+        }
+        csv
+    }
+
+    /// The last recorded balance, or what the set says if we haven't seen
+    /// `address` yet (coins it held before the ledger started watching).
+    fn balance_before(&self, utxo_set: &UtxoSet, address: &str) -> u64 {
+        match self.for_address(address).last() {
+            Some(entry) => entry.balance_after,
+            None => get_balance(utxo_set, address),
+        }
+    }
+
+    fn push(&mut self, address: &str, txid: &str, delta: i64, balance_after: u64, timestamp: u64) {
+        self.entries
+            .entry(address.to_string())
+            .or_default()
+            .push(LedgerEntry {
+                txid: txid.to_string(),
+                delta,
+                balance_after,
+                timestamp,
+            });
+    }
+}
+
+/// Quotes a CSV field if it contains a comma, quote, or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -445,3 +445,342 @@ fn test_multiple_outputs_same_recipient() {
     let bob_utxos = get_utxos_for_address(&utxo_set, "Bob");
     assert_eq!(bob_utxos.len(), 3);
 }
+
+// ============================================================================
+// TESTS: BATCH APPLICATION
+// ============================================================================
+
+#[test]
+fn test_batch_chained_spend_succeeds() {
+    let mut utxo_set = setup_genesis();
+
+    let txs = vec![
+        Transaction::new(
+            "tx1".to_string(),
+            vec![TxInput::new("genesis:0".to_string(), "Alice".to_string())],
+            vec![
+                TxOutput::new("Bob".to_string(), 60),
+                TxOutput::new("Alice".to_string(), 40),
+            ],
+        ),
+        // Spends an output created by tx1 in the same batch
+        Transaction::new(
+            "tx2".to_string(),
+            vec![TxInput::new("tx1:0".to_string(), "Bob".to_string())],
+            vec![TxOutput::new("Charlie".to_string(), 60)],
+        ),
+    ];
+
+    let summary = apply_transactions(&mut utxo_set, &txs).unwrap();
+    assert_eq!(summary.consumed, 2);
+    assert_eq!(summary.created, 3);
+    assert_eq!(summary.total_fees, 0);
+
+    assert!(!utxo_set.contains_key("tx1:0"));
+    assert_eq!(get_balance(&utxo_set, "Alice"), 40);
+    assert_eq!(get_balance(&utxo_set, "Bob"), 50);
+    assert_eq!(get_balance(&utxo_set, "Charlie"), 60);
+}
+
+#[test]
+fn test_batch_double_spend_rolls_back() {
+    let mut utxo_set = setup_genesis();
+    let before = utxo_set.clone();
+
+    let txs = vec![
+        Transaction::new(
+            "tx1".to_string(),
+            vec![TxInput::new("genesis:0".to_string(), "Alice".to_string())],
+            vec![TxOutput::new("Bob".to_string(), 100)],
+        ),
+        Transaction::new(
+            "tx2".to_string(),
+            vec![TxInput::new("genesis:0".to_string(), "Alice".to_string())],
+            vec![TxOutput::new("Charlie".to_string(), 100)],
+        ),
+    ];
+
+    let err = apply_transactions(&mut utxo_set, &txs).unwrap_err();
+    assert_eq!(
+        err,
+        UtxoError::DoubleSpend {
+            txid: "tx2".to_string(),
+            outpoint: "genesis:0".to_string(),
+        }
+    );
+    // tx1 was valid on its own, but the whole batch is rejected
+    assert_eq!(utxo_set, before);
+}
+
+#[test]
+fn test_batch_double_spend_within_one_transaction() {
+    let mut utxo_set = setup_genesis();
+
+    let tx = Transaction::new(
+        "tx1".to_string(),
+        vec![
+            TxInput::new("genesis:0".to_string(), "Alice".to_string()),
+            TxInput::new("genesis:0".to_string(), "Alice".to_string()),
+        ],
+        vec![TxOutput::new("Alice".to_string(), 200)],
+    );
+
+    let err = apply_transactions(&mut utxo_set, &[tx]).unwrap_err();
+    assert!(matches!(err, UtxoError::DoubleSpend { .. }));
+    assert_eq!(get_balance(&utxo_set, "Alice"), 100);
+}
+
+#[test]
+fn test_batch_later_failure_restores_spent_and_created() {
+    let mut utxo_set = setup_genesis();
+    let before = utxo_set.clone();
+
+    let txs = vec![
+        Transaction::new(
+            "tx1".to_string(),
+            vec![TxInput::new("genesis:1".to_string(), "Bob".to_string())],
+            vec![TxOutput::new("Alice".to_string(), 45)],
+        ),
+        Transaction::new(
+            "tx2".to_string(),
+            vec![TxInput::new("tx1:0".to_string(), "Mallory".to_string())],
+            vec![TxOutput::new("Mallory".to_string(), 45)],
+        ),
+    ];
+
+    let err = apply_transactions(&mut utxo_set, &txs).unwrap_err();
+    assert!(matches!(err, UtxoError::OwnershipViolation { .. }));
+    assert_eq!(utxo_set, before);
+}
+
+#[test]
+fn test_batch_fee_totals() {
+    let mut utxo_set = setup_genesis();
+
+    let txs = vec![
+        Transaction::new(
+            "tx1".to_string(),
+            vec![TxInput::new("genesis:0".to_string(), "Alice".to_string())],
+            vec![TxOutput::new("Bob".to_string(), 95)],
+        ),
+        Transaction::new(
+            "tx2".to_string(),
+            vec![
+                TxInput::new("genesis:1".to_string(), "Bob".to_string()),
+                TxInput::new("tx1:0".to_string(), "Bob".to_string()),
+            ],
+            vec![TxOutput::new("Charlie".to_string(), 142)],
+        ),
+    ];
+
+    let summary = apply_transactions(&mut utxo_set, &txs).unwrap();
+    assert_eq!(summary.total_fees, 5 + 3);
+    assert_eq!(get_balance(&utxo_set, "Charlie"), 142);
+}
+
+#[test]
+fn test_batch_unknown_and_insufficient_errors() {
+    let mut utxo_set = setup_genesis();
+
+    let unknown = Transaction::new(
+        "tx1".to_string(),
+        vec![TxInput::new("fake:0".to_string(), "Alice".to_string())],
+        vec![],
+    );
+    assert!(matches!(
+        apply_transactions(&mut utxo_set, &[unknown]),
+        Err(UtxoError::UnknownOutpoint { .. })
+    ));
+
+    let greedy = Transaction::new(
+        "tx2".to_string(),
+        vec![TxInput::new("genesis:1".to_string(), "Bob".to_string())],
+        vec![TxOutput::new("Bob".to_string(), 51)],
+    );
+    let err = apply_transactions(&mut utxo_set, &[greedy]).unwrap_err();
+    assert_eq!(err.to_string(), "tx2: outputs (51) exceed inputs (50)");
+}
+
+#[test]
+fn test_batch_empty_is_noop() {
+    let mut utxo_set = setup_genesis();
+    let summary = apply_transactions(&mut utxo_set, &[]).unwrap();
+    assert_eq!(summary, AppliedSummary::default());
+    assert_eq!(utxo_set.len(), 2);
+}
+
+// ============================================================================
+// TESTS: LEDGER
+// ============================================================================
+
+fn tx(id: &str, inputs: &[(&str, &str)], outputs: &[(&str, u64)]) -> Transaction {
+    Transaction::new(
+        id.to_string(),
+        inputs
+            .iter()
+            .map(|(utxo, spender)| TxInput::new(utxo.to_string(), spender.to_string()))
+            .collect(),
+        outputs
+            .iter()
+            .map(|(recipient, amount)| TxOutput::new(recipient.to_string(), *amount))
+            .collect(),
+    )
+}
+
+/// Alice starts with 10, pays Bob 6 (4 back as change), Bob pays Carol 5
+/// (1 change, 0 fee), then Alice and Carol pool their coins for Bob with
+/// a fee of 1.
+fn ledger_scenario() -> (UtxoSet, Ledger) {
+    let mut utxo_set = UtxoSet::new();
+    let mut ledger = Ledger::new();
+    ledger.create_genesis(&mut utxo_set, "genesis:0", "Alice", 10, 100);
+    ledger
+        .apply(
+            &mut utxo_set,
+            &tx(
+                "tx1",
+                &[("genesis:0", "Alice")],
+                &[("Bob", 6), ("Alice", 4)],
+            ),
+            200,
+        )
+        .unwrap();
+    ledger
+        .apply(
+            &mut utxo_set,
+            &tx("tx2", &[("tx1:0", "Bob")], &[("Carol", 5), ("Bob", 1)]),
+            300,
+        )
+        .unwrap();
+    ledger
+        .apply(
+            &mut utxo_set,
+            &tx(
+                "tx3",
+                &[("tx1:1", "Alice"), ("tx2:0", "Carol")],
+                &[("Bob", 8)],
+            ),
+            400,
+        )
+        .unwrap();
+    (utxo_set, ledger)
+}
+
+#[test]
+fn test_ledger_nets_change_into_one_delta() {
+    let (_, ledger) = ledger_scenario();
+
+    let alice: Vec<(&str, i64, u64)> = ledger
+        .for_address("Alice")
+        .iter()
+        .map(|e| (e.txid.as_str(), e.delta, e.balance_after))
+        .collect();
+    assert_eq!(alice, [("genesis", 10, 10), ("tx1", -6, 4), ("tx3", -4, 0)]);
+
+    let bob: Vec<(&str, i64, u64)> = ledger
+        .for_address("Bob")
+        .iter()
+        .map(|e| (e.txid.as_str(), e.delta, e.balance_after))
+        .collect();
+    assert_eq!(bob, [("tx1", 6, 6), ("tx2", -5, 1), ("tx3", 8, 9)]);
+
+    let carol = ledger.for_address("Carol");
+    assert_eq!(carol.len(), 2);
+    assert_eq!((carol[1].delta, carol[1].timestamp), (-5, 400));
+    assert!(ledger.for_address("Nobody").is_empty());
+}
+
+#[test]
+fn test_ledger_balance_matches_utxo_set() {
+    let mut utxo_set = setup_genesis();
+    let mut ledger = Ledger::new();
+    let history = [
+        tx(
+            "tx1",
+            &[("genesis:0", "Alice")],
+            &[("Bob", 30), ("Alice", 65)],
+        ),
+        tx(
+            "tx2",
+            &[("genesis:1", "Bob"), ("tx1:0", "Bob")],
+            &[("Alice", 70), ("Bob", 10)],
+        ),
+        tx(
+            "tx3",
+            &[("tx1:1", "Alice"), ("tx2:0", "Alice")],
+            &[("Alice", 135)],
+        ),
+    ];
+    for (i, t) in history.iter().enumerate() {
+        ledger.apply(&mut utxo_set, t, i as u64).unwrap();
+        for address in ["Alice", "Bob"] {
+            // Genesis coins predate the ledger; they seed the first entry
+            if let Some(last) = ledger.for_address(address).last() {
+                assert_eq!(last.balance_after, get_balance(&utxo_set, address));
+            }
+        }
+    }
+    assert_eq!(ledger.for_address("Alice").len(), 3);
+    assert_eq!(ledger.for_address("Alice")[2].delta, 0);
+}
+
+#[test]
+fn test_ledger_failed_transaction_records_nothing() {
+    let (mut utxo_set, mut ledger) = ledger_scenario();
+    let before = ledger.for_address("Bob").to_vec();
+    let result = ledger.apply(
+        &mut utxo_set,
+        &tx("bad", &[("tx3:0", "Bob")], &[("Eve", 9)]),
+        500,
+    );
+    assert!(result.is_err());
+    assert_eq!(ledger.for_address("Bob"), &before[..]);
+    assert!(ledger.for_address("Eve").is_empty());
+}
+
+#[test]
+fn test_ledger_replay_matches_incremental() {
+    let mut utxo_set = UtxoSet::new();
+    create_genesis_utxo(&mut utxo_set, "genesis:0", "Alice", 10);
+    let history = vec![
+        (
+            tx(
+                "tx1",
+                &[("genesis:0", "Alice")],
+                &[("Bob", 6), ("Alice", 4)],
+            ),
+            1,
+        ),
+        (tx("tx2", &[("tx1:0", "Bob")], &[("Carol", 6)]), 2),
+    ];
+    let ledger = Ledger::replay(&mut utxo_set, &history).unwrap();
+    assert_eq!(ledger.for_address("Alice")[0].balance_after, 4);
+    assert_eq!(ledger.for_address("Carol")[0].balance_after, 6);
+    assert_eq!(ledger.for_address("Bob").len(), 2);
+
+    let mut fresh = UtxoSet::new();
+    assert!(Ledger::replay(&mut fresh, &history).is_err());
+}
+
+#[test]
+fn test_ledger_export_csv_columns() {
+    let (_, ledger) = ledger_scenario();
+    let csv = ledger.export_csv("Bob");
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "txid,delta,balance_after,timestamp");
+    assert_eq!(&lines[1..], ["tx1,6,6,200", "tx2,-5,1,300", "tx3,8,9,400"]);
+    assert!(lines.iter().all(|line| line.split(',').count() == 4));
+
+    assert_eq!(
+        ledger.export_csv("Nobody"),
+        "txid,delta,balance_after,timestamp\n"
+    );
+
+    let mut utxo_set = UtxoSet::new();
+    let mut ledger = Ledger::new();
+    ledger.create_genesis(&mut utxo_set, "mint, \"v2\":0", "Dana", 5, 1);
+    assert_eq!(
+        ledger.export_csv("Dana").lines().nth(1),
+        Some("\"mint, \"\"v2\"\"\",5,5,1")
+    );
+}
@@ -2,18 +2,22 @@
 //!
 //! Student-facing API for chat entities, queues, and registry state.
 
-use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
 
 #[derive(Clone, Debug)]
 pub struct Client {
     pub id: u32,
     pub username: String,
     pub is_connected: bool,
+    pub room: String,
 }
 
 impl Client {
     pub fn new(id: u32, username: String) -> Self {
-        // TODO: Construct connected client.
+        // TODO: Construct connected client in the LOBBY.
         let _ = (id, username);
         todo!("Create Client")
     }
@@ -61,25 +65,33 @@ impl Message {
 }
 
 #[derive(Clone)]
-pub struct MessageQueue {
-    messages: VecDeque<Message>,
+pub struct MessageQueue<T = Message> {
+    messages: VecDeque<T>,
     max_size: usize,
 }
 
 impl MessageQueue {
     pub fn new(max_size: usize) -> Self {
-        // TODO: Construct empty bounded queue.
+        // TODO: Delegate to bounded.
         let _ = max_size;
         todo!("Create MessageQueue")
     }
+}
+
+impl<T> MessageQueue<T> {
+    pub fn bounded(max_size: usize) -> Self {
+        // TODO: Construct empty bounded queue.
+        let _ = max_size;
+        todo!("Create bounded queue")
+    }
 
-    pub fn enqueue(&mut self, message: Message) {
+    pub fn enqueue(&mut self, message: T) {
         // TODO: Push message; drop oldest when full.
         let _ = message;
         todo!("Enqueue message")
     }
 
-    pub fn dequeue(&mut self) -> Option<Message> {
+    pub fn dequeue(&mut self) -> Option<T> {
         // TODO: Pop next message FIFO.
         todo!("Dequeue message")
     }
@@ -112,8 +124,9 @@ impl ClientRegistry {
         todo!("Create ClientRegistry")
     }
 
-    pub fn register(&mut self, username: String) -> Client {
-        // TODO: Allocate ID, create client, store clone, return client.
+    pub fn register(&mut self, username: String) -> Result<Client, RegistrationError> {
+        // TODO: Validate the name, reject names of connected clients (any case),
+        // then allocate ID, create client, store clone, return client.
         let _ = username;
         todo!("Register client")
     }
@@ -124,6 +137,23 @@ impl ClientRegistry {
         todo!("Find client")
     }
 
+    pub fn find_by_name(&self, name: &str) -> Option<Client> {
+        // TODO: Case-insensitive match among connected clients.
+        let _ = name;
+        todo!("Find client by name")
+    }
+
+    pub fn rename(&mut self, id: u32, new_name: &str) -> Result<(), RegistrationError> {
+        // TODO: Same validation as register; renaming yourself is fine.
+        let _ = (id, new_name);
+        todo!("Rename client")
+    }
+
+    pub fn clients(&self) -> &[Client] {
+        // TODO: Return every registered client.
+        todo!("List all clients")
+    }
+
     pub fn active_clients(&self) -> Vec<Client> {
         // TODO: Return connected clients.
         todo!("List active clients")
@@ -144,6 +174,18 @@ impl ClientRegistry {
         // TODO: Return active client count.
         todo!("Count active clients")
     }
+
+    pub fn current_room(&self, id: u32) -> Option<String> {
+        // TODO: Return the client's current room.
+        let _ = id;
+        todo!("Look up current room")
+    }
+
+    pub fn set_room(&mut self, id: u32, room: &str) {
+        // TODO: Update the client's current room.
+        let _ = (id, room);
+        todo!("Set current room")
+    }
 }
 
 impl Default for ClientRegistry {
@@ -152,6 +194,31 @@ impl Default for ClientRegistry {
     }
 }
 
+pub const MAX_USERNAME_LEN: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistrationError {
+    InvalidName(String),
+    NameTaken,
+    UnknownClient(u32),
+}
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // TODO: Human-readable message for each variant.
+        let _ = f;
+        todo!("Format registration error")
+    }
+}
+
+impl std::error::Error for RegistrationError {}
+
+pub fn validate_username(name: &str) -> Result<String, RegistrationError> {
+    // TODO: Trim; reject empty, > MAX_USERNAME_LEN chars, control chars, leading '/'.
+    let _ = name;
+    todo!("Validate username")
+}
+
 pub fn is_command(input: &str) -> bool {
     // TODO: Commands start with '/'.
     let _ = input;
@@ -164,5 +231,720 @@ pub fn parse_command(input: &str) -> Option<&str> {
     todo!("Parse command input")
 }
 
+#[derive(Clone, Debug)]
+pub struct MessageHistory {
+    entries: VecDeque<(u64, Message)>,
+    next_seq: u64,
+}
+
+impl MessageHistory {
+    pub fn new() -> Self {
+        // TODO: Empty history; first seq is 1.
+        todo!("Create MessageHistory")
+    }
+
+    pub fn record(&mut self, message: Message) -> u64 {
+        // TODO: Store message with the next seq and return that seq.
+        let _ = message;
+        todo!("Record message")
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &Message)> {
+        // TODO: Yield (seq, message) pairs, oldest first.
+        self.entries.iter().map(|(seq, message)| (*seq, message))
+    }
+
+    pub fn purge_before(&mut self, seq: u64) -> usize {
+        // TODO: Drop messages with a lower seq; return how many.
+        let _ = seq;
+        todo!("Purge old messages")
+    }
+
+    pub fn len(&self) -> usize {
+        // TODO: Return stored message count.
+        todo!("Count stored messages")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // TODO: Return true when nothing is stored.
+        todo!("Check history empty")
+    }
+
+    pub fn total_recorded(&self) -> u64 {
+        // TODO: Count every recorded message, purged ones included.
+        todo!("Count recorded messages")
+    }
+}
+
+impl Default for MessageHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub const SERVER_SENDER_ID: u32 = 0;
+pub const RECENT_SEQ_LIMIT: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientDto {
+    pub id: u32,
+    pub username: String,
+    pub connected: bool,
+    pub message_count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientDetailDto {
+    pub id: u32,
+    pub username: String,
+    pub display_name: String,
+    pub connected: bool,
+    pub message_count: usize,
+    pub recent_message_seqs: Vec<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ServerMetricsDto {
+    pub registered_clients: usize,
+    pub active_clients: usize,
+    pub total_messages: u64,
+    pub stored_messages: usize,
+    pub uptime_ticks: u64,
+    pub queue_depths: Vec<QueueDepthDto>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoomDto {
+    pub name: String,
+    pub member_count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoomDetailDto {
+    pub name: String,
+    pub members: Vec<ClientDto>,
+    pub recent_message_seqs: Vec<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueueDepthDto {
+    pub client_id: u32,
+    pub pending: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdminActionResult {
+    pub action: String,
+    pub affected: usize,
+    pub seq: Option<u64>,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum AdminError {
+    ClientNotFound { id: u32 },
+    ClientNotConnected { id: u32 },
+    RoomNotFound { room: String },
+    EmptyAnnouncement,
+}
+
+impl AdminError {
+    pub fn code(&self) -> &'static str {
+        // TODO: Match the serialized snake_case variant names.
+        todo!("Return error code")
+    }
+}
+
+impl fmt::Display for AdminError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // TODO: Human-readable message for each variant.
+        let _ = f;
+        todo!("Format admin error")
+    }
+}
+
+impl std::error::Error for AdminError {}
+
+pub struct AdminApi<'a> {
+    state: AdminState<'a>,
+}
+
+enum AdminState<'a> {
+    Parts {
+        registry: &'a mut ClientRegistry,
+        history: &'a mut MessageHistory,
+    },
+    Room(&'a mut ChatRoom),
+}
+
+impl<'a> AdminApi<'a> {
+    pub fn new(registry: &'a mut ClientRegistry, history: &'a mut MessageHistory) -> Self {
+        AdminApi {
+            state: AdminState::Parts { registry, history },
+        }
+    }
+
+    pub fn list_clients(&self) -> Vec<ClientDto> {
+        // TODO: Map every Client to a ClientDto (count its stored messages).
+        todo!("List clients as DTOs")
+    }
+
+    pub fn client_detail(&self, id: u32) -> Result<ClientDetailDto, AdminError> {
+        // TODO: ClientNotFound for unknown ids; include recent message seqs.
+        let _ = id;
+        todo!("Describe one client")
+    }
+
+    pub fn list_rooms(&self) -> Vec<RoomDto> {
+        // TODO: Every room with its member count; none without a ChatRoom.
+        todo!("List rooms as DTOs")
+    }
+
+    pub fn room_detail(&self, name: &str) -> Result<RoomDetailDto, AdminError> {
+        // TODO: RoomNotFound for unknown rooms; list the members and the
+        // room's recent message seqs.
+        let _ = name;
+        todo!("Describe one room")
+    }
+
+    pub fn server_metrics(&self) -> ServerMetricsDto {
+        // TODO: Collect counters from registry and history, plus (with a
+        // ChatRoom) the uptime ticks and each connected client's pending
+        // event count.
+        todo!("Report server metrics")
+    }
+
+    pub fn kick_client(&mut self, id: u32) -> Result<AdminActionResult, AdminError> {
+        // TODO: Error if unknown or offline; otherwise disconnect the client,
+        // through ChatRoom::leave when there is a room.
+        let _ = id;
+        todo!("Kick client")
+    }
+
+    pub fn broadcast_announcement(&mut self, text: &str) -> Result<AdminActionResult, AdminError> {
+        // TODO: Reject empty text, record a server message, and (with a
+        // ChatRoom) queue it for every connected client.
+        let _ = text;
+        todo!("Broadcast announcement")
+    }
+
+    pub fn purge_history_before(&mut self, seq: u64) -> AdminActionResult {
+        // TODO: Purge history and report how many messages were removed.
+        let _ = seq;
+        todo!("Purge history")
+    }
+}
+
+pub const LOBBY: &str = "lobby";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RoomCommand {
+    Join(String),
+    Leave(String),
+    Rooms,
+}
+
+impl RoomCommand {
+    pub fn parse(input: &str) -> Option<Self> {
+        // TODO: Use parse_command, then match join/leave <room> and rooms.
+        let _ = input;
+        todo!("Parse room command")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RoomError {
+    UnknownClient { id: u32 },
+    NotInRoom { room: String },
+    CannotLeaveLobby,
+}
+
+impl fmt::Display for RoomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // TODO: Human-readable message for each variant.
+        let _ = f;
+        todo!("Format room error")
+    }
+}
+
+impl std::error::Error for RoomError {}
+
+#[derive(Clone, Debug)]
+pub struct RoomManager {
+    rooms: BTreeMap<String, BTreeSet<u32>>,
+    joined: HashMap<u32, Vec<String>>,
+}
+
+impl RoomManager {
+    pub fn new() -> Self {
+        // TODO: Start with an empty lobby.
+        todo!("Create room manager")
+    }
+
+    pub fn connect(&mut self, client_id: u32) {
+        // TODO: Put the client in the lobby.
+        let _ = client_id;
+        todo!("Connect client to lobby")
+    }
+
+    pub fn join(&mut self, client_id: u32, room: &str) -> String {
+        // TODO: Create the room if needed, leave the lobby, make it current.
+        let _ = (client_id, room);
+        todo!("Join room")
+    }
+
+    pub fn leave(&mut self, client_id: u32, room: &str) -> Result<String, RoomError> {
+        // TODO: Leave the room, drop empty rooms, fall back to the lobby.
+        let _ = (client_id, room);
+        todo!("Leave room")
+    }
+
+    pub fn disconnect(&mut self, client_id: u32) {
+        // TODO: Remove the client from every room.
+        let _ = client_id;
+        todo!("Disconnect from rooms")
+    }
+
+    pub fn broadcast_targets(&self, room: &str) -> Vec<u32> {
+        // TODO: Sorted member ids of the room.
+        let _ = room;
+        todo!("List room members")
+    }
+
+    pub fn rooms(&self) -> Vec<(String, usize)> {
+        // TODO: Room names with member counts.
+        todo!("List rooms")
+    }
+
+    pub fn contains(&self, room: &str) -> bool {
+        // TODO: Check if the room exists.
+        let _ = room;
+        todo!("Check room exists")
+    }
+}
+
+impl Default for RoomManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn route_message(
+    registry: &ClientRegistry,
+    rooms: &RoomManager,
+    message: &Message,
+) -> Vec<u32> {
+    // TODO: Connected members of the sender's current room, minus the sender.
+    let _ = (registry, rooms, message);
+    todo!("Route message")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Allowed,
+    Limited { retry_after: Duration },
+}
+
+#[derive(Clone, Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    capacity: u32,
+    refill_per_sec: f64,
+    buckets: HashMap<u32, Bucket>,
+}
+
+impl RateLimiter {
+    pub fn new(capacity: u32, refill_per_sec: f64) -> Self {
+        // TODO: Store capacity and rate; start with no buckets. Panic on a
+        // zero capacity, which could never allow anything.
+        let _ = (capacity, refill_per_sec);
+        todo!("Create rate limiter")
+    }
+
+    pub fn check(&mut self, client_id: u32, now: Instant) -> Decision {
+        // TODO: Create a full bucket on first sight, refill by elapsed time,
+        // then spend a token or report how long until one is available.
+        let _ = (client_id, now);
+        todo!("Check token bucket")
+    }
+
+    pub fn forget(&mut self, client_id: u32) {
+        // TODO: Remove the client's bucket.
+        let _ = client_id;
+        todo!("Forget client bucket")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PostError {
+    UnknownClient { id: u32 },
+    NotConnected { id: u32 },
+    EmptyMessage,
+    RateLimited { retry_after: Duration },
+}
+
+impl fmt::Display for PostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // TODO: Human-readable message for each variant.
+        let _ = f;
+        todo!("Format post error")
+    }
+}
+
+impl std::error::Error for PostError {}
+
+pub struct ChatRoom {
+    registry: ClientRegistry,
+    history: MessageHistory,
+    limiter: RateLimiter,
+    rooms: RoomManager,
+    outboxes: HashMap<u32, Outbox>,
+    room_seqs: HashMap<String, VecDeque<u64>>,
+    ticks: u64,
+}
+
+impl ChatRoom {
+    pub fn new(limiter: RateLimiter) -> Self {
+        ChatRoom {
+            registry: ClientRegistry::new(),
+            history: MessageHistory::new(),
+            limiter,
+            rooms: RoomManager::new(),
+            outboxes: HashMap::new(),
+            room_seqs: HashMap::new(),
+            ticks: 0,
+        }
+    }
+
+    pub fn tick(&mut self) {
+        // TODO: Advance the logical clock by one.
+        todo!("Tick")
+    }
+
+    pub fn uptime_ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn join(&mut self, username: String) -> Result<Client, RegistrationError> {
+        // TODO: Register the client, put it in the lobby, give it an outbox,
+        // and publish UserJoined to everyone else.
+        let _ = username;
+        todo!("Join chat room")
+    }
+
+    pub fn leave(&mut self, id: u32) -> usize {
+        // TODO: Disconnect the client, leave all rooms, forget its bucket.
+        // Publish UserLeft only if its outbox was still there, and return
+        // how many events that outbox still held.
+        let _ = id;
+        todo!("Leave chat room")
+    }
+
+    pub fn rename(&mut self, id: u32, new_name: &str) -> Result<(), RegistrationError> {
+        // TODO: Rename a connected client and publish UserRenamed if the
+        // name actually changed.
+        let _ = (id, new_name);
+        todo!("Rename client")
+    }
+
+    pub fn set_event_filter(&mut self, id: u32, filter: EventFilter) {
+        // TODO: Store the filter on the client's outbox.
+        let _ = (id, filter);
+        todo!("Set event filter")
+    }
+
+    pub fn next_event(&mut self, id: u32) -> Option<ServerEvent> {
+        // TODO: Dequeue from the client's outbox.
+        let _ = id;
+        todo!("Next event")
+    }
+
+    pub fn drain_events(&mut self, id: u32) -> Vec<ServerEvent> {
+        // TODO: Dequeue everything from the client's outbox.
+        let _ = id;
+        todo!("Drain events")
+    }
+
+    pub fn run_room_command(
+        &mut self,
+        client_id: u32,
+        command: &RoomCommand,
+    ) -> Result<String, RoomError> {
+        // TODO: Apply join/leave/rooms and keep the registry's room in sync.
+        let _ = (client_id, command);
+        todo!("Run room command")
+    }
+
+    pub fn recipients(&self, seq: u64) -> Vec<u32> {
+        // TODO: Route the stored message with route_message.
+        let _ = seq;
+        todo!("Message recipients")
+    }
+
+    pub fn post(&mut self, client_id: u32, input: &str, now: Instant) -> Result<u64, PostError> {
+        // TODO: Check client, reject empty input, consult the limiter, record
+        // (remembering the seq under the sender's room in room_seqs), and
+        // queue ServerEvent::Chat for the routed recipients.
+        let _ = (client_id, input, now);
+        todo!("Post message")
+    }
+
+    pub fn registry(&self) -> &ClientRegistry {
+        &self.registry
+    }
+
+    pub fn history(&self) -> &MessageHistory {
+        &self.history
+    }
+
+    pub fn rooms(&self) -> &RoomManager {
+        &self.rooms
+    }
+
+    pub fn admin(&mut self) -> AdminApi<'_> {
+        AdminApi {
+            state: AdminState::Room(self),
+        }
+    }
+
+    pub fn mailroom(&mut self) -> Mailroom<'_> {
+        Mailroom {
+            registry: &self.registry,
+            outboxes: &mut self.outboxes,
+        }
+    }
+}
+
+pub const OUTBOX_CAPACITY: usize = 256;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServerEvent {
+    UserJoined {
+        id: u32,
+        username: String,
+    },
+    UserLeft {
+        id: u32,
+        username: String,
+    },
+    UserRenamed {
+        id: u32,
+        old_name: String,
+        new_name: String,
+    },
+    Chat(Message),
+}
+
+impl ServerEvent {
+    pub fn format_for_broadcast(&self) -> String {
+        // TODO: "* alice joined", "* alice left",
+        // "* alice is now known as bob", or the chat line.
+        todo!("Format server event")
+    }
+
+    pub fn is_presence(&self) -> bool {
+        // TODO: Everything except Chat.
+        todo!("Is presence event")
+    }
+
+    pub fn client_id(&self) -> u32 {
+        // TODO: The id the event is about (sender for Chat).
+        todo!("Event client id")
+    }
+}
+
+pub type EventQueue = MessageQueue<ServerEvent>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EventFilter {
+    #[default]
+    Everything,
+    ChatOnly,
+}
+
+impl EventFilter {
+    pub fn allows(&self, event: &ServerEvent) -> bool {
+        // TODO: ChatOnly drops presence events.
+        let _ = event;
+        todo!("Filter event")
+    }
+}
+
+#[derive(Clone)]
+struct Outbox {
+    queue: EventQueue,
+    filter: EventFilter,
+}
+
+pub struct Mailroom<'a> {
+    registry: &'a ClientRegistry,
+    outboxes: &'a mut HashMap<u32, Outbox>,
+}
+
+impl<'a> Mailroom<'a> {
+    pub fn deliver_to(&mut self, client_id: u32, message: Message) -> bool {
+        // TODO: false unless the registry says the client is active;
+        // otherwise enqueue ServerEvent::Chat into its outbox.
+        let _ = (self.registry, client_id, message);
+        todo!("Deliver to one client")
+    }
+
+    pub fn broadcast(&mut self, message: Message, except: Option<u32>) -> usize {
+        // TODO: deliver_to every active client except `except`; return how
+        // many received it.
+        let _ = (message, except);
+        todo!("Broadcast message")
+    }
+
+    pub fn flush(&mut self, client_id: u32, max_batch: usize) -> Vec<ServerEvent> {
+        // TODO: Dequeue up to max_batch events from the outbox, oldest first.
+        let _ = (&self.outboxes, client_id, max_batch);
+        todo!("Flush a batch")
+    }
+
+    pub fn pending(&self, client_id: u32) -> usize {
+        let _ = client_id;
+        todo!("Pending event count")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    pub timestamp: u64,
+    pub message: Message,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogReplay {
+    pub entries: Vec<LogEntry>,
+    pub skipped: usize,
+}
+
+impl LogReplay {
+    pub fn messages(&self) -> Vec<Message> {
+        todo!("Collect the messages from the entries")
+    }
+}
+
+pub struct MessageLog {
+    file: std::fs::File,
+    path: std::path::PathBuf,
+}
+
+impl MessageLog {
+    pub fn open(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        // TODO: Open in append mode, creating the file if missing.
+        let _ = path;
+        todo!("Open message log")
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    pub fn append(&mut self, message: &Message) -> std::io::Result<()> {
+        // TODO: Write "timestamp\tsender_id\tsender_name\tcontent\n" with
+        // tabs, newlines, and backslashes escaped, in a single write.
+        let _ = (&self.file, message);
+        todo!("Append message")
+    }
+
+    pub fn replay(path: impl AsRef<std::path::Path>) -> std::io::Result<Vec<Message>> {
+        let _ = path;
+        todo!("Replay all messages")
+    }
+
+    pub fn replay_entries(path: impl AsRef<std::path::Path>) -> std::io::Result<LogReplay> {
+        // TODO: Parse every line; skip and count the ones that don't parse.
+        let _ = path;
+        todo!("Replay entries")
+    }
+
+    pub fn tail(path: impl AsRef<std::path::Path>, n: usize) -> std::io::Result<Vec<Message>> {
+        let _ = (path, n);
+        todo!("Last n messages")
+    }
+
+    pub fn tail_entries(path: impl AsRef<std::path::Path>, n: usize) -> std::io::Result<LogReplay> {
+        // TODO: Seek to the end and read backwards in chunks until n entries parse.
+        let _ = (path, n);
+        todo!("Last n entries")
+    }
+}
+
+pub const DEFAULT_MAX_FRAME_LEN: usize = 64 * 1024;
+
+pub fn encode_frame(message: &Message) -> Vec<u8> {
+    // TODO: [u32 length][u32 sender_id][u16 name_len][name][content], all
+    // little-endian, where length counts every byte after itself.
+    let _ = message;
+    todo!("Encode a frame")
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    FrameTooLarge { length: usize, max: usize },
+    FrameTooShort { length: usize },
+    NameOutOfBounds { name_len: usize, length: usize },
+    InvalidUtf8 { field: &'static str },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // TODO: Describe each decode failure.
+        let _ = f;
+        todo!("Format decode error")
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[derive(Clone, Debug)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+    max_frame_len: usize,
+    skip: usize,
+}
+
+impl FrameDecoder {
+    pub fn new() -> Self {
+        Self::with_max_frame_len(DEFAULT_MAX_FRAME_LEN)
+    }
+
+    pub fn with_max_frame_len(max_frame_len: usize) -> Self {
+        // TODO: Empty buffer, nothing to skip.
+        let _ = max_frame_len;
+        todo!("Create decoder")
+    }
+
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Result<Message, DecodeError>> {
+        // TODO: Buffer the bytes, then decode every complete frame. Report an
+        // oversized frame once and skip its declared length, even across
+        // later feeds; a malformed frame yields an Err for that frame only.
+        let _ = (bytes, self.skip);
+        todo!("Decode buffered frames")
+    }
+}
+
+impl Default for FrameDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[doc(hidden)]
 pub mod solution;
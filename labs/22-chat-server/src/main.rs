@@ -1,13 +1,21 @@
 //! # Chat Server Components Demo
 
-use chat_server::solution::{self, ClientRegistry, Message, MessageQueue};
+use chat_server::solution::{
+    self, encode_frame, AdminApi, ChatRoom, ClientRegistry, EventFilter, FrameDecoder, Message,
+    MessageHistory, MessageLog, MessageQueue, RateLimiter, RoomCommand,
+};
+use std::time::{Duration, Instant};
 
 fn main() {
     println!("=== Chat Server Components Demo ===\n");
 
     let mut registry = ClientRegistry::new();
-    let alice = registry.register("alice".to_string());
-    let bob = registry.register("bob".to_string());
+    let alice = registry
+        .register("alice".to_string())
+        .expect("valid username");
+    let bob = registry
+        .register("bob".to_string())
+        .expect("valid username");
 
     let mut queue = MessageQueue::new(5);
     let msg = Message::new(alice.id, alice.username.clone(), "hello bob".to_string());
@@ -19,4 +27,115 @@ fn main() {
     println!("queued messages: {}", queue.size());
     println!("active clients: {}", registry.active_count());
     println!("is '/users' command? {}", solution::is_command("/users"));
+
+    println!("\n--- Admin API ---");
+    let mut history = MessageHistory::new();
+    history.record(msg);
+    let mut admin = AdminApi::new(&mut registry, &mut history);
+    println!("{:?}", admin.kick_client(bob.id));
+    println!(
+        "{:?}",
+        admin.broadcast_announcement("server restarting soon")
+    );
+    println!("{:?}", admin.server_metrics());
+
+    println!("\n--- Rate limiting ---");
+    let mut room = ChatRoom::new(RateLimiter::new(3, 1.0));
+    let carol = room.join("carol".to_string()).expect("valid username");
+    let start = Instant::now();
+    for i in 1..=4 {
+        match room.post(carol.id, &format!("spam {}", i), start) {
+            Ok(seq) => println!("accepted as seq {}", seq),
+            Err(err) => println!("to carol only: {}", err),
+        }
+    }
+    let later = start + Duration::from_secs(1);
+    println!("after 1s: {:?}", room.post(carol.id, "sorry", later));
+
+    println!("\n--- Rooms ---");
+    let dave = room.join("dave".to_string()).expect("valid username");
+    for input in ["/join rust", "/rooms"] {
+        let command = RoomCommand::parse(input).expect("room command");
+        println!(
+            "{} -> {:?}",
+            input,
+            room.run_room_command(dave.id, &command)
+        );
+    }
+    let seq = room
+        .post(dave.id, "anyone here?", later)
+        .expect("dave can post");
+    println!("recipients in #rust: {:?}", room.recipients(seq));
+
+    println!("\n--- Server events ---");
+    let erin = room.join("erin".to_string()).expect("valid username");
+    room.set_event_filter(carol.id, EventFilter::ChatOnly);
+    let _ = room.rename(erin.id, "erin_b");
+    room.leave(erin.id);
+    for (name, id) in [("carol", carol.id), ("dave", dave.id)] {
+        for event in room.drain_events(id) {
+            println!("to {}: {}", name, event.format_for_broadcast());
+        }
+    }
+
+    println!("\n--- Mailroom ---");
+    let mut mailroom = room.mailroom();
+    for i in 1..=3 {
+        let text = format!("update {}", i);
+        mailroom.broadcast(
+            Message::new(dave.id, "dave".to_string(), text),
+            Some(dave.id),
+        );
+    }
+    for event in mailroom.flush(carol.id, 2) {
+        println!("to carol: {}", event.format_for_broadcast());
+    }
+    println!("carol leaves, discarding {} unread", room.leave(carol.id));
+
+    println!("\n--- Message log ---");
+    let path = std::env::temp_dir().join("chat_server_demo.log");
+    let _ = std::fs::remove_file(&path);
+    match MessageLog::open(&path) {
+        Ok(mut log) => {
+            for m in [
+                Message::new(dave.id, "dave".to_string(), "first\tline".to_string()),
+                Message::new(carol.id, "carol".to_string(), "two\nlines".to_string()),
+                Message::new(dave.id, "dave".to_string(), "bye".to_string()),
+            ] {
+                if let Err(err) = log.append(&m) {
+                    println!("append failed: {}", err);
+                }
+            }
+            println!("replayed: {:?}", MessageLog::replay(&path).map(|m| m.len()));
+            if let Ok(tail) = MessageLog::tail(&path, 2) {
+                for m in tail {
+                    println!("tail: {:?}", m.format_for_broadcast());
+                }
+            }
+        }
+        Err(err) => println!("could not open log: {}", err),
+    }
+    let _ = std::fs::remove_file(&path);
+
+    println!("\n--- Binary framing ---");
+    let mut stream = encode_frame(&Message::new(
+        alice.id,
+        "alice".to_string(),
+        "multi\nline".to_string(),
+    ));
+    stream.extend(encode_frame(&Message::new(
+        bob.id,
+        "bob".to_string(),
+        "ok".to_string(),
+    )));
+    let mut decoder = FrameDecoder::new();
+    // Feed in uneven reads, as a TCP socket might deliver them.
+    for chunk in stream.chunks(7) {
+        for result in decoder.feed(chunk) {
+            match result {
+                Ok(m) => println!("frame from {}: {:?}", m.sender_name, m.content),
+                Err(err) => println!("bad frame: {}", err),
+            }
+        }
+    }
 }
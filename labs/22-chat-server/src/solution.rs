@@ -4,7 +4,13 @@
 //! and concurrent connection handling. Demonstrates Arc<Mutex<T>> for shared state
 //! and error handling in networked applications.
 
-use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// Represents a client connected to the chat server.
 ///
@@ -17,6 +23,8 @@ pub struct Client {
     pub id: u32,
     pub username: String,
     pub is_connected: bool,
+    /// The room plain messages from this client are sent to
+    pub room: String,
 }
 
 impl Client {
@@ -31,6 +39,7 @@ impl Client {
             id,
             username,
             is_connected: true,
+            room: LOBBY.to_string(),
         }
     }
 
@@ -108,15 +117,26 @@ impl Message {
 /// - Stores messages for a client
 /// - Useful when client temporarily unavailable
 /// - In real server: message persistence
+///
+/// The item type defaults to `Message`, so `MessageQueue::new` keeps
+/// working as before; `ChatRoom` uses `MessageQueue<ServerEvent>` as each
+/// client's outbox (see `EventQueue`).
 #[derive(Clone)]
-pub struct MessageQueue {
-    messages: VecDeque<Message>,
+pub struct MessageQueue<T = Message> {
+    messages: VecDeque<T>,
     max_size: usize,
 }
 
 impl MessageQueue {
     /// Create a new message queue with max capacity
     pub fn new(max_size: usize) -> Self {
+        MessageQueue::bounded(max_size)
+    }
+}
+
+impl<T> MessageQueue<T> {
+    /// Create an empty queue of any item type holding at most `max_size`
+    pub fn bounded(max_size: usize) -> Self {
         MessageQueue {
             messages: VecDeque::new(),
             max_size,
@@ -130,7 +150,7 @@ impl MessageQueue {
     /// - Server generates messages faster than client reads
     /// - Queue buffers messages (up to max_size)
     /// - When queue full, drop oldest (or reject)
-    pub fn enqueue(&mut self, message: Message) {
+    pub fn enqueue(&mut self, message: T) {
         if self.messages.len() >= self.max_size {
             self.messages.pop_front();  // Drop oldest
         }
@@ -138,7 +158,7 @@ impl MessageQueue {
     }
 
     /// Get next message (FIFO)
-    pub fn dequeue(&mut self) -> Option<Message> {
+    pub fn dequeue(&mut self) -> Option<T> {
         self.messages.pop_front()
     }
 
@@ -187,13 +207,21 @@ impl ClientRegistry {
     /// - Takes ownership of username
     /// - Returns the assigned client ID
     /// - Adds client to registry
-    pub fn register(&mut self, username: String) -> Client {
+    ///
+    /// The name is trimmed and validated with `validate_username`, and must
+    /// not match (ignoring case) the name of a connected client.
+    pub fn register(&mut self, username: String) -> Result<Client, RegistrationError> {
+        let username = validate_username(&username)?;
+        if self.find_by_name(&username).is_some() {
+            return Err(RegistrationError::NameTaken);
+        }
+
         let id = self.next_id;
         self.next_id += 1;
 
         let client = Client::new(id, username);
         self.clients.push(client.clone());
-        client
+        Ok(client)
     }
 
     /// Find a client by ID
@@ -201,6 +229,41 @@ impl ClientRegistry {
         self.clients.iter().find(|c| c.id == id).cloned()
     }
 
+    /// Find a connected client by name, ignoring case
+    ///
+    /// Disconnected clients are skipped: their names are free to be reused,
+    /// so several old clients may share a name.
+    pub fn find_by_name(&self, name: &str) -> Option<Client> {
+        let wanted = name.trim().to_lowercase();
+        self.clients
+            .iter()
+            .find(|c| c.is_connected && c.username.to_lowercase() == wanted)
+            .cloned()
+    }
+
+    /// Change a client's username, with the same rules as `register`
+    ///
+    /// Changing only the case of your own name is allowed.
+    pub fn rename(&mut self, id: u32, new_name: &str) -> Result<(), RegistrationError> {
+        let new_name = validate_username(new_name)?;
+        if self.find_client(id).is_none() {
+            return Err(RegistrationError::UnknownClient(id));
+        }
+        if self.find_by_name(&new_name).is_some_and(|c| c.id != id) {
+            return Err(RegistrationError::NameTaken);
+        }
+
+        if let Some(client) = self.clients.iter_mut().find(|c| c.id == id) {
+            client.username = new_name;
+        }
+        Ok(())
+    }
+
+    /// Get every client ever registered, connected or not
+    pub fn clients(&self) -> &[Client] {
+        &self.clients
+    }
+
     /// Get all active clients
     pub fn active_clients(&self) -> Vec<Client> {
         self.clients.iter().filter(|c| c.is_connected).cloned().collect()
@@ -222,6 +285,21 @@ impl ClientRegistry {
     pub fn active_count(&self) -> usize {
         self.active_clients().len()
     }
+
+    /// The room a client is currently talking in
+    pub fn current_room(&self, id: u32) -> Option<String> {
+        self.clients
+            .iter()
+            .find(|c| c.id == id)
+            .map(|c| c.room.clone())
+    }
+
+    /// Change a client's current room
+    pub fn set_room(&mut self, id: u32, room: &str) {
+        if let Some(client) = self.clients.iter_mut().find(|c| c.id == id) {
+            client.room = room.to_string();
+        }
+    }
 }
 
 impl Default for ClientRegistry {
@@ -230,6 +308,57 @@ impl Default for ClientRegistry {
     }
 }
 
+/// Longest allowed username, in characters.
+pub const MAX_USERNAME_LEN: usize = 32;
+
+/// Why a username was refused.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistrationError {
+    /// The name breaks a format rule; the string says which one.
+    InvalidName(String),
+    /// A connected client already uses this name (ignoring case).
+    NameTaken,
+    UnknownClient(u32),
+}
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistrationError::InvalidName(reason) => write!(f, "invalid username: {}", reason),
+            RegistrationError::NameTaken => write!(f, "username is already taken"),
+            RegistrationError::UnknownClient(id) => write!(f, "no client with id {}", id),
+        }
+    }
+}
+
+impl std::error::Error for RegistrationError {}
+
+/// Check a username's format and return it trimmed
+///
+/// **Teaching: Validate at the boundary**
+/// - Names show up in transcripts and `/msg <name>` lookups
+/// - Control characters could mess up other users' terminals
+/// - A leading `/` would make the name look like a command
+/// - Length is counted in chars, not bytes, so "émile" is 5
+pub fn validate_username(name: &str) -> Result<String, RegistrationError> {
+    let name = name.trim();
+    let invalid = |reason: &str| Err(RegistrationError::InvalidName(reason.to_string()));
+
+    if name.is_empty() {
+        return invalid("name is empty");
+    }
+    if name.chars().count() > MAX_USERNAME_LEN {
+        return invalid("name is longer than 32 characters");
+    }
+    if name.chars().any(char::is_control) {
+        return invalid("name contains control characters");
+    }
+    if name.starts_with('/') {
+        return invalid("name starts with '/'");
+    }
+    Ok(name.to_string())
+}
+
 /// Validates commands in the chat protocol.
 ///
 /// **Teaching: Input validation**
@@ -251,6 +380,1636 @@ pub fn parse_command(input: &str) -> Option<&str> {
     }
 }
 
+/// Stores chat messages with increasing sequence numbers.
+///
+/// **Teaching: Stable identifiers**
+/// - Each recorded message gets a `seq` (1, 2, 3, ...)
+/// - Seqs never change, even after older messages are purged
+/// - So "everything before seq 42" stays meaningful over time
+#[derive(Clone, Debug)]
+pub struct MessageHistory {
+    entries: VecDeque<(u64, Message)>,
+    next_seq: u64,
+}
+
+impl MessageHistory {
+    /// Create an empty history; the first message gets seq 1
+    pub fn new() -> Self {
+        MessageHistory {
+            entries: VecDeque::new(),
+            next_seq: 1,
+        }
+    }
+
+    /// Store a message and return its sequence number
+    pub fn record(&mut self, message: Message) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.entries.push_back((seq, message));
+        seq
+    }
+
+    /// Iterate over stored messages, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &Message)> {
+        self.entries.iter().map(|(seq, message)| (*seq, message))
+    }
+
+    /// Remove every message with a seq lower than `seq`; returns how many
+    pub fn purge_before(&mut self, seq: u64) -> usize {
+        let before = self.entries.len();
+        // Seqs are increasing, so the old messages are all at the front.
+        while self.entries.front().is_some_and(|(s, _)| *s < seq) {
+            self.entries.pop_front();
+        }
+        before - self.entries.len()
+    }
+
+    /// Number of messages currently stored
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if no messages are stored
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of messages ever recorded, including purged ones
+    pub fn total_recorded(&self) -> u64 {
+        self.next_seq - 1
+    }
+}
+
+impl Default for MessageHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ============================================================================
+// ADMIN API
+// ============================================================================
+// An HTTP admin panel (for example built with the axum lab) needs to look
+// at and manage the server's state. `AdminApi` is the layer in between: it
+// borrows the registry and history and answers with plain DTOs (data
+// transfer objects) that derive `Serialize`/`Deserialize`.
+//
+// The DTOs are deliberately separate from `Client` and `Message`. If we
+// serialized the internal structs directly, every field we later add or
+// rename inside the server would silently change the JSON API. With DTOs,
+// the mapping is written down in one place and the JSON shape only changes
+// when we change it on purpose.
+
+/// Sender id used for server announcements (client ids start at 1).
+pub const SERVER_SENDER_ID: u32 = 0;
+
+/// How many of a client's most recent message seqs `client_detail` lists.
+pub const RECENT_SEQ_LIMIT: usize = 10;
+
+/// One row in the client list.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientDto {
+    pub id: u32,
+    pub username: String,
+    pub connected: bool,
+    /// Messages from this client still stored in the history.
+    pub message_count: usize,
+}
+
+/// Everything the admin panel shows for a single client.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientDetailDto {
+    pub id: u32,
+    pub username: String,
+    pub display_name: String,
+    pub connected: bool,
+    pub message_count: usize,
+    /// Seqs of the client's latest stored messages, oldest first.
+    pub recent_message_seqs: Vec<u64>,
+}
+
+/// Server-wide counters.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ServerMetricsDto {
+    pub registered_clients: usize,
+    pub active_clients: usize,
+    /// Every message ever recorded, including purged ones.
+    pub total_messages: u64,
+    /// Messages currently held in the history.
+    pub stored_messages: usize,
+    /// Ticks of the server's logical clock since start (`ChatRoom::tick`);
+    /// 0 when the API was built without a `ChatRoom`.
+    pub uptime_ticks: u64,
+    /// Undelivered events per connected client, by id; empty when the API
+    /// was built without a `ChatRoom`.
+    pub queue_depths: Vec<QueueDepthDto>,
+}
+
+/// One row in the room list.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoomDto {
+    pub name: String,
+    pub member_count: usize,
+}
+
+/// Everything the admin panel shows for a single room.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoomDetailDto {
+    pub name: String,
+    /// Members in id order.
+    pub members: Vec<ClientDto>,
+    /// Seqs of the latest stored messages posted in the room, oldest first.
+    pub recent_message_seqs: Vec<u64>,
+}
+
+/// How far behind one client's outbox is.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueueDepthDto {
+    pub client_id: u32,
+    pub pending: usize,
+}
+
+/// The JSON body returned by a successful admin action.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdminActionResult {
+    /// Machine-readable action name, e.g. `"kick_client"`.
+    pub action: String,
+    /// How many clients or messages the action touched.
+    pub affected: usize,
+    /// Seq of a message the action created, if any.
+    pub seq: Option<u64>,
+    /// Human-readable summary.
+    pub detail: String,
+}
+
+/// Admin failures, serialized with a machine-readable `code` field:
+/// `{"code": "client_not_found", "id": 7}`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum AdminError {
+    ClientNotFound { id: u32 },
+    ClientNotConnected { id: u32 },
+    RoomNotFound { room: String },
+    EmptyAnnouncement,
+}
+
+impl AdminError {
+    /// The same code that appears in the serialized form
+    pub fn code(&self) -> &'static str {
+        match self {
+            AdminError::ClientNotFound { .. } => "client_not_found",
+            AdminError::ClientNotConnected { .. } => "client_not_connected",
+            AdminError::RoomNotFound { .. } => "room_not_found",
+            AdminError::EmptyAnnouncement => "empty_announcement",
+        }
+    }
+}
+
+impl fmt::Display for AdminError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdminError::ClientNotFound { id } => write!(f, "no client with id {}", id),
+            AdminError::ClientNotConnected { id } => write!(f, "client {} is not connected", id),
+            AdminError::RoomNotFound { room } => write!(f, "no room named #{}", room),
+            AdminError::EmptyAnnouncement => write!(f, "announcement text is empty"),
+        }
+    }
+}
+
+impl std::error::Error for AdminError {}
+
+/// Admin facade over the server state.
+///
+/// **From the borrow checker's perspective:**
+/// - Holds `&mut` borrows, so nothing else can touch the server state
+///   while an admin request is being handled
+/// - In a real server the caller would lock the `Arc<Mutex<>>`s first and
+///   build an `AdminApi` from the guards for the duration of one request
+///
+/// Built from a `ChatRoom` (see `ChatRoom::admin`), kicks and announcements
+/// go through the room the same way a disconnect or a chat message does.
+/// Built from a bare registry and history, there are no rooms or outboxes:
+/// announcements are only recorded and room queries find nothing.
+pub struct AdminApi<'a> {
+    state: AdminState<'a>,
+}
+
+enum AdminState<'a> {
+    Parts {
+        registry: &'a mut ClientRegistry,
+        history: &'a mut MessageHistory,
+    },
+    Room(&'a mut ChatRoom),
+}
+
+impl<'a> AdminApi<'a> {
+    pub fn new(registry: &'a mut ClientRegistry, history: &'a mut MessageHistory) -> Self {
+        AdminApi {
+            state: AdminState::Parts { registry, history },
+        }
+    }
+
+    /// All clients, connected or not, in registration order
+    pub fn list_clients(&self) -> Vec<ClientDto> {
+        self.registry()
+            .clients()
+            .iter()
+            .map(|client| self.client_dto(client))
+            .collect()
+    }
+
+    pub fn client_detail(&self, id: u32) -> Result<ClientDetailDto, AdminError> {
+        let client = self
+            .registry()
+            .find_client(id)
+            .ok_or(AdminError::ClientNotFound { id })?;
+        let seqs = self.seqs_from(id);
+        let recent = seqs[seqs.len().saturating_sub(RECENT_SEQ_LIMIT)..].to_vec();
+
+        Ok(ClientDetailDto {
+            id: client.id,
+            display_name: client.display_name(),
+            username: client.username,
+            connected: client.is_connected,
+            message_count: seqs.len(),
+            recent_message_seqs: recent,
+        })
+    }
+
+    /// Every room with its member count, sorted by name
+    pub fn list_rooms(&self) -> Vec<RoomDto> {
+        let Some(room) = self.room() else {
+            return Vec::new();
+        };
+        room.rooms
+            .rooms()
+            .into_iter()
+            .map(|(name, member_count)| RoomDto { name, member_count })
+            .collect()
+    }
+
+    pub fn room_detail(&self, name: &str) -> Result<RoomDetailDto, AdminError> {
+        let room = self
+            .room()
+            .filter(|room| room.rooms.contains(name))
+            .ok_or_else(|| AdminError::RoomNotFound {
+                room: name.to_string(),
+            })?;
+        let members = room
+            .rooms
+            .broadcast_targets(name)
+            .into_iter()
+            .filter_map(|id| room.registry.find_client(id))
+            .map(|client| self.client_dto(&client))
+            .collect();
+
+        Ok(RoomDetailDto {
+            name: name.to_string(),
+            members,
+            recent_message_seqs: room.recent_room_seqs(name),
+        })
+    }
+
+    pub fn server_metrics(&self) -> ServerMetricsDto {
+        let registry = self.registry();
+        let history = self.history();
+        ServerMetricsDto {
+            registered_clients: registry.client_count(),
+            active_clients: registry.active_count(),
+            total_messages: history.total_recorded(),
+            stored_messages: history.len(),
+            uptime_ticks: self.room().map_or(0, |room| room.ticks),
+            queue_depths: self.queue_depths(),
+        }
+    }
+
+    fn queue_depths(&self) -> Vec<QueueDepthDto> {
+        let Some(room) = self.room() else {
+            return Vec::new();
+        };
+        room.registry
+            .active_clients()
+            .iter()
+            .map(|client| QueueDepthDto {
+                client_id: client.id,
+                pending: room.outboxes.get(&client.id).map_or(0, |o| o.queue.size()),
+            })
+            .collect()
+    }
+
+    /// Disconnect a client, exactly as if it had left on its own
+    pub fn kick_client(&mut self, id: u32) -> Result<AdminActionResult, AdminError> {
+        let client = self
+            .registry()
+            .find_client(id)
+            .ok_or(AdminError::ClientNotFound { id })?;
+        if !client.is_active() {
+            return Err(AdminError::ClientNotConnected { id });
+        }
+
+        match &mut self.state {
+            AdminState::Parts { registry, .. } => registry.disconnect(id),
+            AdminState::Room(room) => {
+                room.leave(id);
+            }
+        }
+        Ok(AdminActionResult {
+            action: "kick_client".to_string(),
+            affected: 1,
+            seq: None,
+            detail: format!("kicked {}", client.display_name()),
+        })
+    }
+
+    /// Record a server message and queue it for every connected client;
+    /// `affected` is the number of clients it reaches
+    pub fn broadcast_announcement(&mut self, text: &str) -> Result<AdminActionResult, AdminError> {
+        let message = Message::parse(SERVER_SENDER_ID, "server".to_string(), text)
+            .ok_or(AdminError::EmptyAnnouncement)?;
+        let (seq, recipients) = match &mut self.state {
+            AdminState::Parts { registry, history } => {
+                (history.record(message), registry.active_count())
+            }
+            AdminState::Room(room) => room.announce(message),
+        };
+
+        Ok(AdminActionResult {
+            action: "broadcast_announcement".to_string(),
+            affected: recipients,
+            seq: Some(seq),
+            detail: format!("announcement sent to {} clients", recipients),
+        })
+    }
+
+    /// Drop every stored message older than `seq`
+    pub fn purge_history_before(&mut self, seq: u64) -> AdminActionResult {
+        let history = match &mut self.state {
+            AdminState::Parts { history, .. } => history,
+            AdminState::Room(room) => &mut room.history,
+        };
+        let removed = history.purge_before(seq);
+        AdminActionResult {
+            action: "purge_history_before".to_string(),
+            affected: removed,
+            seq: None,
+            detail: format!("purged {} messages before seq {}", removed, seq),
+        }
+    }
+
+    fn registry(&self) -> &ClientRegistry {
+        match &self.state {
+            AdminState::Parts { registry, .. } => registry,
+            AdminState::Room(room) => &room.registry,
+        }
+    }
+
+    fn history(&self) -> &MessageHistory {
+        match &self.state {
+            AdminState::Parts { history, .. } => history,
+            AdminState::Room(room) => &room.history,
+        }
+    }
+
+    fn room(&self) -> Option<&ChatRoom> {
+        match &self.state {
+            AdminState::Parts { .. } => None,
+            AdminState::Room(room) => Some(room),
+        }
+    }
+
+    fn client_dto(&self, client: &Client) -> ClientDto {
+        ClientDto {
+            id: client.id,
+            username: client.username.clone(),
+            connected: client.is_active(),
+            message_count: self.seqs_from(client.id).len(),
+        }
+    }
+
+    fn seqs_from(&self, sender_id: u32) -> Vec<u64> {
+        self.history()
+            .iter()
+            .filter(|(_, message)| message.sender_id == sender_id)
+            .map(|(seq, _)| seq)
+            .collect()
+    }
+}
+
+// ============================================================================
+// CHAT ROOMS
+// ============================================================================
+// Instead of one global room, clients can `/join` named rooms. A client may
+// be a member of several rooms; the one joined most recently is its
+// *current* room, and plain messages only go there. A client that isn't in
+// any room sits in the lobby, which always exists. Other rooms are created
+// on first join and removed when the last member leaves.
+
+/// The room every client starts in.
+pub const LOBBY: &str = "lobby";
+
+/// Room-related chat commands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RoomCommand {
+    Join(String),
+    Leave(String),
+    Rooms,
+}
+
+impl RoomCommand {
+    /// Parse `/join <room>`, `/leave <room>` or `/rooms`
+    ///
+    /// A leading `#` on the room name is ignored. Returns None for plain
+    /// messages, other commands, and `/join` or `/leave` without a room name.
+    pub fn parse(input: &str) -> Option<Self> {
+        let command = parse_command(input)?;
+        let mut parts = command.split_whitespace();
+        let name = parts.next()?;
+        let arg = parts.next().map(|room| room.trim_start_matches('#'));
+        if parts.next().is_some() {
+            return None;
+        }
+
+        match (name, arg) {
+            (_, Some("")) => None,
+            ("join", Some(room)) => Some(RoomCommand::Join(room.to_string())),
+            ("leave", Some(room)) => Some(RoomCommand::Leave(room.to_string())),
+            ("rooms", None) => Some(RoomCommand::Rooms),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RoomError {
+    UnknownClient { id: u32 },
+    NotInRoom { room: String },
+    CannotLeaveLobby,
+}
+
+impl fmt::Display for RoomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoomError::UnknownClient { id } => write!(f, "no client with id {}", id),
+            RoomError::NotInRoom { room } => write!(f, "you are not in #{}", room),
+            RoomError::CannotLeaveLobby => write!(f, "the lobby cannot be left"),
+        }
+    }
+}
+
+impl std::error::Error for RoomError {}
+
+/// Maps room names to member client ids.
+///
+/// **Teaching: Two indexes over the same data**
+/// - `rooms` answers "who is in #rust?" (for broadcasting)
+/// - `joined` answers "which rooms is client 3 in, in join order?"
+/// - Every method updates both, so they never disagree
+/// - BTree collections keep `/rooms` output and targets sorted
+#[derive(Clone, Debug)]
+pub struct RoomManager {
+    rooms: BTreeMap<String, BTreeSet<u32>>,
+    joined: HashMap<u32, Vec<String>>,
+}
+
+impl RoomManager {
+    /// Create a manager containing only the (empty) lobby
+    pub fn new() -> Self {
+        let mut rooms = BTreeMap::new();
+        rooms.insert(LOBBY.to_string(), BTreeSet::new());
+        RoomManager {
+            rooms,
+            joined: HashMap::new(),
+        }
+    }
+
+    /// Put a newly connected client in the lobby
+    pub fn connect(&mut self, client_id: u32) {
+        self.joined.entry(client_id).or_default();
+        self.lobby_mut().insert(client_id);
+    }
+
+    /// Join (or create) a room and make it current; returns the current room
+    ///
+    /// Joining the lobby leaves every other room.
+    pub fn join(&mut self, client_id: u32, room: &str) -> String {
+        if room == LOBBY {
+            for name in self.joined.remove(&client_id).unwrap_or_default() {
+                self.remove_member(&name, client_id);
+            }
+            self.connect(client_id);
+            return LOBBY.to_string();
+        }
+
+        self.lobby_mut().remove(&client_id);
+        self.rooms
+            .entry(room.to_string())
+            .or_default()
+            .insert(client_id);
+
+        let joined = self.joined.entry(client_id).or_default();
+        joined.retain(|name| name != room);
+        joined.push(room.to_string());
+        room.to_string()
+    }
+
+    /// Leave a room; returns the client's new current room
+    pub fn leave(&mut self, client_id: u32, room: &str) -> Result<String, RoomError> {
+        if room == LOBBY {
+            return Err(RoomError::CannotLeaveLobby);
+        }
+        let joined = self
+            .joined
+            .get_mut(&client_id)
+            .filter(|joined| joined.iter().any(|name| name == room))
+            .ok_or_else(|| RoomError::NotInRoom {
+                room: room.to_string(),
+            })?;
+        joined.retain(|name| name != room);
+        let current = joined.last().cloned();
+
+        self.remove_member(room, client_id);
+        match current {
+            Some(room) => Ok(room),
+            None => {
+                self.lobby_mut().insert(client_id);
+                Ok(LOBBY.to_string())
+            }
+        }
+    }
+
+    /// Remove a client from every room, including the lobby
+    pub fn disconnect(&mut self, client_id: u32) {
+        for name in self.joined.remove(&client_id).unwrap_or_default() {
+            self.remove_member(&name, client_id);
+        }
+        self.lobby_mut().remove(&client_id);
+    }
+
+    /// Member ids of a room, sorted; empty if the room doesn't exist
+    pub fn broadcast_targets(&self, room: &str) -> Vec<u32> {
+        self.rooms
+            .get(room)
+            .map(|members| members.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Every room with its member count, sorted by name
+    pub fn rooms(&self) -> Vec<(String, usize)> {
+        self.rooms
+            .iter()
+            .map(|(name, members)| (name.clone(), members.len()))
+            .collect()
+    }
+
+    /// Check if a room currently exists
+    pub fn contains(&self, room: &str) -> bool {
+        self.rooms.contains_key(room)
+    }
+
+    fn lobby_mut(&mut self) -> &mut BTreeSet<u32> {
+        self.rooms.entry(LOBBY.to_string()).or_default()
+    }
+
+    /// Remove one member, garbage-collecting the room if it is now empty
+    fn remove_member(&mut self, room: &str, client_id: u32) {
+        if let Some(members) = self.rooms.get_mut(room) {
+            members.remove(&client_id);
+            if members.is_empty() && room != LOBBY {
+                self.rooms.remove(room);
+            }
+        }
+    }
+}
+
+impl Default for RoomManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Who should receive a plain message: the connected members of the
+/// sender's current room, not including the sender.
+pub fn route_message(
+    registry: &ClientRegistry,
+    rooms: &RoomManager,
+    message: &Message,
+) -> Vec<u32> {
+    let Some(room) = registry.current_room(message.sender_id) else {
+        return Vec::new();
+    };
+    rooms
+        .broadcast_targets(&room)
+        .into_iter()
+        .filter(|&id| id != message.sender_id)
+        .filter(|&id| registry.find_client(id).is_some_and(|c| c.is_active()))
+        .collect()
+}
+
+// ============================================================================
+// RATE LIMITING
+// ============================================================================
+// One chatty (or malicious) client can flood everyone else. A token bucket
+// gives each client `capacity` tokens; every message costs one, and tokens
+// trickle back at `refill_per_sec`. That allows short bursts but caps the
+// long-run rate.
+//
+// Time is passed in as `now` instead of calling `Instant::now()` inside, so
+// tests can "advance the clock" by adding a Duration to a fixed Instant.
+
+/// Result of asking the limiter whether a client may send.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Allowed,
+    /// Try again after this long; by then one token will have refilled.
+    Limited {
+        retry_after: Duration,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Per-client token buckets.
+///
+/// **Teaching: Lazy state**
+/// - A bucket is created (full) the first time a client id is checked
+/// - Refill is computed from the elapsed time on each `check`, so no
+///   background timer is needed
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    capacity: u32,
+    refill_per_sec: f64,
+    buckets: HashMap<u32, Bucket>,
+}
+
+impl RateLimiter {
+    /// A limiter allowing bursts of `capacity` messages, refilling
+    /// `refill_per_sec` tokens per second. A rate of zero never refills.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero: a bucket that can't hold one token would
+    /// refuse every message forever.
+    pub fn new(capacity: u32, refill_per_sec: f64) -> Self {
+        assert!(capacity > 0, "rate limiter capacity must be at least 1");
+        RateLimiter {
+            capacity,
+            refill_per_sec: refill_per_sec.max(0.0),
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Spend one token for `client_id` if it has one
+    pub fn check(&mut self, client_id: u32, now: Instant) -> Decision {
+        let capacity = f64::from(self.capacity);
+        let rate = self.refill_per_sec;
+        let bucket = self.buckets.entry(client_id).or_insert(Bucket {
+            tokens: capacity,
+            last_refill: now,
+        });
+
+        // saturating: a `now` earlier than the last check just adds nothing
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * rate).min(capacity);
+        bucket.last_refill = bucket.last_refill.max(now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Decision::Allowed
+        } else if rate > 0.0 {
+            let missing = 1.0 - bucket.tokens;
+            Decision::Limited {
+                // A tiny rate can push this past Duration::MAX; that is
+                // "never" for practical purposes.
+                retry_after: Duration::try_from_secs_f64(missing / rate).unwrap_or(Duration::MAX),
+            }
+        } else {
+            Decision::Limited {
+                retry_after: Duration::MAX,
+            }
+        }
+    }
+
+    /// Drop a client's bucket (e.g. when it disconnects)
+    pub fn forget(&mut self, client_id: u32) {
+        self.buckets.remove(&client_id);
+    }
+}
+
+/// Why `ChatRoom::post` refused a message. The `Display` text is meant to
+/// be sent back to the posting client only.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PostError {
+    UnknownClient { id: u32 },
+    NotConnected { id: u32 },
+    EmptyMessage,
+    RateLimited { retry_after: Duration },
+}
+
+impl fmt::Display for PostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PostError::UnknownClient { id } => write!(f, "no client with id {}", id),
+            PostError::NotConnected { id } => write!(f, "client {} is not connected", id),
+            PostError::EmptyMessage => write!(f, "message is empty"),
+            PostError::RateLimited { retry_after } => write!(
+                f,
+                "slow down: try again in {:.1}s",
+                retry_after.as_secs_f64()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PostError {}
+
+/// The server's shared state with a single entry point for chat messages.
+///
+/// **Teaching: One path for every message**
+/// - Everything a client says goes through `post`
+/// - So validation and rate limiting happen in exactly one place
+/// - Accepted messages land in the history; rejected ones return an error
+///   the connection handler writes back to that client only
+/// - Accepted messages, joins, leaves, and renames all become
+///   `ServerEvent`s in the recipients' outboxes
+pub struct ChatRoom {
+    registry: ClientRegistry,
+    history: MessageHistory,
+    limiter: RateLimiter,
+    rooms: RoomManager,
+    outboxes: HashMap<u32, Outbox>,
+    /// Latest seqs posted in each room, at most `RECENT_SEQ_LIMIT` per room
+    room_seqs: HashMap<String, VecDeque<u64>>,
+    ticks: u64,
+}
+
+impl ChatRoom {
+    pub fn new(limiter: RateLimiter) -> Self {
+        ChatRoom {
+            registry: ClientRegistry::new(),
+            history: MessageHistory::new(),
+            limiter,
+            rooms: RoomManager::new(),
+            outboxes: HashMap::new(),
+            room_seqs: HashMap::new(),
+            ticks: 0,
+        }
+    }
+
+    /// Advance the server's logical clock; the accept loop calls this once
+    /// per pass. Admin metrics report uptime in these ticks.
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+    }
+
+    pub fn uptime_ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Register a new client; it starts in the lobby and everyone else
+    /// gets a `UserJoined` event
+    pub fn join(&mut self, username: String) -> Result<Client, RegistrationError> {
+        let client = self.registry.register(username)?;
+        self.rooms.connect(client.id);
+        self.outboxes.insert(client.id, Outbox::new());
+        self.publish(ServerEvent::UserJoined {
+            id: client.id,
+            username: client.username.clone(),
+        });
+        Ok(client)
+    }
+
+    /// Disconnect a client, remove it from all rooms, and forget its
+    /// rate-limit bucket
+    ///
+    /// The `UserLeft` event is tied to dropping the client's outbox, so
+    /// calling this twice (an admin kick is a call too) announces it only
+    /// once.
+    /// Returns how many undelivered events the dropped outbox still held.
+    pub fn leave(&mut self, id: u32) -> usize {
+        self.registry.disconnect(id);
+        self.rooms.disconnect(id);
+        self.limiter.forget(id);
+
+        let Some(outbox) = self.outboxes.remove(&id) else {
+            return 0;
+        };
+        if let Some(client) = self.registry.find_client(id) {
+            self.publish(ServerEvent::UserLeft {
+                id,
+                username: client.username,
+            });
+        }
+        outbox.queue.size()
+    }
+
+    /// Change a connected client's name and tell everyone else
+    ///
+    /// Same rules as `ClientRegistry::rename`; a no-op rename (same name,
+    /// same case) sends no event.
+    pub fn rename(&mut self, id: u32, new_name: &str) -> Result<(), RegistrationError> {
+        let client = self
+            .registry
+            .find_client(id)
+            .filter(|c| c.is_active())
+            .ok_or(RegistrationError::UnknownClient(id))?;
+        self.registry.rename(id, new_name)?;
+
+        let new_name = self
+            .registry
+            .find_client(id)
+            .map(|c| c.username)
+            .unwrap_or_default();
+        if new_name != client.username {
+            self.publish(ServerEvent::UserRenamed {
+                id,
+                old_name: client.username,
+                new_name,
+            });
+        }
+        Ok(())
+    }
+
+    /// Choose which events a client receives from now on
+    pub fn set_event_filter(&mut self, id: u32, filter: EventFilter) {
+        if let Some(outbox) = self.outboxes.get_mut(&id) {
+            outbox.filter = filter;
+        }
+    }
+
+    /// Pop the next event waiting for a client
+    pub fn next_event(&mut self, id: u32) -> Option<ServerEvent> {
+        self.outboxes.get_mut(&id)?.queue.dequeue()
+    }
+
+    /// Take every event waiting for a client, oldest first
+    pub fn drain_events(&mut self, id: u32) -> Vec<ServerEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = self.next_event(id) {
+            events.push(event);
+        }
+        events
+    }
+
+    /// Queue a presence event for every connected client except the one it
+    /// is about
+    fn publish(&mut self, event: ServerEvent) {
+        let subject = event.client_id();
+        let targets: Vec<u32> = self
+            .registry
+            .active_clients()
+            .iter()
+            .map(|c| c.id)
+            .filter(|&id| id != subject)
+            .collect();
+        self.deliver(&targets, &event);
+    }
+
+    /// Queue `event` for each target whose filter lets it through
+    fn deliver(&mut self, targets: &[u32], event: &ServerEvent) {
+        for id in targets {
+            if let Some(outbox) = self.outboxes.get_mut(id) {
+                if outbox.filter.allows(event) {
+                    outbox.queue.enqueue(event.clone());
+                }
+            }
+        }
+    }
+
+    /// Run a room command for a client; returns the reply to send it
+    pub fn run_room_command(
+        &mut self,
+        client_id: u32,
+        command: &RoomCommand,
+    ) -> Result<String, RoomError> {
+        if !self
+            .registry
+            .find_client(client_id)
+            .is_some_and(|c| c.is_active())
+        {
+            return Err(RoomError::UnknownClient { id: client_id });
+        }
+
+        match command {
+            RoomCommand::Join(room) => {
+                let current = self.rooms.join(client_id, room);
+                self.registry.set_room(client_id, &current);
+                Ok(format!("now talking in #{}", current))
+            }
+            RoomCommand::Leave(room) => {
+                let current = self.rooms.leave(client_id, room)?;
+                self.registry.set_room(client_id, &current);
+                Ok(format!("left #{}, now talking in #{}", room, current))
+            }
+            RoomCommand::Rooms => Ok(self
+                .rooms
+                .rooms()
+                .iter()
+                .map(|(name, count)| format!("#{} ({})", name, count))
+                .collect::<Vec<_>>()
+                .join(", ")),
+        }
+    }
+
+    /// Ids that should receive the message stored under `seq`
+    ///
+    /// Routing uses the sender's current room, so call this right after
+    /// `post` (before the sender can switch rooms).
+    pub fn recipients(&self, seq: u64) -> Vec<u32> {
+        self.history
+            .iter()
+            .find(|(s, _)| *s == seq)
+            .map(|(_, message)| route_message(&self.registry, &self.rooms, message))
+            .unwrap_or_default()
+    }
+
+    /// Validate, rate-limit, and record a message; returns its seq
+    ///
+    /// Empty input is rejected before the limiter, so it costs no token.
+    /// An accepted message is queued as `ServerEvent::Chat` for everyone
+    /// `recipients` would return.
+    pub fn post(&mut self, client_id: u32, input: &str, now: Instant) -> Result<u64, PostError> {
+        let client = self
+            .registry
+            .find_client(client_id)
+            .ok_or(PostError::UnknownClient { id: client_id })?;
+        if !client.is_active() {
+            return Err(PostError::NotConnected { id: client_id });
+        }
+        let message =
+            Message::parse(client.id, client.username, input).ok_or(PostError::EmptyMessage)?;
+
+        if let Decision::Limited { retry_after } = self.limiter.check(client_id, now) {
+            return Err(PostError::RateLimited { retry_after });
+        }
+        let targets = route_message(&self.registry, &self.rooms, &message);
+        let room = self.registry.current_room(client_id);
+        let seq = self.history.record(message.clone());
+        if let Some(room) = room {
+            let seqs = self.room_seqs.entry(room).or_default();
+            if seqs.len() == RECENT_SEQ_LIMIT {
+                seqs.pop_front();
+            }
+            seqs.push_back(seq);
+        }
+        self.deliver(&targets, &ServerEvent::Chat(message));
+        Ok(seq)
+    }
+
+    /// Record a server message and queue it as `ServerEvent::Chat` for
+    /// every connected client, whatever room it is in; returns the seq and
+    /// how many clients it was queued for
+    fn announce(&mut self, message: Message) -> (u64, usize) {
+        let targets: Vec<u32> = self
+            .registry
+            .active_clients()
+            .iter()
+            .map(|c| c.id)
+            .collect();
+        let seq = self.history.record(message.clone());
+        self.deliver(&targets, &ServerEvent::Chat(message));
+        (seq, targets.len())
+    }
+
+    /// Seqs of the latest messages posted in `room` that are still stored
+    fn recent_room_seqs(&self, room: &str) -> Vec<u64> {
+        let Some(seqs) = self.room_seqs.get(room) else {
+            return Vec::new();
+        };
+        let stored: HashSet<u64> = self.history.iter().map(|(seq, _)| seq).collect();
+        seqs.iter()
+            .copied()
+            .filter(|seq| stored.contains(seq))
+            .collect()
+    }
+
+    pub fn registry(&self) -> &ClientRegistry {
+        &self.registry
+    }
+
+    pub fn history(&self) -> &MessageHistory {
+        &self.history
+    }
+
+    pub fn rooms(&self) -> &RoomManager {
+        &self.rooms
+    }
+
+    /// Borrow the whole room as an `AdminApi`, with rooms, outbox depths,
+    /// and uptime available to it
+    pub fn admin(&mut self) -> AdminApi<'_> {
+        AdminApi {
+            state: AdminState::Room(self),
+        }
+    }
+
+    /// Borrow the outboxes as a `Mailroom` for batched delivery
+    pub fn mailroom(&mut self) -> Mailroom<'_> {
+        Mailroom {
+            registry: &self.registry,
+            outboxes: &mut self.outboxes,
+        }
+    }
+}
+
+// ============================================================================
+// SERVER EVENTS
+// ============================================================================
+// Chat messages are not the only thing clients need to hear about: people
+// join, leave, and change names. Rather than writing those notices straight
+// to sockets from three different places, `ChatRoom` turns each of them into
+// a `ServerEvent` and pushes it through the same per-client queue as chat.
+// The connection handler then has one loop: pop an event, format it, write
+// it. Presence notices are rendered as `* alice joined` so they stand out
+// from `alice: hello`.
+
+/// How many undelivered events each client's outbox holds before the
+/// oldest are dropped.
+pub const OUTBOX_CAPACITY: usize = 256;
+
+/// Something a connected client should be told about.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServerEvent {
+    UserJoined {
+        id: u32,
+        username: String,
+    },
+    UserLeft {
+        id: u32,
+        username: String,
+    },
+    UserRenamed {
+        id: u32,
+        old_name: String,
+        new_name: String,
+    },
+    Chat(Message),
+}
+
+impl ServerEvent {
+    /// Render the event as one line for the wire
+    pub fn format_for_broadcast(&self) -> String {
+        match self {
+            ServerEvent::UserJoined { username, .. } => format!("* {} joined", username),
+            ServerEvent::UserLeft { username, .. } => format!("* {} left", username),
+            ServerEvent::UserRenamed {
+                old_name, new_name, ..
+            } => format!("* {} is now known as {}", old_name, new_name),
+            ServerEvent::Chat(message) => message.format_for_broadcast(),
+        }
+    }
+
+    /// Join/leave/rename notices, as opposed to chat
+    pub fn is_presence(&self) -> bool {
+        !matches!(self, ServerEvent::Chat(_))
+    }
+
+    /// The client the event is about (the sender, for chat)
+    pub fn client_id(&self) -> u32 {
+        match self {
+            ServerEvent::UserJoined { id, .. }
+            | ServerEvent::UserLeft { id, .. }
+            | ServerEvent::UserRenamed { id, .. } => *id,
+            ServerEvent::Chat(message) => message.sender_id,
+        }
+    }
+}
+
+/// A client's outbox: the message queue, holding events instead.
+pub type EventQueue = MessageQueue<ServerEvent>;
+
+/// Which events a client wants delivered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EventFilter {
+    #[default]
+    Everything,
+    /// Chat only: no join/leave/rename notices
+    ChatOnly,
+}
+
+impl EventFilter {
+    pub fn allows(&self, event: &ServerEvent) -> bool {
+        match self {
+            EventFilter::Everything => true,
+            EventFilter::ChatOnly => !event.is_presence(),
+        }
+    }
+}
+
+/// Per-client delivery state kept by `ChatRoom`.
+#[derive(Clone)]
+struct Outbox {
+    queue: EventQueue,
+    filter: EventFilter,
+}
+
+impl Outbox {
+    fn new() -> Self {
+        Outbox {
+            queue: EventQueue::bounded(OUTBOX_CAPACITY),
+            filter: EventFilter::default(),
+        }
+    }
+}
+
+// ============================================================================
+// MAILROOM
+// ============================================================================
+// The outboxes above already give every connected client its own queue;
+// what the connection loop still needs is a way to hand out work in
+// bounded pieces and to push server-side messages into those queues
+// without going through `post`. The `Mailroom` is that view of a
+// `ChatRoom`, borrowed the same way `AdminApi` is:
+//
+//   loop {
+//       let batch = room.mailroom().flush(id, 32);
+//       for event in batch { write_line(&event.format_for_broadcast()) }
+//   }
+//
+// Batching bounds how long one client's backlog can hold up the loop, and
+// whatever does not fit stays queued in order for the next flush. There is
+// no second set of queues: `deliver_to` and `broadcast` enqueue into the
+// same outboxes as chat and presence events, reach exactly the registry's
+// active clients, and keep the usual overflow rule (when an outbox holds
+// `OUTBOX_CAPACITY` events, the oldest goes). An outbox starts as an empty
+// `VecDeque`, which allocates nothing until the first event arrives.
+
+/// Batched delivery into a `ChatRoom`'s per-client outboxes.
+pub struct Mailroom<'a> {
+    registry: &'a ClientRegistry,
+    outboxes: &'a mut HashMap<u32, Outbox>,
+}
+
+impl<'a> Mailroom<'a> {
+    /// Queue a message for one connected client. Returns false if the
+    /// client is unknown or disconnected; the message is dropped.
+    ///
+    /// Chat passes every `EventFilter`, so a connected client always
+    /// receives it.
+    pub fn deliver_to(&mut self, client_id: u32, message: Message) -> bool {
+        if !self.is_active(client_id) {
+            return false;
+        }
+        match self.outboxes.get_mut(&client_id) {
+            Some(outbox) => {
+                outbox.queue.enqueue(ServerEvent::Chat(message));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Queue a copy for every active client except `except` (usually the
+    /// sender). Returns how many clients received it.
+    pub fn broadcast(&mut self, message: Message, except: Option<u32>) -> usize {
+        let targets: Vec<u32> = self
+            .registry
+            .active_clients()
+            .iter()
+            .map(|c| c.id)
+            .filter(|&id| Some(id) != except)
+            .collect();
+        targets
+            .into_iter()
+            .filter(|&id| self.deliver_to(id, message.clone()))
+            .count()
+    }
+
+    /// Dequeue up to `max_batch` events, oldest first. The rest stay
+    /// queued for the next call.
+    pub fn flush(&mut self, client_id: u32, max_batch: usize) -> Vec<ServerEvent> {
+        let Some(outbox) = self.outboxes.get_mut(&client_id) else {
+            return Vec::new();
+        };
+        let mut batch = Vec::with_capacity(max_batch.min(outbox.queue.size()));
+        while batch.len() < max_batch {
+            match outbox.queue.dequeue() {
+                Some(event) => batch.push(event),
+                None => break,
+            }
+        }
+        batch
+    }
+
+    /// Events waiting for a client (0 once it has left).
+    pub fn pending(&self, client_id: u32) -> usize {
+        self.outboxes.get(&client_id).map_or(0, |o| o.queue.size())
+    }
+
+    fn is_active(&self, client_id: u32) -> bool {
+        self.registry
+            .find_client(client_id)
+            .is_some_and(|c| c.is_active())
+    }
+}
+
+// ============================================================================
+// MESSAGE LOG
+// ============================================================================
+// Messages only live in memory, so a restart loses the whole conversation.
+// `MessageLog` appends each message to a text file, one per line:
+//
+//     timestamp<TAB>sender_id<TAB>sender_name<TAB>content
+//
+// Tabs, newlines, and backslashes inside fields are escaped (`\t`, `\n`,
+// `\\`), so a raw tab always separates fields and a raw newline always ends
+// an entry. Append-only means a crash can at worst damage the last line;
+// replay skips any line it can't parse and reports how many it skipped.
+
+/// One message read back from a log, with the time it was appended.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub message: Message,
+}
+
+/// The result of reading a log: the good entries and how many lines were
+/// skipped because they couldn't be parsed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogReplay {
+    pub entries: Vec<LogEntry>,
+    pub skipped: usize,
+}
+
+impl LogReplay {
+    /// Just the messages, oldest first.
+    pub fn messages(&self) -> Vec<Message> {
+        self.entries.iter().map(|e| e.message.clone()).collect()
+    }
+}
+
+/// An append-only, line-based message log on disk.
+pub struct MessageLog {
+    file: File,
+    path: PathBuf,
+}
+
+impl MessageLog {
+    /// How far `tail` reads backwards at a time.
+    const TAIL_CHUNK: u64 = 8 * 1024;
+
+    /// Open `path` for appending, creating it if needed.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(MessageLog { file, path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `message`, stamped with the current time.
+    ///
+    /// The whole line goes out in one `write_all`, so entries from separate
+    /// calls never interleave.
+    pub fn append(&mut self, message: &Message) -> io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let line = encode_log_line(timestamp, message);
+        self.file.write_all(line.as_bytes())
+    }
+
+    /// Every readable message in the log, oldest first.
+    pub fn replay(path: impl AsRef<Path>) -> io::Result<Vec<Message>> {
+        Ok(Self::replay_entries(path)?.messages())
+    }
+
+    /// Like `replay`, but keeps timestamps and the corrupt-line count.
+    pub fn replay_entries(path: impl AsRef<Path>) -> io::Result<LogReplay> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut replay = LogReplay::default();
+        // Split on raw bytes: a line with invalid UTF-8 is corrupt, not a
+        // reason to abort the whole replay.
+        for line in reader.split(b'\n') {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            match decode_log_line(&line) {
+                Some(entry) => replay.entries.push(entry),
+                None => replay.skipped += 1,
+            }
+        }
+        Ok(replay)
+    }
+
+    /// The last `n` readable messages, oldest first.
+    pub fn tail(path: impl AsRef<Path>, n: usize) -> io::Result<Vec<Message>> {
+        Ok(Self::tail_entries(path, n)?.messages())
+    }
+
+    /// Like `tail`, but keeps timestamps and the corrupt-line count.
+    ///
+    /// Reads the file backwards in chunks from the end and stops as soon as
+    /// it has `n` good entries, so the cost depends on `n`, not on how long
+    /// the log is. `skipped` counts only the corrupt lines it passed over.
+    pub fn tail_entries(path: impl AsRef<Path>, n: usize) -> io::Result<LogReplay> {
+        let mut file = File::open(path)?;
+        let mut pos = file.seek(SeekFrom::End(0))?;
+        let mut replay = LogReplay::default();
+        // Bytes read so far that don't yet form a complete line: everything
+        // before the earliest newline we've seen.
+        let mut pending: Vec<u8> = Vec::new();
+
+        let take = |line: &[u8], replay: &mut LogReplay| {
+            if line.is_empty() {
+                return;
+            }
+            match decode_log_line(line) {
+                Some(entry) => replay.entries.push(entry),
+                None => replay.skipped += 1,
+            }
+        };
+
+        while pos > 0 && replay.entries.len() < n {
+            let len = Self::TAIL_CHUNK.min(pos);
+            pos -= len;
+            let mut chunk = vec![0; len as usize];
+            file.seek(SeekFrom::Start(pos))?;
+            file.read_exact(&mut chunk)?;
+            chunk.extend_from_slice(&pending);
+            pending = chunk;
+
+            // Everything after a newline is a complete line.
+            while let Some(newline) = pending.iter().rposition(|&b| b == b'\n') {
+                let line = pending.split_off(newline + 1);
+                pending.truncate(newline);
+                take(&line, &mut replay);
+                if replay.entries.len() == n {
+                    break;
+                }
+            }
+        }
+        // At the start of the file, whatever is left is the first line.
+        if pos == 0 && replay.entries.len() < n {
+            take(&pending, &mut replay);
+        }
+
+        // Collected newest first.
+        replay.entries.reverse();
+        Ok(replay)
+    }
+}
+
+/// Format one log line, including the trailing newline.
+fn encode_log_line(timestamp: u64, message: &Message) -> String {
+    format!(
+        "{}\t{}\t{}\t{}\n",
+        timestamp,
+        message.sender_id,
+        escape_log_field(&message.sender_name),
+        escape_log_field(&message.content)
+    )
+}
+
+/// Parse one line (without its newline). `None` means corrupt.
+fn decode_log_line(line: &[u8]) -> Option<LogEntry> {
+    let line = std::str::from_utf8(line).ok()?;
+    let fields: Vec<&str> = line.split('\t').collect();
+    if let [timestamp, sender_id, sender_name, content] = fields[..] {
+        Some(LogEntry {
+            timestamp: timestamp.parse().ok()?,
+            message: Message::new(
+                sender_id.parse().ok()?,
+                unescape_log_field(sender_name)?,
+                unescape_log_field(content)?,
+            ),
+        })
+    } else {
+        None
+    }
+}
+
+fn escape_log_field(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Reverse `escape_log_field`. An unknown or dangling escape means the line
+/// was not written by us, so it is treated as corrupt.
+fn unescape_log_field(field: &str) -> Option<String> {
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => out.push('\\'),
+            't' => out.push('\t'),
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+// ============================================================================
+// BINARY FRAMING
+// ============================================================================
+// `format_for_broadcast` is a line of text, so a newline inside a message
+// looks exactly like the end of one, and a reader can't tell where a message
+// stops. A length-prefixed frame says up front how many bytes follow:
+//
+//     [u32 length][u32 sender_id][u16 name_len][name bytes][content bytes]
+//      \________/ \____________________________________________________/
+//       4 bytes          `length` bytes, all integers little-endian
+//
+// `length` counts everything after itself, so content length is whatever is
+// left after the name. TCP delivers a byte stream, not messages: one read
+// can hold half a frame or three frames and a bit. `FrameDecoder` buffers
+// whatever it is fed and hands back each frame once all of it has arrived.
+//
+// A peer that is buggy or hostile can send anything. The decoder refuses to
+// buffer a frame above its size limit - it reports the error and skips the
+// declared length instead - and a bad frame (bad UTF-8, a name running past
+// the end) costs only that frame, never the connection or a panic.
+
+/// Bytes after the length prefix that every frame has: sender id + name length.
+const FRAME_FIXED_LEN: usize = 4 + 2;
+
+/// Default `FrameDecoder` limit on `length`: 64 KiB.
+pub const DEFAULT_MAX_FRAME_LEN: usize = 64 * 1024;
+
+/// Encode `message` as one length-prefixed frame.
+///
+/// # Panics
+///
+/// If the name is longer than `u16::MAX` bytes or the frame would be longer
+/// than `u32::MAX` bytes. Registered usernames are far below both.
+pub fn encode_frame(message: &Message) -> Vec<u8> {
+    let name = message.sender_name.as_bytes();
+    let content = message.content.as_bytes();
+    let name_len = u16::try_from(name.len()).expect("sender name longer than u16::MAX bytes");
+    let length = u32::try_from(FRAME_FIXED_LEN + name.len() + content.len())
+        .expect("frame longer than u32::MAX bytes");
+
+    let mut frame = Vec::with_capacity(4 + length as usize);
+    frame.extend_from_slice(&length.to_le_bytes());
+    frame.extend_from_slice(&message.sender_id.to_le_bytes());
+    frame.extend_from_slice(&name_len.to_le_bytes());
+    frame.extend_from_slice(name);
+    frame.extend_from_slice(content);
+    frame
+}
+
+/// Why one frame couldn't be decoded. The decoder carries on with the next.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// `length` is above the decoder's limit; the frame was skipped unread.
+    FrameTooLarge { length: usize, max: usize },
+    /// `length` is too small to hold the sender id and name length.
+    FrameTooShort { length: usize },
+    /// `name_len` runs past the end of the frame.
+    NameOutOfBounds { name_len: usize, length: usize },
+    /// The name or content is not valid UTF-8.
+    InvalidUtf8 { field: &'static str },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::FrameTooLarge { length, max } => {
+                write!(f, "frame of {} bytes exceeds limit of {}", length, max)
+            }
+            DecodeError::FrameTooShort { length } => {
+                write!(f, "frame of {} bytes is too short for a header", length)
+            }
+            DecodeError::NameOutOfBounds { name_len, length } => write!(
+                f,
+                "name of {} bytes does not fit in frame of {}",
+                name_len, length
+            ),
+            DecodeError::InvalidUtf8 { field } => write!(f, "{} is not valid UTF-8", field),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Turns a stream of byte chunks back into messages.
+#[derive(Clone, Debug)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+    max_frame_len: usize,
+    /// Bytes of an oversized frame still to be thrown away.
+    skip: usize,
+}
+
+impl FrameDecoder {
+    /// A decoder with the `DEFAULT_MAX_FRAME_LEN` limit.
+    pub fn new() -> Self {
+        Self::with_max_frame_len(DEFAULT_MAX_FRAME_LEN)
+    }
+
+    /// A decoder that rejects frames whose `length` is above `max_frame_len`.
+    pub fn with_max_frame_len(max_frame_len: usize) -> Self {
+        FrameDecoder {
+            buffer: Vec::new(),
+            max_frame_len,
+            skip: 0,
+        }
+    }
+
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    /// Bytes held back waiting for the rest of a frame.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Add `bytes` and return every frame they complete, in order.
+    ///
+    /// Each item is the message or the error for one frame; an error never
+    /// stops the frames after it from decoding.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Result<Message, DecodeError>> {
+        // Still discarding an oversized frame: its bytes never hit the buffer.
+        let skipped = self.skip.min(bytes.len());
+        self.skip -= skipped;
+        self.buffer.extend_from_slice(&bytes[skipped..]);
+
+        let mut results = Vec::new();
+        let mut pos = 0;
+        while self.skip == 0 {
+            let rest = &self.buffer[pos..];
+            let Some(prefix) = rest.get(..4) else {
+                break;
+            };
+            let length = u32::from_le_bytes(prefix.try_into().unwrap()) as usize;
+
+            if length > self.max_frame_len {
+                results.push(Err(DecodeError::FrameTooLarge {
+                    length,
+                    max: self.max_frame_len,
+                }));
+                // Drop what we have of it and skip the rest as it arrives.
+                let available = (rest.len() - 4).min(length);
+                pos += 4 + available;
+                self.skip = length - available;
+                continue;
+            }
+
+            let Some(frame) = rest.get(4..4 + length) else {
+                break;
+            };
+            results.push(decode_frame_body(frame));
+            pos += 4 + length;
+        }
+        self.buffer.drain(..pos);
+        results
+    }
+}
+
+impl Default for FrameDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decode everything after the length prefix.
+fn decode_frame_body(frame: &[u8]) -> Result<Message, DecodeError> {
+    let length = frame.len();
+    if length < FRAME_FIXED_LEN {
+        return Err(DecodeError::FrameTooShort { length });
+    }
+    let sender_id = u32::from_le_bytes(frame[0..4].try_into().unwrap());
+    let name_len = u16::from_le_bytes(frame[4..6].try_into().unwrap()) as usize;
+    let body = &frame[FRAME_FIXED_LEN..];
+    if name_len > body.len() {
+        return Err(DecodeError::NameOutOfBounds { name_len, length });
+    }
+
+    let (name, content) = body.split_at(name_len);
+    let name = std::str::from_utf8(name).map_err(|_| DecodeError::InvalidUtf8 { field: "name" })?;
+    let content =
+        std::str::from_utf8(content).map_err(|_| DecodeError::InvalidUtf8 { field: "content" })?;
+    Ok(Message::new(
+        sender_id,
+        name.to_string(),
+        content.to_string(),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,7 +2069,7 @@ mod tests {
     #[test]
     fn test_client_registry() {
         let mut registry = ClientRegistry::new();
-        let client = registry.register("alice".to_string());
+        let client = registry.register("alice".to_string()).unwrap();
         assert_eq!(client.id, 1);
         assert_eq!(registry.client_count(), 1);
     }
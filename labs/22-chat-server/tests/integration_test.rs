@@ -4,6 +4,19 @@
 //! These verify the message protocol, client management, and broadcast logic.
 
 use chat_server::solution::{Client, Message, MessageQueue, ClientRegistry, is_command, parse_command};
+use chat_server::solution::{
+    AdminActionResult, AdminApi, AdminError, ClientDetailDto, ClientDto, MessageHistory,
+    QueueDepthDto, RoomDetailDto, RoomDto, ServerMetricsDto,
+};
+use chat_server::solution::{ChatRoom, Decision, PostError, RateLimiter};
+use chat_server::solution::{route_message, RoomCommand, RoomError, RoomManager, LOBBY};
+use chat_server::solution::{validate_username, RegistrationError};
+use chat_server::solution::{LogReplay, MessageLog};
+use chat_server::solution::{EventFilter, EventQueue, ServerEvent};
+use chat_server::solution::{encode_frame, DecodeError, FrameDecoder, DEFAULT_MAX_FRAME_LEN};
+use chat_server::solution::OUTBOX_CAPACITY;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
 
 // ============================================================================
 // CLIENT TESTS
@@ -205,7 +218,7 @@ fn test_registry_creation() {
 #[test]
 fn test_registry_register_single() {
     let mut registry = ClientRegistry::new();
-    let client = registry.register("alice".to_string());
+    let client = registry.register("alice".to_string()).unwrap();
 
     assert_eq!(client.id, 1);
     assert_eq!(registry.client_count(), 1);
@@ -215,9 +228,9 @@ fn test_registry_register_single() {
 fn test_registry_register_multiple() {
     let mut registry = ClientRegistry::new();
 
-    let c1 = registry.register("alice".to_string());
-    let c2 = registry.register("bob".to_string());
-    let c3 = registry.register("charlie".to_string());
+    let c1 = registry.register("alice".to_string()).unwrap();
+    let c2 = registry.register("bob".to_string()).unwrap();
+    let c3 = registry.register("charlie".to_string()).unwrap();
 
     assert_eq!(c1.id, 1);
     assert_eq!(c2.id, 2);
@@ -228,7 +241,7 @@ fn test_registry_register_multiple() {
 #[test]
 fn test_registry_find_client() {
     let mut registry = ClientRegistry::new();
-    let client = registry.register("alice".to_string());
+    let client = registry.register("alice".to_string()).unwrap();
 
     let found = registry.find_client(1);
     assert!(found.is_some());
@@ -245,8 +258,8 @@ fn test_registry_find_nonexistent() {
 fn test_registry_active_clients() {
     let mut registry = ClientRegistry::new();
 
-    registry.register("alice".to_string());
-    registry.register("bob".to_string());
+    registry.register("alice".to_string()).unwrap();
+    registry.register("bob".to_string()).unwrap();
 
     let active = registry.active_clients();
     assert_eq!(active.len(), 2);
@@ -255,7 +268,7 @@ fn test_registry_active_clients() {
 #[test]
 fn test_registry_disconnect() {
     let mut registry = ClientRegistry::new();
-    registry.register("alice".to_string());
+    registry.register("alice".to_string()).unwrap();
 
     assert_eq!(registry.active_count(), 1);
 
@@ -268,9 +281,9 @@ fn test_registry_disconnect() {
 fn test_registry_disconnect_multiple() {
     let mut registry = ClientRegistry::new();
 
-    registry.register("alice".to_string());
-    registry.register("bob".to_string());
-    registry.register("charlie".to_string());
+    registry.register("alice".to_string()).unwrap();
+    registry.register("bob".to_string()).unwrap();
+    registry.register("charlie".to_string()).unwrap();
 
     registry.disconnect(1);
     registry.disconnect(3);
@@ -341,11 +354,11 @@ fn test_server_workflow() {
     let mut registry = ClientRegistry::new();
 
     // Client 1 joins
-    let client1 = registry.register("alice".to_string());
+    let client1 = registry.register("alice".to_string()).unwrap();
     assert_eq!(registry.active_count(), 1);
 
     // Client 2 joins
-    let _client2 = registry.register("bob".to_string());
+    let _client2 = registry.register("bob".to_string()).unwrap();
     assert_eq!(registry.active_count(), 2);
 
     // Client 1 sends message
@@ -367,8 +380,8 @@ fn test_broadcast_to_multiple_clients() {
     let mut queue1 = MessageQueue::new(10);
     let mut queue2 = MessageQueue::new(10);
 
-    let client1 = registry.register("alice".to_string());
-    let _client2 = registry.register("bob".to_string());
+    let client1 = registry.register("alice".to_string()).unwrap();
+    let _client2 = registry.register("bob".to_string()).unwrap();
 
     // Client 1 broadcasts message
     let msg = Message::new(client1.id, client1.username.clone(), "Hello!".to_string());
@@ -426,7 +439,7 @@ fn test_multiple_client_sessions() {
     // Simulate multiple clients joining and leaving
     for i in 0..5 {
         let username = format!("user{}", i);
-        let client = registry.register(username);
+        let client = registry.register(username).unwrap();
         assert_eq!(client.id as usize, i + 1);
     }
 
@@ -439,3 +452,1472 @@ fn test_multiple_client_sessions() {
     assert_eq!(registry.active_count(), 3);
     assert_eq!(registry.client_count(), 5);
 }
+
+// ============================================================================
+// ADMIN API
+// ============================================================================
+
+/// alice (id 1) sent 3 messages, bob (id 2) sent 1 and left, carol (id 3)
+/// is connected but quiet. Seqs: alice 1, 2, 4; bob 3.
+fn populated_server() -> (ClientRegistry, MessageHistory) {
+    let mut registry = ClientRegistry::new();
+    let alice = registry.register("alice".to_string()).unwrap();
+    let bob = registry.register("bob".to_string()).unwrap();
+    registry.register("carol".to_string()).unwrap();
+
+    let mut history = MessageHistory::new();
+    for (sender, text) in [
+        (&alice, "hi"),
+        (&alice, "anyone?"),
+        (&bob, "hey"),
+        (&alice, "bye"),
+    ] {
+        history.record(Message::new(
+            sender.id,
+            sender.username.clone(),
+            text.to_string(),
+        ));
+    }
+    registry.disconnect(bob.id);
+
+    (registry, history)
+}
+
+#[test]
+fn test_admin_list_clients_maps_fields() {
+    let (mut registry, mut history) = populated_server();
+    let api = AdminApi::new(&mut registry, &mut history);
+
+    let clients = api.list_clients();
+    assert_eq!(
+        clients,
+        vec![
+            ClientDto {
+                id: 1,
+                username: "alice".into(),
+                connected: true,
+                message_count: 3
+            },
+            ClientDto {
+                id: 2,
+                username: "bob".into(),
+                connected: false,
+                message_count: 1
+            },
+            ClientDto {
+                id: 3,
+                username: "carol".into(),
+                connected: true,
+                message_count: 0
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_admin_client_detail() {
+    let (mut registry, mut history) = populated_server();
+    let api = AdminApi::new(&mut registry, &mut history);
+
+    let detail = api.client_detail(1).unwrap();
+    assert_eq!(detail.display_name, "[1] alice");
+    assert_eq!(detail.message_count, 3);
+    assert_eq!(detail.recent_message_seqs, vec![1, 2, 4]);
+
+    assert_eq!(
+        api.client_detail(99),
+        Err(AdminError::ClientNotFound { id: 99 })
+    );
+}
+
+#[test]
+fn test_admin_client_detail_limits_recent_seqs() {
+    let mut registry = ClientRegistry::new();
+    let alice = registry.register("alice".to_string()).unwrap();
+    let mut history = MessageHistory::new();
+    for i in 0..15 {
+        history.record(Message::new(alice.id, "alice".into(), format!("m{}", i)));
+    }
+    let api = AdminApi::new(&mut registry, &mut history);
+
+    let detail = api.client_detail(alice.id).unwrap();
+    assert_eq!(detail.message_count, 15);
+    assert_eq!(detail.recent_message_seqs, (6..=15).collect::<Vec<u64>>());
+}
+
+#[test]
+fn test_admin_server_metrics() {
+    let (mut registry, mut history) = populated_server();
+    let api = AdminApi::new(&mut registry, &mut history);
+
+    assert_eq!(
+        api.server_metrics(),
+        ServerMetricsDto {
+            registered_clients: 3,
+            active_clients: 2,
+            total_messages: 4,
+            stored_messages: 4,
+            uptime_ticks: 0,
+            queue_depths: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn test_admin_metrics_from_chat_room_include_uptime_and_queues() {
+    let mut room = ChatRoom::new(RateLimiter::new(10, 1.0));
+    let alice = room.join("alice".to_string()).unwrap();
+    let bob = room.join("bob".to_string()).unwrap();
+    let carol = room.join("carol".to_string()).unwrap();
+    for _ in 0..3 {
+        room.tick();
+    }
+    room.drain_events(alice.id);
+    room.post(alice.id, "hi", Instant::now()).unwrap();
+    room.leave(carol.id);
+
+    let metrics = room.admin().server_metrics();
+    assert_eq!(metrics.uptime_ticks, 3);
+    // bob: carol joined, hi, carol left; alice: only carol left
+    assert_eq!(
+        metrics.queue_depths,
+        vec![
+            QueueDepthDto {
+                client_id: alice.id,
+                pending: 1
+            },
+            QueueDepthDto {
+                client_id: bob.id,
+                pending: 3
+            },
+        ]
+    );
+    assert_eq!(room.uptime_ticks(), 3);
+}
+
+/// alice and bob in #rust, carol in the lobby; each has posted once and no
+/// events are waiting.
+fn admin_room() -> (ChatRoom, [u32; 3]) {
+    let mut room = ChatRoom::new(RateLimiter::new(10, 1.0));
+    let ids = ["alice", "bob", "carol"].map(|name| room.join(name.to_string()).unwrap().id);
+    let now = Instant::now();
+    for id in &ids[..2] {
+        room.run_room_command(*id, &RoomCommand::Join("rust".into()))
+            .unwrap();
+    }
+    for id in ids {
+        room.post(id, "hi", now).unwrap();
+    }
+    for id in ids {
+        room.drain_events(id);
+    }
+    (room, ids)
+}
+
+#[test]
+fn test_admin_lists_rooms_and_room_detail() {
+    let (mut room, [alice, bob, _]) = admin_room();
+    let api = room.admin();
+
+    assert_eq!(
+        api.list_rooms(),
+        vec![
+            RoomDto {
+                name: LOBBY.into(),
+                member_count: 1
+            },
+            RoomDto {
+                name: "rust".into(),
+                member_count: 2
+            },
+        ]
+    );
+
+    let detail = api.room_detail("rust").unwrap();
+    assert_eq!(detail.name, "rust");
+    let members: Vec<u32> = detail.members.iter().map(|m| m.id).collect();
+    assert_eq!(members, vec![alice, bob]);
+    assert_eq!(detail.members[0].message_count, 1);
+    assert_eq!(detail.recent_message_seqs, vec![1, 2]);
+    assert_eq!(api.room_detail(LOBBY).unwrap().recent_message_seqs, vec![3]);
+
+    assert_eq!(
+        api.room_detail("nope"),
+        Err(AdminError::RoomNotFound {
+            room: "nope".into()
+        })
+    );
+}
+
+#[test]
+fn test_admin_room_detail_skips_purged_messages() {
+    let (mut room, _) = admin_room();
+    let mut api = room.admin();
+    api.purge_history_before(2);
+    assert_eq!(
+        api.room_detail("rust").unwrap().recent_message_seqs,
+        vec![2]
+    );
+}
+
+#[test]
+fn test_admin_without_chat_room_has_no_rooms() {
+    let (mut registry, mut history) = populated_server();
+    let api = AdminApi::new(&mut registry, &mut history);
+    assert!(api.list_rooms().is_empty());
+    assert!(matches!(
+        api.room_detail(LOBBY),
+        Err(AdminError::RoomNotFound { .. })
+    ));
+}
+
+#[test]
+fn test_admin_kick_through_chat_room_disconnects_fully() {
+    let (mut room, [alice, bob, carol]) = admin_room();
+
+    let result = room.admin().kick_client(bob).unwrap();
+    assert_eq!(result.detail, "kicked [2] bob");
+
+    assert!(!room.registry().find_client(bob).unwrap().is_active());
+    assert_eq!(room.rooms().broadcast_targets("rust"), vec![alice]);
+    assert_eq!(room.next_event(bob), None, "bob's outbox is gone");
+    for id in [alice, carol] {
+        assert_eq!(
+            room.drain_events(id),
+            vec![ServerEvent::UserLeft {
+                id: bob,
+                username: "bob".into()
+            }]
+        );
+    }
+    // bob no longer receives #rust messages.
+    let seq = room.post(alice, "still here?", Instant::now()).unwrap();
+    assert!(room.recipients(seq).is_empty());
+}
+
+#[test]
+fn test_admin_announcement_reaches_every_connected_client() {
+    let (mut room, [alice, bob, carol]) = admin_room();
+    room.leave(carol);
+    room.drain_events(alice);
+    room.drain_events(bob);
+
+    let result = room.admin().broadcast_announcement("restart soon").unwrap();
+    assert_eq!(result.affected, 2);
+    assert_eq!(result.seq, Some(4));
+
+    for id in [alice, bob] {
+        let events = room.drain_events(id);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].format_for_broadcast(), "server: restart soon");
+    }
+    assert_eq!(room.next_event(carol), None);
+    assert_eq!(room.history().total_recorded(), 4);
+}
+
+#[test]
+fn test_admin_kick_client() {
+    let (mut registry, mut history) = populated_server();
+    let mut api = AdminApi::new(&mut registry, &mut history);
+
+    let result = api.kick_client(3).unwrap();
+    assert_eq!(result.action, "kick_client");
+    assert_eq!(result.affected, 1);
+    assert_eq!(result.detail, "kicked [3] carol");
+    assert_eq!(api.server_metrics().active_clients, 1);
+
+    assert_eq!(
+        api.kick_client(3),
+        Err(AdminError::ClientNotConnected { id: 3 })
+    );
+    assert_eq!(
+        api.kick_client(42),
+        Err(AdminError::ClientNotFound { id: 42 })
+    );
+    assert!(!registry.find_client(3).unwrap().is_active());
+}
+
+#[test]
+fn test_admin_broadcast_announcement() {
+    let (mut registry, mut history) = populated_server();
+    let mut api = AdminApi::new(&mut registry, &mut history);
+
+    let result = api.broadcast_announcement("  maintenance at 5pm ").unwrap();
+    assert_eq!(result.action, "broadcast_announcement");
+    assert_eq!(result.affected, 2, "reaches the connected clients only");
+    assert_eq!(result.seq, Some(5));
+
+    assert_eq!(
+        api.broadcast_announcement("   "),
+        Err(AdminError::EmptyAnnouncement)
+    );
+    assert_eq!(api.server_metrics().total_messages, 5);
+
+    let (seq, message) = history.iter().last().unwrap();
+    assert_eq!(seq, 5);
+    assert_eq!(message.format_for_broadcast(), "server: maintenance at 5pm");
+}
+
+#[test]
+fn test_admin_purge_history_before() {
+    let (mut registry, mut history) = populated_server();
+    let mut api = AdminApi::new(&mut registry, &mut history);
+
+    let result = api.purge_history_before(3);
+    assert_eq!(result.action, "purge_history_before");
+    assert_eq!(result.affected, 2);
+
+    let metrics = api.server_metrics();
+    assert_eq!(metrics.stored_messages, 2);
+    assert_eq!(
+        metrics.total_messages, 4,
+        "purging keeps the lifetime total"
+    );
+    assert_eq!(api.client_detail(1).unwrap().recent_message_seqs, vec![4]);
+
+    assert_eq!(api.purge_history_before(3).affected, 0);
+}
+
+#[test]
+fn test_admin_error_codes() {
+    let errors = [
+        (
+            AdminError::ClientNotFound { id: 7 },
+            json!({"code": "client_not_found", "id": 7}),
+        ),
+        (
+            AdminError::ClientNotConnected { id: 7 },
+            json!({"code": "client_not_connected", "id": 7}),
+        ),
+        (
+            AdminError::RoomNotFound {
+                room: "rust".into(),
+            },
+            json!({"code": "room_not_found", "room": "rust"}),
+        ),
+        (
+            AdminError::EmptyAnnouncement,
+            json!({"code": "empty_announcement"}),
+        ),
+    ];
+    for (error, expected) in errors {
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value, expected);
+        assert_eq!(value["code"], error.code());
+        assert_eq!(serde_json::from_value::<AdminError>(value).unwrap(), error);
+    }
+}
+
+fn round_trip<T>(dto: &T, fields: &[&str]) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let value = serde_json::to_value(dto).unwrap();
+    let mut keys: Vec<&str> = value
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    keys.sort_unstable();
+    let mut expected = fields.to_vec();
+    expected.sort_unstable();
+    assert_eq!(keys, expected);
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn test_admin_dtos_serde_round_trip() {
+    let (mut registry, mut history) = populated_server();
+    let mut api = AdminApi::new(&mut registry, &mut history);
+
+    let client = api.list_clients().remove(0);
+    assert_eq!(
+        round_trip(&client, &["id", "username", "connected", "message_count"]),
+        client
+    );
+
+    let detail: ClientDetailDto = api.client_detail(1).unwrap();
+    let fields = [
+        "id",
+        "username",
+        "display_name",
+        "connected",
+        "message_count",
+        "recent_message_seqs",
+    ];
+    assert_eq!(round_trip(&detail, &fields), detail);
+
+    let metrics = api.server_metrics();
+    let fields = [
+        "registered_clients",
+        "active_clients",
+        "total_messages",
+        "stored_messages",
+        "uptime_ticks",
+        "queue_depths",
+    ];
+    assert_eq!(round_trip(&metrics, &fields), metrics);
+
+    let result: AdminActionResult = api.broadcast_announcement("hello").unwrap();
+    assert_eq!(
+        round_trip(&result, &["action", "affected", "seq", "detail"]),
+        result
+    );
+    let value: Value = serde_json::to_value(api.purge_history_before(1)).unwrap();
+    assert_eq!(value["seq"], Value::Null);
+}
+
+#[test]
+fn test_admin_room_dtos_serde_round_trip() {
+    let (mut room, _) = admin_room();
+    let api = room.admin();
+
+    let summary = api.list_rooms().remove(0);
+    assert_eq!(round_trip(&summary, &["name", "member_count"]), summary);
+
+    let detail: RoomDetailDto = api.room_detail("rust").unwrap();
+    assert_eq!(
+        round_trip(&detail, &["name", "members", "recent_message_seqs"]),
+        detail
+    );
+}
+
+// ============================================================================
+// RATE LIMITING TESTS
+// ============================================================================
+
+#[test]
+fn test_rate_limiter_burst_then_limited() {
+    let mut limiter = RateLimiter::new(3, 1.0);
+    let now = Instant::now();
+
+    for _ in 0..3 {
+        assert_eq!(limiter.check(1, now), Decision::Allowed);
+    }
+    assert_eq!(
+        limiter.check(1, now),
+        Decision::Limited {
+            retry_after: Duration::from_secs(1)
+        }
+    );
+}
+
+#[test]
+fn test_rate_limiter_refills_over_time() {
+    let mut limiter = RateLimiter::new(2, 2.0);
+    let start = Instant::now();
+
+    assert_eq!(limiter.check(1, start), Decision::Allowed);
+    assert_eq!(limiter.check(1, start), Decision::Allowed);
+    assert!(matches!(limiter.check(1, start), Decision::Limited { .. }));
+
+    // Half a second at 2 tokens/sec buys exactly one more message
+    let later = start + Duration::from_millis(500);
+    assert_eq!(limiter.check(1, later), Decision::Allowed);
+    assert!(matches!(limiter.check(1, later), Decision::Limited { .. }));
+
+    // A long pause refills only up to capacity
+    let much_later = later + Duration::from_secs(60);
+    assert_eq!(limiter.check(1, much_later), Decision::Allowed);
+    assert_eq!(limiter.check(1, much_later), Decision::Allowed);
+    assert!(matches!(
+        limiter.check(1, much_later),
+        Decision::Limited { .. }
+    ));
+}
+
+#[test]
+fn test_rate_limiter_independent_buckets() {
+    let mut limiter = RateLimiter::new(1, 1.0);
+    let now = Instant::now();
+
+    assert_eq!(limiter.check(1, now), Decision::Allowed);
+    assert!(matches!(limiter.check(1, now), Decision::Limited { .. }));
+    assert_eq!(limiter.check(2, now), Decision::Allowed);
+}
+
+#[test]
+fn test_rate_limiter_forget_resets_bucket() {
+    let mut limiter = RateLimiter::new(1, 0.0);
+    let now = Instant::now();
+
+    assert_eq!(limiter.check(1, now), Decision::Allowed);
+    assert_eq!(
+        limiter.check(1, now + Duration::from_secs(3600)),
+        Decision::Limited {
+            retry_after: Duration::MAX
+        }
+    );
+    limiter.forget(1);
+    assert_eq!(limiter.check(1, now), Decision::Allowed);
+}
+
+#[test]
+fn test_rate_limiter_tiny_rate_saturates_retry_after() {
+    let mut limiter = RateLimiter::new(1, 1e-300);
+    let now = Instant::now();
+
+    assert_eq!(limiter.check(1, now), Decision::Allowed);
+    assert_eq!(
+        limiter.check(1, now),
+        Decision::Limited {
+            retry_after: Duration::MAX
+        }
+    );
+}
+
+#[test]
+#[should_panic(expected = "capacity")]
+fn test_rate_limiter_rejects_zero_capacity() {
+    RateLimiter::new(0, 1.0);
+}
+
+#[test]
+fn test_chat_room_post_rejects_limited_client_only() {
+    let mut room = ChatRoom::new(RateLimiter::new(2, 1.0));
+    let alice = room.join("alice".to_string()).unwrap();
+    let bob = room.join("bob".to_string()).unwrap();
+    let now = Instant::now();
+
+    assert_eq!(room.post(alice.id, "one", now), Ok(1));
+    assert_eq!(room.post(alice.id, "two", now), Ok(2));
+    let err = room.post(alice.id, "three", now).unwrap_err();
+    assert_eq!(
+        err,
+        PostError::RateLimited {
+            retry_after: Duration::from_secs(1)
+        }
+    );
+    assert_eq!(err.to_string(), "slow down: try again in 1.0s");
+
+    // Bob is unaffected and the rejected message was never recorded
+    assert_eq!(room.post(bob.id, "hi", now), Ok(3));
+    assert_eq!(room.history().len(), 3);
+
+    assert_eq!(
+        room.post(alice.id, "three", now + Duration::from_secs(1)),
+        Ok(4)
+    );
+}
+
+#[test]
+fn test_chat_room_post_validation() {
+    let mut room = ChatRoom::new(RateLimiter::new(1, 1.0));
+    let alice = room.join("alice".to_string()).unwrap();
+    let now = Instant::now();
+
+    // Empty input is rejected without spending the only token
+    assert_eq!(
+        room.post(alice.id, "   ", now),
+        Err(PostError::EmptyMessage)
+    );
+    assert_eq!(room.post(alice.id, "hello", now), Ok(1));
+
+    assert_eq!(
+        room.post(99, "hello", now),
+        Err(PostError::UnknownClient { id: 99 })
+    );
+    room.leave(alice.id);
+    assert_eq!(
+        room.post(alice.id, "hello", now),
+        Err(PostError::NotConnected { id: alice.id })
+    );
+}
+
+// ============================================================================
+// CHAT ROOM TESTS
+// ============================================================================
+
+#[test]
+fn test_room_join_leave_transitions() {
+    let mut rooms = RoomManager::new();
+    rooms.connect(1);
+    assert_eq!(rooms.broadcast_targets(LOBBY), vec![1]);
+
+    assert_eq!(rooms.join(1, "rust"), "rust");
+    assert_eq!(rooms.join(1, "go"), "go");
+    assert!(rooms.broadcast_targets(LOBBY).is_empty());
+
+    // Leaving the current room falls back to the previously joined one
+    assert_eq!(rooms.leave(1, "go"), Ok("rust".to_string()));
+    // Leaving the last room drops back to the lobby
+    assert_eq!(rooms.leave(1, "rust"), Ok(LOBBY.to_string()));
+    assert_eq!(rooms.broadcast_targets(LOBBY), vec![1]);
+
+    assert_eq!(
+        rooms.leave(1, "rust"),
+        Err(RoomError::NotInRoom {
+            room: "rust".to_string()
+        })
+    );
+    assert_eq!(rooms.leave(1, LOBBY), Err(RoomError::CannotLeaveLobby));
+}
+
+#[test]
+fn test_room_auto_creation_and_cleanup() {
+    let mut rooms = RoomManager::new();
+    rooms.connect(1);
+    rooms.connect(2);
+    assert!(!rooms.contains("rust"));
+
+    rooms.join(1, "rust");
+    rooms.join(2, "rust");
+    assert!(rooms.contains("rust"));
+    assert_eq!(
+        rooms.rooms(),
+        vec![("lobby".to_string(), 0), ("rust".to_string(), 2)]
+    );
+
+    rooms.leave(1, "rust").unwrap();
+    assert!(rooms.contains("rust"));
+    rooms.disconnect(2);
+    assert!(!rooms.contains("rust"));
+
+    // The lobby is never garbage-collected
+    assert_eq!(rooms.rooms(), vec![("lobby".to_string(), 1)]);
+}
+
+#[test]
+fn test_room_join_lobby_leaves_other_rooms() {
+    let mut rooms = RoomManager::new();
+    rooms.connect(1);
+    rooms.join(1, "rust");
+    rooms.join(1, "go");
+
+    assert_eq!(rooms.join(1, LOBBY), LOBBY);
+    assert_eq!(rooms.rooms(), vec![("lobby".to_string(), 1)]);
+}
+
+#[test]
+fn test_room_broadcast_targets_per_room() {
+    let mut room = ChatRoom::new(RateLimiter::new(10, 1.0));
+    let alice = room.join("alice".to_string()).unwrap();
+    let bob = room.join("bob".to_string()).unwrap();
+    let carol = room.join("carol".to_string()).unwrap();
+    let now = Instant::now();
+
+    room.run_room_command(alice.id, &RoomCommand::Join("rust".to_string()))
+        .unwrap();
+    room.run_room_command(bob.id, &RoomCommand::Join("rust".to_string()))
+        .unwrap();
+    assert_eq!(
+        room.rooms().broadcast_targets("rust"),
+        vec![alice.id, bob.id]
+    );
+    assert_eq!(room.rooms().broadcast_targets(LOBBY), vec![carol.id]);
+    assert_eq!(
+        room.registry().current_room(alice.id),
+        Some("rust".to_string())
+    );
+
+    let seq = room.post(alice.id, "hi rustaceans", now).unwrap();
+    assert_eq!(room.recipients(seq), vec![bob.id]);
+
+    let seq = room.post(carol.id, "anyone here?", now).unwrap();
+    assert!(room.recipients(seq).is_empty());
+
+    // Disconnected members no longer receive messages
+    room.leave(bob.id);
+    let seq = room.post(alice.id, "bob?", now).unwrap();
+    assert!(room.recipients(seq).is_empty());
+}
+
+#[test]
+fn test_route_message_skips_disconnected_members() {
+    let mut registry = ClientRegistry::new();
+    let mut rooms = RoomManager::new();
+    let alice = registry.register("alice".to_string()).unwrap();
+    let bob = registry.register("bob".to_string()).unwrap();
+    rooms.connect(alice.id);
+    rooms.connect(bob.id);
+
+    let message = Message::new(alice.id, alice.username.clone(), "hi".to_string());
+    assert_eq!(route_message(&registry, &rooms, &message), vec![bob.id]);
+
+    // Kicked via the registry only; routing still filters bob out
+    registry.disconnect(bob.id);
+    assert!(route_message(&registry, &rooms, &message).is_empty());
+}
+
+#[test]
+fn test_room_command_parsing() {
+    assert_eq!(parse_command("/join rust"), Some("join rust"));
+    assert_eq!(
+        RoomCommand::parse("/join rust"),
+        Some(RoomCommand::Join("rust".to_string()))
+    );
+    assert_eq!(
+        RoomCommand::parse("  /leave #rust "),
+        Some(RoomCommand::Leave("rust".to_string()))
+    );
+    assert_eq!(RoomCommand::parse("/rooms"), Some(RoomCommand::Rooms));
+
+    assert_eq!(RoomCommand::parse("/join"), None);
+    assert_eq!(RoomCommand::parse("/join #"), None);
+    assert_eq!(RoomCommand::parse("/join a b"), None);
+    assert_eq!(RoomCommand::parse("/rooms extra"), None);
+    assert_eq!(RoomCommand::parse("/quit"), None);
+    assert_eq!(RoomCommand::parse("join rust"), None);
+}
+
+#[test]
+fn test_room_command_replies() {
+    let mut room = ChatRoom::new(RateLimiter::new(10, 1.0));
+    let alice = room.join("alice".to_string()).unwrap();
+
+    let join = RoomCommand::parse("/join rust").unwrap();
+    assert_eq!(
+        room.run_room_command(alice.id, &join),
+        Ok("now talking in #rust".to_string())
+    );
+    assert_eq!(
+        room.run_room_command(alice.id, &RoomCommand::Rooms),
+        Ok("#lobby (0), #rust (1)".to_string())
+    );
+
+    let leave = RoomCommand::parse("/leave rust").unwrap();
+    assert_eq!(
+        room.run_room_command(alice.id, &leave),
+        Ok("left #rust, now talking in #lobby".to_string())
+    );
+    assert_eq!(
+        room.registry().current_room(alice.id),
+        Some(LOBBY.to_string())
+    );
+
+    assert_eq!(
+        room.run_room_command(99, &RoomCommand::Rooms),
+        Err(RoomError::UnknownClient { id: 99 })
+    );
+}
+
+#[test]
+fn test_new_client_starts_in_lobby() {
+    let client = Client::new(1, "alice".to_string());
+    assert_eq!(client.room, LOBBY);
+}
+
+// ============================================================================
+// SERVER EVENT TESTS
+// ============================================================================
+
+fn presence_room() -> (ChatRoom, Client, Client) {
+    let mut room = ChatRoom::new(RateLimiter::new(10, 1.0));
+    let alice = room.join("alice".to_string()).unwrap();
+    let bob = room.join("bob".to_string()).unwrap();
+    (room, alice, bob)
+}
+
+#[test]
+fn test_server_event_formatting() {
+    let joined = ServerEvent::UserJoined {
+        id: 1,
+        username: "alice".to_string(),
+    };
+    let left = ServerEvent::UserLeft {
+        id: 1,
+        username: "alice".to_string(),
+    };
+    let renamed = ServerEvent::UserRenamed {
+        id: 1,
+        old_name: "alice".to_string(),
+        new_name: "ally".to_string(),
+    };
+    let chat = ServerEvent::Chat(Message::new(1, "alice".to_string(), "hi".to_string()));
+
+    assert_eq!(joined.format_for_broadcast(), "* alice joined");
+    assert_eq!(left.format_for_broadcast(), "* alice left");
+    assert_eq!(
+        renamed.format_for_broadcast(),
+        "* alice is now known as ally"
+    );
+    assert_eq!(chat.format_for_broadcast(), "alice: hi");
+
+    assert!(joined.is_presence() && left.is_presence() && renamed.is_presence());
+    assert!(!chat.is_presence());
+    assert_eq!(chat.client_id(), 1);
+}
+
+#[test]
+fn test_join_event_reaches_others_but_not_self() {
+    let (mut room, alice, bob) = presence_room();
+
+    assert_eq!(
+        room.drain_events(alice.id),
+        vec![ServerEvent::UserJoined {
+            id: bob.id,
+            username: "bob".to_string()
+        }]
+    );
+    // Bob joined after alice, and his own join is not echoed back
+    assert!(room.drain_events(bob.id).is_empty());
+}
+
+#[test]
+fn test_leave_event_sent_exactly_once() {
+    let (mut room, alice, bob) = presence_room();
+    room.drain_events(alice.id);
+
+    room.leave(bob.id);
+    room.leave(bob.id);
+
+    assert_eq!(
+        room.drain_events(alice.id),
+        vec![ServerEvent::UserLeft {
+            id: bob.id,
+            username: "bob".to_string()
+        }]
+    );
+    assert_eq!(room.next_event(bob.id), None);
+}
+
+#[test]
+fn test_leave_after_admin_kick_still_announced_once() {
+    let (mut room, alice, bob) = presence_room();
+    room.drain_events(alice.id);
+
+    room.admin().kick_client(bob.id).unwrap();
+    room.leave(bob.id);
+    room.leave(bob.id);
+
+    let events = room.drain_events(alice.id);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].format_for_broadcast(), "* bob left");
+}
+
+#[test]
+fn test_rename_event() {
+    let (mut room, alice, bob) = presence_room();
+    room.drain_events(alice.id);
+
+    room.rename(bob.id, "robert").unwrap();
+    assert_eq!(
+        room.drain_events(alice.id),
+        vec![ServerEvent::UserRenamed {
+            id: bob.id,
+            old_name: "bob".to_string(),
+            new_name: "robert".to_string()
+        }]
+    );
+    assert!(room.drain_events(bob.id).is_empty());
+
+    // Same name: nothing to announce. Taken name: error, no event.
+    room.rename(bob.id, "robert").unwrap();
+    assert_eq!(
+        room.rename(bob.id, "ALICE"),
+        Err(RegistrationError::NameTaken)
+    );
+    assert!(room.drain_events(alice.id).is_empty());
+
+    room.leave(bob.id);
+    assert_eq!(
+        room.rename(bob.id, "bobby"),
+        Err(RegistrationError::UnknownClient(bob.id))
+    );
+}
+
+#[test]
+fn test_chat_goes_through_outboxes() {
+    let (mut room, alice, bob) = presence_room();
+    room.drain_events(alice.id);
+
+    let seq = room.post(alice.id, "hello", Instant::now()).unwrap();
+    assert_eq!(room.recipients(seq), vec![bob.id]);
+    assert_eq!(
+        room.next_event(bob.id),
+        Some(ServerEvent::Chat(Message::new(
+            alice.id,
+            "alice".to_string(),
+            "hello".to_string()
+        )))
+    );
+    assert_eq!(room.next_event(bob.id), None);
+    assert_eq!(room.next_event(alice.id), None);
+}
+
+#[test]
+fn test_event_filter_suppresses_presence_not_chat() {
+    let (mut room, alice, bob) = presence_room();
+    room.drain_events(alice.id);
+    room.set_event_filter(alice.id, EventFilter::ChatOnly);
+
+    let carol = room.join("carol".to_string()).unwrap();
+    room.rename(carol.id, "caroline").unwrap();
+    room.post(bob.id, "hi all", Instant::now()).unwrap();
+    room.leave(carol.id);
+
+    let lines: Vec<String> = room
+        .drain_events(alice.id)
+        .iter()
+        .map(ServerEvent::format_for_broadcast)
+        .collect();
+    assert_eq!(lines, vec!["bob: hi all".to_string()]);
+
+    // Bob kept the default filter and saw everything
+    let lines: Vec<String> = room
+        .drain_events(bob.id)
+        .iter()
+        .map(ServerEvent::format_for_broadcast)
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            "* carol joined".to_string(),
+            "* carol is now known as caroline".to_string(),
+            "* caroline left".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_event_queue_reuses_message_queue() {
+    let mut queue = EventQueue::bounded(1);
+    queue.enqueue(ServerEvent::UserJoined {
+        id: 1,
+        username: "alice".to_string(),
+    });
+    queue.enqueue(ServerEvent::UserLeft {
+        id: 1,
+        username: "alice".to_string(),
+    });
+
+    assert_eq!(queue.size(), 1);
+    assert!(matches!(
+        queue.dequeue(),
+        Some(ServerEvent::UserLeft { .. })
+    ));
+    assert!(EventFilter::default().allows(&ServerEvent::UserLeft {
+        id: 1,
+        username: "alice".to_string()
+    }));
+}
+
+// ============================================================================
+// USERNAME VALIDATION TESTS
+// ============================================================================
+
+fn invalid(result: Result<Client, RegistrationError>) -> bool {
+    matches!(result, Err(RegistrationError::InvalidName(_)))
+}
+
+#[test]
+fn test_register_rejects_invalid_names() {
+    let mut registry = ClientRegistry::new();
+
+    assert!(invalid(registry.register(String::new())));
+    assert!(invalid(registry.register("   ".to_string())));
+    assert!(invalid(registry.register("a".repeat(33))));
+    assert!(invalid(registry.register("bad\u{7}name".to_string())));
+    assert!(invalid(registry.register("new\nline".to_string())));
+    assert!(invalid(registry.register("/quit".to_string())));
+    assert_eq!(registry.client_count(), 0);
+
+    // Exactly 32 chars is fine, and length counts chars rather than bytes
+    assert!(registry.register("a".repeat(32)).is_ok());
+    assert!(registry.register("é".repeat(32)).is_ok());
+}
+
+#[test]
+fn test_register_trims_name() {
+    let mut registry = ClientRegistry::new();
+    let client = registry.register("  alice  ".to_string()).unwrap();
+    assert_eq!(client.username, "alice");
+    assert_eq!(validate_username(" bob ").unwrap(), "bob");
+}
+
+#[test]
+fn test_register_name_taken_case_insensitive() {
+    let mut registry = ClientRegistry::new();
+    registry.register("Alice".to_string()).unwrap();
+
+    assert_eq!(
+        registry.register("alice".to_string()).unwrap_err(),
+        RegistrationError::NameTaken
+    );
+    assert_eq!(
+        registry.register(" ALICE ".to_string()).unwrap_err(),
+        RegistrationError::NameTaken
+    );
+    assert_eq!(registry.client_count(), 1);
+}
+
+#[test]
+fn test_register_reuses_name_after_disconnect() {
+    let mut registry = ClientRegistry::new();
+    let old = registry.register("alice".to_string()).unwrap();
+    registry.disconnect(old.id);
+
+    let new = registry.register("alice".to_string()).unwrap();
+    assert_ne!(old.id, new.id);
+    assert_eq!(registry.find_by_name("ALICE").unwrap().id, new.id);
+}
+
+#[test]
+fn test_find_by_name() {
+    let mut registry = ClientRegistry::new();
+    let bob = registry.register("Bob".to_string()).unwrap();
+
+    assert_eq!(registry.find_by_name("bob").unwrap().id, bob.id);
+    assert!(registry.find_by_name("carol").is_none());
+
+    registry.disconnect(bob.id);
+    assert!(registry.find_by_name("bob").is_none());
+}
+
+#[test]
+fn test_rename() {
+    let mut registry = ClientRegistry::new();
+    let alice = registry.register("alice".to_string()).unwrap();
+    let bob = registry.register("bob".to_string()).unwrap();
+
+    assert_eq!(
+        registry.rename(bob.id, "ALICE"),
+        Err(RegistrationError::NameTaken)
+    );
+    assert!(matches!(
+        registry.rename(bob.id, ""),
+        Err(RegistrationError::InvalidName(_))
+    ));
+    assert_eq!(
+        registry.rename(99, "zed"),
+        Err(RegistrationError::UnknownClient(99))
+    );
+    assert_eq!(registry.find_client(bob.id).unwrap().username, "bob");
+
+    // Changing the case of your own name is fine
+    assert_eq!(registry.rename(alice.id, "Alice"), Ok(()));
+    assert_eq!(registry.rename(bob.id, " robert "), Ok(()));
+    assert_eq!(registry.find_by_name("robert").unwrap().id, bob.id);
+    assert!(registry.find_by_name("bob").is_none());
+}
+
+#[test]
+fn test_registration_error_display() {
+    let err = validate_username("/admin").unwrap_err();
+    assert_eq!(err.to_string(), "invalid username: name starts with '/'");
+    assert_eq!(
+        RegistrationError::NameTaken.to_string(),
+        "username is already taken"
+    );
+}
+
+// ============================================================================
+// MESSAGE LOG TESTS
+// ============================================================================
+
+fn msg(id: u32, name: &str, content: &str) -> Message {
+    Message::new(id, name.to_string(), content.to_string())
+}
+
+#[test]
+fn test_message_log_round_trip_with_escapes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chat.log");
+    let messages = vec![
+        msg(1, "alice", "hello"),
+        msg(2, "bob", "tab\there\nand a newline"),
+        msg(1, "alice", "backslash \\t is not a tab"),
+        msg(3, "carol", ""),
+        msg(2, "bob", "trailing newline\n"),
+    ];
+
+    let mut log = MessageLog::open(&path).unwrap();
+    for m in &messages {
+        log.append(m).unwrap();
+    }
+
+    assert_eq!(MessageLog::replay(&path).unwrap(), messages);
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.lines().count(), messages.len());
+}
+
+#[test]
+fn test_message_log_reopen_appends() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chat.log");
+
+    MessageLog::open(&path)
+        .unwrap()
+        .append(&msg(1, "alice", "first"))
+        .unwrap();
+    MessageLog::open(&path)
+        .unwrap()
+        .append(&msg(2, "bob", "second"))
+        .unwrap();
+
+    let replay = MessageLog::replay_entries(&path).unwrap();
+    assert_eq!(
+        replay.messages(),
+        vec![msg(1, "alice", "first"), msg(2, "bob", "second")]
+    );
+    assert_eq!(replay.skipped, 0);
+    assert!(replay.entries.iter().all(|e| e.timestamp > 0));
+}
+
+#[test]
+fn test_message_log_tail_of_large_log() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chat.log");
+    let mut log = MessageLog::open(&path).unwrap();
+    for i in 0..5_000 {
+        log.append(&msg(i % 7, "user", &format!("message number {}", i)))
+            .unwrap();
+    }
+
+    let tail = MessageLog::tail(&path, 25).unwrap();
+    assert_eq!(tail.len(), 25);
+    assert_eq!(tail[0].content, "message number 4975");
+    assert_eq!(tail[24].content, "message number 4999");
+
+    // Asking for more than exists returns everything, in order.
+    let all = MessageLog::tail(&path, 10_000).unwrap();
+    assert_eq!(all.len(), 5_000);
+    assert_eq!(all, MessageLog::replay(&path).unwrap());
+    assert!(MessageLog::tail(&path, 0).unwrap().is_empty());
+}
+
+#[test]
+fn test_message_log_skips_garbage_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chat.log");
+    let mut log = MessageLog::open(&path).unwrap();
+    log.append(&msg(1, "alice", "before")).unwrap();
+    drop(log);
+
+    let mut raw = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    use std::io::Write;
+    raw.write_all(b"this is not a log line\n").unwrap();
+    raw.write_all(b"17\tnot-a-number\tbob\thi\n").unwrap();
+    raw.write_all(b"17\t2\tbob\tbad escape \\q\n").unwrap();
+    raw.write_all(&[0xff, 0xfe, b'\n']).unwrap();
+    drop(raw);
+
+    MessageLog::open(&path)
+        .unwrap()
+        .append(&msg(2, "bob", "after"))
+        .unwrap();
+
+    let replay = MessageLog::replay_entries(&path).unwrap();
+    assert_eq!(
+        replay.messages(),
+        vec![msg(1, "alice", "before"), msg(2, "bob", "after")]
+    );
+    assert_eq!(replay.skipped, 4);
+
+    let tail = MessageLog::tail_entries(&path, 2).unwrap();
+    assert_eq!(tail.messages(), replay.messages());
+    assert_eq!(tail.skipped, 4);
+
+    let last = MessageLog::tail_entries(&path, 1).unwrap();
+    assert_eq!(
+        last,
+        LogReplay {
+            entries: replay.entries[1..].to_vec(),
+            skipped: 0
+        }
+    );
+}
+
+#[test]
+fn test_message_log_missing_file_is_error() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(MessageLog::replay(dir.path().join("missing.log")).is_err());
+    assert!(MessageLog::tail(dir.path().join("missing.log"), 3).is_err());
+}
+
+// ============================================================================
+// MAILROOM TESTS
+// ============================================================================
+
+fn mail(sender_id: u32, content: &str) -> Message {
+    Message::new(sender_id, format!("user{}", sender_id), content.to_string())
+}
+
+fn contents(batch: Vec<ServerEvent>) -> Vec<String> {
+    batch
+        .into_iter()
+        .map(|event| match event {
+            ServerEvent::Chat(message) => message.content,
+            other => panic!("expected chat, got {:?}", other),
+        })
+        .collect()
+}
+
+/// A room with three connected clients and no events waiting.
+fn quiet_room() -> (ChatRoom, [u32; 3]) {
+    let mut room = ChatRoom::new(RateLimiter::new(10, 1.0));
+    let ids = ["alice", "bob", "carol"].map(|name| room.join(name.to_string()).unwrap().id);
+    for id in ids {
+        room.drain_events(id);
+    }
+    (room, ids)
+}
+
+#[test]
+fn test_mailroom_broadcast_except_skips_sender() {
+    let (mut room, [alice, bob, carol]) = quiet_room();
+    let mut mailroom = room.mailroom();
+
+    assert_eq!(mailroom.broadcast(mail(bob, "hi all"), Some(bob)), 2);
+    assert_eq!(contents(mailroom.flush(alice, 10)), vec!["hi all"]);
+    assert_eq!(contents(mailroom.flush(carol, 10)), vec!["hi all"]);
+    assert!(mailroom.flush(bob, 10).is_empty());
+
+    assert_eq!(mailroom.broadcast(mail(0, "server notice"), None), 3);
+    assert_eq!(mailroom.pending(bob), 1);
+}
+
+#[test]
+fn test_mailroom_shares_outboxes_with_chat() {
+    let (mut room, [alice, bob, _]) = quiet_room();
+    room.post(alice, "from post", Instant::now()).unwrap();
+    room.mailroom().deliver_to(bob, mail(0, "from mailroom"));
+
+    assert_eq!(room.mailroom().pending(bob), 2);
+    assert_eq!(
+        contents(room.drain_events(bob)),
+        vec!["from post", "from mailroom"]
+    );
+}
+
+#[test]
+fn test_mailroom_flush_batches_in_order() {
+    let (mut room, [alice, ..]) = quiet_room();
+    let mut mailroom = room.mailroom();
+    for i in 1..=5 {
+        assert!(mailroom.deliver_to(alice, mail(0, &format!("m{}", i))));
+    }
+
+    assert_eq!(contents(mailroom.flush(alice, 2)), vec!["m1", "m2"]);
+    assert_eq!(mailroom.pending(alice), 3);
+    assert!(mailroom.flush(alice, 0).is_empty());
+    assert_eq!(contents(mailroom.flush(alice, 2)), vec!["m3", "m4"]);
+    assert_eq!(contents(mailroom.flush(alice, 2)), vec!["m5"]);
+    assert!(mailroom.flush(alice, 2).is_empty());
+}
+
+#[test]
+fn test_mailroom_only_reaches_active_clients() {
+    let (mut room, [alice, bob, carol]) = quiet_room();
+    room.admin().kick_client(carol).unwrap();
+    room.leave(bob);
+    room.drain_events(alice);
+
+    let mut mailroom = room.mailroom();
+    assert!(!mailroom.deliver_to(bob, mail(0, "hello?")));
+    assert!(!mailroom.deliver_to(carol, mail(0, "hello?")));
+    assert!(!mailroom.deliver_to(99, mail(0, "hello?")));
+    assert_eq!(mailroom.broadcast(mail(0, "anyone?"), None), 1);
+    assert_eq!(mailroom.pending(alice), 1);
+    assert_eq!(mailroom.pending(bob), 0);
+}
+
+#[test]
+fn test_leave_reports_discarded_events() {
+    let (mut room, [alice, bob, _]) = quiet_room();
+    for i in 0..4 {
+        room.mailroom()
+            .deliver_to(bob, mail(0, &format!("unread {}", i)));
+    }
+    room.mailroom().flush(bob, 1);
+
+    assert_eq!(room.leave(bob), 3);
+    assert!(!room.mailroom().deliver_to(bob, mail(0, "too late")));
+
+    // Nothing queued, and a second leave, both discard nothing
+    room.drain_events(alice);
+    assert_eq!(room.leave(alice), 0);
+    assert_eq!(room.leave(bob), 0);
+}
+
+#[test]
+fn test_mailroom_full_outbox_drops_oldest() {
+    let (mut room, [alice, bob, _]) = quiet_room();
+    let mut mailroom = room.mailroom();
+    for i in 1..=OUTBOX_CAPACITY + 2 {
+        mailroom.broadcast(mail(alice, &format!("m{}", i)), Some(alice));
+    }
+
+    assert_eq!(mailroom.pending(bob), OUTBOX_CAPACITY);
+    let batch = contents(mailroom.flush(bob, 2));
+    assert_eq!(batch, vec!["m3", "m4"]);
+
+    // Overflow only counts what is still queued at leave
+    assert_eq!(room.leave(bob), OUTBOX_CAPACITY - 2);
+}
+
+// ============================================================================
+// FRAMING TESTS
+// ============================================================================
+
+/// A frame with arbitrary header values and raw name/content bytes.
+fn raw_frame(sender_id: u32, name: &[u8], content: &[u8]) -> Vec<u8> {
+    let length = (6 + name.len() + content.len()) as u32;
+    let mut frame = length.to_le_bytes().to_vec();
+    frame.extend_from_slice(&sender_id.to_le_bytes());
+    frame.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    frame.extend_from_slice(name);
+    frame.extend_from_slice(content);
+    frame
+}
+
+#[test]
+fn test_encode_frame_layout() {
+    let frame = encode_frame(&msg(0x0102_0304, "al", "hi!"));
+    assert_eq!(
+        frame,
+        [11, 0, 0, 0, 4, 3, 2, 1, 2, 0, b'a', b'l', b'h', b'i', b'!']
+    );
+}
+
+#[test]
+fn test_frame_round_trip() {
+    let original = msg(7, "zoë", "line one\nline two\twith tab, ünïcode 🚀");
+    let mut decoder = FrameDecoder::new();
+    assert_eq!(decoder.max_frame_len(), DEFAULT_MAX_FRAME_LEN);
+    assert_eq!(decoder.feed(&encode_frame(&original)), vec![Ok(original)]);
+    assert_eq!(decoder.buffered(), 0);
+
+    // Empty content is a valid frame.
+    let empty = msg(1, "bob", "");
+    assert_eq!(decoder.feed(&encode_frame(&empty)), vec![Ok(empty)]);
+}
+
+#[test]
+fn test_frame_split_across_three_feeds() {
+    let message = msg(42, "carol", "split across reads");
+    let frame = encode_frame(&message);
+    let mut decoder = FrameDecoder::new();
+
+    // Cut inside the length prefix, then inside the name.
+    assert!(decoder.feed(&frame[..3]).is_empty());
+    assert_eq!(decoder.buffered(), 3);
+    assert!(decoder.feed(&frame[3..12]).is_empty());
+    assert_eq!(decoder.feed(&frame[12..]), vec![Ok(message)]);
+    assert_eq!(decoder.buffered(), 0);
+}
+
+#[test]
+fn test_frame_byte_at_a_time() {
+    let message = msg(3, "dave", "slow network");
+    let mut decoder = FrameDecoder::new();
+    let mut decoded = Vec::new();
+    for byte in encode_frame(&message) {
+        decoded.extend(decoder.feed(&[byte]));
+    }
+    assert_eq!(decoded, vec![Ok(message)]);
+}
+
+#[test]
+fn test_two_frames_in_one_feed() {
+    let first = msg(1, "alice", "hello");
+    let second = msg(2, "bob", "hi");
+    let mut bytes = encode_frame(&first);
+    bytes.extend(encode_frame(&second));
+    // Plus the start of a third frame, which must wait.
+    bytes.extend(&encode_frame(&first)[..5]);
+
+    let mut decoder = FrameDecoder::new();
+    assert_eq!(decoder.feed(&bytes), vec![Ok(first), Ok(second)]);
+    assert_eq!(decoder.buffered(), 5);
+}
+
+#[test]
+fn test_oversized_frame_skipped_and_recovered() {
+    let mut decoder = FrameDecoder::with_max_frame_len(16);
+    assert_eq!(decoder.max_frame_len(), 16);
+    let big = encode_frame(&msg(1, "spammer", "this content is far too long"));
+    let after = msg(2, "al", "back");
+
+    // The oversized frame arrives in pieces; the error is reported once.
+    let results = decoder.feed(&big[..10]);
+    assert_eq!(
+        results,
+        vec![Err(DecodeError::FrameTooLarge {
+            length: big.len() - 4,
+            max: 16,
+        })]
+    );
+    assert_eq!(decoder.buffered(), 0, "oversized frames are never buffered");
+
+    let mut rest = big[10..].to_vec();
+    rest.extend(encode_frame(&after));
+    assert_eq!(decoder.feed(&rest), vec![Ok(after)]);
+}
+
+#[test]
+fn test_malformed_frames_only_fail_themselves() {
+    let good = msg(9, "erin", "ok");
+    let mut bytes = raw_frame(1, "name".as_bytes(), &[0xff, 0xfe]);
+    bytes.extend(raw_frame(2, &[0xc3], b"x"));
+    bytes.extend(encode_frame(&good));
+    // length 2 can't hold a header.
+    bytes.extend([2, 0, 0, 0, 0xaa, 0xbb]);
+    // name_len 200 in a frame with 3 bytes after the header.
+    bytes.extend([9, 0, 0, 0, 1, 0, 0, 0, 200, 0, b'a', b'b', b'c']);
+    bytes.extend(encode_frame(&good));
+
+    let results = FrameDecoder::new().feed(&bytes);
+    assert_eq!(
+        results,
+        vec![
+            Err(DecodeError::InvalidUtf8 { field: "content" }),
+            Err(DecodeError::InvalidUtf8 { field: "name" }),
+            Ok(good.clone()),
+            Err(DecodeError::FrameTooShort { length: 2 }),
+            Err(DecodeError::NameOutOfBounds {
+                name_len: 200,
+                length: 9,
+            }),
+            Ok(good),
+        ]
+    );
+}
+
+#[test]
+fn test_decode_error_display() {
+    let err = DecodeError::FrameTooLarge {
+        length: 100,
+        max: 16,
+    };
+    assert_eq!(err.to_string(), "frame of 100 bytes exceeds limit of 16");
+    assert_eq!(
+        DecodeError::InvalidUtf8 { field: "name" }.to_string(),
+        "name is not valid UTF-8"
+    );
+}
+
+#[test]
+fn test_frame_decoder_random_bytes_never_panic() {
+    // xorshift: deterministic "random" input without a rand dependency.
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for round in 0..200 {
+        let mut decoder = FrameDecoder::with_max_frame_len(64 + round);
+        for _ in 0..20 {
+            let len = (next() % 64) as usize;
+            let mut chunk: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            // Small lengths now and then, so some frames actually complete.
+            if len >= 4 && next() % 2 == 0 {
+                chunk[..4].copy_from_slice(&((next() % 40) as u32).to_le_bytes());
+            }
+            for result in decoder.feed(&chunk) {
+                if let Err(err) = result {
+                    assert!(!err.to_string().is_empty());
+                }
+            }
+            // At most one partial frame is ever held back.
+            assert!(decoder.buffered() <= decoder.max_frame_len() + 4);
+        }
+    }
+}
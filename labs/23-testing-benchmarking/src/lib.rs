@@ -2,6 +2,10 @@
 //!
 //! Student-facing API with arithmetic and utility exercises.
 
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
+
 pub fn add(a: i32, b: i32) -> i32 {
     // TODO: Return sum.
     let _ = (a, b);
@@ -44,39 +48,677 @@ pub fn fibonacci(n: u32) -> u64 {
     todo!("Implement fibonacci")
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalcError {
+    Overflow,
+    DivisionByZero,
+}
+
+impl std::fmt::Display for CalcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalcError::Overflow => write!(f, "arithmetic overflow"),
+            CalcError::DivisionByZero => write!(f, "cannot divide by zero"),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpKind {
+    Add(i32),
+    Subtract(i32),
+    Multiply(i32),
+    Divide(i32),
+    Pow(u32),
+    Reset,
+}
+
+/// One successful operation, with the value before and after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operation {
+    pub kind: OpKind,
+    pub before: i32,
+    pub after: i32,
+}
+
 pub struct Calculator {
     pub value: i32,
+    history: Vec<Operation>,
 }
 
 impl Calculator {
     pub fn new(initial: i32) -> Self {
-        // TODO: Construct calculator with initial value.
+        // TODO: Construct calculator with initial value and empty history.
         let _ = initial;
         todo!("Create Calculator")
     }
 
-    pub fn add(&mut self, n: i32) {
-        // TODO: Increase current value.
+    pub fn add(&mut self, n: i32) -> Result<(), CalcError> {
+        // TODO: checked_add; on success record the operation.
         let _ = n;
         todo!("Calculator add")
     }
 
-    pub fn subtract(&mut self, n: i32) {
-        // TODO: Decrease current value.
+    pub fn subtract(&mut self, n: i32) -> Result<(), CalcError> {
+        // TODO: checked_sub; on success record the operation.
         let _ = n;
         todo!("Calculator subtract")
     }
 
-    pub fn multiply(&mut self, n: i32) {
-        // TODO: Multiply current value.
+    pub fn multiply(&mut self, n: i32) -> Result<(), CalcError> {
+        // TODO: checked_mul; on success record the operation.
         let _ = n;
         todo!("Calculator multiply")
     }
 
+    pub fn divide(&mut self, n: i32) -> Result<(), CalcError> {
+        // TODO: DivisionByZero for 0, otherwise checked_div.
+        let _ = n;
+        todo!("Calculator divide")
+    }
+
+    pub fn pow(&mut self, exp: u32) -> Result<(), CalcError> {
+        // TODO: checked_pow; on success record the operation.
+        let _ = exp;
+        todo!("Calculator pow")
+    }
+
     pub fn reset(&mut self) {
-        // TODO: Reset value to zero.
+        // TODO: Set value to zero and record it.
         todo!("Calculator reset")
     }
+
+    pub fn undo(&mut self) -> Option<Operation> {
+        // TODO: Pop the last operation and restore its `before` value.
+        todo!("Calculator undo")
+    }
+
+    pub fn history(&self) -> &[Operation] {
+        &self.history
+    }
+}
+
+/// A uniquely named temp directory that is removed recursively on Drop.
+pub struct TempWorkspace {
+    root: PathBuf,
+    armed: bool,
+}
+
+impl TempWorkspace {
+    pub fn new() -> Self {
+        // TODO: Build a unique name (pid + atomic counter + timestamp) under
+        // std::env::temp_dir() and create the directory.
+        todo!("Create TempWorkspace")
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn path(&self, name: &str) -> io::Result<PathBuf> {
+        // TODO: Reject empty names, absolute paths, and `..` components with
+        // ErrorKind::InvalidInput; otherwise join onto the root.
+        let _ = name;
+        todo!("Resolve workspace path")
+    }
+
+    pub fn file(&self, name: &str, contents: &str) -> PathBuf {
+        // TODO: Delegate to file_bytes.
+        let _ = (name, contents);
+        todo!("Write text fixture")
+    }
+
+    pub fn file_bytes(&self, name: &str, contents: &[u8]) -> PathBuf {
+        // TODO: Create parent directories, then write the bytes.
+        let _ = (name, contents);
+        todo!("Write binary fixture")
+    }
+
+    pub fn dir(&self, name: &str) -> PathBuf {
+        // TODO: Create the directory (and parents).
+        let _ = name;
+        todo!("Create fixture directory")
+    }
+
+    pub fn csv_file<H, R, F>(&self, name: &str, headers: &[H], rows: &[R]) -> PathBuf
+    where
+        H: AsRef<str>,
+        R: AsRef<[F]>,
+        F: AsRef<str>,
+    {
+        // TODO: Quote fields containing `,`, `"`, or line breaks; double
+        // embedded quotes.
+        let _ = (name, headers, rows);
+        todo!("Write CSV fixture")
+    }
+
+    pub fn json_file(&self, name: &str, value: &impl Serialize) -> PathBuf {
+        // TODO: Serialize with serde_json::to_string_pretty and write.
+        let _ = (name, value);
+        todo!("Write JSON fixture")
+    }
+
+    pub fn tree(&self, entries: &[(&str, &str)]) -> Vec<PathBuf> {
+        // TODO: Call file() for each entry.
+        let _ = entries;
+        todo!("Write fixture tree")
+    }
+
+    pub fn read(&self, name: &str) -> String {
+        // TODO: Read a workspace file to a String.
+        let _ = name;
+        todo!("Read fixture")
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        // TODO: Return false for escaping names instead of erroring.
+        let _ = name;
+        todo!("Check fixture exists")
+    }
+
+    pub fn list(&self) -> Vec<PathBuf> {
+        // TODO: Recursively collect entries relative to the root, sorted.
+        todo!("List fixtures")
+    }
+
+    pub fn keep(self) -> PathBuf {
+        // TODO: Disarm cleanup and return the root.
+        todo!("Keep workspace")
+    }
+}
+
+impl Default for TempWorkspace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TempWorkspace {
+    fn drop(&mut self) {
+        // TODO: When armed, remove the root with fs::remove_dir_all and
+        // ignore errors (never panic in Drop).
+        let _ = (&self.root, self.armed);
+    }
+}
+
+// ============================================================================
+// PROPERTY-BASED TESTING (a tiny proptest)
+// ============================================================================
+// Example-based tests check the cases you thought of. Property-based tests
+// state a rule that must hold for ALL inputs ("add(a, b) == add(b, a)"),
+// then throw hundreds of generated inputs at it. When one fails, the runner
+// SHRINKS it: tries smaller versions of the input that still fail, so you
+// get `[0, -1]` instead of a 37-element vector of random noise.
+//
+// Real projects use the `proptest` or `quickcheck` crates. This module is a
+// dependency-free miniature to show the moving parts.
+
+pub mod proptest_lite {
+    use std::fmt::Debug;
+    use std::ops::RangeInclusive;
+
+    /// Seed used by `check_property`, so every run generates the same cases.
+    pub const DEFAULT_SEED: u64 = 0x5EED_CAFE_F00D_1234;
+
+    /// Upper bound on shrink steps, in case a generator shrinks forever.
+    const MAX_SHRINK_STEPS: usize = 1_000;
+
+    /// Advances `seed` and returns the next pseudo-random number (splitmix64).
+    pub fn next_u64(seed: &mut u64) -> u64 {
+        *seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = *seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A source of random test inputs.
+    ///
+    /// All randomness comes from `seed`, so the same seed always produces
+    /// the same inputs - a failing case can be replayed exactly.
+    pub trait Gen<T> {
+        fn gen(&mut self, seed: &mut u64) -> T;
+
+        /// Smaller candidates to try when `value` fails. Every candidate
+        /// must be something `gen` could have produced.
+        fn shrink(&self, _value: &T) -> Vec<T> {
+            Vec::new()
+        }
+    }
+
+    /// Integers in an inclusive range; see `int_range`.
+    #[derive(Debug, Clone)]
+    pub struct IntRange {
+        lo: i32,
+        hi: i32,
+    }
+
+    /// Generates integers in `range`, shrinking toward 0 (or toward the
+    /// range end nearest 0 if 0 is out of range).
+    pub fn int_range(range: RangeInclusive<i32>) -> IntRange {
+        let (lo, hi) = range.into_inner();
+        assert!(lo <= hi, "empty range");
+        IntRange { lo, hi }
+    }
+
+    impl Gen<i32> for IntRange {
+        fn gen(&mut self, seed: &mut u64) -> i32 {
+            // TODO: Map next_u64(seed) into lo..=hi (use i64 to avoid overflow).
+            let _ = seed;
+            todo!("Generate integer in range")
+        }
+
+        fn shrink(&self, value: &i32) -> Vec<i32> {
+            // TODO: Candidates moving toward 0 clamped into the range:
+            // the target itself, then halfway between target and value.
+            let _ = value;
+            todo!("Shrink integer")
+        }
+    }
+
+    /// Vectors of up to `max_len` elements; see `vec_of`.
+    #[derive(Debug, Clone)]
+    pub struct VecOf<G> {
+        element: G,
+        max_len: usize,
+    }
+
+    /// Generates vectors of 0..=`max_len` elements from `element`. Shrinks
+    /// by dropping elements, then by shrinking individual elements.
+    pub fn vec_of<G>(element: G, max_len: usize) -> VecOf<G> {
+        VecOf { element, max_len }
+    }
+
+    impl<T: Clone, G: Gen<T>> Gen<Vec<T>> for VecOf<G> {
+        fn gen(&mut self, seed: &mut u64) -> Vec<T> {
+            let len = (next_u64(seed) % (self.max_len as u64 + 1)) as usize;
+            (0..len).map(|_| self.element.gen(seed)).collect()
+        }
+
+        fn shrink(&self, value: &Vec<T>) -> Vec<Vec<T>> {
+            // TODO: Each single-element removal, then each element shrunk
+            // with self.element.shrink.
+            let _ = value;
+            todo!("Shrink vector")
+        }
+    }
+
+    /// Strings drawn from an alphabet; see `string_of`.
+    #[derive(Debug, Clone)]
+    pub struct StringOf {
+        alphabet: Vec<char>,
+        max_len: usize,
+    }
+
+    /// Generates strings of 0..=`max_len` chars taken from `alphabet`.
+    /// Shrinks by dropping characters.
+    pub fn string_of(alphabet: &str, max_len: usize) -> StringOf {
+        let alphabet: Vec<char> = alphabet.chars().collect();
+        assert!(!alphabet.is_empty(), "empty alphabet");
+        StringOf { alphabet, max_len }
+    }
+
+    impl Gen<String> for StringOf {
+        fn gen(&mut self, seed: &mut u64) -> String {
+            let len = (next_u64(seed) % (self.max_len as u64 + 1)) as usize;
+            (0..len)
+                .map(|_| self.alphabet[(next_u64(seed) % self.alphabet.len() as u64) as usize])
+                .collect()
+        }
+
+        fn shrink(&self, value: &String) -> Vec<String> {
+            let chars: Vec<char> = value.chars().collect();
+            (0..chars.len())
+                .map(|skip| {
+                    chars
+                        .iter()
+                        .enumerate()
+                        .filter(|&(i, _)| i != skip)
+                        .map(|(_, c)| c)
+                        .collect()
+                })
+                .collect()
+        }
+    }
+
+    /// Pairs of values from two generators; see `pair`.
+    #[derive(Debug, Clone)]
+    pub struct Pair<A, B> {
+        first: A,
+        second: B,
+    }
+
+    /// Generates `(a, b)` tuples. Shrinks one side at a time.
+    pub fn pair<A, B>(first: A, second: B) -> Pair<A, B> {
+        Pair { first, second }
+    }
+
+    impl<X: Clone, Y: Clone, A: Gen<X>, B: Gen<Y>> Gen<(X, Y)> for Pair<A, B> {
+        fn gen(&mut self, seed: &mut u64) -> (X, Y) {
+            let x = self.first.gen(seed);
+            let y = self.second.gen(seed);
+            (x, y)
+        }
+
+        fn shrink(&self, (x, y): &(X, Y)) -> Vec<(X, Y)> {
+            let lefts = self.first.shrink(x).into_iter().map(|x| (x, y.clone()));
+            let rights = self.second.shrink(y).into_iter().map(|y| (x.clone(), y));
+            lefts.chain(rights).collect()
+        }
+    }
+
+    /// Outcome of `check_property`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum PropertyResult<T> {
+        Passed {
+            cases: usize,
+        },
+        Failed {
+            /// 0-based index of the first failing case.
+            case: usize,
+            /// The input as generated.
+            original: T,
+            /// The smallest failing input shrinking found.
+            shrunk: T,
+            shrink_steps: usize,
+        },
+    }
+
+    impl<T> PropertyResult<T> {
+        pub fn is_passed(&self) -> bool {
+            matches!(self, PropertyResult::Passed { .. })
+        }
+
+        /// The minimal failing input, if the property failed.
+        pub fn counterexample(&self) -> Option<&T> {
+            match self {
+                PropertyResult::Passed { .. } => None,
+                PropertyResult::Failed { shrunk, .. } => Some(shrunk),
+            }
+        }
+    }
+
+    /// Runs `prop` on `cases` generated inputs using `DEFAULT_SEED`.
+    pub fn check_property<T: Clone + Debug>(
+        cases: usize,
+        gen: impl Gen<T>,
+        prop: impl Fn(&T) -> bool,
+    ) -> PropertyResult<T> {
+        check_property_with_seed(DEFAULT_SEED, cases, gen, prop)
+    }
+
+    /// Like `check_property`, with an explicit seed.
+    ///
+    /// Stops at the first failing input and shrinks it greedily: take the
+    /// first shrink candidate that still fails, repeat until none do.
+    pub fn check_property_with_seed<T: Clone + Debug>(
+        seed: u64,
+        cases: usize,
+        mut gen: impl Gen<T>,
+        prop: impl Fn(&T) -> bool,
+    ) -> PropertyResult<T> {
+        // TODO: Generate `cases` inputs; on the first failure, greedily
+        // replace it with the first failing shrink candidate until none fail
+        // (at most MAX_SHRINK_STEPS times).
+        let _ = (seed, cases, &mut gen, prop, MAX_SHRINK_STEPS);
+        todo!("Run property check")
+    }
+}
+
+// ============================================================================
+// SNAPSHOT TESTING (golden files)
+// ============================================================================
+// Some outputs are tedious to assert field by field: a rendered report, a
+// pretty-printed AST, a big `Debug` dump. A snapshot test stores the output
+// once in a "golden" file and compares every later run against it. When the
+// output changes on purpose, you re-run with `UPDATE_SNAPSHOTS=1` and review
+// the rewritten files in your diff like any other code change.
+//
+// The `insta` crate is the real-world tool. This module shows the core:
+// files live in `tests/snapshots/<name>.snap`, a mismatch fails with a
+// unified diff, and update mode rewrites instead of failing.
+
+pub mod snapshot {
+    use std::env;
+    use std::fmt::{self, Debug};
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    /// Set this environment variable to `1` to (re)write snapshots instead
+    /// of failing on a mismatch.
+    pub const UPDATE_ENV_VAR: &str = "UPDATE_SNAPSHOTS";
+
+    /// Lines of unchanged context around each change in `render_diff`.
+    const DIFF_CONTEXT: usize = 3;
+
+    /// What `Snapshots::check` did when it succeeded.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SnapshotOutcome {
+        /// The stored snapshot matched.
+        Matched,
+        /// Update mode wrote a snapshot that did not exist yet.
+        Created,
+        /// Update mode replaced a snapshot that differed.
+        Updated,
+    }
+
+    /// Why a snapshot check failed.
+    #[derive(Debug)]
+    pub enum SnapshotError {
+        /// Names must be plain file stems: no separators, no `..`.
+        InvalidName(String),
+        /// There is no stored snapshot and update mode is off.
+        Missing { path: PathBuf },
+        /// The stored snapshot differs; `diff` is from `render_diff`.
+        Mismatch { path: PathBuf, diff: String },
+        /// Reading or writing the snapshot file failed.
+        Io(io::Error),
+    }
+
+    impl fmt::Display for SnapshotError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            // TODO: One message per variant; Missing should mention UPDATE_ENV_VAR,
+            // Mismatch should include the diff.
+            let _ = (self, f);
+            todo!("Display SnapshotError")
+        }
+    }
+
+    impl std::error::Error for SnapshotError {}
+
+    impl From<io::Error> for SnapshotError {
+        fn from(err: io::Error) -> Self {
+            SnapshotError::Io(err)
+        }
+    }
+
+    /// A snapshot directory plus the update-mode switch.
+    ///
+    /// The free functions `assert_snapshot` and `assert_debug_snapshot` use
+    /// `Snapshots::default()`; tests of the snapshot machinery itself point
+    /// a `Snapshots` at a temporary directory instead.
+    #[derive(Debug, Clone)]
+    pub struct Snapshots {
+        root: PathBuf,
+        update: bool,
+    }
+
+    impl Snapshots {
+        /// Snapshots stored under `root`. Update mode starts from the
+        /// `UPDATE_SNAPSHOTS` environment variable.
+        pub fn new(root: impl Into<PathBuf>) -> Self {
+            // TODO: update = (env var UPDATE_ENV_VAR == "1").
+            let _ = (root.into(), env::var(UPDATE_ENV_VAR));
+            todo!("Create Snapshots")
+        }
+
+        /// Overrides update mode regardless of the environment.
+        pub fn with_update(self, update: bool) -> Self {
+            let _ = update;
+            todo!("Override update mode")
+        }
+
+        pub fn root(&self) -> &Path {
+            todo!("Return root")
+        }
+
+        /// Where the snapshot called `name` is stored.
+        pub fn path_for(&self, name: &str) -> Result<PathBuf, SnapshotError> {
+            // TODO: Reject "", ".", ".." and names containing / or \\;
+            // otherwise root/<name>.snap.
+            let _ = name;
+            todo!("Snapshot path")
+        }
+
+        /// Compares `actual` with the stored snapshot without panicking.
+        ///
+        /// Line endings and trailing newlines are normalized on both sides,
+        /// so a snapshot file touched by an editor or a Windows checkout
+        /// still matches.
+        pub fn check(&self, name: &str, actual: &str) -> Result<SnapshotOutcome, SnapshotError> {
+            // TODO: Read the file (NotFound = no snapshot), normalize both sides,
+            // and compare. On a difference or missing file, write `actual + "\n"`
+            // in update mode, otherwise return Mismatch (with render_diff) or
+            // Missing.
+            let _ = (
+                name,
+                actual,
+                fs::read_to_string::<&Path>,
+                io::ErrorKind::NotFound,
+            );
+            todo!("Check snapshot")
+        }
+
+        /// Panics with a diff unless `actual` matches the stored snapshot.
+        #[track_caller]
+        pub fn assert_snapshot(&self, name: &str, actual: &str) {
+            let _ = (name, actual);
+            todo!("Panic with the error on failure")
+        }
+
+        /// Snapshots the pretty `{:#?}` form of `value`.
+        #[track_caller]
+        pub fn assert_debug_snapshot(&self, name: &str, value: &impl Debug) {
+            // TODO: Snapshot format!("{:#?}", value).
+            let _ = (name, value);
+            todo!("Debug snapshot")
+        }
+    }
+
+    impl Default for Snapshots {
+        /// `tests/snapshots` under the crate being tested. Cargo sets
+        /// `CARGO_MANIFEST_DIR` when it runs tests; outside cargo we fall
+        /// back to the current directory.
+        fn default() -> Self {
+            // TODO: $CARGO_MANIFEST_DIR (or ".") joined with tests/snapshots.
+            todo!("Default snapshot root")
+        }
+    }
+
+    /// Compares `actual` with `tests/snapshots/<name>.snap`.
+    #[track_caller]
+    pub fn assert_snapshot(name: &str, actual: &str) {
+        Snapshots::default().assert_snapshot(name, actual);
+    }
+
+    /// Compares `{:#?}` of `value` with `tests/snapshots/<name>.snap`.
+    #[track_caller]
+    pub fn assert_debug_snapshot(name: &str, value: &impl Debug) {
+        Snapshots::default().assert_debug_snapshot(name, value);
+    }
+
+    /// A line-based unified diff from `expected` to `actual`, or an empty
+    /// string when they are equal.
+    ///
+    /// Lines are matched with a longest-common-subsequence table, so an
+    /// inserted line shows up as one `+` line instead of shifting every
+    /// line after it. Changes are grouped into `@@` hunks with three lines
+    /// of context; hunks whose context would overlap are merged.
+    pub fn render_diff(expected: &str, actual: &str) -> String {
+        // TODO: Build an LCS table over lines, walk it into equal/delete/
+        // insert operations (deletions first), then print
+        // "--- expected\n+++ actual\n" followed by
+        // "@@ -start,len +start,len @@" hunks with DIFF_CONTEXT lines of
+        // context and ' ', '-', '+' line prefixes.
+        let _ = (expected, actual, DIFF_CONTEXT);
+        todo!("Render a unified diff")
+    }
+}
+
+// ============================================================================
+// FAKE DATA (fixed-seed generators)
+// ============================================================================
+// A seeded faker: varied test data that is identical on every run. Build it
+// on `proptest_lite::next_u64` and use only integer math.
+
+pub mod fake {
+    use super::proptest_lite::next_u64;
+    use super::OpKind;
+    use std::ops::RangeInclusive;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FakeData {
+        state: u64,
+    }
+
+    impl FakeData {
+        pub fn new(seed: u64) -> Self {
+            FakeData { state: seed }
+        }
+
+        pub fn next_u64(&mut self) -> u64 {
+            next_u64(&mut self.state)
+        }
+
+        /// A uniformly distributed integer in `range`. Panics if empty.
+        pub fn int_in(&mut self, range: RangeInclusive<i64>) -> i64 {
+            // TODO: Reject draws above the largest multiple of the span so
+            // `% span` isn't biased. Mind the full i64 range.
+            let _ = range;
+            todo!("Draw an integer from a range")
+        }
+
+        pub fn pick<'a, T>(&mut self, slice: &'a [T]) -> &'a T {
+            let _ = slice;
+            todo!("Pick a random element")
+        }
+
+        pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+            // TODO: Fisher-Yates: swap each i (from the end) with a random j <= i.
+            let _ = slice;
+            todo!("Shuffle in place")
+        }
+
+        /// A "First Last" name from fixed lists.
+        pub fn name(&mut self) -> String {
+            todo!("Generate a name")
+        }
+
+        /// A lowercase `first.last<n>@example.com`-style address.
+        pub fn email(&mut self) -> String {
+            todo!("Generate an email")
+        }
+
+        /// `words` words, first capitalized, ending in '.'; "" for zero.
+        pub fn sentence(&mut self, words: usize) -> String {
+            let _ = words;
+            todo!("Generate a sentence")
+        }
+
+        pub fn vec_i32(&mut self, len: usize, range: RangeInclusive<i32>) -> Vec<i32> {
+            let _ = (len, range);
+            todo!("Generate a vector of integers")
+        }
+
+        /// Random Add/Subtract/Multiply/Divide/Pow operations.
+        pub fn calculator_ops(&mut self, len: usize) -> Vec<OpKind> {
+            let _ = len;
+            todo!("Generate calculator operations")
+        }
+    }
 }
 
 #[doc(hidden)]
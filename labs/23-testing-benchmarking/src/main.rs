@@ -1,6 +1,7 @@
 //! # Testing and Benchmarking Demo
 
-use testing_benchmarking::solution::{self, Calculator};
+use testing_benchmarking::solution::proptest_lite::{check_property, int_range, vec_of};
+use testing_benchmarking::solution::{self, Calculator, TempWorkspace};
 
 fn main() {
     println!("=== Testing and Benchmarking Demo ===\n");
@@ -14,8 +15,31 @@ fn main() {
     println!("fibonacci(10) = {}", solution::fibonacci(10));
 
     let mut calc = Calculator::new(10);
-    calc.add(5);
-    calc.multiply(2);
-    calc.subtract(4);
+    calc.add(5).expect("no overflow");
+    calc.multiply(2).expect("no overflow");
+    calc.subtract(4).expect("no overflow");
     println!("calculator value = {}", calc.value);
+    println!("calc.pow(40) = {:?}", calc.pow(40));
+    println!("calc.divide(0) = {:?}", calc.divide(0));
+    println!("undo {:?} -> value = {}", calc.undo(), calc.value);
+
+    let ws = TempWorkspace::new();
+    ws.tree(&[("fixtures/a.txt", "alpha"), ("fixtures/b.txt", "beta")]);
+    ws.csv_file("people.csv", &["name", "city"], &[["Ada", "London, UK"]]);
+    println!("\nworkspace at {:?}", ws.root());
+    println!("workspace files = {:?}", ws.list());
+    println!("people.csv =\n{}", ws.read("people.csv"));
+
+    println!("\n--- Property-based testing ---");
+    let result = check_property(500, vec_of(int_range(0..=50), 20), |v| {
+        v.iter().sum::<i32>() < 100
+    });
+    println!("\"every vector sums to < 100\": {:?}", result);
+
+    println!("\n--- Fake data (seed 42) ---");
+    let mut fake = solution::fake::FakeData::new(42);
+    println!("name     = {}", fake.name());
+    println!("email    = {}", fake.email());
+    println!("sentence = {}", fake.sentence(6));
+    println!("ints     = {:?}", fake.vec_i32(8, -10..=10));
 }
@@ -7,6 +7,13 @@
 // Run tests with: cargo test
 // Run benchmarks with: cargo bench (requires criterion in Cargo.toml)
 
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
 // ============================================================================
 // LIBRARY CODE
 // ============================================================================
@@ -112,9 +119,57 @@ pub fn fibonacci(n: u32) -> u64 {
     }
 }
 
+/// Why a `Calculator` operation was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalcError {
+    /// The result doesn't fit in an `i32`.
+    Overflow,
+    DivisionByZero,
+}
+
+impl std::fmt::Display for CalcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalcError::Overflow => write!(f, "arithmetic overflow"),
+            CalcError::DivisionByZero => write!(f, "cannot divide by zero"),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+/// Which operation was applied, with its operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpKind {
+    Add(i32),
+    Subtract(i32),
+    Multiply(i32),
+    Divide(i32),
+    Pow(u32),
+    Reset,
+}
+
+/// One successful operation in a `Calculator`'s history.
+///
+/// We store the value from `before` the operation rather than computing an
+/// inverse: multiplying by 0 or integer division can't be undone by math.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operation {
+    pub kind: OpKind,
+    pub before: i32,
+    pub after: i32,
+}
+
 /// A simple calculator struct to demonstrate testing methods.
+///
+/// Every operation uses `checked_*` arithmetic: plain `+` panics on
+/// overflow in debug builds but silently wraps in release builds, so the
+/// same test could pass or fail depending on the profile. On error `value`
+/// is left unchanged and nothing is recorded.
 pub struct Calculator {
+    /// Writing this directly bypasses the history.
     pub value: i32,
+    history: Vec<Operation>,
 }
 
 impl Calculator {
@@ -128,27 +183,1070 @@ impl Calculator {
     /// assert_eq!(calc.value, 10);
     /// ```ignore
     pub fn new(initial: i32) -> Self {
-        Calculator { value: initial }
+        Calculator {
+            value: initial,
+            history: Vec::new(),
+        }
     }
 
     /// Adds to the current value.
-    pub fn add(&mut self, n: i32) {
-        self.value += n;
+    pub fn add(&mut self, n: i32) -> Result<(), CalcError> {
+        self.apply(OpKind::Add(n), self.value.checked_add(n))
     }
 
     /// Subtracts from the current value.
-    pub fn subtract(&mut self, n: i32) {
-        self.value -= n;
+    pub fn subtract(&mut self, n: i32) -> Result<(), CalcError> {
+        self.apply(OpKind::Subtract(n), self.value.checked_sub(n))
     }
 
     /// Multiplies the current value.
-    pub fn multiply(&mut self, n: i32) {
-        self.value *= n;
+    pub fn multiply(&mut self, n: i32) -> Result<(), CalcError> {
+        self.apply(OpKind::Multiply(n), self.value.checked_mul(n))
+    }
+
+    /// Divides the current value (integer division, rounding toward zero).
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use testing_benchmarking::{CalcError, Calculator};
+    /// let mut calc = Calculator::new(7);
+    /// assert_eq!(calc.divide(0), Err(CalcError::DivisionByZero));
+    /// calc.divide(2).unwrap();
+    /// assert_eq!(calc.value, 3);
+    /// ```
+    pub fn divide(&mut self, n: i32) -> Result<(), CalcError> {
+        if n == 0 {
+            return Err(CalcError::DivisionByZero);
+        }
+        // `i32::MIN / -1` is the one division that overflows.
+        self.apply(OpKind::Divide(n), self.value.checked_div(n))
+    }
+
+    /// Raises the current value to `exp`. Anything to the power 0 is 1.
+    pub fn pow(&mut self, exp: u32) -> Result<(), CalcError> {
+        self.apply(OpKind::Pow(exp), self.value.checked_pow(exp))
     }
 
     /// Resets the calculator to zero.
     pub fn reset(&mut self) {
-        self.value = 0;
+        let _ = self.apply(OpKind::Reset, Some(0));
+    }
+
+    /// Reverts the most recent operation and returns it, or `None` if the
+    /// history is empty.
+    pub fn undo(&mut self) -> Option<Operation> {
+        let last = self.history.pop()?;
+        self.value = last.before;
+        Some(last)
+    }
+
+    /// Every successful operation, oldest first.
+    pub fn history(&self) -> &[Operation] {
+        &self.history
+    }
+
+    /// Commits `result` (or reports overflow) and records the operation.
+    fn apply(&mut self, kind: OpKind, result: Option<i32>) -> Result<(), CalcError> {
+        let after = result.ok_or(CalcError::Overflow)?;
+        self.history.push(Operation {
+            kind,
+            before: self.value,
+            after,
+        });
+        self.value = after;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// TEST FIXTURES: TEMPORARY WORKSPACES
+// ============================================================================
+// Many tests need real files on disk: a CSV to parse, a log to search, a
+// config to load. Hand-rolling `std::env::temp_dir().join("my_test")` has two
+// problems:
+//
+// 1. Two tests running in parallel pick the same name and trample each other.
+// 2. If the test panics before its cleanup line, the files leak forever.
+//
+// `TempWorkspace` solves both with RAII: every workspace gets a unique
+// directory, and `Drop` removes it. Because Rust runs destructors while a
+// panic unwinds the stack, cleanup happens even when an assertion fails.
+
+/// Process-wide counter so two workspaces created in the same nanosecond
+/// (easy to hit with parallel tests) still get different names.
+static WORKSPACE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A uniquely named directory under `std::env::temp_dir()` that is removed
+/// recursively when dropped.
+///
+/// Builder methods panic on I/O failure: a fixture that cannot be set up is a
+/// broken test, and a panic with a clear message is the most useful report.
+///
+/// # Examples
+///
+/// ```ignore
+/// use testing_benchmarking::solution::TempWorkspace;
+///
+/// let ws = TempWorkspace::new();
+/// let path = ws.file("notes.txt", "hello");
+/// assert_eq!(ws.read("notes.txt"), "hello");
+/// // `ws` is dropped here and the directory disappears.
+/// ```
+pub struct TempWorkspace {
+    root: PathBuf,
+    // `keep()` flips this to false so Drop leaves the files alone.
+    armed: bool,
+}
+
+impl TempWorkspace {
+    /// Creates a fresh, empty workspace directory.
+    pub fn new() -> Self {
+        // pid + counter + timestamp: unique across threads (counter) and
+        // across concurrently running test binaries (pid).
+        let id = WORKSPACE_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let name = format!("rust-edu-ws-{}-{}-{}", std::process::id(), id, nanos);
+        let root = std::env::temp_dir().join(name);
+
+        // `create_dir` (not `create_dir_all`) fails if the directory already
+        // exists, so we never silently share a directory with someone else.
+        fs::create_dir(&root)
+            .unwrap_or_else(|e| panic!("failed to create workspace {:?}: {}", root, e));
+
+        TempWorkspace { root, armed: true }
+    }
+
+    /// The absolute path of the workspace directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolves a relative name inside the workspace without touching disk.
+    ///
+    /// Absolute paths and `..` components are rejected with
+    /// `ErrorKind::InvalidInput`, so a fixture can never write outside its
+    /// own directory.
+    pub fn path(&self, name: &str) -> io::Result<PathBuf> {
+        let relative = Path::new(name);
+        for component in relative.components() {
+            match component {
+                Component::Normal(_) | Component::CurDir => {}
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("path {:?} escapes the workspace", name),
+                    ));
+                }
+            }
+        }
+        if name.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty path"));
+        }
+        Ok(self.root.join(relative))
+    }
+
+    /// Writes a UTF-8 file, creating parent directories as needed.
+    pub fn file(&self, name: &str, contents: &str) -> PathBuf {
+        self.file_bytes(name, contents.as_bytes())
+    }
+
+    /// Writes a binary file, creating parent directories as needed.
+    pub fn file_bytes(&self, name: &str, contents: &[u8]) -> PathBuf {
+        let path = self.resolve(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .unwrap_or_else(|e| panic!("failed to create {:?}: {}", parent, e));
+        }
+        fs::write(&path, contents).unwrap_or_else(|e| panic!("failed to write {:?}: {}", path, e));
+        path
+    }
+
+    /// Creates a (possibly nested) directory.
+    pub fn dir(&self, name: &str) -> PathBuf {
+        let path = self.resolve(name);
+        fs::create_dir_all(&path).unwrap_or_else(|e| panic!("failed to create {:?}: {}", path, e));
+        path
+    }
+
+    /// Writes a CSV file with a header row, quoting fields per RFC 4180.
+    ///
+    /// Fields containing a comma, a double quote, or a line break are wrapped
+    /// in quotes, and embedded quotes are doubled (`a"b` becomes `"a""b"`).
+    pub fn csv_file<H, R, F>(&self, name: &str, headers: &[H], rows: &[R]) -> PathBuf
+    where
+        H: AsRef<str>,
+        R: AsRef<[F]>,
+        F: AsRef<str>,
+    {
+        let mut out = String::new();
+        push_csv_record(&mut out, headers.iter().map(|h| h.as_ref()));
+        for row in rows {
+            push_csv_record(&mut out, row.as_ref().iter().map(|f| f.as_ref()));
+        }
+        self.file(name, &out)
+    }
+
+    /// Serializes `value` as pretty-printed JSON.
+    pub fn json_file(&self, name: &str, value: &impl Serialize) -> PathBuf {
+        let json = serde_json::to_string_pretty(value)
+            .unwrap_or_else(|e| panic!("failed to serialize {:?}: {}", name, e));
+        self.file(name, &json)
+    }
+
+    /// Creates many files at once; nested paths like `"a/b.txt"` get their
+    /// parent directories automatically.
+    pub fn tree(&self, entries: &[(&str, &str)]) -> Vec<PathBuf> {
+        entries
+            .iter()
+            .map(|(name, contents)| self.file(name, contents))
+            .collect()
+    }
+
+    /// Reads a file back as a string.
+    pub fn read(&self, name: &str) -> String {
+        let path = self.resolve(name);
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {:?}: {}", path, e))
+    }
+
+    /// Returns true if `name` exists inside the workspace.
+    ///
+    /// Names that would escape the workspace simply report `false`.
+    pub fn exists(&self, name: &str) -> bool {
+        self.path(name).map(|p| p.exists()).unwrap_or(false)
+    }
+
+    /// Lists every file and directory in the workspace, recursively, as
+    /// paths relative to the root, sorted for deterministic assertions.
+    pub fn list(&self) -> Vec<PathBuf> {
+        let mut entries = Vec::new();
+        collect_entries(&self.root, &self.root, &mut entries)
+            .unwrap_or_else(|e| panic!("failed to list {:?}: {}", self.root, e));
+        entries.sort();
+        entries
+    }
+
+    /// Disarms cleanup and returns the root path.
+    ///
+    /// Handy when a test fails and you want to inspect its files. The caller
+    /// becomes responsible for deleting the directory.
+    pub fn keep(mut self) -> PathBuf {
+        self.armed = false;
+        self.root.clone()
+    }
+
+    fn resolve(&self, name: &str) -> PathBuf {
+        self.path(name).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Default for TempWorkspace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TempWorkspace {
+    fn drop(&mut self) {
+        // Never panic in Drop: a second panic during unwinding aborts the
+        // whole test binary. Best effort is the right call for cleanup.
+        if self.armed {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+}
+
+fn push_csv_record<'a>(out: &mut String, fields: impl Iterator<Item = &'a str>) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push('\n');
+}
+
+fn collect_entries(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if let Ok(relative) = path.strip_prefix(root) {
+            out.push(relative.to_path_buf());
+        }
+        if path.is_dir() {
+            collect_entries(root, &path, out)?;
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// PROPERTY-BASED TESTING (a tiny proptest)
+// ============================================================================
+// Example-based tests check the cases you thought of. Property-based tests
+// state a rule that must hold for ALL inputs ("add(a, b) == add(b, a)"),
+// then throw hundreds of generated inputs at it. When one fails, the runner
+// SHRINKS it: tries smaller versions of the input that still fail, so you
+// get `[0, -1]` instead of a 37-element vector of random noise.
+//
+// Real projects use the `proptest` or `quickcheck` crates. This module is a
+// dependency-free miniature to show the moving parts.
+
+pub mod proptest_lite {
+    use std::fmt::Debug;
+    use std::ops::RangeInclusive;
+
+    /// Seed used by `check_property`, so every run generates the same cases.
+    pub const DEFAULT_SEED: u64 = 0x5EED_CAFE_F00D_1234;
+
+    /// Upper bound on shrink steps, in case a generator shrinks forever.
+    const MAX_SHRINK_STEPS: usize = 1_000;
+
+    /// Advances `seed` and returns the next pseudo-random number (splitmix64).
+    pub fn next_u64(seed: &mut u64) -> u64 {
+        *seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = *seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A source of random test inputs.
+    ///
+    /// All randomness comes from `seed`, so the same seed always produces
+    /// the same inputs - a failing case can be replayed exactly.
+    pub trait Gen<T> {
+        fn gen(&mut self, seed: &mut u64) -> T;
+
+        /// Smaller candidates to try when `value` fails. Every candidate
+        /// must be something `gen` could have produced.
+        fn shrink(&self, _value: &T) -> Vec<T> {
+            Vec::new()
+        }
+    }
+
+    /// Integers in an inclusive range; see `int_range`.
+    #[derive(Debug, Clone)]
+    pub struct IntRange {
+        lo: i32,
+        hi: i32,
+    }
+
+    /// Generates integers in `range`, shrinking toward 0 (or toward the
+    /// range end nearest 0 if 0 is out of range).
+    pub fn int_range(range: RangeInclusive<i32>) -> IntRange {
+        let (lo, hi) = range.into_inner();
+        assert!(lo <= hi, "empty range");
+        IntRange { lo, hi }
+    }
+
+    impl Gen<i32> for IntRange {
+        fn gen(&mut self, seed: &mut u64) -> i32 {
+            let span = (self.hi as i64 - self.lo as i64 + 1) as u64;
+            (self.lo as i64 + (next_u64(seed) % span) as i64) as i32
+        }
+
+        fn shrink(&self, value: &i32) -> Vec<i32> {
+            let target = 0.clamp(self.lo, self.hi);
+            if *value == target {
+                return Vec::new();
+            }
+            // Jump straight to the target first, then try halving the distance.
+            let halfway = target + (*value - target) / 2;
+            let mut candidates = vec![target];
+            if halfway != target && halfway != *value {
+                candidates.push(halfway);
+            }
+            candidates
+        }
+    }
+
+    /// Vectors of up to `max_len` elements; see `vec_of`.
+    #[derive(Debug, Clone)]
+    pub struct VecOf<G> {
+        element: G,
+        max_len: usize,
+    }
+
+    /// Generates vectors of 0..=`max_len` elements from `element`. Shrinks
+    /// by dropping elements, then by shrinking individual elements.
+    pub fn vec_of<G>(element: G, max_len: usize) -> VecOf<G> {
+        VecOf { element, max_len }
+    }
+
+    impl<T: Clone, G: Gen<T>> Gen<Vec<T>> for VecOf<G> {
+        fn gen(&mut self, seed: &mut u64) -> Vec<T> {
+            let len = (next_u64(seed) % (self.max_len as u64 + 1)) as usize;
+            (0..len).map(|_| self.element.gen(seed)).collect()
+        }
+
+        fn shrink(&self, value: &Vec<T>) -> Vec<Vec<T>> {
+            let mut candidates = Vec::new();
+            for i in 0..value.len() {
+                let mut smaller = value.clone();
+                smaller.remove(i);
+                candidates.push(smaller);
+            }
+            for (i, item) in value.iter().enumerate() {
+                for shrunk in self.element.shrink(item) {
+                    let mut simpler = value.clone();
+                    simpler[i] = shrunk;
+                    candidates.push(simpler);
+                }
+            }
+            candidates
+        }
+    }
+
+    /// Strings drawn from an alphabet; see `string_of`.
+    #[derive(Debug, Clone)]
+    pub struct StringOf {
+        alphabet: Vec<char>,
+        max_len: usize,
+    }
+
+    /// Generates strings of 0..=`max_len` chars taken from `alphabet`.
+    /// Shrinks by dropping characters.
+    pub fn string_of(alphabet: &str, max_len: usize) -> StringOf {
+        let alphabet: Vec<char> = alphabet.chars().collect();
+        assert!(!alphabet.is_empty(), "empty alphabet");
+        StringOf { alphabet, max_len }
+    }
+
+    impl Gen<String> for StringOf {
+        fn gen(&mut self, seed: &mut u64) -> String {
+            let len = (next_u64(seed) % (self.max_len as u64 + 1)) as usize;
+            (0..len)
+                .map(|_| self.alphabet[(next_u64(seed) % self.alphabet.len() as u64) as usize])
+                .collect()
+        }
+
+        fn shrink(&self, value: &String) -> Vec<String> {
+            let chars: Vec<char> = value.chars().collect();
+            (0..chars.len())
+                .map(|skip| {
+                    chars
+                        .iter()
+                        .enumerate()
+                        .filter(|&(i, _)| i != skip)
+                        .map(|(_, c)| c)
+                        .collect()
+                })
+                .collect()
+        }
+    }
+
+    /// Pairs of values from two generators; see `pair`.
+    #[derive(Debug, Clone)]
+    pub struct Pair<A, B> {
+        first: A,
+        second: B,
+    }
+
+    /// Generates `(a, b)` tuples. Shrinks one side at a time.
+    pub fn pair<A, B>(first: A, second: B) -> Pair<A, B> {
+        Pair { first, second }
+    }
+
+    impl<X: Clone, Y: Clone, A: Gen<X>, B: Gen<Y>> Gen<(X, Y)> for Pair<A, B> {
+        fn gen(&mut self, seed: &mut u64) -> (X, Y) {
+            let x = self.first.gen(seed);
+            let y = self.second.gen(seed);
+            (x, y)
+        }
+
+        fn shrink(&self, (x, y): &(X, Y)) -> Vec<(X, Y)> {
+            let lefts = self.first.shrink(x).into_iter().map(|x| (x, y.clone()));
+            let rights = self.second.shrink(y).into_iter().map(|y| (x.clone(), y));
+            lefts.chain(rights).collect()
+        }
+    }
+
+    /// Outcome of `check_property`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum PropertyResult<T> {
+        Passed {
+            cases: usize,
+        },
+        Failed {
+            /// 0-based index of the first failing case.
+            case: usize,
+            /// The input as generated.
+            original: T,
+            /// The smallest failing input shrinking found.
+            shrunk: T,
+            shrink_steps: usize,
+        },
+    }
+
+    impl<T> PropertyResult<T> {
+        pub fn is_passed(&self) -> bool {
+            matches!(self, PropertyResult::Passed { .. })
+        }
+
+        /// The minimal failing input, if the property failed.
+        pub fn counterexample(&self) -> Option<&T> {
+            match self {
+                PropertyResult::Passed { .. } => None,
+                PropertyResult::Failed { shrunk, .. } => Some(shrunk),
+            }
+        }
+    }
+
+    /// Runs `prop` on `cases` generated inputs using `DEFAULT_SEED`.
+    pub fn check_property<T: Clone + Debug>(
+        cases: usize,
+        gen: impl Gen<T>,
+        prop: impl Fn(&T) -> bool,
+    ) -> PropertyResult<T> {
+        check_property_with_seed(DEFAULT_SEED, cases, gen, prop)
+    }
+
+    /// Like `check_property`, with an explicit seed.
+    ///
+    /// Stops at the first failing input and shrinks it greedily: take the
+    /// first shrink candidate that still fails, repeat until none do.
+    pub fn check_property_with_seed<T: Clone + Debug>(
+        seed: u64,
+        cases: usize,
+        mut gen: impl Gen<T>,
+        prop: impl Fn(&T) -> bool,
+    ) -> PropertyResult<T> {
+        let mut seed = seed;
+        for case in 0..cases {
+            let original = gen.gen(&mut seed);
+            if prop(&original) {
+                continue;
+            }
+
+            let mut shrunk = original.clone();
+            let mut shrink_steps = 0;
+            while shrink_steps < MAX_SHRINK_STEPS {
+                match gen.shrink(&shrunk).into_iter().find(|c| !prop(c)) {
+                    Some(smaller) => {
+                        shrunk = smaller;
+                        shrink_steps += 1;
+                    }
+                    None => break,
+                }
+            }
+            return PropertyResult::Failed {
+                case,
+                original,
+                shrunk,
+                shrink_steps,
+            };
+        }
+        PropertyResult::Passed { cases }
+    }
+}
+
+// ============================================================================
+// SNAPSHOT TESTING (golden files)
+// ============================================================================
+// Some outputs are tedious to assert field by field: a rendered report, a
+// pretty-printed AST, a big `Debug` dump. A snapshot test stores the output
+// once in a "golden" file and compares every later run against it. When the
+// output changes on purpose, you re-run with `UPDATE_SNAPSHOTS=1` and review
+// the rewritten files in your diff like any other code change.
+//
+// The `insta` crate is the real-world tool. This module shows the core:
+// files live in `tests/snapshots/<name>.snap`, a mismatch fails with a
+// unified diff, and update mode rewrites instead of failing.
+
+pub mod snapshot {
+    use std::env;
+    use std::fmt::{self, Debug};
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    /// Set this environment variable to `1` to (re)write snapshots instead
+    /// of failing on a mismatch.
+    pub const UPDATE_ENV_VAR: &str = "UPDATE_SNAPSHOTS";
+
+    /// Lines of unchanged context around each change in `render_diff`.
+    const DIFF_CONTEXT: usize = 3;
+
+    /// What `Snapshots::check` did when it succeeded.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SnapshotOutcome {
+        /// The stored snapshot matched.
+        Matched,
+        /// Update mode wrote a snapshot that did not exist yet.
+        Created,
+        /// Update mode replaced a snapshot that differed.
+        Updated,
+    }
+
+    /// Why a snapshot check failed.
+    #[derive(Debug)]
+    pub enum SnapshotError {
+        /// Names must be plain file stems: no separators, no `..`.
+        InvalidName(String),
+        /// There is no stored snapshot and update mode is off.
+        Missing { path: PathBuf },
+        /// The stored snapshot differs; `diff` is from `render_diff`.
+        Mismatch { path: PathBuf, diff: String },
+        /// Reading or writing the snapshot file failed.
+        Io(io::Error),
+    }
+
+    impl fmt::Display for SnapshotError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                SnapshotError::InvalidName(name) => {
+                    write!(f, "invalid snapshot name {:?}", name)
+                }
+                SnapshotError::Missing { path } => write!(
+                    f,
+                    "snapshot {} does not exist; run with {}=1 to create it",
+                    path.display(),
+                    UPDATE_ENV_VAR
+                ),
+                SnapshotError::Mismatch { path, diff } => write!(
+                    f,
+                    "snapshot {} does not match (run with {}=1 to accept):\n{}",
+                    path.display(),
+                    UPDATE_ENV_VAR,
+                    diff
+                ),
+                SnapshotError::Io(err) => write!(f, "snapshot I/O error: {}", err),
+            }
+        }
+    }
+
+    impl std::error::Error for SnapshotError {}
+
+    impl From<io::Error> for SnapshotError {
+        fn from(err: io::Error) -> Self {
+            SnapshotError::Io(err)
+        }
+    }
+
+    /// A snapshot directory plus the update-mode switch.
+    ///
+    /// The free functions `assert_snapshot` and `assert_debug_snapshot` use
+    /// `Snapshots::default()`; tests of the snapshot machinery itself point
+    /// a `Snapshots` at a temporary directory instead.
+    #[derive(Debug, Clone)]
+    pub struct Snapshots {
+        root: PathBuf,
+        update: bool,
+    }
+
+    impl Snapshots {
+        /// Snapshots stored under `root`. Update mode starts from the
+        /// `UPDATE_SNAPSHOTS` environment variable.
+        pub fn new(root: impl Into<PathBuf>) -> Self {
+            Snapshots {
+                root: root.into(),
+                update: env::var(UPDATE_ENV_VAR).is_ok_and(|v| v == "1"),
+            }
+        }
+
+        /// Overrides update mode regardless of the environment.
+        pub fn with_update(mut self, update: bool) -> Self {
+            self.update = update;
+            self
+        }
+
+        pub fn root(&self) -> &Path {
+            &self.root
+        }
+
+        /// Where the snapshot called `name` is stored.
+        pub fn path_for(&self, name: &str) -> Result<PathBuf, SnapshotError> {
+            let valid =
+                !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\']);
+            if !valid {
+                return Err(SnapshotError::InvalidName(name.to_string()));
+            }
+            Ok(self.root.join(format!("{}.snap", name)))
+        }
+
+        /// Compares `actual` with the stored snapshot without panicking.
+        ///
+        /// Line endings and trailing newlines are normalized on both sides,
+        /// so a snapshot file touched by an editor or a Windows checkout
+        /// still matches.
+        pub fn check(&self, name: &str, actual: &str) -> Result<SnapshotOutcome, SnapshotError> {
+            let path = self.path_for(name)?;
+            let actual = normalize(actual);
+
+            let expected = match fs::read_to_string(&path) {
+                Ok(contents) => Some(normalize(&contents)),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+                Err(err) => return Err(err.into()),
+            };
+
+            match expected {
+                Some(expected) if expected == actual => Ok(SnapshotOutcome::Matched),
+                Some(expected) if !self.update => Err(SnapshotError::Mismatch {
+                    path,
+                    diff: render_diff(&expected, &actual),
+                }),
+                None if !self.update => Err(SnapshotError::Missing { path }),
+                existing => {
+                    fs::create_dir_all(&self.root)?;
+                    fs::write(&path, format!("{}\n", actual))?;
+                    Ok(if existing.is_some() {
+                        SnapshotOutcome::Updated
+                    } else {
+                        SnapshotOutcome::Created
+                    })
+                }
+            }
+        }
+
+        /// Panics with a diff unless `actual` matches the stored snapshot.
+        #[track_caller]
+        pub fn assert_snapshot(&self, name: &str, actual: &str) {
+            if let Err(err) = self.check(name, actual) {
+                panic!("{}", err);
+            }
+        }
+
+        /// Snapshots the pretty `{:#?}` form of `value`.
+        #[track_caller]
+        pub fn assert_debug_snapshot(&self, name: &str, value: &impl Debug) {
+            self.assert_snapshot(name, &format!("{:#?}", value));
+        }
+    }
+
+    impl Default for Snapshots {
+        /// `tests/snapshots` under the crate being tested. Cargo sets
+        /// `CARGO_MANIFEST_DIR` when it runs tests; outside cargo we fall
+        /// back to the current directory.
+        fn default() -> Self {
+            let base = env::var_os("CARGO_MANIFEST_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("."));
+            Snapshots::new(base.join("tests").join("snapshots"))
+        }
+    }
+
+    /// Compares `actual` with `tests/snapshots/<name>.snap`.
+    #[track_caller]
+    pub fn assert_snapshot(name: &str, actual: &str) {
+        Snapshots::default().assert_snapshot(name, actual);
+    }
+
+    /// Compares `{:#?}` of `value` with `tests/snapshots/<name>.snap`.
+    #[track_caller]
+    pub fn assert_debug_snapshot(name: &str, value: &impl Debug) {
+        Snapshots::default().assert_debug_snapshot(name, value);
+    }
+
+    fn normalize(text: &str) -> String {
+        text.replace("\r\n", "\n")
+            .trim_end_matches('\n')
+            .to_string()
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum DiffOp {
+        Equal,
+        Delete,
+        Insert,
+    }
+
+    /// A line-based unified diff from `expected` to `actual`, or an empty
+    /// string when they are equal.
+    ///
+    /// Lines are matched with a longest-common-subsequence table, so an
+    /// inserted line shows up as one `+` line instead of shifting every
+    /// line after it. Changes are grouped into `@@` hunks with three lines
+    /// of context; hunks whose context would overlap are merged.
+    pub fn render_diff(expected: &str, actual: &str) -> String {
+        if expected == actual {
+            return String::new();
+        }
+        let old: Vec<&str> = expected.lines().collect();
+        let new: Vec<&str> = actual.lines().collect();
+
+        // lcs[i][j] = length of the LCS of old[i..] and new[j..].
+        let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i][j] = if old[i] == new[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        // Walk the table forwards. Preferring deletions on ties puts the
+        // `-` lines of a replaced block before its `+` lines.
+        let mut ops = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                ops.push((DiffOp::Equal, old[i]));
+                i += 1;
+                j += 1;
+            } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                ops.push((DiffOp::Delete, old[i]));
+                i += 1;
+            } else {
+                ops.push((DiffOp::Insert, new[j]));
+                j += 1;
+            }
+        }
+
+        // Lines that differ only in a trailing newline compare equal.
+        if ops.iter().all(|(op, _)| *op == DiffOp::Equal) {
+            return String::new();
+        }
+
+        let mut out = String::from("--- expected\n+++ actual\n");
+        let mut start = 0;
+        while let Some(first_change) = ops[start..]
+            .iter()
+            .position(|(op, _)| *op != DiffOp::Equal)
+            .map(|p| p + start)
+        {
+            let hunk_start = first_change.saturating_sub(DIFF_CONTEXT);
+
+            // Extend the hunk while the next change is close enough that
+            // the context between them would overlap.
+            let mut last_change = first_change;
+            loop {
+                let next = ops[last_change + 1..]
+                    .iter()
+                    .position(|(op, _)| *op != DiffOp::Equal)
+                    .map(|p| p + last_change + 1);
+                match next {
+                    Some(n) if n - last_change <= 2 * DIFF_CONTEXT + 1 => last_change = n,
+                    _ => break,
+                }
+            }
+            let hunk_end = (last_change + DIFF_CONTEXT + 1).min(ops.len());
+
+            let old_before = ops[..hunk_start]
+                .iter()
+                .filter(|(op, _)| *op != DiffOp::Insert)
+                .count();
+            let new_before = ops[..hunk_start]
+                .iter()
+                .filter(|(op, _)| *op != DiffOp::Delete)
+                .count();
+            let hunk = &ops[hunk_start..hunk_end];
+            let old_len = hunk.iter().filter(|(op, _)| *op != DiffOp::Insert).count();
+            let new_len = hunk.iter().filter(|(op, _)| *op != DiffOp::Delete).count();
+
+            out.push_str(&format!(
+                "@@ -{} +{} @@\n",
+                hunk_range(old_before, old_len),
+                hunk_range(new_before, new_len)
+            ));
+            for (op, line) in hunk {
+                let marker = match op {
+                    DiffOp::Equal => ' ',
+                    DiffOp::Delete => '-',
+                    DiffOp::Insert => '+',
+                };
+                out.push(marker);
+                out.push_str(line);
+                out.push('\n');
+            }
+            start = hunk_end;
+        }
+        out
+    }
+
+    /// `start,len` as unified diffs write it: 1-based, except that an
+    /// empty range names the line before it.
+    fn hunk_range(before: usize, len: usize) -> String {
+        if len == 0 {
+            format!("{},0", before)
+        } else {
+            format!("{},{}", before + 1, len)
+        }
+    }
+}
+
+// ============================================================================
+// FAKE DATA (fixed-seed generators)
+// ============================================================================
+// Tests often need "some realistic-looking input": a list of users, a few
+// sentences, a shuffled deck. Hard-coding it is tedious, and real randomness
+// makes failures impossible to replay. A seeded faker gives both: plenty of
+// varied data, and the same data on every run, every machine.
+//
+// Everything is integer math on a splitmix64 stream (the same generator
+// `proptest_lite` uses), so a seed means the same sequence on any platform.
+// No floats, no `usize`-width-dependent arithmetic.
+
+pub mod fake {
+    use super::proptest_lite::next_u64;
+    use super::OpKind;
+    use std::ops::RangeInclusive;
+
+    const FIRST_NAMES: &[&str] = &[
+        "Ada", "Alan", "Barbara", "Dennis", "Edsger", "Frances", "Grace", "Ken", "Linus",
+        "Margaret", "Niklaus", "Radia", "Sophie", "Tim", "Tony", "Yukihiro",
+    ];
+
+    const LAST_NAMES: &[&str] = &[
+        "Allen",
+        "Hamilton",
+        "Hopper",
+        "Kernighan",
+        "Knuth",
+        "Lamport",
+        "Liskov",
+        "Lovelace",
+        "Perlman",
+        "Ritchie",
+        "Stroustrup",
+        "Thompson",
+        "Torvalds",
+        "Turing",
+        "Wilson",
+        "Wirth",
+    ];
+
+    // Reserved for documentation (RFC 2606): no test email ever reaches
+    // a real inbox.
+    const EMAIL_DOMAINS: &[&str] = &["example.com", "example.org", "example.net"];
+
+    const WORDS: &[&str] = &[
+        "borrow", "cargo", "closure", "crate", "enum", "future", "generic", "iterator", "lifetime",
+        "macro", "module", "pattern", "slice", "struct", "thread", "trait", "unsafe", "vector",
+    ];
+
+    /// A deterministic generator of test data.
+    ///
+    /// Clone it to replay the same values from the current point.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FakeData {
+        state: u64,
+    }
+
+    impl FakeData {
+        /// A generator whose whole output is determined by `seed`.
+        pub fn new(seed: u64) -> Self {
+            FakeData { state: seed }
+        }
+
+        /// The next raw 64-bit value.
+        pub fn next_u64(&mut self) -> u64 {
+            next_u64(&mut self.state)
+        }
+
+        /// A uniformly distributed integer in `range`.
+        ///
+        /// Uses rejection sampling rather than a bare `% span`, which would
+        /// favour small values whenever the span doesn't divide 2^64.
+        ///
+        /// # Panics
+        /// If the range is empty.
+        pub fn int_in(&mut self, range: RangeInclusive<i64>) -> i64 {
+            let (lo, hi) = range.into_inner();
+            assert!(lo <= hi, "empty range");
+            let span = (hi as i128 - lo as i128 + 1) as u128;
+            if span > u64::MAX as u128 {
+                // The whole of i64: every u64 maps to exactly one value.
+                return self.next_u64() as i64;
+            }
+            let span = span as u64;
+            let limit = u64::MAX - u64::MAX % span;
+            loop {
+                let x = self.next_u64();
+                if x < limit {
+                    return (lo as i128 + (x % span) as i128) as i64;
+                }
+            }
+        }
+
+        /// A uniformly chosen element of `slice`.
+        ///
+        /// # Panics
+        /// If the slice is empty.
+        pub fn pick<'a, T>(&mut self, slice: &'a [T]) -> &'a T {
+            assert!(!slice.is_empty(), "cannot pick from an empty slice");
+            &slice[self.index(slice.len())]
+        }
+
+        /// Shuffles `slice` in place (Fisher-Yates); every order is equally
+        /// likely.
+        pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+            for i in (1..slice.len()).rev() {
+                let j = self.index(i + 1);
+                slice.swap(i, j);
+            }
+        }
+
+        /// A "First Last" name.
+        pub fn name(&mut self) -> String {
+            let first = self.pick(FIRST_NAMES);
+            let last = self.pick(LAST_NAMES);
+            format!("{} {}", first, last)
+        }
+
+        /// A lowercase `first.last<n>@domain` address on a reserved domain.
+        pub fn email(&mut self) -> String {
+            let first = self.pick(FIRST_NAMES).to_lowercase();
+            let last = self.pick(LAST_NAMES).to_lowercase();
+            let n = self.int_in(1..=99);
+            let domain = self.pick(EMAIL_DOMAINS);
+            format!("{}.{}{}@{}", first, last, n, domain)
+        }
+
+        /// `words` words, the first capitalized, ending in a period. Zero
+        /// words gives an empty string.
+        pub fn sentence(&mut self, words: usize) -> String {
+            let mut sentence = (0..words)
+                .map(|_| *self.pick(WORDS))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if let Some(first) = sentence.get_mut(..1) {
+                first.make_ascii_uppercase();
+                sentence.push('.');
+            }
+            sentence
+        }
+
+        /// `len` integers from `range`, e.g. inputs for fuzzing `is_prime`.
+        pub fn vec_i32(&mut self, len: usize, range: RangeInclusive<i32>) -> Vec<i32> {
+            let (lo, hi) = range.into_inner();
+            (0..len)
+                .map(|_| self.int_in(lo as i64..=hi as i64) as i32)
+                .collect()
+        }
+
+        /// `len` random `Calculator` operations with small operands, so a
+        /// sequence of them rarely overflows. Never `Reset`.
+        pub fn calculator_ops(&mut self, len: usize) -> Vec<OpKind> {
+            (0..len)
+                .map(|_| {
+                    let n = self.int_in(-100..=100) as i32;
+                    match self.int_in(0..=4) {
+                        0 => OpKind::Add(n),
+                        1 => OpKind::Subtract(n),
+                        2 => OpKind::Multiply(n),
+                        3 => OpKind::Divide(n),
+                        _ => OpKind::Pow(self.int_in(0..=3) as u32),
+                    }
+                })
+                .collect()
+        }
+
+        /// An index below `len`, going through `int_in` so it's unbiased and
+        /// the same on 32- and 64-bit targets.
+        fn index(&mut self, len: usize) -> usize {
+            self.int_in(0..=len as i64 - 1) as usize
+        }
     }
 }
 
@@ -288,38 +1386,38 @@ mod tests {
     #[test]
     fn test_calculator_add() {
         let mut calc = Calculator::new(10);
-        calc.add(5);
+        calc.add(5).unwrap();
         assert_eq!(calc.value, 15);
 
-        calc.add(-3);
+        calc.add(-3).unwrap();
         assert_eq!(calc.value, 12);
     }
 
     #[test]
     fn test_calculator_subtract() {
         let mut calc = Calculator::new(20);
-        calc.subtract(5);
+        calc.subtract(5).unwrap();
         assert_eq!(calc.value, 15);
 
-        calc.subtract(20);
+        calc.subtract(20).unwrap();
         assert_eq!(calc.value, -5);
     }
 
     #[test]
     fn test_calculator_multiply() {
         let mut calc = Calculator::new(5);
-        calc.multiply(3);
+        calc.multiply(3).unwrap();
         assert_eq!(calc.value, 15);
 
-        calc.multiply(0);
+        calc.multiply(0).unwrap();
         assert_eq!(calc.value, 0);
     }
 
     #[test]
     fn test_calculator_reset() {
         let mut calc = Calculator::new(100);
-        calc.add(50);
-        calc.multiply(2);
+        calc.add(50).unwrap();
+        calc.multiply(2).unwrap();
         assert_eq!(calc.value, 300);
 
         calc.reset();
@@ -329,9 +1427,9 @@ mod tests {
     #[test]
     fn test_calculator_chaining() {
         let mut calc = Calculator::new(10);
-        calc.add(5);      // 15
-        calc.multiply(2); // 30
-        calc.subtract(10); // 20
+        calc.add(5).unwrap(); // 15
+        calc.multiply(2).unwrap(); // 30
+        calc.subtract(10).unwrap(); // 20
         assert_eq!(calc.value, 20);
     }
 
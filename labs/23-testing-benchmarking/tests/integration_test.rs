@@ -1,3 +1,10 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use testing_benchmarking::solution::fake::FakeData;
+use testing_benchmarking::solution::proptest_lite::*;
+use testing_benchmarking::solution::snapshot::{
+    self, render_diff, SnapshotError, SnapshotOutcome, Snapshots,
+};
 use testing_benchmarking::solution::*;
 
 // ============================================================================
@@ -173,35 +180,35 @@ fn test_calculator_creation_negative() {
 #[test]
 fn test_calculator_add() {
     let mut calc = Calculator::new(10);
-    calc.add(5);
+    calc.add(5).unwrap();
     assert_eq!(calc.value, 15);
 }
 
 #[test]
 fn test_calculator_subtract() {
     let mut calc = Calculator::new(20);
-    calc.subtract(5);
+    calc.subtract(5).unwrap();
     assert_eq!(calc.value, 15);
 }
 
 #[test]
 fn test_calculator_multiply() {
     let mut calc = Calculator::new(5);
-    calc.multiply(3);
+    calc.multiply(3).unwrap();
     assert_eq!(calc.value, 15);
 }
 
 #[test]
 fn test_calculator_multiply_by_zero() {
     let mut calc = Calculator::new(100);
-    calc.multiply(0);
+    calc.multiply(0).unwrap();
     assert_eq!(calc.value, 0);
 }
 
 #[test]
 fn test_calculator_reset() {
     let mut calc = Calculator::new(100);
-    calc.add(50);
+    calc.add(50).unwrap();
     calc.reset();
     assert_eq!(calc.value, 0);
 }
@@ -209,19 +216,96 @@ fn test_calculator_reset() {
 #[test]
 fn test_calculator_chained_operations() {
     let mut calc = Calculator::new(10);
-    calc.add(5);       // 15
-    calc.multiply(2);  // 30
-    calc.subtract(10); // 20
+    calc.add(5).unwrap(); // 15
+    calc.multiply(2).unwrap(); // 30
+    calc.subtract(10).unwrap(); // 20
     assert_eq!(calc.value, 20);
 }
 
 #[test]
 fn test_calculator_negative_result() {
     let mut calc = Calculator::new(5);
-    calc.subtract(10);
+    calc.subtract(10).unwrap();
     assert_eq!(calc.value, -5);
 }
 
+#[test]
+fn test_calculator_overflow_leaves_value_intact() {
+    let mut calc = Calculator::new(i32::MAX - 1);
+    assert_eq!(calc.add(5), Err(CalcError::Overflow));
+    assert_eq!(calc.value, i32::MAX - 1);
+
+    let mut calc = Calculator::new(i32::MIN);
+    assert_eq!(calc.subtract(1), Err(CalcError::Overflow));
+    assert_eq!(calc.multiply(2), Err(CalcError::Overflow));
+    assert_eq!(calc.divide(-1), Err(CalcError::Overflow));
+    assert_eq!(calc.value, i32::MIN);
+    assert!(
+        calc.history().is_empty(),
+        "failed operations are not recorded"
+    );
+}
+
+#[test]
+fn test_calculator_divide() {
+    let mut calc = Calculator::new(7);
+    calc.divide(2).unwrap();
+    assert_eq!(calc.value, 3);
+
+    assert_eq!(calc.divide(0), Err(CalcError::DivisionByZero));
+    assert_eq!(calc.value, 3);
+    assert_eq!(
+        CalcError::DivisionByZero.to_string(),
+        "cannot divide by zero"
+    );
+}
+
+#[test]
+fn test_calculator_pow() {
+    let mut calc = Calculator::new(3);
+    calc.pow(4).unwrap();
+    assert_eq!(calc.value, 81);
+
+    calc.pow(0).unwrap();
+    assert_eq!(calc.value, 1);
+
+    let mut calc = Calculator::new(0);
+    calc.pow(0).unwrap();
+    assert_eq!(calc.value, 1);
+
+    let mut calc = Calculator::new(2);
+    assert_eq!(calc.pow(31), Err(CalcError::Overflow));
+    assert_eq!(calc.value, 2);
+}
+
+#[test]
+fn test_calculator_undo_chain() {
+    let mut calc = Calculator::new(10);
+    calc.add(5).unwrap(); // 15
+    calc.multiply(0).unwrap(); // 0
+    calc.subtract(4).unwrap(); // -4
+    calc.pow(2).unwrap(); // 16
+    calc.divide(3).unwrap(); // 5
+    calc.reset(); // 0
+    assert_eq!(calc.history().len(), 6);
+    assert_eq!(
+        calc.history()[1],
+        Operation {
+            kind: OpKind::Multiply(0),
+            before: 15,
+            after: 0
+        }
+    );
+
+    let expected_after_each_undo = [5, 16, -4, 0, 15, 10];
+    for expected in expected_after_each_undo {
+        assert!(calc.undo().is_some());
+        assert_eq!(calc.value, expected);
+    }
+    assert_eq!(calc.undo(), None);
+    assert_eq!(calc.value, 10);
+}
+
 // ============================================================================
 // PROPERTY-BASED TESTS
 // ============================================================================
@@ -258,3 +342,530 @@ fn test_multiply_identity() {
     assert_eq!(multiply(42, 1), 42);
     assert_eq!(multiply(1, 42), 42);
 }
+
+// ============================================================================
+// TESTS: TEMP WORKSPACE FIXTURES
+// ============================================================================
+
+#[test]
+fn test_workspace_removed_on_drop() {
+    let root = {
+        let ws = TempWorkspace::new();
+        ws.file("a.txt", "hello");
+        assert!(ws.root().exists());
+        ws.root().to_path_buf()
+    };
+    assert!(!root.exists(), "workspace should be removed on drop");
+}
+
+#[test]
+fn test_workspace_removed_after_panic() {
+    let mut root: Option<PathBuf> = None;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let ws = TempWorkspace::new();
+        root = Some(ws.root().to_path_buf());
+        ws.file("data/log.txt", "partial");
+        panic!("simulated test failure");
+    }));
+
+    assert!(result.is_err());
+    let root = root.expect("closure should record the root before panicking");
+    assert!(
+        !root.exists(),
+        "workspace should be removed while unwinding"
+    );
+}
+
+#[test]
+fn test_workspace_file_read_and_exists() {
+    let ws = TempWorkspace::new();
+    let path = ws.file("notes.txt", "line one\nline two");
+
+    assert_eq!(path, ws.root().join("notes.txt"));
+    assert!(ws.exists("notes.txt"));
+    assert!(!ws.exists("missing.txt"));
+    assert_eq!(ws.read("notes.txt"), "line one\nline two");
+}
+
+#[test]
+fn test_workspace_file_bytes_and_dir() {
+    let ws = TempWorkspace::new();
+    let bin = ws.file_bytes("blob.bin", &[0, 159, 146, 150]);
+    let dir = ws.dir("nested/empty");
+
+    assert_eq!(std::fs::read(bin).unwrap(), vec![0, 159, 146, 150]);
+    assert!(dir.is_dir());
+}
+
+#[test]
+fn test_workspace_nested_tree() {
+    let ws = TempWorkspace::new();
+    let paths = ws.tree(&[("a/b.txt", "bee"), ("a/c/d.txt", "dee"), ("top.txt", "top")]);
+
+    assert_eq!(paths.len(), 3);
+    assert_eq!(ws.read("a/c/d.txt"), "dee");
+    assert_eq!(
+        ws.list(),
+        vec![
+            PathBuf::from("a"),
+            PathBuf::from("a/b.txt"),
+            PathBuf::from("a/c"),
+            PathBuf::from("a/c/d.txt"),
+            PathBuf::from("top.txt"),
+        ]
+    );
+}
+
+#[test]
+fn test_workspace_csv_quoting() {
+    let ws = TempWorkspace::new();
+    ws.csv_file(
+        "people.csv",
+        &["name", "note"],
+        &[["Ada", "plain"], ["Smith, J", "said \"hi\""]],
+    );
+
+    assert_eq!(
+        ws.read("people.csv"),
+        "name,note\nAda,plain\n\"Smith, J\",\"said \"\"hi\"\"\"\n"
+    );
+}
+
+#[test]
+fn test_workspace_json_file() {
+    #[derive(serde::Serialize)]
+    struct Config {
+        name: String,
+        retries: u32,
+    }
+
+    let ws = TempWorkspace::new();
+    ws.json_file(
+        "config.json",
+        &Config {
+            name: "lab".to_string(),
+            retries: 3,
+        },
+    );
+
+    let parsed: serde_json::Value = serde_json::from_str(&ws.read("config.json")).unwrap();
+    assert_eq!(parsed["name"], "lab");
+    assert_eq!(parsed["retries"], 3);
+}
+
+#[test]
+fn test_workspace_rejects_traversal() {
+    let ws = TempWorkspace::new();
+
+    let err = ws.path("../escape.txt").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(ws.path("a/../../escape.txt").is_err());
+    assert!(ws.path("/etc/passwd").is_err());
+    assert!(!ws.exists("../anything"));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        ws.file("../escape.txt", "nope");
+    }));
+    assert!(
+        result.is_err(),
+        "file() must refuse to write outside the workspace"
+    );
+    assert!(!ws.root().parent().unwrap().join("escape.txt").exists());
+}
+
+#[test]
+fn test_workspace_keep_disarms_cleanup() {
+    let ws = TempWorkspace::new();
+    ws.file("debug.log", "inspect me");
+    let root = ws.keep();
+
+    assert!(
+        root.join("debug.log").exists(),
+        "kept workspace should survive drop"
+    );
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(!root.exists());
+}
+
+#[test]
+fn test_concurrent_workspaces_do_not_collide() {
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            std::thread::spawn(move || {
+                let ws = TempWorkspace::new();
+                ws.file("id.txt", &i.to_string());
+                assert_eq!(ws.read("id.txt"), i.to_string());
+                ws.root().to_path_buf()
+            })
+        })
+        .collect();
+
+    let mut roots: Vec<PathBuf> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    roots.sort();
+    roots.dedup();
+    assert_eq!(roots.len(), 8, "every workspace needs a unique directory");
+}
+
+// ============================================================================
+// TESTS: PROPERTIES OF THE LIBRARY FUNCTIONS
+// ============================================================================
+
+#[test]
+fn prop_add_is_commutative() {
+    let small = || int_range(-1_000_000..=1_000_000);
+    let result = check_property(500, pair(small(), small()), |&(a, b)| {
+        add(a, b) == add(b, a)
+    });
+    assert!(result.is_passed(), "{:?}", result);
+}
+
+#[test]
+fn prop_fibonacci_recurrence() {
+    // fibonacci(93) overflows u64, so keep n + 2 <= 92
+    let result = check_property(300, int_range(0..=90), |&n| {
+        let n = n as u32;
+        fibonacci(n + 2) == fibonacci(n + 1) + fibonacci(n)
+    });
+    assert!(result.is_passed(), "{:?}", result);
+}
+
+#[test]
+fn prop_is_prime_matches_trial_division() {
+    fn trial_division(n: u32) -> bool {
+        n >= 2 && (2..n).all(|d| n % d != 0)
+    }
+    let result = check_property(500, int_range(0..=10_000), |&n| {
+        is_prime(n as u32) == trial_division(n as u32)
+    });
+    assert!(result.is_passed(), "{:?}", result);
+}
+
+// ============================================================================
+// TESTS: THE PROPERTY RUNNER ITSELF
+// ============================================================================
+
+#[test]
+fn test_false_int_property_shrinks_within_bounds() {
+    let result = check_property(1_000, int_range(-5_000..=5_000), |&x| x < 1_000);
+
+    match result {
+        PropertyResult::Failed {
+            original, shrunk, ..
+        } => {
+            assert!(original >= 1_000);
+            // Halving toward 0 stops once half the value would pass
+            assert!((1_000..2_000).contains(&shrunk), "shrunk to {}", shrunk);
+            assert!(shrunk <= original);
+        }
+        PropertyResult::Passed { .. } => panic!("property should fail"),
+    }
+}
+
+#[test]
+fn test_false_vec_property_shrinks_to_local_minimum() {
+    let gen = vec_of(int_range(0..=50), 20);
+    let sum_below_100 = |v: &Vec<i32>| v.iter().sum::<i32>() < 100;
+    let result = check_property(500, gen.clone(), sum_below_100);
+
+    let shrunk = result.counterexample().expect("property should fail");
+    assert!(!sum_below_100(shrunk));
+    assert!(shrunk.iter().all(|x| (0..=50).contains(x)));
+    // No further shrink candidate still fails
+    assert!(gen.shrink(shrunk).iter().all(sum_below_100));
+}
+
+#[test]
+fn test_false_string_property_shrinks_to_single_char() {
+    let result = check_property(200, string_of("xyz", 10), |s| !s.contains('z'));
+    assert_eq!(result.counterexample(), Some(&"z".to_string()));
+}
+
+#[test]
+fn test_true_property_passes_deterministically() {
+    let run = || check_property(200, vec_of(int_range(-10..=10), 8), |v| v.len() <= 8);
+    assert_eq!(run(), PropertyResult::Passed { cases: 200 });
+    assert_eq!(run(), run());
+
+    // Same seed, same inputs
+    let mut gen = string_of("abc", 5);
+    let (mut seed_a, mut seed_b) = (42, 42);
+    let a: Vec<String> = (0..10).map(|_| gen.gen(&mut seed_a)).collect();
+    let b: Vec<String> = (0..10).map(|_| gen.gen(&mut seed_b)).collect();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_int_range_stays_in_bounds() {
+    let mut gen = int_range(-3..=3);
+    let mut seed = DEFAULT_SEED;
+    let values: Vec<i32> = (0..200).map(|_| gen.gen(&mut seed)).collect();
+    assert!(values.iter().all(|v| (-3..=3).contains(v)));
+    assert!(values.contains(&-3) && values.contains(&3));
+
+    // 0 is out of range, so shrinking heads for the nearest end instead
+    assert_eq!(int_range(10..=20).shrink(&18), vec![10, 14]);
+    assert!(int_range(10..=20).shrink(&10).is_empty());
+}
+
+// ============================================================================
+// TESTS: SNAPSHOTS
+// ============================================================================
+
+#[test]
+fn test_render_diff_single_change() {
+    assert_eq!(
+        render_diff("a\nb\nc", "a\nB\nc"),
+        "--- expected\n+++ actual\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+    );
+}
+
+#[test]
+fn test_render_diff_insert_and_delete() {
+    // An inserted line doesn't shift everything after it
+    assert_eq!(
+        render_diff("a\nc", "a\nb\nc"),
+        "--- expected\n+++ actual\n@@ -1,2 +1,3 @@\n a\n+b\n c\n"
+    );
+    assert_eq!(
+        render_diff("a\nb", ""),
+        "--- expected\n+++ actual\n@@ -1,2 +0,0 @@\n-a\n-b\n"
+    );
+}
+
+#[test]
+fn test_render_diff_separate_hunks() {
+    let expected: Vec<String> = (1..=20).map(|n| n.to_string()).collect();
+    let mut actual = expected.clone();
+    actual[1] = "two".to_string();
+    actual[17] = "eighteen".to_string();
+
+    let diff = render_diff(&expected.join("\n"), &actual.join("\n"));
+    assert_eq!(
+        diff,
+        "--- expected\n+++ actual\n\
+         @@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n\
+         @@ -15,6 +15,6 @@\n 15\n 16\n 17\n-18\n+eighteen\n 19\n 20\n"
+    );
+}
+
+#[test]
+fn test_render_diff_identical_is_empty() {
+    assert_eq!(render_diff("same\ntext", "same\ntext"), "");
+    assert_eq!(render_diff("", ""), "");
+    assert_eq!(render_diff("trailing\n", "trailing"), "");
+}
+
+#[test]
+fn test_snapshot_round_trip() {
+    let ws = TempWorkspace::new();
+    let snapshots = Snapshots::new(ws.root().join("snapshots")).with_update(true);
+
+    assert_eq!(
+        snapshots.check("greeting", "hello\nworld").unwrap(),
+        SnapshotOutcome::Created
+    );
+    assert_eq!(ws.read("snapshots/greeting.snap"), "hello\nworld\n");
+
+    let snapshots = snapshots.with_update(false);
+    assert_eq!(
+        snapshots.check("greeting", "hello\r\nworld\n").unwrap(),
+        SnapshotOutcome::Matched
+    );
+    snapshots.assert_snapshot("greeting", "hello\nworld");
+}
+
+#[test]
+fn test_snapshot_missing_and_mismatch() {
+    let ws = TempWorkspace::new();
+    let snapshots = Snapshots::new(ws.root()).with_update(false);
+
+    match snapshots.check("absent", "anything") {
+        Err(SnapshotError::Missing { path }) => assert_eq!(path, ws.root().join("absent.snap")),
+        other => panic!("expected Missing, got {:?}", other),
+    }
+    assert!(
+        !ws.exists("absent.snap"),
+        "check must not write outside update mode"
+    );
+
+    ws.file("report.snap", "total: 3\nstatus: ok\n");
+    let err = snapshots
+        .check("report", "total: 4\nstatus: ok")
+        .unwrap_err();
+    match &err {
+        SnapshotError::Mismatch { diff, .. } => {
+            assert_eq!(
+                diff,
+                "--- expected\n+++ actual\n@@ -1,2 +1,2 @@\n-total: 3\n+total: 4\n status: ok\n"
+            );
+        }
+        other => panic!("expected Mismatch, got {:?}", other),
+    }
+    assert!(err.to_string().contains("+total: 4"));
+    assert_eq!(ws.read("report.snap"), "total: 3\nstatus: ok\n");
+
+    let result = panic::catch_unwind(|| snapshots.assert_snapshot("report", "total: 4"));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_snapshot_update_mode_rewrites() {
+    let ws = TempWorkspace::new();
+    ws.file("config.snap", "old value\n");
+    let snapshots = Snapshots::new(ws.root()).with_update(true);
+
+    assert_eq!(
+        snapshots.check("config", "new value").unwrap(),
+        SnapshotOutcome::Updated
+    );
+    assert_eq!(ws.read("config.snap"), "new value\n");
+    assert_eq!(
+        snapshots.check("config", "new value").unwrap(),
+        SnapshotOutcome::Matched
+    );
+}
+
+#[test]
+fn test_snapshot_rejects_path_like_names() {
+    let snapshots = Snapshots::new("unused").with_update(true);
+    for name in ["", "..", "nested/name", "..\\escape"] {
+        assert!(
+            matches!(
+                snapshots.check(name, "x"),
+                Err(SnapshotError::InvalidName(_))
+            ),
+            "{:?} should be rejected",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_debug_snapshot_of_fibonacci() {
+    // Compared against the committed tests/snapshots/fibonacci_first_10.snap
+    let values: Vec<u64> = (0..10).map(fibonacci).collect();
+    snapshot::assert_debug_snapshot("fibonacci_first_10", &values);
+}
+
+// ============================================================================
+// TESTS: FAKE DATA
+// ============================================================================
+
+#[test]
+fn test_fake_data_is_reproducible() {
+    let sample = |seed| {
+        let mut fake = FakeData::new(seed);
+        (
+            fake.name(),
+            fake.email(),
+            fake.sentence(6),
+            fake.int_in(-1_000..=1_000),
+            fake.vec_i32(5, 0..=9),
+        )
+    };
+    assert_eq!(sample(42), sample(42));
+    assert_ne!(sample(42), sample(43));
+
+    // Pinned so a change to the generator can't slip by unnoticed.
+    let mut fake = FakeData::new(7);
+    let draws: Vec<i64> = (0..5).map(|_| fake.int_in(0..=999)).collect();
+    assert_eq!(draws, [487, 804, 346, 203, 674]);
+}
+
+#[test]
+fn test_fake_int_in_hits_every_bucket_and_stays_in_range() {
+    let mut fake = FakeData::new(1);
+    let mut hits = [0usize; 10];
+    for _ in 0..10_000 {
+        let n = fake.int_in(-5..=4);
+        assert!((-5..=4).contains(&n));
+        hits[(n + 5) as usize] += 1;
+    }
+    // ~1000 each; a biased or broken generator would leave gaps.
+    assert!(hits.iter().all(|&h| (800..1200).contains(&h)), "{:?}", hits);
+
+    assert_eq!(fake.int_in(3..=3), 3);
+    fake.int_in(i64::MIN..=i64::MAX);
+}
+
+#[test]
+fn test_fake_shuffle_is_a_permutation() {
+    let mut fake = FakeData::new(9);
+    let original: Vec<u32> = (0..100).collect();
+    let mut shuffled = original.clone();
+    fake.shuffle(&mut shuffled);
+    assert_ne!(shuffled, original);
+    let mut sorted = shuffled.clone();
+    sorted.sort();
+    assert_eq!(sorted, original);
+
+    let mut empty: [u8; 0] = [];
+    fake.shuffle(&mut empty);
+}
+
+#[test]
+fn test_fake_pick_and_sentence() {
+    let mut fake = FakeData::new(3);
+    let colors = ["red", "green", "blue"];
+    for _ in 0..50 {
+        assert!(colors.contains(fake.pick(&colors)));
+    }
+
+    assert_eq!(fake.sentence(0), "");
+    let sentence = fake.sentence(5);
+    assert_eq!(sentence.split(' ').count(), 5);
+    assert!(sentence.starts_with(|c: char| c.is_ascii_uppercase()));
+    assert!(sentence.ends_with('.'));
+}
+
+#[test]
+fn test_fake_emails_are_well_formed() {
+    let mut fake = FakeData::new(11);
+    for _ in 0..200 {
+        let email = fake.email();
+        let (local, domain) = email.split_once('@').expect("has an @");
+        assert!(!local.is_empty() && !local.starts_with('.') && !local.ends_with('.'));
+        assert!(local
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '.'));
+        assert!(!domain.contains('@'));
+        assert!(domain.starts_with("example."), "{}", email);
+    }
+}
+
+#[test]
+fn fuzz_is_prime_against_trial_division() {
+    fn trial_division(n: u32) -> bool {
+        n >= 2 && (2..n).all(|d| n % d != 0)
+    }
+    let mut fake = FakeData::new(2024);
+    for n in fake.vec_i32(2_000, 0..=20_000) {
+        assert_eq!(is_prime(n as u32), trial_division(n as u32), "n = {}", n);
+    }
+}
+
+#[test]
+fn fuzz_calculator_undo_restores_every_value() {
+    let mut fake = FakeData::new(5);
+    let mut calc = Calculator::new(1);
+    let mut values = vec![calc.value];
+    for op in fake.calculator_ops(300) {
+        let result = match op {
+            OpKind::Add(n) => calc.add(n),
+            OpKind::Subtract(n) => calc.subtract(n),
+            OpKind::Multiply(n) => calc.multiply(n),
+            OpKind::Divide(n) => calc.divide(n),
+            OpKind::Pow(exp) => calc.pow(exp),
+            OpKind::Reset => unreachable!("calculator_ops never resets"),
+        };
+        if result.is_ok() {
+            values.push(calc.value);
+        }
+    }
+    values.pop();
+    while let Some(expected) = values.pop() {
+        calc.undo().unwrap();
+        assert_eq!(calc.value, expected);
+    }
+    assert!(calc.undo().is_none());
+}
@@ -33,6 +33,7 @@ pub fn is_64_bit() -> bool {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct User {
     pub id: u32,
     pub name: String,
@@ -47,10 +48,12 @@ impl User {
     }
 
     pub fn deactivate(&mut self) {
+        // TODO: Clear `active`; if logging_enabled(), log an Info event.
         todo!("Deactivate user")
     }
 
     pub fn activate(&mut self) {
+        // TODO: Set `active`; if logging_enabled(), log an Info event.
         todo!("Activate user")
     }
 
@@ -86,20 +89,154 @@ pub fn count_enabled_features() -> usize {
     todo!("Count enabled features")
 }
 
-#[derive(Debug, Clone)]
+pub trait Serializer {
+    fn format(&self) -> &'static str;
+    fn serialize_user(&self, u: &User) -> Result<String, String>;
+}
+
+#[cfg(feature = "json")]
+pub struct JsonSerializer;
+
+#[cfg(feature = "json")]
+impl Serializer for JsonSerializer {
+    fn format(&self) -> &'static str {
+        "json"
+    }
+
+    fn serialize_user(&self, _u: &User) -> Result<String, String> {
+        // TODO: Use serde_json (User derives Serialize under this feature).
+        todo!("Serialize user to JSON")
+    }
+}
+
+#[cfg(feature = "xml")]
+pub struct XmlSerializer;
+
+#[cfg(feature = "xml")]
+impl Serializer for XmlSerializer {
+    fn format(&self) -> &'static str {
+        "xml"
+    }
+
+    fn serialize_user(&self, _u: &User) -> Result<String, String> {
+        // TODO: Write a small XML document, escaping &, <, >, " and '.
+        todo!("Serialize user to XML")
+    }
+}
+
+pub struct SerializerRegistry;
+
+impl SerializerRegistry {
+    pub fn available() -> Vec<Box<dyn Serializer>> {
+        // TODO: Push each serializer behind its own #[cfg(feature = "...")].
+        todo!("Collect compiled-in serializers")
+    }
+
+    pub fn find(_format: &str) -> Option<Box<dyn Serializer>> {
+        todo!("Find a serializer by format name")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub message: String,
+}
+
+pub trait Logger {
+    fn log(&self, level: LogLevel, message: &str);
+}
+
+#[derive(Debug, Default)]
+pub struct NullLogger;
+
+impl Logger for NullLogger {
+    #[inline]
+    fn log(&self, _level: LogLevel, _message: &str) {
+        // TODO: Nothing at all - this is what "zero overhead" compiles to.
+        todo!("Discard the event")
+    }
+}
+
+#[cfg(feature = "logging")]
+#[derive(Debug, Default)]
+pub struct CaptureLogger;
+
+#[cfg(feature = "logging")]
+impl Logger for CaptureLogger {
+    fn log(&self, _level: LogLevel, _message: &str) {
+        // TODO: Push a LogRecord onto a thread_local! RefCell<Vec<LogRecord>>
+        // and forward the message to log::log!.
+        todo!("Capture the event")
+    }
+}
+
+#[cfg(feature = "logging")]
+pub type DefaultLogger = CaptureLogger;
+
+#[cfg(not(feature = "logging"))]
+pub type DefaultLogger = NullLogger;
+
+pub const fn logging_enabled() -> bool {
+    // TODO: cfg!(feature = "logging") - evaluated in this crate, unlike a
+    // macro_rules! body, which would check the caller's features.
+    // (todo! can't be used in a const fn; panic! with a plain string can.)
+    panic!("not yet implemented: is the logging feature on?")
+}
+
+pub fn log_event(_level: LogLevel, _message: &str) {
+    // TODO: Forward to DefaultLogger.
+    todo!("Log one event")
+}
+
+pub fn take_logs() -> Vec<LogRecord> {
+    // TODO: Drain this thread's records; an empty Vec without the feature.
+    todo!("Take captured events")
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct BuildInfo {
-    pub platform: &'static str,
-    pub arch: &'static str,
+    pub platform: String,
+    pub arch: String,
     pub pointer_width: usize,
     pub debug: bool,
-    pub build_mode: &'static str,
+    pub build_mode: String,
+    pub crate_name: String,
+    pub crate_version: String,
+    pub rustc_channel_guess: String,
+    pub git_hash: Option<String>,
 }
 
 impl BuildInfo {
     pub fn collect() -> Self {
-        // TODO: Aggregate compile-time build info.
+        // TODO: Aggregate compile-time build info. Use env!("CARGO_PKG_NAME"),
+        // env!("CARGO_PKG_VERSION") and option_env!("GIT_HASH"). Log a Debug
+        // event when logging_enabled().
         todo!("Collect build info")
     }
+
+    pub fn to_json(&self) -> String {
+        // TODO: Write a flat JSON object by hand, escaping string values.
+        todo!("Serialize BuildInfo to JSON")
+    }
+
+    pub fn from_json(_json: &str) -> Result<Self, String> {
+        // TODO: Parse a flat JSON object of strings, numbers, bools and null.
+        todo!("Parse BuildInfo from JSON")
+    }
+
+    pub fn diff(&self, _other: &BuildInfo) -> Vec<String> {
+        // TODO: One "field: ours -> theirs" entry per differing field.
+        todo!("List differing fields")
+    }
 }
 
 impl std::fmt::Display for BuildInfo {
@@ -108,6 +245,11 @@ impl std::fmt::Display for BuildInfo {
     }
 }
 
+pub fn guess_rustc_channel() -> &'static str {
+    // TODO: Inspect option_env!("RUSTUP_TOOLCHAIN"); fall back to "unknown".
+    todo!("Guess rustc channel")
+}
+
 pub fn get_endianness() -> &'static str {
     // TODO: Return little-endian or big-endian.
     todo!("Detect endianness")
@@ -118,5 +260,56 @@ pub fn get_os_family() -> &'static str {
     todo!("Detect OS family")
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CpuFeatures {
+    pub sse2: bool,
+    pub avx2: bool,
+    pub neon: bool,
+}
+
+impl CpuFeatures {
+    pub fn detect() -> Self {
+        // TODO: Gate per-arch checks with #[cfg(target_arch = "...")]:
+        // is_x86_feature_detected! on x86/x86_64,
+        // std::arch::is_aarch64_feature_detected! on aarch64, default elsewhere.
+        todo!("Detect CPU features")
+    }
+
+    pub fn has_wide_registers(&self) -> bool {
+        todo!("Any vector extension present?")
+    }
+
+    pub fn summarize(&self) -> String {
+        // TODO: "sse2=yes avx2=no neon=no threads=N"
+        todo!("Summarize CPU features")
+    }
+}
+
+pub fn recommended_parallelism() -> usize {
+    // TODO: std::thread::available_parallelism, falling back to 1.
+    todo!("Recommended thread count")
+}
+
+pub fn sum_bytes_simple(data: &[u8]) -> u64 {
+    let _ = data;
+    todo!("Sum bytes one at a time")
+}
+
+pub fn sum_bytes_chunked(data: &[u8]) -> u64 {
+    // TODO: chunks_exact(8) into several accumulators, then add the remainder.
+    let _ = data;
+    todo!("Sum bytes in chunks")
+}
+
+pub fn fast_sum_with(features: &CpuFeatures, data: &[u8]) -> u64 {
+    let _ = (features, data);
+    todo!("Pick a summing loop from the features")
+}
+
+pub fn fast_sum(data: &[u8]) -> u64 {
+    let _ = data;
+    todo!("Sum with the detected CPU features")
+}
+
 #[doc(hidden)]
 pub mod solution;
@@ -3,6 +3,10 @@
 use cargo_features::solution;
 
 fn main() {
+    // With "logging" on, RUST_LOG=debug also prints events to stderr.
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
     println!("=== Cargo Features Demo ===\n");
     println!("platform: {}", solution::get_platform_name());
     println!("arch: {}", solution::get_target_arch());
@@ -12,6 +16,47 @@ fn main() {
     println!("endianness: {}", solution::get_endianness());
     println!("os family: {}", solution::get_os_family());
 
+    let cpu = solution::CpuFeatures::detect();
+    println!("cpu: {}", cpu.summarize());
+    let data: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+    println!(
+        "fast_sum over {} bytes = {} ({} path)",
+        data.len(),
+        solution::fast_sum(&data),
+        if cpu.has_wide_registers() {
+            "chunked"
+        } else {
+            "simple"
+        }
+    );
+
     let info = solution::BuildInfo::collect();
     println!("build info: {}", info);
+    println!(
+        "crate: {} v{} (rustc channel: {}, git: {})",
+        info.crate_name,
+        info.crate_version,
+        info.rustc_channel_guess,
+        info.git_hash.as_deref().unwrap_or("unknown")
+    );
+    println!("as json: {}", info.to_json());
+
+    let mut user = solution::User::new(1, "Alice", "alice@example.com");
+    let serializers = solution::SerializerRegistry::available();
+    if serializers.is_empty() {
+        println!("\nno serializers compiled in");
+    }
+    for serializer in serializers {
+        match serializer.serialize_user(&user) {
+            Ok(out) => println!("\n[{}] {}", serializer.format(), out),
+            Err(e) => println!("\n[{}] error: {}", serializer.format(), e),
+        }
+    }
+
+    user.deactivate();
+    user.activate();
+    println!("\nlogging enabled: {}", solution::logging_enabled());
+    for record in solution::take_logs() {
+        println!("  [{:?}] {}", record.level, record.message);
+    }
 }
@@ -9,7 +9,9 @@
 // - cfg!(debug_assertions) for build profile detection
 // - #[cfg(target_arch = "...")] for architecture detection
 // - #[cfg(feature = "...")] for feature-gated code
+// - Runtime CPU feature detection with a portable fallback
 // - Conditional struct derives and implementations
+// - A feature-gated logging facade that compiles to nothing when off
 
 // ============================================================================
 // PLATFORM DETECTION
@@ -158,7 +160,11 @@ pub fn is_64_bit() -> bool {
 ///                                │ (18 bytes + cap)      │
 ///                                └──────────────────────┘
 /// ```
+///
+/// With the "json" feature on, `cfg_attr` adds the serde derives. Without
+/// it, serde is not even a dependency and the attribute disappears.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct User {
     pub id: u32,
     pub name: String,
@@ -183,11 +189,17 @@ impl User {
     /// Deactivates this user account.
     pub fn deactivate(&mut self) {
         self.active = false;
+        if logging_enabled() {
+            log_event(LogLevel::Info, &format!("user {} deactivated", self.id));
+        }
     }
 
     /// Activates this user account.
     pub fn activate(&mut self) {
         self.active = true;
+        if logging_enabled() {
+            log_event(LogLevel::Info, &format!("user {} activated", self.id));
+        }
     }
 
     /// Returns a display-friendly summary of the user.
@@ -253,6 +265,229 @@ pub fn count_enabled_features() -> usize {
         .count()
 }
 
+// ============================================================================
+// FEATURE-GATED SERIALIZERS
+// ============================================================================
+
+/// A pluggable output format for users.
+///
+/// The trait itself is always compiled. Only the implementations are
+/// feature-gated, so callers can code against `dyn Serializer` without
+/// knowing which formats this build includes.
+pub trait Serializer {
+    /// Short format name, matching the Cargo feature that enables it.
+    fn format(&self) -> &'static str;
+
+    /// Renders a user in this format.
+    fn serialize_user(&self, u: &User) -> Result<String, String>;
+}
+
+/// JSON output backed by serde_json. Only exists with the "json" feature.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonSerializer;
+
+#[cfg(feature = "json")]
+impl Serializer for JsonSerializer {
+    fn format(&self) -> &'static str {
+        "json"
+    }
+
+    fn serialize_user(&self, u: &User) -> Result<String, String> {
+        serde_json::to_string(u).map_err(|e| e.to_string())
+    }
+}
+
+/// Minimal hand-rolled XML output. Only exists with the "xml" feature.
+#[cfg(feature = "xml")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct XmlSerializer;
+
+#[cfg(feature = "xml")]
+impl Serializer for XmlSerializer {
+    fn format(&self) -> &'static str {
+        "xml"
+    }
+
+    fn serialize_user(&self, u: &User) -> Result<String, String> {
+        Ok(format!(
+            "<user id=\"{}\" active=\"{}\"><name>{}</name><email>{}</email></user>",
+            u.id,
+            u.active,
+            xml_escape(&u.name),
+            xml_escape(&u.email)
+        ))
+    }
+}
+
+/// Escapes the five characters XML treats specially.
+#[cfg(feature = "xml")]
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Runtime view of the serializers compiled into this build.
+///
+/// Features are decided at compile time, but the registry lets the rest of
+/// the program discover them at runtime -- the same idea as a plugin list.
+pub struct SerializerRegistry;
+
+impl SerializerRegistry {
+    /// Returns one instance of every serializer compiled in.
+    ///
+    /// Each `push` is behind its own `#[cfg]`, so a build with neither
+    /// feature gets an empty Vec rather than a compile error.
+    #[allow(clippy::vec_init_then_push)]
+    pub fn available() -> Vec<Box<dyn Serializer>> {
+        #[allow(unused_mut)]
+        let mut serializers: Vec<Box<dyn Serializer>> = Vec::new();
+
+        #[cfg(feature = "json")]
+        serializers.push(Box::new(JsonSerializer));
+
+        #[cfg(feature = "xml")]
+        serializers.push(Box::new(XmlSerializer));
+
+        serializers
+    }
+
+    /// Looks up a compiled-in serializer by format name.
+    pub fn find(format: &str) -> Option<Box<dyn Serializer>> {
+        Self::available().into_iter().find(|s| s.format() == format)
+    }
+}
+
+// ============================================================================
+// LOGGING FACADE
+// ============================================================================
+// The "logging" feature used to be listed in `get_feature_statuses` without
+// changing anything. Now it switches this facade on:
+//
+//   feature on:  log_event -> CaptureLogger -> thread-local Vec<LogRecord>
+//                                           -> the `log` crate (env_logger)
+//   feature off: log_event -> NullLogger    -> nothing
+//
+// With the feature off, `NullLogger::log` is an empty `#[inline]` function,
+// so a call to it compiles to no instructions at all. The one cost left is
+// building the message: `format!` still runs before the call. Callers avoid
+// that by checking the `const fn logging_enabled()` first - it is a
+// compile-time `false`, so the whole `if` block is removed.
+//
+// Why a `const fn` and not a `log_enabled!()` macro? A `macro_rules!` body
+// that says `cfg!(feature = "logging")` is expanded in the CALLER's crate
+// and checks the caller's features, not ours. A function is compiled here,
+// so it answers for this crate.
+//
+// Records are kept per thread. Tests run on separate threads, so one test's
+// events never show up in another's `take_logs()`.
+
+/// How important a log event is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+/// One captured log event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub message: String,
+}
+
+/// Somewhere log events can go.
+pub trait Logger {
+    fn log(&self, level: LogLevel, message: &str);
+}
+
+/// Discards every event. The default when "logging" is off.
+#[derive(Debug, Default)]
+pub struct NullLogger;
+
+impl Logger for NullLogger {
+    #[inline]
+    fn log(&self, _level: LogLevel, _message: &str) {}
+}
+
+/// Records events for `take_logs` and forwards them to the `log` crate.
+/// Only exists with the "logging" feature.
+#[cfg(feature = "logging")]
+#[derive(Debug, Default)]
+pub struct CaptureLogger;
+
+#[cfg(feature = "logging")]
+thread_local! {
+    static CAPTURED: std::cell::RefCell<Vec<LogRecord>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+#[cfg(feature = "logging")]
+impl Logger for CaptureLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        let log_level = match level {
+            LogLevel::Error => log::Level::Error,
+            LogLevel::Warn => log::Level::Warn,
+            LogLevel::Info => log::Level::Info,
+            LogLevel::Debug => log::Level::Debug,
+        };
+        log::log!(log_level, "{}", message);
+        CAPTURED.with(|logs| {
+            logs.borrow_mut().push(LogRecord {
+                level,
+                message: message.to_string(),
+            })
+        });
+    }
+}
+
+/// The logger `log_event` uses, picked at compile time.
+#[cfg(feature = "logging")]
+pub type DefaultLogger = CaptureLogger;
+
+/// The logger `log_event` uses, picked at compile time.
+#[cfg(not(feature = "logging"))]
+pub type DefaultLogger = NullLogger;
+
+/// True when this crate was built with the "logging" feature.
+///
+/// Check it before formatting an expensive message; when it is `false` the
+/// compiler drops the whole branch.
+pub const fn logging_enabled() -> bool {
+    cfg!(feature = "logging")
+}
+
+/// Sends one event to the `DefaultLogger`.
+#[inline]
+pub fn log_event(level: LogLevel, message: &str) {
+    DefaultLogger::default().log(level, message);
+}
+
+/// Removes and returns every event logged on this thread so far.
+///
+/// Always empty without the "logging" feature.
+pub fn take_logs() -> Vec<LogRecord> {
+    #[cfg(feature = "logging")]
+    {
+        CAPTURED.with(|logs| std::mem::take(&mut *logs.borrow_mut()))
+    }
+    #[cfg(not(feature = "logging"))]
+    {
+        Vec::new()
+    }
+}
+
 // ============================================================================
 // BUILD INFORMATION STRUCT
 // ============================================================================
@@ -261,13 +496,20 @@ pub fn count_enabled_features() -> usize {
 ///
 /// This is a pattern commonly used in production Rust applications to
 /// expose build metadata (often combined with the `built` crate).
-#[derive(Debug, Clone)]
+///
+/// The string fields are owned so a `BuildInfo` can also be rebuilt from
+/// JSON written by a different binary -- see [`BuildInfo::from_json`].
+#[derive(Debug, Clone, PartialEq)]
 pub struct BuildInfo {
-    pub platform: &'static str,
-    pub arch: &'static str,
+    pub platform: String,
+    pub arch: String,
     pub pointer_width: usize,
     pub debug: bool,
-    pub build_mode: &'static str,
+    pub build_mode: String,
+    pub crate_name: String,
+    pub crate_version: String,
+    pub rustc_channel_guess: String,
+    pub git_hash: Option<String>,
 }
 
 impl BuildInfo {
@@ -275,14 +517,166 @@ impl BuildInfo {
     ///
     /// All values are determined at compile time. This function simply
     /// aggregates them into a convenient struct.
+    ///
+    /// `env!` fails the build if the variable is missing, which is fine for
+    /// the CARGO_PKG_* variables Cargo always sets. `option_env!` yields
+    /// `None` instead, so GIT_HASH is only filled in when a build script or
+    /// CI job exports it.
     pub fn collect() -> Self {
-        BuildInfo {
-            platform: get_platform_name(),
-            arch: get_target_arch(),
+        let info = BuildInfo {
+            platform: get_platform_name().to_string(),
+            arch: get_target_arch().to_string(),
             pointer_width: get_pointer_width(),
             debug: is_debug_build(),
-            build_mode: get_build_mode(),
+            build_mode: get_build_mode().to_string(),
+            crate_name: env!("CARGO_PKG_NAME").to_string(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            rustc_channel_guess: guess_rustc_channel().to_string(),
+            git_hash: option_env!("GIT_HASH")
+                .filter(|h| !h.is_empty())
+                .map(str::to_string),
+        };
+        if logging_enabled() {
+            log_event(
+                LogLevel::Debug,
+                &format!(
+                    "collected build info: {} {} ({})",
+                    info.crate_name, info.crate_version, info.build_mode
+                ),
+            );
+        }
+        info
+    }
+
+    /// Serializes to a single-line JSON object.
+    ///
+    /// Hand-written so it works even when the optional serde dependency is
+    /// compiled out. Field order is fixed, so equal values give equal text.
+    pub fn to_json(&self) -> String {
+        let git_hash = match &self.git_hash {
+            Some(hash) => json_string(hash),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"platform\":{},\"arch\":{},\"pointer_width\":{},\"debug\":{},\
+             \"build_mode\":{},\"crate_name\":{},\"crate_version\":{},\
+             \"rustc_channel_guess\":{},\"git_hash\":{}}}",
+            json_string(&self.platform),
+            json_string(&self.arch),
+            self.pointer_width,
+            self.debug,
+            json_string(&self.build_mode),
+            json_string(&self.crate_name),
+            json_string(&self.crate_version),
+            json_string(&self.rustc_channel_guess),
+            git_hash
+        )
+    }
+
+    /// Parses the output of [`BuildInfo::to_json`].
+    ///
+    /// Accepts any flat JSON object with the same keys, in any order and
+    /// with any whitespace. Unknown keys are ignored; missing ones are an
+    /// error, except `git_hash`, which defaults to `None`.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let fields = parse_flat_object(json)?;
+        let get = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v)
+                .ok_or_else(|| format!("missing field `{}`", key))
+        };
+        let string = |key: &str| match get(key)? {
+            JsonScalar::Str(s) => Ok(s.clone()),
+            other => Err(format!("field `{}`: expected string, got {:?}", key, other)),
+        };
+
+        let pointer_width = match get("pointer_width")? {
+            JsonScalar::Number(n) => n
+                .parse::<usize>()
+                .map_err(|_| format!("field `pointer_width`: invalid number {}", n))?,
+            other => {
+                return Err(format!(
+                    "field `pointer_width`: expected number, got {:?}",
+                    other
+                ))
+            }
+        };
+        let debug = match get("debug")? {
+            JsonScalar::Bool(b) => *b,
+            other => return Err(format!("field `debug`: expected bool, got {:?}", other)),
+        };
+        let git_hash = match get("git_hash") {
+            Err(_) | Ok(JsonScalar::Null) => None,
+            Ok(JsonScalar::Str(s)) => Some(s.clone()),
+            Ok(other) => {
+                return Err(format!(
+                    "field `git_hash`: expected string, got {:?}",
+                    other
+                ))
+            }
+        };
+
+        Ok(BuildInfo {
+            platform: string("platform")?,
+            arch: string("arch")?,
+            pointer_width,
+            debug,
+            build_mode: string("build_mode")?,
+            crate_name: string("crate_name")?,
+            crate_version: string("crate_version")?,
+            rustc_channel_guess: string("rustc_channel_guess")?,
+            git_hash,
+        })
+    }
+
+    /// Lists every field that differs, as `"field: ours -> theirs"`.
+    ///
+    /// Handy for "built on X, running on Y" diagnostics: compare the
+    /// `BuildInfo` embedded in a saved report with the current binary's.
+    pub fn diff(&self, other: &BuildInfo) -> Vec<String> {
+        fn show(hash: &Option<String>) -> String {
+            hash.clone().unwrap_or_else(|| "none".to_string())
         }
+
+        let pairs = [
+            ("platform", self.platform.clone(), other.platform.clone()),
+            ("arch", self.arch.clone(), other.arch.clone()),
+            (
+                "pointer_width",
+                self.pointer_width.to_string(),
+                other.pointer_width.to_string(),
+            ),
+            ("debug", self.debug.to_string(), other.debug.to_string()),
+            (
+                "build_mode",
+                self.build_mode.clone(),
+                other.build_mode.clone(),
+            ),
+            (
+                "crate_name",
+                self.crate_name.clone(),
+                other.crate_name.clone(),
+            ),
+            (
+                "crate_version",
+                self.crate_version.clone(),
+                other.crate_version.clone(),
+            ),
+            (
+                "rustc_channel_guess",
+                self.rustc_channel_guess.clone(),
+                other.rustc_channel_guess.clone(),
+            ),
+            ("git_hash", show(&self.git_hash), show(&other.git_hash)),
+        ];
+
+        pairs
+            .into_iter()
+            .filter(|(_, ours, theirs)| ours != theirs)
+            .map(|(field, ours, theirs)| format!("{}: {} -> {}", field, ours, theirs))
+            .collect()
     }
 }
 
@@ -296,6 +690,156 @@ impl std::fmt::Display for BuildInfo {
     }
 }
 
+/// Best guess at the compiler release channel.
+///
+/// There is no `cfg` for the channel, so this reads RUSTUP_TOOLCHAIN, which
+/// rustup sets while compiling (e.g. "nightly-x86_64-unknown-linux-gnu").
+/// Builds outside rustup report "unknown".
+pub fn guess_rustc_channel() -> &'static str {
+    match option_env!("RUSTUP_TOOLCHAIN") {
+        Some(toolchain) if toolchain.starts_with("nightly") => "nightly",
+        Some(toolchain) if toolchain.starts_with("beta") => "beta",
+        Some(toolchain) if toolchain.starts_with("stable") => "stable",
+        // Pinned versions like "1.75.0-x86_64-..." are stable releases.
+        Some(toolchain) if toolchain.starts_with(|c: char| c.is_ascii_digit()) => "stable",
+        _ => "unknown",
+    }
+}
+
+/// Quotes and escapes a string as a JSON string literal.
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A JSON value that is not an object or array.
+#[derive(Debug, Clone, PartialEq)]
+enum JsonScalar {
+    Str(String),
+    Number(String),
+    Bool(bool),
+    Null,
+}
+
+type JsonChars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+/// Parses `{"key": scalar, ...}` into key/value pairs.
+///
+/// Just enough JSON for `BuildInfo`: nested objects and arrays are
+/// rejected rather than skipped.
+fn parse_flat_object(input: &str) -> Result<Vec<(String, JsonScalar)>, String> {
+    let mut chars = input.chars().peekable();
+    let mut fields = Vec::new();
+
+    fn skip_ws(chars: &mut JsonChars<'_>) {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+    }
+
+    fn expect(chars: &mut JsonChars<'_>, want: char) -> Result<(), String> {
+        skip_ws(chars);
+        match chars.next() {
+            Some(c) if c == want => Ok(()),
+            Some(c) => Err(format!("expected '{}', found '{}'", want, c)),
+            None => Err(format!("expected '{}', found end of input", want)),
+        }
+    }
+
+    fn parse_string(chars: &mut JsonChars<'_>) -> Result<String, String> {
+        expect(chars, '"')?;
+        let mut out = String::new();
+        loop {
+            match chars.next() {
+                Some('"') => return Ok(out),
+                Some('\\') => match chars.next() {
+                    Some('"') => out.push('"'),
+                    Some('\\') => out.push('\\'),
+                    Some('/') => out.push('/'),
+                    Some('n') => out.push('\n'),
+                    Some('r') => out.push('\r'),
+                    Some('t') => out.push('\t'),
+                    Some('u') => {
+                        let hex: String = chars.by_ref().take(4).collect();
+                        let code = u32::from_str_radix(&hex, 16)
+                            .map_err(|_| format!("invalid escape \\u{}", hex))?;
+                        out.push(char::from_u32(code).ok_or("invalid unicode escape")?);
+                    }
+                    Some(c) => return Err(format!("invalid escape \\{}", c)),
+                    None => return Err("unterminated string".to_string()),
+                },
+                Some(c) => out.push(c),
+                None => return Err("unterminated string".to_string()),
+            }
+        }
+    }
+
+    fn parse_scalar(chars: &mut JsonChars<'_>) -> Result<JsonScalar, String> {
+        skip_ws(chars);
+        match chars.peek() {
+            Some('"') => parse_string(chars).map(JsonScalar::Str),
+            Some(c) if c.is_ascii_alphanumeric() || *c == '-' => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.') {
+                        word.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                match word.as_str() {
+                    "true" => Ok(JsonScalar::Bool(true)),
+                    "false" => Ok(JsonScalar::Bool(false)),
+                    "null" => Ok(JsonScalar::Null),
+                    _ if word.parse::<f64>().is_ok() => Ok(JsonScalar::Number(word)),
+                    _ => Err(format!("unexpected token `{}`", word)),
+                }
+            }
+            Some(c) => Err(format!("unsupported value starting with '{}'", c)),
+            None => Err("expected value, found end of input".to_string()),
+        }
+    }
+
+    expect(&mut chars, '{')?;
+    skip_ws(&mut chars);
+    if chars.peek() == Some(&'}') {
+        chars.next();
+    } else {
+        loop {
+            let key = parse_string(&mut chars)?;
+            expect(&mut chars, ':')?;
+            fields.push((key, parse_scalar(&mut chars)?));
+            skip_ws(&mut chars);
+            match chars.next() {
+                Some(',') => skip_ws(&mut chars),
+                Some('}') => break,
+                Some(c) => return Err(format!("expected ',' or '}}', found '{}'", c)),
+                None => return Err("unterminated object".to_string()),
+            }
+        }
+    }
+
+    skip_ws(&mut chars);
+    match chars.next() {
+        None => Ok(fields),
+        Some(c) => Err(format!("trailing characters after object: '{}'", c)),
+    }
+}
+
 // ============================================================================
 // CONDITIONAL COMPILATION UTILITIES
 // ============================================================================
@@ -325,6 +869,130 @@ pub fn get_os_family() -> &'static str {
     }
 }
 
+// ============================================================================
+// RUNTIME CPU CAPABILITIES
+// ============================================================================
+// target_arch is fixed when we compile, but the CPU we run on is not: an
+// x86_64 binary may land on a machine with or without AVX2. So the pattern
+// is two-layered:
+//
+//   1. #[cfg(target_arch = "...")] decides WHICH checks exist at all
+//      (is_x86_feature_detected! does not even compile on ARM).
+//   2. The detection macros ask the CPU at runtime, and the standard
+//      library caches the answer after the first call.
+//
+// Every code path is compiled on every platform; detection only picks one.
+// That is what keeps the fast path honest: the portable loop is always
+// there to compare against.
+
+/// SIMD-related CPU capabilities found at runtime.
+///
+/// Flags for other architectures are always `false`, so on an unknown
+/// target this is just `CpuFeatures::default()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CpuFeatures {
+    pub sse2: bool,
+    pub avx2: bool,
+    pub neon: bool,
+}
+
+impl CpuFeatures {
+    /// Asks the running CPU which features it supports.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn detect() -> Self {
+        CpuFeatures {
+            sse2: is_x86_feature_detected!("sse2"),
+            avx2: is_x86_feature_detected!("avx2"),
+            neon: false,
+        }
+    }
+
+    /// Asks the running CPU which features it supports.
+    #[cfg(target_arch = "aarch64")]
+    pub fn detect() -> Self {
+        CpuFeatures {
+            neon: std::arch::is_aarch64_feature_detected!("neon"),
+            ..Self::default()
+        }
+    }
+
+    /// Portable fallback: no runtime checks, every flag is `false`.
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    pub fn detect() -> Self {
+        Self::default()
+    }
+
+    /// True if any vector extension is present, i.e. the CPU is likely to
+    /// run a chunked loop well once the compiler vectorizes it.
+    pub fn has_wide_registers(&self) -> bool {
+        self.sse2 || self.avx2 || self.neon
+    }
+
+    /// One-line report, e.g. "sse2=yes avx2=no neon=no threads=8".
+    pub fn summarize(&self) -> String {
+        let flag = |on: bool| if on { "yes" } else { "no" };
+        format!(
+            "sse2={} avx2={} neon={} threads={}",
+            flag(self.sse2),
+            flag(self.avx2),
+            flag(self.neon),
+            recommended_parallelism()
+        )
+    }
+}
+
+/// Number of threads worth spawning for CPU-bound work.
+///
+/// Wraps `std::thread::available_parallelism`, which can fail (e.g. in a
+/// restricted sandbox or on an unsupported platform). In that case we fall
+/// back to 1: running single-threaded is always correct, just slower.
+pub fn recommended_parallelism() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Byte sum, one element at a time. Works everywhere.
+pub fn sum_bytes_simple(data: &[u8]) -> u64 {
+    data.iter().map(|&b| u64::from(b)).sum()
+}
+
+/// Byte sum over 8-byte chunks with independent accumulators.
+///
+/// The four accumulators break the dependency chain of a single running
+/// total, which lets the optimizer keep several additions in flight (and
+/// use vector registers when the target has them).
+pub fn sum_bytes_chunked(data: &[u8]) -> u64 {
+    let chunks = data.chunks_exact(8);
+    let tail = chunks.remainder();
+    let mut acc = [0u64; 4];
+
+    for chunk in chunks {
+        acc[0] += u64::from(chunk[0]) + u64::from(chunk[4]);
+        acc[1] += u64::from(chunk[1]) + u64::from(chunk[5]);
+        acc[2] += u64::from(chunk[2]) + u64::from(chunk[6]);
+        acc[3] += u64::from(chunk[3]) + u64::from(chunk[7]);
+    }
+
+    acc.iter().sum::<u64>() + sum_bytes_simple(tail)
+}
+
+/// Sums `data`, picking the loop from the given capabilities.
+pub fn fast_sum_with(features: &CpuFeatures, data: &[u8]) -> u64 {
+    if features.has_wide_registers() {
+        sum_bytes_chunked(data)
+    } else {
+        sum_bytes_simple(data)
+    }
+}
+
+/// Sums `data` with the best loop for the running CPU.
+///
+/// Both paths return the same value; only speed differs.
+pub fn fast_sum(data: &[u8]) -> u64 {
+    fast_sum_with(&CpuFeatures::detect(), data)
+}
+
 // ============================================================================
 // WHAT RUST DOES UNDER THE HOOD
 // ============================================================================
@@ -364,5 +1032,8 @@ pub fn get_os_family() -> &'static str {
 // 6. Features are additive -- they can only add functionality
 // 7. Zero runtime cost for all conditional compilation
 // 8. Use BuildInfo pattern to aggregate compile-time metadata
+//    (env! for required Cargo vars, option_env! for CI-injected ones)
 // 9. FeatureStatus pattern makes feature reporting testable
 // 10. Always provide fallback implementations for unknown platforms
+// 11. Gate the implementations, not the trait, so callers stay feature-agnostic
+// 12. cfg picks which checks compile; runtime detection picks which path runs
@@ -340,6 +340,73 @@ fn test_count_enabled_features() {
     );
 }
 
+// ============================================================================
+// SERIALIZER REGISTRY TESTS
+// ============================================================================
+
+#[test]
+fn test_registry_matches_feature_statuses() {
+    let formats: Vec<&str> = SerializerRegistry::available()
+        .iter()
+        .map(|s| s.format())
+        .collect();
+    for status in get_feature_statuses() {
+        if status.name == "json" || status.name == "xml" {
+            assert_eq!(
+                formats.contains(&status.name.as_str()),
+                status.enabled,
+                "registry disagrees with feature status for {}",
+                status.name
+            );
+        }
+    }
+}
+
+#[test]
+fn test_registry_find_unknown_format() {
+    assert!(SerializerRegistry::find("yaml").is_none());
+}
+
+#[cfg(not(any(feature = "json", feature = "xml")))]
+#[test]
+fn test_registry_empty_without_features() {
+    assert!(SerializerRegistry::available().is_empty());
+    assert!(SerializerRegistry::find("json").is_none());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_serializer_round_trip() {
+    let mut user = User::new(7, "Ada \"Countess\" Lovelace", "ada@example.com");
+    user.deactivate();
+    let json = JsonSerializer.serialize_user(&user).unwrap();
+    let back: User = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, user);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_registry_finds_json() {
+    let serializer = SerializerRegistry::find("json").expect("json is compiled in");
+    assert_eq!(serializer.format(), "json");
+    let out = serializer
+        .serialize_user(&User::new(1, "Alice", "alice@example.com"))
+        .unwrap();
+    assert!(out.contains("\"name\":\"Alice\""));
+}
+
+#[cfg(feature = "xml")]
+#[test]
+fn test_xml_serializer_escapes_text() {
+    let user = User::new(2, "Tom & <Jerry>", "t@example.com");
+    let xml = XmlSerializer.serialize_user(&user).unwrap();
+    assert_eq!(
+        xml,
+        "<user id=\"2\" active=\"true\"><name>Tom &amp; &lt;Jerry&gt;</name>\
+         <email>t@example.com</email></user>"
+    );
+}
+
 // ============================================================================
 // BUILD INFO TESTS
 // ============================================================================
@@ -397,6 +464,77 @@ fn test_build_info_clone() {
     assert_eq!(info.build_mode, cloned.build_mode);
 }
 
+#[test]
+fn test_build_info_crate_metadata() {
+    let info = BuildInfo::collect();
+    assert!(
+        !info.crate_version.is_empty(),
+        "Version should not be empty"
+    );
+    assert_eq!(info.crate_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(info.crate_name, "cargo-features");
+    assert_eq!(info.rustc_channel_guess, guess_rustc_channel());
+    assert!(["stable", "beta", "nightly", "unknown"].contains(&info.rustc_channel_guess.as_str()));
+}
+
+#[test]
+fn test_build_info_json_round_trip() {
+    let info = BuildInfo::collect();
+    let back = BuildInfo::from_json(&info.to_json()).unwrap();
+    assert_eq!(back, info);
+}
+
+#[test]
+fn test_build_info_json_round_trip_escapes_and_git_hash() {
+    let mut info = BuildInfo::collect();
+    info.platform = "Weird \"OS\"\\n\ttab".to_string();
+    info.git_hash = Some("abc123".to_string());
+    let json = info.to_json();
+    assert!(json.contains("\"git_hash\":\"abc123\""));
+    assert_eq!(BuildInfo::from_json(&json).unwrap(), info);
+
+    info.git_hash = None;
+    let json = info.to_json();
+    assert!(json.contains("\"git_hash\":null"));
+    assert_eq!(BuildInfo::from_json(&json).unwrap(), info);
+}
+
+#[test]
+fn test_build_info_from_json_rejects_bad_input() {
+    assert!(BuildInfo::from_json("").is_err());
+    assert!(BuildInfo::from_json("{}")
+        .unwrap_err()
+        .contains("missing field"));
+    let json = BuildInfo::collect()
+        .to_json()
+        .replace("\"debug\":", "\"debug\":\"yes\",\"x\":");
+    assert!(BuildInfo::from_json(&json).is_err());
+    let trailing = format!("{} extra", BuildInfo::collect().to_json());
+    assert!(BuildInfo::from_json(&trailing).is_err());
+}
+
+#[test]
+fn test_build_info_diff() {
+    let built = BuildInfo::collect();
+    assert!(built.diff(&built.clone()).is_empty());
+
+    let mut running = built.clone();
+    running.platform = "Plan9".to_string();
+    running.crate_version = "9.9.9".to_string();
+    running.git_hash = Some("deadbeef".to_string());
+    let mut expected_hash = built.git_hash.clone().unwrap_or_else(|| "none".to_string());
+    expected_hash.push_str(" -> deadbeef");
+
+    assert_eq!(
+        built.diff(&running),
+        vec![
+            format!("platform: {} -> Plan9", built.platform),
+            format!("crate_version: {} -> 9.9.9", built.crate_version),
+            format!("git_hash: {}", expected_hash),
+        ]
+    );
+}
+
 // ============================================================================
 // ENDIANNESS AND OS FAMILY TESTS
 // ============================================================================
@@ -439,6 +577,89 @@ fn test_os_family_is_windows() {
     assert_eq!(get_os_family(), "windows");
 }
 
+// ============================================================================
+// CPU CAPABILITY TESTS
+// ============================================================================
+
+/// Deterministic pseudo-random bytes (xorshift), so failures reproduce.
+fn pseudo_random_bytes(len: usize, mut seed: u64) -> Vec<u8> {
+    (0..len)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed >> 24) as u8
+        })
+        .collect()
+}
+
+#[test]
+fn test_cpu_features_detect_does_not_panic() {
+    let features = CpuFeatures::detect();
+    assert_eq!(
+        features,
+        CpuFeatures::detect(),
+        "detection should be stable"
+    );
+
+    let arch = get_target_arch();
+    if arch != "x86_64" && arch != "x86" {
+        assert!(!features.sse2 && !features.avx2);
+    }
+    if arch != "aarch64" {
+        assert!(!features.neon);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn test_x86_64_always_has_sse2() {
+    // SSE2 is part of the x86_64 baseline.
+    assert!(CpuFeatures::detect().sse2);
+}
+
+#[test]
+fn test_cpu_features_summary_lists_every_flag() {
+    let summary = CpuFeatures::default().summarize();
+    assert!(summary.starts_with("sse2=no avx2=no neon=no threads="));
+    assert!(CpuFeatures::detect().summarize().contains("threads="));
+}
+
+#[test]
+fn test_recommended_parallelism_is_at_least_one() {
+    assert!(recommended_parallelism() >= 1);
+}
+
+#[test]
+fn test_sum_paths_agree_on_random_data() {
+    for (len, seed) in [(0, 1), (1, 2), (7, 3), (8, 4), (9, 5), (1000, 6), (4099, 7)] {
+        let data = pseudo_random_bytes(len, seed);
+        let expected = sum_bytes_simple(&data);
+        assert_eq!(sum_bytes_chunked(&data), expected, "len {}", len);
+        assert_eq!(fast_sum(&data), expected, "len {}", len);
+    }
+}
+
+#[test]
+fn test_fast_sum_same_result_for_any_features() {
+    let data = pseudo_random_bytes(513, 42);
+    let wide = CpuFeatures {
+        avx2: true,
+        ..CpuFeatures::default()
+    };
+    assert_eq!(
+        fast_sum_with(&wide, &data),
+        fast_sum_with(&CpuFeatures::default(), &data)
+    );
+}
+
+#[test]
+fn test_sum_bytes_all_max_values() {
+    let data = vec![u8::MAX; 10_000];
+    assert_eq!(sum_bytes_chunked(&data), 255 * 10_000);
+    assert_eq!(sum_bytes_simple(&data), 255 * 10_000);
+}
+
 // ============================================================================
 // CROSS-FUNCTION CONSISTENCY TESTS
 // ============================================================================
@@ -498,3 +719,96 @@ fn test_user_activate_deactivate_cycle() {
         assert!(user.active);
     }
 }
+
+// ============================================================================
+// LOGGING FACADE TESTS
+// ============================================================================
+
+#[test]
+fn test_logging_enabled_matches_feature_status() {
+    let status = get_feature_statuses()
+        .into_iter()
+        .find(|s| s.name == "logging")
+        .unwrap();
+    assert_eq!(logging_enabled(), status.enabled);
+    // Usable in const context.
+    const ENABLED: bool = logging_enabled();
+    assert_eq!(ENABLED, cfg!(feature = "logging"));
+}
+
+#[test]
+fn test_null_logger_accepts_events() {
+    NullLogger.log(LogLevel::Error, "dropped on the floor");
+}
+
+#[cfg(feature = "logging")]
+#[test]
+fn test_log_event_captured() {
+    take_logs();
+    log_event(LogLevel::Warn, "disk almost full");
+    log_event(LogLevel::Error, "disk full");
+    assert_eq!(
+        take_logs(),
+        vec![
+            LogRecord {
+                level: LogLevel::Warn,
+                message: "disk almost full".to_string(),
+            },
+            LogRecord {
+                level: LogLevel::Error,
+                message: "disk full".to_string(),
+            },
+        ]
+    );
+    assert!(take_logs().is_empty(), "take_logs drains the buffer");
+}
+
+#[cfg(feature = "logging")]
+#[test]
+fn test_user_activation_logged() {
+    take_logs();
+    let mut user = User::new(7, "Alice", "alice@example.com");
+    assert!(take_logs().is_empty(), "creating a user logs nothing");
+    user.deactivate();
+    user.activate();
+    let logs = take_logs();
+    assert_eq!(
+        logs.iter().map(|r| r.level).collect::<Vec<_>>(),
+        [LogLevel::Info, LogLevel::Info]
+    );
+    assert_eq!(logs[0].message, "user 7 deactivated");
+    assert_eq!(logs[1].message, "user 7 activated");
+}
+
+#[cfg(feature = "logging")]
+#[test]
+fn test_build_info_collect_logged() {
+    take_logs();
+    let info = BuildInfo::collect();
+    let logs = take_logs();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].level, LogLevel::Debug);
+    assert!(logs[0].message.contains(&info.crate_version));
+}
+
+#[cfg(feature = "logging")]
+#[test]
+fn test_logs_are_per_thread() {
+    take_logs();
+    std::thread::spawn(|| log_event(LogLevel::Info, "other thread"))
+        .join()
+        .unwrap();
+    assert!(take_logs().is_empty());
+}
+
+#[cfg(not(feature = "logging"))]
+#[test]
+fn test_logging_disabled_records_nothing() {
+    assert!(!logging_enabled());
+    log_event(LogLevel::Error, "nobody hears this");
+    let mut user = User::new(1, "Bob", "bob@example.com");
+    user.deactivate();
+    user.activate();
+    BuildInfo::collect();
+    assert!(take_logs().is_empty());
+}
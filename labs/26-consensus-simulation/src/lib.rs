@@ -76,5 +76,226 @@ pub fn is_byzantine_safe(num_nodes: usize, num_faulty: usize) -> bool {
     todo!("Check Byzantine safety")
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum PbftMessage {
+    PrePrepare {
+        view: u32,
+        value: i32,
+    },
+    Prepare {
+        node_id: usize,
+        view: u32,
+        value: i32,
+    },
+    Commit {
+        node_id: usize,
+        view: u32,
+        value: i32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultyBehavior {
+    Silent,
+    Equivocate,
+}
+
+#[derive(Debug, Clone)]
+pub struct PbftResult {
+    pub view: u32,
+    pub proposed_value: i32,
+    pub quorum: usize,
+    pub pre_prepare_messages: usize,
+    pub prepare_messages: usize,
+    pub commit_messages: usize,
+    pub committed: Vec<(usize, i32)>,
+    pub decided: Option<i32>,
+    pub safety_held: bool,
+    pub failed_nodes: Vec<usize>,
+}
+
+impl PbftResult {
+    pub fn total_messages(&self) -> usize {
+        todo!("Sum the per-phase message counts")
+    }
+}
+
+pub struct PbftSimulation {
+    pub view: u32,
+    pub proposal_value: i32,
+    pub faulty_behavior: FaultyBehavior,
+}
+
+impl PbftSimulation {
+    pub fn new(view: u32, proposal_value: i32) -> Self {
+        let _ = (view, proposal_value);
+        todo!("Create a simulation with silent faulty nodes")
+    }
+
+    pub fn with_faulty_behavior(self, behavior: FaultyBehavior) -> Self {
+        let _ = behavior;
+        todo!("Set the faulty behavior")
+    }
+
+    pub fn run(&self, nodes: Vec<Node>) -> PbftResult {
+        // TODO: One thread per node, with its own inbox channel, plus a
+        // shared channel back to the coordinator. Drive PrePrepare,
+        // Prepare, and Commit in lockstep, relaying (and counting) every
+        // message. Quorum is 2f + 1 matching messages.
+        // Don't block on a node that will never answer: wait with
+        // recv_timeout and record nodes whose thread has ended (or panicked
+        // on join) in failed_nodes.
+        let _ = nodes;
+        todo!("Run the three PBFT phases")
+    }
+}
+
+pub const SUMMARY_CSV_HEADER: &str =
+    "nodes,faulty,trials,consensus_trials,avg_yes_fraction,byzantine_safe";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationSummary {
+    pub num_nodes: usize,
+    pub num_faulty: usize,
+    pub trials: u32,
+    pub consensus_trials: u32,
+    pub avg_yes_fraction: f64,
+    pub byzantine_safe: bool,
+}
+
+impl SimulationSummary {
+    pub fn consensus_rate(&self) -> f64 {
+        todo!("consensus_trials / trials, 0.0 for no trials")
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        // TODO: Same column order as SUMMARY_CSV_HEADER; fraction with {:.4}.
+        todo!("Format summary as CSV row")
+    }
+}
+
+pub fn summaries_to_csv(summaries: &[SimulationSummary]) -> String {
+    let _ = summaries;
+    todo!("Header line, then one row per summary")
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimulationHarness;
+
+impl SimulationHarness {
+    pub fn new() -> Self {
+        SimulationHarness
+    }
+
+    pub fn nodes_for_trial(num_nodes: usize, num_faulty: usize, trial: u32) -> Vec<Node> {
+        // TODO: First num_faulty ids are faulty and accept when
+        // (trial + id) is even; the rest are honest.
+        let _ = (num_nodes, num_faulty, trial);
+        todo!("Build nodes for one trial")
+    }
+
+    pub fn run_config(
+        &self,
+        num_nodes: usize,
+        num_faulty: usize,
+        trials: u32,
+        proposal: i32,
+    ) -> SimulationSummary {
+        // TODO: Run ConsensusCoordinator once per trial and aggregate.
+        let _ = (num_nodes, num_faulty, trials, proposal);
+        todo!("Summarize one configuration")
+    }
+
+    pub fn run_grid(
+        &self,
+        node_counts: &[usize],
+        faulty_counts: &[usize],
+        trials: u32,
+        proposal: i32,
+    ) -> Vec<SimulationSummary> {
+        // TODO: Every (nodes, faulty) pair with faulty <= nodes.
+        let _ = (node_counts, faulty_counts, trials, proposal);
+        todo!("Run the configuration grid")
+    }
+}
+
+/// One vote as written to the journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoteRecord {
+    pub round: u32,
+    pub value: i32,
+    pub accept: bool,
+}
+
+/// Why a node refused to vote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoteError {
+    ConflictingProposal {
+        round: u32,
+        voted_value: i32,
+        proposed_value: i32,
+    },
+}
+
+pub type PersistFn = Box<dyn FnMut(&VoteRecord) + Send>;
+
+/// A node's journal of votes, at most one per round.
+#[derive(Default)]
+pub struct VoteLog {
+    entries: Vec<VoteRecord>,
+    by_round: std::collections::HashMap<u32, usize>,
+    persist: Option<PersistFn>,
+}
+
+impl VoteLog {
+    pub fn new() -> Self {
+        VoteLog::default()
+    }
+
+    pub fn with_persist(persist: impl FnMut(&VoteRecord) + Send + 'static) -> Self {
+        let _ = persist;
+        todo!("Empty log with a persist callback")
+    }
+
+    pub fn recover(entries: Vec<VoteRecord>) -> Self {
+        // TODO: Rebuild the journal and the round index; if two records share
+        // a round, keep the first.
+        let _ = entries;
+        todo!("Rebuild a log after a crash")
+    }
+
+    pub fn recover_with_persist(
+        entries: Vec<VoteRecord>,
+        persist: impl FnMut(&VoteRecord) + Send + 'static,
+    ) -> Self {
+        let _ = (entries, persist);
+        todo!("Recover, then keep persisting")
+    }
+
+    pub fn vote_for(&self, round: u32) -> Option<&VoteRecord> {
+        let _ = (round, &self.entries, &self.by_round, &self.persist);
+        todo!("Look up a round's vote")
+    }
+
+    pub fn entries(&self) -> &[VoteRecord] {
+        todo!("Return the journal")
+    }
+}
+
+impl Node {
+    pub fn process_proposal_durable(
+        &mut self,
+        round: u32,
+        value: i32,
+        log: &mut VoteLog,
+    ) -> Result<bool, VoteError> {
+        // TODO: If `log` has a vote for `round`, return it for the same value
+        // and ConflictingProposal for any other. Otherwise decide with
+        // `process_proposal` and persist the vote BEFORE returning it.
+        let _ = (round, value, log);
+        todo!("Vote at most once per round")
+    }
+}
+
 #[doc(hidden)]
 pub mod solution;
@@ -1,6 +1,9 @@
 //! # Consensus Simulation Demo
 
-use consensus_simulation::solution::{ConsensusCoordinator, Node, NodeType};
+use consensus_simulation::solution::{
+    summaries_to_csv, ConsensusCoordinator, FaultyBehavior, Node, NodeType, PbftSimulation,
+    SimulationHarness, VoteLog,
+};
 
 fn main() {
     println!("=== Consensus Simulation Demo ===\n");
@@ -18,4 +21,53 @@ fn main() {
     println!("round: {}", result.round);
     println!("yes votes: {}/{}", result.yes_votes, result.total_votes);
     println!("consensus reached: {}", result.consensus_reached);
+
+    println!("\n=== PBFT Three-Phase Commit ===\n");
+    for faulty in [1, 2] {
+        let nodes: Vec<Node> = (0..4)
+            .map(|id| {
+                if id < faulty {
+                    Node::new_faulty(id, true)
+                } else {
+                    Node::new(id, NodeType::Honest)
+                }
+            })
+            .collect();
+        let result = PbftSimulation::new(0, 42)
+            .with_faulty_behavior(FaultyBehavior::Equivocate)
+            .run(nodes);
+
+        println!("4 nodes, {} equivocating:", faulty);
+        println!(
+            "  messages: {} pre-prepare, {} prepare, {} commit",
+            result.pre_prepare_messages, result.prepare_messages, result.commit_messages
+        );
+        println!("  committed: {:?}", result.committed);
+        println!("  safety held: {}", result.safety_held);
+    }
+
+    println!("\n=== Simulation Grid (CSV) ===\n");
+    let summaries = SimulationHarness::new().run_grid(&[4, 7], &[0, 1, 2, 3], 4, 42);
+    print!("{}", summaries_to_csv(&summaries));
+
+    println!("\n=== Durable Votes ===\n");
+    let mut node = Node::new(0, NodeType::Honest);
+    let mut log = VoteLog::new();
+    println!(
+        "round 1, value 42: {:?}",
+        node.process_proposal_durable(1, 42, &mut log)
+    );
+    println!(
+        "retry:             {:?}",
+        node.process_proposal_durable(1, 42, &mut log)
+    );
+    match node.process_proposal_durable(1, 7, &mut log) {
+        Ok(vote) => println!("unexpected second vote: {}", vote),
+        Err(err) => println!("round 1, value 7:  {}", err),
+    }
+    let mut restarted = VoteLog::recover(log.entries().to_vec());
+    println!(
+        "after restart, value 7 again: {:?}",
+        node.process_proposal_durable(1, 7, &mut restarted)
+    );
 }
@@ -22,8 +22,10 @@
 // In this library version, faulty nodes use a configurable "faulty_accepts"
 // flag so tests can be deterministic and reproducible.
 
-use std::sync::mpsc::{self, Receiver, Sender};
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
 use std::thread;
+use std::time::Duration;
 
 // ============================================================================
 // MESSAGE TYPES
@@ -277,6 +279,737 @@ pub fn is_byzantine_safe(num_nodes: usize, num_faulty: usize) -> bool {
     num_faulty <= byzantine_tolerance(num_nodes)
 }
 
+// ============================================================================
+// PBFT: THREE-PHASE COMMIT
+// ============================================================================
+// The single yes/no vote above trusts every vote it receives. PBFT does not:
+// a node only acts once it has heard the *same* thing from a quorum of
+// 2f + 1 nodes, where f = byzantine_tolerance(n). Any two quorums of that
+// size overlap in at least f + 1 nodes, so at least one honest node sits in
+// both - which is exactly why n must be at least 3f + 1.
+//
+// The three phases:
+// 1. PrePrepare: the leader (primary) sends the value to every backup.
+// 2. Prepare: every node that accepts the value broadcasts Prepare to all
+//    others. A node is "prepared" after seeing 2f + 1 matching Prepares
+//    (its own included).
+// 3. Commit: prepared nodes broadcast Commit. A node commits the value
+//    after seeing 2f + 1 matching Commits.
+//
+// Nodes never talk to each other directly here: each one sends its
+// outgoing messages to the coordinator, which relays them into the
+// recipients' inboxes. The coordinator also drives the phases in lockstep
+// with `Tick`s, so every message of one phase is delivered before anyone
+// starts the next.
+
+/// Messages exchanged in the PBFT simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PbftMessage {
+    /// The leader proposes a value for this view
+    PrePrepare { view: u32, value: i32 },
+    /// A node tells the others it accepted the pre-prepared value
+    Prepare {
+        node_id: usize,
+        view: u32,
+        value: i32,
+    },
+    /// A prepared node tells the others it is ready to commit
+    Commit {
+        node_id: usize,
+        view: u32,
+        value: i32,
+    },
+}
+
+/// How `NodeType::Faulty` nodes misbehave in a `PbftSimulation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultyBehavior {
+    /// Send nothing at all (a crashed or withholding node)
+    Silent,
+    /// Tell even-numbered nodes the proposed value and odd-numbered nodes
+    /// `value + 1`, in every phase
+    Equivocate,
+}
+
+/// The outcome of a `PbftSimulation` run.
+#[derive(Debug, Clone)]
+pub struct PbftResult {
+    /// The view (round) that was simulated
+    pub view: u32,
+    /// The value the leader was asked to propose
+    pub proposed_value: i32,
+    /// Matching messages a node needs before it moves on: 2f + 1
+    pub quorum: usize,
+    /// Messages delivered in each phase
+    pub pre_prepare_messages: usize,
+    pub prepare_messages: usize,
+    pub commit_messages: usize,
+    /// Honest nodes that committed, with the value they committed, by id
+    pub committed: Vec<(usize, i32)>,
+    /// The committed value, if any honest node committed and safety held
+    pub decided: Option<i32>,
+    /// Whether no two honest nodes committed different values
+    pub safety_held: bool,
+    /// Nodes whose thread stopped answering (it panicked, or its id was
+    /// reused by a later node), by id; they take no further part
+    pub failed_nodes: Vec<usize>,
+}
+
+impl PbftResult {
+    /// Total messages delivered across all three phases.
+    pub fn total_messages(&self) -> usize {
+        self.pre_prepare_messages + self.prepare_messages + self.commit_messages
+    }
+}
+
+/// Runs one view of a PBFT-style three-phase commit over channels.
+///
+/// The leader is the node at position `view % n` in the list passed to
+/// `run`, like PBFT's rotating primary. Faulty nodes (any `NodeType::Faulty`,
+/// their `faulty_accepts` flag is not used here) follow `faulty_behavior`.
+pub struct PbftSimulation {
+    pub view: u32,
+    pub proposal_value: i32,
+    pub faulty_behavior: FaultyBehavior,
+}
+
+/// What the coordinator puts into a node's inbox.
+enum Envelope {
+    Deliver(PbftMessage),
+    Tick(PbftStep),
+}
+
+/// One node's messages for the current step, as (recipient id, message).
+struct Outgoing {
+    /// The sender's position in the list passed to `run`
+    from: usize,
+    messages: Vec<(usize, PbftMessage)>,
+}
+
+/// How long the coordinator waits for an answer before checking whether
+/// the nodes it is waiting on are still alive.
+const PBFT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PbftStep {
+    PrePrepare,
+    Prepare,
+    Commit,
+    Decide,
+}
+
+const PBFT_STEPS: [PbftStep; 4] = [
+    PbftStep::PrePrepare,
+    PbftStep::Prepare,
+    PbftStep::Commit,
+    PbftStep::Decide,
+];
+
+/// Everything a node thread needs to know up front.
+struct PbftNodeContext {
+    node: Node,
+    position: usize,
+    peers: Vec<usize>,
+    leader: usize,
+    view: u32,
+    value: i32,
+    quorum: usize,
+    faulty_behavior: FaultyBehavior,
+}
+
+impl PbftSimulation {
+    /// Creates a simulation where faulty nodes stay silent.
+    pub fn new(view: u32, proposal_value: i32) -> Self {
+        PbftSimulation {
+            view,
+            proposal_value,
+            faulty_behavior: FaultyBehavior::Silent,
+        }
+    }
+
+    /// Sets how faulty nodes misbehave.
+    pub fn with_faulty_behavior(mut self, behavior: FaultyBehavior) -> Self {
+        self.faulty_behavior = behavior;
+        self
+    }
+
+    /// Runs the three phases with one thread per node.
+    ///
+    /// Node ids are expected to be unique; they are used to address
+    /// messages. A node thread that dies mid-run is listed in
+    /// `failed_nodes` and the run carries on without it.
+    pub fn run(&self, nodes: Vec<Node>) -> PbftResult {
+        let n = nodes.len();
+        let quorum = 2 * byzantine_tolerance(n) + 1;
+        let mut result = PbftResult {
+            view: self.view,
+            proposed_value: self.proposal_value,
+            quorum,
+            pre_prepare_messages: 0,
+            prepare_messages: 0,
+            commit_messages: 0,
+            committed: Vec::new(),
+            decided: None,
+            safety_held: true,
+            failed_nodes: Vec::new(),
+        };
+        if n == 0 {
+            return result;
+        }
+
+        let leader = nodes[self.view as usize % n].id;
+        let peers: Vec<usize> = nodes.iter().map(|node| node.id).collect();
+        let (outbox_tx, outbox_rx) = mpsc::channel::<Outgoing>();
+
+        let mut inboxes = HashMap::new();
+        let mut node_handles = vec![];
+        for (position, node) in nodes.into_iter().enumerate() {
+            let (inbox_tx, inbox_rx) = mpsc::channel();
+            inboxes.insert(node.id, inbox_tx);
+
+            let ctx = PbftNodeContext {
+                node,
+                position,
+                peers: peers.clone(),
+                leader,
+                view: self.view,
+                value: self.proposal_value,
+                quorum,
+                faulty_behavior: self.faulty_behavior,
+            };
+            let outbox = outbox_tx.clone();
+            node_handles.push(thread::spawn(move || {
+                let id = ctx.node.id;
+                let node_type = ctx.node.node_type;
+                (id, node_type, run_pbft_node(ctx, inbox_rx, outbox))
+            }));
+        }
+        drop(outbox_tx);
+
+        let mut alive = vec![true; n];
+        for step in PBFT_STEPS {
+            for inbox in inboxes.values() {
+                let _ = inbox.send(Envelope::Tick(step));
+            }
+            // Every live node answers every tick (possibly with nothing),
+            // so once each has answered this step's traffic is complete. A
+            // node whose thread has ended never will: the other nodes keep
+            // the channel open, so blocking on it would wait forever.
+            let mut waiting: HashSet<usize> = (0..n).filter(|&i| alive[i]).collect();
+            while !waiting.is_empty() {
+                let outgoing = match outbox_rx.recv_timeout(PBFT_POLL_INTERVAL) {
+                    Ok(outgoing) => outgoing,
+                    Err(RecvTimeoutError::Timeout) => {
+                        waiting.retain(|&i| {
+                            alive[i] = !node_handles[i].is_finished();
+                            alive[i]
+                        });
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+                waiting.remove(&outgoing.from);
+                for (to, message) in outgoing.messages {
+                    let Some(inbox) = inboxes.get(&to) else {
+                        continue;
+                    };
+                    match message {
+                        PbftMessage::PrePrepare { .. } => result.pre_prepare_messages += 1,
+                        PbftMessage::Prepare { .. } => result.prepare_messages += 1,
+                        PbftMessage::Commit { .. } => result.commit_messages += 1,
+                    }
+                    let _ = inbox.send(Envelope::Deliver(message));
+                }
+            }
+        }
+
+        // Closing the inboxes ends every node's loop.
+        drop(inboxes);
+        for (position, handle) in node_handles.into_iter().enumerate() {
+            match handle.join() {
+                Ok(_) if !alive[position] => result.failed_nodes.push(peers[position]),
+                Ok((id, NodeType::Honest, Some(value))) => result.committed.push((id, value)),
+                Ok(_) => {}
+                Err(_) => result.failed_nodes.push(peers[position]),
+            }
+        }
+        result.committed.sort_unstable();
+
+        result.safety_held = result
+            .committed
+            .windows(2)
+            .all(|pair| pair[0].1 == pair[1].1);
+        if result.safety_held {
+            result.decided = result.committed.first().map(|&(_, value)| value);
+        }
+        result
+    }
+}
+
+/// The body of one node thread. Returns the value it committed, if any.
+fn run_pbft_node(
+    ctx: PbftNodeContext,
+    inbox: Receiver<Envelope>,
+    outbox: Sender<Outgoing>,
+) -> Option<i32> {
+    let me = ctx.node.id;
+    let mut pre_prepares: Vec<i32> = Vec::new();
+    let mut prepares: HashMap<i32, HashSet<usize>> = HashMap::new();
+    let mut commits: HashMap<i32, HashSet<usize>> = HashMap::new();
+    let mut accepted = None;
+    let mut prepared = None;
+    let mut committed = None;
+
+    for envelope in inbox {
+        let step = match envelope {
+            Envelope::Deliver(PbftMessage::PrePrepare { view, value }) if view == ctx.view => {
+                pre_prepares.push(value);
+                continue;
+            }
+            Envelope::Deliver(PbftMessage::Prepare {
+                node_id,
+                view,
+                value,
+            }) if view == ctx.view => {
+                prepares.entry(value).or_default().insert(node_id);
+                continue;
+            }
+            Envelope::Deliver(PbftMessage::Commit {
+                node_id,
+                view,
+                value,
+            }) if view == ctx.view => {
+                commits.entry(value).or_default().insert(node_id);
+                continue;
+            }
+            Envelope::Deliver(_) => continue,
+            Envelope::Tick(step) => step,
+        };
+
+        let messages = if ctx.node.node_type == NodeType::Faulty {
+            faulty_messages(&ctx, step)
+        } else {
+            match step {
+                PbftStep::PrePrepare if me == ctx.leader => {
+                    pre_prepares.push(ctx.value);
+                    broadcast(&ctx, |_| PbftMessage::PrePrepare {
+                        view: ctx.view,
+                        value: ctx.value,
+                    })
+                }
+                PbftStep::Prepare => {
+                    // Exactly one pre-prepare, and a value we'd vote for.
+                    // Two different pre-prepares prove the leader is lying.
+                    if let [value] = pre_prepares[..] {
+                        if ctx.node.process_proposal(ctx.view, value) {
+                            accepted = Some(value);
+                        }
+                    }
+                    match accepted {
+                        Some(value) => {
+                            prepares.entry(value).or_default().insert(me);
+                            broadcast(&ctx, |_| PbftMessage::Prepare {
+                                node_id: me,
+                                view: ctx.view,
+                                value,
+                            })
+                        }
+                        None => Vec::new(),
+                    }
+                }
+                PbftStep::Commit => {
+                    prepared = accepted
+                        .filter(|value| prepares.get(value).map_or(0, HashSet::len) >= ctx.quorum);
+                    match prepared {
+                        Some(value) => {
+                            commits.entry(value).or_default().insert(me);
+                            broadcast(&ctx, |_| PbftMessage::Commit {
+                                node_id: me,
+                                view: ctx.view,
+                                value,
+                            })
+                        }
+                        None => Vec::new(),
+                    }
+                }
+                PbftStep::Decide => {
+                    committed = prepared
+                        .filter(|value| commits.get(value).map_or(0, HashSet::len) >= ctx.quorum);
+                    Vec::new()
+                }
+                _ => Vec::new(),
+            }
+        };
+
+        let outgoing = Outgoing {
+            from: ctx.position,
+            messages,
+        };
+        if outbox.send(outgoing).is_err() {
+            break;
+        }
+    }
+
+    committed
+}
+
+/// Builds one message for every other node, letting the message depend on
+/// the recipient (which is how a faulty node equivocates).
+fn broadcast<F>(ctx: &PbftNodeContext, make: F) -> Vec<(usize, PbftMessage)>
+where
+    F: Fn(usize) -> PbftMessage,
+{
+    ctx.peers
+        .iter()
+        .filter(|&&peer| peer != ctx.node.id)
+        .map(|&peer| (peer, make(peer)))
+        .collect()
+}
+
+/// What a faulty node sends in `step`.
+fn faulty_messages(ctx: &PbftNodeContext, step: PbftStep) -> Vec<(usize, PbftMessage)> {
+    if ctx.faulty_behavior == FaultyBehavior::Silent {
+        return Vec::new();
+    }
+
+    let me = ctx.node.id;
+    let view = ctx.view;
+    let lie = |peer: usize| {
+        if peer % 2 == 0 {
+            ctx.value
+        } else {
+            ctx.value.wrapping_add(1)
+        }
+    };
+    match step {
+        PbftStep::PrePrepare if me == ctx.leader => {
+            broadcast(ctx, |peer| PbftMessage::PrePrepare {
+                view,
+                value: lie(peer),
+            })
+        }
+        PbftStep::Prepare => broadcast(ctx, |peer| PbftMessage::Prepare {
+            node_id: me,
+            view,
+            value: lie(peer),
+        }),
+        PbftStep::Commit => broadcast(ctx, |peer| PbftMessage::Commit {
+            node_id: me,
+            view,
+            value: lie(peer),
+        }),
+        _ => Vec::new(),
+    }
+}
+
+// ============================================================================
+// SIMULATION HARNESS: MANY CONFIGURATIONS AT ONCE
+// ============================================================================
+// One coordinator run answers "did THIS setup agree?". To see how the
+// outcome depends on cluster size and the number of faulty nodes, the
+// harness runs every (nodes, faulty) pair for several trials and boils each
+// configuration down to one `SimulationSummary`.
+//
+// Faulty nodes have no randomness: faulty node `id` accepts in trial `t`
+// exactly when `t + id` is even. Neighbouring faulty nodes therefore vote
+// differently within a trial, each one flips between trials, and two runs
+// of the same grid produce identical summaries.
+
+/// Column names for `SimulationSummary::to_csv_row`, in order.
+pub const SUMMARY_CSV_HEADER: &str =
+    "nodes,faulty,trials,consensus_trials,avg_yes_fraction,byzantine_safe";
+
+/// Aggregate outcome of one configuration over all its trials.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationSummary {
+    pub num_nodes: usize,
+    pub num_faulty: usize,
+    pub trials: u32,
+    /// Trials in which the majority voted yes.
+    pub consensus_trials: u32,
+    /// Mean over trials of yes votes / total votes (0.0 with no votes).
+    pub avg_yes_fraction: f64,
+    /// What `is_byzantine_safe` says about this configuration.
+    pub byzantine_safe: bool,
+}
+
+impl SimulationSummary {
+    /// Fraction of trials that reached consensus (0.0 for zero trials).
+    pub fn consensus_rate(&self) -> f64 {
+        if self.trials == 0 {
+            0.0
+        } else {
+            f64::from(self.consensus_trials) / f64::from(self.trials)
+        }
+    }
+
+    /// One CSV line (no trailing newline) matching `SUMMARY_CSV_HEADER`.
+    /// The fraction is printed with 4 decimals so output is stable.
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{:.4},{}",
+            self.num_nodes,
+            self.num_faulty,
+            self.trials,
+            self.consensus_trials,
+            self.avg_yes_fraction,
+            self.byzantine_safe
+        )
+    }
+}
+
+/// Header plus one row per summary, each line ending in `\n`.
+pub fn summaries_to_csv(summaries: &[SimulationSummary]) -> String {
+    let mut csv = String::from(SUMMARY_CSV_HEADER);
+    csv.push('\n');
+    for summary in summaries {
+        csv.push_str(&summary.to_csv_row());
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Runs `ConsensusCoordinator` over a grid of configurations.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimulationHarness;
+
+impl SimulationHarness {
+    pub fn new() -> Self {
+        SimulationHarness
+    }
+
+    /// The nodes for one trial: ids `0..num_faulty` are faulty, the rest
+    /// honest. Faulty node `id` accepts when `trial + id` is even.
+    pub fn nodes_for_trial(num_nodes: usize, num_faulty: usize, trial: u32) -> Vec<Node> {
+        (0..num_nodes)
+            .map(|id| {
+                if id < num_faulty {
+                    Node::new_faulty(id, (trial as usize + id) % 2 == 0)
+                } else {
+                    Node::new(id, NodeType::Honest)
+                }
+            })
+            .collect()
+    }
+
+    /// Run `trials` rounds of one configuration and summarize them.
+    /// The trial index doubles as the round number.
+    pub fn run_config(
+        &self,
+        num_nodes: usize,
+        num_faulty: usize,
+        trials: u32,
+        proposal: i32,
+    ) -> SimulationSummary {
+        let mut consensus_trials = 0;
+        let mut yes_fraction_sum = 0.0;
+
+        for trial in 0..trials {
+            let nodes = Self::nodes_for_trial(num_nodes, num_faulty, trial);
+            let result = ConsensusCoordinator::new(trial, num_nodes, proposal).run(nodes);
+            if result.consensus_reached {
+                consensus_trials += 1;
+            }
+            if result.total_votes > 0 {
+                yes_fraction_sum += result.yes_votes as f64 / result.total_votes as f64;
+            }
+        }
+
+        SimulationSummary {
+            num_nodes,
+            num_faulty,
+            trials,
+            consensus_trials,
+            avg_yes_fraction: if trials == 0 {
+                0.0
+            } else {
+                yes_fraction_sum / f64::from(trials)
+            },
+            byzantine_safe: is_byzantine_safe(num_nodes, num_faulty),
+        }
+    }
+
+    /// One summary per (node count, faulty count) pair, node counts in the
+    /// outer loop. Pairs with more faulty nodes than nodes are skipped.
+    pub fn run_grid(
+        &self,
+        node_counts: &[usize],
+        faulty_counts: &[usize],
+        trials: u32,
+        proposal: i32,
+    ) -> Vec<SimulationSummary> {
+        let mut summaries = Vec::new();
+        for &num_nodes in node_counts {
+            for &num_faulty in faulty_counts {
+                if num_faulty <= num_nodes {
+                    summaries.push(self.run_config(num_nodes, num_faulty, trials, proposal));
+                }
+            }
+        }
+        summaries
+    }
+}
+
+// ============================================================================
+// DURABLE VOTES: SURVIVING A CRASH
+// ============================================================================
+// Safety in voting protocols rests on one promise: a node votes at most once
+// per round. If a node votes "accept 42" in round 3, crashes, restarts with
+// empty memory, and is then asked about 43 in round 3, it could happily vote
+// again, and two different values might each collect a majority.
+//
+// Real nodes prevent this by writing every vote to disk *before* sending it
+// (a write-ahead log). `VoteLog` models that journal in memory. The
+// `persist` callback stands in for the disk write; tests hand it a closure
+// that copies records somewhere they control, "crash" by dropping the log,
+// optionally truncate what was saved, and restart with `VoteLog::recover`.
+//
+// `Node::process_proposal_durable` checks the log before deciding:
+// - same round, same value  -> repeat the recorded vote (idempotent retry)
+// - same round, other value -> `VoteError::ConflictingProposal`
+// - new round               -> decide, persist, then answer
+
+/// One vote as written to the journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoteRecord {
+    pub round: u32,
+    pub value: i32,
+    pub accept: bool,
+}
+
+/// Why a node refused to vote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoteError {
+    /// The node already voted on `voted_value` in `round`.
+    ConflictingProposal {
+        round: u32,
+        voted_value: i32,
+        proposed_value: i32,
+    },
+}
+
+impl std::fmt::Display for VoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VoteError::ConflictingProposal {
+                round,
+                voted_value,
+                proposed_value,
+            } => write!(
+                f,
+                "round {}: already voted on {}, refusing {}",
+                round, voted_value, proposed_value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VoteError {}
+
+/// Called with each record as it is appended, before the vote is returned.
+pub type PersistFn = Box<dyn FnMut(&VoteRecord) + Send>;
+
+/// A node's journal of votes, at most one per round.
+#[derive(Default)]
+pub struct VoteLog {
+    entries: Vec<VoteRecord>,
+    by_round: HashMap<u32, usize>,
+    persist: Option<PersistFn>,
+}
+
+impl VoteLog {
+    /// An empty log that persists nowhere.
+    pub fn new() -> Self {
+        VoteLog::default()
+    }
+
+    /// An empty log that hands every new record to `persist`.
+    pub fn with_persist(persist: impl FnMut(&VoteRecord) + Send + 'static) -> Self {
+        VoteLog {
+            persist: Some(Box::new(persist)),
+            ..VoteLog::default()
+        }
+    }
+
+    /// Rebuild a log from records saved before a crash.
+    ///
+    /// If the saved records somehow hold two votes for one round, the first
+    /// wins: that's the vote other nodes may already have seen.
+    pub fn recover(entries: Vec<VoteRecord>) -> Self {
+        let mut log = VoteLog::new();
+        for record in entries {
+            if !log.by_round.contains_key(&record.round) {
+                log.by_round.insert(record.round, log.entries.len());
+                log.entries.push(record);
+            }
+        }
+        log
+    }
+
+    /// Like `recover`, but new records are persisted with `persist`.
+    pub fn recover_with_persist(
+        entries: Vec<VoteRecord>,
+        persist: impl FnMut(&VoteRecord) + Send + 'static,
+    ) -> Self {
+        VoteLog {
+            persist: Some(Box::new(persist)),
+            ..VoteLog::recover(entries)
+        }
+    }
+
+    /// The recorded vote for `round`, if any.
+    pub fn vote_for(&self, round: u32) -> Option<&VoteRecord> {
+        self.by_round.get(&round).map(|&i| &self.entries[i])
+    }
+
+    /// Every record in the order it was written.
+    pub fn entries(&self) -> &[VoteRecord] {
+        &self.entries
+    }
+
+    /// Journal a vote for a round that has none yet, persisting it first.
+    fn append(&mut self, record: VoteRecord) {
+        if let Some(persist) = self.persist.as_mut() {
+            persist(&record);
+        }
+        self.by_round.insert(record.round, self.entries.len());
+        self.entries.push(record);
+    }
+}
+
+impl Node {
+    /// `process_proposal`, but at most one vote per round, remembered in `log`.
+    ///
+    /// Asking again about the same round and value returns the recorded vote
+    /// without re-evaluating it; a different value in a round that already
+    /// has a vote is refused.
+    pub fn process_proposal_durable(
+        &mut self,
+        round: u32,
+        value: i32,
+        log: &mut VoteLog,
+    ) -> Result<bool, VoteError> {
+        if let Some(previous) = log.vote_for(round) {
+            if previous.value != value {
+                return Err(VoteError::ConflictingProposal {
+                    round,
+                    voted_value: previous.value,
+                    proposed_value: value,
+                });
+            }
+            return Ok(previous.accept);
+        }
+
+        let accept = self.process_proposal(round, value);
+        log.append(VoteRecord {
+            round,
+            value,
+            accept,
+        });
+        Ok(accept)
+    }
+}
+
 // ============================================================================
 // WHAT RUST DOES UNDER THE HOOD
 // ============================================================================
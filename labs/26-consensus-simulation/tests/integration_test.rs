@@ -9,10 +9,16 @@
 // - Byzantine fault tolerance calculations
 // - Message types and structure
 // - Edge cases (single node, all faulty, boundary values)
+// - PBFT three-phase commit (quorums, equivocation, message counts)
+// - Simulation harness over a grid of configurations, with CSV output
+// - Durable vote log: idempotent retries, conflicts, crash recovery
 
 use consensus_simulation::solution::{
-    byzantine_tolerance, is_byzantine_safe, ConsensusCoordinator, Message, Node, NodeType,
+    byzantine_tolerance, is_byzantine_safe, summaries_to_csv, ConsensusCoordinator, FaultyBehavior,
+    Message, Node, NodeType, PbftSimulation, SimulationHarness, VoteError, VoteLog, VoteRecord,
+    SUMMARY_CSV_HEADER,
 };
+use std::sync::{Arc, Mutex};
 
 // ============================================================================
 // HELPER FUNCTIONS
@@ -721,3 +727,358 @@ fn test_faulty_nodes_on_invalid_boundary_value() {
     assert!(!result.consensus_reached);
     assert_eq!(result.yes_votes, 1);
 }
+
+// ============================================================================
+// PBFT THREE-PHASE COMMIT TESTS
+// ============================================================================
+
+/// Creates `total` nodes where the ids in `faulty` are faulty.
+fn make_pbft_nodes(total: usize, faulty: &[usize]) -> Vec<Node> {
+    (0..total)
+        .map(|id| {
+            if faulty.contains(&id) {
+                Node::new_faulty(id, true)
+            } else {
+                Node::new(id, NodeType::Honest)
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_pbft_all_honest_commits() {
+    let result = PbftSimulation::new(0, 42).run(make_honest_nodes(4));
+
+    assert_eq!(result.quorum, 3);
+    assert_eq!(result.decided, Some(42));
+    assert_eq!(result.committed, vec![(0, 42), (1, 42), (2, 42), (3, 42)]);
+    assert!(result.safety_held);
+}
+
+#[test]
+fn test_pbft_four_nodes_one_faulty_commits() {
+    for behavior in [FaultyBehavior::Silent, FaultyBehavior::Equivocate] {
+        let result = PbftSimulation::new(0, 42)
+            .with_faulty_behavior(behavior)
+            .run(make_pbft_nodes(4, &[3]));
+
+        assert_eq!(result.decided, Some(42), "{:?}", behavior);
+        assert_eq!(
+            result.committed,
+            vec![(0, 42), (1, 42), (2, 42)],
+            "{:?}",
+            behavior
+        );
+        assert!(result.safety_held);
+    }
+}
+
+#[test]
+fn test_pbft_four_nodes_two_faulty_does_not_commit() {
+    let result = PbftSimulation::new(0, 42).run(make_pbft_nodes(4, &[2, 3]));
+
+    assert!(result.committed.is_empty());
+    assert_eq!(result.decided, None);
+    // Nothing was committed, so nothing conflicting was either.
+    assert!(result.safety_held);
+}
+
+#[test]
+fn test_pbft_two_faulty_equivocating_breaks_safety() {
+    // Faulty leader 0 and faulty node 1 tell node 2 "42" and node 3 "43".
+    // With f = 1 but two liars, each honest node gathers a quorum for a
+    // different value.
+    let result = PbftSimulation::new(0, 42)
+        .with_faulty_behavior(FaultyBehavior::Equivocate)
+        .run(make_pbft_nodes(4, &[0, 1]));
+
+    assert_eq!(result.committed, vec![(2, 42), (3, 43)]);
+    assert!(!result.safety_held);
+    assert_eq!(result.decided, None);
+}
+
+#[test]
+fn test_pbft_equivocating_leader_cannot_split_honest_nodes() {
+    // The faulty leader sends 42 to node 2 and 43 to nodes 1 and 3. Only
+    // one of those values can reach a quorum among 3 honest nodes.
+    let result = PbftSimulation::new(0, 42)
+        .with_faulty_behavior(FaultyBehavior::Equivocate)
+        .run(make_pbft_nodes(4, &[0]));
+
+    assert!(result.safety_held);
+    assert_eq!(result.committed, vec![(1, 43), (3, 43)]);
+    assert_eq!(result.decided, Some(43));
+}
+
+#[test]
+fn test_pbft_honest_nodes_reject_invalid_value() {
+    let result = PbftSimulation::new(0, 5000).run(make_honest_nodes(4));
+
+    assert_eq!(result.pre_prepare_messages, 3);
+    assert_eq!(result.prepare_messages, 0);
+    assert!(result.committed.is_empty());
+}
+
+#[test]
+fn test_pbft_message_counts_are_quadratic() {
+    for n in [1, 4, 7, 10] {
+        let result = PbftSimulation::new(0, 42).run(make_honest_nodes(n));
+
+        assert_eq!(result.pre_prepare_messages, n - 1);
+        assert_eq!(result.prepare_messages, n * (n - 1));
+        assert_eq!(result.commit_messages, n * (n - 1));
+        assert_eq!(result.total_messages(), (n - 1) * (2 * n + 1));
+        assert_eq!(result.committed.len(), n);
+    }
+}
+
+#[test]
+fn test_pbft_silent_nodes_send_nothing() {
+    let result = PbftSimulation::new(0, 42).run(make_pbft_nodes(4, &[3]));
+
+    // Three honest nodes each prepare and commit to the other three.
+    assert_eq!(result.prepare_messages, 9);
+    assert_eq!(result.commit_messages, 9);
+}
+
+#[test]
+fn test_pbft_leader_rotates_with_view() {
+    // View 1 makes node 1 the leader; it is silent, so nothing happens.
+    let result = PbftSimulation::new(1, 42).run(make_pbft_nodes(4, &[1]));
+    assert_eq!(result.pre_prepare_messages, 0);
+    assert!(result.committed.is_empty());
+
+    // View 2 hands leadership to an honest node and the round succeeds.
+    let result = PbftSimulation::new(2, 42).run(make_pbft_nodes(4, &[1]));
+    assert_eq!(result.decided, Some(42));
+}
+
+#[test]
+fn test_pbft_dead_node_is_reported_not_awaited() {
+    // The second node 0 takes over the first one's inbox, so the first
+    // thread ends straight away and never answers a step
+    let mut nodes = make_honest_nodes(4);
+    nodes.insert(0, Node::new(0, NodeType::Honest));
+    let result = PbftSimulation::new(0, 42).run(nodes);
+
+    assert_eq!(result.failed_nodes, vec![0]);
+    assert_eq!(result.decided, Some(42));
+    assert!(result.safety_held);
+}
+
+#[test]
+fn test_pbft_no_nodes() {
+    let result = PbftSimulation::new(0, 42).run(Vec::new());
+    assert_eq!(result.total_messages(), 0);
+    assert_eq!(result.decided, None);
+}
+
+// ============================================================================
+// SIMULATION HARNESS TESTS
+// ============================================================================
+
+#[test]
+fn test_harness_zero_faulty_always_reaches_consensus() {
+    let summaries = SimulationHarness::new().run_grid(&[1, 4, 7], &[0], 5, 42);
+    assert_eq!(summaries.len(), 3);
+    for summary in &summaries {
+        assert_eq!(summary.consensus_trials, 5);
+        assert_eq!(summary.consensus_rate(), 1.0);
+        assert_eq!(summary.avg_yes_fraction, 1.0);
+        assert!(summary.byzantine_safe);
+    }
+}
+
+#[test]
+fn test_harness_flags_configs_beyond_bft_tolerance() {
+    let summaries = SimulationHarness::new().run_grid(&[4], &[1, 2, 4], 4, 42);
+    let safety: Vec<(usize, bool)> = summaries
+        .iter()
+        .map(|s| (s.num_faulty, s.byzantine_safe))
+        .collect();
+    assert_eq!(safety, vec![(1, true), (2, false), (4, false)]);
+
+    // All four faulty: two accept in every trial, 2/4 is not a majority
+    let all_faulty = &summaries[2];
+    assert_eq!(all_faulty.consensus_trials, 0);
+    assert_eq!(all_faulty.avg_yes_fraction, 0.5);
+}
+
+#[test]
+fn test_harness_faulty_votes_alternate_by_trial() {
+    let votes = |trial| -> Vec<bool> {
+        SimulationHarness::nodes_for_trial(3, 2, trial)
+            .iter()
+            .map(|n| n.process_proposal(trial, 42))
+            .collect()
+    };
+    assert_eq!(votes(0), vec![true, false, true]);
+    assert_eq!(votes(1), vec![false, true, true]);
+    assert_eq!(votes(2), votes(0));
+
+    // Honest nodes reject an invalid proposal, so only faulty yes votes count
+    let summary = SimulationHarness::new().run_config(3, 2, 2, 5000);
+    assert_eq!(summary.consensus_trials, 0);
+    assert!((summary.avg_yes_fraction - 1.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_harness_skips_impossible_configs() {
+    let summaries = SimulationHarness::new().run_grid(&[2, 3], &[0, 3], 1, 42);
+    let configs: Vec<(usize, usize)> = summaries
+        .iter()
+        .map(|s| (s.num_nodes, s.num_faulty))
+        .collect();
+    assert_eq!(configs, vec![(2, 0), (3, 0), (3, 3)]);
+}
+
+#[test]
+fn test_harness_csv_columns_match_header() {
+    let summaries = SimulationHarness::new().run_grid(&[4, 5], &[0, 1, 2], 3, 42);
+    let csv = summaries_to_csv(&summaries);
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines[0], SUMMARY_CSV_HEADER);
+    assert_eq!(lines.len(), summaries.len() + 1);
+    let columns = SUMMARY_CSV_HEADER.split(',').count();
+    for line in &lines[1..] {
+        assert_eq!(line.split(',').count(), columns, "{}", line);
+    }
+    assert_eq!(lines[1], "4,0,3,3,1.0000,true");
+    assert!(csv.ends_with('\n'));
+    assert_eq!(summaries_to_csv(&[]), format!("{}\n", SUMMARY_CSV_HEADER));
+}
+
+#[test]
+fn test_harness_runs_are_deterministic() {
+    let harness = SimulationHarness::new();
+    let first = harness.run_grid(&[3, 4, 6], &[0, 1, 2, 3], 6, 42);
+    let second = harness.run_grid(&[3, 4, 6], &[0, 1, 2, 3], 6, 42);
+    assert_eq!(first, second);
+    assert_eq!(summaries_to_csv(&first), summaries_to_csv(&second));
+}
+
+// ============================================================================
+// DURABLE VOTE LOG TESTS
+// ============================================================================
+
+/// A log whose persisted records land in the returned "disk".
+fn log_with_disk() -> (VoteLog, Arc<Mutex<Vec<VoteRecord>>>) {
+    let disk = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&disk);
+    let log = VoteLog::with_persist(move |record| sink.lock().unwrap().push(*record));
+    (log, disk)
+}
+
+#[test]
+fn test_durable_vote_is_idempotent() {
+    let mut node = Node::new_faulty(0, true);
+    let (mut log, disk) = log_with_disk();
+
+    assert_eq!(node.process_proposal_durable(1, 42, &mut log), Ok(true));
+    // Even if the node's mood changes, a retry gets the recorded answer
+    node.faulty_accepts = false;
+    assert_eq!(node.process_proposal_durable(1, 42, &mut log), Ok(true));
+
+    assert_eq!(log.entries().len(), 1);
+    assert_eq!(
+        *disk.lock().unwrap(),
+        [VoteRecord {
+            round: 1,
+            value: 42,
+            accept: true
+        }]
+    );
+}
+
+#[test]
+fn test_durable_vote_rejects_conflicting_proposal() {
+    let mut node = Node::new(0, NodeType::Honest);
+    let mut log = VoteLog::new();
+
+    assert_eq!(node.process_proposal_durable(3, 5000, &mut log), Ok(false));
+    let err = node.process_proposal_durable(3, 42, &mut log).unwrap_err();
+    assert_eq!(
+        err,
+        VoteError::ConflictingProposal {
+            round: 3,
+            voted_value: 5000,
+            proposed_value: 42
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "round 3: already voted on 5000, refusing 42"
+    );
+    assert_eq!(log.vote_for(3).map(|r| r.value), Some(5000));
+}
+
+#[test]
+fn test_recovery_preserves_the_vote() {
+    let mut node = Node::new(0, NodeType::Honest);
+    let (mut log, disk) = log_with_disk();
+    node.process_proposal_durable(1, 10, &mut log).unwrap();
+    node.process_proposal_durable(2, 20, &mut log).unwrap();
+
+    // Crash mid-round 2: memory is gone, the disk survives
+    drop(log);
+    let saved = disk.lock().unwrap().clone();
+    let mut node = Node::new(0, NodeType::Honest);
+    let mut log = VoteLog::recover(saved);
+
+    assert_eq!(node.process_proposal_durable(2, 20, &mut log), Ok(true));
+    assert!(matches!(
+        node.process_proposal_durable(2, 21, &mut log),
+        Err(VoteError::ConflictingProposal { round: 2, .. })
+    ));
+    assert_eq!(log.entries().len(), 2);
+}
+
+#[test]
+fn test_lost_write_lets_a_node_vote_twice() {
+    // Why persistence matters: truncate the last record as if the crash
+    // happened before it hit the disk, and the round is up for grabs again.
+    let mut node = Node::new(0, NodeType::Honest);
+    let (mut log, disk) = log_with_disk();
+    node.process_proposal_durable(1, 10, &mut log).unwrap();
+    node.process_proposal_durable(2, 20, &mut log).unwrap();
+
+    let mut saved = disk.lock().unwrap().clone();
+    saved.truncate(1);
+    let mut log = VoteLog::recover(saved);
+    assert_eq!(node.process_proposal_durable(2, 21, &mut log), Ok(true));
+    assert!(node.process_proposal_durable(1, 11, &mut log).is_err());
+}
+
+#[test]
+fn test_recover_keeps_first_vote_per_round_and_keeps_persisting() {
+    let record = |round, value| VoteRecord {
+        round,
+        value,
+        accept: true,
+    };
+    let disk = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&disk);
+    let mut log = VoteLog::recover_with_persist(vec![record(1, 10), record(1, 99)], move |r| {
+        sink.lock().unwrap().push(*r)
+    });
+    assert_eq!(log.entries(), [record(1, 10)]);
+
+    let mut node = Node::new(0, NodeType::Honest);
+    node.process_proposal_durable(2, 20, &mut log).unwrap();
+    assert_eq!(*disk.lock().unwrap(), [record(2, 20)]);
+}
+
+#[test]
+fn test_durable_rounds_are_independent() {
+    let mut node = Node::new(0, NodeType::Honest);
+    let mut log = VoteLog::new();
+    assert_eq!(node.process_proposal_durable(1, 42, &mut log), Ok(true));
+    assert_eq!(node.process_proposal_durable(2, 0, &mut log), Ok(false));
+    assert_eq!(node.process_proposal_durable(3, 42, &mut log), Ok(true));
+    assert!(log.vote_for(4).is_none());
+    assert_eq!(
+        log.entries().iter().map(|r| r.round).collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+}
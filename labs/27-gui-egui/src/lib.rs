@@ -2,14 +2,23 @@
 //!
 //! Student-facing state model API for the egui lab.
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MyApp {
+    #[serde(default)]
+    pub schema_version: u32,
     pub counter: i32,
     pub text: String,
     pub show_settings: bool,
     pub slider_value: f32,
     pub dark_mode: bool,
     pub notes: String,
+    // TODO: Older files lack these; give them serde defaults.
+    pub favorite_actions: ReorderableList<String>,
+    pub notes_history: EditHistory,
 }
 
 impl Default for MyApp {
@@ -44,6 +53,26 @@ impl MyApp {
         todo!("Append notes")
     }
 
+    pub fn set_notes(&mut self, new_text: &str) {
+        // TODO: Record the old text in `notes_history` (as a keystroke when
+        // exactly one char was inserted or removed), then replace it.
+        // `clear_notes` and `append_to_notes` should go through here too.
+        let _ = new_text;
+        todo!("Replace notes with undo support")
+    }
+
+    pub fn record_snapshot(&mut self) {
+        todo!("Record the current notes as a coalescible undo point")
+    }
+
+    pub fn undo(&mut self) -> bool {
+        todo!("Restore the previous notes")
+    }
+
+    pub fn redo(&mut self) -> bool {
+        todo!("Re-apply the last undone notes edit")
+    }
+
     pub fn character_count(&self) -> usize {
         todo!("Count characters")
     }
@@ -76,6 +105,320 @@ impl MyApp {
     pub fn slider_progress(&self) -> f32 {
         todo!("Get slider progress")
     }
+
+    pub fn favorite_labels(&self) -> Vec<&str> {
+        todo!("List favorite action labels in display order")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ItemId(pub u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveRecord {
+    pub id: ItemId,
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReorderError {
+    UnknownId(ItemId),
+    InvalidOrder,
+}
+
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum PersistError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+    Invalid(String),
+}
+
+impl std::fmt::Display for PersistError {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        todo!("Describe persistence failure")
+    }
+}
+
+impl std::error::Error for PersistError {}
+
+impl MyApp {
+    pub fn save_to(&self, _path: impl AsRef<Path>) -> Result<(), PersistError> {
+        // TODO: Write to a temp file beside `path`, then rename it over
+        // `path` so a crash never leaves a half-written file.
+        todo!("Write state as pretty JSON")
+    }
+
+    pub fn load_from(_path: impl AsRef<Path>) -> Result<MyApp, PersistError> {
+        // TODO: Parse, then migrate: version 0 files just need their
+        // version bumped; anything newer than CURRENT_SCHEMA_VERSION is
+        // an error. Then reject files whose lists or histories break their
+        // invariants (duplicate ids, out-of-range undo entries, ...).
+        todo!("Read and migrate saved state")
+    }
+
+    pub fn load_or_default(_path: impl AsRef<Path>) -> Result<MyApp, PersistError> {
+        // TODO: Only a missing file falls back to the default state.
+        todo!("Load state, defaulting on first run")
+    }
+}
+
+pub const DEFAULT_EDIT_HISTORY_DEPTH: usize = 100;
+pub const DEFAULT_COALESCE_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditHistory {
+    undo_stack: std::collections::VecDeque<String>,
+    redo_stack: Vec<String>,
+    max_depth: usize,
+    coalesce_limit: usize,
+    run_length: usize,
+}
+
+impl EditHistory {
+    pub fn new(max_depth: usize, coalesce_limit: usize) -> Self {
+        let _ = (max_depth, coalesce_limit);
+        todo!("Create an empty bounded history")
+    }
+
+    pub fn record(&mut self, current: &str, keystroke: bool) {
+        // TODO: Clear redo. Fold the keystroke into the open run if it has
+        // room, otherwise push a snapshot (evicting the oldest at the cap).
+        let _ = (current, keystroke);
+        todo!("Record an undo point")
+    }
+
+    pub fn undo(&mut self, current: &str) -> Option<String> {
+        let _ = current;
+        todo!("Pop an undo snapshot, saving current for redo")
+    }
+
+    pub fn redo(&mut self, current: &str) -> Option<String> {
+        let _ = current;
+        todo!("Pop a redo snapshot, saving current for undo")
+    }
+
+    pub fn can_undo(&self) -> bool {
+        todo!("Check for undo snapshots")
+    }
+
+    pub fn can_redo(&self) -> bool {
+        todo!("Check for redo snapshots")
+    }
+
+    pub fn undo_len(&self) -> usize {
+        todo!("Count undo snapshots")
+    }
+
+    pub fn redo_len(&self) -> usize {
+        todo!("Count redo snapshots")
+    }
+
+    pub fn clear(&mut self) {
+        todo!("Drop all snapshots")
+    }
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_EDIT_HISTORY_DEPTH, DEFAULT_COALESCE_LIMIT)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorderableList<T> {
+    items: Vec<(ItemId, T)>,
+    next_id: u64,
+    history_limit: usize,
+}
+
+impl<T> ReorderableList<T> {
+    pub fn new() -> Self {
+        todo!("Create empty list with default history limit")
+    }
+
+    pub fn with_history_limit(limit: usize) -> Self {
+        let _ = limit;
+        todo!("Create empty list with bounded history")
+    }
+
+    pub fn push(&mut self, item: T) -> ItemId {
+        // TODO: Assign the next never-reused id.
+        let _ = item;
+        todo!("Append item")
+    }
+
+    pub fn remove(&mut self, id: ItemId) -> Option<T> {
+        // TODO: Removing shifts indices, so clear the history.
+        let _ = id;
+        todo!("Remove item by id")
+    }
+
+    pub fn len(&self) -> usize {
+        todo!("Count items")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        todo!("Check for empty list")
+    }
+
+    pub fn index_of(&self, id: ItemId) -> Option<usize> {
+        let _ = id;
+        todo!("Find current index of id")
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let _ = index;
+        todo!("Get item by index")
+    }
+
+    pub fn get_by_id(&self, id: ItemId) -> Option<&T> {
+        let _ = id;
+        todo!("Get item by id")
+    }
+
+    pub fn order(&self) -> Vec<ItemId> {
+        todo!("List ids in display order")
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ItemId, &T)> {
+        self.items.iter().map(|(id, item)| (*id, item))
+    }
+
+    pub fn move_item(&mut self, id: ItemId, to_index: usize) -> Result<MoveRecord, ReorderError> {
+        // TODO: Clamp to_index to the last position; skip history for no-ops.
+        let _ = (id, to_index);
+        todo!("Move item to index")
+    }
+
+    pub fn move_relative(&mut self, id: ItemId, delta: isize) -> Result<MoveRecord, ReorderError> {
+        let _ = (id, delta);
+        todo!("Move item by delta, clamped at both ends")
+    }
+
+    pub fn swap(&mut self, id_a: ItemId, id_b: ItemId) -> Result<[MoveRecord; 2], ReorderError> {
+        let _ = (id_a, id_b);
+        todo!("Swap two items")
+    }
+
+    pub fn undo(&mut self) -> Option<Vec<MoveRecord>> {
+        todo!("Revert the most recent move or swap")
+    }
+
+    pub fn history_len(&self) -> usize {
+        todo!("Count undoable actions")
+    }
+
+    pub fn apply_order(&mut self, order: &[ItemId]) -> Result<(), ReorderError> {
+        // TODO: Validate that order is a permutation before reordering.
+        let _ = order;
+        todo!("Restore a saved order")
+    }
+}
+
+impl<T> Default for ReorderableList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Increment,
+    Decrement,
+    ResetCounter,
+    ToggleTheme,
+    ToggleSettings,
+    ClearNotes,
+    UndoNotes,
+    RedoNotes,
+}
+
+impl MyApp {
+    pub fn apply(&mut self, action: Action) {
+        // TODO: Call the matching MyApp method for each variant.
+        let _ = action;
+        todo!("Perform action")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ActionId(pub &'static str);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteCommand {
+    pub id: ActionId,
+    pub title: String,
+    pub keywords: Vec<String>,
+    pub action: Action,
+}
+
+impl PaletteCommand {
+    pub fn new(id: &'static str, title: &str, keywords: &[&str], action: Action) -> Self {
+        let _ = (id, title, keywords, action);
+        todo!("Build command")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoredCommand {
+    pub id: ActionId,
+    pub title: String,
+    pub score: i32,
+    pub positions: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaletteError {
+    UnknownAction(ActionId),
+    DuplicateAction(ActionId),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    commands: Vec<PaletteCommand>,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        todo!("Create empty palette")
+    }
+
+    pub fn with_default_commands() -> Self {
+        todo!("Register a command for every Action")
+    }
+
+    pub fn register(&mut self, command: PaletteCommand) -> Result<(), PaletteError> {
+        // TODO: Reject ids that are already registered.
+        let _ = command;
+        todo!("Register command")
+    }
+
+    pub fn commands(&self) -> &[PaletteCommand] {
+        todo!("List commands in registration order")
+    }
+
+    pub fn get(&self, id: ActionId) -> Option<&PaletteCommand> {
+        let _ = id;
+        todo!("Find command by id")
+    }
+
+    pub fn search(&self, query: &str) -> Vec<ScoredCommand> {
+        // TODO: Empty query lists everything in registration order.
+        // Otherwise match the query as a case-insensitive subsequence of
+        // the title (or a keyword) and score it: bonus for word starts and
+        // consecutive matches, penalty for gaps. Sort best first, stably.
+        let _ = query;
+        todo!("Fuzzy-search commands")
+    }
+
+    pub fn apply_action(&self, app: &mut MyApp, id: ActionId) -> Result<(), PaletteError> {
+        let _ = (app, id);
+        todo!("Run the command, or report an unknown id")
+    }
 }
 
 #[doc(hidden)]
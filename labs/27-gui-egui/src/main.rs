@@ -1,11 +1,21 @@
 //! # GUI egui Model Demo
 
-use gui_egui::solution::MyApp;
+use gui_egui::solution::{CommandPalette, MyApp};
+use std::path::PathBuf;
 
 fn main() {
     println!("=== GUI Model Demo ===\n");
 
-    let mut app = MyApp::new();
+    // A corrupted state file is reported and left on disk; we start fresh
+    // rather than refusing to run.
+    let state_path = state_file_path();
+    let mut app = match MyApp::load_or_default(&state_path) {
+        Ok(app) => app,
+        Err(err) => {
+            eprintln!("Warning: could not load {}: {}", state_path.display(), err);
+            MyApp::new()
+        }
+    };
     app.increment();
     app.increment();
     app.decrement();
@@ -16,4 +26,51 @@ fn main() {
     println!("theme: {}", app.theme_name());
     println!("slider: {} ({:.2})", app.slider_value, app.slider_progress());
     println!("notes: {} chars, {} words", app.character_count(), app.word_count());
+
+    let settings = app.favorite_actions.order()[3];
+    let record = app
+        .favorite_actions
+        .move_item(settings, 0)
+        .expect("known id");
+    println!(
+        "favorites: {:?} (moved {:?})",
+        app.favorite_labels(),
+        record
+    );
+    app.favorite_actions.undo();
+    println!("after undo: {:?}", app.favorite_labels());
+
+    app.clear_notes();
+    for word in ["Hello", "Hello,", "Hello, notes"] {
+        app.set_notes(word);
+    }
+    app.undo();
+    println!("notes after undo: {:?}", app.notes);
+    app.redo();
+    println!("notes after redo: {:?}", app.notes);
+
+    let palette = CommandPalette::with_default_commands();
+    for query in ["tgt", "dark", "inc"] {
+        let ranked: Vec<(String, i32)> = palette
+            .search(query)
+            .into_iter()
+            .map(|r| (r.title, r.score))
+            .collect();
+        println!("palette {:?}: {:?}", query, ranked);
+    }
+    if let Some(best) = palette.search("tgt").first() {
+        palette
+            .apply_action(&mut app, best.id)
+            .expect("search returns registered ids");
+        println!("ran {:?}, theme is now {}", best.title, app.theme_name());
+    }
+
+    match app.save_to(&state_path) {
+        Ok(()) => println!("state saved to {}", state_path.display()),
+        Err(err) => eprintln!("Error: could not save state: {}", err),
+    }
+}
+
+fn state_file_path() -> PathBuf {
+    std::env::temp_dir().join("gui-egui-demo-state.json")
 }
@@ -27,6 +27,13 @@
 // All String fields own heap-allocated UTF-8 data.
 // Primitive fields (i32, f32, bool) live entirely on the stack.
 
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
 // ============================================================================
 // APPLICATION STATE
 // ============================================================================
@@ -40,8 +47,17 @@
 /// This pattern (state struct + methods) is the recommended way to
 /// architect egui applications. Business logic lives here in lib.rs,
 /// and rendering lives in the `eframe::App::update()` impl in main.rs.
-#[derive(Debug, Clone)]
+///
+/// Everything here is plain data, so the whole struct is serialized as-is
+/// by `save_to`. Fields added after the first file format carry
+/// `#[serde(default)]` so older files still load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MyApp {
+    /// Version of the on-disk format this state was written with. Files
+    /// from before versioning have no such field and read as 0.
+    #[serde(default)]
+    pub schema_version: u32,
+
     /// Counter value for the increment/decrement demo.
     pub counter: i32,
 
@@ -59,6 +75,14 @@ pub struct MyApp {
 
     /// Multi-line notepad content.
     pub notes: String,
+
+    /// User-ordered shortcut list shown in the toolbar; drag to reorder.
+    #[serde(default = "default_favorite_actions")]
+    pub favorite_actions: ReorderableList<String>,
+
+    /// Undo/redo snapshots of `notes`.
+    #[serde(default)]
+    pub notes_history: EditHistory,
 }
 
 impl Default for MyApp {
@@ -68,6 +92,7 @@ impl Default for MyApp {
     /// is used by eframe to initialize the app state.
     fn default() -> Self {
         Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
             counter: 0,
             text: String::from("Type something here..."),
             show_settings: false,
@@ -76,10 +101,23 @@ impl Default for MyApp {
             notes: String::from(
                 "This is a simple notepad.\nYou can edit this text.\n\nTry the buttons below!",
             ),
+            favorite_actions: default_favorite_actions(),
+            notes_history: EditHistory::default(),
         }
     }
 }
 
+/// Toolbar shortcuts in their out-of-the-box order.
+pub const DEFAULT_FAVORITE_ACTIONS: [&str; 4] =
+    ["Increment", "Toggle theme", "Clear notes", "Open settings"];
+
+fn default_favorite_actions() -> ReorderableList<String> {
+    DEFAULT_FAVORITE_ACTIONS
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
 // ============================================================================
 // COUNTER OPERATIONS
 // ============================================================================
@@ -117,21 +155,78 @@ impl MyApp {
     // TEXT / NOTES OPERATIONS
     // ========================================================================
 
-    /// Clears all notes content.
+    /// Clears all notes content. Undoable.
     ///
     /// This sets the notes string to empty without deallocating the
     /// underlying buffer. The capacity is preserved for efficiency --
     /// the user will likely type new content immediately.
     pub fn clear_notes(&mut self) {
-        self.notes.clear();
+        self.apply_notes("", false);
     }
 
     /// Appends text to the notes, preceded by a newline if notes is non-empty.
+    /// Undoable.
     pub fn append_to_notes(&mut self, text: &str) {
-        if !self.notes.is_empty() {
-            self.notes.push('\n');
+        let mut new_text = self.notes.clone();
+        if !new_text.is_empty() {
+            new_text.push('\n');
+        }
+        new_text.push_str(text);
+        self.apply_notes(&new_text, false);
+    }
+
+    /// Replaces the notes, recording the old text for undo.
+    ///
+    /// This is what the GUI calls with the `TextEdit` buffer after each
+    /// change. Single-character edits (typing or backspacing one char) are
+    /// coalesced, so undo removes a burst of typing rather than one letter.
+    pub fn set_notes(&mut self, new_text: &str) {
+        let keystroke = is_single_char_edit(&self.notes, new_text);
+        self.apply_notes(new_text, keystroke);
+    }
+
+    /// Records the current notes as an undo point, for frontends that edit
+    /// `notes` in place instead of calling `set_notes`.
+    ///
+    /// Call it before applying each change. Consecutive calls coalesce like
+    /// keystrokes do in `set_notes`.
+    pub fn record_snapshot(&mut self) {
+        self.notes_history.record(&self.notes, true);
+    }
+
+    /// Restores the notes as they were before the last edit. Returns
+    /// `false` when there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.notes_history.undo(&self.notes) {
+            Some(previous) => {
+                self.notes = previous;
+                true
+            }
+            None => false,
         }
-        self.notes.push_str(text);
+    }
+
+    /// Re-applies the last undone edit. Returns `false` when there is
+    /// nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.notes_history.redo(&self.notes) {
+            Some(next) => {
+                self.notes = next;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Every notes mutation funnels through here so none of them can skip
+    // the history. Unchanged text is not an edit and records nothing.
+    fn apply_notes(&mut self, new_text: &str, keystroke: bool) {
+        if self.notes == new_text {
+            return;
+        }
+        self.notes_history.record(&self.notes, keystroke);
+        self.notes.clear();
+        self.notes.push_str(new_text);
     }
 
     /// Returns the number of characters in the notes.
@@ -218,6 +313,996 @@ impl MyApp {
     pub fn slider_progress(&self) -> f32 {
         self.slider_value / 100.0
     }
+
+    // ========================================================================
+    // FAVORITE ACTIONS
+    // ========================================================================
+
+    /// Favorite action labels in their current (user-chosen) order.
+    pub fn favorite_labels(&self) -> Vec<&str> {
+        self.favorite_actions
+            .iter()
+            .map(|(_, label)| label.as_str())
+            .collect()
+    }
+}
+
+// ============================================================================
+// PERSISTENCE
+// ============================================================================
+// State is saved as pretty JSON so it can be inspected (and hand-edited)
+// between runs. The format is versioned: `schema_version` is written into
+// every file, and `migrate` brings older files up to date after serde has
+// filled any missing fields with their defaults.
+//
+// Version history:
+// - 0: counter, text, show_settings, slider_value, dark_mode, notes
+// - 1: adds schema_version, favorite_actions, notes_history
+
+/// The on-disk format version written by `save_to`.
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// Errors from saving or loading `MyApp` state.
+#[derive(Debug)]
+pub enum PersistError {
+    /// The file couldn't be read or written.
+    Io(io::Error),
+    /// The file isn't valid app state (malformed JSON or wrong shape).
+    Json(serde_json::Error),
+    /// The file was written by a newer version of the app.
+    UnsupportedVersion(u32),
+    /// The file parsed, but its contents break an invariant (duplicate
+    /// ids, out-of-range undo history, ...). Loading it would risk a panic
+    /// later, so it is refused up front.
+    Invalid(String),
+}
+
+impl From<io::Error> for PersistError {
+    fn from(err: io::Error) -> Self {
+        PersistError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for PersistError {
+    fn from(err: serde_json::Error) -> Self {
+        PersistError::Json(err)
+    }
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistError::Io(err) => write!(f, "I/O error: {}", err),
+            PersistError::Json(err) => write!(f, "invalid state file: {}", err),
+            PersistError::UnsupportedVersion(version) => write!(
+                f,
+                "state file has schema version {}, newest supported is {}",
+                version, CURRENT_SCHEMA_VERSION
+            ),
+            PersistError::Invalid(reason) => write!(f, "inconsistent state file: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PersistError::Io(err) => Some(err),
+            PersistError::Json(err) => Some(err),
+            PersistError::UnsupportedVersion(_) | PersistError::Invalid(_) => None,
+        }
+    }
+}
+
+impl MyApp {
+    /// Writes the whole state to `path` as pretty-printed JSON.
+    ///
+    /// The JSON goes to a temporary file next to `path` that is then
+    /// renamed over it. A rename within one directory is atomic, so a crash
+    /// mid-save leaves either the old file or the new one, never half of
+    /// each.
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), PersistError> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)?;
+
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp = path.with_file_name(tmp_name);
+        let result = (|| {
+            let mut file = fs::File::create(&tmp)?;
+            file.write_all(json.as_bytes())?;
+            // Make sure the bytes are on disk before the rename publishes them.
+            file.sync_all()?;
+            fs::rename(&tmp, path)
+        })();
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        result?;
+        Ok(())
+    }
+
+    /// Reads state saved by `save_to`, upgrading older formats.
+    pub fn load_from(path: impl AsRef<Path>) -> Result<MyApp, PersistError> {
+        let json = fs::read_to_string(path)?;
+        let app: MyApp = serde_json::from_str(&json)?;
+        app.migrate()
+    }
+
+    /// Like `load_from`, but a missing file (first run) gives the default
+    /// state.
+    ///
+    /// Every other failure is returned: a corrupted file should be reported,
+    /// not silently replaced by defaults and then overwritten on exit.
+    pub fn load_or_default(path: impl AsRef<Path>) -> Result<MyApp, PersistError> {
+        match Self::load_from(path) {
+            Err(PersistError::Io(err)) if err.kind() == io::ErrorKind::NotFound => {
+                Ok(MyApp::default())
+            }
+            result => result,
+        }
+    }
+
+    /// Upgrades freshly deserialized state to `CURRENT_SCHEMA_VERSION`.
+    fn migrate(mut self) -> Result<MyApp, PersistError> {
+        match self.schema_version {
+            // v0 predates the favorites bar and undo history; serde has
+            // already filled both with defaults, so only the number moves.
+            0 => {}
+            CURRENT_SCHEMA_VERSION => {}
+            newer => return Err(PersistError::UnsupportedVersion(newer)),
+        }
+        // Deserialize fills fields in directly, bypassing the methods that
+        // normally keep these invariants, so a hand-edited file can break
+        // them.
+        self.favorite_actions
+            .check_invariants()
+            .map_err(|reason| PersistError::Invalid(format!("favorite_actions: {}", reason)))?;
+        self.notes_history
+            .check_invariants()
+            .map_err(|reason| PersistError::Invalid(format!("notes_history: {}", reason)))?;
+        self.schema_version = CURRENT_SCHEMA_VERSION;
+        Ok(self)
+    }
+}
+
+// ============================================================================
+// EDIT HISTORY
+// ============================================================================
+// Undo for a text box is a pair of stacks of whole-text snapshots: before
+// an edit, the old text goes onto the undo stack; undo moves the current
+// text onto the redo stack and pops the undo stack. Any new edit makes the
+// redo stack meaningless, so it is cleared.
+//
+// Snapshotting on every keystroke would make undo painfully fine-grained
+// (and fill the history with near-identical strings), so keystrokes are
+// COALESCED: the first one in a run takes a snapshot, the next
+// `coalesce_limit - 1` are folded into it. Anything bigger than a
+// keystroke, and any undo/redo, ends the run.
+
+/// Default number of snapshots `EditHistory` keeps for undo.
+pub const DEFAULT_EDIT_HISTORY_DEPTH: usize = 100;
+
+/// Default number of keystrokes folded into a single undo step.
+pub const DEFAULT_COALESCE_LIMIT: usize = 20;
+
+/// Bounded undo/redo history of text snapshots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditHistory {
+    undo_stack: VecDeque<String>,
+    redo_stack: Vec<String>,
+    max_depth: usize,
+    coalesce_limit: usize,
+    // Keystrokes in the current run; 0 means no run is open.
+    run_length: usize,
+}
+
+impl EditHistory {
+    /// Creates a history that keeps at most `max_depth` undo snapshots and
+    /// folds up to `coalesce_limit` keystrokes into each. A limit of 0 or 1
+    /// gives every keystroke its own snapshot.
+    pub fn new(max_depth: usize, coalesce_limit: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            max_depth,
+            coalesce_limit,
+            run_length: 0,
+        }
+    }
+
+    /// Records `current` (the text about to be replaced) as an undo point.
+    ///
+    /// With `keystroke` set, the call may be folded into the previous
+    /// snapshot instead; see the section comment above.
+    pub fn record(&mut self, current: &str, keystroke: bool) {
+        self.redo_stack.clear();
+
+        if keystroke && self.run_length > 0 && self.run_length < self.coalesce_limit {
+            self.run_length += 1;
+            return;
+        }
+
+        self.push_undo(current.to_string());
+        self.run_length = usize::from(keystroke);
+    }
+
+    /// Steps back: stores `current` for redo and returns the text to show.
+    pub fn undo(&mut self, current: &str) -> Option<String> {
+        let previous = self.undo_stack.pop_back()?;
+        self.redo_stack.push(current.to_string());
+        self.run_length = 0;
+        Some(previous)
+    }
+
+    /// Steps forward again: stores `current` for undo and returns the text
+    /// to show.
+    pub fn redo(&mut self, current: &str) -> Option<String> {
+        let next = self.redo_stack.pop()?;
+        self.push_undo(current.to_string());
+        self.run_length = 0;
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Number of undo steps available.
+    pub fn undo_len(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    /// Number of redo steps available.
+    pub fn redo_len(&self) -> usize {
+        self.redo_stack.len()
+    }
+
+    /// Drops all snapshots.
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.run_length = 0;
+    }
+
+    /// What `new`, `record`, `undo` and `redo` guarantee, for state that
+    /// came from somewhere else (a loaded file).
+    fn check_invariants(&self) -> Result<(), String> {
+        if self.undo_stack.len() > self.max_depth {
+            return Err(format!(
+                "{} undo snapshots, limit is {}",
+                self.undo_stack.len(),
+                self.max_depth
+            ));
+        }
+        if self.run_length > self.coalesce_limit.max(1) {
+            return Err(format!(
+                "keystroke run of {}, limit is {}",
+                self.run_length, self.coalesce_limit
+            ));
+        }
+        Ok(())
+    }
+
+    fn push_undo(&mut self, snapshot: String) {
+        if self.max_depth == 0 {
+            return;
+        }
+        if self.undo_stack.len() == self.max_depth {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(snapshot);
+    }
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_EDIT_HISTORY_DEPTH, DEFAULT_COALESCE_LIMIT)
+    }
+}
+
+/// Whether `after` is `before` with exactly one char inserted or removed.
+fn is_single_char_edit(before: &str, after: &str) -> bool {
+    let (short, long) = if before.len() < after.len() {
+        (before, after)
+    } else {
+        (after, before)
+    };
+    if long.chars().count() != short.chars().count() + 1 {
+        return false;
+    }
+    // Skip the common prefix; what is left of `long` must be exactly one
+    // char followed by the rest of `short`.
+    let prefix = long
+        .char_indices()
+        .zip(short.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(short.len(), |((i, _), _)| i);
+    let mut rest = long[prefix..].chars();
+    rest.next();
+    rest.as_str() == &short[prefix..]
+}
+
+// ============================================================================
+// REORDERABLE LIST
+// ============================================================================
+// Drag-and-drop reordering looks like a UI problem, but almost all of it is
+// model logic:
+//
+// - Items need STABLE IDS. Indices change every time something moves, so
+//   "the item the user grabbed" must be identified by an id that survives
+//   reordering. egui also uses such ids to keep widget state attached to
+//   the right row.
+// - Every move produces a `MoveRecord { id, from, to }`. The GUI can use it
+//   to animate the row sliding from `from` to `to`, and the model keeps a
+//   bounded history of them so the last move can be undone.
+//
+// The list is generic over `T`, so the same code reorders todo items, tabs,
+// or (in this lab) favorite toolbar actions.
+
+/// Stable identifier assigned when an item is inserted. Never reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ItemId(pub u64);
+
+/// One item changing position: it was at index `from` and is now at `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveRecord {
+    pub id: ItemId,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Why a reorder request was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReorderError {
+    /// No item with this id is in the list.
+    UnknownId(ItemId),
+    /// `apply_order` was given something other than a permutation of the
+    /// current ids (missing, extra, or duplicated ids).
+    InvalidOrder,
+}
+
+impl fmt::Display for ReorderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReorderError::UnknownId(id) => write!(f, "no item with id {}", id.0),
+            ReorderError::InvalidOrder => write!(f, "order is not a permutation of the list"),
+        }
+    }
+}
+
+impl std::error::Error for ReorderError {}
+
+/// One user action in the undo history. A swap changes two positions at
+/// once, so it is undone as a unit rather than as two separate moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Change {
+    Move(MoveRecord),
+    Swap(MoveRecord, MoveRecord),
+}
+
+/// Default number of actions kept for undo.
+pub const DEFAULT_HISTORY_LIMIT: usize = 32;
+
+/// An ordered list whose items keep stable ids while being reordered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorderableList<T> {
+    // (id, item) pairs in display order. A Vec keeps iteration and indexed
+    // access trivial; moves are O(n), which is fine for UI-sized lists.
+    items: Vec<(ItemId, T)>,
+    next_id: u64,
+    history: VecDeque<Change>,
+    history_limit: usize,
+}
+
+impl<T> ReorderableList<T> {
+    /// Creates an empty list with the default history limit.
+    pub fn new() -> Self {
+        Self::with_history_limit(DEFAULT_HISTORY_LIMIT)
+    }
+
+    /// Creates an empty list that remembers at most `limit` actions.
+    pub fn with_history_limit(limit: usize) -> Self {
+        Self {
+            items: Vec::new(),
+            next_id: 0,
+            history: VecDeque::new(),
+            history_limit: limit,
+        }
+    }
+
+    /// Appends an item and returns its new, never-reused id.
+    ///
+    /// Appending does not shift existing indices, so history stays valid.
+    pub fn push(&mut self, item: T) -> ItemId {
+        let id = ItemId(self.next_id);
+        self.next_id += 1;
+        self.items.push((id, item));
+        id
+    }
+
+    /// Removes an item by id.
+    ///
+    /// Removal shifts indices, which would make older `MoveRecord`s lie, so
+    /// the undo history is cleared.
+    pub fn remove(&mut self, id: ItemId) -> Option<T> {
+        let index = self.index_of(id)?;
+        self.history.clear();
+        Some(self.items.remove(index).1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Current position of `id`, if present.
+    pub fn index_of(&self, id: ItemId) -> Option<usize> {
+        self.items.iter().position(|(item_id, _)| *item_id == id)
+    }
+
+    /// Item at a display position.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.items.get(index).map(|(_, item)| item)
+    }
+
+    /// Item by stable id, wherever it currently sits.
+    pub fn get_by_id(&self, id: ItemId) -> Option<&T> {
+        self.items
+            .iter()
+            .find(|(item_id, _)| *item_id == id)
+            .map(|(_, item)| item)
+    }
+
+    /// Ids in current display order.
+    pub fn order(&self) -> Vec<ItemId> {
+        self.items.iter().map(|(id, _)| *id).collect()
+    }
+
+    /// Iterates `(id, &item)` pairs in current display order.
+    pub fn iter(&self) -> impl Iterator<Item = (ItemId, &T)> {
+        self.items.iter().map(|(id, item)| (*id, item))
+    }
+
+    /// Moves `id` to `to_index`. Indices past the end are clamped to the
+    /// last position (dropping "below the list" means "move to the end").
+    ///
+    /// Moving an item onto its own position is reported but not recorded,
+    /// so undo never "undoes" a no-op.
+    pub fn move_item(&mut self, id: ItemId, to_index: usize) -> Result<MoveRecord, ReorderError> {
+        let from = self.index_of(id).ok_or(ReorderError::UnknownId(id))?;
+        let to = to_index.min(self.items.len() - 1);
+        let record = MoveRecord { id, from, to };
+        if from != to {
+            let entry = self.items.remove(from);
+            self.items.insert(to, entry);
+            self.record(Change::Move(record));
+        }
+        Ok(record)
+    }
+
+    /// Moves `id` by `delta` positions (negative = towards the front),
+    /// clamped at both ends.
+    pub fn move_relative(&mut self, id: ItemId, delta: isize) -> Result<MoveRecord, ReorderError> {
+        let from = self.index_of(id).ok_or(ReorderError::UnknownId(id))?;
+        let target = (from as isize).saturating_add(delta).max(0) as usize;
+        self.move_item(id, target)
+    }
+
+    /// Exchanges the positions of two items. Returns one record per item.
+    pub fn swap(&mut self, id_a: ItemId, id_b: ItemId) -> Result<[MoveRecord; 2], ReorderError> {
+        let a = self.index_of(id_a).ok_or(ReorderError::UnknownId(id_a))?;
+        let b = self.index_of(id_b).ok_or(ReorderError::UnknownId(id_b))?;
+        let records = [
+            MoveRecord {
+                id: id_a,
+                from: a,
+                to: b,
+            },
+            MoveRecord {
+                id: id_b,
+                from: b,
+                to: a,
+            },
+        ];
+        if a != b {
+            self.items.swap(a, b);
+            self.record(Change::Swap(records[0], records[1]));
+        }
+        Ok(records)
+    }
+
+    /// Reverts the most recent move or swap, returning the records that
+    /// describe the reverting motion (handy for animating the undo too).
+    pub fn undo(&mut self) -> Option<Vec<MoveRecord>> {
+        match self.history.pop_back()? {
+            Change::Move(m) => {
+                let entry = self.items.remove(m.to);
+                self.items.insert(m.from, entry);
+                Some(vec![MoveRecord {
+                    id: m.id,
+                    from: m.to,
+                    to: m.from,
+                }])
+            }
+            Change::Swap(a, b) => {
+                self.items.swap(a.from, b.from);
+                Some(vec![
+                    MoveRecord {
+                        id: a.id,
+                        from: a.to,
+                        to: a.from,
+                    },
+                    MoveRecord {
+                        id: b.id,
+                        from: b.to,
+                        to: b.from,
+                    },
+                ])
+            }
+        }
+    }
+
+    /// Number of actions that can currently be undone.
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Rearranges the list to match a previously saved `order()`.
+    ///
+    /// `order` must contain every current id exactly once. This is how a
+    /// persisted custom order is restored; history is cleared because the
+    /// restored order is a new starting point.
+    pub fn apply_order(&mut self, order: &[ItemId]) -> Result<(), ReorderError> {
+        // Validate before touching anything so a bad order leaves the list
+        // exactly as it was.
+        let unique: HashSet<ItemId> = order.iter().copied().collect();
+        let is_permutation = order.len() == self.items.len()
+            && unique.len() == order.len()
+            && self.items.iter().all(|(id, _)| unique.contains(id));
+        if !is_permutation {
+            return Err(ReorderError::InvalidOrder);
+        }
+
+        let position: HashMap<ItemId, usize> =
+            order.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        self.items.sort_by_key(|(id, _)| position[id]);
+        self.history.clear();
+        Ok(())
+    }
+
+    /// What the list's methods guarantee, for state that came from
+    /// somewhere else (a loaded file): unique ids below `next_id`, and an
+    /// undo history that fits its limit and only points at real positions.
+    fn check_invariants(&self) -> Result<(), String> {
+        let mut seen = HashSet::new();
+        for (id, _) in &self.items {
+            if !seen.insert(*id) {
+                return Err(format!("duplicate id {}", id.0));
+            }
+            if id.0 >= self.next_id {
+                return Err(format!("id {} is not below next_id {}", id.0, self.next_id));
+            }
+        }
+        if self.history.len() > self.history_limit {
+            return Err(format!(
+                "{} undo entries, limit is {}",
+                self.history.len(),
+                self.history_limit
+            ));
+        }
+        // Moves and swaps never change the length, so every recorded index
+        // must be valid now for undo to be safe.
+        let len = self.items.len();
+        let in_range = |m: &MoveRecord| m.from < len && m.to < len;
+        let ok = self.history.iter().all(|change| match change {
+            Change::Move(m) => in_range(m),
+            Change::Swap(a, b) => in_range(a) && in_range(b),
+        });
+        if !ok {
+            return Err(format!("undo history points past the {} items", len));
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, change: Change) {
+        if self.history_limit == 0 {
+            return;
+        }
+        if self.history.len() == self.history_limit {
+            self.history.pop_front();
+        }
+        self.history.push_back(change);
+    }
+}
+
+impl<T> Default for ReorderableList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for ReorderableList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for item in iter {
+            list.push(item);
+        }
+        list
+    }
+}
+
+impl<T> std::ops::Index<usize> for ReorderableList<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.items[index].1
+    }
+}
+
+// ============================================================================
+// COMMAND PALETTE
+// ============================================================================
+// A command palette (Ctrl+Shift+P in most editors) is a text box that finds
+// actions by typing a few letters of their name. Like drag-and-drop, nearly
+// all of it is model logic:
+//
+// - Every action the palette can run is an `Action` value, and
+//   `MyApp::apply` is the one place that turns it into a state change. The
+//   buttons in the GUI and the palette end up calling the same code.
+// - A `CommandPalette` is a registry of `PaletteCommand`s: a stable id, the
+//   title shown to the user, extra keywords, and the `Action` to run.
+// - `search` ranks commands with FUZZY SUBSEQUENCE MATCHING: "tgt" matches
+//   "Toggle theme" because t, g, t appear in that order. Many titles match
+//   most short queries, so the scoring decides what the user sees first:
+//
+//     each matched character         +1
+//     match at the start of a word   +8   ("T"oggle "t"heme)
+//     match right after the previous +5   ("tog" in "Toggle")
+//     each skipped character between -1
+//
+//   A title can usually be matched in several ways, so we pick the best-
+//   scoring one with a small dynamic program (titles are short).
+
+/// Everything a palette command can do to the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Increment,
+    Decrement,
+    ResetCounter,
+    ToggleTheme,
+    ToggleSettings,
+    ClearNotes,
+    UndoNotes,
+    RedoNotes,
+}
+
+impl MyApp {
+    /// Performs `action`. The GUI's buttons and the command palette both
+    /// route through here, so they can never disagree about what an action
+    /// does.
+    pub fn apply(&mut self, action: Action) {
+        match action {
+            Action::Increment => self.increment(),
+            Action::Decrement => self.decrement(),
+            Action::ResetCounter => self.reset_counter(),
+            Action::ToggleTheme => self.toggle_theme(),
+            Action::ToggleSettings => self.toggle_settings(),
+            Action::ClearNotes => self.clear_notes(),
+            // Nothing to undo/redo is not an error for a palette command.
+            Action::UndoNotes => {
+                self.undo();
+            }
+            Action::RedoNotes => {
+                self.redo();
+            }
+        }
+    }
+}
+
+/// Stable identifier of a palette command, e.g. `"theme.toggle"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ActionId(pub &'static str);
+
+/// One entry in the palette.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteCommand {
+    pub id: ActionId,
+    /// What the palette displays and matches against first.
+    pub title: String,
+    /// Other words that should find this command ("dark" for the theme).
+    pub keywords: Vec<String>,
+    pub action: Action,
+}
+
+impl PaletteCommand {
+    pub fn new(id: &'static str, title: &str, keywords: &[&str], action: Action) -> Self {
+        Self {
+            id: ActionId(id),
+            title: title.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            action,
+        }
+    }
+}
+
+/// A command that matched a search query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoredCommand {
+    pub id: ActionId,
+    pub title: String,
+    /// Higher is better. Only meaningful relative to other results.
+    pub score: i32,
+    /// Char indices into `title` that matched, for highlighting. Empty when
+    /// the query was empty or only a keyword matched.
+    pub positions: Vec<usize>,
+}
+
+/// Why a palette operation was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaletteError {
+    /// No command with this id is registered.
+    UnknownAction(ActionId),
+    /// A command with this id is already registered.
+    DuplicateAction(ActionId),
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaletteError::UnknownAction(id) => write!(f, "no command with id {:?}", id.0),
+            PaletteError::DuplicateAction(id) => {
+                write!(f, "a command with id {:?} is already registered", id.0)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+// Keyword matches rank below equally good title matches: the title is what
+// the user sees, so a hit there is the less surprising result.
+const KEYWORD_PENALTY: i32 = 4;
+
+/// A searchable registry of commands, kept in registration order.
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    commands: Vec<PaletteCommand>,
+}
+
+impl CommandPalette {
+    /// Creates a palette with no commands.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a palette with a command for every `Action`.
+    pub fn with_default_commands() -> Self {
+        let mut palette = Self::new();
+        let commands = [
+            PaletteCommand::new(
+                "counter.increment",
+                "Increment counter",
+                &["add", "plus"],
+                Action::Increment,
+            ),
+            PaletteCommand::new(
+                "counter.decrement",
+                "Decrement counter",
+                &["subtract", "minus"],
+                Action::Decrement,
+            ),
+            PaletteCommand::new(
+                "counter.reset",
+                "Reset counter",
+                &["zero"],
+                Action::ResetCounter,
+            ),
+            PaletteCommand::new(
+                "theme.toggle",
+                "Toggle theme",
+                &["dark", "light", "mode"],
+                Action::ToggleTheme,
+            ),
+            PaletteCommand::new(
+                "settings.toggle",
+                "Toggle settings",
+                &["preferences", "options"],
+                Action::ToggleSettings,
+            ),
+            PaletteCommand::new(
+                "notes.clear",
+                "Clear notes",
+                &["erase", "delete"],
+                Action::ClearNotes,
+            ),
+            PaletteCommand::new(
+                "notes.undo",
+                "Undo notes edit",
+                &["revert"],
+                Action::UndoNotes,
+            ),
+            PaletteCommand::new("notes.redo", "Redo notes edit", &[], Action::RedoNotes),
+        ];
+        for command in commands {
+            palette
+                .register(command)
+                .expect("default command ids are unique");
+        }
+        palette
+    }
+
+    /// Adds a command after the existing ones.
+    pub fn register(&mut self, command: PaletteCommand) -> Result<(), PaletteError> {
+        if self.get(command.id).is_some() {
+            return Err(PaletteError::DuplicateAction(command.id));
+        }
+        self.commands.push(command);
+        Ok(())
+    }
+
+    /// All commands in registration order.
+    pub fn commands(&self) -> &[PaletteCommand] {
+        &self.commands
+    }
+
+    pub fn get(&self, id: ActionId) -> Option<&PaletteCommand> {
+        self.commands.iter().find(|c| c.id == id)
+    }
+
+    /// Commands matching `query`, best first.
+    ///
+    /// An empty (or all-whitespace) query lists every command in
+    /// registration order, which is what a freshly opened palette shows.
+    /// Ties keep registration order too, so results don't shuffle between
+    /// keystrokes.
+    pub fn search(&self, query: &str) -> Vec<ScoredCommand> {
+        let query: Vec<char> = query
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect();
+
+        let mut results: Vec<ScoredCommand> = self
+            .commands
+            .iter()
+            .filter_map(|command| {
+                let (score, positions) = if query.is_empty() {
+                    (0, Vec::new())
+                } else {
+                    match_command(&query, command)?
+                };
+                Some(ScoredCommand {
+                    id: command.id,
+                    title: command.title.clone(),
+                    score,
+                    positions,
+                })
+            })
+            .collect();
+        // Stable sort: equal scores stay in registration order.
+        results.sort_by_key(|result| std::cmp::Reverse(result.score));
+        results
+    }
+
+    /// Runs the command registered as `id` against `app`.
+    ///
+    /// An unknown id leaves `app` untouched and reports the error, so a
+    /// stale id (say, from a keybinding config) can't do anything by
+    /// accident.
+    pub fn apply_action(&self, app: &mut MyApp, id: ActionId) -> Result<(), PaletteError> {
+        let command = self.get(id).ok_or(PaletteError::UnknownAction(id))?;
+        app.apply(command.action);
+        Ok(())
+    }
+}
+
+/// The better of the title match and the best keyword match.
+fn match_command(query: &[char], command: &PaletteCommand) -> Option<(i32, Vec<usize>)> {
+    let title = fuzzy_match(query, &command.title);
+    let keyword = command
+        .keywords
+        .iter()
+        .filter_map(|k| fuzzy_match(query, k))
+        .map(|(score, _)| score - KEYWORD_PENALTY)
+        .max();
+    match (title, keyword) {
+        (Some(title), Some(k)) if title.0 >= k => Some(title),
+        (Some(title), None) => Some(title),
+        (_, Some(k)) => Some((k, Vec::new())),
+        (None, None) => None,
+    }
+}
+
+const MATCH_SCORE: i32 = 1;
+const WORD_START_BONUS: i32 = 8;
+const CONSECUTIVE_BONUS: i32 = 5;
+const GAP_PENALTY: i32 = 1;
+
+/// Best score for `query` (lowercase chars) as a subsequence of `text`,
+/// with the matched char indices, or `None` if it doesn't match.
+fn fuzzy_match(query: &[char], text: &str) -> Option<(i32, Vec<usize>)> {
+    let original: Vec<char> = text.chars().collect();
+    // Lowercase char by char so indices line up with `original` ("İ"
+    // lowercases to two chars; keeping the first is fine for matching).
+    let lower: Vec<char> = original
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let (m, n) = (query.len(), lower.len());
+    if m == 0 || m > n {
+        return None;
+    }
+
+    // best[i][j]: best score matching query[..=i] with query[i] at text[j].
+    // from[i][j]: where query[i - 1] matched on that best path.
+    let mut best = vec![vec![None::<i32>; n]; m];
+    let mut from = vec![vec![0usize; n]; m];
+    for i in 0..m {
+        for j in i..n {
+            if lower[j] != query[i] {
+                continue;
+            }
+            let here = MATCH_SCORE + word_start_bonus(&original, j);
+            if i == 0 {
+                best[i][j] = Some(here);
+                continue;
+            }
+            for k in (i - 1)..j {
+                let Some(prev) = best[i - 1][k] else {
+                    continue;
+                };
+                let link = if k + 1 == j {
+                    CONSECUTIVE_BONUS
+                } else {
+                    -GAP_PENALTY * (j - k - 1) as i32
+                };
+                let score = prev + here + link;
+                if best[i][j].map_or(true, |b| score > b) {
+                    best[i][j] = Some(score);
+                    from[i][j] = k;
+                }
+            }
+        }
+    }
+
+    // Leftmost end wins ties, so positions favour earlier matches.
+    let (mut j, score) = best[m - 1]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, s)| s.map(|s| (j, s)))
+        .fold(None, |acc: Option<(usize, i32)>, (j, s)| match acc {
+            Some((_, b)) if b >= s => acc,
+            _ => Some((j, s)),
+        })?;
+    let mut positions = vec![0; m];
+    for i in (0..m).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+    Some((score, positions))
+}
+
+/// Bonus for matching at index `j`: the first char, a char after a
+/// non-alphanumeric separator, or an uppercase letter after a lowercase one
+/// (camelCase).
+fn word_start_bonus(text: &[char], j: usize) -> i32 {
+    let starts_word = match j.checked_sub(1).map(|p| text[p]) {
+        None => true,
+        Some(prev) => !prev.is_alphanumeric() || (prev.is_lowercase() && text[j].is_uppercase()),
+    };
+    if starts_word {
+        WORD_START_BONUS
+    } else {
+        0
+    }
 }
 
 // ============================================================================
@@ -4,7 +4,13 @@
 // requiring a windowing system, GPU, or display. The entire model is
 // framework-independent and fully testable.
 
-use gui_egui::solution::MyApp;
+use gui_egui::solution::{
+    Action, ActionId, CommandPalette, EditHistory, ItemId, MoveRecord, MyApp, PaletteCommand,
+    PaletteError, PersistError, ReorderError, ReorderableList, CURRENT_SCHEMA_VERSION,
+};
+use serde_json::{json, Value};
+use std::fs;
+use tempfile::tempdir;
 
 // ============================================================================
 // DEFAULT / INITIALIZATION TESTS
@@ -606,3 +612,749 @@ fn test_clear_notes_does_not_affect_text_input() {
         "Clearing notes should not affect text input field"
     );
 }
+
+// ============================================================================
+// REORDERABLE LIST TESTS
+// ============================================================================
+
+fn letters() -> (ReorderableList<&'static str>, Vec<ItemId>) {
+    let mut list = ReorderableList::new();
+    let ids = ["a", "b", "c", "d"].iter().map(|l| list.push(*l)).collect();
+    (list, ids)
+}
+
+fn contents(list: &ReorderableList<&'static str>) -> Vec<&'static str> {
+    list.iter().map(|(_, item)| *item).collect()
+}
+
+#[test]
+fn test_reorderable_ids_are_stable_and_unique() {
+    let (mut list, ids) = letters();
+    assert_eq!(list.order(), ids);
+
+    list.move_item(ids[0], 3).unwrap();
+    assert_eq!(
+        list.get_by_id(ids[0]),
+        Some(&"a"),
+        "id should follow its item"
+    );
+    assert_eq!(list.index_of(ids[0]), Some(3));
+
+    list.remove(ids[1]);
+    let new_id = list.push("e");
+    assert!(!ids.contains(&new_id), "ids must never be reused");
+}
+
+#[test]
+fn test_reorderable_move_clamps_out_of_range() {
+    let (mut list, ids) = letters();
+    let record = list.move_item(ids[1], 99).unwrap();
+
+    assert_eq!(
+        record,
+        MoveRecord {
+            id: ids[1],
+            from: 1,
+            to: 3
+        }
+    );
+    assert_eq!(contents(&list), vec!["a", "c", "d", "b"]);
+    assert_eq!(list[3], "b");
+    assert_eq!(list.get(3), Some(&"b"));
+}
+
+#[test]
+fn test_reorderable_move_relative_past_both_ends() {
+    let (mut list, ids) = letters();
+
+    let record = list.move_relative(ids[2], -10).unwrap();
+    assert_eq!(record.to, 0);
+    assert_eq!(contents(&list), vec!["c", "a", "b", "d"]);
+
+    let record = list.move_relative(ids[2], 10).unwrap();
+    assert_eq!(
+        record,
+        MoveRecord {
+            id: ids[2],
+            from: 0,
+            to: 3
+        }
+    );
+    assert_eq!(contents(&list), vec!["a", "b", "d", "c"]);
+
+    list.move_relative(ids[0], 1).unwrap();
+    assert_eq!(contents(&list), vec!["b", "a", "d", "c"]);
+}
+
+#[test]
+fn test_reorderable_unknown_id_errors() {
+    let (mut list, _) = letters();
+    let ghost = ItemId(999);
+
+    assert_eq!(
+        list.move_item(ghost, 0),
+        Err(ReorderError::UnknownId(ghost))
+    );
+    assert_eq!(
+        list.move_relative(ghost, 1),
+        Err(ReorderError::UnknownId(ghost))
+    );
+    let first = list.order()[0];
+    assert_eq!(list.swap(first, ghost), Err(ReorderError::UnknownId(ghost)));
+    assert_eq!(
+        contents(&list),
+        vec!["a", "b", "c", "d"],
+        "errors must not mutate"
+    );
+    assert_eq!(list.history_len(), 0);
+}
+
+#[test]
+fn test_reorderable_swap_and_undo() {
+    let (mut list, ids) = letters();
+    let records = list.swap(ids[0], ids[3]).unwrap();
+
+    assert_eq!(
+        records[0],
+        MoveRecord {
+            id: ids[0],
+            from: 0,
+            to: 3
+        }
+    );
+    assert_eq!(
+        records[1],
+        MoveRecord {
+            id: ids[3],
+            from: 3,
+            to: 0
+        }
+    );
+    assert_eq!(contents(&list), vec!["d", "b", "c", "a"]);
+
+    list.undo().unwrap();
+    assert_eq!(contents(&list), vec!["a", "b", "c", "d"]);
+}
+
+#[test]
+fn test_reorderable_undo_restores_exact_previous_order() {
+    let (mut list, ids) = letters();
+    list.move_item(ids[0], 2).unwrap();
+    let before = list.order();
+
+    list.move_item(ids[3], 0).unwrap();
+    let undo = list.undo().unwrap();
+
+    assert_eq!(list.order(), before);
+    assert_eq!(
+        undo,
+        vec![MoveRecord {
+            id: ids[3],
+            from: 0,
+            to: 3
+        }]
+    );
+
+    list.undo().unwrap();
+    assert_eq!(list.order(), ids);
+    assert!(list.undo().is_none(), "nothing left to undo");
+}
+
+#[test]
+fn test_reorderable_no_op_move_is_not_recorded() {
+    let (mut list, ids) = letters();
+    let record = list.move_item(ids[1], 1).unwrap();
+
+    assert_eq!(record.from, record.to);
+    assert_eq!(list.history_len(), 0);
+}
+
+#[test]
+fn test_reorderable_history_is_bounded() {
+    let mut list = ReorderableList::with_history_limit(2);
+    let a = list.push("a");
+    list.push("b");
+    list.push("c");
+
+    list.move_item(a, 1).unwrap(); // b a c  (evicted)
+    list.move_item(a, 2).unwrap(); // b c a
+    list.move_item(a, 0).unwrap(); // a b c
+    assert_eq!(list.history_len(), 2);
+
+    list.undo().unwrap();
+    list.undo().unwrap();
+    assert!(
+        list.undo().is_none(),
+        "oldest move should have been evicted"
+    );
+    assert_eq!(contents(&list), vec!["b", "a", "c"]);
+}
+
+#[test]
+fn test_reorderable_scripted_shuffle() {
+    let (mut list, ids) = letters();
+    list.move_item(ids[3], 0).unwrap(); // d a b c
+    list.swap(ids[0], ids[2]).unwrap(); // d c b a
+    list.move_relative(ids[1], -1).unwrap(); // d b c a
+    list.move_item(ids[0], 1).unwrap(); // d a b c
+
+    assert_eq!(contents(&list), vec!["d", "a", "b", "c"]);
+    assert_eq!(list.order(), vec![ids[3], ids[0], ids[1], ids[2]]);
+}
+
+#[test]
+fn test_reorderable_order_round_trip() {
+    let (mut list, ids) = letters();
+    list.move_item(ids[2], 0).unwrap();
+    list.swap(ids[1], ids[3]).unwrap();
+    let saved = list.order();
+
+    let (mut restored, _) = letters();
+    restored.apply_order(&saved).unwrap();
+    assert_eq!(contents(&restored), contents(&list));
+    assert_eq!(restored.history_len(), 0);
+}
+
+#[test]
+fn test_reorderable_apply_order_rejects_non_permutation() {
+    let (mut list, ids) = letters();
+
+    assert_eq!(list.apply_order(&ids[..3]), Err(ReorderError::InvalidOrder));
+    assert_eq!(
+        list.apply_order(&[ids[0], ids[0], ids[1], ids[2]]),
+        Err(ReorderError::InvalidOrder)
+    );
+    assert_eq!(
+        list.apply_order(&[ids[0], ids[1], ids[2], ItemId(42)]),
+        Err(ReorderError::InvalidOrder)
+    );
+    assert_eq!(contents(&list), vec!["a", "b", "c", "d"]);
+}
+
+#[test]
+fn test_app_favorite_actions_reorder() {
+    let mut app = MyApp::new();
+    assert_eq!(
+        app.favorite_labels(),
+        vec!["Increment", "Toggle theme", "Clear notes", "Open settings"]
+    );
+
+    let settings = app.favorite_actions.order()[3];
+    app.favorite_actions.move_item(settings, 0).unwrap();
+    assert_eq!(app.favorite_labels()[0], "Open settings");
+
+    let cloned = app.clone();
+    assert_eq!(cloned.favorite_labels(), app.favorite_labels());
+}
+
+// ============================================================================
+// NOTES UNDO / REDO TESTS
+// ============================================================================
+
+/// Types `text` one char at a time through `set_notes`, like the GUI does.
+fn type_chars(app: &mut MyApp, text: &str) {
+    for ch in text.chars() {
+        let mut next = app.notes.clone();
+        next.push(ch);
+        app.set_notes(&next);
+    }
+}
+
+#[test]
+fn test_undo_restores_previous_notes() {
+    let mut app = MyApp::new();
+    let original = app.notes.clone();
+    app.set_notes("rewritten");
+
+    assert!(app.undo());
+    assert_eq!(app.notes, original);
+    assert!(!app.undo(), "nothing left to undo");
+    assert_eq!(app.notes, original);
+}
+
+#[test]
+fn test_redo_reapplies_undone_edit() {
+    let mut app = MyApp::new();
+    app.set_notes("first");
+    app.set_notes("second");
+
+    assert!(app.undo());
+    assert_eq!(app.notes, "first");
+    assert!(app.redo());
+    assert_eq!(app.notes, "second");
+    assert!(!app.redo());
+}
+
+#[test]
+fn test_new_edit_after_undo_clears_redo() {
+    let mut app = MyApp::new();
+    app.set_notes("first");
+    app.set_notes("second");
+    app.undo();
+    assert!(app.notes_history.can_redo());
+
+    app.set_notes("branch");
+    assert!(!app.notes_history.can_redo());
+    assert!(!app.redo());
+    assert!(app.undo());
+    assert_eq!(app.notes, "first");
+}
+
+#[test]
+fn test_clear_and_append_are_undoable() {
+    let mut app = MyApp::new();
+    let original = app.notes.clone();
+    app.clear_notes();
+    app.append_to_notes("Line 1");
+    app.append_to_notes("Line 2");
+    assert_eq!(app.notes, "Line 1\nLine 2");
+
+    assert!(app.undo());
+    assert_eq!(app.notes, "Line 1");
+    assert!(app.undo());
+    assert_eq!(app.notes, "");
+    assert!(app.undo());
+    assert_eq!(app.notes, original);
+}
+
+#[test]
+fn test_unchanged_notes_record_nothing() {
+    let mut app = MyApp::new();
+    let current = app.notes.clone();
+    app.set_notes(&current);
+    app.clear_notes();
+    app.clear_notes();
+
+    assert_eq!(app.notes_history.undo_len(), 1);
+}
+
+#[test]
+fn test_edit_history_cap_evicts_oldest() {
+    let mut app = MyApp::new();
+    app.notes_history = EditHistory::new(3, 1);
+    for text in ["a1", "b22", "c333", "d4444", "e55555"] {
+        app.set_notes(text);
+    }
+
+    assert_eq!(app.notes_history.undo_len(), 3);
+    let mut seen = Vec::new();
+    while app.undo() {
+        seen.push(app.notes.clone());
+    }
+    assert_eq!(seen, vec!["d4444", "c333", "b22"]);
+}
+
+#[test]
+fn test_keystrokes_coalesce_into_one_undo_step() {
+    let mut app = MyApp::new();
+    app.clear_notes();
+    type_chars(&mut app, "hello");
+
+    assert!(app.undo());
+    assert_eq!(app.notes, "");
+    assert!(app.redo());
+    assert_eq!(app.notes, "hello");
+}
+
+#[test]
+fn test_backspace_coalesces_too() {
+    let mut app = MyApp::new();
+    app.set_notes("hello");
+    app.set_notes("hell");
+    app.set_notes("hel");
+
+    assert!(app.undo());
+    assert_eq!(app.notes, "hello");
+}
+
+#[test]
+fn test_coalescing_respects_limit() {
+    let mut app = MyApp::new();
+    app.notes_history = EditHistory::new(10, 3);
+    app.clear_notes();
+    type_chars(&mut app, "abcdefg");
+
+    // Runs of three: "" -> "abc", "abc" -> "abcdef", "abcdef" -> "abcdefg".
+    app.undo();
+    assert_eq!(app.notes, "abcdef");
+    app.undo();
+    assert_eq!(app.notes, "abc");
+    app.undo();
+    assert_eq!(app.notes, "");
+}
+
+#[test]
+fn test_larger_edit_breaks_coalescing() {
+    let mut app = MyApp::new();
+    app.clear_notes();
+    type_chars(&mut app, "ab");
+    app.set_notes("ab pasted");
+    type_chars(&mut app, "cd");
+
+    app.undo();
+    assert_eq!(app.notes, "ab pasted");
+    app.undo();
+    assert_eq!(app.notes, "ab");
+    app.undo();
+    assert_eq!(app.notes, "");
+}
+
+#[test]
+fn test_non_adjacent_single_char_changes_are_not_keystrokes() {
+    let mut app = MyApp::new();
+    app.set_notes("abc");
+    app.set_notes("abd"); // replaced a char: same length
+    app.set_notes("abde");
+
+    app.undo();
+    assert_eq!(app.notes, "abd");
+    app.undo();
+    assert_eq!(app.notes, "abc");
+}
+
+#[test]
+fn test_record_snapshot_for_in_place_edits() {
+    let mut app = MyApp::new();
+    app.clear_notes();
+    for ch in ['x', 'y', 'z'] {
+        app.record_snapshot();
+        app.notes.push(ch);
+    }
+
+    // One snapshot for the clear, one for the whole run of pushes.
+    assert_eq!(app.notes_history.undo_len(), 2);
+    app.undo();
+    assert_eq!(app.notes, "");
+}
+
+#[test]
+fn test_multibyte_keystrokes() {
+    let mut app = MyApp::new();
+    app.clear_notes();
+    type_chars(&mut app, "café ✓");
+
+    // One snapshot for the clear, one for the typing.
+    assert_eq!(app.notes_history.undo_len(), 2);
+    // Deleting the three-byte '✓' is still a single keystroke.
+    app.set_notes("café ");
+    assert_eq!(app.notes_history.undo_len(), 2);
+    app.undo();
+    assert_eq!(app.notes, "");
+}
+
+// ============================================================================
+// PERSISTENCE TESTS
+// ============================================================================
+
+#[test]
+fn test_save_and_load_round_trip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.json");
+
+    let mut app = MyApp::new();
+    app.increment();
+    app.toggle_theme();
+    app.set_slider_value(12.5);
+    app.set_notes("saved notes");
+    let first = app.favorite_actions.order()[0];
+    app.favorite_actions.move_item(first, 3).unwrap();
+    app.save_to(&path).unwrap();
+
+    let mut loaded = MyApp::load_from(&path).unwrap();
+    assert_eq!(loaded.schema_version, CURRENT_SCHEMA_VERSION);
+    assert_eq!(loaded.counter, 1);
+    assert!(!loaded.dark_mode);
+    assert_eq!(loaded.slider_value, 12.5);
+    assert_eq!(loaded.notes, "saved notes");
+    assert_eq!(loaded.favorite_labels(), app.favorite_labels());
+
+    // Histories come back too, so undo works across restarts.
+    assert!(loaded.undo());
+    assert_eq!(loaded.notes, MyApp::new().notes);
+    assert!(loaded.favorite_actions.undo().is_some());
+    assert_eq!(loaded.favorite_labels()[0], "Increment");
+}
+
+#[test]
+fn test_saved_file_is_pretty_json() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.json");
+    MyApp::new().save_to(&path).unwrap();
+
+    let json = fs::read_to_string(&path).unwrap();
+    assert!(json.contains("\n  \"schema_version\": 1"));
+}
+
+#[test]
+fn test_load_or_default_when_file_missing() {
+    let dir = tempdir().unwrap();
+    let app = MyApp::load_or_default(dir.path().join("missing.json")).unwrap();
+
+    assert_eq!(app.notes, MyApp::new().notes);
+    assert_eq!(app.schema_version, CURRENT_SCHEMA_VERSION);
+}
+
+#[test]
+fn test_load_from_missing_file_is_io_error() {
+    let dir = tempdir().unwrap();
+    let result = MyApp::load_from(dir.path().join("missing.json"));
+    assert!(matches!(result, Err(PersistError::Io(_))));
+}
+
+#[test]
+fn test_corrupted_file_is_reported_not_replaced() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.json");
+    fs::write(&path, "{ \"counter\": 3, \"text\": ").unwrap();
+
+    let result = MyApp::load_or_default(&path);
+    assert!(matches!(result, Err(PersistError::Json(_))));
+    // The broken file is left alone for the user to inspect.
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .starts_with("{ \"counter\": 3"));
+}
+
+#[test]
+fn test_load_v0_file_without_new_fields() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.json");
+    fs::write(
+        &path,
+        r#"{
+            "counter": 7,
+            "text": "old",
+            "show_settings": true,
+            "slider_value": 30.0,
+            "dark_mode": false,
+            "notes": "from v0"
+        }"#,
+    )
+    .unwrap();
+
+    let app = MyApp::load_from(&path).unwrap();
+    assert_eq!(app.schema_version, CURRENT_SCHEMA_VERSION);
+    assert_eq!(app.counter, 7);
+    assert_eq!(app.notes, "from v0");
+    assert!(app.show_settings);
+    assert_eq!(app.favorite_labels(), MyApp::new().favorite_labels());
+    assert!(!app.notes_history.can_undo());
+}
+
+#[test]
+fn test_load_rejects_newer_schema_version() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.json");
+    let mut app = MyApp::new();
+    app.schema_version = CURRENT_SCHEMA_VERSION + 1;
+    app.save_to(&path).unwrap();
+
+    match MyApp::load_or_default(&path) {
+        Err(PersistError::UnsupportedVersion(version)) => {
+            assert_eq!(version, CURRENT_SCHEMA_VERSION + 1)
+        }
+        other => panic!("expected UnsupportedVersion, got {:?}", other.map(|_| ())),
+    }
+}
+
+/// Saves the default state, lets `edit` tamper with the JSON, and loads it.
+fn load_tampered(edit: impl FnOnce(&mut Value)) -> Result<MyApp, PersistError> {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.json");
+    let mut app = MyApp::new();
+    let first = app.favorite_actions.order()[0];
+    app.favorite_actions.move_item(first, 3).unwrap();
+    app.set_notes("edited");
+    app.save_to(&path).unwrap();
+
+    let mut json: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    edit(&mut json);
+    fs::write(&path, json.to_string()).unwrap();
+    MyApp::load_from(&path)
+}
+
+#[test]
+fn test_load_rejects_inconsistent_state() {
+    assert!(load_tampered(|_| {}).is_ok());
+
+    type Tamper = fn(&mut Value);
+    let cases: [(&str, Tamper); 5] = [
+        ("duplicate id", |json| {
+            json["favorite_actions"]["items"][1][0] = json!(0);
+        }),
+        ("next_id", |json| {
+            json["favorite_actions"]["next_id"] = json!(1);
+        }),
+        ("points past", |json| {
+            json["favorite_actions"]["history"][0]["Move"]["to"] = json!(99);
+        }),
+        ("undo entries", |json| {
+            json["favorite_actions"]["history_limit"] = json!(0);
+        }),
+        ("undo snapshots", |json| {
+            json["notes_history"]["max_depth"] = json!(0);
+        }),
+    ];
+    for (expected, edit) in cases {
+        match load_tampered(edit) {
+            Err(err @ PersistError::Invalid(_)) => {
+                assert!(err.to_string().contains(expected), "{}", err)
+            }
+            other => panic!(
+                "{}: expected Invalid, got {:?}",
+                expected,
+                other.map(|_| ())
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_save_replaces_file_without_leaving_temp_files() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("state.json");
+    fs::write(&path, "old contents").unwrap();
+
+    let mut app = MyApp::new();
+    app.increment();
+    app.save_to(&path).unwrap();
+
+    assert_eq!(MyApp::load_from(&path).unwrap().counter, 1);
+    let names: Vec<_> = fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(names, vec!["state.json"]);
+}
+
+#[test]
+fn test_failed_save_keeps_previous_file() {
+    let dir = tempdir().unwrap();
+    // Renaming a file over a directory fails, after the temp file is written.
+    let path = dir.path().join("state.json");
+    fs::create_dir(&path).unwrap();
+
+    assert!(matches!(
+        MyApp::new().save_to(&path),
+        Err(PersistError::Io(_))
+    ));
+    assert!(path.is_dir());
+    assert!(!dir.path().join("state.json.tmp").exists());
+}
+
+// ============================================================================
+// COMMAND PALETTE TESTS
+// ============================================================================
+
+fn titles(palette: &CommandPalette, query: &str) -> Vec<String> {
+    palette
+        .search(query)
+        .into_iter()
+        .map(|result| result.title)
+        .collect()
+}
+
+#[test]
+fn test_palette_fuzzy_ranking_prefers_word_starts() {
+    let palette = CommandPalette::with_default_commands();
+    let results = palette.search("tgt");
+    assert_eq!(results[0].title, "Toggle theme");
+    assert_eq!(results[0].positions, vec![0, 2, 7]);
+    // "Toggle settings" also contains t..g..t, just not at a word start.
+    let settings = results
+        .iter()
+        .find(|r| r.title == "Toggle settings")
+        .expect("weaker match still listed");
+    assert!(results[0].score > settings.score);
+}
+
+#[test]
+fn test_palette_consecutive_matches_rank_higher() {
+    let palette = CommandPalette::with_default_commands();
+    assert_eq!(titles(&palette, "clear")[0], "Clear notes");
+    assert_eq!(titles(&palette, "RESET")[0], "Reset counter");
+}
+
+#[test]
+fn test_palette_empty_query_lists_all_in_order() {
+    let palette = CommandPalette::with_default_commands();
+    let all: Vec<String> = palette.commands().iter().map(|c| c.title.clone()).collect();
+    assert_eq!(all.len(), 8);
+    assert_eq!(titles(&palette, ""), all);
+    assert_eq!(titles(&palette, "   "), all);
+    assert!(palette.search("").iter().all(|r| r.positions.is_empty()));
+}
+
+#[test]
+fn test_palette_non_matching_query() {
+    let palette = CommandPalette::with_default_commands();
+    assert!(palette.search("xyzzy").is_empty());
+    // Subsequence means order matters.
+    assert!(palette.search("emeht").is_empty());
+}
+
+#[test]
+fn test_palette_keywords_match_below_titles() {
+    let palette = CommandPalette::with_default_commands();
+    let results = palette.search("dark");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, ActionId("theme.toggle"));
+    assert!(results[0].positions.is_empty());
+}
+
+#[test]
+fn test_palette_executes_each_action() {
+    let palette = CommandPalette::with_default_commands();
+    let mut app = MyApp::new();
+    let run = |app: &mut MyApp, id| palette.apply_action(app, ActionId(id)).unwrap();
+
+    run(&mut app, "counter.increment");
+    run(&mut app, "counter.increment");
+    assert_eq!(app.counter, 2);
+    run(&mut app, "counter.decrement");
+    assert_eq!(app.counter, 1);
+    run(&mut app, "counter.reset");
+    assert_eq!(app.counter, 0);
+
+    let dark = app.dark_mode;
+    run(&mut app, "theme.toggle");
+    assert_eq!(app.dark_mode, !dark);
+
+    let shown = app.show_settings;
+    run(&mut app, "settings.toggle");
+    assert_eq!(app.show_settings, !shown);
+
+    let notes = app.notes.clone();
+    run(&mut app, "notes.clear");
+    assert_eq!(app.notes, "");
+    run(&mut app, "notes.undo");
+    assert_eq!(app.notes, notes);
+    run(&mut app, "notes.redo");
+    assert_eq!(app.notes, "");
+}
+
+#[test]
+fn test_palette_unknown_action_is_noop_error() {
+    let palette = CommandPalette::with_default_commands();
+    let mut app = MyApp::new();
+    app.increment();
+    let before = serde_json::to_string(&app).unwrap();
+
+    assert_eq!(
+        palette.apply_action(&mut app, ActionId("counter.triple")),
+        Err(PaletteError::UnknownAction(ActionId("counter.triple")))
+    );
+    assert_eq!(serde_json::to_string(&app).unwrap(), before);
+}
+
+#[test]
+fn test_palette_rejects_duplicate_ids() {
+    let mut palette = CommandPalette::new();
+    let command = PaletteCommand::new("counter.bump", "Bump", &[], Action::Increment);
+    assert_eq!(palette.register(command.clone()), Ok(()));
+    assert_eq!(
+        palette.register(command),
+        Err(PaletteError::DuplicateAction(ActionId("counter.bump")))
+    );
+    assert_eq!(palette.commands().len(), 1);
+}
@@ -9,6 +9,8 @@ pub struct Todo {
     pub id: u64,
     pub title: String,
     pub completed: bool,
+    #[serde(default)]
+    pub created_at: u64,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -23,6 +25,35 @@ pub struct UpdateTodo {
     pub completed: Option<bool>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Page {
+    pub items: Vec<Todo>,
+    pub next_cursor: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TodoStats {
+    pub total: usize,
+    pub completed: usize,
+    pub pending: usize,
+    pub completion_ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DayBucket {
+    pub day_start: u64,
+    pub created: usize,
+    pub completed: usize,
+}
+
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashedTodo {
+    pub todo: Todo,
+    pub deleted_at: u64,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppError {
     NotFound,
@@ -50,6 +81,12 @@ impl TodoStore {
         todo!("Add todo")
     }
 
+    pub fn add_todo_at(&mut self, create_todo: CreateTodo, now: u64) -> Todo {
+        // TODO: Like add_todo, but stamp `created_at` with `now`.
+        let _ = (create_todo, now);
+        todo!("Add todo with a creation time")
+    }
+
     pub fn get_todo(&self, id: u64) -> Option<&Todo> {
         let _ = id;
         todo!("Get todo")
@@ -69,10 +106,58 @@ impl TodoStore {
     }
 
     pub fn delete_todo(&mut self, id: u64) -> Option<Todo> {
+        // TODO: Soft delete: call delete_todo_at with the current Unix time.
         let _ = id;
         todo!("Delete todo")
     }
 
+    pub fn delete_todo_at(&mut self, id: u64, now: u64) -> Option<Todo> {
+        // TODO: Move the todo (and its search postings) out of the store and
+        // into a trash map, stamped with `now`.
+        let _ = (id, now);
+        todo!("Move todo to trash")
+    }
+
+    pub fn restore_todo(&mut self, id: u64) -> Result<Todo, AppError> {
+        // TODO: Move a trashed todo back (re-indexing its title), or
+        // AppError::NotFound. IDs are never reused, so it can't collide.
+        let _ = id;
+        todo!("Restore todo from trash")
+    }
+
+    pub fn list_trash(&self) -> Vec<TrashedTodo> {
+        todo!("List trash sorted by deletion time")
+    }
+
+    pub fn purge_older_than(&mut self, cutoff: u64) -> usize {
+        // TODO: Drop trashed todos with deleted_at < cutoff; return how many.
+        let _ = cutoff;
+        todo!("Purge old trash")
+    }
+
+    pub fn page_after(&self, cursor: Option<u64>, limit: usize) -> Page {
+        // TODO: Return up to `limit` (at least 1) todos with id > cursor in
+        // ascending id order. next_cursor is the last id on the page, or
+        // None when no todos remain after it.
+        let _ = (cursor, limit);
+        todo!("Page through todos")
+    }
+
+    pub fn search(&self, query: &str) -> Vec<Todo> {
+        // TODO: Maintain a word -> ids inverted index in add/update/delete,
+        // then intersect the postings of every query word (the last word
+        // also matches as a prefix unless the query ends in a separator).
+        // Return the todos sorted by id.
+        let _ = query;
+        todo!("Search todos (AND)")
+    }
+
+    pub fn search_any(&self, query: &str) -> Vec<Todo> {
+        // TODO: Same as search, but union the postings instead.
+        let _ = query;
+        todo!("Search todos (OR)")
+    }
+
     pub fn count(&self) -> usize {
         todo!("Count todos")
     }
@@ -88,6 +173,19 @@ impl TodoStore {
     pub fn pending_count(&self) -> usize {
         todo!("Count pending todos")
     }
+
+    pub fn stats(&self) -> TodoStats {
+        // TODO: The ratio of an empty store is 0.0, not NaN.
+        todo!("Compute todo statistics")
+    }
+
+    pub fn stats_daily(&self, now: u64, days: usize) -> Vec<DayBucket> {
+        // TODO: One bucket per UTC day for the last `days` days (oldest
+        // first, ending with the day containing `now`), zeros included.
+        // Buckets are half-open: [day_start, day_start + SECONDS_PER_DAY).
+        let _ = (now, days);
+        todo!("Bucket todos by creation day")
+    }
 }
 
 impl Default for TodoStore {
@@ -96,6 +194,13 @@ impl Default for TodoStore {
     }
 }
 
+pub fn tokenize(text: &str) -> Vec<String> {
+    // TODO: Split on non-alphanumeric characters, drop empty pieces, and
+    // lowercase each word.
+    let _ = text;
+    todo!("Tokenize text")
+}
+
 pub fn validate_create_todo(create: &CreateTodo) -> Result<(), AppError> {
     let _ = create;
     todo!("Validate create todo")
@@ -106,5 +211,19 @@ pub fn validate_update_todo(update: &UpdateTodo) -> Result<(), AppError> {
     todo!("Validate update todo")
 }
 
+pub fn encode_cursor(id: u64) -> String {
+    // TODO: URL-safe base64 (no padding) of the id's 8 big-endian bytes
+    // followed by a 4-byte checksum of the id.
+    let _ = id;
+    todo!("Encode cursor token")
+}
+
+pub fn decode_cursor(token: &str) -> Result<u64, AppError> {
+    // TODO: Reverse encode_cursor; any malformed token or checksum mismatch
+    // is AppError::BadRequest.
+    let _ = token;
+    todo!("Decode cursor token")
+}
+
 #[doc(hidden)]
 pub mod solution;
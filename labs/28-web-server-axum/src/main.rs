@@ -1,6 +1,6 @@
 //! # Web Server Model Demo
 
-use web_server_axum::solution::{CreateTodo, TodoStore};
+use web_server_axum::solution::{decode_cursor, encode_cursor, CreateTodo, TodoStore, SECONDS_PER_DAY};
 
 fn main() {
     println!("=== Web Server Model Demo ===\n");
@@ -12,4 +12,32 @@ fn main() {
     println!("created: {:?}", t1);
     println!("created: {:?}", t2);
     println!("count: {} (completed: {}, pending: {})", store.count(), store.completed_count(), store.pending_count());
+
+    let first = store.page_after(None, 1);
+    let token = first.next_cursor.map(encode_cursor);
+    println!("page 1: {:?}, next token: {:?}", first.items, token);
+    if let Some(token) = token {
+        let cursor = decode_cursor(&token).expect("token we just issued");
+        println!("page 2: {:?}", store.page_after(Some(cursor), 1).items);
+    }
+
+    store.delete_todo_at(t1.id, 1_000);
+    println!(
+        "after delete: count {}, trash {:?}",
+        store.count(),
+        store.list_trash()
+    );
+    match store.restore_todo(t1.id) {
+        Ok(todo) => println!("restored: {:?}", todo),
+        Err(err) => println!("restore failed: {}", err),
+    }
+    store.delete_todo_at(t2.id, 1_000);
+    println!("purged {} old todos", store.purge_older_than(2_000));
+
+    let mut stats_store = TodoStore::new();
+    let now = 20_000 * SECONDS_PER_DAY + 3_600;
+    stats_store.add_todo_at(CreateTodo { title: "Old".to_string(), completed: true }, now - 2 * SECONDS_PER_DAY);
+    stats_store.add_todo_at(CreateTodo { title: "New".to_string(), completed: false }, now);
+    println!("stats: {}", serde_json::to_string(&stats_store.stats()).unwrap());
+    println!("daily: {}", serde_json::to_string(&stats_store.stats_daily(now, 3)).unwrap());
 }
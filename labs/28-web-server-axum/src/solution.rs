@@ -17,6 +17,8 @@
 // ┌─────────────────────────────────┐
 // │ todos: HashMap<u64, Todo>       │──────> Heap: Hash table with Todos
 // │ next_id: u64                    │
+// │ index: HashMap<String, HashSet> │──────> Heap: word -> todo ids
+// │ trash: HashMap<u64, Trashed..>  │──────> Heap: soft-deleted todos
 // └─────────────────────────────────┘
 //
 // Each Todo in the HashMap:
@@ -24,13 +26,14 @@
 // │ id: u64 (8 bytes)      │       │ "Learn Rust"     │
 // │ title: ptr+len+cap     │──────>│ (10 bytes + cap) │
 // │ completed: bool (1 b.) │       └──────────────────┘
+// │ created_at: u64        │
 // └────────────────────────┘
 // ```
 // In main.rs, the store is wrapped in Arc<RwLock<TodoStore>> for
 // thread-safe shared access across async request handlers.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // ============================================================================
 // DATA MODELS
@@ -52,6 +55,11 @@ pub struct Todo {
     pub id: u64,
     pub title: String,
     pub completed: bool,
+    /// When the todo was added (seconds since the Unix epoch, or whatever
+    /// clock was passed to `add_todo_at`). Missing in older JSON, so it
+    /// defaults to 0 when deserializing.
+    #[serde(default)]
+    pub created_at: u64,
 }
 
 /// Request body for creating a new todo.
@@ -76,6 +84,49 @@ pub struct UpdateTodo {
     pub completed: Option<bool>,
 }
 
+/// One page of todos from `TodoStore::page_after`.
+///
+/// `next_cursor` is the id of the last item on this page, to be passed back
+/// as the cursor for the following page. It is None once there is nothing
+/// after this page, so clients loop until they see None.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Page {
+    pub items: Vec<Todo>,
+    pub next_cursor: Option<u64>,
+}
+
+/// Response body for a `/stats` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TodoStats {
+    pub total: usize,
+    pub completed: usize,
+    pub pending: usize,
+    /// `completed / total`, or 0.0 for an empty store.
+    pub completion_ratio: f64,
+}
+
+/// Todos created during one UTC day, from `TodoStore::stats_daily`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DayBucket {
+    /// Midnight UTC at the start of the day, in seconds since the epoch.
+    pub day_start: u64,
+    /// Todos created that day.
+    pub created: usize,
+    /// How many of those are completed now.
+    pub completed: usize,
+}
+
+/// Length of a `DayBucket`. Days are UTC, so there are no DST jumps.
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A soft-deleted todo and when it was deleted (seconds since the Unix
+/// epoch, or whatever clock the caller passed to `delete_todo_at`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashedTodo {
+    pub todo: Todo,
+    pub deleted_at: u64,
+}
+
 // ============================================================================
 // ERROR TYPES
 // ============================================================================
@@ -122,10 +173,24 @@ impl std::error::Error for AppError {}
 ///
 /// This separation keeps the store simple and testable while allowing
 /// the web framework to add concurrency as needed.
+///
+/// # Search Index
+/// `index` is an inverted index: each lowercase title word maps to the ids
+/// of the todos containing it. Every method that changes a title keeps it
+/// in sync, so `search` never has to scan the todos themselves.
+///
+/// # Trash
+/// Deleting a todo moves it to `trash` instead of dropping it, so it can be
+/// restored until `purge_older_than` removes it for good. Trashed todos are
+/// invisible to every other method (lookups, listings, counts, search).
+/// IDs come from `next_id`, which only ever grows, so a restored todo can
+/// never collide with one created after it was deleted.
 #[derive(Debug)]
 pub struct TodoStore {
     todos: HashMap<u64, Todo>,
     next_id: u64,
+    index: HashMap<String, HashSet<u64>>,
+    trash: HashMap<u64, TrashedTodo>,
 }
 
 impl TodoStore {
@@ -137,6 +202,8 @@ impl TodoStore {
         TodoStore {
             todos: HashMap::new(),
             next_id: 1,
+            index: HashMap::new(),
+            trash: HashMap::new(),
         }
     }
 
@@ -148,13 +215,23 @@ impl TodoStore {
     ///
     /// # Arguments
     /// * `create_todo` - The title and initial completed status.
+    ///
+    /// The todo is stamped with the current system time; use `add_todo_at`
+    /// to supply the clock yourself.
     pub fn add_todo(&mut self, create_todo: CreateTodo) -> Todo {
+        self.add_todo_at(create_todo, unix_now())
+    }
+
+    /// Like `add_todo`, but records `now` as the creation time.
+    pub fn add_todo_at(&mut self, create_todo: CreateTodo, now: u64) -> Todo {
         let todo = Todo {
             id: self.next_id,
             title: create_todo.title,
             completed: create_todo.completed,
+            created_at: now,
         };
 
+        index_title(&mut self.index, todo.id, &todo.title);
         self.todos.insert(self.next_id, todo.clone());
         self.next_id += 1;
 
@@ -193,10 +270,17 @@ impl TodoStore {
     /// Fields set to None are left unchanged. This implements PATCH semantics.
     ///
     /// Returns the updated Todo if found, or None if the ID doesn't exist.
+    ///
+    /// A new title replaces the todo's postings in the search index: words
+    /// only in the old title stop matching, words in the new one start.
     pub fn update_todo(&mut self, id: u64, update: UpdateTodo) -> Option<Todo> {
         if let Some(todo) = self.todos.get_mut(&id) {
             if let Some(title) = update.title {
-                todo.title = title;
+                let old_title = std::mem::replace(&mut todo.title, title);
+                // `todo` borrows `self.todos`, so the index helpers take the
+                // index field directly rather than `&mut self`.
+                unindex_title(&mut self.index, id, &old_title);
+                index_title(&mut self.index, id, &todo.title);
             }
             if let Some(completed) = update.completed {
                 todo.completed = completed;
@@ -207,12 +291,144 @@ impl TodoStore {
         }
     }
 
-    /// Removes a todo by ID and returns it.
+    /// Moves a todo to the trash and returns a copy of it.
     ///
-    /// Returns None if no todo with the given ID exists.
-    /// The removed Todo is returned as an owned value (moved out of the HashMap).
+    /// Returns None if no todo with the given ID exists (including one that
+    /// is already in the trash). The deletion is stamped with the current
+    /// system time; use `delete_todo_at` to supply the clock yourself.
     pub fn delete_todo(&mut self, id: u64) -> Option<Todo> {
-        self.todos.remove(&id)
+        self.delete_todo_at(id, unix_now())
+    }
+
+    /// Like `delete_todo`, but records `now` as the deletion time.
+    pub fn delete_todo_at(&mut self, id: u64, now: u64) -> Option<Todo> {
+        let todo = self.todos.remove(&id)?;
+        unindex_title(&mut self.index, id, &todo.title);
+        self.trash.insert(
+            id,
+            TrashedTodo {
+                todo: todo.clone(),
+                deleted_at: now,
+            },
+        );
+        Some(todo)
+    }
+
+    /// Moves a todo out of the trash and back into the store, unchanged.
+    ///
+    /// Returns `AppError::NotFound` if the ID isn't in the trash (never
+    /// deleted, already restored, or purged).
+    pub fn restore_todo(&mut self, id: u64) -> Result<Todo, AppError> {
+        let trashed = self.trash.remove(&id).ok_or(AppError::NotFound)?;
+        let todo = trashed.todo;
+        // IDs are never reused, so nothing can have taken this one.
+        debug_assert!(!self.todos.contains_key(&id));
+        index_title(&mut self.index, id, &todo.title);
+        self.todos.insert(id, todo.clone());
+        Ok(todo)
+    }
+
+    /// Returns the trashed todos, oldest deletion first (ties by ID).
+    pub fn list_trash(&self) -> Vec<TrashedTodo> {
+        let mut trash: Vec<TrashedTodo> = self.trash.values().cloned().collect();
+        trash.sort_by_key(|t| (t.deleted_at, t.todo.id));
+        trash
+    }
+
+    /// Permanently removes every todo deleted before `cutoff` and returns
+    /// how many were removed. Todos deleted at exactly `cutoff` stay.
+    pub fn purge_older_than(&mut self, cutoff: u64) -> usize {
+        let before = self.trash.len();
+        self.trash.retain(|_, t| t.deleted_at >= cutoff);
+        before - self.trash.len()
+    }
+
+    /// Returns the todos whose titles contain every word in `query`,
+    /// ordered by ID.
+    ///
+    /// Matching is case-insensitive and ignores punctuation. The last
+    /// query word also matches as a prefix ("buy mil" finds "Buy milk") so
+    /// results can update as the user types; a trailing space or
+    /// punctuation marks it as complete. An empty query matches nothing.
+    pub fn search(&self, query: &str) -> Vec<Todo> {
+        let mut postings = self.query_postings(query).into_iter();
+        let Some(first) = postings.next() else {
+            return Vec::new();
+        };
+        let ids = postings.fold(first, |acc, ids| &acc & &ids);
+        self.todos_by_id(ids)
+    }
+
+    /// Like `search`, but returns todos containing ANY query word.
+    pub fn search_any(&self, query: &str) -> Vec<Todo> {
+        let ids = self
+            .query_postings(query)
+            .into_iter()
+            .fold(HashSet::new(), |acc, ids| &acc | &ids);
+        self.todos_by_id(ids)
+    }
+
+    /// One set of matching IDs per query word. The last word is expanded
+    /// to every indexed word it prefixes, unless the query ends with a
+    /// separator.
+    ///
+    /// The prefix lookup scans every indexed word. That's fine for a todo
+    /// list; a large index would keep its words in a sorted structure
+    /// (BTreeMap or a trie) and walk only the matching range.
+    fn query_postings(&self, query: &str) -> Vec<HashSet<u64>> {
+        let words = tokenize(query);
+        let last_is_prefix = query.ends_with(char::is_alphanumeric);
+
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                if last_is_prefix && i == words.len() - 1 {
+                    self.index
+                        .iter()
+                        .filter(|(indexed, _)| indexed.starts_with(word.as_str()))
+                        .flat_map(|(_, ids)| ids.iter().copied())
+                        .collect()
+                } else {
+                    self.index.get(word).cloned().unwrap_or_default()
+                }
+            })
+            .collect()
+    }
+
+    fn todos_by_id(&self, ids: HashSet<u64>) -> Vec<Todo> {
+        let mut ids: Vec<u64> = ids.into_iter().collect();
+        ids.sort_unstable();
+        ids.iter()
+            .filter_map(|id| self.todos.get(id))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns up to `limit` todos with an id greater than `cursor`, in
+    /// ascending id order. Pass `None` for the first page.
+    ///
+    /// Unlike offset pagination ("skip 10, take 10"), a cursor names the
+    /// last item the client saw. Deleting a todo that was already served
+    /// doesn't shift the next page, so nothing is skipped or repeated.
+    /// A `limit` of 0 is treated as 1 so a client can't get stuck
+    /// requesting empty pages forever.
+    pub fn page_after(&self, cursor: Option<u64>, limit: usize) -> Page {
+        let limit = limit.max(1);
+        let after = cursor.unwrap_or(0);
+
+        let mut todos: Vec<&Todo> = self.todos.values().filter(|t| t.id > after).collect();
+        todos.sort_by_key(|t| t.id);
+
+        let has_more = todos.len() > limit;
+        let items: Vec<Todo> = todos.into_iter().take(limit).cloned().collect();
+        let next_cursor = if has_more {
+            items.last().map(|t| t.id)
+        } else {
+            None
+        };
+
+        Page { items, next_cursor }
     }
 
     /// Returns the number of todos in the store.
@@ -234,6 +450,57 @@ impl TodoStore {
     pub fn pending_count(&self) -> usize {
         self.todos.values().filter(|t| !t.completed).count()
     }
+
+    /// Totals for a `/stats` endpoint. Trashed todos are not counted.
+    pub fn stats(&self) -> TodoStats {
+        let total = self.count();
+        let completed = self.completed_count();
+        TodoStats {
+            total,
+            completed,
+            pending: total - completed,
+            // An empty store is 0% done, not NaN (which JSON can't even hold)
+            completion_ratio: if total == 0 {
+                0.0
+            } else {
+                completed as f64 / total as f64
+            },
+        }
+    }
+
+    /// How many todos were created on each of the last `days` UTC days,
+    /// oldest first, ending with the day that contains `now`.
+    ///
+    /// Every day gets a bucket, even with nothing in it. Each bucket covers
+    /// `[day_start, day_start + SECONDS_PER_DAY)`, so a todo created exactly
+    /// at midnight belongs to the day that starts then and no other.
+    pub fn stats_daily(&self, now: u64, days: usize) -> Vec<DayBucket> {
+        let today = now / SECONDS_PER_DAY;
+        // With more days than the clock can go back, start at day 0
+        let first = today.saturating_sub(days.saturating_sub(1) as u64);
+        let mut buckets: Vec<DayBucket> = (first..=today)
+            .take(days)
+            .map(|day| DayBucket {
+                day_start: day * SECONDS_PER_DAY,
+                created: 0,
+                completed: 0,
+            })
+            .collect();
+
+        for todo in self.todos.values() {
+            let day = todo.created_at / SECONDS_PER_DAY;
+            if day < first || day > today {
+                continue;
+            }
+            if let Some(bucket) = buckets.get_mut((day - first) as usize) {
+                bucket.created += 1;
+                if todo.completed {
+                    bucket.completed += 1;
+                }
+            }
+        }
+        buckets
+    }
 }
 
 impl Default for TodoStore {
@@ -242,6 +509,46 @@ impl Default for TodoStore {
     }
 }
 
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// SEARCH INDEX HELPERS
+// ============================================================================
+
+/// Splits `text` into lowercase words on any non-alphanumeric character.
+///
+/// "Buy milk, eggs & bread!" -> ["buy", "milk", "eggs", "bread"]
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn index_title(index: &mut HashMap<String, HashSet<u64>>, id: u64, title: &str) {
+    for word in tokenize(title) {
+        index.entry(word).or_default().insert(id);
+    }
+}
+
+/// Removes `id` from the postings of every word in `title`, dropping words
+/// no todo uses anymore so they can't show up in prefix matches.
+fn unindex_title(index: &mut HashMap<String, HashSet<u64>>, id: u64, title: &str) {
+    for word in tokenize(title) {
+        if let Some(ids) = index.get_mut(&word) {
+            ids.remove(&id);
+            if ids.is_empty() {
+                index.remove(&word);
+            }
+        }
+    }
+}
+
 // ============================================================================
 // VALIDATION HELPERS
 // ============================================================================
@@ -285,6 +592,83 @@ pub fn validate_update_todo(update: &UpdateTodo) -> Result<(), AppError> {
     Ok(())
 }
 
+// ============================================================================
+// CURSOR TOKENS
+// ============================================================================
+// `Page::next_cursor` is a raw id. Handing that to clients invites them to
+// build cursors by hand ("?after=41"), which couples them to our id scheme.
+// Instead the HTTP layer sends an opaque token: the id as 8 big-endian
+// bytes plus a 4-byte checksum, encoded as URL-safe base64 without padding.
+//
+// The checksum catches typos and casual tampering, not attackers: anyone
+// who reads this code can forge a token. A real API would sign tokens with
+// an HMAC key when that matters.
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Length of an encoded token: 12 bytes -> 16 base64 characters.
+const CURSOR_TOKEN_LEN: usize = 16;
+
+/// Encodes a cursor id as an opaque, URL-safe token.
+pub fn encode_cursor(id: u64) -> String {
+    let mut bytes = [0u8; 12];
+    bytes[..8].copy_from_slice(&id.to_be_bytes());
+    bytes[8..].copy_from_slice(&cursor_checksum(id).to_be_bytes());
+
+    let mut token = String::with_capacity(CURSOR_TOKEN_LEN);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16 | (chunk[1] as u32) << 8 | chunk[2] as u32;
+        for shift in [18, 12, 6, 0] {
+            token.push(BASE64_ALPHABET[(n >> shift) as usize & 0x3F] as char);
+        }
+    }
+    token
+}
+
+/// Decodes a token from `encode_cursor` back into an id.
+///
+/// Returns `AppError::BadRequest` if the token has the wrong length,
+/// contains characters outside the alphabet, or fails the checksum.
+pub fn decode_cursor(token: &str) -> Result<u64, AppError> {
+    let invalid = || AppError::BadRequest("Invalid cursor".to_string());
+
+    if token.len() != CURSOR_TOKEN_LEN {
+        return Err(invalid());
+    }
+
+    let mut bytes = Vec::with_capacity(12);
+    for chunk in token.as_bytes().chunks(4) {
+        let mut n = 0u32;
+        for &c in chunk {
+            let value = BASE64_ALPHABET
+                .iter()
+                .position(|&a| a == c)
+                .ok_or_else(invalid)?;
+            n = n << 6 | value as u32;
+        }
+        bytes.extend_from_slice(&n.to_be_bytes()[1..]);
+    }
+
+    let id = u64::from_be_bytes(bytes[..8].try_into().expect("8 bytes"));
+    let checksum = u32::from_be_bytes(bytes[8..].try_into().expect("4 bytes"));
+    if checksum != cursor_checksum(id) {
+        return Err(invalid());
+    }
+    Ok(id)
+}
+
+/// FNV-1a over the id bytes, with a fixed prefix so that a checksum over
+/// some other 8 bytes isn't accidentally a valid cursor checksum.
+fn cursor_checksum(id: u64) -> u32 {
+    let mut hash: u32 = 0x811C_9DC5;
+    for &byte in b"todo-cursor".iter().chain(id.to_be_bytes().iter()) {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
 // ============================================================================
 // WHAT RUST DOES UNDER THE HOOD
 // ============================================================================
@@ -305,6 +689,12 @@ pub fn validate_update_todo(update: &UpdateTodo) -> Result<(), AppError> {
 //    delete_todo() (which take &mut self). This prevents iterator
 //    invalidation at compile time.
 //
+//    delete_todo() still returns Option<Todo>, but the store now keeps a
+//    copy in the trash, so the Todo is cloned once and that clone
+//    allocates a new title buffer. The original is moved out of the map
+//    and back to the caller, which copies only its fields; its title's
+//    heap buffer stays where it is.
+//
 // 4. SERDE DERIVE
 //    #[derive(Serialize, Deserialize)] generates efficient conversion
 //    code at compile time. No runtime reflection or type inspection.
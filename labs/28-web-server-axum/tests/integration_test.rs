@@ -759,11 +759,13 @@ fn test_todo_equality() {
         id: 1,
         title: "Test".to_string(),
         completed: false,
+        created_at: 0,
     };
     let b = Todo {
         id: 1,
         title: "Test".to_string(),
         completed: false,
+        created_at: 0,
     };
     assert_eq!(a, b);
 }
@@ -774,11 +776,13 @@ fn test_todo_inequality() {
         id: 1,
         title: "A".to_string(),
         completed: false,
+        created_at: 0,
     };
     let b = Todo {
         id: 2,
         title: "A".to_string(),
         completed: false,
+        created_at: 0,
     };
     assert_ne!(a, b, "Todos with different IDs should not be equal");
 }
@@ -789,6 +793,7 @@ fn test_todo_clone() {
         id: 1,
         title: "Clone me".to_string(),
         completed: true,
+        created_at: 0,
     };
     let cloned = original.clone();
     assert_eq!(original, cloned);
@@ -800,6 +805,7 @@ fn test_todo_debug_format() {
         id: 1,
         title: "Test".to_string(),
         completed: false,
+        created_at: 0,
     };
     let debug = format!("{:?}", todo);
     assert!(debug.contains("Todo"));
@@ -816,6 +822,7 @@ fn test_todo_serializes_to_json() {
         id: 1,
         title: "Test".to_string(),
         completed: false,
+        created_at: 0,
     };
     let json = serde_json::to_string(&todo).unwrap();
     assert!(json.contains("\"id\":1"));
@@ -940,6 +947,443 @@ fn test_store_delete_all() {
     assert!(store.is_empty());
 }
 
+// ============================================================================
+// PAGINATION TESTS
+// ============================================================================
+
+fn store_with(n: u64) -> TodoStore {
+    let mut store = TodoStore::new();
+    for i in 1..=n {
+        store.add_todo(CreateTodo {
+            title: format!("Task {}", i),
+            completed: false,
+        });
+    }
+    store
+}
+
+fn ids(page: &Page) -> Vec<u64> {
+    page.items.iter().map(|t| t.id).collect()
+}
+
+#[test]
+fn test_page_after_walks_all_todos() {
+    let store = store_with(25);
+
+    let first = store.page_after(None, 10);
+    assert_eq!(ids(&first), (1..=10).collect::<Vec<_>>());
+    assert_eq!(first.next_cursor, Some(10));
+
+    let second = store.page_after(first.next_cursor, 10);
+    assert_eq!(ids(&second), (11..=20).collect::<Vec<_>>());
+    assert_eq!(second.next_cursor, Some(20));
+
+    let third = store.page_after(second.next_cursor, 10);
+    assert_eq!(ids(&third), (21..=25).collect::<Vec<_>>());
+    assert_eq!(third.next_cursor, None);
+}
+
+#[test]
+fn test_page_after_terminal_cursor_is_none() {
+    // A page that ends exactly on the last todo has nothing after it
+    let store = store_with(20);
+    let second = store.page_after(Some(10), 10);
+    assert_eq!(ids(&second), (11..=20).collect::<Vec<_>>());
+    assert_eq!(second.next_cursor, None);
+
+    assert_eq!(
+        store.page_after(Some(20), 10),
+        Page {
+            items: vec![],
+            next_cursor: None
+        }
+    );
+    assert_eq!(TodoStore::new().page_after(None, 10).next_cursor, None);
+}
+
+#[test]
+fn test_page_after_stable_when_todo_deleted_between_pages() {
+    let mut store = store_with(25);
+    let first = store.page_after(None, 10);
+
+    // Deleting an already-served todo and one on the next page must not
+    // skip or repeat anything.
+    store.delete_todo(5);
+    store.delete_todo(15);
+
+    let second = store.page_after(first.next_cursor, 10);
+    let expected: Vec<u64> = (11..=21).filter(|&id| id != 15).collect();
+    assert_eq!(ids(&second), expected);
+
+    let third = store.page_after(second.next_cursor, 10);
+    assert_eq!(ids(&third), (22..=25).collect::<Vec<_>>());
+    assert_eq!(third.next_cursor, None);
+}
+
+#[test]
+fn test_page_after_deleted_cursor_still_works() {
+    let mut store = store_with(5);
+    let first = store.page_after(None, 2);
+    assert_eq!(first.next_cursor, Some(2));
+
+    store.delete_todo(2);
+    assert_eq!(ids(&store.page_after(first.next_cursor, 2)), vec![3, 4]);
+}
+
+#[test]
+fn test_page_after_zero_limit_still_advances() {
+    let store = store_with(3);
+    let page = store.page_after(None, 0);
+    assert_eq!(ids(&page), vec![1]);
+    assert_eq!(page.next_cursor, Some(1));
+}
+
+#[test]
+fn test_cursor_token_round_trip() {
+    for id in [0, 1, 10, 255, 1 << 40, u64::MAX] {
+        let token = encode_cursor(id);
+        assert_eq!(decode_cursor(&token), Ok(id), "token {}", token);
+        assert!(token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+}
+
+#[test]
+fn test_cursor_token_is_opaque() {
+    let token = encode_cursor(42);
+    assert!(!token.contains("42"));
+    assert_ne!(encode_cursor(42), encode_cursor(43));
+}
+
+#[test]
+fn test_decode_cursor_rejects_invalid_tokens() {
+    let bad_request = Err(AppError::BadRequest("Invalid cursor".to_string()));
+    assert_eq!(decode_cursor(""), bad_request);
+    assert_eq!(decode_cursor("10"), bad_request);
+    assert_eq!(decode_cursor("not a valid token"), bad_request);
+    assert_eq!(decode_cursor("!!!!!!!!!!!!!!!!"), bad_request);
+
+    let token = encode_cursor(10);
+    assert_eq!(decode_cursor(&format!("{}A", token)), bad_request);
+    assert_eq!(decode_cursor(&token[..15]), bad_request);
+}
+
+#[test]
+fn test_decode_cursor_rejects_forged_tokens() {
+    // Changing any character changes the id or the checksum, so the
+    // checksum no longer matches.
+    let token = encode_cursor(10);
+    for i in 0..token.len() {
+        let mut forged: Vec<u8> = token.bytes().collect();
+        forged[i] = if forged[i] == b'A' { b'B' } else { b'A' };
+        let forged = String::from_utf8(forged).unwrap();
+        assert!(
+            matches!(decode_cursor(&forged), Err(AppError::BadRequest(_))),
+            "forged token {} was accepted",
+            forged
+        );
+    }
+}
+
+#[test]
+fn test_paging_with_tokens() {
+    let store = store_with(25);
+    let mut seen = Vec::new();
+    let mut token: Option<String> = None;
+
+    loop {
+        let cursor = token.as_deref().map(decode_cursor).transpose().unwrap();
+        let page = store.page_after(cursor, 10);
+        seen.extend(ids(&page));
+        match page.next_cursor {
+            Some(next) => token = Some(encode_cursor(next)),
+            None => break,
+        }
+    }
+    assert_eq!(seen, (1..=25).collect::<Vec<_>>());
+}
+
+// ============================================================================
+// SEARCH INDEX TESTS
+// ============================================================================
+
+fn store_with_titles(titles: &[&str]) -> TodoStore {
+    let mut store = TodoStore::new();
+    for title in titles {
+        store.add_todo(CreateTodo {
+            title: title.to_string(),
+            completed: false,
+        });
+    }
+    store
+}
+
+fn result_ids(todos: &[Todo]) -> Vec<u64> {
+    todos.iter().map(|t| t.id).collect()
+}
+
+fn rename(store: &mut TodoStore, id: u64, title: &str) {
+    store.update_todo(
+        id,
+        UpdateTodo {
+            title: Some(title.to_string()),
+            completed: None,
+        },
+    );
+}
+
+#[test]
+fn test_tokenize_splits_and_lowercases() {
+    assert_eq!(
+        tokenize("Buy milk, eggs & BREAD!"),
+        vec!["buy", "milk", "eggs", "bread"]
+    );
+    assert_eq!(
+        tokenize("re-read chapter_2"),
+        vec!["re", "read", "chapter", "2"]
+    );
+    assert!(tokenize("  ...  ").is_empty());
+}
+
+#[test]
+fn test_search_single_word_case_insensitive() {
+    let store = store_with_titles(&["Buy milk", "Walk the dog", "MILK the cow"]);
+    assert_eq!(result_ids(&store.search("milk ")), vec![1, 3]);
+    assert_eq!(result_ids(&store.search("Milk ")), vec![1, 3]);
+    assert!(store.search("cat ").is_empty());
+    assert!(store.search("").is_empty());
+    assert!(store.search_any("  ").is_empty());
+}
+
+#[test]
+fn test_search_and_vs_or() {
+    let store = store_with_titles(&["Buy milk", "Buy bread", "Bake bread", "Read a book"]);
+
+    assert_eq!(result_ids(&store.search("buy bread ")), vec![2]);
+    assert_eq!(result_ids(&store.search_any("buy bread ")), vec![1, 2, 3]);
+    assert!(store.search("milk bread ").is_empty());
+    assert_eq!(result_ids(&store.search_any("milk bread ")), vec![1, 2, 3]);
+
+    // Word order and repeats don't matter
+    assert_eq!(result_ids(&store.search("bread, buy, buy ")), vec![2]);
+}
+
+#[test]
+fn test_search_prefix_matches_last_word() {
+    let store = store_with_titles(&["Buy milk", "Buy milkshake", "Mill visit", "Buy bread"]);
+
+    assert_eq!(result_ids(&store.search("mil")), vec![1, 2, 3]);
+    assert_eq!(result_ids(&store.search("buy mil")), vec![1, 2]);
+    assert_eq!(result_ids(&store.search("buy milks")), vec![2]);
+
+    // Only the last word is a prefix; a trailing separator completes it
+    assert!(store.search("bu milk").is_empty());
+    assert_eq!(result_ids(&store.search("buy milk ")), vec![1]);
+    assert_eq!(result_ids(&store.search_any("bread mi")), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn test_update_title_replaces_postings() {
+    let mut store = store_with_titles(&["Buy milk", "Milk the cow"]);
+    rename(&mut store, 1, "Buy bread");
+
+    assert_eq!(result_ids(&store.search("milk ")), vec![2]);
+    assert_eq!(result_ids(&store.search("bread ")), vec![1]);
+    assert_eq!(result_ids(&store.search("buy ")), vec![1]);
+    assert!(store.search("buy milk").is_empty());
+
+    // No stale word left behind for prefix matching either
+    rename(&mut store, 2, "Feed the cow");
+    assert!(store.search("mil").is_empty());
+}
+
+#[test]
+fn test_update_title_keeps_shared_words() {
+    // The todo's own words in both titles must survive the swap
+    let mut store = store_with_titles(&["Buy milk"]);
+    rename(&mut store, 1, "buy MILK today");
+    assert_eq!(result_ids(&store.search("buy milk ")), vec![1]);
+    assert_eq!(result_ids(&store.search("today")), vec![1]);
+}
+
+#[test]
+fn test_update_completed_only_keeps_index() {
+    let mut store = store_with_titles(&["Buy milk"]);
+    store.update_todo(
+        1,
+        UpdateTodo {
+            title: None,
+            completed: Some(true),
+        },
+    );
+    let found = store.search("milk");
+    assert_eq!(result_ids(&found), vec![1]);
+    assert!(found[0].completed);
+}
+
+#[test]
+fn test_delete_removes_postings() {
+    let mut store = store_with_titles(&["Buy milk", "Buy bread"]);
+    store.delete_todo(1);
+
+    assert!(store.search("milk ").is_empty());
+    assert!(store.search("mil").is_empty());
+    assert_eq!(result_ids(&store.search("buy ")), vec![2]);
+
+    store.delete_todo(2);
+    assert!(store.search_any("buy bread").is_empty());
+}
+
+// ============================================================================
+// SOFT DELETE TESTS
+// ============================================================================
+
+fn add(store: &mut TodoStore, title: &str, completed: bool) -> Todo {
+    store.add_todo(CreateTodo {
+        title: title.to_string(),
+        completed,
+    })
+}
+
+#[test]
+fn test_delete_then_restore_round_trip() {
+    let mut store = TodoStore::new();
+    add(&mut store, "Water plants", false);
+    let original = store
+        .update_todo(
+            1,
+            UpdateTodo {
+                title: Some("Water the ferns".to_string()),
+                completed: Some(true),
+            },
+        )
+        .unwrap();
+
+    let deleted = store.delete_todo_at(1, 100).unwrap();
+    assert_eq!(deleted, original);
+    assert_eq!(
+        store.list_trash(),
+        vec![TrashedTodo {
+            todo: original.clone(),
+            deleted_at: 100
+        }]
+    );
+
+    let restored = store.restore_todo(1).unwrap();
+    assert_eq!(restored, original);
+    assert_eq!(store.get_todo(1), Some(&original));
+    assert!(store.list_trash().is_empty());
+    // The title is searchable again.
+    assert_eq!(store.search("ferns"), vec![original]);
+}
+
+#[test]
+fn test_trashed_todos_are_invisible() {
+    let mut store = TodoStore::new();
+    add(&mut store, "Keep me", true);
+    add(&mut store, "Trash me", true);
+    store.delete_todo_at(2, 5);
+
+    assert!(store.get_todo(2).is_none());
+    assert_eq!(store.get_all_todos().len(), 1);
+    assert_eq!(store.get_all_todos_sorted()[0].id, 1);
+    assert_eq!(store.count(), 1);
+    assert_eq!(store.completed_count(), 1);
+    assert!(store.search("trash").is_empty());
+    assert_eq!(store.page_after(None, 10).items.len(), 1);
+    assert!(store
+        .update_todo(
+            2,
+            UpdateTodo {
+                title: None,
+                completed: Some(false),
+            }
+        )
+        .is_none());
+    // Deleting again doesn't re-stamp it.
+    assert!(store.delete_todo_at(2, 9).is_none());
+    assert_eq!(store.list_trash()[0].deleted_at, 5);
+}
+
+#[test]
+fn test_restore_unknown_id_is_not_found() {
+    let mut store = TodoStore::new();
+    add(&mut store, "Live", false);
+    assert_eq!(store.restore_todo(1), Err(AppError::NotFound));
+    assert_eq!(store.restore_todo(42), Err(AppError::NotFound));
+
+    store.delete_todo_at(1, 0);
+    assert!(store.restore_todo(1).is_ok());
+    assert_eq!(store.restore_todo(1), Err(AppError::NotFound));
+}
+
+#[test]
+fn test_ids_not_reused_after_delete() {
+    let mut store = TodoStore::new();
+    add(&mut store, "First", false);
+    add(&mut store, "Second", false);
+    store.delete_todo_at(2, 10);
+    store.purge_older_than(11);
+
+    let third = add(&mut store, "Third", false);
+    assert_eq!(third.id, 3);
+
+    store.delete_todo_at(3, 20);
+    let fourth = add(&mut store, "Fourth", false);
+    assert_eq!(fourth.id, 4);
+    assert_eq!(store.restore_todo(3).unwrap().title, "Third");
+    assert_eq!(store.get_todo(4).unwrap().title, "Fourth");
+}
+
+#[test]
+fn test_list_trash_sorted_by_deletion_time() {
+    let mut store = TodoStore::new();
+    for title in ["a", "b", "c", "d"] {
+        add(&mut store, title, false);
+    }
+    store.delete_todo_at(3, 50);
+    store.delete_todo_at(1, 70);
+    store.delete_todo_at(4, 50);
+    store.delete_todo_at(2, 10);
+
+    let order: Vec<(u64, u64)> = store
+        .list_trash()
+        .iter()
+        .map(|t| (t.todo.id, t.deleted_at))
+        .collect();
+    assert_eq!(order, vec![(2, 10), (3, 50), (4, 50), (1, 70)]);
+}
+
+#[test]
+fn test_purge_boundary_is_exclusive() {
+    let mut store = TodoStore::new();
+    for title in ["old", "edge", "new"] {
+        add(&mut store, title, false);
+    }
+    store.delete_todo_at(1, 99);
+    store.delete_todo_at(2, 100);
+    store.delete_todo_at(3, 101);
+
+    assert_eq!(store.purge_older_than(100), 1);
+    let remaining: Vec<u64> = store.list_trash().iter().map(|t| t.todo.id).collect();
+    assert_eq!(remaining, vec![2, 3]);
+    assert_eq!(store.restore_todo(1), Err(AppError::NotFound));
+
+    assert_eq!(store.purge_older_than(100), 0);
+    assert_eq!(store.purge_older_than(u64::MAX), 2);
+    assert!(store.list_trash().is_empty());
+}
+
+#[test]
+fn test_delete_todo_stamps_current_time() {
+    let mut store = TodoStore::new();
+    add(&mut store, "Now", false);
+    store.delete_todo(1);
+    // Any real clock is well past 2020-01-01.
+    assert!(store.list_trash()[0].deleted_at > 1_577_836_800);
+}
+
 // ============================================================================
 // COMBINED WORKFLOW TESTS
 // ============================================================================
@@ -1033,3 +1477,135 @@ fn test_full_api_simulation() {
     assert_eq!(all[2].id, 4);
     assert!(!all[2].completed);
 }
+
+// ============================================================================
+// STATS TESTS
+// ============================================================================
+
+fn add_at(store: &mut TodoStore, title: &str, completed: bool, now: u64) -> Todo {
+    store.add_todo_at(
+        CreateTodo {
+            title: title.to_string(),
+            completed,
+        },
+        now,
+    )
+}
+
+#[test]
+fn test_add_todo_at_records_created_at() {
+    let mut store = TodoStore::new();
+    let todo = add_at(&mut store, "Stamped", false, 1_234);
+    assert_eq!(todo.created_at, 1_234);
+    assert_eq!(store.get_todo(todo.id).unwrap().created_at, 1_234);
+
+    // add_todo uses the system clock
+    let now = add_at(&mut store, "x", false, 0);
+    assert_eq!(now.created_at, 0);
+    let live = store.add_todo(CreateTodo {
+        title: "Live".to_string(),
+        completed: false,
+    });
+    assert!(live.created_at > 1_600_000_000);
+}
+
+#[test]
+fn test_stats_counts_and_ratio() {
+    let mut store = TodoStore::new();
+    let a = add_at(&mut store, "A", true, 0);
+    add_at(&mut store, "B", false, 0);
+    add_at(&mut store, "C", true, 0);
+    add_at(&mut store, "D", false, 0);
+    assert_eq!(
+        store.stats(),
+        TodoStats {
+            total: 4,
+            completed: 2,
+            pending: 2,
+            completion_ratio: 0.5,
+        }
+    );
+
+    // Trashed todos drop out of the stats
+    store.delete_todo_at(a.id, 10);
+    let stats = store.stats();
+    assert_eq!((stats.total, stats.completed), (3, 1));
+    assert!((stats.completion_ratio - 1.0 / 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_stats_empty_store_ratio_is_zero() {
+    let stats = TodoStore::new().stats();
+    assert_eq!(stats.total, 0);
+    assert_eq!(stats.completion_ratio, 0.0);
+    assert!(!stats.completion_ratio.is_nan());
+}
+
+#[test]
+fn test_stats_daily_buckets_include_empty_days() {
+    let day = SECONDS_PER_DAY;
+    let now = 100 * day + 3_600; // 01:00 on day 100
+    let mut store = TodoStore::new();
+    add_at(&mut store, "today", true, 100 * day + 60);
+    add_at(&mut store, "two days ago", false, 98 * day + 500);
+    add_at(&mut store, "also two days ago", true, 98 * day + 80_000);
+    add_at(&mut store, "too old", false, 90 * day);
+
+    let buckets = store.stats_daily(now, 4);
+    let starts: Vec<u64> = buckets.iter().map(|b| b.day_start).collect();
+    assert_eq!(starts, [97 * day, 98 * day, 99 * day, 100 * day]);
+    let created: Vec<usize> = buckets.iter().map(|b| b.created).collect();
+    assert_eq!(created, [0, 2, 0, 1]);
+    let completed: Vec<usize> = buckets.iter().map(|b| b.completed).collect();
+    assert_eq!(completed, [0, 1, 0, 1]);
+
+    assert!(store.stats_daily(now, 0).is_empty());
+    assert!(TodoStore::new()
+        .stats_daily(now, 3)
+        .iter()
+        .all(|b| b.created == 0 && b.completed == 0));
+}
+
+#[test]
+fn test_stats_daily_boundary_is_counted_once() {
+    let day = SECONDS_PER_DAY;
+    let mut store = TodoStore::new();
+    add_at(&mut store, "midnight", false, 5 * day);
+    add_at(&mut store, "last second", false, 5 * day - 1);
+
+    let buckets = store.stats_daily(5 * day, 2);
+    assert_eq!(buckets[0].day_start, 4 * day);
+    assert_eq!(buckets[0].created, 1);
+    assert_eq!(buckets[1].created, 1);
+    assert_eq!(buckets.iter().map(|b| b.created).sum::<usize>(), 2);
+
+    // Asking for more days than the clock has been running
+    let buckets = store.stats_daily(5 * day, 10);
+    assert_eq!(buckets.len(), 6);
+    assert_eq!(buckets[0].day_start, 0);
+}
+
+#[test]
+fn test_stats_serialization_shape() {
+    let mut store = TodoStore::new();
+    add_at(&mut store, "A", true, 0);
+    let stats = serde_json::to_value(store.stats()).unwrap();
+    let mut keys: Vec<&str> = stats
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    keys.sort();
+    assert_eq!(keys, ["completed", "completion_ratio", "pending", "total"]);
+    assert_eq!(stats["completion_ratio"], 1.0);
+
+    let bucket = serde_json::to_value(&store.stats_daily(0, 1)[0]).unwrap();
+    assert_eq!(
+        bucket,
+        serde_json::json!({"day_start": 0, "created": 1, "completed": 1})
+    );
+
+    let todo = serde_json::to_value(store.get_todo(1).unwrap()).unwrap();
+    assert_eq!(todo["created_at"], 0);
+}
@@ -2,8 +2,54 @@
 //!
 //! Student-facing API for a fixed worker thread pool.
 
+use std::collections::HashMap;
+use std::time::Duration;
+
 pub type Job = Box<dyn FnOnce() + Send + 'static>;
 
+pub const DEFAULT_LABEL: &str = "unlabeled";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LabelStats {
+    pub jobs: u64,
+    pub busy_time: Duration,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolMetrics {
+    pub per_label: HashMap<&'static str, LabelStats>,
+    pub total_jobs: u64,
+    pub total_busy_time: Duration,
+    pub uptime: Duration,
+}
+
+impl PoolMetrics {
+    pub fn label(&self, label: &str) -> LabelStats {
+        let _ = label;
+        todo!("Look up stats for one label")
+    }
+}
+
+pub struct ThreadPoolBuilder;
+
+impl ThreadPoolBuilder {
+    pub fn name(self, name: impl Into<String>) -> Self {
+        let _ = name.into();
+        todo!("Set the worker name prefix")
+    }
+
+    pub fn num_threads(self, num_threads: usize) -> Self {
+        let _ = num_threads;
+        todo!("Set the worker count")
+    }
+
+    pub fn build(self) -> ThreadPool {
+        // TODO: Spawn each worker with thread::Builder so it is named
+        // `<name>-worker-<id>` (or `worker-<id>`), sharing one metrics struct.
+        todo!("Build the ThreadPool")
+    }
+}
+
 pub struct ThreadPool;
 
 impl ThreadPool {
@@ -12,6 +58,10 @@ impl ThreadPool {
         todo!("Create ThreadPool")
     }
 
+    pub fn builder() -> ThreadPoolBuilder {
+        todo!("Start a builder with default settings")
+    }
+
     pub fn execute<F>(&self, f: F)
     where
         F: FnOnce() + Send + 'static,
@@ -20,9 +70,27 @@ impl ThreadPool {
         todo!("Execute job")
     }
 
+    pub fn execute_labeled<F>(&self, label: &'static str, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        // TODO: Send the label with the job; the worker times `job()` and
+        // records the duration under that label.
+        let _ = (label, f);
+        todo!("Execute labeled job")
+    }
+
     pub fn worker_count(&self) -> usize {
         todo!("Return worker count")
     }
+
+    pub fn name(&self) -> Option<&str> {
+        todo!("Return pool name")
+    }
+
+    pub fn metrics(&self) -> PoolMetrics {
+        todo!("Snapshot the pool metrics")
+    }
 }
 
 pub struct Worker {
@@ -21,4 +21,22 @@ fn main() {
     thread::sleep(Duration::from_millis(100));
     println!("workers: {}", pool.worker_count());
     println!("jobs completed: {}", counter.load(Ordering::SeqCst));
+
+    let io_pool = ThreadPool::builder().name("io").num_threads(2).build();
+    for _ in 0..3 {
+        io_pool.execute_labeled("fetch", || thread::sleep(Duration::from_millis(10)));
+    }
+    io_pool.execute(|| {
+        println!("running on {:?}", thread::current().name());
+    });
+
+    thread::sleep(Duration::from_millis(100));
+    let metrics = io_pool.metrics();
+    for (label, stats) in &metrics.per_label {
+        println!("{}: {} jobs, {:?} busy", label, stats.jobs, stats.busy_time);
+    }
+    println!(
+        "total: {} jobs, {:?} busy over {:?}",
+        metrics.total_jobs, metrics.total_busy_time, metrics.uptime
+    );
 }
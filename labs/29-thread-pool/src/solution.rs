@@ -1,42 +1,174 @@
+use std::collections::HashMap;
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
 pub type Job = Box<dyn FnOnce() + Send + 'static>;
 
+/// Label recorded for jobs submitted with plain `execute`.
+pub const DEFAULT_LABEL: &str = "unlabeled";
+
 // Classroom narrative:
 // 1. ThreadPool owns a sender and workers; each worker owns a JoinHandle and shares the receiver via Arc<Mutex<_>>.
 // 2. Jobs are boxed on the heap so they can be sent across threads; Message enum separates NewJob vs Terminate.
 // 3. execute() passes a shared sender clone; Drop flushes shutdown signals before joining threads.
+// 4. Workers share one Arc<Metrics>: totals are atomics (no lock on the hot path),
+//    the per-label map sits behind a Mutex that is only held for one map update.
 
 enum Message {
-    NewJob(Job),
+    NewJob { label: &'static str, job: Job },
     Terminate,
 }
 
-pub struct ThreadPool {
-    workers: Vec<Worker>,
-    sender: Option<mpsc::Sender<Message>>,
+/// How many jobs with one label have finished, and how long they ran.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LabelStats {
+    pub jobs: u64,
+    pub busy_time: Duration,
 }
 
-impl ThreadPool {
-    pub fn new(size: usize) -> ThreadPool {
-        assert!(size > 0, "Thread pool size must be greater than 0");
+/// A point-in-time copy of a pool's counters.
+///
+/// Only finished jobs are counted; a job that is still running shows up in
+/// the next snapshot.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolMetrics {
+    pub per_label: HashMap<&'static str, LabelStats>,
+    pub total_jobs: u64,
+    /// Time spent inside jobs, summed over all workers. Can exceed `uptime`
+    /// when several workers are busy at once.
+    pub total_busy_time: Duration,
+    pub uptime: Duration,
+}
+
+impl PoolMetrics {
+    /// Stats for `label`, zero if no such job has finished.
+    pub fn label(&self, label: &str) -> LabelStats {
+        self.per_label.get(label).copied().unwrap_or_default()
+    }
+}
+
+struct Metrics {
+    started: Instant,
+    // The totals are summed from this map in `snapshot`, so a snapshot
+    // can never show a job in the totals but not under its label.
+    per_label: Mutex<HashMap<&'static str, LabelStats>>,
+}
+
+impl Metrics {
+    fn new() -> Metrics {
+        Metrics {
+            started: Instant::now(),
+            per_label: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn record(&self, label: &'static str, elapsed: Duration) {
+        let mut per_label = self.per_label.lock().unwrap();
+        let stats = per_label.entry(label).or_default();
+        stats.jobs += 1;
+        stats.busy_time += elapsed;
+    }
+
+    fn snapshot(&self) -> PoolMetrics {
+        let per_label = self.per_label.lock().unwrap().clone();
+        PoolMetrics {
+            total_jobs: per_label.values().map(|stats| stats.jobs).sum(),
+            total_busy_time: per_label.values().map(|stats| stats.busy_time).sum(),
+            per_label,
+            uptime: self.started.elapsed(),
+        }
+    }
+}
+
+/// Configures a `ThreadPool` before any threads are spawned.
+pub struct ThreadPoolBuilder {
+    name: Option<String>,
+    num_threads: usize,
+}
+
+impl ThreadPoolBuilder {
+    /// Prefix for worker thread names: `name("io")` gives `io-worker-0`,
+    /// `io-worker-1`, ... Unnamed pools use `worker-0`, `worker-1`, ...
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads;
+        self
+    }
+
+    pub fn build(self) -> ThreadPool {
+        assert!(
+            self.num_threads > 0,
+            "Thread pool size must be greater than 0"
+        );
 
         let (sender, receiver) = mpsc::channel();
         let receiver = Arc::new(Mutex::new(receiver));
+        let metrics = Arc::new(Metrics::new());
 
-        let mut workers = Vec::with_capacity(size);
-        for id in 0..size {
-            workers.push(Worker::new(id, Arc::clone(&receiver)));
+        let mut workers = Vec::with_capacity(self.num_threads);
+        for id in 0..self.num_threads {
+            let thread_name = match &self.name {
+                Some(name) => format!("{}-worker-{}", name, id),
+                None => format!("worker-{}", id),
+            };
+            workers.push(Worker::new(
+                id,
+                thread_name,
+                Arc::clone(&receiver),
+                Arc::clone(&metrics),
+            ));
         }
 
         ThreadPool {
+            name: self.name,
             workers,
             sender: Some(sender),
+            metrics,
         }
     }
+}
+
+impl Default for ThreadPoolBuilder {
+    fn default() -> Self {
+        ThreadPoolBuilder {
+            name: None,
+            num_threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+pub struct ThreadPool {
+    name: Option<String>,
+    workers: Vec<Worker>,
+    sender: Option<mpsc::Sender<Message>>,
+    metrics: Arc<Metrics>,
+}
+
+impl ThreadPool {
+    pub fn new(size: usize) -> ThreadPool {
+        ThreadPool::builder().num_threads(size).build()
+    }
+
+    /// Start configuring a pool. Defaults to one thread per CPU and no name.
+    pub fn builder() -> ThreadPoolBuilder {
+        ThreadPoolBuilder::default()
+    }
 
     pub fn execute<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.execute_labeled(DEFAULT_LABEL, f);
+    }
+
+    /// Like `execute`, but the job's count and run time are reported under
+    /// `label` in `metrics()`.
+    pub fn execute_labeled<F>(&self, label: &'static str, f: F)
     where
         F: FnOnce() + Send + 'static,
     {
@@ -44,13 +176,22 @@ impl ThreadPool {
         self.sender
             .as_ref()
             .unwrap()
-            .send(Message::NewJob(job))
+            .send(Message::NewJob { label, job })
             .unwrap();
     }
 
     pub fn worker_count(&self) -> usize {
         self.workers.len()
     }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Counters for every job finished so far.
+    pub fn metrics(&self) -> PoolMetrics {
+        self.metrics.snapshot()
+    }
 }
 
 impl Drop for ThreadPool {
@@ -77,14 +218,26 @@ pub struct Worker {
 }
 
 impl Worker {
-    fn new(id: usize, receiver: Arc<Mutex<mpsc::Receiver<Message>>>) -> Worker {
-        let thread = thread::spawn(move || loop {
-            let message = receiver.lock().unwrap().recv().unwrap();
-            match message {
-                Message::NewJob(job) => job(),
-                Message::Terminate => break,
-            }
-        });
+    fn new(
+        id: usize,
+        name: String,
+        receiver: Arc<Mutex<mpsc::Receiver<Message>>>,
+        metrics: Arc<Metrics>,
+    ) -> Worker {
+        let thread = thread::Builder::new()
+            .name(name)
+            .spawn(move || loop {
+                let message = receiver.lock().unwrap().recv().unwrap();
+                match message {
+                    Message::NewJob { label, job } => {
+                        let start = Instant::now();
+                        job();
+                        metrics.record(label, start.elapsed());
+                    }
+                    Message::Terminate => break,
+                }
+            })
+            .expect("failed to spawn worker thread");
 
         Worker {
             id,
@@ -7,11 +7,13 @@
 // - Graceful shutdown via Drop
 // - Concurrent job execution
 // - Edge cases: pool size of 1, many jobs on few workers
+// - Named worker threads and per-label job metrics
 
-use thread_pool::solution::ThreadPool;
+use thread_pool::solution::{LabelStats, PoolMetrics, ThreadPool, DEFAULT_LABEL};
 
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Barrier};
+use std::sync::{mpsc, Arc, Barrier, Mutex};
 use std::thread;
 use std::time::Duration;
 
@@ -363,3 +365,115 @@ fn test_worker_count() {
         assert_eq!(pool.worker_count(), size);
     }
 }
+
+// ============================================================================
+// NAMED POOLS AND METRICS
+// ============================================================================
+
+/// Metrics are recorded after each job returns, so poll until `jobs` have
+/// finished rather than guessing a sleep.
+fn wait_for_jobs(pool: &ThreadPool, jobs: u64) -> PoolMetrics {
+    for _ in 0..500 {
+        let metrics = pool.metrics();
+        if metrics.total_jobs >= jobs {
+            return metrics;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    panic!("timed out waiting for {} jobs", jobs);
+}
+
+#[test]
+fn test_builder_sets_size_and_name() {
+    let pool = ThreadPool::builder().name("io").num_threads(3).build();
+    assert_eq!(pool.worker_count(), 3);
+    assert_eq!(pool.name(), Some("io"));
+
+    let unnamed = ThreadPool::new(1);
+    assert_eq!(unnamed.name(), None);
+}
+
+#[test]
+#[should_panic(expected = "Thread pool size must be greater than 0")]
+fn test_builder_with_zero_threads_panics() {
+    let _pool = ThreadPool::builder().num_threads(0).build();
+}
+
+#[test]
+fn test_worker_threads_are_named() {
+    let pool = ThreadPool::builder().name("io").num_threads(4).build();
+    let names = Arc::new(Mutex::new(HashSet::new()));
+    let barrier = Arc::new(Barrier::new(4));
+
+    // The barrier forces all four workers to take one job each.
+    for _ in 0..4 {
+        let names = Arc::clone(&names);
+        let barrier = Arc::clone(&barrier);
+        pool.execute(move || {
+            let name = thread::current().name().map(str::to_string);
+            names.lock().unwrap().insert(name);
+            barrier.wait();
+        });
+    }
+    wait_for_jobs(&pool, 4);
+
+    let expected: HashSet<Option<String>> =
+        (0..4).map(|id| Some(format!("io-worker-{}", id))).collect();
+    assert_eq!(*names.lock().unwrap(), expected);
+
+    let unnamed = ThreadPool::new(1);
+    let (tx, rx) = mpsc::channel();
+    unnamed.execute(move || {
+        tx.send(thread::current().name().map(str::to_string))
+            .unwrap();
+    });
+    assert_eq!(rx.recv().unwrap().as_deref(), Some("worker-0"));
+}
+
+#[test]
+fn test_labels_accumulate_counts() {
+    let pool = ThreadPool::new(3);
+    for _ in 0..5 {
+        pool.execute_labeled("parse", || {});
+    }
+    for _ in 0..2 {
+        pool.execute_labeled("render", || {});
+    }
+    let metrics = wait_for_jobs(&pool, 7);
+
+    assert_eq!(metrics.total_jobs, 7);
+    assert_eq!(metrics.label("parse").jobs, 5);
+    assert_eq!(metrics.label("render").jobs, 2);
+    assert_eq!(metrics.label("missing"), LabelStats::default());
+    assert_eq!(metrics.per_label.len(), 2);
+}
+
+#[test]
+fn test_unlabeled_jobs_use_default_bucket() {
+    let pool = ThreadPool::new(2);
+    pool.execute(|| {});
+    pool.execute(|| {});
+    pool.execute_labeled("io", || {});
+    let metrics = wait_for_jobs(&pool, 3);
+
+    assert_eq!(metrics.label(DEFAULT_LABEL).jobs, 2);
+    assert_eq!(metrics.label("io").jobs, 1);
+}
+
+#[test]
+fn test_busy_time_covers_job_durations_and_grows() {
+    let pool = ThreadPool::new(2);
+    for _ in 0..2 {
+        pool.execute_labeled("sleepy", || thread::sleep(Duration::from_millis(30)));
+    }
+    let first = wait_for_jobs(&pool, 2);
+    assert!(first.total_busy_time >= Duration::from_millis(60));
+    assert!(first.label("sleepy").busy_time >= Duration::from_millis(60));
+    assert_eq!(first.label("sleepy").busy_time, first.total_busy_time);
+
+    pool.execute(|| thread::sleep(Duration::from_millis(10)));
+    let second = wait_for_jobs(&pool, 3);
+    assert!(second.total_busy_time >= first.total_busy_time + Duration::from_millis(10));
+    assert!(second.uptime >= first.uptime);
+    assert!(second.label(DEFAULT_LABEL).busy_time >= Duration::from_millis(10));
+}
@@ -4,7 +4,7 @@
 
 use std::marker::PhantomData;
 use std::ptr;
-use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::atomic::{AtomicPtr, AtomicU64, AtomicUsize, Ordering};
 
 pub struct Node<T> {
     _marker: PhantomData<T>,
@@ -12,6 +12,22 @@ pub struct Node<T> {
 
 pub struct LockFreeStack<T> {
     head: AtomicPtr<Node<T>>,
+    operations: AtomicU64,
+    cas_failures: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentionStats {
+    pub operations: u64,
+    pub cas_failures: u64,
+}
+
+impl ContentionStats {
+    pub fn failure_ratio(&self) -> f64 {
+        // TODO: cas_failures / (operations + cas_failures), or 0.0 when
+        // nothing was attempted.
+        todo!("Compute the CAS failure ratio")
+    }
 }
 
 impl<T> LockFreeStack<T> {
@@ -32,6 +48,30 @@ impl<T> LockFreeStack<T> {
         let _ = (self, Ordering::Acquire, Ordering::AcqRel);
         todo!("Implement the lock-free pop operation")
     }
+
+    pub fn push_batch(&self, items: Vec<T>) {
+        // TODO: Link the items into a private chain (last item on top), then
+        // splice the whole chain onto the head with one CAS loop.
+        let _ = (self, items);
+        todo!("Push a batch with a single CAS")
+    }
+
+    pub fn operations(&self) -> u64 {
+        // TODO: Count successful CASes in push/pop/push_batch (Relaxed).
+        let _ = &self.operations;
+        todo!("Read the operations counter")
+    }
+
+    pub fn cas_failures(&self) -> u64 {
+        // TODO: Count failed CASes, and back off exponentially with
+        // std::hint::spin_loop() before each retry.
+        let _ = &self.cas_failures;
+        todo!("Read the CAS failure counter")
+    }
+
+    pub fn contention_stats(&self) -> ContentionStats {
+        todo!("Snapshot both counters")
+    }
 }
 
 impl<T> Drop for LockFreeStack<T> {
@@ -40,5 +80,70 @@ impl<T> Drop for LockFreeStack<T> {
     }
 }
 
+/// Treiber stack that defers freeing popped nodes until `collect` sees no
+/// push/pop in flight.
+pub struct ReclaimedStack<T> {
+    retired_len: AtomicUsize,
+    active: AtomicUsize,
+    generation: AtomicU64,
+    _owns: PhantomData<T>,
+}
+
+// Pushing on one thread and popping on another moves `T` between threads,
+// so sharing the stack needs `T: Send` (and never `T: Sync`).
+unsafe impl<T: Send> Send for ReclaimedStack<T> {}
+unsafe impl<T: Send> Sync for ReclaimedStack<T> {}
+
+impl<T> ReclaimedStack<T> {
+    pub fn new() -> Self {
+        // TODO: Empty head and retired lists, all counters at zero.
+        todo!("Initialize the reclaiming stack")
+    }
+
+    pub fn push(&self, val: T) {
+        // TODO: Bump `active` for the whole operation (a guard whose Drop
+        // decrements it), then do the usual Treiber push.
+        let _ = (self, val);
+        todo!("Push while marked active")
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        // TODO: Like LockFreeStack::pop while marked active, but after the
+        // winning CAS move the value out and push the node onto the retired
+        // list instead of freeing it.
+        let _ = &self.active;
+        todo!("Pop and retire the node")
+    }
+
+    pub fn collect(&self) -> usize {
+        // TODO: Swap the retired list out. If `active` is zero, free the
+        // batch (without dropping the moved-out values) and bump
+        // `generation`; otherwise push the batch back and return 0.
+        let _ = &self.generation;
+        todo!("Free retired nodes once quiescent")
+    }
+
+    pub fn retired_len(&self) -> usize {
+        let _ = &self.retired_len;
+        todo!("Count nodes waiting to be freed")
+    }
+
+    pub fn generation(&self) -> u64 {
+        todo!("Count successful collect passes")
+    }
+}
+
+impl<T> Default for ReclaimedStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for ReclaimedStack<T> {
+    fn drop(&mut self) {
+        // TODO: Drop remaining values, then free every node, retired or not.
+    }
+}
+
 #[doc(hidden)]
 pub mod solution;
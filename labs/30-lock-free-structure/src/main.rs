@@ -3,7 +3,7 @@
 //! This binary demonstrates the `LockFreeStack` from our library.
 //! Run with: cargo run -p lock-free-structure
 
-use lock_free_structure::solution::LockFreeStack;
+use lock_free_structure::solution::{LockFreeStack, ReclaimedStack};
 use std::sync::Arc;
 use std::thread;
 
@@ -29,13 +29,41 @@ fn main() {
     println!();
 
     // ============================================================================
-    // DEMO 2: Concurrent Stress Test
+    // DEMO 2: Batch Push
     // ============================================================================
-    println!("2. Concurrent Stress Test:\n");
+    println!("2. Batch Push (one CAS for many items):\n");
+    println!("   -------------------------------------\n");
+
+    let stack = LockFreeStack::new();
+    stack.push_batch(vec![1, 2, 3]);
+    let drained: Vec<i32> = std::iter::from_fn(|| stack.pop()).collect();
+    println!("   push_batch([1, 2, 3]) then pop all: {:?}\n", drained);
+    println!("   {:?}\n", stack.contention_stats());
+    println!();
+
+    // ============================================================================
+    // DEMO 3: Concurrent Stress Test
+    // ============================================================================
+    println!("3. Concurrent Stress Test:\n");
     println!("   ----------------------\n");
     demo_concurrent_stress_test();
     println!();
 
+    // ============================================================================
+    // DEMO 4: Deferred Reclamation
+    // ============================================================================
+    println!("4. Deferred Reclamation (ReclaimedStack):\n");
+    println!("   -------------------------------------\n");
+
+    let stack = ReclaimedStack::new();
+    for i in 0..5 {
+        stack.push(i);
+    }
+    let popped: Vec<i32> = (0..3).filter_map(|_| stack.pop()).collect();
+    println!("   Popped {:?}; {} nodes retired, not freed\n", popped, stack.retired_len());
+    println!("   collect() freed {} nodes (generation {})\n", stack.collect(), stack.generation());
+    println!();
+
     println!("=== Demo Complete! ===\n");
     println!("\nNow try:\n");
     println!("  1. Look at src/solution.rs for detailed explanations\n");
@@ -77,6 +105,13 @@ fn demo_concurrent_stress_test() {
     }
 
     println!("   Popped {} unique items from the stack.\n", count);
+    let stats = stack.contention_stats();
+    println!(
+        "   CAS failures: {} of {} attempts ({:.2}%)\n",
+        stats.cas_failures,
+        stats.operations + stats.cas_failures,
+        stats.failure_ratio() * 100.0
+    );
 
     if count == num_threads * items_per_thread {
         println!("   ✅ Success: All items were pushed and popped correctly!\n");
@@ -1,5 +1,10 @@
+use std::hint;
+use std::mem::ManuallyDrop;
 use std::ptr;
-use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::atomic::{AtomicPtr, AtomicU64, AtomicUsize, Ordering};
+
+/// Upper bound on spin iterations between CAS retries.
+const MAX_BACKOFF_SPINS: u32 = 1 << 10;
 
 /// Node held on the heap. `next` is a raw pointer to the next node.
 pub struct Node<T> {
@@ -8,8 +13,63 @@ pub struct Node<T> {
 }
 
 /// A simple lock-free stack using atomic compare-and-swap.
+///
+/// Failed CAS attempts back off exponentially before retrying, and the
+/// stack counts successes and failures so contention can be measured.
 pub struct LockFreeStack<T> {
     head: AtomicPtr<Node<T>>,
+    operations: AtomicU64,
+    cas_failures: AtomicU64,
+}
+
+/// A snapshot of a stack's contention counters.
+///
+/// The two counters are read separately, so under concurrent use they may
+/// be a few operations apart. That's fine for measuring, not for logic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentionStats {
+    /// Successful CASes: one per push, per non-empty pop, per batch.
+    pub operations: u64,
+    /// CAS attempts that lost a race and had to retry.
+    pub cas_failures: u64,
+}
+
+impl ContentionStats {
+    /// Fraction of CAS attempts that failed, from 0.0 (no contention) up
+    /// toward 1.0. Returns 0.0 when nothing has been attempted.
+    pub fn failure_ratio(&self) -> f64 {
+        let attempts = self.operations + self.cas_failures;
+        if attempts == 0 {
+            0.0
+        } else {
+            self.cas_failures as f64 / attempts as f64
+        }
+    }
+}
+
+/// Exponential backoff for CAS retry loops.
+///
+/// Each failure spins twice as long as the last, up to
+/// `MAX_BACKOFF_SPINS`. Spreading retries out gives the thread that won
+/// the race time to finish, instead of every loser immediately hammering
+/// the same cache line again.
+struct Backoff {
+    spins: u32,
+}
+
+impl Backoff {
+    fn new() -> Self {
+        Backoff { spins: 1 }
+    }
+
+    fn spin(&mut self) {
+        for _ in 0..self.spins {
+            // Tells the CPU we're busy-waiting (PAUSE on x86), which saves
+            // power and frees resources for a sibling hyperthread.
+            hint::spin_loop();
+        }
+        self.spins = (self.spins * 2).min(MAX_BACKOFF_SPINS);
+    }
 }
 
 impl<T> LockFreeStack<T> {
@@ -17,6 +77,8 @@ impl<T> LockFreeStack<T> {
     pub fn new() -> Self {
         Self {
             head: AtomicPtr::new(ptr::null_mut()),
+            operations: AtomicU64::new(0),
+            cas_failures: AtomicU64::new(0),
         }
     }
 
@@ -30,25 +92,59 @@ impl<T> LockFreeStack<T> {
             next: ptr::null_mut(),
         }));
 
+        // A single node is a batch whose first and last node coincide.
+        self.splice(new_node, new_node);
+    }
+
+    /// Pushes every item in `items` with a single CAS.
+    ///
+    /// The result is the same as pushing the items one by one in order, so
+    /// the last item ends up on top. The nodes are linked privately first;
+    /// only the final splice touches the shared head, so a producer with
+    /// many items contends once instead of once per item.
+    pub fn push_batch(&self, items: Vec<T>) {
+        let mut top: *mut Node<T> = ptr::null_mut();
+        let mut bottom: *mut Node<T> = ptr::null_mut();
+
+        for val in items {
+            let node = Box::into_raw(Box::new(Node { val, next: top }));
+            if bottom.is_null() {
+                bottom = node;
+            }
+            top = node;
+        }
+
+        if !top.is_null() {
+            self.splice(top, bottom);
+        }
+    }
+
+    /// Links the private chain `top ..= bottom` above the current head.
+    fn splice(&self, top: *mut Node<T>, bottom: *mut Node<T>) {
+        let mut backoff = Backoff::new();
         loop {
             let head = self.head.load(Ordering::Acquire);
-            // SAFETY: new_node is uniquely owned in this loop until successful CAS.
+            // SAFETY: the chain is uniquely owned in this loop until successful CAS.
             unsafe {
-                (*new_node).next = head;
+                (*bottom).next = head;
             }
 
             if self
                 .head
-                .compare_exchange(head, new_node, Ordering::Release, Ordering::Relaxed)
+                .compare_exchange(head, top, Ordering::Release, Ordering::Relaxed)
                 .is_ok()
             {
-                break;
+                self.operations.fetch_add(1, Ordering::Relaxed);
+                return;
             }
+            self.cas_failures.fetch_add(1, Ordering::Relaxed);
+            backoff.spin();
         }
     }
 
     /// Pops a node by atomically moving the head pointer.
     pub fn pop(&self) -> Option<T> {
+        let mut backoff = Backoff::new();
         loop {
             let head = self.head.load(Ordering::Acquire);
             if head.is_null() {
@@ -63,10 +159,34 @@ impl<T> LockFreeStack<T> {
                 .compare_exchange(head, next, Ordering::AcqRel, Ordering::Acquire)
                 .is_ok()
             {
+                self.operations.fetch_add(1, Ordering::Relaxed);
                 // SAFETY: successful CAS gives exclusive ownership of old head.
                 let boxed = unsafe { Box::from_raw(head) };
                 return Some(boxed.val);
             }
+            self.cas_failures.fetch_add(1, Ordering::Relaxed);
+            backoff.spin();
+        }
+    }
+
+    /// Number of successful CASes so far (pushes, non-empty pops, batches).
+    ///
+    /// The counters use Relaxed ordering: they are statistics, not
+    /// synchronization, so they don't need to order anything else.
+    pub fn operations(&self) -> u64 {
+        self.operations.load(Ordering::Relaxed)
+    }
+
+    /// Number of CAS attempts that lost a race and were retried.
+    pub fn cas_failures(&self) -> u64 {
+        self.cas_failures.load(Ordering::Relaxed)
+    }
+
+    /// Both counters in one snapshot.
+    pub fn contention_stats(&self) -> ContentionStats {
+        ContentionStats {
+            operations: self.operations(),
+            cas_failures: self.cas_failures(),
         }
     }
 }
@@ -77,3 +197,286 @@ impl<T> Drop for LockFreeStack<T> {
         while self.pop().is_some() {}
     }
 }
+
+// ============================================================================
+// DEFERRED RECLAMATION: ReclaimedStack
+// ============================================================================
+//
+// `LockFreeStack::pop` frees the old head right after winning its CAS. But a
+// second popper may have loaded the same head a moment earlier and be about
+// to read `(*head).next` - from memory that was just freed. Worse, the
+// allocator may hand that address straight back to a new `push`, so the
+// loser's stale CAS can *succeed* (the ABA problem from the README).
+//
+// `ReclaimedStack` fixes both by never freeing a node while anyone might
+// still be looking at it:
+//
+//   pop():     unlink node ---> push it onto `retired` (memory stays valid)
+//   collect(): detach `retired` ---> is `active == 0`? ---> free the batch
+//                                          |
+//                                          +-- no: put the batch back
+//
+// Every push/pop holds `active` above zero for its whole duration. A node
+// is retired only after it was unlinked, so once the retired batch has been
+// detached, the only operations that can still hold a pointer into it are
+// ones that were already running. If `active` reads zero after the detach,
+// all of those have finished, and nobody new can find the nodes.
+//
+// All atomics here use `SeqCst`. The argument above needs "the detach, then
+// the `active` check, then a new operation's load of `head`" to happen in
+// one agreed order across threads; Acquire/Release alone doesn't give that.
+//
+// WHAT THIS CLOSES (compared with `LockFreeStack`):
+//   - Use-after-free in pop: a loser reading `(*head).next` on a node that
+//     was just popped. The node is retired, not freed, while the loser runs.
+//   - ABA through address reuse: while an operation is in flight, no popped
+//     node's memory goes back to the allocator, so a fresh push can't get
+//     the same address and fool a stale CAS.
+//
+// WHAT IT DOESN'T (and real hazard pointers do):
+//   - Bounded garbage. One slow thread, or traffic that never pauses, keeps
+//     `active` above zero, so `collect` frees nothing and `retired` grows
+//     without limit. Hazard pointers publish *which* nodes each thread is
+//     using and can free everything else, so unreclaimed memory stays at
+//     O(threads x hazards) no matter what.
+//   - Per-node precision. Quiescence is all-or-nothing: a reader touching
+//     node A blocks the freeing of unrelated node B.
+//   - Scalability. Every operation bumps the same `active` cache line, which
+//     becomes a hot spot of its own; hazard pointers are per-thread slots.
+//   - Automatic reclamation. Nothing is freed until someone calls
+//     `collect` (or drops the stack).
+
+/// A stack node that can sit on the retired list after being popped.
+///
+/// `val` is `ManuallyDrop` because `pop` moves the value out while the node
+/// itself stays allocated; freeing the node later must not drop `val` again.
+struct ReclaimNode<T> {
+    val: ManuallyDrop<T>,
+    next: *mut ReclaimNode<T>,
+    /// Link in the retired list. Kept separate from `next`, which stale
+    /// poppers may still be reading when the node is retired.
+    retired_next: *mut ReclaimNode<T>,
+}
+
+/// A Treiber stack whose popped nodes are freed later, by `collect`, once
+/// no push or pop is in flight.
+///
+/// Behaves exactly like `LockFreeStack` for push/pop; the difference is
+/// only in when node memory goes back to the allocator.
+pub struct ReclaimedStack<T> {
+    head: AtomicPtr<ReclaimNode<T>>,
+    /// Popped nodes waiting to be freed.
+    retired: AtomicPtr<ReclaimNode<T>>,
+    /// Number of nodes on `retired`.
+    retired_len: AtomicUsize,
+    /// Pushes and pops currently running.
+    active: AtomicUsize,
+    /// Number of `collect` passes that found the stack quiescent.
+    generation: AtomicU64,
+}
+
+// `AtomicPtr` is `Send + Sync` whatever it points to, so the compiler would
+// derive both for any `T`; we state the real bounds by hand. Sharing a
+// `&ReclaimedStack` lets one thread push a value and another pop it, which
+// moves the `T` between threads, so even `Sync` needs `T: Send`. Nothing
+// ever hands out a `&T`, so `T: Sync` is not required.
+unsafe impl<T: Send> Send for ReclaimedStack<T> {}
+unsafe impl<T: Send> Sync for ReclaimedStack<T> {}
+
+/// Marks one push/pop as in flight for as long as it lives.
+///
+/// Decrementing in `Drop` keeps the count right even if the operation
+/// unwinds; a leaked increment would block `collect` forever.
+struct ActiveGuard<'a> {
+    active: &'a AtomicUsize,
+}
+
+impl<'a> ActiveGuard<'a> {
+    fn enter(active: &'a AtomicUsize) -> Self {
+        active.fetch_add(1, Ordering::SeqCst);
+        ActiveGuard { active }
+    }
+}
+
+impl Drop for ActiveGuard<'_> {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl<T> ReclaimedStack<T> {
+    /// Creates an empty stack with an empty retired list.
+    pub fn new() -> Self {
+        Self {
+            head: AtomicPtr::new(ptr::null_mut()),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            retired_len: AtomicUsize::new(0),
+            active: AtomicUsize::new(0),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Pushes `val` on top of the stack.
+    ///
+    /// Push never dereferences the head it reads, so it couldn't touch a
+    /// retired node anyway; it still counts as active so that `collect`
+    /// has one simple rule: "nothing running".
+    pub fn push(&self, val: T) {
+        let _guard = ActiveGuard::enter(&self.active);
+        let node = Box::into_raw(Box::new(ReclaimNode {
+            val: ManuallyDrop::new(val),
+            next: ptr::null_mut(),
+            retired_next: ptr::null_mut(),
+        }));
+
+        let mut backoff = Backoff::new();
+        loop {
+            let head = self.head.load(Ordering::SeqCst);
+            // SAFETY: the node is private to this loop until the CAS succeeds.
+            unsafe {
+                (*node).next = head;
+            }
+            if self
+                .head
+                .compare_exchange(head, node, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                return;
+            }
+            backoff.spin();
+        }
+    }
+
+    /// Pops the top value. The emptied node goes onto the retired list
+    /// instead of being freed.
+    pub fn pop(&self) -> Option<T> {
+        let _guard = ActiveGuard::enter(&self.active);
+        let mut backoff = Backoff::new();
+        loop {
+            let head = self.head.load(Ordering::SeqCst);
+            if head.is_null() {
+                return None;
+            }
+
+            // SAFETY: `head` was reachable after we became active, so it can
+            // at most have been retired since - never freed.
+            let next = unsafe { (*head).next };
+
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                // SAFETY: winning the CAS makes us the only thread that will
+                // ever read `val`; ManuallyDrop stops the node dropping it again.
+                let val = unsafe { ManuallyDrop::take(&mut (*head).val) };
+                self.retire(head);
+                return Some(val);
+            }
+            backoff.spin();
+        }
+    }
+
+    /// Pushes an unlinked node onto the retired list.
+    fn retire(&self, node: *mut ReclaimNode<T>) {
+        self.retired_len.fetch_add(1, Ordering::SeqCst);
+        self.push_retired(node, node);
+    }
+
+    /// Links the private retired chain `top ..= bottom` onto `retired`.
+    fn push_retired(&self, top: *mut ReclaimNode<T>, bottom: *mut ReclaimNode<T>) {
+        loop {
+            let old = self.retired.load(Ordering::SeqCst);
+            // SAFETY: the chain is owned by this call until the CAS succeeds,
+            // and only the retired list ever reads `retired_next`.
+            unsafe {
+                (*bottom).retired_next = old;
+            }
+            if self
+                .retired
+                .compare_exchange(old, top, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                return;
+            }
+            hint::spin_loop();
+        }
+    }
+
+    /// Frees retired nodes if no push or pop is in flight, returning how
+    /// many were freed.
+    ///
+    /// Returns 0 and keeps the nodes retired if anything was running; call
+    /// again later. Safe to call from any thread, concurrently with
+    /// push/pop and with other `collect` calls.
+    pub fn collect(&self) -> usize {
+        let batch = self.retired.swap(ptr::null_mut(), Ordering::SeqCst);
+        if batch.is_null() {
+            return 0;
+        }
+
+        if self.active.load(Ordering::SeqCst) != 0 {
+            // Someone may still hold a pointer into the batch. Hand it back.
+            let mut bottom = batch;
+            // SAFETY: the detached batch is owned by this call.
+            unsafe {
+                while !(*bottom).retired_next.is_null() {
+                    bottom = (*bottom).retired_next;
+                }
+            }
+            self.push_retired(batch, bottom);
+            return 0;
+        }
+
+        // SAFETY: every node in the batch was unlinked before it was retired,
+        // and every operation that could have seen it has finished.
+        let freed = unsafe { free_retired(batch) };
+        self.retired_len.fetch_sub(freed, Ordering::SeqCst);
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        freed
+    }
+
+    /// Popped nodes not yet freed by `collect`.
+    pub fn retired_len(&self) -> usize {
+        self.retired_len.load(Ordering::SeqCst)
+    }
+
+    /// How many `collect` passes have freed a batch.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+}
+
+/// Frees a retired chain without dropping the (already moved-out) values.
+///
+/// # Safety
+///
+/// The caller must own the chain: no other thread may reach any node in it.
+unsafe fn free_retired<T>(mut node: *mut ReclaimNode<T>) -> usize {
+    let mut freed = 0;
+    while !node.is_null() {
+        let next = (*node).retired_next;
+        drop(Box::from_raw(node));
+        node = next;
+        freed += 1;
+    }
+    freed
+}
+
+impl<T> Default for ReclaimedStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for ReclaimedStack<T> {
+    /// `&mut self` proves no operation is in flight, so everything can go:
+    /// live values are popped and dropped, then every node is freed.
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+        // SAFETY: exclusive access; nothing else can reach the retired list.
+        unsafe {
+            free_retired(*self.retired.get_mut());
+        }
+    }
+}
@@ -6,10 +6,11 @@
 //! - Edge cases (empty stack, etc.)
 //! - Memory safety (drop behavior)
 //! - Stress testing under contention from multiple threads
+//! - Deferred node reclamation in `ReclaimedStack`
 
-use lock_free_structure::solution::LockFreeStack;
-use std::sync::Arc;
-use std::sync::atomic::Ordering;
+use lock_free_structure::solution::{ContentionStats, LockFreeStack, ReclaimedStack};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
 use std::thread;
 
 // ============================================================================
@@ -233,6 +234,138 @@ fn test_concurrent_push_and_pop() {
     );
 }
 
+// ============================================================================
+// CONTENTION METRICS AND BATCH PUSH TESTS
+// ============================================================================
+
+#[test]
+fn test_counters_zero_without_contention() {
+    let stack = LockFreeStack::new();
+    assert_eq!(stack.contention_stats().failure_ratio(), 0.0);
+
+    for i in 0..100 {
+        stack.push(i);
+    }
+    stack.push_batch(vec![100, 101, 102]);
+    while stack.pop().is_some() {}
+    assert_eq!(stack.pop(), None);
+
+    // 100 pushes + 1 batch + 103 pops; the empty pop never reaches a CAS
+    assert_eq!(stack.operations(), 204);
+    assert_eq!(stack.cas_failures(), 0);
+    assert_eq!(
+        stack.contention_stats(),
+        ContentionStats {
+            operations: 204,
+            cas_failures: 0
+        }
+    );
+}
+
+#[test]
+fn test_failure_ratio() {
+    let stats = ContentionStats {
+        operations: 3,
+        cas_failures: 1,
+    };
+    assert_eq!(stats.failure_ratio(), 0.25);
+    let stats = ContentionStats {
+        operations: 0,
+        cas_failures: 0,
+    };
+    assert_eq!(stats.failure_ratio(), 0.0);
+}
+
+/// One round of push/pop pairs from `threads` threads released together.
+/// Returns the stats, the values popped by workers, and the leftovers.
+fn contended_round(threads: usize, pairs: usize) -> (ContentionStats, usize, usize) {
+    let stack = Arc::new(LockFreeStack::new());
+    let barrier = Arc::new(Barrier::new(threads));
+
+    let handles: Vec<_> = (0..threads)
+        .map(|t| {
+            let stack = Arc::clone(&stack);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                let mut popped = 0;
+                for i in 0..pairs {
+                    stack.push(t * pairs + i);
+                    if stack.pop().is_some() {
+                        popped += 1;
+                    }
+                }
+                popped
+            })
+        })
+        .collect();
+
+    let popped: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    let leftover = std::iter::from_fn(|| stack.pop()).count();
+    (stack.contention_stats(), popped, leftover)
+}
+
+#[test]
+fn test_contention_recorded_without_losing_elements() {
+    // CAS races depend on scheduling (on a single core they need a
+    // preemption between load and CAS), so repeat until one shows up.
+    let (threads, pairs) = (8, 20_000);
+    let mut failures = 0;
+    for _ in 0..50 {
+        let (stats, popped, leftover) = contended_round(threads, pairs);
+        assert_eq!(popped + leftover, threads * pairs, "elements were lost");
+        assert!(stats.operations >= (threads * pairs + popped) as u64);
+        failures = stats.cas_failures;
+        if failures > 0 {
+            assert!(stats.failure_ratio() > 0.0);
+            break;
+        }
+    }
+    assert!(failures > 0, "no CAS failure observed under contention");
+}
+
+#[test]
+fn test_push_batch_preserves_lifo_order() {
+    let stack = LockFreeStack::new();
+    stack.push(0);
+    stack.push_batch(vec![1, 2, 3]);
+    stack.push(4);
+    stack.push_batch(Vec::new());
+
+    let drained: Vec<i32> = std::iter::from_fn(|| stack.pop()).collect();
+    assert_eq!(drained, vec![4, 3, 2, 1, 0]);
+    // One CAS per push and per non-empty batch, one per pop
+    assert_eq!(stack.operations(), 3 + 5);
+}
+
+#[test]
+fn test_concurrent_push_batch() {
+    let stack = Arc::new(LockFreeStack::new());
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || {
+                for b in 0..100 {
+                    let base = (t * 100 + b) * 10;
+                    stack.push_batch((base..base + 10).collect());
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // Batches are spliced whole, so each one pops as a descending run
+    let drained: Vec<usize> = std::iter::from_fn(|| stack.pop()).collect();
+    assert_eq!(drained.len(), 4000);
+    for run in drained.chunks(10) {
+        let base = run[9];
+        assert_eq!(base % 10, 0);
+        assert_eq!(run, (base..base + 10).rev().collect::<Vec<_>>());
+    }
+}
+
 // ============================================================================
 // STRESS TEST
 // ============================================================================
@@ -278,3 +411,188 @@ fn test_stress_high_contention() {
     // The number of remaining items must be less than or equal to the total number of pushes
     assert!(final_count <= num_threads * (ops_per_thread / 2));
 }
+
+// ============================================================================
+// RECLAIMED STACK TESTS
+// ============================================================================
+
+/// Counts drops so tests can spot values dropped twice or never.
+struct Tracked {
+    id: usize,
+    drops: Arc<AtomicUsize>,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_reclaimed_matches_lock_free_stack() {
+    let plain = LockFreeStack::new();
+    let reclaimed = ReclaimedStack::new();
+
+    // Pseudo-random mix of pushes and pops, including pops on empty.
+    let mut seed: u32 = 12345;
+    for i in 0..500 {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        if (seed >> 16) % 3 == 0 {
+            assert_eq!(plain.pop(), reclaimed.pop(), "pop #{}", i);
+        } else {
+            plain.push(i);
+            reclaimed.push(i);
+        }
+        if i % 50 == 0 {
+            reclaimed.collect();
+        }
+    }
+    loop {
+        let expected = plain.pop();
+        assert_eq!(expected, reclaimed.pop());
+        if expected.is_none() {
+            break;
+        }
+    }
+}
+
+#[test]
+fn test_reclaimed_is_shareable_for_send_only_values() {
+    fn assert_send_sync<S: Send + Sync>(_: &S) {}
+
+    // `Cell` is Send but not Sync; the stack only moves values, so it can
+    // still be shared between threads.
+    let stack = ReclaimedStack::new();
+    assert_send_sync(&stack);
+    thread::scope(|s| {
+        s.spawn(|| stack.push(std::cell::Cell::new(7)));
+    });
+    assert_eq!(stack.pop().map(|c| c.get()), Some(7));
+}
+
+#[test]
+fn test_reclaimed_lifo_and_empty() {
+    let stack = ReclaimedStack::new();
+    assert_eq!(stack.pop(), None::<String>);
+    for word in ["a", "b", "c"] {
+        stack.push(word.to_string());
+    }
+    assert_eq!(stack.pop().as_deref(), Some("c"));
+    assert_eq!(stack.pop().as_deref(), Some("b"));
+    assert_eq!(stack.pop().as_deref(), Some("a"));
+    assert_eq!(stack.pop(), None);
+    assert_eq!(stack.pop(), None);
+}
+
+#[test]
+fn test_pop_retires_until_collect() {
+    let stack = ReclaimedStack::new();
+    assert_eq!(stack.collect(), 0);
+    assert_eq!(stack.generation(), 0, "nothing to free is not a pass");
+
+    for i in 0..5 {
+        stack.push(i);
+    }
+    stack.pop();
+    stack.pop();
+    assert_eq!(stack.retired_len(), 2);
+
+    // Single-threaded, nothing is in flight between calls.
+    assert_eq!(stack.collect(), 2);
+    assert_eq!(stack.retired_len(), 0);
+    assert_eq!(stack.generation(), 1);
+
+    stack.pop();
+    assert_eq!(stack.retired_len(), 1);
+    assert_eq!(stack.collect(), 1);
+    assert_eq!(stack.generation(), 2);
+    assert_eq!(stack.pop(), Some(1));
+}
+
+#[test]
+fn test_reclaimed_values_dropped_exactly_once() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    {
+        let stack = ReclaimedStack::new();
+        for id in 0..10 {
+            stack.push(Tracked {
+                id,
+                drops: Arc::clone(&drops),
+            });
+        }
+        let popped: Vec<Tracked> = (0..4).filter_map(|_| stack.pop()).collect();
+        assert_eq!(
+            popped.iter().map(|t| t.id).collect::<Vec<_>>(),
+            [9, 8, 7, 6]
+        );
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+        // Freeing retired nodes must not drop the values that moved out.
+        assert_eq!(stack.collect(), 4);
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        drop(popped);
+        assert_eq!(drops.load(Ordering::SeqCst), 4);
+
+        // Leave some values live and some nodes retired for Drop to handle.
+        stack.pop();
+        stack.pop();
+        assert_eq!(stack.retired_len(), 2);
+    }
+    assert_eq!(drops.load(Ordering::SeqCst), 10);
+}
+
+#[test]
+fn test_reclaimed_concurrent_push_pop_collect() {
+    // Miri is orders of magnitude slower; keep the same shape, fewer ops.
+    let (threads, ops) = if cfg!(miri) { (3, 40) } else { (8, 5_000) };
+    let stack = Arc::new(ReclaimedStack::new());
+    let drops = Arc::new(AtomicUsize::new(0));
+    let barrier = Arc::new(Barrier::new(threads));
+
+    let handles: Vec<_> = (0..threads)
+        .map(|t| {
+            let stack = Arc::clone(&stack);
+            let drops = Arc::clone(&drops);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                let mut popped = Vec::new();
+                for i in 0..ops {
+                    stack.push(Tracked {
+                        id: t * ops + i,
+                        drops: Arc::clone(&drops),
+                    });
+                    if i % 2 == 1 {
+                        popped.extend(stack.pop().map(|v| v.id));
+                        popped.extend(stack.pop().map(|v| v.id));
+                    }
+                    if i % 16 == 0 {
+                        stack.collect();
+                    }
+                }
+                popped
+            })
+        })
+        .collect();
+
+    let mut seen = vec![false; threads * ops];
+    for handle in handles {
+        for id in handle.join().unwrap() {
+            assert!(!seen[id], "value {} popped twice", id);
+            seen[id] = true;
+        }
+    }
+    while let Some(value) = stack.pop() {
+        assert!(!seen[value.id], "value {} popped twice", value.id);
+        seen[value.id] = true;
+    }
+    assert!(seen.iter().all(|&s| s), "every pushed value comes back out");
+    assert_eq!(drops.load(Ordering::SeqCst), threads * ops);
+
+    // All threads have joined, so the stack is quiescent: one pass drains
+    // everything that is still retired.
+    let pending = stack.retired_len();
+    assert_eq!(stack.collect(), pending);
+    assert_eq!(stack.retired_len(), 0);
+    assert_eq!(stack.collect(), 0);
+}
@@ -24,6 +24,11 @@
 //! -   Add more stack manipulation instructions like `Pop`, `Dup`, `Swap`, `Over`.
 //! -   Add control flow instructions like `Jmp` (unconditional jump) and
 //!     `JmpIf` (conditional jump).
+//! -   Add functions: `Call`/`Ret`, then call frames with locals
+//!     (`CallArgs`, `LoadLocal`, `StoreLocal`, `RetVal`) so recursive
+//!     functions don't fight over the operand stack.
+//! -   Add a string constant pool: `PushConst` pushes an index, `PrintStr`
+//!     prints the string it names, and `Concat` builds new strings at runtime.
 //!
 //! ## Running Your Code
 //!
@@ -54,8 +59,25 @@ pub enum Instruction {
     Dup,
     Swap,
     Over,
+    Eq,
+    Gt,
+    Lt,
+    Ne,
+    Ge,
+    Le,
     Jmp(usize),
     JmpIf(usize),
+    JmpIfNot(usize),
+    Call(usize),
+    Ret,
+    CallArgs(usize, usize),
+    LoadLocal(usize),
+    StoreLocal(usize),
+    RetVal,
+    Print,
+    PushConst(usize),
+    Concat,
+    PrintStr,
 }
 
 // TODO: Define VmError enum
@@ -68,7 +90,28 @@ pub enum Instruction {
 pub enum VmError {
     StackUnderflow,
     DivisionByZero,
+    Overflow,
     InvalidInstructionPointer,
+    CallStackUnderflow,
+    CallStackOverflow,
+    InvalidLocal(usize),
+    InvalidConstant(usize),
+    Compile(CompileError),
+}
+
+pub type VmResult<T> = Result<T, VmError>;
+
+pub const MAX_CALL_DEPTH: usize = 1024;
+pub const MAX_LOCALS: usize = 256;
+
+// TODO (stretch): one call frame per active call.
+// Keep the return address, the frame's locals (arguments first), and the
+// operand stack height at call time so `RetVal` can clean up after the callee.
+#[derive(Debug, Default)]
+struct Frame {
+    _return_addr: usize,
+    _locals: Vec<i32>,
+    _stack_base: usize,
 }
 
 // TODO: Define the VM struct
@@ -79,6 +122,10 @@ pub struct VM {
     _program: Vec<Instruction>,
     _stack: Vec<i32>,
     _ip: usize,
+    _output: Vec<i32>,
+    _constants: Vec<String>,
+    _string_output: Vec<String>,
+    _frames: Vec<Frame>,
 }
 
 
@@ -93,6 +140,32 @@ impl VM {
         todo!("Initialize the VM");
     }
 
+    /// Creates a new VM whose constant pool starts out as `constants`.
+    pub fn with_constants(program: Vec<Instruction>, constants: Vec<String>) -> Self {
+        // TODO (stretch): like `new`, but keep the constant pool too.
+        let _ = (program, constants);
+        todo!("Initialize the VM with a constant pool");
+    }
+
+    /// Values popped by `Print`, in order.
+    pub fn output(&self) -> &[i32] {
+        todo!("Return printed values");
+    }
+
+    /// Strings printed by `PrintStr`, in order.
+    pub fn string_output(&self) -> &[String] {
+        todo!("Return printed strings");
+    }
+
+    /// The string at `index` in the constant pool, if any.
+    pub fn constant(&self, index: usize) -> Option<&str> {
+        // TODO (stretch): `Concat` appends to the pool, so this includes
+        // strings built at runtime. Bad indices are `VmError::InvalidConstant`
+        // inside `run`.
+        let _ = index;
+        todo!("Look up a constant");
+    }
+
     /// Runs the VM until it halts or an error occurs.
     ///
     /// Returns the last value on the stack if successful, or an error.
@@ -112,7 +185,8 @@ impl VM {
         //      - Pop the required number of operands from the stack.
         //      - Check for stack underflow! If `pop()` returns `None`,
         //        return `Err(VmError::StackUnderflow)`.
-        //      - Perform the operation.
+        //      - Perform the operation with `checked_*`, returning
+        //        `Err(VmError::Overflow)` if the result doesn't fit.
         //      - Check for division by zero!
         //      - Push the result back onto the stack.
         //
@@ -125,6 +199,30 @@ impl VM {
 }
 
 
+// TODO (stretch): compile lab 36's expression AST to bytecode.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompileError {
+    NonIntegerLiteral(f64),
+    UnsupportedVariable(String),
+}
+
+/// Compiles `expr` to a program ending in `Print`, `Halt`.
+pub fn compile(
+    expr: &interpreter::solution::parser::Expr,
+) -> Result<Vec<Instruction>, CompileError> {
+    // TODO: Walk the tree in post-order: emit the left operand, then the
+    // right operand, then the operator. Literals must be whole numbers that
+    // fit in an i32. Unary minus can be emitted as `0 - x`.
+    let _ = expr;
+    todo!("Compile the AST");
+}
+
+/// Compiles and runs `expr`, returning the printed value.
+pub fn run_program(expr: &interpreter::solution::parser::Expr) -> VmResult<i32> {
+    let _ = expr;
+    todo!("Compile, run, and return the output");
+}
+
 // Re-export the solution module so people can compare
 #[doc(hidden)]
 pub mod solution;
@@ -4,7 +4,9 @@
 //! a few sample programs.
 //! Run with: cargo run -p basic-vm
 
-use basic_vm::solution::{Instruction, VM, VmError};
+use basic_vm::solution::{compile, run_program, Instruction, VM, VmError};
+use interpreter::solution::lexer::tokenize;
+use interpreter::solution::parser::parse;
 
 fn main() {
     println!("=== Basic Stack-Based Virtual Machine Demo ===\n");
@@ -96,6 +98,73 @@ fn main() {
     println!("   Program: Push(10), Add");
     run_and_print(&program4);
 
+    // ============================================================================
+    // DEMO 5: Compiling an Interpreter Expression
+    // ============================================================================
+    println!("5. Program 5: compile \"(2 + 3) * -4\" from the interpreter lab");
+    println!("   -------------------------------------------------------");
+    let expr = tokenize("(2 + 3) * -4")
+        .ok()
+        .and_then(|tokens| parse(tokens).ok())
+        .expect("valid expression");
+    match compile(&expr) {
+        Ok(program) => println!("   Bytecode: {:?}", program),
+        Err(e) => println!("   Compile error: {}", e),
+    }
+    println!("   run_program -> {:?}\n", run_program(&expr));
+
+    // ============================================================================
+    // DEMO 6: Recursive Function with Call Frames: fib(10)
+    // ============================================================================
+    println!("6. Program 6: recursive fib(10) using CallArgs and locals");
+    println!("   -----------------------------------------------------");
+    let program6 = vec![
+        Instruction::Push(10),
+        Instruction::CallArgs(3, 1), // fib(10)
+        Instruction::Halt,
+        // fib(n): local 0 is n
+        Instruction::LoadLocal(0), // 3
+        Instruction::Push(2),
+        Instruction::Lt,
+        Instruction::JmpIfNot(9),
+        Instruction::LoadLocal(0), // n < 2: return n
+        Instruction::RetVal,
+        Instruction::LoadLocal(0), // 9: fib(n - 1) + fib(n - 2)
+        Instruction::Push(1),
+        Instruction::Sub,
+        Instruction::CallArgs(3, 1),
+        Instruction::LoadLocal(0),
+        Instruction::Push(2),
+        Instruction::Sub,
+        Instruction::CallArgs(3, 1),
+        Instruction::Add,
+        Instruction::RetVal,
+    ];
+    run_and_print(&program6);
+
+    // ============================================================================
+    // DEMO 7: Strings from a Constant Pool
+    // ============================================================================
+    println!("7. Program 7: hello world with PushConst, Concat, and PrintStr");
+    println!("   ----------------------------------------------------------");
+    let program7 = vec![
+        Instruction::PushConst(0),
+        Instruction::PushConst(1),
+        Instruction::Concat, // "Hello, " + "world!" becomes constant 2
+        Instruction::PrintStr,
+        Instruction::Push(42),
+        Instruction::Print,
+        Instruction::Halt,
+    ];
+    let mut vm = VM::with_constants(program7, vec!["Hello, ".into(), "world!".into()]);
+    match vm.run() {
+        Ok(_) => {
+            println!("   strings: {:?}", vm.string_output());
+            println!("   numbers: {:?}\n", vm.output());
+        }
+        Err(e) => println!("   ❌ Error: {:?}\n", e),
+    }
+
     println!("=== Demo Complete! ===");
 }
 
@@ -113,7 +182,13 @@ fn run_and_print(program: &[Instruction]) {
             let error_msg = match e {
                 VmError::StackUnderflow => "Stack Underflow",
                 VmError::DivisionByZero => "Division by Zero",
+                VmError::Overflow => "Arithmetic Overflow",
                 VmError::InvalidInstructionPointer => "Invalid Instruction Pointer",
+                VmError::CallStackUnderflow => "Call Stack Underflow",
+                VmError::CallStackOverflow => "Call Stack Overflow",
+                VmError::InvalidLocal(_) => "Invalid Local",
+                VmError::InvalidConstant(_) => "Invalid Constant",
+                VmError::Compile(_) => "Compile Error",
             };
             println!("   ❌ Error: {}", error_msg);
         }
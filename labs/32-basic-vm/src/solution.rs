@@ -23,11 +23,18 @@
 //! - **Pattern Matching**: The core of the execution loop.
 //! - **State Management**: Managing the VM's internal state (`stack`, `ip`).
 //! - **Custom Error Types**: Creating a dedicated error enum for VM-specific issues.
+//! - **Call Frames**: Giving each function call its own locals and return address.
+//! - **Compiling to Bytecode**: Lowering lab 36's expression AST to instructions.
+//! - **Constant Pools**: Keeping strings out of the `i32` stack by pushing
+//!   their index instead.
+
+use interpreter::solution::parser::{BinaryOp, Expr};
+use std::fmt;
 
 /// The instruction set for our Virtual Machine.
 ///
 /// Each variant represents a unique operation (opcode).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Instruction {
     // --- Basic Arithmetic ---
     /// Push a constant value onto the stack.
@@ -58,12 +65,51 @@ pub enum Instruction {
     Gt,
     /// Pop two values, push 1 if the second-to-top is less than the top, else 0.
     Lt,
+    /// Pop two values, push 1 if they are not equal, else 0.
+    Ne,
+    /// Pop two values, push 1 if the second-to-top is greater than or equal to the top, else 0.
+    Ge,
+    /// Pop two values, push 1 if the second-to-top is less than or equal to the top, else 0.
+    Le,
 
     // --- Control Flow ---
     /// Unconditionally jump to the given address (instruction index).
     Jmp(usize),
     /// Pop a value; if it is non-zero, jump to the given address.
     JmpIf(usize),
+    /// Pop a value; if it is zero, jump to the given address.
+    JmpIfNot(usize),
+
+    // --- Functions ---
+    /// Push a frame holding only the return address and jump to the given
+    /// address. Caller and callee share the operand stack.
+    Call(usize),
+    /// Pop the current frame and jump back to its return address.
+    Ret,
+    /// `CallArgs(target, argc)`: pop `argc` values into the new frame's
+    /// locals (the first argument pushed becomes local 0) and jump.
+    CallArgs(usize, usize),
+    /// Push local `n` of the current frame.
+    LoadLocal(usize),
+    /// Pop a value into local `n` of the current frame.
+    StoreLocal(usize),
+    /// Pop the return value, discard the frame and anything the callee left
+    /// on the stack, push the return value, and jump back.
+    RetVal,
+
+    // --- Strings ---
+    /// Push the index of a string in the constant pool. The string itself
+    /// stays in the pool; the stack only ever holds `i32`s.
+    PushConst(usize),
+    /// Pop two constant indices, append the second-to-top's string followed
+    /// by the top's to the pool, and push the new string's index.
+    Concat,
+
+    // --- Output ---
+    /// Pop a value and append it to the VM's output.
+    Print,
+    /// Pop a constant index and append that string to the string output.
+    PrintStr,
 
     // --- Halting ---
     /// Stop program execution.
@@ -77,8 +123,46 @@ pub enum VmError {
     StackUnderflow,
     /// Attempted to divide by zero.
     DivisionByZero,
+    /// An arithmetic result didn't fit in an `i32`. Plain operators would
+    /// panic in debug builds (and `i32::MIN / -1` in every build).
+    Overflow,
     /// The instruction pointer went out of the program's bounds.
     InvalidInstructionPointer,
+    /// `Ret`/`RetVal` with no call to return from.
+    CallStackUnderflow,
+    /// More than `MAX_CALL_DEPTH` nested calls (usually runaway recursion).
+    CallStackOverflow,
+    /// Loaded a local that was never set, or stored past `MAX_LOCALS`.
+    InvalidLocal(usize),
+    /// A constant index that isn't in the pool.
+    InvalidConstant(usize),
+    /// `run_program` couldn't compile the expression.
+    Compile(CompileError),
+}
+
+/// Shorthand for results from the VM and its helpers.
+pub type VmResult<T> = Result<T, VmError>;
+
+/// How many calls may be active at once, not counting the top level.
+pub const MAX_CALL_DEPTH: usize = 1024;
+
+/// How many local slots a single frame may use.
+pub const MAX_LOCALS: usize = 256;
+
+/// One activation record on the call stack.
+///
+/// The operand stack is shared by everyone, but each call gets its own
+/// locals, so a recursive call can't overwrite its caller's variables.
+#[derive(Debug, Default)]
+struct Frame {
+    /// Where `Ret`/`RetVal` resumes in the caller.
+    return_addr: usize,
+    /// Arguments first, then whatever `StoreLocal` adds. Storing past the
+    /// end grows the vector, filling any gap with zeros.
+    locals: Vec<i32>,
+    /// Operand stack height when the call started (after the arguments
+    /// were popped). `RetVal` truncates back to it.
+    stack_base: usize,
 }
 
 /// A simple stack-based Virtual Machine.
@@ -89,18 +173,53 @@ pub struct VM {
     stack: Vec<i32>,
     /// The instruction pointer, indicating the index of the next instruction.
     ip: usize,
+    /// Values popped by `Print`, in order.
+    output: Vec<i32>,
+    /// Strings referenced by `PushConst`. `Concat` appends to it at runtime.
+    constants: Vec<String>,
+    /// Strings printed by `PrintStr`, in order.
+    string_output: Vec<String>,
+    /// The call stack. The bottom frame belongs to the top-level code and
+    /// is never popped.
+    frames: Vec<Frame>,
 }
 
 impl VM {
     /// Creates a new VM with a given program.
     pub fn new(program: Vec<Instruction>) -> Self {
+        VM::with_constants(program, Vec::new())
+    }
+
+    /// Creates a new VM whose constant pool starts out as `constants`, so
+    /// `PushConst(0)` refers to `constants[0]`.
+    pub fn with_constants(program: Vec<Instruction>, constants: Vec<String>) -> Self {
         VM {
             program,
             stack: Vec::new(),
             ip: 0,
+            output: Vec::new(),
+            constants,
+            string_output: Vec::new(),
+            frames: vec![Frame::default()],
         }
     }
 
+    /// Everything printed so far.
+    pub fn output(&self) -> &[i32] {
+        &self.output
+    }
+
+    /// Every string printed by `PrintStr` so far.
+    pub fn string_output(&self) -> &[String] {
+        &self.string_output
+    }
+
+    /// The string at `index` in the constant pool, including any built by
+    /// `Concat`.
+    pub fn constant(&self, index: usize) -> Option<&str> {
+        self.constants.get(index).map(String::as_str)
+    }
+
     /// Runs the VM until it halts or an error occurs.
     ///
     /// The main "fetch-decode-execute" loop happens here.
@@ -119,17 +238,17 @@ impl VM {
                 Instruction::Add => {
                     let b = self.stack.pop().ok_or(VmError::StackUnderflow)?;
                     let a = self.stack.pop().ok_or(VmError::StackUnderflow)?;
-                    self.stack.push(a + b);
+                    self.stack.push(a.checked_add(b).ok_or(VmError::Overflow)?);
                 }
                 Instruction::Sub => {
                     let b = self.stack.pop().ok_or(VmError::StackUnderflow)?;
                     let a = self.stack.pop().ok_or(VmError::StackUnderflow)?;
-                    self.stack.push(a - b);
+                    self.stack.push(a.checked_sub(b).ok_or(VmError::Overflow)?);
                 }
                 Instruction::Mul => {
                     let b = self.stack.pop().ok_or(VmError::StackUnderflow)?;
                     let a = self.stack.pop().ok_or(VmError::StackUnderflow)?;
-                    self.stack.push(a * b);
+                    self.stack.push(a.checked_mul(b).ok_or(VmError::Overflow)?);
                 }
                 Instruction::Div => {
                     let b = self.stack.pop().ok_or(VmError::StackUnderflow)?;
@@ -137,7 +256,8 @@ impl VM {
                         return Err(VmError::DivisionByZero);
                     }
                     let a = self.stack.pop().ok_or(VmError::StackUnderflow)?;
-                    self.stack.push(a / b);
+                    // b != 0 here, so the only failure is i32::MIN / -1.
+                    self.stack.push(a.checked_div(b).ok_or(VmError::Overflow)?);
                 }
                 Instruction::Pop => {
                     self.stack.pop().ok_or(VmError::StackUnderflow)?;
@@ -153,8 +273,13 @@ impl VM {
                     self.stack.push(a);
                 }
                 Instruction::Over => {
-                    let b = self.stack.get(self.stack.len() - 2).ok_or(VmError::StackUnderflow)?;
-                    self.stack.push(*b);
+                    // checked_sub: with fewer than two values, `len() - 2` would underflow.
+                    let index = self
+                        .stack
+                        .len()
+                        .checked_sub(2)
+                        .ok_or(VmError::StackUnderflow)?;
+                    self.stack.push(self.stack[index]);
                 }
                 Instruction::Eq => {
                     let b = self.stack.pop().ok_or(VmError::StackUnderflow)?;
@@ -171,6 +296,21 @@ impl VM {
                     let a = self.stack.pop().ok_or(VmError::StackUnderflow)?;
                     self.stack.push(if a < b { 1 } else { 0 });
                 }
+                Instruction::Ne => {
+                    let b = self.stack.pop().ok_or(VmError::StackUnderflow)?;
+                    let a = self.stack.pop().ok_or(VmError::StackUnderflow)?;
+                    self.stack.push(if a != b { 1 } else { 0 });
+                }
+                Instruction::Ge => {
+                    let b = self.stack.pop().ok_or(VmError::StackUnderflow)?;
+                    let a = self.stack.pop().ok_or(VmError::StackUnderflow)?;
+                    self.stack.push(if a >= b { 1 } else { 0 });
+                }
+                Instruction::Le => {
+                    let b = self.stack.pop().ok_or(VmError::StackUnderflow)?;
+                    let a = self.stack.pop().ok_or(VmError::StackUnderflow)?;
+                    self.stack.push(if a <= b { 1 } else { 0 });
+                }
                 Instruction::Jmp(addr) => {
                     if addr >= self.program.len() {
                         return Err(VmError::InvalidInstructionPointer);
@@ -186,6 +326,79 @@ impl VM {
                         self.ip = addr;
                     }
                 }
+                Instruction::JmpIfNot(addr) => {
+                    if addr >= self.program.len() {
+                        return Err(VmError::InvalidInstructionPointer);
+                    }
+                    let cond = self.stack.pop().ok_or(VmError::StackUnderflow)?;
+                    if cond == 0 {
+                        self.ip = addr;
+                    }
+                }
+                Instruction::Call(addr) => {
+                    self.call(addr, Vec::new())?;
+                }
+                Instruction::Ret => {
+                    let frame = self.pop_frame()?;
+                    self.ip = frame.return_addr;
+                }
+                Instruction::CallArgs(addr, argc) => {
+                    let split = self
+                        .stack
+                        .len()
+                        .checked_sub(argc)
+                        .ok_or(VmError::StackUnderflow)?;
+                    let args = self.stack.split_off(split);
+                    self.call(addr, args)?;
+                }
+                Instruction::LoadLocal(n) => {
+                    let value = self
+                        .current_frame()
+                        .locals
+                        .get(n)
+                        .copied()
+                        .ok_or(VmError::InvalidLocal(n))?;
+                    self.stack.push(value);
+                }
+                Instruction::StoreLocal(n) => {
+                    if n >= MAX_LOCALS {
+                        return Err(VmError::InvalidLocal(n));
+                    }
+                    let value = self.stack.pop().ok_or(VmError::StackUnderflow)?;
+                    let locals = &mut self.current_frame().locals;
+                    if locals.len() <= n {
+                        locals.resize(n + 1, 0);
+                    }
+                    locals[n] = value;
+                }
+                Instruction::RetVal => {
+                    let frame = self.pop_frame()?;
+                    let value = self.stack.pop().ok_or(VmError::StackUnderflow)?;
+                    self.stack.truncate(frame.stack_base);
+                    self.stack.push(value);
+                    self.ip = frame.return_addr;
+                }
+                Instruction::PushConst(index) => {
+                    // Only the index goes on the stack; PrintStr/Concat check it
+                    let value =
+                        i32::try_from(index).map_err(|_| VmError::InvalidConstant(index))?;
+                    self.stack.push(value);
+                }
+                Instruction::Concat => {
+                    let b = self.pop_constant()?;
+                    let a = self.pop_constant()?;
+                    let joined = format!("{}{}", self.constants[a], self.constants[b]);
+                    self.constants.push(joined);
+                    self.stack.push(self.constants.len() as i32 - 1);
+                }
+                Instruction::Print => {
+                    let value = self.stack.pop().ok_or(VmError::StackUnderflow)?;
+                    self.output.push(value);
+                }
+                Instruction::PrintStr => {
+                    let index = self.pop_constant()?;
+                    self.string_output.push(self.constants[index].clone());
+                }
                 Instruction::Halt => {
                     // Break the loop to stop execution.
                     break;
@@ -196,4 +409,139 @@ impl VM {
         // After the loop (due to Halt or end of program), return the top of the stack.
         Ok(self.stack.pop())
     }
+
+    /// Push a frame whose locals start as `args` and jump to `addr`.
+    fn call(&mut self, addr: usize, args: Vec<i32>) -> VmResult<()> {
+        if addr >= self.program.len() {
+            return Err(VmError::InvalidInstructionPointer);
+        }
+        // The bottom frame is the top level, not a call
+        if self.frames.len() > MAX_CALL_DEPTH {
+            return Err(VmError::CallStackOverflow);
+        }
+        self.frames.push(Frame {
+            return_addr: self.ip,
+            locals: args,
+            stack_base: self.stack.len(),
+        });
+        self.ip = addr;
+        Ok(())
+    }
+
+    /// Pop the innermost frame; the top-level frame can't be popped.
+    fn pop_frame(&mut self) -> VmResult<Frame> {
+        if self.frames.len() <= 1 {
+            return Err(VmError::CallStackUnderflow);
+        }
+        self.frames.pop().ok_or(VmError::CallStackUnderflow)
+    }
+
+    /// Pop a value and check that it names a string in the pool.
+    fn pop_constant(&mut self) -> VmResult<usize> {
+        let value = self.stack.pop().ok_or(VmError::StackUnderflow)?;
+        // A negative value wraps to a huge index, which is just as invalid
+        let index = value as usize;
+        if index >= self.constants.len() {
+            return Err(VmError::InvalidConstant(index));
+        }
+        Ok(index)
+    }
+
+    fn current_frame(&mut self) -> &mut Frame {
+        // `frames` always holds at least the top-level frame
+        self.frames
+            .last_mut()
+            .expect("top-level frame is never popped")
+    }
+}
+
+// ============================================================================
+// COMPILER: INTERPRETER AST -> BYTECODE
+// ============================================================================
+// Lab 36 evaluates an `Expr` tree by walking it. The same tree can instead be
+// *compiled*: a post-order walk (left, right, then the operator) emits exactly
+// the push/pop sequence a stack machine needs, because each subtree leaves
+// one value on the stack. Precedence and parentheses are already encoded in
+// the tree's shape, so the compiler never has to think about them.
+//
+// The VM works on `i32`, the interpreter on `f64`. Rather than silently
+// truncate, the compiler rejects literals that aren't whole numbers or don't
+// fit in an `i32`. Division differs too: the VM's `Div` truncates toward
+// zero (`7 / 2` is 3, the interpreter says 3.5), so results only agree when
+// every division is exact.
+
+/// Why an expression couldn't be compiled to bytecode.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompileError {
+    /// The literal has a fractional part or is out of `i32` range.
+    NonIntegerLiteral(f64),
+    /// The VM has no variables.
+    UnsupportedVariable(String),
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::NonIntegerLiteral(n) => {
+                write!(f, "literal {} is not an i32 integer", n)
+            }
+            CompileError::UnsupportedVariable(name) => {
+                write!(f, "variables are not supported: {}", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+/// Compile `expr` into a program that prints its value and halts.
+pub fn compile(expr: &Expr) -> Result<Vec<Instruction>, CompileError> {
+    let mut program = Vec::new();
+    emit(expr, &mut program)?;
+    program.push(Instruction::Print);
+    program.push(Instruction::Halt);
+    Ok(program)
+}
+
+fn emit(expr: &Expr, program: &mut Vec<Instruction>) -> Result<(), CompileError> {
+    match expr {
+        Expr::Literal(n) => {
+            let fits = n.fract() == 0.0 && *n >= i32::MIN as f64 && *n <= i32::MAX as f64;
+            if !fits {
+                return Err(CompileError::NonIntegerLiteral(*n));
+            }
+            program.push(Instruction::Push(*n as i32));
+        }
+        Expr::Variable(name) => return Err(CompileError::UnsupportedVariable(name.clone())),
+        Expr::Grouping(inner) => emit(inner, program)?,
+        // No Neg instruction: -x is 0 - x.
+        Expr::UnaryMinus(inner) => {
+            program.push(Instruction::Push(0));
+            emit(inner, program)?;
+            program.push(Instruction::Sub);
+        }
+        Expr::Binary { op, left, right } => {
+            emit(left, program)?;
+            emit(right, program)?;
+            program.push(match op {
+                BinaryOp::Add => Instruction::Add,
+                BinaryOp::Subtract => Instruction::Sub,
+                BinaryOp::Multiply => Instruction::Mul,
+                BinaryOp::Divide => Instruction::Div,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Compile `expr`, run it, and return the value it printed.
+///
+/// Runtime errors are the VM's own, so `1 / 0` fails with
+/// `VmError::DivisionByZero`.
+pub fn run_program(expr: &Expr) -> VmResult<i32> {
+    let program = compile(expr).map_err(VmError::Compile)?;
+    let mut vm = VM::new(program);
+    vm.run()?;
+    // `compile` always ends in exactly one Print.
+    vm.output().first().copied().ok_or(VmError::StackUnderflow)
 }
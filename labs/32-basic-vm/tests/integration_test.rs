@@ -4,7 +4,11 @@
 //! checking for correctness of arithmetic, stack manipulation, control flow,
 //! and error handling.
 
-use basic_vm::solution::{Instruction, VM, VmError};
+use basic_vm::solution::{compile, run_program, CompileError, Instruction, VM, VmError};
+use basic_vm::solution::{MAX_CALL_DEPTH, MAX_LOCALS};
+use interpreter::solution::evaluator::evaluate;
+use interpreter::solution::lexer::tokenize;
+use interpreter::solution::parser::{parse, Expr};
 
 /// Helper to run a program and assert that it returns a specific value.
 fn assert_program_result(program: Vec<Instruction>, expected: i32) {
@@ -200,6 +204,24 @@ fn test_division_by_zero() {
     assert_program_error(program, VmError::DivisionByZero);
 }
 
+#[test]
+fn test_arithmetic_overflow_is_error() {
+    for (a, b, op) in [
+        (i32::MAX, 1, Instruction::Add),
+        (i32::MIN, 1, Instruction::Sub),
+        (i32::MAX, 2, Instruction::Mul),
+        (i32::MIN, -1, Instruction::Div),
+    ] {
+        let program = vec![
+            Instruction::Push(a),
+            Instruction::Push(b),
+            op,
+            Instruction::Halt,
+        ];
+        assert_program_error(program, VmError::Overflow);
+    }
+}
+
 #[test]
 fn test_invalid_jump_pointer() {
     let program = vec![Instruction::Jmp(100)];
@@ -220,3 +242,387 @@ fn test_empty_program() {
     let mut vm = VM::new(program);
     assert_eq!(vm.run().unwrap(), None);
 }
+
+// ============================================================================
+// EXTRA COMPARISONS AND JUMPS
+// ============================================================================
+
+#[test]
+fn test_ne_ge_le() {
+    use Instruction::*;
+    assert_program_result(vec![Push(3), Push(4), Ne, Halt], 1);
+    assert_program_result(vec![Push(4), Push(4), Ne, Halt], 0);
+    assert_program_result(vec![Push(4), Push(4), Ge, Halt], 1);
+    assert_program_result(vec![Push(3), Push(4), Ge, Halt], 0);
+    assert_program_result(vec![Push(4), Push(4), Le, Halt], 1);
+    assert_program_result(vec![Push(5), Push(4), Le, Halt], 0);
+}
+
+#[test]
+fn test_jmp_if_not() {
+    use Instruction::*;
+    // if (2 >= 3) { 1 } else { 2 }
+    let program = vec![
+        Push(2),
+        Push(3),
+        Ge,
+        JmpIfNot(6),
+        Push(1),
+        Halt,
+        Push(2),
+        Halt,
+    ];
+    assert_program_result(program, 2);
+    assert_program_error(
+        vec![Push(0), JmpIfNot(9)],
+        VmError::InvalidInstructionPointer,
+    );
+}
+
+#[test]
+fn test_print_collects_output() {
+    use Instruction::*;
+    let mut vm = VM::new(vec![Push(1), Print, Push(2), Print, Push(3), Halt]);
+    assert_eq!(vm.run().unwrap(), Some(3));
+    assert_eq!(vm.output(), &[1, 2]);
+    assert_program_error(vec![Print], VmError::StackUnderflow);
+}
+
+// ============================================================================
+// COMPILER TESTS (INTERPRETER AST -> BYTECODE)
+// ============================================================================
+
+fn parse_expr(source: &str) -> Expr {
+    parse(tokenize(source).unwrap()).unwrap()
+}
+
+#[test]
+fn test_compile_emits_postfix_ending_in_print_halt() {
+    use Instruction::*;
+    assert_eq!(
+        compile(&parse_expr("1 + 2 * 3")).unwrap(),
+        vec![Push(1), Push(2), Push(3), Mul, Add, Print, Halt]
+    );
+    assert_eq!(
+        compile(&parse_expr("-5")).unwrap(),
+        vec![Push(0), Push(5), Sub, Print, Halt]
+    );
+}
+
+#[test]
+fn test_compiled_programs_agree_with_interpreter() {
+    let cases = [
+        "42",
+        "2 + 3",
+        "10 - 4 - 3",
+        "6 * 7",
+        "20 / 5",
+        "100 / 10 / 2",
+        "-3 + 10",
+        "-(4 - 9)",
+        "2 * 3 + 4 * 5",
+        "(8 - 2) * (3 + 1) / 4",
+    ];
+    for source in cases {
+        let expr = parse_expr(source);
+        let expected = evaluate(&expr).unwrap();
+        assert_eq!(run_program(&expr).unwrap() as f64, expected, "{}", source);
+    }
+}
+
+#[test]
+fn test_compiled_precedence() {
+    assert_eq!(run_program(&parse_expr("2 + 3 * 4")).unwrap(), 14);
+    assert_eq!(run_program(&parse_expr("(2 + 3) * 4")).unwrap(), 20);
+    assert_eq!(run_program(&parse_expr("10 - 6 / 2")).unwrap(), 7);
+}
+
+#[test]
+fn test_compiled_nested_parentheses() {
+    let expr = parse_expr("((15 / (7 - (1 + 1))) * 3) - (2 + (1 + 1))");
+    assert_eq!(evaluate(&expr).unwrap(), 5.0);
+    assert_eq!(run_program(&expr).unwrap(), 5);
+}
+
+#[test]
+fn test_compiled_division_by_zero_is_vm_error() {
+    assert_eq!(
+        run_program(&parse_expr("1 / (2 - 2)")),
+        Err(VmError::DivisionByZero)
+    );
+}
+
+#[test]
+fn test_compiled_overflow_is_vm_error() {
+    assert_eq!(
+        run_program(&parse_expr("2147483647 + 1")),
+        Err(VmError::Overflow)
+    );
+    assert_eq!(
+        run_program(&parse_expr("(0 - 2147483647 - 1) / (0 - 1)")),
+        Err(VmError::Overflow)
+    );
+    assert_eq!(run_program(&parse_expr("2147483646 + 1")), Ok(i32::MAX));
+}
+
+#[test]
+fn test_compile_rejects_non_integers_and_variables() {
+    assert_eq!(
+        compile(&parse_expr("1.5 + 1")),
+        Err(CompileError::NonIntegerLiteral(1.5))
+    );
+    assert_eq!(
+        compile(&parse_expr("3000000000")),
+        Err(CompileError::NonIntegerLiteral(3_000_000_000.0))
+    );
+    assert_eq!(
+        run_program(&parse_expr("x * 2")),
+        Err(VmError::Compile(CompileError::UnsupportedVariable(
+            "x".to_string()
+        )))
+    );
+    // Whole numbers written with a decimal point are fine.
+    assert_eq!(run_program(&parse_expr("2.0 * 4")).unwrap(), 8);
+}
+
+// ============================================================================
+// CALL FRAME TESTS
+// ============================================================================
+
+/// `fib(n)` as a recursive function; the function body starts at address 3.
+fn fib_program(n: i32) -> Vec<Instruction> {
+    use Instruction::*;
+    vec![
+        Push(n),
+        CallArgs(3, 1),
+        Halt,
+        // fib: if n < 2 return n
+        LoadLocal(0),
+        Push(2),
+        Lt,
+        JmpIfNot(9),
+        LoadLocal(0),
+        RetVal,
+        // 9: return fib(n - 1) + fib(n - 2)
+        LoadLocal(0),
+        Push(1),
+        Sub,
+        CallArgs(3, 1),
+        LoadLocal(0),
+        Push(2),
+        Sub,
+        CallArgs(3, 1),
+        Add,
+        RetVal,
+    ]
+}
+
+#[test]
+fn test_iterative_factorial_with_locals() {
+    use Instruction::*;
+    let factorial = |n: i32| {
+        vec![
+            Push(n),
+            StoreLocal(0), // n
+            Push(1),
+            StoreLocal(1), // acc
+            // 4: while n > 1
+            LoadLocal(0),
+            Push(1),
+            Gt,
+            JmpIfNot(17),
+            LoadLocal(1),
+            LoadLocal(0),
+            Mul,
+            StoreLocal(1), // acc = acc * n
+            LoadLocal(0),
+            Push(1),
+            Sub,
+            StoreLocal(0), // n = n - 1
+            Jmp(4),
+            // 17:
+            LoadLocal(1),
+            Halt,
+        ]
+    };
+    assert_program_result(factorial(0), 1);
+    assert_program_result(factorial(1), 1);
+    assert_program_result(factorial(5), 120);
+    assert_program_result(factorial(10), 3_628_800);
+}
+
+#[test]
+fn test_recursive_fibonacci_with_call_args() {
+    let known = [0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89, 144, 233, 377, 610];
+    for (n, &expected) in known.iter().enumerate() {
+        let mut vm = VM::new(fib_program(n as i32));
+        assert_eq!(vm.run().unwrap(), Some(expected), "fib({})", n);
+    }
+}
+
+#[test]
+fn test_call_args_order_and_return_value() {
+    use Instruction::*;
+    // sub(a, b) = a - b; the first argument pushed is local 0
+    let program = vec![
+        Push(10),
+        Push(3),
+        CallArgs(4, 2),
+        Halt,
+        LoadLocal(0),
+        LoadLocal(1),
+        Sub,
+        RetVal,
+    ];
+    assert_program_result(program, 7);
+}
+
+#[test]
+fn test_callee_cannot_clobber_caller_locals() {
+    use Instruction::*;
+    let program = vec![
+        Push(42),
+        StoreLocal(0),
+        Push(7),
+        Push(1),
+        CallArgs(9, 1), // leaves 7 below the return value
+        Add,            // 7 + 5
+        LoadLocal(0),
+        Add, // + 42, still the caller's local
+        Halt,
+        // 9: overwrite local 0, leave junk on the stack, return 5
+        Push(99),
+        StoreLocal(0),
+        Push(-1),
+        Push(-2),
+        Push(5),
+        RetVal,
+    ];
+    assert_program_result(program, 54);
+}
+
+#[test]
+fn test_plain_call_and_ret_share_the_stack() {
+    use Instruction::*;
+    // double(): replaces the top of the shared stack with twice its value
+    let program = vec![Push(21), Call(3), Halt, Dup, Add, Ret];
+    assert_program_result(program, 42);
+}
+
+#[test]
+fn test_call_frame_errors() {
+    use Instruction::*;
+    assert_program_error(vec![Ret], VmError::CallStackUnderflow);
+    assert_program_error(vec![Push(1), RetVal], VmError::CallStackUnderflow);
+    assert_program_error(vec![LoadLocal(0)], VmError::InvalidLocal(0));
+    assert_program_error(
+        vec![Push(1), StoreLocal(MAX_LOCALS)],
+        VmError::InvalidLocal(MAX_LOCALS),
+    );
+    // Locals are per frame: the callee can't see the caller's local 1
+    assert_program_error(
+        vec![
+            Push(5),
+            StoreLocal(1),
+            Push(0),
+            CallArgs(5, 1),
+            Halt,
+            LoadLocal(1),
+        ],
+        VmError::InvalidLocal(1),
+    );
+    assert_program_error(vec![Push(1), CallArgs(0, 2)], VmError::StackUnderflow);
+    assert_program_error(vec![Call(7)], VmError::InvalidInstructionPointer);
+}
+
+#[test]
+fn test_runaway_recursion_overflows_call_stack() {
+    use Instruction::*;
+    let mut vm = VM::new(vec![Call(0)]);
+    assert_eq!(vm.run().unwrap_err(), VmError::CallStackOverflow);
+
+    // countdown(n) recurses n + 1 times, so MAX_CALL_DEPTH calls fit exactly
+    let countdown = |n: usize| {
+        vec![
+            Push(n as i32),
+            CallArgs(3, 1),
+            Halt,
+            LoadLocal(0),
+            JmpIfNot(10),
+            LoadLocal(0),
+            Push(1),
+            Sub,
+            CallArgs(3, 1),
+            RetVal,
+            Push(0),
+            RetVal,
+        ]
+    };
+    assert_program_result(countdown(MAX_CALL_DEPTH - 1), 0);
+    assert_program_error(countdown(MAX_CALL_DEPTH), VmError::CallStackOverflow);
+}
+
+// ============================================================================
+// STRING CONSTANT TESTS
+// ============================================================================
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_hello_world() {
+    use Instruction::*;
+    let mut vm = VM::with_constants(
+        vec![PushConst(0), PrintStr, Halt],
+        strings(&["Hello, world!"]),
+    );
+    assert_eq!(vm.run().unwrap(), None);
+    assert_eq!(vm.string_output(), &["Hello, world!".to_string()]);
+    assert!(vm.output().is_empty());
+}
+
+#[test]
+fn test_invalid_constant_index() {
+    use Instruction::*;
+    let mut vm = VM::with_constants(vec![PushConst(1), PrintStr], strings(&["only one"]));
+    assert_eq!(vm.run(), Err(VmError::InvalidConstant(1)));
+
+    // A plain VM has an empty pool, and Concat checks its operands too
+    assert_program_error(vec![PushConst(0), PrintStr], VmError::InvalidConstant(0));
+    let mut vm = VM::with_constants(vec![PushConst(0), Push(7), Concat], strings(&["a"]));
+    assert_eq!(vm.run(), Err(VmError::InvalidConstant(7)));
+    assert_program_error(vec![PrintStr], VmError::StackUnderflow);
+}
+
+#[test]
+fn test_concat_grows_the_pool() {
+    use Instruction::*;
+    let program = vec![PushConst(0), PushConst(1), Concat, Dup, PrintStr, Halt];
+    let mut vm = VM::with_constants(program, strings(&["foo", "bar"]));
+    assert_eq!(vm.run().unwrap(), Some(2));
+    assert_eq!(vm.constant(2), Some("foobar"));
+    assert_eq!(vm.constant(0), Some("foo"));
+    assert_eq!(vm.constant(3), None);
+    assert_eq!(vm.string_output(), &["foobar".to_string()]);
+}
+
+#[test]
+fn test_print_and_print_str_together() {
+    use Instruction::*;
+    let program = vec![
+        PushConst(0),
+        PrintStr,
+        Push(6),
+        Push(7),
+        Mul,
+        Print,
+        PushConst(1),
+        PrintStr,
+        Halt,
+    ];
+    let mut vm = VM::with_constants(program, strings(&["answer:", "done"]));
+    assert_eq!(vm.run().unwrap(), None);
+    assert_eq!(vm.output(), &[42]);
+    assert_eq!(vm.string_output(), &strings(&["answer:", "done"])[..]);
+}
@@ -17,7 +17,12 @@
 //!     the value and marks it as recently used. If the cache is full, it evicts
 //!     the least recently used item before inserting the new one.
 //!
-//! 4.  **`get()`**: Retrieves a value for a key. If the key exists, it marks it
+//! 4.  **`with_weigher()` / `try_put()`**: A weighted cache keeps the *sum*
+//!     of entry weights under a budget instead of counting entries. `try_put`
+//!     evicts from the LRU end until the new entry fits, and hands the entry
+//!     back as `Err` if it is heavier than the whole budget.
+//!
+//! 5.  **`get()`**: Retrieves a value for a key. If the key exists, it marks it
 //!     as recently used and returns a reference to the value. Otherwise, it
 //!     returns `None`.
 //!
@@ -46,6 +51,9 @@
 use std::collections::{HashMap, LinkedList};
 use std::hash::Hash;
 
+/// Computes the weight of an entry for a weighted cache.
+type Weigher<K, V> = Box<dyn Fn(&K, &V) -> u64 + Send + Sync>;
+
 // TODO: Define the LruCache struct.
 // It should be generic over a key `K` and a value `V`.
 // K needs to have the `Eq` and `Hash` traits.
@@ -53,12 +61,17 @@ use std::hash::Hash;
 // - capacity: usize
 // - map: a HashMap to store keys and values
 // - list: a LinkedList to track the order of usage (MRU to LRU)
+// - weigher, max_weight, total_weight: for weighted caches (an unweighted
+//   cache weighs every entry as 1)
 //
 // pub struct LruCache<K: Eq + Hash, V> { ... }
 pub struct LruCache<K: Eq + Hash, V> {
     _capacity: usize,
-    _map: HashMap<K, V>,
+    _map: HashMap<K, (V, u64)>,
     _list: LinkedList<K>,
+    _weigher: Weigher<K, V>,
+    _max_weight: u64,
+    _total_weight: u64,
 }
 
 impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
@@ -69,6 +82,47 @@ impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
         todo!("Initialize LruCache with capacity, an empty map, and an empty list");
     }
 
+    /// Creates a cache whose entries' total weight stays at or below `max_weight`.
+    pub fn with_weigher(
+        max_weight: u64,
+        weigher: impl Fn(&K, &V) -> u64 + Send + Sync + 'static,
+    ) -> Self {
+        // TODO: Like `new`, but store the weigher and the weight budget.
+        let _ = (max_weight, weigher);
+        todo!("Initialize a weighted LruCache");
+    }
+
+    /// Puts a key-value pair, returning it as `Err` if it can never fit.
+    pub fn try_put(&mut self, key: K, value: V) -> Result<(), (K, V)> {
+        // TODO: Weigh the entry and reject it if it exceeds `max_weight`.
+        // Updating a key adjusts the total by the weight difference; either
+        // way, pop keys off the LRU end until the total fits the budget.
+        let _ = (key, value);
+        todo!("Implement the try_put method");
+    }
+
+    /// Removes a key, returning its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        // TODO: Remove from both the map and the list, and subtract its weight.
+        let _ = key;
+        todo!("Implement the remove method");
+    }
+
+    /// The keys from most recently used to least recently used.
+    pub fn keys_mru_order(&self) -> Vec<&K> {
+        todo!("Collect the list's keys");
+    }
+
+    /// The sum of all entry weights.
+    pub fn total_weight(&self) -> u64 {
+        todo!("Return the total weight");
+    }
+
+    /// The weight budget.
+    pub fn max_weight(&self) -> u64 {
+        todo!("Return the max weight");
+    }
+
     /// Puts a key-value pair into the cache.
     pub fn put(&mut self, key: K, value: V) {
         // TODO: Implement the put logic.
@@ -65,6 +65,33 @@ fn main() {
     assert!(cache.get(&1).is_some());
     println!();
 
+    // ============================================================================
+    // DEMO 3: Weighted Entries
+    // ============================================================================
+    println!("3. Weighted Entries:");
+    println!("   ----------------");
+    // Budget of 16 bytes, each entry weighs its body length.
+    let mut bodies = LruCache::with_weigher(16, |_: &&str, body: &String| body.len() as u64);
+    bodies.put("/a", "tiny".to_string());
+    bodies.put("/b", "small".to_string());
+    bodies.put("/c", "medium".to_string());
+    println!(
+        "   Three bodies, weight {}/{}",
+        bodies.total_weight(),
+        bodies.max_weight()
+    );
+
+    bodies.put("/d", "a much larger".to_string());
+    println!(
+        "   Put a 13-byte body. MRU order now: {:?}",
+        bodies.keys_mru_order()
+    );
+
+    match bodies.try_put("/huge", "x".repeat(100)) {
+        Ok(()) => println!("   Stored /huge"),
+        Err((key, body)) => println!("   Rejected {} ({} bytes > budget)", key, body.len()),
+    }
+    println!();
 
     println!("=== Demo Complete! ===");
     println!("\nNow try:");
@@ -17,6 +17,19 @@
 //!     back. This structure allows for O(1) additions to the front and O(1)
 //!     removals from the back.
 //!
+//! ## Weighted Entries
+//!
+//! By default every entry counts as 1 towards the capacity. A cache built with
+//! `with_weigher` instead asks a closure how heavy each entry is (say, the
+//! byte length of a cached HTTP body) and keeps the *sum* of weights under a
+//! budget. One heavy insert may then evict several light entries, and an
+//! entry heavier than the whole budget can never fit, so `try_put` hands it
+//! back instead of emptying the cache for nothing.
+//!
+//! The plain constructor is just the weighted cache with a weigher that
+//! always returns 1 and a budget equal to the capacity, so there is only one
+//! eviction path to get right.
+//!
 //! ## Performance Note
 //!
 //! The one operation that is *not* O(1) in this implementation is moving an
@@ -34,14 +47,21 @@ use std::collections::{HashMap, LinkedList};
 use std::hash::Hash;
 use std::fmt;
 
+/// Computes the weight of an entry for a weighted cache.
+type Weigher<K, V> = Box<dyn Fn(&K, &V) -> u64 + Send + Sync>;
+
 /// A Least Recently Used (LRU) cache.
 pub struct LruCache<K: Eq + Hash, V> {
     capacity: usize,
-    /// `map` stores the key and its corresponding value.
-    map: HashMap<K, V>,
+    /// `map` stores the key, its corresponding value, and the value's weight
+    /// (remembered so removal doesn't have to ask the weigher again).
+    map: HashMap<K, (V, u64)>,
     /// `list` stores the keys in order of usage, from most recently used (front)
     /// to least recently used (back).
     list: LinkedList<K>,
+    weigher: Weigher<K, V>,
+    max_weight: u64,
+    total_weight: u64,
 }
 
 impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
@@ -56,32 +76,90 @@ impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
             capacity,
             map: HashMap::with_capacity(capacity),
             list: LinkedList::new(),
+            weigher: Box::new(|_, _| 1),
+            max_weight: capacity as u64,
+            total_weight: 0,
+        }
+    }
+
+    /// Creates a cache that keeps the total weight of its entries at or
+    /// below `max_weight`, weighing each entry with `weigher`.
+    ///
+    /// `max_weight` must be greater than 0.
+    pub fn with_weigher(
+        max_weight: u64,
+        weigher: impl Fn(&K, &V) -> u64 + Send + Sync + 'static,
+    ) -> Self {
+        if max_weight == 0 {
+            panic!("LRU Cache max weight must be greater than 0");
+        }
+        LruCache {
+            capacity: usize::try_from(max_weight).unwrap_or(usize::MAX),
+            map: HashMap::new(),
+            list: LinkedList::new(),
+            weigher: Box::new(weigher),
+            max_weight,
+            total_weight: 0,
         }
     }
 
     /// Puts a key-value pair into the cache.
+    ///
+    /// An entry heavier than `max_weight` is dropped; use `try_put` to get
+    /// it back.
     pub fn put(&mut self, key: K, value: V) {
-        if self.map.contains_key(&key) {
+        let _ = self.try_put(key, value);
+    }
+
+    /// Puts a key-value pair into the cache, evicting least recently used
+    /// entries until it fits.
+    ///
+    /// Fails, returning the pair and leaving the cache untouched, if the
+    /// entry alone weighs more than `max_weight`.
+    pub fn try_put(&mut self, key: K, value: V) -> Result<(), (K, V)> {
+        let weight = (self.weigher)(&key, &value);
+        if weight > self.max_weight {
+            return Err((key, value));
+        }
+
+        if let Some((_, old_weight)) = self.map.remove(&key) {
             // --- Key already exists ---
-            // 1. Update the value in the map.
-            self.map.insert(key.clone(), value);
+            // 1. Swap in the new value, adjusting the total by the difference.
+            self.total_weight = self.total_weight - old_weight + weight;
+            self.map.insert(key.clone(), (value, weight));
             // 2. Mark the key as most recently used by moving it to the front of the list.
             self.move_to_front(&key);
+            // 3. A heavier value may push the total over budget. The updated
+            //    key is at the front, so it is evicted last, and since it fits
+            //    on its own the loop stops before reaching it.
+            self.evict_until(0);
         } else {
             // --- New key ---
-            // 1. Check if the cache is at capacity.
-            if self.list.len() == self.capacity {
-                // Evict the least recently used item.
-                if let Some(lru_key) = self.list.pop_back() {
-                    // Remove it from the map as well.
-                    self.map.remove(&lru_key);
-                }
-            }
+            // 1. Evict least recently used items until the new entry fits.
+            //    (Unweighted: every entry weighs 1, so this evicts exactly
+            //    one item when the cache is full.)
+            self.evict_until(weight);
             // 2. Insert the new key and value.
-            self.map.insert(key.clone(), value);
+            self.map.insert(key.clone(), (value, weight));
+            self.total_weight += weight;
             // 3. Add the new key to the front of the list (most recently used).
             self.list.push_front(key);
         }
+        Ok(())
+    }
+
+    /// Evict from the LRU end until `incoming` more weight fits.
+    fn evict_until(&mut self, incoming: u64) {
+        while self.total_weight + incoming > self.max_weight {
+            match self.list.pop_back() {
+                Some(lru_key) => {
+                    if let Some((_, weight)) = self.map.remove(&lru_key) {
+                        self.total_weight -= weight;
+                    }
+                }
+                None => break,
+            }
+        }
     }
 
     /// Gets a reference to a value for a given key.
@@ -92,12 +170,27 @@ impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
             // Mark the key as most recently used.
             self.move_to_front(key);
             // Now, return the value from the map.
-            self.map.get(key)
+            self.map.get(key).map(|(value, _)| value)
         } else {
             None
         }
     }
 
+    /// Removes a key from the cache, returning its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (value, weight) = self.map.remove(key)?;
+        self.total_weight -= weight;
+        // Moving the key to the front and popping it reuses the O(n) walk.
+        self.move_to_front(key);
+        self.list.pop_front();
+        Some(value)
+    }
+
+    /// The keys from most recently used to least recently used.
+    pub fn keys_mru_order(&self) -> Vec<&K> {
+        self.list.iter().collect()
+    }
+
     /// Helper method to move a key to the front of the usage list.
     ///
     /// This is the O(n) part of the implementation.
@@ -129,10 +222,22 @@ impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
         self.list.is_empty()
     }
 
-    /// Returns the capacity of the cache.
+    /// Returns the capacity of the cache. For a weighted cache this is
+    /// `max_weight`, since it has no fixed entry limit.
     pub fn capacity(&self) -> usize {
         self.capacity
     }
+
+    /// The sum of the weights of all entries (the entry count when
+    /// unweighted).
+    pub fn total_weight(&self) -> u64 {
+        self.total_weight
+    }
+
+    /// The weight budget (the capacity when unweighted).
+    pub fn max_weight(&self) -> u64 {
+        self.max_weight
+    }
 }
 
 /// Implement `Debug` for easy printing of the cache's state.
@@ -141,6 +246,7 @@ impl<K: fmt::Debug + Eq + Hash, V: fmt::Debug> fmt::Debug for LruCache<K, V> {
         f.debug_struct("LruCache")
             .field("capacity", &self.capacity)
             .field("size", &self.list.len())
+            .field("weight", &self.total_weight)
             .field("order (MRU->LRU)", &self.list)
             .finish()
     }
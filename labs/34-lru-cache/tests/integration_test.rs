@@ -6,6 +6,7 @@
 //! - Updating existing values
 //! - Correctly tracking usage order
 //! - Edge cases like zero capacity (panic) and capacity 1.
+//! - Weighted entries: multi-entry eviction, oversized rejection, weight accounting
 
 use lru_cache::solution::LruCache;
 
@@ -152,4 +153,113 @@ fn test_complex_access_pattern() {
 
     assert_eq!(cache.get(&1), Some(&10)); // 1, 6, 5, 2
     assert_eq!(cache.get(&2), Some(&20)); // 2, 1, 6, 5
-}
\ No newline at end of file
+}
+// ============================================================================
+// WEIGHTED ENTRIES
+// ============================================================================
+
+fn body_cache(max_weight: u64) -> LruCache<&'static str, String> {
+    LruCache::with_weigher(max_weight, |_, body: &String| body.len() as u64)
+}
+
+#[test]
+fn test_unweighted_cache_weighs_entries_as_one() {
+    let mut cache = LruCache::new(3);
+    assert_eq!(cache.max_weight(), 3);
+    cache.put("a", 1);
+    cache.put("b", 2);
+    assert_eq!(cache.total_weight(), 2);
+    cache.put("a", 11);
+    assert_eq!(cache.total_weight(), 2);
+    assert_eq!(cache.try_put("c", 3), Ok(()));
+    assert_eq!(cache.try_put("d", 4), Ok(()));
+    assert_eq!(cache.total_weight(), 3);
+    assert_eq!(cache.keys_mru_order(), vec![&"d", &"c", &"a"]);
+}
+
+#[test]
+fn test_heavy_insert_evicts_several_entries() {
+    let mut cache = body_cache(10);
+    cache.put("a", "xx".to_string());
+    cache.put("b", "xxx".to_string());
+    cache.put("c", "xx".to_string());
+    cache.put("d", "x".to_string());
+    assert_eq!(cache.total_weight(), 8);
+
+    // 'a' becomes MRU, so 'b' then 'c' are the ones to go.
+    cache.get(&"a");
+    cache.put("e", "xxxxxx".to_string());
+
+    assert_eq!(cache.keys_mru_order(), vec![&"e", &"a", &"d"]);
+    assert_eq!(cache.total_weight(), 9);
+    assert_eq!(cache.get(&"b"), None);
+    assert_eq!(cache.get(&"c"), None);
+}
+
+#[test]
+fn test_oversized_entry_rejected() {
+    let mut cache = body_cache(4);
+    cache.put("small", "ab".to_string());
+
+    let rejected = cache.try_put("huge", "abcde".to_string());
+    assert_eq!(rejected, Err(("huge", "abcde".to_string())));
+    // Nothing was evicted to make room for an entry that could never fit.
+    assert_eq!(cache.keys_mru_order(), vec![&"small"]);
+    assert_eq!(cache.total_weight(), 2);
+
+    // Oversized updates leave the old value in place.
+    assert!(cache.try_put("small", "abcdef".to_string()).is_err());
+    assert_eq!(cache.get(&"small"), Some(&"ab".to_string()));
+
+    // An entry exactly at the budget fits.
+    assert_eq!(cache.try_put("full", "abcd".to_string()), Ok(()));
+    assert_eq!(cache.keys_mru_order(), vec![&"full"]);
+}
+
+#[test]
+fn test_weight_accounting_across_updates_and_removes() {
+    let mut cache = body_cache(10);
+    cache.put("a", "xxx".to_string());
+    cache.put("b", "xxxx".to_string());
+    assert_eq!(cache.total_weight(), 7);
+
+    cache.put("a", "x".to_string());
+    assert_eq!(cache.total_weight(), 5);
+
+    // Growing 'b' pushes the total to 11, so the LRU entry 'a' goes.
+    cache.put("b", "xxxxxxxxxx".to_string());
+    assert_eq!(cache.total_weight(), 10);
+    assert_eq!(cache.keys_mru_order(), vec![&"b"]);
+
+    assert_eq!(cache.remove(&"b"), Some("xxxxxxxxxx".to_string()));
+    assert_eq!(cache.remove(&"b"), None);
+    assert_eq!(cache.total_weight(), 0);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_weighted_keys_mru_order_tracks_access() {
+    let mut cache = body_cache(6);
+    cache.put("a", "xx".to_string());
+    cache.put("b", "xx".to_string());
+    cache.put("c", "xx".to_string());
+    assert_eq!(cache.keys_mru_order(), vec![&"c", &"b", &"a"]);
+
+    cache.get(&"a");
+    cache.remove(&"c");
+    assert_eq!(cache.keys_mru_order(), vec![&"a", &"b"]);
+
+    // Zero-weight entries never force an eviction.
+    cache.put("d", String::new());
+    cache.put("e", "xx".to_string());
+    assert_eq!(cache.keys_mru_order(), vec![&"e", &"d", &"a", &"b"]);
+    cache.put("f", "x".to_string());
+    assert_eq!(cache.keys_mru_order(), vec![&"f", &"e", &"d", &"a"]);
+    assert_eq!(cache.total_weight(), 5);
+}
+
+#[test]
+#[should_panic]
+fn test_zero_max_weight_panics() {
+    body_cache(0);
+}
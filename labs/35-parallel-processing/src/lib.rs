@@ -37,6 +37,9 @@
 //! Check out `src/solution.rs` for a complete, heavily-commented solution.
 
 use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
 
 // A deliberately slow primality test.
 // This is to make the performance difference more obvious.
@@ -98,6 +101,391 @@ where
 }
 
 
+// --- Divide and Conquer (Sequential Versions Provided) ---
+//
+// Parallel iterators are great for "do the same thing to every element".
+// Recursive algorithms like merge sort split the problem instead: sort the
+// left half and the right half, then combine. The two halves are independent,
+// so `rayon::join(a, b)` can run them at the same time. Rayon's work stealing
+// means idle threads pick up halves that busy threads haven't started yet.
+
+/// Below this many elements, spawning parallel work costs more than it saves.
+pub const SEQUENTIAL_CUTOFF: usize = 1024;
+
+/// Merges two sorted slices into `out`, which must be exactly as long as both.
+/// Takes from `left` on ties, which keeps the sort stable.
+fn merge(left: &[i32], right: &[i32], out: &mut [i32]) {
+    let (mut i, mut j) = (0, 0);
+    for slot in out.iter_mut() {
+        if j >= right.len() || (i < left.len() && left[i] <= right[j]) {
+            *slot = left[i];
+            i += 1;
+        } else {
+            *slot = right[j];
+            j += 1;
+        }
+    }
+}
+
+/// Sorts both halves of `data` with `sort_halves`, then merges them.
+fn merge_sort_with(data: &mut [i32], sort_halves: impl FnOnce(&mut [i32], &mut [i32])) {
+    let mid = data.len() / 2;
+    let (left, right) = data.split_at_mut(mid);
+    sort_halves(left, right);
+
+    // Merge into a scratch buffer, then copy back.
+    let mut merged = vec![0; data.len()];
+    merge(&data[..mid], &data[mid..], &mut merged);
+    data.copy_from_slice(&merged);
+}
+
+/// Classic top-down merge sort on a single thread.
+pub fn sequential_merge_sort(data: &mut [i32]) {
+    if data.len() <= 1 {
+        return;
+    }
+    merge_sort_with(data, |left, right| {
+        sequential_merge_sort(left);
+        sequential_merge_sort(right);
+    });
+}
+
+/// Merge sort that sorts the two halves in parallel with `rayon::join`.
+pub fn parallel_merge_sort(data: &mut [i32]) {
+    // TODO: Below SEQUENTIAL_CUTOFF, fall back to `sequential_merge_sort`.
+    // Otherwise use `merge_sort_with` and sort the halves with `rayon::join`.
+    let _ = data;
+    todo!("Implement parallel merge sort");
+}
+
+/// Median of the first, middle and last elements. A fixed rule (no
+/// randomness) keeps quickselect deterministic.
+fn choose_pivot(data: &[i32]) -> i32 {
+    let mut candidates = [data[0], data[data.len() / 2], data[data.len() - 1]];
+    candidates.sort_unstable();
+    candidates[1]
+}
+
+/// Returns the `k`-th smallest element (0-based), or `None` if `k` is out
+/// of range. `partition` splits a slice into (less, equal count, greater).
+fn quickselect_with(
+    data: &[i32],
+    mut k: usize,
+    partition: impl Fn(&[i32], i32) -> (Vec<i32>, usize, Vec<i32>),
+) -> Option<i32> {
+    if k >= data.len() {
+        return None;
+    }
+    let mut current = data.to_vec();
+    loop {
+        let pivot = choose_pivot(&current);
+        let (less, equal, greater) = partition(&current, pivot);
+        if k < less.len() {
+            current = less;
+        } else if k < less.len() + equal {
+            return Some(pivot);
+        } else {
+            k -= less.len() + equal;
+            current = greater;
+        }
+    }
+}
+
+fn partition_sequential(data: &[i32], pivot: i32) -> (Vec<i32>, usize, Vec<i32>) {
+    let less = data.iter().copied().filter(|&x| x < pivot).collect();
+    let equal = data.iter().filter(|&&x| x == pivot).count();
+    let greater = data.iter().copied().filter(|&x| x > pivot).collect();
+    (less, equal, greater)
+}
+
+fn partition_parallel(data: &[i32], pivot: i32) -> (Vec<i32>, usize, Vec<i32>) {
+    // TODO: Like `partition_sequential`, but with `.par_iter()` for slices
+    // longer than SEQUENTIAL_CUTOFF.
+    let _ = (data, pivot);
+    todo!("Implement parallel partition");
+}
+
+/// Finds the `k`-th smallest element (0-based) without sorting, sequentially.
+/// Same answer as `sorted[k]`.
+pub fn sequential_quickselect(data: &[i32], k: usize) -> Option<i32> {
+    quickselect_with(data, k, partition_sequential)
+}
+
+/// Finds the `k`-th smallest element (0-based), partitioning large slices
+/// in parallel.
+pub fn parallel_quickselect(data: &[i32], k: usize) -> Option<i32> {
+    quickselect_with(data, k, partition_parallel)
+}
+
+// --- Composable Pipelines ---
+//
+// Real data processing is rarely one `map`. A pipeline chains stages, each
+// taking the whole `Vec<T>` and returning a new one. Every stage runs in
+// parallel internally, and we time each one so you can see where the time
+// actually goes (often it's not where you'd guess).
+
+/// One stage: takes the data, returns the transformed data.
+type Stage<T> = Box<dyn Fn(Vec<T>) -> Vec<T> + Sync + Send>;
+
+/// What happened in one stage of a pipeline run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageStat {
+    /// e.g. `"map"`, `"filter"` or `"chunked(1000)"`
+    pub name: String,
+    pub input_len: usize,
+    pub output_len: usize,
+    /// Wall-clock time for this stage.
+    pub duration: Duration,
+}
+
+/// The output of `Pipeline::execute` plus one `StageStat` per stage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineResult<T> {
+    pub output: Vec<T>,
+    pub stage_stats: Vec<StageStat>,
+}
+
+impl<T> PipelineResult<T> {
+    /// Sum of all stage durations.
+    pub fn total_duration(&self) -> Duration {
+        self.stage_stats.iter().map(|stat| stat.duration).sum()
+    }
+}
+
+/// A list of parallel stages applied in order.
+///
+/// Stages are stored as boxed closures (`Box<dyn Fn ...>`) because every
+/// closure has its own unique type; boxing lets them all live in one `Vec`.
+/// The `Sync + Send` bounds let rayon's threads share them.
+pub struct Pipeline<T> {
+    stages: Vec<(String, Stage<T>)>,
+}
+
+impl<T: Send + Sync + 'static> Pipeline<T> {
+    pub fn new() -> Self {
+        Pipeline { stages: Vec::new() }
+    }
+
+    /// Adds a stage that transforms every element.
+    pub fn add_map<F>(self, f: F) -> Self
+    where
+        F: Fn(T) -> T + Sync + Send + 'static,
+    {
+        // TODO: Box a closure that runs `f` over `data.into_par_iter()`
+        // and push it with the name "map".
+        let _ = f;
+        todo!("Add map stage");
+    }
+
+    /// Adds a stage that keeps only the elements matching `pred`.
+    pub fn add_filter<P>(self, pred: P) -> Self
+    where
+        P: Fn(&T) -> bool + Sync + Send + 'static,
+    {
+        // TODO: Like `add_map`, but with `.filter(...)`; name it "filter".
+        let _ = pred;
+        todo!("Add filter stage");
+    }
+
+    /// Adds a stage that hands `f` whole chunks of `chunk_size` elements.
+    /// A size of 0 is treated as 1.
+    pub fn add_chunked<F>(self, chunk_size: usize, f: F) -> Self
+    where
+        F: Fn(&[T]) -> Vec<T> + Sync + Send + 'static,
+    {
+        // TODO: Use `par_chunks`, keep chunk order, flatten the results.
+        // Name the stage "chunked(<size>)".
+        let _ = (chunk_size, f);
+        todo!("Add chunked stage");
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Runs every stage in order, timing each one.
+    pub fn execute(&self, data: Vec<T>) -> PipelineResult<T> {
+        // TODO: Feed the data through each stage, recording a StageStat
+        // (name, input/output length, elapsed time) per stage.
+        let _ = (data, Instant::now());
+        todo!("Execute pipeline");
+    }
+}
+
+impl<T: Send + Sync + 'static> Default for Pipeline<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// --- Word Frequency with fold/reduce (Sequential Version Provided) ---
+//
+// Counting words in parallel with one shared `HashMap` would need a lock on
+// every insert. Instead:
+// - `fold` gives each rayon worker its OWN map to fill (no sharing at all)
+// - `reduce` merges those per-worker maps pairwise into one result
+// The number of maps is roughly the number of threads, not the number of
+// lines, so merging is cheap.
+
+/// How words are normalized before counting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordCountOptions {
+    /// Lowercase words so "The" and "the" count together.
+    pub lowercase: bool,
+    /// Skip words shorter than this many characters (after stripping).
+    pub min_length: usize,
+}
+
+impl Default for WordCountOptions {
+    fn default() -> Self {
+        WordCountOptions {
+            lowercase: true,
+            min_length: 1,
+        }
+    }
+}
+
+/// Splits a line on whitespace and normalizes each word.
+///
+/// Only leading/trailing non-alphanumeric characters are stripped, so
+/// "don't" and "naïve" survive intact while "(hello!)" becomes "hello".
+fn words<'a>(line: &'a str, options: &'a WordCountOptions) -> impl Iterator<Item = String> + 'a {
+    line.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(move |word| !word.is_empty() && word.chars().count() >= options.min_length)
+        .map(move |word| {
+            if options.lowercase {
+                word.to_lowercase()
+            } else {
+                word.to_string()
+            }
+        })
+}
+
+fn count_into(
+    mut counts: HashMap<String, usize>,
+    line: &str,
+    options: &WordCountOptions,
+) -> HashMap<String, usize> {
+    for word in words(line, options) {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Counts words line by line on a single thread.
+pub fn sequential_word_count(text: &str, options: &WordCountOptions) -> HashMap<String, usize> {
+    text.lines().fold(HashMap::new(), |counts, line| {
+        count_into(counts, line, options)
+    })
+}
+
+/// Counts words with per-thread maps (`fold`) merged at the end (`reduce`).
+pub fn parallel_word_count(text: &str, options: &WordCountOptions) -> HashMap<String, usize> {
+    // TODO: Use `text.par_lines()`, `.fold(HashMap::new, ...)` with
+    // `count_into`, then `.reduce(HashMap::new, ...)` to merge the maps.
+    let _ = (text, options);
+    todo!("Implement parallel word count");
+}
+
+/// The `n` most frequent words, most frequent first; ties alphabetical.
+pub fn top_n_words(counts: &HashMap<String, usize>, n: usize) -> Vec<(String, usize)> {
+    // TODO: Sort by count descending, then word ascending, and keep `n`.
+    let _ = (counts, n);
+    todo!("Implement top-n words");
+}
+
+pub const CANCEL_CHECK_INTERVAL: usize = 1024;
+pub const PRIME_BLOCK_SIZE: u32 = 4096;
+
+/// The lowest-index match and its index, like a sequential `position`.
+pub fn parallel_find_first(
+    data: &[i32],
+    pred: impl Fn(&i32) -> bool + Sync,
+) -> Option<(usize, i32)> {
+    // TODO: `par_iter().enumerate().find_first(...)`. Why not `find_any`?
+    let _ = (data, pred);
+    todo!("Implement lowest-index parallel find");
+}
+
+/// `Some(found)`, or `None` if `cancel` was set first.
+pub fn parallel_any_with_cancel(
+    data: &[i32],
+    pred: impl Fn(&i32) -> bool + Sync,
+    cancel: &AtomicBool,
+) -> Option<bool> {
+    // TODO: Walk `par_chunks(CANCEL_CHECK_INTERVAL)` with `find_map_any`,
+    // checking the flag before each chunk.
+    let _ = (data, pred, cancel);
+    todo!("Implement cancellable parallel any");
+}
+
+/// The first `count` primes in `start..=end`, ascending.
+pub fn find_primes_parallel_early_stop(start: u32, end: u32, count: usize) -> Vec<u32> {
+    // TODO: Test blocks of PRIME_BLOCK_SIZE numbers in parallel, one block
+    // at a time, until `count` primes are found.
+    let _ = (start, end, count);
+    todo!("Implement early-stopping prime search");
+}
+
+// --- Parallel Prefix Sums (Sequential Version Provided) ---
+//
+// Blocked scan: sum each chunk in parallel, scan the chunk totals
+// sequentially to get each chunk's offset, then scan each chunk in parallel
+// starting from its offset.
+
+pub const SCAN_CHUNK_SIZE: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanOverflow {
+    pub index: usize,
+}
+
+impl std::fmt::Display for ScanOverflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "prefix sum overflows i64 at index {}", self.index)
+    }
+}
+
+impl std::error::Error for ScanOverflow {}
+
+/// Inclusive prefix sums on one thread, wrapping on overflow.
+pub fn sequential_prefix_sum(data: &[i64]) -> Vec<i64> {
+    data.iter()
+        .scan(0i64, |total, &x| {
+            *total = total.wrapping_add(x);
+            Some(*total)
+        })
+        .collect()
+}
+
+/// Inclusive prefix sums with a blocked scan; same result as sequential.
+pub fn parallel_prefix_sum(data: &[i64]) -> Vec<i64> {
+    // TODO: `par_chunks(SCAN_CHUNK_SIZE)` for the chunk totals, a sequential
+    // scan for the offsets, then `par_chunks_mut` zipped with the input and
+    // offsets. Fixed-size chunks keep the result independent of threads.
+    let _ = data;
+    todo!("Implement the blocked parallel scan");
+}
+
+/// Exclusive prefix sums: `out[0] == 0`.
+pub fn parallel_exclusive_prefix_sum(data: &[i64]) -> Vec<i64> {
+    let _ = data;
+    todo!("Implement the exclusive blocked scan");
+}
+
+/// Inclusive prefix sums, or the first index that overflows `i64`.
+pub fn checked_parallel_prefix_sum(data: &[i64]) -> Result<Vec<i64>, ScanOverflow> {
+    // TODO: Keep totals in i128 and narrow with `i64::try_from`; report the
+    // lowest failing index across chunks.
+    let _ = data;
+    todo!("Implement the checked blocked scan");
+}
+
 // Re-export the solution module so people can compare
 #[doc(hidden)]
 pub mod solution;
\ No newline at end of file
@@ -61,6 +61,104 @@ fn main() {
     println!("   (Running on a machine with {} logical CPU cores)", num_cores);
 
 
+    // ============================================================================
+    // DEMO 3: Divide and Conquer with rayon::join
+    // ============================================================================
+    println!("\n4. Merge sort on 2,000,000 numbers");
+    let data: Vec<i32> = (0..2_000_000_i64)
+        .map(|i| ((i * 7_919) % 1_000_003) as i32)
+        .collect();
+
+    let mut seq = data.clone();
+    let start = Instant::now();
+    solution::sequential_merge_sort(&mut seq);
+    println!("   -> Sequential: {:?}", start.elapsed());
+
+    let mut par = data.clone();
+    let start = Instant::now();
+    solution::parallel_merge_sort(&mut par);
+    println!("   -> Parallel:   {:?}", start.elapsed());
+    assert_eq!(seq, par);
+
+    let median = solution::parallel_quickselect(&data, data.len() / 2);
+    println!("   -> Median via parallel quickselect: {:?}", median);
+
+    // ============================================================================
+    // DEMO 4: A Timed Pipeline
+    // ============================================================================
+    println!("\n5. Pipeline: keep primes -> square -> sum per chunk");
+    let pipeline = solution::Pipeline::new()
+        .add_filter(|&n: &u64| n > 1 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0))
+        .add_map(|n| n * n)
+        .add_chunked(10_000, |chunk: &[u64]| vec![chunk.iter().sum()]);
+    let result = pipeline.execute((0..500_000).collect());
+    for stat in &result.stage_stats {
+        println!(
+            "   -> {:<14} {:>7} -> {:<7} in {:?}",
+            stat.name, stat.input_len, stat.output_len, stat.duration
+        );
+    }
+    println!("   -> Total: {:?}", result.total_duration());
+
+    // ============================================================================
+    // DEMO 5: Word Frequency with fold/reduce
+    // ============================================================================
+    println!("\n6. Word frequency over a repeated paragraph");
+    let text = "It was the best of times, it was the worst of times,\n\
+                it was the age of wisdom, it was the age of foolishness.\n"
+        .repeat(50_000);
+    let options = solution::WordCountOptions::default();
+    let start = Instant::now();
+    let counts = solution::parallel_word_count(&text, &options);
+    println!(
+        "   -> Counted {} distinct words in {:?}",
+        counts.len(),
+        start.elapsed()
+    );
+    for (word, count) in solution::top_n_words(&counts, 3) {
+        println!("   -> {:<6} {}", word, count);
+    }
+
+    // ============================================================================
+    // DEMO 6: Early Exit and Cancellation
+    // ============================================================================
+    println!("\n7. Searches that stop early");
+    let data: Vec<i32> = (0..10_000_000).collect();
+    println!(
+        "   -> First multiple of 7919 above 1000: {:?}",
+        solution::parallel_find_first(&data, |&x| x > 1000 && x % 7919 == 0)
+    );
+    let start = Instant::now();
+    let primes = solution::find_primes_parallel_early_stop(1_000_000_000, u32::MAX, 5);
+    println!(
+        "   -> First 5 primes above 1e9: {:?} in {:?}",
+        primes,
+        start.elapsed()
+    );
+    let cancel = std::sync::atomic::AtomicBool::new(true);
+    println!(
+        "   -> Search with the cancel flag already set: {:?}",
+        solution::parallel_any_with_cancel(&data, |&x| x == 42, &cancel)
+    );
+
+    // ============================================================================
+    // DEMO 7: Parallel Prefix Sums
+    // ============================================================================
+    println!("\n8. Prefix sums with a blocked scan");
+    let values: Vec<i64> = (1..=5_000_000).collect();
+    let start = Instant::now();
+    let sums = solution::parallel_prefix_sum(&values);
+    println!(
+        "   -> Last of {} prefix sums: {} in {:?}",
+        sums.len(),
+        sums[sums.len() - 1],
+        start.elapsed()
+    );
+    println!(
+        "   -> Checked scan of [i64::MAX, 1]: {:?}",
+        solution::checked_parallel_prefix_sum(&[i64::MAX, 1])
+    );
+
     println!("\n=== Demo Complete! ===");
     println!("\nTo see more detailed benchmarks, run:");
     println!("  cargo bench -p parallel-processing");
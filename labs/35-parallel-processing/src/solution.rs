@@ -28,6 +28,9 @@
 //!   distribute work efficiently among threads.
 
 use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 /// A deliberately slow primality test function to make the benefits of
 /// parallelization more apparent. In a real application, you would use a
@@ -92,3 +95,581 @@ where
         .map(|&item| f(item)) // Apply the function `f` to each item in parallel.
         .collect() // Collect the results into a new Vec.
 }
+
+// --- Divide and Conquer ---
+//
+// Parallel iterators are great for "do the same thing to every element".
+// Recursive algorithms like merge sort split the problem instead: sort the
+// left half and the right half, then combine. The two halves are independent,
+// so `rayon::join(a, b)` can run them at the same time. Rayon's work stealing
+// means idle threads pick up halves that busy threads haven't started yet.
+
+/// Below this many elements, spawning parallel work costs more than it saves.
+pub const SEQUENTIAL_CUTOFF: usize = 1024;
+
+/// Merges two sorted slices into `out`, which must be exactly as long as both.
+/// Takes from `left` on ties, which keeps the sort stable.
+fn merge(left: &[i32], right: &[i32], out: &mut [i32]) {
+    let (mut i, mut j) = (0, 0);
+    for slot in out.iter_mut() {
+        if j >= right.len() || (i < left.len() && left[i] <= right[j]) {
+            *slot = left[i];
+            i += 1;
+        } else {
+            *slot = right[j];
+            j += 1;
+        }
+    }
+}
+
+/// Sorts both halves of `data` with `sort_halves`, then merges them.
+fn merge_sort_with(data: &mut [i32], sort_halves: impl FnOnce(&mut [i32], &mut [i32])) {
+    let mid = data.len() / 2;
+    let (left, right) = data.split_at_mut(mid);
+    sort_halves(left, right);
+
+    // Merge into a scratch buffer, then copy back.
+    let mut merged = vec![0; data.len()];
+    merge(&data[..mid], &data[mid..], &mut merged);
+    data.copy_from_slice(&merged);
+}
+
+/// Classic top-down merge sort on a single thread.
+pub fn sequential_merge_sort(data: &mut [i32]) {
+    if data.len() <= 1 {
+        return;
+    }
+    merge_sort_with(data, |left, right| {
+        sequential_merge_sort(left);
+        sequential_merge_sort(right);
+    });
+}
+
+/// Merge sort that sorts the two halves in parallel with `rayon::join`.
+///
+/// `split_at_mut` hands out two non-overlapping `&mut` slices, so the
+/// borrow checker can prove the two closures never touch the same data.
+pub fn parallel_merge_sort(data: &mut [i32]) {
+    if data.len() <= SEQUENTIAL_CUTOFF {
+        sequential_merge_sort(data);
+        return;
+    }
+    merge_sort_with(data, |left, right| {
+        rayon::join(|| parallel_merge_sort(left), || parallel_merge_sort(right));
+    });
+}
+
+/// Median of the first, middle and last elements. A fixed rule (no
+/// randomness) keeps quickselect deterministic.
+fn choose_pivot(data: &[i32]) -> i32 {
+    let mut candidates = [data[0], data[data.len() / 2], data[data.len() - 1]];
+    candidates.sort_unstable();
+    candidates[1]
+}
+
+/// Returns the `k`-th smallest element (0-based), or `None` if `k` is out
+/// of range. `partition` splits a slice into (less, equal count, greater).
+fn quickselect_with(
+    data: &[i32],
+    mut k: usize,
+    partition: impl Fn(&[i32], i32) -> (Vec<i32>, usize, Vec<i32>),
+) -> Option<i32> {
+    if k >= data.len() {
+        return None;
+    }
+    let mut current = data.to_vec();
+    loop {
+        let pivot = choose_pivot(&current);
+        let (less, equal, greater) = partition(&current, pivot);
+        if k < less.len() {
+            current = less;
+        } else if k < less.len() + equal {
+            return Some(pivot);
+        } else {
+            k -= less.len() + equal;
+            current = greater;
+        }
+    }
+}
+
+fn partition_sequential(data: &[i32], pivot: i32) -> (Vec<i32>, usize, Vec<i32>) {
+    let less = data.iter().copied().filter(|&x| x < pivot).collect();
+    let equal = data.iter().filter(|&&x| x == pivot).count();
+    let greater = data.iter().copied().filter(|&x| x > pivot).collect();
+    (less, equal, greater)
+}
+
+fn partition_parallel(data: &[i32], pivot: i32) -> (Vec<i32>, usize, Vec<i32>) {
+    if data.len() <= SEQUENTIAL_CUTOFF {
+        return partition_sequential(data, pivot);
+    }
+    // `collect` on a parallel iterator keeps the original order.
+    let less = data.par_iter().copied().filter(|&x| x < pivot).collect();
+    let equal = data.par_iter().filter(|&&x| x == pivot).count();
+    let greater = data.par_iter().copied().filter(|&x| x > pivot).collect();
+    (less, equal, greater)
+}
+
+/// Finds the `k`-th smallest element (0-based) without sorting, sequentially.
+/// Same answer as `sorted[k]`.
+pub fn sequential_quickselect(data: &[i32], k: usize) -> Option<i32> {
+    quickselect_with(data, k, partition_sequential)
+}
+
+/// Finds the `k`-th smallest element (0-based), partitioning large slices
+/// in parallel.
+pub fn parallel_quickselect(data: &[i32], k: usize) -> Option<i32> {
+    quickselect_with(data, k, partition_parallel)
+}
+
+// --- Composable Pipelines ---
+//
+// Real data processing is rarely one `map`. A pipeline chains stages, each
+// taking the whole `Vec<T>` and returning a new one. Every stage runs in
+// parallel internally, and we time each one so you can see where the time
+// actually goes (often it's not where you'd guess).
+
+/// One stage: takes the data, returns the transformed data.
+type Stage<T> = Box<dyn Fn(Vec<T>) -> Vec<T> + Sync + Send>;
+
+/// What happened in one stage of a pipeline run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageStat {
+    /// e.g. `"map"`, `"filter"` or `"chunked(1000)"`
+    pub name: String,
+    pub input_len: usize,
+    pub output_len: usize,
+    /// Wall-clock time for this stage.
+    pub duration: Duration,
+}
+
+/// The output of `Pipeline::execute` plus one `StageStat` per stage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineResult<T> {
+    pub output: Vec<T>,
+    pub stage_stats: Vec<StageStat>,
+}
+
+impl<T> PipelineResult<T> {
+    /// Sum of all stage durations.
+    pub fn total_duration(&self) -> Duration {
+        self.stage_stats.iter().map(|stat| stat.duration).sum()
+    }
+}
+
+/// A list of parallel stages applied in order.
+///
+/// Stages are stored as boxed closures (`Box<dyn Fn ...>`) because every
+/// closure has its own unique type; boxing lets them all live in one `Vec`.
+/// The `Sync + Send` bounds let rayon's threads share them.
+pub struct Pipeline<T> {
+    stages: Vec<(String, Stage<T>)>,
+}
+
+impl<T: Send + Sync + 'static> Pipeline<T> {
+    /// An empty pipeline, which returns its input unchanged.
+    pub fn new() -> Self {
+        Pipeline { stages: Vec::new() }
+    }
+
+    /// Adds a stage that transforms every element.
+    pub fn add_map<F>(mut self, f: F) -> Self
+    where
+        F: Fn(T) -> T + Sync + Send + 'static,
+    {
+        let stage: Stage<T> = Box::new(move |data: Vec<T>| data.into_par_iter().map(&f).collect());
+        self.stages.push(("map".to_string(), stage));
+        self
+    }
+
+    /// Adds a stage that keeps only the elements matching `pred`.
+    pub fn add_filter<P>(mut self, pred: P) -> Self
+    where
+        P: Fn(&T) -> bool + Sync + Send + 'static,
+    {
+        let stage: Stage<T> =
+            Box::new(move |data: Vec<T>| data.into_par_iter().filter(&pred).collect());
+        self.stages.push(("filter".to_string(), stage));
+        self
+    }
+
+    /// Adds a stage that hands `f` whole chunks of `chunk_size` elements
+    /// (the last chunk may be shorter). Useful when per-element work is too
+    /// small to be worth scheduling on its own. A size of 0 is treated as 1.
+    pub fn add_chunked<F>(mut self, chunk_size: usize, f: F) -> Self
+    where
+        F: Fn(&[T]) -> Vec<T> + Sync + Send + 'static,
+    {
+        let chunk_size = chunk_size.max(1);
+        let stage: Stage<T> = Box::new(move |data: Vec<T>| {
+            let chunks: Vec<Vec<T>> = data.par_chunks(chunk_size).map(&f).collect();
+            chunks.into_iter().flatten().collect()
+        });
+        self.stages
+            .push((format!("chunked({})", chunk_size), stage));
+        self
+    }
+
+    /// Number of stages.
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Runs every stage in order, timing each one.
+    pub fn execute(&self, data: Vec<T>) -> PipelineResult<T> {
+        let mut data = data;
+        let mut stage_stats = Vec::with_capacity(self.stages.len());
+
+        for (name, stage) in &self.stages {
+            let input_len = data.len();
+            let start = Instant::now();
+            data = stage(data);
+            stage_stats.push(StageStat {
+                name: name.clone(),
+                input_len,
+                output_len: data.len(),
+                duration: start.elapsed(),
+            });
+        }
+
+        PipelineResult {
+            output: data,
+            stage_stats,
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> Default for Pipeline<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// --- Word Frequency with fold/reduce ---
+//
+// Counting words in parallel with one shared `HashMap` would need a lock on
+// every insert. Instead:
+// - `fold` gives each rayon worker its OWN map to fill (no sharing at all)
+// - `reduce` merges those per-worker maps pairwise into one result
+// The number of maps is roughly the number of threads, not the number of
+// lines, so merging is cheap.
+
+/// How words are normalized before counting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordCountOptions {
+    /// Lowercase words so "The" and "the" count together.
+    pub lowercase: bool,
+    /// Skip words shorter than this many characters (after stripping).
+    pub min_length: usize,
+}
+
+impl Default for WordCountOptions {
+    fn default() -> Self {
+        WordCountOptions {
+            lowercase: true,
+            min_length: 1,
+        }
+    }
+}
+
+/// Splits a line on whitespace and normalizes each word.
+///
+/// Only leading/trailing non-alphanumeric characters are stripped, so
+/// "don't" and "naïve" survive intact while "(hello!)" becomes "hello".
+fn words<'a>(line: &'a str, options: &'a WordCountOptions) -> impl Iterator<Item = String> + 'a {
+    line.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(move |word| !word.is_empty() && word.chars().count() >= options.min_length)
+        .map(move |word| {
+            if options.lowercase {
+                word.to_lowercase()
+            } else {
+                word.to_string()
+            }
+        })
+}
+
+fn count_into(
+    mut counts: HashMap<String, usize>,
+    line: &str,
+    options: &WordCountOptions,
+) -> HashMap<String, usize> {
+    for word in words(line, options) {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Counts words line by line on a single thread.
+pub fn sequential_word_count(text: &str, options: &WordCountOptions) -> HashMap<String, usize> {
+    text.lines().fold(HashMap::new(), |counts, line| {
+        count_into(counts, line, options)
+    })
+}
+
+/// Counts words with per-thread maps (`fold`) merged at the end (`reduce`).
+pub fn parallel_word_count(text: &str, options: &WordCountOptions) -> HashMap<String, usize> {
+    text.par_lines()
+        .fold(HashMap::new, |counts, line| {
+            count_into(counts, line, options)
+        })
+        .reduce(HashMap::new, |mut merged, counts| {
+            for (word, count) in counts {
+                *merged.entry(word).or_insert(0) += count;
+            }
+            merged
+        })
+}
+
+/// The `n` most frequent words, most frequent first.
+///
+/// `HashMap` iteration order is random, so ties are broken alphabetically
+/// to make the result the same on every run.
+pub fn top_n_words(counts: &HashMap<String, usize>, n: usize) -> Vec<(String, usize)> {
+    let mut entries: Vec<(String, usize)> = counts
+        .iter()
+        .map(|(word, &count)| (word.clone(), count))
+        .collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entries.truncate(n);
+    entries
+}
+
+// --- Early Exit and Cancellation ---
+//
+// `map`/`filter`/`sum` always visit every element. Searches don't have to:
+// rayon's `find_any`, `find_first`, and `any` stop handing out new work once
+// an answer is known. The two `find` flavours differ in WHICH match wins:
+//
+// - `find_any` / `position_any`: whichever thread hits a match first. The
+//   fastest, but the answer can change from run to run.
+// - `find_first` / `position_first`: the match with the lowest index, same as
+//   a sequential `find`. Still stops early: once index `i` matches, work to
+//   the right of `i` is skipped, though work to the left must finish.
+//
+// Sometimes the *caller* wants to stop (a user hit Ctrl-C, a timeout fired).
+// Rayon has no built-in cancellation, so we share an `AtomicBool` and check
+// it between chunks of work. Checking once per chunk rather than once per
+// element keeps the atomic load off the hot path.
+
+/// How many elements a worker processes between looks at the cancel flag.
+pub const CANCEL_CHECK_INTERVAL: usize = 1024;
+
+/// How many numbers `find_primes_parallel_early_stop` tests per round.
+pub const PRIME_BLOCK_SIZE: u32 = 4096;
+
+/// The lowest-index element matching `pred`, with its index, searched in
+/// parallel. Always the same answer as a sequential `iter().position()`.
+pub fn parallel_find_first(
+    data: &[i32],
+    pred: impl Fn(&i32) -> bool + Sync,
+) -> Option<(usize, i32)> {
+    data.par_iter()
+        .enumerate()
+        .find_first(|(_, x)| pred(x))
+        .map(|(i, &x)| (i, x))
+}
+
+/// Whether any element matches `pred`, or `None` if `cancel` was set before
+/// the search could answer.
+///
+/// Workers take `CANCEL_CHECK_INTERVAL` elements at a time and check the
+/// flag before each chunk, so a cancelled search returns after at most one
+/// chunk per thread.
+pub fn parallel_any_with_cancel(
+    data: &[i32],
+    pred: impl Fn(&i32) -> bool + Sync,
+    cancel: &AtomicBool,
+) -> Option<bool> {
+    enum Stop {
+        Found,
+        Cancelled,
+    }
+
+    // Relaxed is enough: the flag carries no data, we only need to see it
+    // eventually.
+    let stopped = data
+        .par_chunks(CANCEL_CHECK_INTERVAL)
+        .find_map_any(|chunk| {
+            if cancel.load(Ordering::Relaxed) {
+                Some(Stop::Cancelled)
+            } else if chunk.iter().any(&pred) {
+                Some(Stop::Found)
+            } else {
+                None
+            }
+        });
+
+    match stopped {
+        Some(Stop::Found) => Some(true),
+        Some(Stop::Cancelled) => None,
+        None => Some(false),
+    }
+}
+
+/// The first `count` primes in `start..=end`, in ascending order (fewer if
+/// the range runs out).
+///
+/// The range is scanned in blocks of `PRIME_BLOCK_SIZE`. Each block is
+/// tested in parallel, and no further blocks are started once `count`
+/// primes are in hand, so a huge `end` costs nothing extra.
+pub fn find_primes_parallel_early_stop(start: u32, end: u32, count: usize) -> Vec<u32> {
+    // `count` is caller-controlled and may be far larger than the range (or
+    // than memory); the vector grows past one block on its own if needed.
+    let range_len = if start <= end {
+        (end - start) as usize + 1
+    } else {
+        0
+    };
+    let mut primes = Vec::with_capacity(count.min(range_len).min(PRIME_BLOCK_SIZE as usize));
+    let mut lo = start;
+    while primes.len() < count && lo <= end {
+        let hi = lo.saturating_add(PRIME_BLOCK_SIZE - 1).min(end);
+        let block: Vec<u32> = (lo..=hi).into_par_iter().filter(|&n| is_prime(n)).collect();
+        primes.extend(block);
+        if hi == end {
+            break;
+        }
+        lo = hi + 1;
+    }
+    primes.truncate(count);
+    primes
+}
+
+// --- Parallel Prefix Sums (Scan) ---
+//
+// A prefix sum looks hopelessly sequential: element `i` needs the total of
+// everything before it. The classic blocked scan splits it into two
+// parallel passes with a tiny sequential step in between:
+//
+// 1. Sum every chunk, all chunks in parallel.
+// 2. Scan those chunk totals sequentially. Entry `c` is now the total of
+//    every chunk before chunk `c`: its offset. (One number per chunk, so
+//    this is cheap.)
+// 3. Scan every chunk in parallel, starting from its offset.
+//
+// Chunks are fixed-size, not "one per thread", so the chunk boundaries --
+// and therefore the result -- never depend on the thread count.
+//
+// Overflow: running totals are kept in `i128`, which no realistic slice of
+// `i64`s can overflow, and narrowed to `i64` only when written out. The
+// plain scans narrow with `as`, which keeps the low 64 bits: exactly what
+// `wrapping_add` all the way would have produced. The checked scan narrows
+// with `try_from` and reports the first position that doesn't fit, so a
+// total that dips out of range inside a chunk but is back in range by the
+// time the real prefix gets there is never misreported.
+
+/// Elements per chunk in the blocked scans.
+pub const SCAN_CHUNK_SIZE: usize = 4096;
+
+/// A prefix sum that doesn't fit in an `i64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanOverflow {
+    /// The first position whose inclusive prefix sum overflows.
+    pub index: usize,
+}
+
+impl std::fmt::Display for ScanOverflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "prefix sum overflows i64 at index {}", self.index)
+    }
+}
+
+impl std::error::Error for ScanOverflow {}
+
+/// Inclusive prefix sums on one thread: `out[i] = data[0] + ... + data[i]`,
+/// wrapping on overflow.
+pub fn sequential_prefix_sum(data: &[i64]) -> Vec<i64> {
+    data.iter()
+        .scan(0i64, |total, &x| {
+            *total = total.wrapping_add(x);
+            Some(*total)
+        })
+        .collect()
+}
+
+/// Inclusive prefix sums with the two-pass blocked scan. Always equal to
+/// `sequential_prefix_sum`, wrapping included.
+pub fn parallel_prefix_sum(data: &[i64]) -> Vec<i64> {
+    blocked_scan(data, false)
+}
+
+/// Exclusive prefix sums: `out[i] = data[0] + ... + data[i - 1]`, so
+/// `out[0] == 0`. Wraps like `parallel_prefix_sum`.
+pub fn parallel_exclusive_prefix_sum(data: &[i64]) -> Vec<i64> {
+    blocked_scan(data, true)
+}
+
+/// Inclusive prefix sums, or the first index where the sum leaves `i64`.
+pub fn checked_parallel_prefix_sum(data: &[i64]) -> Result<Vec<i64>, ScanOverflow> {
+    let offsets = chunk_offsets(data);
+
+    let mut out = vec![0; data.len()];
+    let overflow = out
+        .par_chunks_mut(SCAN_CHUNK_SIZE)
+        .zip(data.par_chunks(SCAN_CHUNK_SIZE))
+        .zip(offsets.par_iter())
+        .enumerate()
+        .filter_map(|(c, ((out, chunk), &offset))| {
+            let mut total = offset;
+            for (i, (slot, &x)) in out.iter_mut().zip(chunk).enumerate() {
+                total += x as i128;
+                match i64::try_from(total) {
+                    Ok(sum) => *slot = sum,
+                    Err(_) => return Some(c * SCAN_CHUNK_SIZE + i),
+                }
+            }
+            None
+        })
+        // Several chunks may overflow; the lowest index is the one a
+        // sequential scan would hit first.
+        .min();
+
+    match overflow {
+        Some(index) => Err(ScanOverflow { index }),
+        None => Ok(out),
+    }
+}
+
+/// Passes 1 and 2: the total of everything before each chunk.
+fn chunk_offsets(data: &[i64]) -> Vec<i128> {
+    let totals: Vec<i128> = data
+        .par_chunks(SCAN_CHUNK_SIZE)
+        .map(|chunk| chunk.iter().map(|&x| x as i128).sum())
+        .collect();
+    totals
+        .iter()
+        .scan(0i128, |running, &chunk_total| {
+            let offset = *running;
+            *running += chunk_total;
+            Some(offset)
+        })
+        .collect()
+}
+
+/// Pass 3 for the wrapping scans.
+fn blocked_scan(data: &[i64], exclusive: bool) -> Vec<i64> {
+    let offsets = chunk_offsets(data);
+
+    let mut out = vec![0; data.len()];
+    out.par_chunks_mut(SCAN_CHUNK_SIZE)
+        .zip(data.par_chunks(SCAN_CHUNK_SIZE))
+        .zip(offsets.par_iter())
+        .for_each(|((out, chunk), &offset)| {
+            let mut total = offset;
+            for (slot, &x) in out.iter_mut().zip(chunk) {
+                if exclusive {
+                    *slot = total as i64;
+                    total += x as i128;
+                } else {
+                    total += x as i128;
+                    *slot = total as i64;
+                }
+            }
+        });
+    out
+}
@@ -4,6 +4,8 @@
 //! same results as their sequential counterparts.
 
 use parallel_processing::solution::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 #[test]
 fn test_sum_of_squares_parallel_matches_sequential() {
@@ -71,4 +73,487 @@ fn test_parallel_map_string_conversion() {
     let to_string = |x| format!("Number: {}", x);
     let result = parallel_map(&data, to_string);
     assert_eq!(result, vec!["Number: 10", "Number: 20", "Number: 30"]);
-}
\ No newline at end of file
+}
+// ============================================================================
+// DIVIDE AND CONQUER
+// ============================================================================
+
+/// Deterministic pseudo-random numbers (a simple LCG), so failures reproduce.
+fn pseudo_random(len: usize, seed: u64) -> Vec<i32> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) as i32) % 10_000 - 5_000
+        })
+        .collect()
+}
+
+fn assert_sorts_like_std(input: Vec<i32>) {
+    let mut expected = input.clone();
+    expected.sort();
+
+    let mut seq = input.clone();
+    sequential_merge_sort(&mut seq);
+    assert_eq!(seq, expected);
+
+    let mut par = input;
+    parallel_merge_sort(&mut par);
+    assert_eq!(par, expected);
+}
+
+#[test]
+fn test_merge_sort_random_matches_std_sort() {
+    for (len, seed) in [(10, 1), (1_000, 2), (1_025, 3), (50_000, 4)] {
+        assert_sorts_like_std(pseudo_random(len, seed));
+    }
+}
+
+#[test]
+fn test_merge_sort_sorted_and_reversed() {
+    let sorted: Vec<i32> = (0..5_000).collect();
+    assert_sorts_like_std(sorted.clone());
+    assert_sorts_like_std(sorted.into_iter().rev().collect());
+}
+
+#[test]
+fn test_merge_sort_duplicates() {
+    let data: Vec<i32> = (0..10_000).map(|i| i % 7).collect();
+    assert_sorts_like_std(data);
+    assert_sorts_like_std(vec![3; 2_000]);
+}
+
+#[test]
+fn test_merge_sort_empty_and_single() {
+    assert_sorts_like_std(vec![]);
+    assert_sorts_like_std(vec![42]);
+}
+
+#[test]
+fn test_quickselect_matches_sorted_index() {
+    let data = pseudo_random(20_000, 5);
+    let mut sorted = data.clone();
+    sorted.sort();
+
+    for k in [0, 1, 999, 10_000, 19_998, 19_999] {
+        assert_eq!(sequential_quickselect(&data, k), Some(sorted[k]));
+        assert_eq!(parallel_quickselect(&data, k), Some(sorted[k]));
+    }
+}
+
+#[test]
+fn test_quickselect_duplicates_and_sorted_inputs() {
+    let dups: Vec<i32> = (0..5_000).map(|i| i % 3).collect();
+    assert_eq!(parallel_quickselect(&dups, 0), Some(0));
+    assert_eq!(parallel_quickselect(&dups, 2_500), Some(1));
+    assert_eq!(parallel_quickselect(&dups, 4_999), Some(2));
+
+    let reversed: Vec<i32> = (0..3_000).rev().collect();
+    assert_eq!(parallel_quickselect(&reversed, 1_234), Some(1_234));
+    assert_eq!(sequential_quickselect(&reversed, 1_234), Some(1_234));
+}
+
+#[test]
+fn test_quickselect_out_of_range() {
+    assert_eq!(parallel_quickselect(&[], 0), None);
+    assert_eq!(sequential_quickselect(&[], 0), None);
+    assert_eq!(parallel_quickselect(&[1, 2, 3], 3), None);
+    assert_eq!(parallel_quickselect(&[7], 0), Some(7));
+}
+
+// ============================================================================
+// PIPELINES
+// ============================================================================
+
+#[test]
+fn test_pipeline_matches_sequential_chain() {
+    let data: Vec<i64> = (0..100_000).collect();
+    let pipeline = Pipeline::new()
+        .add_map(|x: i64| x * 3)
+        .add_filter(|x| x % 2 == 0)
+        .add_map(|x| x + 1);
+
+    let expected: Vec<i64> = data
+        .iter()
+        .map(|x| x * 3)
+        .filter(|x| x % 2 == 0)
+        .map(|x| x + 1)
+        .collect();
+
+    let result = pipeline.execute(data);
+    assert_eq!(result.output, expected);
+}
+
+#[test]
+fn test_pipeline_stage_stats() {
+    let pipeline = Pipeline::new()
+        .add_map(|x: i64| x * 3)
+        .add_filter(|x| x % 2 == 0)
+        .add_chunked(1_000, |chunk: &[i64]| chunk.iter().map(|x| x + 1).collect());
+    assert_eq!(pipeline.len(), 3);
+
+    let result = pipeline.execute((0..100_000).collect());
+    let stats = &result.stage_stats;
+    assert_eq!(stats.len(), 3);
+
+    let names: Vec<&str> = stats.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["map", "filter", "chunked(1000)"]);
+    assert_eq!(
+        (stats[0].input_len, stats[0].output_len),
+        (100_000, 100_000)
+    );
+    assert_eq!((stats[1].input_len, stats[1].output_len), (100_000, 50_000));
+    assert_eq!((stats[2].input_len, stats[2].output_len), (50_000, 50_000));
+    assert_eq!(
+        result.total_duration(),
+        stats.iter().map(|s| s.duration).sum()
+    );
+}
+
+#[test]
+fn test_pipeline_chunked_keeps_order_and_can_resize() {
+    // Each chunk is summed down to a single element
+    let pipeline = Pipeline::new().add_chunked(3, |chunk: &[i32]| vec![chunk.iter().sum()]);
+    let result = pipeline.execute((1..=10).collect());
+    assert_eq!(result.output, vec![6, 15, 24, 10]);
+}
+
+#[test]
+fn test_empty_pipeline_is_identity() {
+    let pipeline: Pipeline<i32> = Pipeline::new();
+    assert!(pipeline.is_empty());
+
+    let result = pipeline.execute(vec![3, 1, 2]);
+    assert_eq!(result.output, vec![3, 1, 2]);
+    assert!(result.stage_stats.is_empty());
+}
+
+// ============================================================================
+// WORD FREQUENCY
+// ============================================================================
+
+const SAMPLE_TEXT: &str = "\
+The quick brown fox jumps over the lazy dog.
+The dog sleeps; the fox runs!
+
+A second paragraph, with (parentheses) and \"quotes\".
+Don't forget: the fox is QUICK.
+
+Third paragraph repeats the the the words words.
+";
+
+#[test]
+fn test_parallel_word_count_matches_sequential() {
+    let options = WordCountOptions::default();
+    let big_text = SAMPLE_TEXT.repeat(200);
+
+    for text in [SAMPLE_TEXT, big_text.as_str(), ""] {
+        assert_eq!(
+            parallel_word_count(text, &options),
+            sequential_word_count(text, &options)
+        );
+    }
+
+    let counts = parallel_word_count(&big_text, &options);
+    assert_eq!(counts["the"], 8 * 200);
+    assert_eq!(counts["fox"], 3 * 200);
+    assert_eq!(counts["don't"], 200);
+    assert_eq!(counts["parentheses"], 200);
+}
+
+#[test]
+fn test_word_count_case_folding_option() {
+    let options = WordCountOptions {
+        lowercase: false,
+        min_length: 1,
+    };
+    let counts = parallel_word_count("The the THE", &options);
+    assert_eq!(counts.len(), 3);
+    assert_eq!(counts["The"], 1);
+
+    let counts = parallel_word_count("The the THE", &WordCountOptions::default());
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts["the"], 3);
+}
+
+#[test]
+fn test_word_count_unicode_words_intact() {
+    let counts = parallel_word_count(
+        "Ünïcödé naïve café — 東京 東京, «Ünïcödé»",
+        &WordCountOptions::default(),
+    );
+    assert_eq!(counts["ünïcödé"], 2);
+    assert_eq!(counts["naïve"], 1);
+    assert_eq!(counts["café"], 1);
+    assert_eq!(counts["東京"], 2);
+    // A lone dash is all punctuation and is dropped
+    assert_eq!(counts.len(), 4);
+}
+
+#[test]
+fn test_word_count_min_length() {
+    let options = WordCountOptions {
+        lowercase: true,
+        min_length: 4,
+    };
+    let counts = parallel_word_count("a an the then théâ there!", &options);
+    let mut words: Vec<&str> = counts.keys().map(String::as_str).collect();
+    words.sort();
+    // "théâ" is 4 chars even though it is more than 4 bytes
+    assert_eq!(words, ["then", "there", "théâ"]);
+}
+
+#[test]
+fn test_top_n_words_ties_are_alphabetical() {
+    let counts = parallel_word_count("b a c b a d c e", &WordCountOptions::default());
+    let top = top_n_words(&counts, 4);
+    assert_eq!(
+        top,
+        vec![
+            ("a".to_string(), 2),
+            ("b".to_string(), 2),
+            ("c".to_string(), 2),
+            ("d".to_string(), 1),
+        ]
+    );
+    assert_eq!(top_n_words(&counts, 100).len(), 5);
+    assert!(top_n_words(&counts, 0).is_empty());
+}
+
+// ============================================================================
+// EARLY EXIT AND CANCELLATION
+// ============================================================================
+
+/// Trial division, written independently of the solution's `is_prime`.
+fn naive_is_prime(n: u32) -> bool {
+    n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0)
+}
+
+#[test]
+fn test_parallel_find_first_returns_lowest_index() {
+    let data = pseudo_random(200_000, 11);
+    for target in [0, 7, 42, 999] {
+        let pred = |x: &i32| x.rem_euclid(1000) == target;
+        let expected = data.iter().position(pred).map(|i| (i, data[i]));
+        assert_eq!(parallel_find_first(&data, pred), expected);
+    }
+
+    // Many matches: still the first one, every time
+    let data: Vec<i32> = (0..100_000).collect();
+    for _ in 0..5 {
+        assert_eq!(parallel_find_first(&data, |&x| x % 3 == 2), Some((2, 2)));
+    }
+    assert_eq!(parallel_find_first(&data, |&x| x < 0), None);
+    assert_eq!(parallel_find_first(&[], |_| true), None);
+}
+
+#[test]
+fn test_parallel_any_with_cancel_answers_when_not_cancelled() {
+    let cancel = AtomicBool::new(false);
+    let data: Vec<i32> = (0..50_000).collect();
+    assert_eq!(
+        parallel_any_with_cancel(&data, |&x| x == 49_999, &cancel),
+        Some(true)
+    );
+    assert_eq!(
+        parallel_any_with_cancel(&data, |&x| x < 0, &cancel),
+        Some(false)
+    );
+
+    cancel.store(true, Ordering::Relaxed);
+    assert_eq!(parallel_any_with_cancel(&data, |_| true, &cancel), None);
+}
+
+#[test]
+fn test_parallel_any_with_cancel_stops_within_a_chunk_per_thread() {
+    let data = vec![0; 10_000_000];
+    let cancel = AtomicBool::new(false);
+    let calls = AtomicUsize::new(0);
+
+    // The predicate itself pulls the plug after 5000 calls
+    let result = parallel_any_with_cancel(
+        &data,
+        |_| {
+            if calls.fetch_add(1, Ordering::Relaxed) == 5_000 {
+                cancel.store(true, Ordering::Relaxed);
+            }
+            false
+        },
+        &cancel,
+    );
+
+    assert_eq!(result, None);
+    let bound = 5_000 + rayon::current_num_threads() * CANCEL_CHECK_INTERVAL * 2;
+    assert!(calls.load(Ordering::Relaxed) <= bound);
+}
+
+#[test]
+fn test_parallel_any_with_cancel_returns_promptly() {
+    // ~30µs per element: scanning everything would take well over a minute
+    let data: Vec<i32> = (0..3_000_000).map(|i| 1_000_000_007 + 2 * i).collect();
+    let slow = |&x: &i32| naive_is_prime(x as u32) && x < 0;
+    let cancel = AtomicBool::new(false);
+
+    let start = Instant::now();
+    let result = std::thread::scope(|s| {
+        s.spawn(|| {
+            std::thread::sleep(Duration::from_millis(50));
+            cancel.store(true, Ordering::Relaxed);
+        });
+        parallel_any_with_cancel(&data, slow, &cancel)
+    });
+
+    assert_eq!(result, None);
+    assert!(
+        start.elapsed() < Duration::from_secs(10),
+        "took {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_find_primes_early_stop_returns_exactly_count() {
+    let primes = find_primes_parallel_early_stop(1_000_000, u32::MAX, 25);
+    assert_eq!(primes.len(), 25);
+    assert!(primes.iter().all(|&p| naive_is_prime(p)));
+    assert!(primes.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(primes[0], 1_000_003);
+
+    // The smallest primes in the range, same as a full scan would give
+    let expected: Vec<u32> = find_primes_sequential(1_000)
+        .into_iter()
+        .filter(|&p| p >= 100)
+        .take(10)
+        .collect();
+    assert_eq!(find_primes_parallel_early_stop(100, 1_000, 10), expected);
+}
+
+#[test]
+fn test_find_primes_early_stop_short_ranges() {
+    assert_eq!(
+        find_primes_parallel_early_stop(0, 10, 100),
+        vec![2, 3, 5, 7]
+    );
+    assert_eq!(
+        find_primes_parallel_early_stop(24, 28, 3),
+        Vec::<u32>::new()
+    );
+    assert_eq!(
+        find_primes_parallel_early_stop(2, 1_000, 0),
+        Vec::<u32>::new()
+    );
+    assert_eq!(find_primes_parallel_early_stop(10, 2, 5), Vec::<u32>::new());
+    // A huge count must not be used as the allocation size
+    assert_eq!(
+        find_primes_parallel_early_stop(2, 10, usize::MAX),
+        vec![2, 3, 5, 7]
+    );
+    assert_eq!(
+        find_primes_parallel_early_stop(u32::MAX - 10, u32::MAX, 5),
+        vec![4_294_967_291]
+    );
+}
+
+// ============================================================================
+// PARALLEL PREFIX SUMS
+// ============================================================================
+
+fn sequential_exclusive(data: &[i64]) -> Vec<i64> {
+    let mut out = vec![0];
+    out.extend(sequential_prefix_sum(data));
+    out.pop();
+    out
+}
+
+#[test]
+fn test_prefix_sum_matches_sequential_on_awkward_lengths() {
+    let lengths = [
+        1,
+        7,
+        SCAN_CHUNK_SIZE - 1,
+        SCAN_CHUNK_SIZE,
+        SCAN_CHUNK_SIZE + 1,
+        3 * SCAN_CHUNK_SIZE + 17,
+        100_003,
+    ];
+    for (seed, len) in lengths.into_iter().enumerate() {
+        let data: Vec<i64> = pseudo_random(len, seed as u64)
+            .into_iter()
+            .map(i64::from)
+            .collect();
+        let expected = sequential_prefix_sum(&data);
+        assert_eq!(parallel_prefix_sum(&data), expected, "len {}", len);
+        assert_eq!(
+            checked_parallel_prefix_sum(&data),
+            Ok(expected),
+            "len {}",
+            len
+        );
+        assert_eq!(
+            parallel_exclusive_prefix_sum(&data),
+            sequential_exclusive(&data),
+            "len {}",
+            len
+        );
+    }
+}
+
+#[test]
+fn test_prefix_sum_empty_and_single() {
+    assert_eq!(sequential_prefix_sum(&[]), Vec::<i64>::new());
+    assert_eq!(parallel_prefix_sum(&[]), Vec::<i64>::new());
+    assert_eq!(parallel_exclusive_prefix_sum(&[]), Vec::<i64>::new());
+    assert_eq!(checked_parallel_prefix_sum(&[]), Ok(vec![]));
+
+    assert_eq!(parallel_prefix_sum(&[-42]), vec![-42]);
+    assert_eq!(parallel_exclusive_prefix_sum(&[-42]), vec![0]);
+    assert_eq!(checked_parallel_prefix_sum(&[-42]), Ok(vec![-42]));
+}
+
+#[test]
+fn test_prefix_sum_wraps_like_sequential() {
+    let mut data = vec![1; 2 * SCAN_CHUNK_SIZE + 5];
+    data[SCAN_CHUNK_SIZE + 3] = i64::MAX;
+    let expected = sequential_prefix_sum(&data);
+    assert!(expected.iter().any(|&x| x < 0), "the sum should wrap");
+    assert_eq!(parallel_prefix_sum(&data), expected);
+}
+
+#[test]
+fn test_checked_prefix_sum_reports_first_overflow() {
+    assert_eq!(
+        checked_parallel_prefix_sum(&[i64::MAX, 1, -5]),
+        Err(ScanOverflow { index: 1 })
+    );
+    assert_eq!(
+        checked_parallel_prefix_sum(&[i64::MIN, -1]),
+        Err(ScanOverflow { index: 1 })
+    );
+
+    // Overflow in a later chunk, with a second one further on.
+    let mut data = vec![0; 3 * SCAN_CHUNK_SIZE];
+    data[SCAN_CHUNK_SIZE + 2] = i64::MAX;
+    data[SCAN_CHUNK_SIZE + 9] = 1;
+    data[2 * SCAN_CHUNK_SIZE + 4] = i64::MAX;
+    let err = checked_parallel_prefix_sum(&data).unwrap_err();
+    assert_eq!(err.index, SCAN_CHUNK_SIZE + 9);
+    assert_eq!(
+        err.to_string(),
+        format!("prefix sum overflows i64 at index {}", SCAN_CHUNK_SIZE + 9)
+    );
+}
+
+#[test]
+fn test_checked_prefix_sum_uses_true_prefixes_not_chunk_totals() {
+    // On its own, chunk 1 runs past i64::MAX; after chunk 0's -10 it never
+    // does.
+    let mut data = vec![0; 2 * SCAN_CHUNK_SIZE];
+    data[0] = -10;
+    data[SCAN_CHUNK_SIZE] = i64::MAX;
+    data[SCAN_CHUNK_SIZE + 1] = 5;
+    let sums = checked_parallel_prefix_sum(&data).unwrap();
+    assert_eq!(sums[SCAN_CHUNK_SIZE + 1], i64::MAX - 5);
+    assert_eq!(sums, sequential_prefix_sum(&data));
+}
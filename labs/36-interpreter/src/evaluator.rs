@@ -4,6 +4,7 @@
 //! by the parser and compute the final value of the expression.
 
 use crate::parser::{BinaryOp, Expr};
+use std::collections::HashMap;
 use thiserror::Error;
 
 // TODO: Define EvalError enum
@@ -15,6 +16,46 @@ use thiserror::Error;
 pub enum EvalError {
     #[error("Division by zero")]
     DivisionByZero,
+    #[error("Unknown variable: {0}")]
+    UnknownVariable(String),
+    #[error("{resource} budget exceeded: used {used} of {limit}")]
+    BudgetExceeded {
+        resource: Resource,
+        used: u64,
+        limit: u64,
+    },
+}
+
+/// Which limit a budgeted evaluation ran into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Ops,
+    Depth,
+}
+
+impl std::fmt::Display for Resource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Resource::Ops => write!(f, "op"),
+            Resource::Depth => write!(f, "depth"),
+        }
+    }
+}
+
+/// Upper bounds for a single budgeted evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionBudget {
+    /// Maximum number of AST nodes evaluated.
+    pub max_ops: u64,
+    /// Maximum nesting depth; the root node is depth 1.
+    pub max_call_depth: u64,
+}
+
+/// What a budgeted evaluation actually consumed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    pub ops: u64,
+    pub peak_depth: u64,
 }
 
 
@@ -43,3 +84,35 @@ pub fn evaluate(expr: &Expr) -> Result<f64, EvalError> {
     //   2. Negate the result.
     todo!("Implement the recursive evaluator");
 }
+
+/// Evaluates `expr`, looking variables up in `env`.
+pub fn evaluate_with_env(expr: &Expr, env: &HashMap<String, f64>) -> Result<f64, EvalError> {
+    // TODO: The same walk as `evaluate`, plus one more case:
+    // - `Expr::Variable(name)`: look `name` up in `env`, returning
+    //   `EvalError::UnknownVariable(name)` if it isn't bound.
+    // Once this works, `evaluate` can just call it with an empty map.
+    let _ = (expr, env);
+    todo!("Implement evaluation with an environment");
+}
+
+/// Evaluates like `evaluate_with_env`, but within `budget`, also returning
+/// the resources used.
+pub fn evaluate_budgeted(
+    expr: &Expr,
+    env: &HashMap<String, f64>,
+    budget: ExecutionBudget,
+) -> Result<(f64, ResourceUsage), EvalError> {
+    // TODO: Write a second recursive walk that carries a small "meter"
+    // (the budget plus a `ResourceUsage`). Leave `evaluate` alone so the
+    // normal path pays nothing for the bookkeeping.
+    //
+    // - Every node visited costs one op. Before doing any work on a node,
+    //   return `BudgetExceeded { resource: Resource::Ops, .. }` if the ops
+    //   are already used up.
+    // - The root is depth 1 and each child is one deeper. Entering a node
+    //   deeper than `max_call_depth` is `BudgetExceeded { resource: Resource::Depth, .. }`.
+    // - Variables are looked up in `env`, just like `evaluate_with_env`.
+    // - Track the deepest level reached in `peak_depth`.
+    let _ = (expr, env, budget);
+    todo!("Implement the budgeted evaluator");
+}
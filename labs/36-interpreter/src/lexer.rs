@@ -18,6 +18,7 @@ use thiserror::Error;
 //     Divide,
 //     LeftParen,
 //     RightParen,
+//     Identifier(String),
 // }
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
@@ -28,6 +29,7 @@ pub enum Token {
     Divide,
     LeftParen,
     RightParen,
+    Identifier(String),
 }
 
 // TODO: Define LexerError enum
@@ -44,6 +46,23 @@ pub enum LexerError {
     InvalidNumber(String),
 }
 
+/// Byte range `start..end` of a token in the source text.
+pub type Span = (usize, usize);
+
+/// A value together with where it came from in the source.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // TODO: Print the token as it appears in source: `+`, `(`, `42`, `x`.
+        let _ = f;
+        todo!("Display a token");
+    }
+}
 
 /// Takes a string and converts it into a sequence of tokens.
 pub fn tokenize(input: &str) -> Result<Vec<Token>, LexerError> {
@@ -56,7 +75,18 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, LexerError> {
     //      and push a `Token::Number`.
     //    - If it's an operator (`+`, `-`, `*`, `/`), push the corresponding token.
     //    - If it's a parenthesis, push the corresponding token.
+    //    - If it's a letter or `_`, collect letters, digits, and underscores
+    //      and push a `Token::Identifier` (e.g. `_1`, `x`).
     //    - If it's any other character, return a `LexerError::UnexpectedCharacter`.
     // 4. Return the `Vec<Token>`.
     todo!("Implement the tokenizer");
 }
+
+/// Like `tokenize`, but records each token's byte span.
+pub fn tokenize_spanned(input: &str) -> Result<Vec<Spanned<Token>>, LexerError> {
+    // TODO: Walk `input.char_indices()` instead of `chars()`. A token starts
+    // at the index of its first character and ends where the next unread
+    // character begins (or at `input.len()`).
+    let _ = input;
+    todo!("Implement the spanned tokenizer");
+}
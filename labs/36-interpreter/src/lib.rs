@@ -39,7 +39,7 @@ pub mod parser;
 use thiserror::Error;
 use lexer::{LexerError, tokenize};
 use parser::{ParseError, parse};
-use evaluator::{EvalError, evaluate};
+use evaluator::{EvalError, ExecutionBudget, ResourceUsage, evaluate, evaluate_budgeted};
 
 /// A top-level error type that encapsulates all possible failures.
 #[derive(Debug, Error, PartialEq)]
@@ -52,6 +52,16 @@ pub enum InterpreterError {
     Evaluator(#[from] EvalError),
 }
 
+impl InterpreterError {
+    /// Like `to_string`, but parse errors also show where in `source` they
+    /// happened (see `ParseError::render`).
+    pub fn render(&self, source: &str) -> String {
+        // TODO: Use `ParseError::render` for parser errors.
+        let _ = source;
+        todo!("Render an interpreter error");
+    }
+}
+
 /// Interprets a mathematical expression from a string.
 ///
 /// This function orchestrates the three main phases:
@@ -60,13 +70,139 @@ pub enum InterpreterError {
 /// 3. Evaluating
 pub fn interpret(input: &str) -> Result<f64, InterpreterError> {
     // TODO: Implement the interpretation pipeline.
-    // 1. Call `tokenize()` from the `lexer` module. Use `?` to propagate errors.
-    // 2. Call `parse()` from the `parser` module. Use `?` to propagate errors.
+    // 1. Call `tokenize_spanned()` from the `lexer` module. Use `?` to propagate errors.
+    // 2. Call `parse_spanned()` from the `parser` module so parse errors carry spans.
     // 3. Call `evaluate()` from the `evaluator` module. Use `?` to propagate errors.
     // 4. Return the final result.
     todo!("Call tokenize, parse, and evaluate in sequence");
 }
 
+/// Like `interpret`, but evaluates within `budget` and reports the
+/// resources used.
+pub fn interpret_budgeted(
+    input: &str,
+    budget: ExecutionBudget,
+) -> Result<(f64, ResourceUsage), InterpreterError> {
+    // TODO: Same pipeline as `interpret`, but finish with `evaluate_budgeted()`
+    // and an empty environment.
+    todo!("Tokenize, parse, and evaluate within a budget");
+}
+
+/// What `Session::eval_line` produced.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalOutput {
+    Value { name: String, value: f64 },
+    Vars(Vec<(String, f64)>),
+    Reset,
+    Empty,
+}
+
+/// REPL state: the results of earlier lines, bound to `_1`, `_2`, ...
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    env: std::collections::HashMap<String, f64>,
+    names: Vec<String>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluates one line: `:vars`, `:reset`, or an expression whose value
+    /// is bound to the next `_N` name.
+    pub fn eval_line(&mut self, line: &str) -> Result<EvalOutput, String> {
+        // TODO:
+        // - Blank line -> `EvalOutput::Empty`.
+        // - `:vars` -> bindings in insertion order; `:reset` -> clear them.
+        // - Otherwise tokenize, parse, and `evaluator::evaluate_with_env`
+        //   against `self.env`. Only bind `_{n}` if evaluation succeeds, so
+        //   a failing line doesn't use up a number.
+        let _ = (&self.env, &self.names, line);
+        todo!("Evaluate a REPL line");
+    }
+
+    pub fn vars(&self) -> Vec<(String, f64)> {
+        todo!("List bindings in insertion order");
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        let _ = name;
+        todo!("Look up a binding");
+    }
+}
+
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// An `interpret` wrapper with `ans`, a memory register, and a history.
+#[derive(Debug, Clone)]
+pub struct Calculator {
+    last: Option<f64>,
+    memory: f64,
+    history: Vec<(String, f64)>,
+    history_limit: usize,
+}
+
+impl Calculator {
+    pub fn new() -> Self {
+        Self::with_history_limit(DEFAULT_HISTORY_LIMIT)
+    }
+
+    pub fn with_history_limit(limit: usize) -> Self {
+        Calculator {
+            last: None,
+            memory: 0.0,
+            history: Vec::new(),
+            history_limit: limit,
+        }
+    }
+
+    /// Evaluates an expression that may use `ans` and `mem`.
+    pub fn eval(&mut self, expression: &str) -> Result<f64, InterpreterError> {
+        // TODO:
+        // - Tokenize and parse as usual, then `evaluator::evaluate_with_env`
+        //   with an environment holding `mem` and (once set) `ans`.
+        // - Only on success: update `last`, push to `history`, and drop the
+        //   oldest entries beyond `history_limit`.
+        let _ = (
+            &self.last,
+            &self.memory,
+            &self.history,
+            self.history_limit,
+            expression,
+        );
+        todo!("Evaluate with ans and mem");
+    }
+
+    pub fn ans(&self) -> Option<f64> {
+        todo!("Return the last result");
+    }
+
+    /// Adds `value` (or the last result when `None`) to memory.
+    pub fn memory_add(&mut self, value: Option<f64>) -> f64 {
+        let _ = value;
+        todo!("Add to memory and return it");
+    }
+
+    pub fn memory_recall(&self) -> f64 {
+        todo!("Return memory");
+    }
+
+    pub fn memory_clear(&mut self) {
+        todo!("Reset memory to 0");
+    }
+
+    pub fn history(&self) -> &[(String, f64)] {
+        todo!("Return the history, oldest first");
+    }
+}
+
+impl Default for Calculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // Re-export the solution module for comparison.
 // Note: In this project, the solution is structured into submodules as well.
 #[doc(hidden)]
@@ -5,7 +5,7 @@
 //! of the program. Our parser will handle operator precedence (multiplication
 //! before addition) and parentheses.
 
-use crate::lexer::Token;
+use crate::lexer::{Span, Spanned, Token};
 use thiserror::Error;
 
 // TODO: Define the AST nodes.
@@ -51,25 +51,36 @@ pub enum Expr {
     },
     Grouping(Box<Expr>),
     UnaryMinus(Box<Expr>),
+    Variable(String),
 }
 
-// TODO: Define ParseError enum
-// For errors like "Unexpected token" or "Unexpected end of input".
-//
-// #[derive(Debug, Error, PartialEq)]
-// pub enum ParseError { ... }
-#[derive(Debug, Error, PartialEq)]
-pub enum ParseError {
-    #[error("Unexpected end of input")]
-    UnexpectedEof,
-    #[error("Unexpected token")]
-    UnexpectedToken,
+// TODO: Define ParseError
+// It carries a human-readable message and, when known, the source span the
+// error is about, e.g. "unclosed '('" pointing at the open paren.
+#[derive(Debug, Error, PartialEq, Clone)]
+#[error("{message}")]
+pub struct ParseError {
+    pub message: String,
+    pub span: Option<Span>,
 }
 
+impl ParseError {
+    /// Formats the message with the source line and a caret under the span.
+    pub fn render(&self, source: &str) -> String {
+        // TODO: Without a span, return the message. Otherwise find the line
+        // containing `span.0` and print:
+        //   "<message> at line L, column C"
+        //   "<the source line>"
+        //   "<padding>^^^"  (one caret per character in the span, at least one)
+        let _ = source;
+        todo!("Render a parse error with a caret");
+    }
+}
 
 /// A parser that builds an AST from a vector of tokens.
 pub struct Parser {
     tokens: Vec<Token>,
+    spans: Vec<Span>,
     pos: usize,
 }
 
@@ -82,7 +93,7 @@ pub fn parse(tokens: Vec<Token>) -> Result<Expr, ParseError> {
     // Grammar:
     // expression -> term ( ( "+" | "-" ) term )*
     // term       -> factor ( ( "*" | "/" ) factor )*
-    // factor     -> NUMBER | "(" expression ")" | "-" factor
+    // factor     -> NUMBER | IDENTIFIER | "(" expression ")" | "-" factor
     //
     // 1. Create a `Parser` struct to hold the token stream and current position.
     // 2. Implement a method for each grammar rule (e.g., `parse_expression`,
@@ -94,3 +105,12 @@ pub fn parse(tokens: Vec<Token>) -> Result<Expr, ParseError> {
     //    navigate the token stream.
     todo!("Implement the recursive-descent parser");
 }
+
+/// Parses tokens from `tokenize_spanned` so errors can point into the source.
+pub fn parse_spanned(tokens: Vec<Spanned<Token>>) -> Result<Expr, ParseError> {
+    // TODO: Keep the spans next to the tokens. Report "unclosed '('" at the
+    // open paren, "expected an expression after '+'" at a dangling operator,
+    // and "unexpected ')' after a complete expression" at leftover tokens.
+    let _ = tokens;
+    todo!("Implement the span-aware parser");
+}
@@ -19,6 +19,7 @@
 //! 1. **Lexing**: `tokenize` iterates `chars()` and builds tokens. Numbers are parsed by collecting digits into a `String` (heap) before converting to `f64`. The result `Vec<Token>` owns its contents on the heap.
 //! 2. **Parsing**: `Parser` owns the token vector. Each recursive call consumes tokens (`advance()`), moving ownership while those tokens live on the heap.
 //! 3. **Evaluation**: AST nodes are borrowed (`&Expr`) to compute results. Binary operations evaluate both sides recursively and combine them, demonstrating how `Box` stores heap nodes and the stack tracks recursive evaluation state.
+//! 4. **Error locations**: `tokenize_spanned` records each token's byte range (`Span`) as it goes. The parser keeps those spans in a vector parallel to the tokens, so a `ParseError` can name the exact range and `render` can draw a caret under it.
 
 use thiserror::Error;
 
@@ -36,6 +37,7 @@ pub mod lexer {
         Divide,
         LeftParen,
         RightParen,
+        Identifier(String),
     }
 
     #[derive(Debug, Error, PartialEq)]
@@ -46,44 +48,79 @@ pub mod lexer {
         InvalidNumber(String),
     }
 
+    impl std::fmt::Display for Token {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Token::Number(n) => write!(f, "{}", n),
+                Token::Plus => write!(f, "+"),
+                Token::Minus => write!(f, "-"),
+                Token::Multiply => write!(f, "*"),
+                Token::Divide => write!(f, "/"),
+                Token::LeftParen => write!(f, "("),
+                Token::RightParen => write!(f, ")"),
+                Token::Identifier(name) => write!(f, "{}", name),
+            }
+        }
+    }
+
+    /// Byte range `start..end` of a token in the source text.
+    pub type Span = (usize, usize);
+
+    /// A value together with where it came from in the source.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Spanned<T> {
+        pub node: T,
+        pub span: Span,
+    }
+
     pub fn tokenize(input: &str) -> Result<Vec<Token>, LexerError> {
+        Ok(tokenize_spanned(input)?
+            .into_iter()
+            .map(|token| token.node)
+            .collect())
+    }
+
+    /// Like `tokenize`, but records the byte span of every token so later
+    /// stages can point at the exact spot in the source.
+    pub fn tokenize_spanned(input: &str) -> Result<Vec<Spanned<Token>>, LexerError> {
         let mut tokens = Vec::new();
-        let mut chars = input.chars().peekable();
+        let mut chars = input.char_indices().peekable();
 
-        while let Some(&ch) = chars.peek() {
-            match ch {
+        while let Some(&(start, ch)) = chars.peek() {
+            let token = match ch {
                 ' ' | '\t' | '\n' | '\r' => {
                     chars.next();
+                    continue;
                 }
                 '+' => {
-                    tokens.push(Token::Plus);
                     chars.next();
+                    Token::Plus
                 }
                 '-' => {
-                    tokens.push(Token::Minus);
                     chars.next();
+                    Token::Minus
                 }
                 '*' => {
-                    tokens.push(Token::Multiply);
                     chars.next();
+                    Token::Multiply
                 }
                 '/' => {
-                    tokens.push(Token::Divide);
                     chars.next();
+                    Token::Divide
                 }
                 '(' => {
-                    tokens.push(Token::LeftParen);
                     chars.next();
+                    Token::LeftParen
                 }
                 ')' => {
-                    tokens.push(Token::RightParen);
                     chars.next();
+                    Token::RightParen
                 }
                 '0'..='9' | '.' => {
                     let mut number = String::new();
                     let mut dot_count = 0_usize;
 
-                    while let Some(&next) = chars.peek() {
+                    while let Some(&(_, next)) = chars.peek() {
                         if next.is_ascii_digit() {
                             number.push(next);
                             chars.next();
@@ -106,12 +143,31 @@ pub mod lexer {
                     let parsed = number
                         .parse::<f64>()
                         .map_err(|_| LexerError::InvalidNumber(number.clone()))?;
-                    tokens.push(Token::Number(parsed));
+                    Token::Number(parsed)
+                }
+                c if c.is_ascii_alphabetic() || c == '_' => {
+                    let mut name = String::new();
+                    while let Some(&(_, next)) = chars.peek() {
+                        if next.is_ascii_alphanumeric() || next == '_' {
+                            name.push(next);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    Token::Identifier(name)
                 }
                 other => {
                     return Err(LexerError::UnexpectedCharacter(other));
                 }
-            }
+            };
+
+            // The token ends where the next unread character begins.
+            let end = chars.peek().map_or(input.len(), |&(i, _)| i);
+            tokens.push(Spanned {
+                node: token,
+                span: (start, end),
+            });
         }
 
         Ok(tokens)
@@ -121,7 +177,7 @@ pub mod lexer {
 pub mod parser {
     //! Recursive-descent parser with precedence handling.
 
-    use crate::solution::lexer::Token;
+    use crate::solution::lexer::{Span, Spanned, Token};
     use thiserror::Error;
 
     #[derive(Debug, PartialEq, Clone, Copy)]
@@ -142,26 +198,87 @@ pub mod parser {
         },
         Grouping(Box<Expr>),
         UnaryMinus(Box<Expr>),
+        Variable(String),
     }
 
-    #[derive(Debug, Error, PartialEq)]
-    pub enum ParseError {
-        #[error("Unexpected end of input")]
-        UnexpectedEndOfInput,
-        #[error("Unexpected token")]
-        UnexpectedToken,
-        #[error("Expected right parenthesis")]
-        ExpectedRightParen,
+    /// A parse failure, with the source range it is about when known.
+    ///
+    /// `span` is `None` when the parser was given bare tokens (see `parse`)
+    /// or when there is nothing to point at, as with empty input.
+    #[derive(Debug, Error, PartialEq, Clone)]
+    #[error("{message}")]
+    pub struct ParseError {
+        pub message: String,
+        pub span: Option<Span>,
+    }
+
+    impl ParseError {
+        fn new(message: impl Into<String>, span: Option<Span>) -> Self {
+            ParseError {
+                message: message.into(),
+                span,
+            }
+        }
+
+        /// Formats the error with the offending source line and a caret
+        /// under the span:
+        ///
+        /// ```text
+        /// unclosed '(' at line 1, column 3
+        /// 2 * (1 + 2
+        ///     ^
+        /// ```
+        ///
+        /// Without a usable span this is just the message.
+        pub fn render(&self, source: &str) -> String {
+            let Some((start, end)) = self.span else {
+                return self.message.clone();
+            };
+            if start > source.len() || !source.is_char_boundary(start) {
+                return self.message.clone();
+            }
+
+            let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = source[start..]
+                .find('\n')
+                .map_or(source.len(), |i| start + i);
+            let caret_end = end.clamp(start, line_end);
+            let width = source
+                .get(start..caret_end)
+                .map_or(1, |s| s.chars().count())
+                .max(1);
+            // Keep tabs so the caret lines up however the terminal renders them.
+            let padding: String = source[line_start..start]
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+
+            format!(
+                "{} at line {}, column {}\n{}\n{}{}",
+                self.message,
+                source[..line_start].matches('\n').count() + 1,
+                source[line_start..start].chars().count() + 1,
+                &source[line_start..line_end],
+                padding,
+                "^".repeat(width)
+            )
+        }
     }
 
     pub struct Parser {
         tokens: Vec<Token>,
+        // Parallel to `tokens`; empty when parsing bare tokens.
+        spans: Vec<Span>,
         pos: usize,
     }
 
     impl Parser {
-        fn new(tokens: Vec<Token>) -> Self {
-            Self { tokens, pos: 0 }
+        fn new(tokens: Vec<Token>, spans: Vec<Span>) -> Self {
+            Self {
+                tokens,
+                spans,
+                pos: 0,
+            }
         }
 
         fn is_at_end(&self) -> bool {
@@ -180,6 +297,34 @@ pub mod parser {
             tok
         }
 
+        fn span_at(&self, pos: usize) -> Option<Span> {
+            self.spans.get(pos).copied()
+        }
+
+        /// Error for a token that cannot appear where it was found.
+        fn unexpected(&self, pos: usize, context: &str) -> ParseError {
+            let found = &self.tokens[pos];
+            ParseError::new(
+                format!("unexpected '{}'{}", found, context),
+                self.span_at(pos),
+            )
+        }
+
+        /// The whole token stream must be exactly one expression.
+        fn parse_all(mut self) -> Result<Expr, ParseError> {
+            if self.is_at_end() {
+                return Err(ParseError::new("empty expression", None));
+            }
+
+            let expr = self.parse_expression()?;
+
+            if self.is_at_end() {
+                Ok(expr)
+            } else {
+                Err(self.unexpected(self.pos, " after a complete expression"))
+            }
+        }
+
         fn parse_expression(&mut self) -> Result<Expr, ParseError> {
             let mut expr = self.parse_term()?;
 
@@ -223,39 +368,45 @@ pub mod parser {
         }
 
         fn parse_factor(&mut self) -> Result<Expr, ParseError> {
+            let pos = self.pos;
             match self.advance() {
                 Some(Token::Number(n)) => Ok(Expr::Literal(n)),
+                Some(Token::Identifier(name)) => Ok(Expr::Variable(name)),
                 Some(Token::Minus) => {
                     let inner = self.parse_factor()?;
                     Ok(Expr::UnaryMinus(Box::new(inner)))
                 }
                 Some(Token::LeftParen) => {
                     let expr = self.parse_expression()?;
+                    let close = self.pos;
                     match self.advance() {
                         Some(Token::RightParen) => Ok(Expr::Grouping(Box::new(expr))),
-                        _ => Err(ParseError::ExpectedRightParen),
+                        Some(_) => Err(self.unexpected(close, ", expected ')'")),
+                        // Point at the '(' that never got closed.
+                        None => Err(ParseError::new("unclosed '('", self.span_at(pos))),
                     }
                 }
-                Some(_) => Err(ParseError::UnexpectedToken),
-                None => Err(ParseError::UnexpectedEndOfInput),
+                Some(_) => Err(self.unexpected(pos, "")),
+                // `parse_all` rejects empty input, so running out here means
+                // the previous token (an operator or '(') lacks its operand.
+                None => Err(ParseError::new(
+                    format!("expected an expression after '{}'", self.tokens[pos - 1]),
+                    self.span_at(pos - 1),
+                )),
             }
         }
     }
 
+    /// Parses bare tokens. Errors carry no span; use `parse_spanned` with
+    /// the output of `tokenize_spanned` to get one.
     pub fn parse(tokens: Vec<Token>) -> Result<Expr, ParseError> {
-        let mut parser = Parser::new(tokens);
-
-        if parser.is_at_end() {
-            return Err(ParseError::UnexpectedEndOfInput);
-        }
-
-        let expr = parser.parse_expression()?;
+        Parser::new(tokens, Vec::new()).parse_all()
+    }
 
-        if parser.is_at_end() {
-            Ok(expr)
-        } else {
-            Err(ParseError::UnexpectedToken)
-        }
+    /// Parses tokens from `tokenize_spanned`; errors point into the source.
+    pub fn parse_spanned(tokens: Vec<Spanned<Token>>) -> Result<Expr, ParseError> {
+        let (tokens, spans) = tokens.into_iter().map(|t| (t.node, t.span)).unzip();
+        Parser::new(tokens, spans).parse_all()
     }
 }
 
@@ -263,22 +414,179 @@ pub mod evaluator {
     //! Evaluator: recursively computes expression values from the AST.
 
     use crate::solution::parser::{BinaryOp, Expr};
+    use std::collections::HashMap;
     use thiserror::Error;
 
     #[derive(Debug, Error, PartialEq)]
     pub enum EvalError {
         #[error("Division by zero")]
         DivisionByZero,
+        #[error("Unknown variable: {0}")]
+        UnknownVariable(String),
+        #[error("{resource} budget exceeded: used {used} of {limit}")]
+        BudgetExceeded {
+            resource: Resource,
+            used: u64,
+            limit: u64,
+        },
     }
 
     pub fn evaluate(expr: &Expr) -> Result<f64, EvalError> {
+        // `HashMap::new` doesn't allocate, so this costs nothing extra.
+        evaluate_with_env(expr, &HashMap::new())
+    }
+
+    /// Evaluates `expr`, looking variables up in `env`.
+    pub fn evaluate_with_env(expr: &Expr, env: &HashMap<String, f64>) -> Result<f64, EvalError> {
         match expr {
             Expr::Literal(n) => Ok(*n),
-            Expr::Grouping(inner) => evaluate(inner),
-            Expr::UnaryMinus(inner) => Ok(-evaluate(inner)?),
+            Expr::Variable(name) => env
+                .get(name)
+                .copied()
+                .ok_or_else(|| EvalError::UnknownVariable(name.clone())),
+            Expr::Grouping(inner) => evaluate_with_env(inner, env),
+            Expr::UnaryMinus(inner) => Ok(-evaluate_with_env(inner, env)?),
             Expr::Binary { op, left, right } => {
-                let l = evaluate(left)?;
-                let r = evaluate(right)?;
+                let l = evaluate_with_env(left, env)?;
+                let r = evaluate_with_env(right, env)?;
+                match op {
+                    BinaryOp::Add => Ok(l + r),
+                    BinaryOp::Subtract => Ok(l - r),
+                    BinaryOp::Multiply => Ok(l * r),
+                    BinaryOp::Divide => {
+                        if r == 0.0 {
+                            Err(EvalError::DivisionByZero)
+                        } else {
+                            Ok(l / r)
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Budgeted evaluation
+    // ------------------------------------------------------------------------
+    // When the interpreter runs untrusted input (say, formulas typed into a
+    // web form), a depth limit alone isn't enough: the caller also wants to
+    // cap the total work and meter what each evaluation cost.
+    //
+    // `evaluate` above stays untouched, so the common path pays nothing.
+    // `evaluate_budgeted` is a separate walk that carries a `Meter`: every
+    // AST node visited costs one op, and entering a child node goes one
+    // level deeper. The environment is only read, never assigned to, and
+    // the language has no strings, so there is nothing to roll back and no
+    // string bytes to charge. Stopping halfway through leaves nothing behind
+    // except the error.
+
+    /// Which limit an evaluation ran into.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Resource {
+        Ops,
+        Depth,
+    }
+
+    impl std::fmt::Display for Resource {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Resource::Ops => write!(f, "op"),
+                Resource::Depth => write!(f, "depth"),
+            }
+        }
+    }
+
+    /// Upper bounds for a single budgeted evaluation.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ExecutionBudget {
+        /// Maximum number of AST nodes evaluated.
+        pub max_ops: u64,
+        /// Maximum nesting depth; the root node is depth 1.
+        pub max_call_depth: u64,
+    }
+
+    impl ExecutionBudget {
+        /// A budget that never runs out; useful for metering without capping.
+        pub fn unlimited() -> Self {
+            ExecutionBudget {
+                max_ops: u64::MAX,
+                max_call_depth: u64::MAX,
+            }
+        }
+    }
+
+    /// What a budgeted evaluation actually consumed.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct ResourceUsage {
+        pub ops: u64,
+        pub peak_depth: u64,
+    }
+
+    struct Meter {
+        budget: ExecutionBudget,
+        usage: ResourceUsage,
+    }
+
+    impl Meter {
+        /// Charges one op for a node at `depth`, failing before any work is
+        /// done on it if either limit would be crossed.
+        fn charge(&mut self, depth: u64) -> Result<(), EvalError> {
+            if depth > self.budget.max_call_depth {
+                return Err(EvalError::BudgetExceeded {
+                    resource: Resource::Depth,
+                    used: depth,
+                    limit: self.budget.max_call_depth,
+                });
+            }
+            if self.usage.ops >= self.budget.max_ops {
+                return Err(EvalError::BudgetExceeded {
+                    resource: Resource::Ops,
+                    used: self.usage.ops,
+                    limit: self.budget.max_ops,
+                });
+            }
+            self.usage.ops += 1;
+            self.usage.peak_depth = self.usage.peak_depth.max(depth);
+            Ok(())
+        }
+    }
+
+    /// Evaluates `expr` like `evaluate_with_env`, but within `budget`,
+    /// returning the result together with the resources it used.
+    ///
+    /// Each AST node costs one op, so `1 + 2` costs 3 (the `Binary` node and
+    /// two literals) and reaches depth 2. A variable lookup is one op.
+    pub fn evaluate_budgeted(
+        expr: &Expr,
+        env: &HashMap<String, f64>,
+        budget: ExecutionBudget,
+    ) -> Result<(f64, ResourceUsage), EvalError> {
+        let mut meter = Meter {
+            budget,
+            usage: ResourceUsage::default(),
+        };
+        let value = eval_metered(expr, env, 1, &mut meter)?;
+        Ok((value, meter.usage))
+    }
+
+    fn eval_metered(
+        expr: &Expr,
+        env: &HashMap<String, f64>,
+        depth: u64,
+        meter: &mut Meter,
+    ) -> Result<f64, EvalError> {
+        meter.charge(depth)?;
+        match expr {
+            Expr::Literal(n) => Ok(*n),
+            Expr::Variable(name) => env
+                .get(name)
+                .copied()
+                .ok_or_else(|| EvalError::UnknownVariable(name.clone())),
+            Expr::Grouping(inner) => eval_metered(inner, env, depth + 1, meter),
+            Expr::UnaryMinus(inner) => Ok(-eval_metered(inner, env, depth + 1, meter)?),
+            Expr::Binary { op, left, right } => {
+                let l = eval_metered(left, env, depth + 1, meter)?;
+                let r = eval_metered(right, env, depth + 1, meter)?;
                 match op {
                     BinaryOp::Add => Ok(l + r),
                     BinaryOp::Subtract => Ok(l - r),
@@ -296,9 +604,12 @@ pub mod evaluator {
     }
 }
 
-use evaluator::{EvalError, evaluate};
-use lexer::{LexerError, tokenize};
-use parser::{ParseError, parse};
+use evaluator::{
+    evaluate, evaluate_budgeted, evaluate_with_env, EvalError, ExecutionBudget, ResourceUsage,
+};
+use std::collections::HashMap;
+use lexer::{LexerError, tokenize_spanned};
+use parser::{ParseError, parse_spanned};
 
 #[derive(Debug, Error, PartialEq)]
 pub enum InterpreterError {
@@ -310,9 +621,235 @@ pub enum InterpreterError {
     Evaluator(#[from] EvalError),
 }
 
+impl InterpreterError {
+    /// Like `to_string`, but parse errors also show where in `source` they
+    /// happened (see `ParseError::render`).
+    pub fn render(&self, source: &str) -> String {
+        match self {
+            InterpreterError::Parser(err) => format!("Parser Error: {}", err.render(source)),
+            other => other.to_string(),
+        }
+    }
+}
+
 pub fn interpret(input: &str) -> Result<f64, InterpreterError> {
-    let tokens = tokenize(input)?;
-    let ast = parse(tokens)?;
+    let tokens = tokenize_spanned(input)?;
+    let ast = parse_spanned(tokens)?;
     let result = evaluate(&ast)?;
     Ok(result)
 }
+
+/// Like `interpret`, but evaluates within `budget` and reports the
+/// resources used. Lexing and parsing are not metered.
+pub fn interpret_budgeted(
+    input: &str,
+    budget: ExecutionBudget,
+) -> Result<(f64, ResourceUsage), InterpreterError> {
+    let tokens = tokenize_spanned(input)?;
+    let ast = parse_spanned(tokens)?;
+    let result = evaluate_budgeted(&ast, &HashMap::new(), budget)?;
+    Ok(result)
+}
+
+// ============================================================================
+// REPL SESSION
+// ============================================================================
+// A REPL needs state that outlives one line: here, the results of earlier
+// lines. Each successful evaluation is bound to the next name `_1`, `_2`, ...
+// and later lines can use those names like variables. `Session` does no I/O
+// at all: it takes a line and returns a structured `EvalOutput`, so the
+// read/print loop lives in the binary and the semantics are unit-testable.
+
+/// What `Session::eval_line` produced.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalOutput {
+    /// An expression was evaluated and bound to `name`.
+    Value { name: String, value: f64 },
+    /// `:vars`: every binding, oldest first.
+    Vars(Vec<(String, f64)>),
+    /// `:reset`: all bindings were dropped.
+    Reset,
+    /// A blank line; nothing happened.
+    Empty,
+}
+
+impl std::fmt::Display for EvalOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalOutput::Value { name, value } => write!(f, "{} = {}", name, value),
+            EvalOutput::Vars(vars) if vars.is_empty() => write!(f, "(no results yet)"),
+            EvalOutput::Vars(vars) => {
+                let lines: Vec<String> = vars
+                    .iter()
+                    .map(|(name, value)| format!("{} = {}", name, value))
+                    .collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+            EvalOutput::Reset => write!(f, "session reset"),
+            EvalOutput::Empty => Ok(()),
+        }
+    }
+}
+
+/// REPL state: the results of earlier lines, bound to `_1`, `_2`, ...
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    env: HashMap<String, f64>,
+    // Names in the order they were bound; `env` alone has no order.
+    names: Vec<String>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluates one line of input.
+    ///
+    /// Lines starting with `:` are session commands (`:vars`, `:reset`).
+    /// Anything else is an expression; on success its value is bound to the
+    /// next result name. A line that fails binds nothing, so it doesn't use
+    /// up a number.
+    pub fn eval_line(&mut self, line: &str) -> Result<EvalOutput, String> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(EvalOutput::Empty);
+        }
+
+        if let Some(command) = line.strip_prefix(':') {
+            return match command.trim() {
+                "vars" => Ok(EvalOutput::Vars(self.vars())),
+                "reset" => {
+                    self.env.clear();
+                    self.names.clear();
+                    Ok(EvalOutput::Reset)
+                }
+                other => Err(format!("Unknown command: :{}", other)),
+            };
+        }
+
+        let value = self.evaluate(line).map_err(|err| err.to_string())?;
+        let name = format!("_{}", self.names.len() + 1);
+        self.env.insert(name.clone(), value);
+        self.names.push(name.clone());
+        Ok(EvalOutput::Value { name, value })
+    }
+
+    /// Every binding, oldest first.
+    pub fn vars(&self) -> Vec<(String, f64)> {
+        self.names
+            .iter()
+            .map(|name| (name.clone(), self.env[name]))
+            .collect()
+    }
+
+    /// Looks up one binding.
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.env.get(name).copied()
+    }
+
+    fn evaluate(&self, input: &str) -> Result<f64, InterpreterError> {
+        let tokens = tokenize_spanned(input)?;
+        let ast = parse_spanned(tokens)?;
+        Ok(evaluate_with_env(&ast, &self.env)?)
+    }
+}
+
+// ============================================================================
+// CALCULATOR MEMORY
+// ============================================================================
+// A pocket calculator has two bits of state a REPL front-end can reuse:
+// the last answer and a memory register. `Calculator` exposes them to
+// expressions as two variables, `ans` and `mem`, so no new syntax is needed:
+// each evaluation just runs `evaluate_with_env` against an environment
+// holding those two names. `ans` only exists once something has been
+// computed; `mem` starts at 0.
+//
+// Every piece of state changes only after an expression has evaluated
+// successfully, so a typo can't clobber `ans` or sneak into the history.
+
+/// How many `(expression, result)` pairs `Calculator::new` keeps.
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// An `interpret` wrapper with `ans`, a memory register, and a history.
+#[derive(Debug, Clone)]
+pub struct Calculator {
+    last: Option<f64>,
+    memory: f64,
+    history: Vec<(String, f64)>,
+    history_limit: usize,
+}
+
+impl Calculator {
+    pub fn new() -> Self {
+        Self::with_history_limit(DEFAULT_HISTORY_LIMIT)
+    }
+
+    /// A calculator that remembers only the newest `limit` evaluations.
+    pub fn with_history_limit(limit: usize) -> Self {
+        Calculator {
+            last: None,
+            memory: 0.0,
+            history: Vec::new(),
+            history_limit: limit,
+        }
+    }
+
+    /// Evaluates `expression`, which may use `ans` and `mem`. On success the
+    /// result becomes the new `ans` and is added to the history.
+    pub fn eval(&mut self, expression: &str) -> Result<f64, InterpreterError> {
+        let tokens = tokenize_spanned(expression)?;
+        let ast = parse_spanned(tokens)?;
+        let value = evaluate_with_env(&ast, &self.env())?;
+
+        self.last = Some(value);
+        self.history.push((expression.trim().to_string(), value));
+        if self.history.len() > self.history_limit {
+            let excess = self.history.len() - self.history_limit;
+            self.history.drain(..excess);
+        }
+        Ok(value)
+    }
+
+    /// The last successful result, if any.
+    pub fn ans(&self) -> Option<f64> {
+        self.last
+    }
+
+    /// `M+`: adds `value`, or the last result when `None`, to memory and
+    /// returns the new memory value. With no last result, `None` adds 0.
+    pub fn memory_add(&mut self, value: Option<f64>) -> f64 {
+        self.memory += value.or(self.last).unwrap_or(0.0);
+        self.memory
+    }
+
+    /// `MR`: the memory register, also available as `mem`.
+    pub fn memory_recall(&self) -> f64 {
+        self.memory
+    }
+
+    /// `MC`: resets memory to 0.
+    pub fn memory_clear(&mut self) {
+        self.memory = 0.0;
+    }
+
+    /// `(expression, result)` pairs, oldest first.
+    pub fn history(&self) -> &[(String, f64)] {
+        &self.history
+    }
+
+    fn env(&self) -> HashMap<String, f64> {
+        let mut env = HashMap::new();
+        if let Some(last) = self.last {
+            env.insert("ans".to_string(), last);
+        }
+        env.insert("mem".to_string(), self.memory);
+        env
+    }
+}
+
+impl Default for Calculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,10 +3,16 @@
 //! These tests verify the full `interpret` pipeline, from string input
 //! to final `f64` result or error.
 
-use interpreter::solution::{interpret, InterpreterError};
-use interpreter::solution::lexer::LexerError;
-use interpreter::solution::parser::ParseError;
-use interpreter::solution::evaluator::EvalError;
+use interpreter::solution::{
+    interpret, interpret_budgeted, Calculator, EvalOutput, InterpreterError, Session,
+    DEFAULT_HISTORY_LIMIT,
+};
+use interpreter::solution::lexer::{tokenize, tokenize_spanned, LexerError, Spanned, Token};
+use interpreter::solution::parser::{parse, parse_spanned, ParseError};
+use interpreter::solution::evaluator::{
+    evaluate_budgeted, evaluate_with_env, EvalError, ExecutionBudget, Resource, ResourceUsage,
+};
+use std::collections::HashMap;
 
 /// Helper to assert that an expression evaluates to the correct number.
 /// Uses a small epsilon for float comparison.
@@ -117,12 +123,24 @@ fn test_lexer_error_unexpected_character() {
 
 #[test]
 fn test_parser_error_unexpected_end() {
-    assert_evals_to_err("1 +", InterpreterError::Parser(ParseError::UnexpectedEndOfInput));
+    assert_evals_to_err(
+        "1 +",
+        InterpreterError::Parser(ParseError {
+            message: "expected an expression after '+'".to_string(),
+            span: Some((2, 3)),
+        }),
+    );
 }
 
 #[test]
 fn test_parser_error_missing_right_paren() {
-    assert_evals_to_err("(1 + 2", InterpreterError::Parser(ParseError::ExpectedRightParen));
+    assert_evals_to_err(
+        "(1 + 2",
+        InterpreterError::Parser(ParseError {
+            message: "unclosed '('".to_string(),
+            span: Some((0, 1)),
+        }),
+    );
 }
 
 #[test]
@@ -133,4 +151,452 @@ fn test_evaluator_error_division_by_zero() {
 #[test]
 fn test_evaluator_error_division_by_zero_in_subexpression() {
     assert_evals_to_err("10 * (1 / (2 - 2))", InterpreterError::Evaluator(EvalError::DivisionByZero));
-}
\ No newline at end of file
+}
+// ============================================================================
+// BUDGETED EVALUATION
+// ============================================================================
+
+fn usage_of(expr: &str) -> ResourceUsage {
+    interpret_budgeted(expr, ExecutionBudget::unlimited())
+        .unwrap()
+        .1
+}
+
+fn budget(max_ops: u64, max_call_depth: u64) -> ExecutionBudget {
+    ExecutionBudget {
+        max_ops,
+        max_call_depth,
+    }
+}
+
+#[test]
+fn test_budgeted_exact_op_counts() {
+    // One op per AST node; the root is depth 1.
+    assert_eq!(
+        usage_of("7"),
+        ResourceUsage {
+            ops: 1,
+            peak_depth: 1
+        }
+    );
+    assert_eq!(
+        usage_of("1 + 2"),
+        ResourceUsage {
+            ops: 3,
+            peak_depth: 2
+        }
+    );
+    // Binary(+, 1, Binary(*, 2, 3))
+    assert_eq!(
+        usage_of("1 + 2 * 3"),
+        ResourceUsage {
+            ops: 5,
+            peak_depth: 3
+        }
+    );
+    // UnaryMinus(Grouping(Binary(*, 2, 3)))
+    assert_eq!(
+        usage_of("-(2 * 3)"),
+        ResourceUsage {
+            ops: 5,
+            peak_depth: 4
+        }
+    );
+}
+
+#[test]
+fn test_budgeted_op_exhaustion_mid_evaluation() {
+    let err = interpret_budgeted("1 + 2 * 3", budget(4, 100)).unwrap_err();
+    assert_eq!(
+        err,
+        InterpreterError::Evaluator(EvalError::BudgetExceeded {
+            resource: Resource::Ops,
+            used: 4,
+            limit: 4,
+        })
+    );
+    assert_eq!(
+        err.to_string(),
+        "Evaluation Error: op budget exceeded: used 4 of 4"
+    );
+
+    // An exact budget is enough.
+    let (value, usage) = interpret_budgeted("1 + 2 * 3", budget(5, 100)).unwrap();
+    assert_eq!(value, 7.0);
+    assert_eq!(usage.ops, 5);
+}
+
+#[test]
+fn test_budgeted_exhaustion_leaves_no_state_behind() {
+    // Evaluation is pure: a failed run must not affect the next one.
+    let input = "(1 + 2) * (3 + 4)";
+    assert!(interpret_budgeted(input, budget(3, 100)).is_err());
+    assert_eq!(
+        interpret_budgeted(input, ExecutionBudget::unlimited())
+            .unwrap()
+            .0,
+        21.0
+    );
+    assert_eq!(interpret(input).unwrap(), 21.0);
+}
+
+#[test]
+fn test_budgeted_depth_limit_and_reporting() {
+    assert_eq!(usage_of("((((1))))").peak_depth, 5);
+
+    let err = interpret_budgeted("((((1))))", budget(100, 4)).unwrap_err();
+    assert_eq!(
+        err,
+        InterpreterError::Evaluator(EvalError::BudgetExceeded {
+            resource: Resource::Depth,
+            used: 5,
+            limit: 4,
+        })
+    );
+    assert!(interpret_budgeted("((((1))))", budget(100, 5)).is_ok());
+}
+
+#[test]
+fn test_budgeted_matches_plain_evaluation() {
+    let corpus = [
+        "1",
+        "1 + 2",
+        "10 - 4 - 3",
+        "2 * 3 + 4 * 5",
+        "((2 + 3) * (4 - 1)) / 5",
+        "-(-(-3))",
+        "0.5 * 8 / 2",
+        "1 / 0",
+        "10 * (1 / (2 - 2))",
+        "1 +",
+        "1 + #",
+    ];
+    for input in corpus {
+        let plain = interpret(input);
+        let budgeted = interpret_budgeted(input, ExecutionBudget::unlimited()).map(|(v, _)| v);
+        assert_eq!(plain, budgeted, "mismatch for {:?}", input);
+    }
+}
+
+#[test]
+fn test_budgeted_looks_up_variables_in_env() {
+    let ast = parse(tokenize("rate * (hours + 1)").unwrap()).unwrap();
+    let env = HashMap::from([("rate".to_string(), 20.0), ("hours".to_string(), 7.0)]);
+
+    // Binary(*, rate, Grouping(Binary(+, hours, 1)))
+    let (value, usage) = evaluate_budgeted(&ast, &env, budget(6, 4)).unwrap();
+    assert_eq!(value, 160.0);
+    assert_eq!(
+        usage,
+        ResourceUsage {
+            ops: 6,
+            peak_depth: 4
+        }
+    );
+    assert_eq!(evaluate_with_env(&ast, &env), Ok(value));
+
+    assert!(matches!(
+        evaluate_budgeted(&ast, &env, budget(5, 4)),
+        Err(EvalError::BudgetExceeded {
+            resource: Resource::Ops,
+            ..
+        })
+    ));
+    assert_eq!(
+        evaluate_budgeted(&ast, &HashMap::new(), ExecutionBudget::unlimited()),
+        Err(EvalError::UnknownVariable("rate".to_string()))
+    );
+}
+
+// ============================================================================
+// REPL SESSION
+// ============================================================================
+
+fn value_of(output: EvalOutput) -> (String, f64) {
+    match output {
+        EvalOutput::Value { name, value } => (name, value),
+        other => panic!("expected a value, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_session_results_are_named_in_order() {
+    let mut session = Session::new();
+    assert_eq!(
+        value_of(session.eval_line("2 + 3").unwrap()),
+        ("_1".to_string(), 5.0)
+    );
+    assert_eq!(
+        value_of(session.eval_line("10").unwrap()),
+        ("_2".to_string(), 10.0)
+    );
+    assert_eq!(
+        value_of(session.eval_line("_1 + _2").unwrap()),
+        ("_3".to_string(), 15.0)
+    );
+    assert_eq!(
+        value_of(session.eval_line("_3 * (_1 - 1)").unwrap()).1,
+        60.0
+    );
+}
+
+#[test]
+fn test_session_error_does_not_consume_a_number() {
+    let mut session = Session::new();
+    session.eval_line("1").unwrap();
+
+    assert!(session.eval_line("1 / 0").is_err());
+    assert!(session.eval_line("1 +").is_err());
+    let err = session.eval_line("_7 + 1").unwrap_err();
+    assert!(err.contains("Unknown variable: _7"), "{}", err);
+
+    assert_eq!(value_of(session.eval_line("2").unwrap()).0, "_2");
+}
+
+#[test]
+fn test_session_reset_clears_state() {
+    let mut session = Session::new();
+    session.eval_line("4").unwrap();
+    session.eval_line("5").unwrap();
+
+    assert_eq!(session.eval_line(":reset"), Ok(EvalOutput::Reset));
+    assert_eq!(session.eval_line(":vars"), Ok(EvalOutput::Vars(vec![])));
+    assert!(session.eval_line("_1").is_err());
+    assert_eq!(
+        value_of(session.eval_line("7").unwrap()),
+        ("_1".to_string(), 7.0)
+    );
+}
+
+#[test]
+fn test_session_vars_in_insertion_order() {
+    let mut session = Session::new();
+    for line in ["3", "1", "2", "_1 + _2 + _3"] {
+        session.eval_line(line).unwrap();
+    }
+
+    let expected = vec![
+        ("_1".to_string(), 3.0),
+        ("_2".to_string(), 1.0),
+        ("_3".to_string(), 2.0),
+        ("_4".to_string(), 6.0),
+    ];
+    assert_eq!(
+        session.eval_line(":vars"),
+        Ok(EvalOutput::Vars(expected.clone()))
+    );
+    assert_eq!(session.vars(), expected);
+    assert_eq!(session.get("_4"), Some(6.0));
+}
+
+#[test]
+fn test_session_blank_lines_and_unknown_commands() {
+    let mut session = Session::new();
+    assert_eq!(session.eval_line("   "), Ok(EvalOutput::Empty));
+    assert!(session.eval_line(":frobnicate").is_err());
+    assert!(session.vars().is_empty());
+}
+
+#[test]
+fn test_variables_outside_session_are_unknown() {
+    assert_evals_to_err(
+        "x + 1",
+        InterpreterError::Evaluator(EvalError::UnknownVariable("x".to_string())),
+    );
+}
+
+// ============================================================================
+// SOURCE SPANS AND ERROR RENDERING
+// ============================================================================
+
+/// The parse error `interpret` reports for `input`.
+fn parse_error(input: &str) -> ParseError {
+    match interpret(input).unwrap_err() {
+        InterpreterError::Parser(err) => err,
+        other => panic!("expected a parse error for {:?}, got {:?}", input, other),
+    }
+}
+
+#[test]
+fn test_tokenize_spanned_byte_ranges() {
+    let tokens = tokenize_spanned("12.5 *(x_1)").unwrap();
+    let spans: Vec<(usize, usize)> = tokens.iter().map(|t| t.span).collect();
+    assert_eq!(spans, vec![(0, 4), (5, 6), (6, 7), (7, 10), (10, 11)]);
+    assert_eq!(
+        tokens[3],
+        Spanned {
+            node: Token::Identifier("x_1".to_string()),
+            span: (7, 10)
+        }
+    );
+}
+
+#[test]
+fn test_render_unclosed_paren() {
+    let source = "2 * (1 + 2";
+    let err = parse_error(source);
+    assert_eq!(err.span, Some((4, 5)));
+    assert_eq!(
+        err.render(source),
+        "unclosed '(' at line 1, column 5\n2 * (1 + 2\n    ^"
+    );
+}
+
+#[test]
+fn test_render_unexpected_trailing_token() {
+    let source = "(1 + 2) 345";
+    let err = parse_error(source);
+    assert_eq!(err.message, "unexpected '345' after a complete expression");
+    assert_eq!(
+        err.render(source),
+        "unexpected '345' after a complete expression at line 1, column 9\n(1 + 2) 345\n        ^^^"
+    );
+}
+
+#[test]
+fn test_render_missing_right_operand() {
+    let source = "1 +\n  4 *";
+    let err = parse_error(source);
+    assert_eq!(err.span, Some((8, 9)));
+    assert_eq!(
+        err.render(source),
+        "expected an expression after '*' at line 2, column 5\n  4 *\n    ^"
+    );
+
+    let err = parse_error("(1 + )");
+    assert_eq!(err.message, "unexpected ')'");
+    assert_eq!(err.span, Some((5, 6)));
+}
+
+#[test]
+fn test_render_without_span_is_just_the_message() {
+    // Bare tokens carry no positions.
+    let err = parse(tokenize("(1").unwrap()).unwrap_err();
+    assert_eq!(err.span, None);
+    assert_eq!(err.render("(1"), "unclosed '('");
+
+    let err = parse_error("   ");
+    assert_eq!(err.message, "empty expression");
+    assert_eq!(err.render("   "), "empty expression");
+}
+
+#[test]
+fn test_interpreter_error_render() {
+    let source = "1 + )";
+    assert_eq!(
+        interpret(source).unwrap_err().render(source),
+        "Parser Error: unexpected ')' at line 1, column 5\n1 + )\n    ^"
+    );
+    // Other stages render like Display.
+    let err = interpret("1 / 0").unwrap_err();
+    assert_eq!(err.render("1 / 0"), err.to_string());
+}
+
+#[test]
+fn test_spans_do_not_change_valid_results() {
+    for source in [
+        "1 + 2 * 3",
+        "-(4 - 10) / 3",
+        "((2))",
+        "  7.5\t*\n2  ",
+        "-3 - -3",
+    ] {
+        let spanned = tokenize_spanned(source).unwrap();
+        let bare: Vec<Token> = spanned.iter().map(|t| t.node.clone()).collect();
+        assert_eq!(bare, tokenize(source).unwrap());
+        assert_eq!(parse_spanned(spanned).unwrap(), parse(bare).unwrap());
+    }
+    assert_evals_to("2 * (3 + 4) - 1", 13.0);
+}
+
+// ============================================================================
+// CALCULATOR MEMORY
+// ============================================================================
+
+#[test]
+fn test_calculator_ans_chains_results() {
+    let mut calc = Calculator::new();
+    assert_eq!(calc.ans(), None);
+    assert_eq!(calc.eval("2 + 3"), Ok(5.0));
+    assert_eq!(calc.eval("ans * 4"), Ok(20.0));
+    assert_eq!(calc.eval("ans - ans / 2"), Ok(10.0));
+    assert_eq!(calc.ans(), Some(10.0));
+}
+
+#[test]
+fn test_calculator_ans_unknown_before_first_result() {
+    let mut calc = Calculator::new();
+    assert_eq!(
+        calc.eval("ans + 1"),
+        Err(InterpreterError::Evaluator(EvalError::UnknownVariable(
+            "ans".to_string()
+        )))
+    );
+}
+
+#[test]
+fn test_calculator_memory_functions() {
+    let mut calc = Calculator::new();
+    assert_eq!(calc.eval("mem"), Ok(0.0));
+
+    calc.eval("6 * 7").unwrap();
+    assert_eq!(calc.memory_add(None), 42.0);
+    assert_eq!(calc.memory_add(Some(8.0)), 50.0);
+    assert_eq!(calc.memory_recall(), 50.0);
+    assert_eq!(calc.eval("mem / 5"), Ok(10.0));
+    // Recalling doesn't change memory; evaluating doesn't either.
+    assert_eq!(calc.memory_recall(), 50.0);
+
+    calc.memory_clear();
+    assert_eq!(calc.eval("mem + ans"), Ok(10.0));
+}
+
+#[test]
+fn test_calculator_memory_add_without_results_adds_nothing() {
+    let mut calc = Calculator::new();
+    assert_eq!(calc.memory_add(None), 0.0);
+}
+
+#[test]
+fn test_calculator_failures_change_nothing() {
+    let mut calc = Calculator::new();
+    calc.eval("1 + 1").unwrap();
+
+    assert!(matches!(
+        calc.eval("1 / 0"),
+        Err(InterpreterError::Evaluator(_))
+    ));
+    assert!(matches!(
+        calc.eval("(1 +"),
+        Err(InterpreterError::Parser(_))
+    ));
+    assert!(matches!(
+        calc.eval("1 $ 2"),
+        Err(InterpreterError::Lexer(_))
+    ));
+    assert!(calc.eval("ans + missing").is_err());
+
+    assert_eq!(calc.ans(), Some(2.0));
+    assert_eq!(calc.history(), &[("1 + 1".to_string(), 2.0)]);
+    assert_eq!(calc.eval("ans"), Ok(2.0));
+}
+
+#[test]
+fn test_calculator_history_is_capped() {
+    let mut calc = Calculator::with_history_limit(3);
+    for n in 1..=5 {
+        calc.eval(&format!(" {} * 10 ", n)).unwrap();
+    }
+    let expected: Vec<(String, f64)> = vec![
+        ("3 * 10".to_string(), 30.0),
+        ("4 * 10".to_string(), 40.0),
+        ("5 * 10".to_string(), 50.0),
+    ];
+    assert_eq!(calc.history(), expected.as_slice());
+
+    let mut calc = Calculator::default();
+    for _ in 0..DEFAULT_HISTORY_LIMIT + 10 {
+        calc.eval("1").unwrap();
+    }
+    assert_eq!(calc.history().len(), DEFAULT_HISTORY_LIMIT);
+}
@@ -19,6 +19,9 @@
 //! 4.  **`Task` and `TaskRunner` (Stretch Goal)**: Structs to manage and run a
 //!     sequence of commands.
 //!
+//! 5.  **`Watcher` (Stretch Goal)**: Poll files for changes and re-run a
+//!     command, debouncing bursts of edits and cancelling stale runs.
+//!
 //! ## Running Your Code
 //!
 //! ```bash
@@ -30,20 +33,39 @@
 //!
 //! Check out `src/solution.rs` for a complete, heavily-commented solution.
 
-use std::process::{Command, Stdio};
-use std::time::Duration;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 // TODO: Define your error type.
-// It should be able to represent I/O errors and a timeout error.
+// It should tell apart a process that could not start (keep the
+// `io::Error` as its `source()`), a timeout (with the partial output),
+// other I/O errors, and a builder that was configured wrongly.
 // #[derive(Debug, Error)]
 // pub enum CommandError { ... }
 #[derive(Debug, Error)]
 pub enum CommandError {
-    #[error("I/O Error: {0}")]
-    Io(#[from] std::io::Error),
-    #[error("Command timed out after {0:?}")]
-    Timeout(Duration),
+    #[error("failed to launch `{command}`: {source}")]
+    LaunchFailed {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("`{command}` timed out after {timeout:?}")]
+    TimedOut {
+        command: String,
+        timeout: Duration,
+        partial: Option<Box<CommandResult>>,
+    },
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("invalid configuration: {0}")]
+    InvalidConfiguration(String),
+    #[error("could not parse command line: {0}")]
+    Parse(#[from] ParseError),
+    #[error("the job running this task was lost before it reported")]
+    JobLost,
 }
 
 
@@ -56,8 +78,50 @@ pub struct CommandResult {
     pub exit_code: i32,
     pub stdout: String,
     pub stderr: String,
+    pub stdout_bytes: Option<Vec<u8>>,
+    pub stderr_bytes: Option<Vec<u8>>,
+    pub duration: Duration,
+    pub max_rss_kb: Option<u64>,
+}
+
+impl CommandResult {
+    /// A one-line report such as `exit 0 in 1.24s, 38 MB peak`.
+    pub fn summary(&self) -> String {
+        // TODO: Format the exit code and seconds with two decimals. Add the
+        // peak memory (MB from 1024 KB up, else KB) when it is known.
+        todo!("Summarize the result on one line");
+    }
+}
+
+// TODO: Keep the exact output bytes for binary-safe callers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawCommandResult {
+    pub exit_code: i32,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    // TODO: Time the child from spawn to reap. On Unix, reap it with
+    // `libc::wait4` to also get `ru_maxrss` (peak memory).
+    pub duration: Duration,
+    pub max_rss_kb: Option<u64>,
 }
 
+impl RawCommandResult {
+    pub fn stdout_lossy(&self) -> String {
+        todo!("Decode stdout with String::from_utf8_lossy");
+    }
+
+    pub fn stderr_lossy(&self) -> String {
+        todo!("Decode stderr with String::from_utf8_lossy");
+    }
+
+    pub fn into_lossy(self) -> CommandResult {
+        todo!("Convert into a CommandResult with no bytes kept");
+    }
+
+    pub fn into_lossy_with_bytes(self) -> CommandResult {
+        todo!("Convert into a CommandResult, keeping stdout/stderr bytes too");
+    }
+}
 
 // TODO: Define the CommandBuilder struct.
 // It should hold all the configuration for a command.
@@ -66,6 +130,8 @@ pub struct CommandResult {
 // - envs: Vec<(String, String)>
 // - current_dir: Option<String>
 // - timeout: Option<Duration>
+// - stdin: Option<Vec<u8>>
+// - raw_output: bool
 //
 // #[derive(Default, Clone)]
 // pub struct CommandBuilder { ... }
@@ -76,6 +142,8 @@ pub struct CommandBuilder {
     envs: Vec<(String, String)>,
     current_dir: Option<String>,
     timeout: Option<Duration>,
+    stdin: Option<Vec<u8>>,
+    raw_output: bool,
 }
 
 impl CommandBuilder {
@@ -84,6 +152,11 @@ impl CommandBuilder {
         todo!("Initialize the CommandBuilder");
     }
 
+    /// Builds a command from one string, split by `parse_command_line`.
+    pub fn from_line(line: &str) -> Result<Self, ParseError> {
+        todo!("Split the line and use the first word as the command");
+    }
+
     /// Adds an argument to the command.
     pub fn arg(mut self, arg: impl Into<String>) -> Self {
         todo!("Add an argument to the args vector");
@@ -104,6 +177,29 @@ impl CommandBuilder {
         todo!("Set the timeout field");
     }
 
+    /// Feeds `bytes` to the command's stdin.
+    pub fn stdin_bytes(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        todo!("Set the stdin field");
+    }
+
+    /// Keeps the exact output bytes in the `CommandResult` too.
+    pub fn raw_output(mut self, raw: bool) -> Self {
+        todo!("Set the raw_output field");
+    }
+
+    /// Spawns the command without waiting for it, with stdout/stderr piped.
+    pub fn spawn(&self) -> Result<Child, CommandError> {
+        // TODO: Build the `std::process::Command` exactly like `run()` does,
+        // but return the `Child` right after `spawn()`. Refactor `run()` to
+        // call this so the setup code lives in one place.
+        // Reject an empty command, empty/`=`-containing env keys, and a zero
+        // timeout with `InvalidConfiguration`; map a failed `spawn()` to
+        // `LaunchFailed`.
+        // With `stdin` set, pipe stdin and write the bytes from a separate
+        // thread (then drop the handle) so neither side can deadlock.
+        todo!("Spawn the configured command");
+    }
+
     /// Executes the command.
     pub fn run(&self) -> Result<CommandResult, CommandError> {
         // TODO: Implement the run logic.
@@ -114,12 +210,72 @@ impl CommandBuilder {
         // 5. If there's a timeout, you'll need to manage waiting for the
         //    process. A simple approach is to loop with `try_wait` for the
         //    duration of the timeout. If it doesn't finish in time,
-        //    `.kill()` the child process and return a timeout error.
+        //    `.kill()` the child process and return `TimedOut`, with the
+        //    output collected after the kill as `partial`.
         // 6. If there's no timeout, use `wait_with_output()`.
         // 7. Collect the exit code, stdout, and stderr into your
         //    `CommandResult` struct and return it.
         todo!("Execute the configured command");
     }
+
+    /// Executes the command, keeping its output as raw bytes.
+    pub fn run_raw(&self) -> Result<RawCommandResult, CommandError> {
+        // TODO: Move the logic of `run()` here and make `run()` a lossy
+        // conversion of this (`into_lossy_with_bytes` when `raw_output` is
+        // set, also for the timeout's `partial`).
+        // Start draining stdout/stderr right after spawning, and after a
+        // timeout kill wait at most KILL_DRAIN_TIMEOUT for EOF: background
+        // grandchildren can keep the pipes open.
+        todo!("Execute the configured command without decoding output");
+    }
+}
+
+/// How long a timed-out command's output may keep trickling in after the kill.
+pub const KILL_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Waits for a spawned child and gathers its exit code and output.
+pub fn collect_result(child: Child) -> Result<CommandResult, CommandError> {
+    // TODO: `wait_with_output()` and convert into a `CommandResult`.
+    // A process killed by a signal has no exit code; use -1.
+    todo!("Collect the child's output");
+}
+
+/// Like `collect_result`, but keeps the output as raw bytes.
+pub fn collect_raw_result(child: Child) -> Result<RawCommandResult, CommandError> {
+    todo!("Collect the child's output as bytes");
+}
+
+/// Runs `stages` as a pipeline, like `a | b | c` in a shell.
+pub fn pipe(stages: &[CommandBuilder]) -> Result<RawCommandResult, CommandError> {
+    // TODO: Run the first stage with `run_raw`, then feed each stage's
+    // stdout bytes to the next with `stdin_bytes`. Return the last result.
+    // An empty pipeline is an `InvalidConfiguration`.
+    todo!("Run a byte-preserving pipeline");
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseError {
+    #[error("command line is empty")]
+    Empty,
+    #[error("unterminated {quote} quote starting at byte {position}")]
+    UnterminatedQuote { quote: char, position: usize },
+    #[error("trailing backslash at end of command line")]
+    TrailingBackslash,
+}
+
+/// Splits `input` into a program and its arguments like a POSIX shell
+/// would, without running one.
+pub fn parse_command_line(input: &str) -> Result<(String, Vec<String>), ParseError> {
+    // TODO: Walk the chars. Unquoted whitespace ends a word; '...' is
+    // literal; in "..." a backslash only escapes " \ $ and `; outside
+    // quotes a backslash escapes anything. Track whether a word has started
+    // so `""` gives an empty argument.
+    todo!("Tokenize a command line");
+}
+
+/// Parses `line` with `parse_command_line` and runs it.
+pub fn run_line(line: &str) -> Result<CommandResult, CommandError> {
+    todo!("CommandBuilder::from_line, then run");
 }
 
 
@@ -138,6 +294,27 @@ pub struct Task {
 // pub struct TaskRunner { ... }
 pub struct TaskRunner {
     tasks: Vec<Task>,
+    max_parallel: Option<usize>,
+    fail_fast: bool,
+}
+
+/// The outcome of one task from `TaskRunner::run_all_pooled`.
+#[derive(Debug)]
+pub struct TaskResult {
+    pub index: usize,
+    pub name: String,
+    /// `None` if `fail_fast` skipped the task.
+    pub result: Option<Result<CommandResult, CommandError>>,
+}
+
+impl TaskResult {
+    pub fn succeeded(&self) -> bool {
+        todo!("Ran and exited with status 0");
+    }
+
+    pub fn skipped(&self) -> bool {
+        todo!("Never started");
+    }
 }
 
 impl TaskRunner {
@@ -146,13 +323,183 @@ impl TaskRunner {
         todo!("Initialize the TaskRunner");
     }
 
+    /// Caps how many tasks `run_all_pooled` keeps in flight (default: the
+    /// pool's worker count).
+    pub fn max_parallel(mut self, max_parallel: usize) -> Self {
+        todo!("Store the limit (at least 1)");
+    }
+
+    /// Stop starting new pooled tasks after the first failure.
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        todo!("Store the flag");
+    }
+
     /// Runs all tasks in sequence, stopping if a task fails.
     pub fn run(&mut self) -> Vec<Result<CommandResult, CommandError>> {
         todo!("Loop through tasks, run them, and collect results");
     }
+
+    /// Runs the tasks concurrently on `pool`, results in task order.
+    pub fn run_all_pooled(&self, pool: &thread_pool::solution::ThreadPool) -> Vec<TaskResult> {
+        // TODO (stretch): Give each job a clone of an mpsc Sender to report
+        // `(index, result)`. Keep at most `max_parallel` jobs submitted, and
+        // with `fail_fast` stop submitting once a result is a failure.
+        // A job that panics must still report (as `JobLost`), and the
+        // runner must not keep its own Sender alive while waiting, or
+        // `recv()` blocks forever.
+        todo!("Run tasks on the thread pool");
+    }
 }
 
 
+// --- Stretch Goal: Watch Mode ---
+
+/// Source of time for `Watcher::run_loop`.
+pub trait Clock {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+/// The real clock: `Instant::now()` and `thread::sleep`.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        todo!("Return Instant::now()");
+    }
+
+    fn sleep(&self, duration: Duration) {
+        todo!("Sleep the current thread");
+    }
+}
+
+/// A fake clock for tests: `sleep` just advances the time instantly.
+// TODO: Clones must share the same time. Hint: `Rc<Cell<Instant>>`.
+#[derive(Clone)]
+pub struct ManualClock {
+    // Add your fields here
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        todo!("Start at Instant::now()");
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        todo!("Move the shared time forward");
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        todo!("Return the shared time");
+    }
+
+    fn sleep(&self, duration: Duration) {
+        todo!("Advance instead of sleeping");
+    }
+}
+
+/// Result of a single poll.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    Changed { paths: Vec<PathBuf> },
+    Unchanged,
+}
+
+/// What `run_loop` reports to its callback.
+#[derive(Debug)]
+pub enum WatchRunEvent {
+    Triggered { paths: Vec<PathBuf> },
+    Finished(CommandResult),
+    Cancelled,
+    Failed(CommandError),
+}
+
+// TODO: Define the Watcher struct.
+// It needs the command, the watched paths, the recursive flag, the poll
+// interval and debounce window, include/exclude patterns, a boxed `Clock`,
+// the last snapshot (path -> modified time + size), the changed paths
+// waiting for the debounce window, and the currently running `Child` (read
+// its stdout/stderr from the moment it's spawned, or a chatty child fills
+// the pipe and never exits).
+pub struct Watcher {
+    // Add your fields here
+}
+
+impl Watcher {
+    /// Creates a watcher with recursive walking, a 500ms poll interval, and
+    /// a 100ms debounce window.
+    pub fn new(command: CommandBuilder) -> Self {
+        todo!("Initialize the Watcher");
+    }
+
+    pub fn watch(mut self, path: impl Into<PathBuf>) -> Self {
+        todo!("Add a file or directory to watch");
+    }
+
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        todo!("Set whether directories are walked recursively");
+    }
+
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        todo!("Set the poll interval");
+    }
+
+    pub fn debounce(mut self, window: Duration) -> Self {
+        todo!("Set the debounce window");
+    }
+
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        todo!("Add an include glob");
+    }
+
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        todo!("Add an exclude glob (exclude wins over include)");
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        todo!("Replace the clock");
+    }
+
+    /// Takes a snapshot and diffs it against the previous one.
+    pub fn poll_once(&mut self, now: Instant) -> WatchEvent {
+        // TODO:
+        // 1. The first call only records the baseline: return `Unchanged`.
+        // 2. Add created, modified (mtime or size), and deleted files to
+        //    the pending set, and remember `now` as the last change.
+        // 3. Once `now - last_change >= debounce`, drain the pending set
+        //    into `Changed`.
+        todo!("Poll for changes");
+    }
+
+    /// Polls up to `max_iterations` times, re-running the command on change.
+    pub fn run_loop<F>(&mut self, max_iterations: usize, mut on_event: F)
+    where
+        F: FnMut(&WatchRunEvent),
+    {
+        // TODO: Each iteration:
+        // 1. Sleep `poll_interval` (not before the first iteration).
+        // 2. If the running child exited, report `Finished` (or `Failed`).
+        // 3. Poll. On `Changed`, kill a still-running child (`Cancelled`),
+        //    report `Triggered`, and `spawn()` the command again.
+        // After the loop, wait for the last run and report it.
+        todo!("Run the watch loop");
+    }
+}
+
+/// Glob-lite matching: `*` stays within one path segment, `**` spans
+/// segments. Patterns without `/` match the file name only.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    todo!("Match a path against a glob pattern");
+}
+
 // Re-export the solution module so people can compare
 #[doc(hidden)]
 pub mod solution;
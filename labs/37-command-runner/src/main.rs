@@ -4,8 +4,13 @@
 //! a few sample external commands.
 //! Run with: cargo run -p command-runner
 
-use command_runner::solution::{CommandBuilder, Task, TaskRunner};
-use std::time::Duration;
+use command_runner::solution::{
+    parse_command_line, run_line, CommandBuilder, ManualClock, Task, TaskRunner, WatchRunEvent,
+    Watcher,
+};
+use std::fs;
+use std::time::{Duration, Instant};
+use thread_pool::solution::ThreadPool;
 
 fn main() {
     println!("=== Command Runner Demo ===\n");
@@ -22,6 +27,7 @@ fn main() {
     println!("   -> Exit Code: {}", result1.exit_code);
     println!("   -> Stdout: {}", result1.stdout.trim());
     println!("   -> Stderr: {}", result1.stderr.trim());
+    println!("   -> Summary: {}", result1.summary());
     println!();
 
     // ============================================================================
@@ -78,6 +84,74 @@ fn main() {
     }
     println!("   -------------------------\n");
 
+    println!("   Running independent tasks on a thread pool (fail fast)...");
+    let pooled = vec![
+        Task::new("sleep".to_string(), CommandBuilder::new("sleep").arg("0.2")),
+        Task::new("fails".to_string(), CommandBuilder::new("false")),
+        Task::new(
+            "never starts".to_string(),
+            CommandBuilder::new("echo").arg("skipped"),
+        ),
+        Task::new(
+            "never starts either".to_string(),
+            CommandBuilder::new("echo").arg("skipped"),
+        ),
+    ];
+    let pool = ThreadPool::new(2);
+    let started = Instant::now();
+    let results = TaskRunner::new(pooled)
+        .max_parallel(2)
+        .fail_fast(true)
+        .run_all_pooled(&pool);
+    for result in &results {
+        let status = if result.skipped() {
+            "skipped"
+        } else if result.succeeded() {
+            "ok"
+        } else {
+            "failed"
+        };
+        println!("   [{}] {}: {}", result.index, result.name, status);
+    }
+    println!("   finished in {:.2?}\n", started.elapsed());
+
+    // ============================================================================
+    // DEMO 5: Watch mode
+    // ============================================================================
+    println!("5. Watching a directory and re-running on change...");
+    let dir = std::env::temp_dir().join(format!("command-runner-watch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("main.rs");
+    fs::write(&source, "fn main() {}").unwrap();
+
+    let mut watcher = Watcher::new(CommandBuilder::new("echo").arg("rebuilding..."))
+        .watch(&dir)
+        .include("*.rs")
+        .debounce(Duration::ZERO)
+        .with_clock(ManualClock::new());
+    watcher.poll_once(Instant::now()); // record the baseline
+    fs::write(&source, "fn main() { println!(\"hi\"); }").unwrap();
+
+    watcher.run_loop(3, |event| match event {
+        WatchRunEvent::Triggered { paths } => println!("   -> Changed: {:?}", paths),
+        WatchRunEvent::Finished(result) => println!("   -> Ran: {}", result.stdout.trim()),
+        WatchRunEvent::Cancelled => println!("   -> Cancelled a stale run"),
+        WatchRunEvent::Failed(e) => println!("   -> Failed: {}", e),
+    });
+    fs::remove_dir_all(&dir).ok();
+    println!();
+
+    println!("6. Running a whole command line (no shell involved)...");
+    let line = r#"echo "quoted  spaces" 'single' \$HOME"#;
+    println!("   Parsed: {:?}", parse_command_line(line));
+    match run_line(line) {
+        Ok(result) => println!("   -> Output: {}", result.stdout.trim_end()),
+        Err(e) => println!("   -> Error: {}", e),
+    }
+    if let Err(e) = run_line("echo 'unterminated") {
+        println!("   -> Rejected: {}", e);
+    }
+    println!();
 
     println!("=== Demo Complete! ===");
 }
\ No newline at end of file
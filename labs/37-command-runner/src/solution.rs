@@ -24,29 +24,146 @@
 //!   gives us more control (like the ability to kill it), instead of the simpler, 
 //!   blocking `output()` method.
 //! - **Timeout Implementation**: A simple, cross-platform timeout is implemented
-//!   by polling `child.try_wait()` in a loop rather than using platform-specific
-//!   APIs.
+//!   by polling for the child's exit in a loop rather than using platform-specific
+//!   timer APIs.
+//! - **Resource Usage**: On Unix we reap children with `wait4`, which hands
+//!   back the kernel's `rusage` record (peak memory and more) along with the
+//!   exit status. `std` has no portable equivalent, so this is the one place
+//!   we call into `libc`, behind `#[cfg(unix)]`.
 
+use std::cell::Cell;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio, Child};
-use std::time::{Duration, Instant};
-use std::io::{Read};
+use std::rc::Rc;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use std::io::{Read, Write};
 use thiserror::Error;
+use thread_pool::solution::ThreadPool;
 
 /// An error type for our command running operations.
+///
+/// Each variant answers a different question, so callers can `match`
+/// instead of inspecting message strings: did the program even start
+/// (`LaunchFailed`), did it take too long (`TimedOut`), did talking to it
+/// break (`IoError`), or was the builder set up wrong before anything ran
+/// (`InvalidConfiguration`)?
 #[derive(Debug, Error)]
 pub enum CommandError {
-    #[error("I/O Error: {0}")]
-    Io(#[from] std::io::Error),
-    #[error("Command timed out after {0:?}")]
-    Timeout(Duration),
+    /// The process could not be started, e.g. the binary does not exist.
+    #[error("failed to launch `{command}`: {source}")]
+    LaunchFailed {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+    /// The process outlived its timeout and was killed. `partial` holds
+    /// whatever it wrote before that, if the output could still be read.
+    /// (Boxed to keep every `Result<_, CommandError>` small.)
+    #[error("`{command}` timed out after {timeout:?}")]
+    TimedOut {
+        command: String,
+        timeout: Duration,
+        partial: Option<Box<CommandResult>>,
+    },
+    /// Waiting for, killing, or reading from a running process failed.
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    /// The builder was rejected before anything was spawned.
+    #[error("invalid configuration: {0}")]
+    InvalidConfiguration(String),
+    /// A command line given to `run_line` could not be split into words.
+    #[error("could not parse command line: {0}")]
+    Parse(#[from] ParseError),
+    /// A pooled task's job panicked, or the pool dropped it without running
+    /// it, so the task never reported a result.
+    #[error("the job running this task was lost before it reported")]
+    JobLost,
 }
 
 /// Holds the result of a completed command.
+///
+/// The output is decoded with `String::from_utf8_lossy`, so any invalid
+/// UTF-8 has already been replaced with U+FFFD. Use `run_raw`, or build the
+/// command with `raw_output(true)`, when the exact bytes matter (binary
+/// output, `tar` to stdout, ...).
 #[derive(Debug, Clone)]
 pub struct CommandResult {
     pub exit_code: i32,
     pub stdout: String,
     pub stderr: String,
+    /// The exact bytes behind `stdout`/`stderr`. Only kept when the command
+    /// was built with `raw_output(true)`; `None` otherwise.
+    pub stdout_bytes: Option<Vec<u8>>,
+    pub stderr_bytes: Option<Vec<u8>>,
+    /// Wall-clock time from spawning the process until it was reaped.
+    pub duration: Duration,
+    /// Peak resident memory in kilobytes, as reported by `wait4`. Always
+    /// `None` off Unix, or when the process was reaped by someone else.
+    pub max_rss_kb: Option<u64>,
+}
+
+impl CommandResult {
+    /// A one-line report such as `exit 0 in 1.24s, 38 MB peak`.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "exit {} in {:.2}s",
+            self.exit_code,
+            self.duration.as_secs_f64()
+        );
+        match self.max_rss_kb {
+            Some(kb) if kb >= 1024 => summary.push_str(&format!(", {} MB peak", kb / 1024)),
+            Some(kb) => summary.push_str(&format!(", {} KB peak", kb)),
+            None => {}
+        }
+        summary
+    }
+}
+
+/// The result of a completed command with its output exactly as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawCommandResult {
+    pub exit_code: i32,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub duration: Duration,
+    pub max_rss_kb: Option<u64>,
+}
+
+impl RawCommandResult {
+    /// stdout as text, with invalid UTF-8 replaced by U+FFFD.
+    pub fn stdout_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.stdout).into_owned()
+    }
+
+    /// stderr as text, with invalid UTF-8 replaced by U+FFFD.
+    pub fn stderr_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.stderr).into_owned()
+    }
+
+    /// Converts to a `CommandResult`, decoding both streams lossily.
+    pub fn into_lossy(self) -> CommandResult {
+        CommandResult {
+            exit_code: self.exit_code,
+            stdout: self.stdout_lossy(),
+            stderr: self.stderr_lossy(),
+            stdout_bytes: None,
+            stderr_bytes: None,
+            duration: self.duration,
+            max_rss_kb: self.max_rss_kb,
+        }
+    }
+
+    /// Like `into_lossy`, but keeps the bytes in `stdout_bytes` and
+    /// `stderr_bytes` as well.
+    pub fn into_lossy_with_bytes(self) -> CommandResult {
+        let mut result = self.clone().into_lossy();
+        result.stdout_bytes = Some(self.stdout);
+        result.stderr_bytes = Some(self.stderr);
+        result
+    }
 }
 
 /// A builder for creating and running external commands.
@@ -57,6 +174,8 @@ pub struct CommandBuilder {
     envs: Vec<(String, String)>,
     current_dir: Option<String>,
     timeout: Option<Duration>,
+    stdin: Option<Vec<u8>>,
+    raw_output: bool,
 }
 
 impl CommandBuilder {
@@ -68,6 +187,18 @@ impl CommandBuilder {
         }
     }
 
+    /// Builds a command from one string, split by `parse_command_line`.
+    ///
+    /// No shell is involved: `from_line("ls *.rs")` passes a literal `*.rs`.
+    pub fn from_line(line: &str) -> Result<Self, ParseError> {
+        let (command, args) = parse_command_line(line)?;
+        Ok(CommandBuilder {
+            command,
+            args,
+            ..Default::default()
+        })
+    }
+
     /// Adds an argument.
     pub fn arg(mut self, arg: impl Into<String>) -> Self {
         self.args.push(arg.into());
@@ -92,8 +223,29 @@ impl CommandBuilder {
         self
     }
 
-    /// Executes the command.
-    pub fn run(&self) -> Result<CommandResult, CommandError> {
+    /// Feeds `bytes` to the command's stdin. Without this, the command
+    /// inherits our stdin.
+    pub fn stdin_bytes(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.stdin = Some(bytes.into());
+        self
+    }
+
+    /// Keeps the exact output bytes in `CommandResult::stdout_bytes` and
+    /// `stderr_bytes` (including the `partial` result of a timeout) next to
+    /// the lossy strings.
+    pub fn raw_output(mut self, raw: bool) -> Self {
+        self.raw_output = raw;
+        self
+    }
+
+    /// Spawns the command without waiting for it, with stdout/stderr piped.
+    ///
+    /// `run()` is built on this; callers that need to keep a handle (for
+    /// example to kill a long-running process, as the `Watcher` does) can
+    /// call it directly and later turn the child into a `CommandResult`
+    /// with `collect_result`.
+    pub fn spawn(&self) -> Result<Child, CommandError> {
+        self.validate()?;
         let mut cmd = Command::new(&self.command);
 
         // Configure the command
@@ -105,46 +257,516 @@ impl CommandBuilder {
         if let Some(dir) = &self.current_dir {
             cmd.current_dir(dir);
         }
+        if self.stdin.is_some() {
+            cmd.stdin(Stdio::piped());
+        }
+
+        let mut child = cmd.spawn().map_err(|source| CommandError::LaunchFailed {
+            command: self.command.clone(),
+            source,
+        })?;
+
+        if let (Some(bytes), Some(mut stdin)) = (self.stdin.clone(), child.stdin.take()) {
+            // Write from another thread: if we wrote here, a child that
+            // fills its stdout pipe before reading all of stdin would block
+            // on us while we block on it. Dropping `stdin` afterwards closes
+            // the pipe so the child sees EOF. A child that exits without
+            // reading everything makes the write fail, which is fine.
+            std::thread::spawn(move || {
+                let _ = stdin.write_all(&bytes);
+            });
+        }
+
+        Ok(child)
+    }
+
+    /// Catches mistakes that `std::process::Command` would otherwise turn
+    /// into confusing launch errors (or, for bad env keys, silently ignore).
+    fn validate(&self) -> Result<(), CommandError> {
+        if self.command.trim().is_empty() {
+            return Err(CommandError::InvalidConfiguration(
+                "command is empty".to_string(),
+            ));
+        }
+        if let Some((key, _)) = self
+            .envs
+            .iter()
+            .find(|(key, _)| key.is_empty() || key.contains('='))
+        {
+            return Err(CommandError::InvalidConfiguration(format!(
+                "invalid environment variable name {:?}",
+                key
+            )));
+        }
+        if self.timeout == Some(Duration::ZERO) {
+            return Err(CommandError::InvalidConfiguration(
+                "timeout must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Executes the command.
+    pub fn run(&self) -> Result<CommandResult, CommandError> {
+        self.run_raw().map(|raw| self.decode(raw))
+    }
+
+    /// Turns raw output into a `CommandResult`, keeping the bytes if
+    /// `raw_output` was asked for.
+    fn decode(&self, raw: RawCommandResult) -> CommandResult {
+        if self.raw_output {
+            raw.into_lossy_with_bytes()
+        } else {
+            raw.into_lossy()
+        }
+    }
+
+    /// Executes the command, keeping its output as raw bytes.
+    ///
+    /// `run()` is this plus a lossy UTF-8 decode. On a timeout, `partial`
+    /// in the error is decoded the same way (keeping the bytes only with
+    /// `raw_output(true)`).
+    pub fn run_raw(&self) -> Result<RawCommandResult, CommandError> {
+        // Spawn the child process, starting the clock just before.
+        let start = Instant::now();
+        let child = self.spawn()?;
+
+        let Some(timeout) = self.timeout else {
+            // --- No Timeout Logic ---
+            return finish(child, start, None);
+        };
+
+        // --- Timeout Logic ---
+        // Drain the pipes while we poll, so a chatty child can't fill them
+        // and stall until the timeout.
+        let mut running = Running::new(child, start);
+        loop {
+            // Check if the process has finished
+            if let Some(exit) = try_reap(&mut running.child)? {
+                return running.finish(Some(exit));
+            }
+            if start.elapsed() > timeout {
+                // Timeout exceeded, kill the process and keep whatever it
+                // managed to print. Anything it started in the background
+                // may still hold the pipes open, so don't wait for EOF
+                // longer than `KILL_DRAIN_TIMEOUT`.
+                running.child.kill()?;
+                return Err(CommandError::TimedOut {
+                    command: self.command.clone(),
+                    timeout,
+                    partial: running
+                        .finish_within(KILL_DRAIN_TIMEOUT)
+                        .map(|raw| Box::new(self.decode(raw)))
+                        .ok(),
+                });
+            }
+            // Sleep for a short duration before checking again
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+/// Waits for a spawned child and gathers its exit code and output.
+///
+/// A bare `Child` doesn't know when it was spawned, so `duration` counts
+/// from this call.
+pub fn collect_result(child: Child) -> Result<CommandResult, CommandError> {
+    collect_raw_result(child).map(RawCommandResult::into_lossy)
+}
+
+/// Like `collect_result`, but keeps the output as raw bytes.
+pub fn collect_raw_result(child: Child) -> Result<RawCommandResult, CommandError> {
+    finish(child, Instant::now(), None)
+}
+
+/// How long a timed-out command's output may keep trickling in after the
+/// kill. A grandchild that inherited stdout (`sh -c "server &"`) keeps the
+/// pipe open after its parent dies; past this, we settle for what was read.
+pub const KILL_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How a reaped child ended.
+struct Exit {
+    exit_code: i32,
+    max_rss_kb: Option<u64>,
+}
+
+/// Reads the child's remaining output and, unless `exit` says it was
+/// already reaped, waits for it. `started` is when the child was spawned.
+fn finish(
+    child: Child,
+    started: Instant,
+    exit: Option<Exit>,
+) -> Result<RawCommandResult, CommandError> {
+    Running::new(child, started).finish(exit)
+}
+
+/// A spawned child whose stdout and stderr are already being drained.
+///
+/// Draining must start as soon as the child exists, not once it exits: a
+/// child that writes more than a pipe buffer (about 64 KiB on Linux) blocks
+/// until someone reads, so it would never exit and we would never read.
+struct Running {
+    child: Child,
+    started: Instant,
+    stdout: Reader,
+    stderr: Reader,
+}
+
+impl Running {
+    fn new(mut child: Child, started: Instant) -> Self {
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+        Running {
+            child,
+            started,
+            stdout,
+            stderr,
+        }
+    }
+
+    /// Waits for the child unless `exit` says it was already reaped, then
+    /// collects the output.
+    fn finish(mut self, exit: Option<Exit>) -> Result<RawCommandResult, CommandError> {
+        let exit = match exit {
+            Some(exit) => exit,
+            None => reap(&mut self.child)?,
+        };
+        let duration = self.started.elapsed();
+
+        Ok(RawCommandResult {
+            exit_code: exit.exit_code,
+            stdout: self.stdout.join()?,
+            stderr: self.stderr.join()?,
+            duration,
+            max_rss_kb: exit.max_rss_kb,
+        })
+    }
+
+    /// Reaps the (already killed) child, then gives the readers up to
+    /// `grace` to reach EOF. A reader still blocked after that is left to
+    /// finish on its own, and its output so far is used instead.
+    fn finish_within(mut self, grace: Duration) -> Result<RawCommandResult, CommandError> {
+        let exit = reap(&mut self.child)?;
+        let duration = self.started.elapsed();
+
+        let deadline = Instant::now() + grace;
+        while !(self.stdout.is_finished() && self.stderr.is_finished()) && Instant::now() < deadline
+        {
+            std::thread::sleep(Duration::from_millis(10));
+        }
 
-        // Spawn the child process
-        let mut child = cmd.spawn()?;
-
-        if let Some(timeout) = self.timeout {
-            // --- Timeout Logic ---
-            let start = Instant::now();
-            loop {
-                // Check if the process has finished
-                match child.try_wait()? {
-                    Some(status) => { // Process finished
-                        // Collect output after process has exited
-                        let output = child.wait_with_output()?;
-                        return Ok(CommandResult {
-                            exit_code: status.code().unwrap_or(1),
-                            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-                            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-                        });
+        Ok(RawCommandResult {
+            exit_code: exit.exit_code,
+            stdout: self.stdout.join_or_snapshot()?,
+            stderr: self.stderr.join_or_snapshot()?,
+            duration,
+            max_rss_kb: exit.max_rss_kb,
+        })
+    }
+}
+
+/// A thread copying one of the child's pipes into a shared buffer, so the
+/// output so far can be read without waiting for EOF.
+struct Reader {
+    bytes: Arc<Mutex<Vec<u8>>>,
+    handle: std::thread::JoinHandle<std::io::Result<()>>,
+}
+
+impl Reader {
+    fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Waits for EOF and returns everything read.
+    fn join(self) -> std::io::Result<Vec<u8>> {
+        let Reader { bytes, handle } = self;
+        handle
+            .join()
+            .unwrap_or_else(|_| Err(std::io::Error::other("output reader panicked")))?;
+        let mut bytes = bytes.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(std::mem::take(&mut *bytes))
+    }
+
+    /// Everything read if the reader is done, otherwise what it has read
+    /// so far (the thread keeps running until the pipe closes).
+    fn join_or_snapshot(self) -> std::io::Result<Vec<u8>> {
+        if self.is_finished() {
+            self.join()
+        } else {
+            Ok(self.snapshot())
+        }
+    }
+
+    fn snapshot(&self) -> Vec<u8> {
+        self.bytes.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> Reader {
+    let bytes = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&bytes);
+    let handle = std::thread::spawn(move || {
+        let Some(mut pipe) = pipe else {
+            return Ok(());
+        };
+        let mut chunk = [0u8; 8192];
+        loop {
+            match pipe.read(&mut chunk) {
+                Ok(0) => return Ok(()),
+                Ok(n) => sink
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    });
+    Reader { bytes, handle }
+}
+
+/// Blocks until the child exits and reaps it.
+fn reap(child: &mut Child) -> std::io::Result<Exit> {
+    #[cfg(unix)]
+    if let Some(exit) = rusage::wait4(child, 0)? {
+        return Ok(exit);
+    }
+    let status = child.wait()?;
+    Ok(Exit {
+        exit_code: status.code().unwrap_or(1),
+        max_rss_kb: None,
+    })
+}
+
+/// Reaps the child if it has exited, without blocking.
+fn try_reap(child: &mut Child) -> std::io::Result<Option<Exit>> {
+    #[cfg(unix)]
+    {
+        match rusage::wait4(child, libc::WNOHANG) {
+            Ok(Some(exit)) => return Ok(Some(exit)),
+            Ok(None) if !rusage::already_reaped(child) => return Ok(None),
+            Ok(None) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(child.try_wait()?.map(|status| Exit {
+        exit_code: status.code().unwrap_or(1),
+        max_rss_kb: None,
+    }))
+}
+
+#[cfg(unix)]
+mod rusage {
+    //! `wait4(2)`: `waitpid` plus the child's resource usage.
+
+    use super::Exit;
+    use std::process::Child;
+
+    /// Waits for `child` with `wait4`. `Ok(None)` means it is still running
+    /// (only possible with `WNOHANG`) or that `std` already reaped it, in
+    /// which case the kernel no longer has its usage and the caller falls
+    /// back to `Child::wait`.
+    pub(super) fn wait4(child: &Child, options: libc::c_int) -> std::io::Result<Option<Exit>> {
+        let pid = child.id() as libc::pid_t;
+        let mut status: libc::c_int = 0;
+        // SAFETY: `rusage` is a plain C struct; all-zero bytes are a valid value.
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        loop {
+            // SAFETY: both out-pointers refer to live, writable locals.
+            let ret = unsafe { libc::wait4(pid, &mut status, options, &mut usage) };
+            match ret {
+                0 => return Ok(None),
+                -1 => {
+                    let err = std::io::Error::last_os_error();
+                    match err.raw_os_error() {
+                        Some(libc::EINTR) => continue,
+                        Some(libc::ECHILD) => return Ok(None),
+                        _ => return Err(err),
                     }
-                    None => { // Process still running
-                        if start.elapsed() > timeout {
-                            // Timeout exceeded, kill the process
-                            child.kill()?;
-                            return Err(CommandError::Timeout(timeout));
+                }
+                _ => {
+                    let exit_code = if libc::WIFEXITED(status) {
+                        libc::WEXITSTATUS(status)
+                    } else {
+                        1 // killed by a signal: no exit code, same as `run`
+                    };
+                    return Ok(Some(Exit {
+                        exit_code,
+                        max_rss_kb: Some(max_rss_kb(&usage)),
+                    }));
+                }
+            }
+        }
+    }
+
+    /// Whether `wait4` returned `None` because the child is gone rather
+    /// than still running.
+    pub(super) fn already_reaped(child: &Child) -> bool {
+        let pid = child.id() as libc::pid_t;
+        // SAFETY: signal 0 only checks that the process exists.
+        unsafe { libc::kill(pid, 0) == -1 }
+    }
+
+    // Linux reports `ru_maxrss` in kilobytes, macOS in bytes.
+    fn max_rss_kb(usage: &libc::rusage) -> u64 {
+        let raw = usage.ru_maxrss.max(0) as u64;
+        if cfg!(any(target_os = "macos", target_os = "ios")) {
+            raw / 1024
+        } else {
+            raw
+        }
+    }
+}
+
+/// Runs `stages` as a pipeline, like `a | b | c` in a shell.
+///
+/// Each stage's stdout bytes become the next stage's stdin unchanged, so
+/// binary data survives the trip. Stages run one after another with the
+/// whole output buffered in between, which is simple but means the
+/// pipeline holds one stage's output in memory at a time.
+///
+/// As in a shell without `pipefail`, a failing middle stage doesn't stop
+/// the pipeline: the result is the last stage's. Launch and I/O errors do
+/// stop it. Any `stdin_bytes` on the first stage is kept.
+///
+/// `duration` is the sum over all stages and `max_rss_kb` the largest
+/// peak of any stage (`None` if any stage's is unknown).
+pub fn pipe(stages: &[CommandBuilder]) -> Result<RawCommandResult, CommandError> {
+    let (first, rest) = stages
+        .split_first()
+        .ok_or_else(|| CommandError::InvalidConfiguration("pipeline has no stages".to_string()))?;
+
+    let mut result = first.run_raw()?;
+    for stage in rest {
+        let next = stage.clone().stdin_bytes(result.stdout).run_raw()?;
+        result = RawCommandResult {
+            duration: result.duration + next.duration,
+            max_rss_kb: result
+                .max_rss_kb
+                .zip(next.max_rss_kb)
+                .map(|(a, b)| a.max(b)),
+            ..next
+        };
+    }
+    Ok(result)
+}
+
+/// Why `parse_command_line` rejected its input.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseError {
+    /// The line has no words at all.
+    #[error("command line is empty")]
+    Empty,
+    /// A quote opened at byte `position` is never closed.
+    #[error("unterminated {quote} quote starting at byte {position}")]
+    UnterminatedQuote { quote: char, position: usize },
+    /// The line ends with a backslash, so there is nothing to escape.
+    #[error("trailing backslash at end of command line")]
+    TrailingBackslash,
+}
+
+/// Splits `input` into a program and its arguments, the way a POSIX shell
+/// splits words - without running a shell.
+///
+/// - Unquoted whitespace separates words.
+/// - `'single quotes'` keep everything inside literally.
+/// - `"double quotes"` keep everything literally except `\"`, `\\`, `\$`
+///   and `` \` ``, where the backslash escapes the next character. Any other
+///   backslash stays as typed, so `"a\nb"` is the four characters `a\nb`.
+/// - Outside quotes, a backslash makes the next character literal.
+/// - Quotes join with the text around them: `pre"fix"ed` is one word, and
+///   `""` on its own is an empty argument.
+///
+/// Everything else a shell would interpret - `$VAR`, `*`, `|`, `>`, `;` -
+/// is passed through as plain text. That is the point: a string from a
+/// config file or a user can't smuggle in a second command.
+pub fn parse_command_line(input: &str) -> Result<(String, Vec<String>), ParseError> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    // True once the current word has started, even if it is still empty
+    // (after `""`), so empty arguments survive.
+    let mut in_word = false;
+    let mut chars = input.char_indices();
+
+    while let Some((position, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, c)) => word.push(c),
+                        None => {
+                            return Err(ParseError::UnterminatedQuote {
+                                quote: '\'',
+                                position,
+                            })
                         }
-                        // Sleep for a short duration before checking again
-                        std::thread::sleep(Duration::from_millis(50));
                     }
                 }
             }
-        } else {
-            // --- No Timeout Logic ---
-            let output = child.wait_with_output()?;
-            Ok(CommandResult {
-                exit_code: output.status.code().unwrap_or(1),
-                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            })
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c @ ('"' | '\\' | '$' | '`'))) => word.push(c),
+                            Some((_, c)) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => {
+                                return Err(ParseError::UnterminatedQuote {
+                                    quote: '"',
+                                    position,
+                                })
+                            }
+                        },
+                        Some((_, c)) => word.push(c),
+                        None => {
+                            return Err(ParseError::UnterminatedQuote {
+                                quote: '"',
+                                position,
+                            })
+                        }
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                match chars.next() {
+                    Some((_, c)) => word.push(c),
+                    None => return Err(ParseError::TrailingBackslash),
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
         }
     }
+    if in_word {
+        words.push(word);
+    }
+
+    let mut words = words.into_iter();
+    let command = words.next().ok_or(ParseError::Empty)?;
+    Ok((command, words.collect()))
+}
+
+/// Parses `line` with `parse_command_line` and runs it.
+///
+/// Shorthand for `CommandBuilder::from_line(line)?.run()` when no timeout,
+/// environment or working directory is needed.
+pub fn run_line(line: &str) -> Result<CommandResult, CommandError> {
+    CommandBuilder::from_line(line)?.run()
 }
 
 /// Represents a single named task to be run.
@@ -162,11 +784,53 @@ impl Task {
 /// Runs a sequence of tasks.
 pub struct TaskRunner {
     tasks: Vec<Task>,
+    max_parallel: Option<usize>,
+    fail_fast: bool,
+}
+
+/// The outcome of one task from `TaskRunner::run_all_pooled`.
+#[derive(Debug)]
+pub struct TaskResult {
+    /// Position of the task in the list given to `TaskRunner::new`.
+    pub index: usize,
+    pub name: String,
+    /// `None` when the task was never started because an earlier one
+    /// failed with `fail_fast` on.
+    pub result: Option<Result<CommandResult, CommandError>>,
+}
+
+impl TaskResult {
+    /// Ran and exited with status 0.
+    pub fn succeeded(&self) -> bool {
+        matches!(&self.result, Some(Ok(res)) if res.exit_code == 0)
+    }
+
+    pub fn skipped(&self) -> bool {
+        self.result.is_none()
+    }
 }
 
 impl TaskRunner {
     pub fn new(tasks: Vec<Task>) -> Self {
-        TaskRunner { tasks }
+        TaskRunner {
+            tasks,
+            max_parallel: None,
+            fail_fast: false,
+        }
+    }
+
+    /// Caps how many tasks `run_all_pooled` keeps in flight. Defaults to the
+    /// pool's worker count; 0 is treated as 1.
+    pub fn max_parallel(mut self, max_parallel: usize) -> Self {
+        self.max_parallel = Some(max_parallel.max(1));
+        self
+    }
+
+    /// Makes `run_all_pooled` stop starting tasks after the first failure.
+    /// Tasks already running are allowed to finish.
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
     }
 
     /// Runs all tasks in sequence, stopping if one fails.
@@ -175,6 +839,9 @@ impl TaskRunner {
         for task in &self.tasks {
             println!("Running task: \"{}\"...", task.name);
             let result = task.builder.run();
+            if let Ok(res) = &result {
+                println!("Task \"{}\": {}", task.name, res.summary());
+            }
             match &result {
                 Ok(res) if res.exit_code == 0 => {
                     results.push(result);
@@ -184,7 +851,8 @@ impl TaskRunner {
                     println!("Task \"{}\" failed, stopping runner.", task.name);
                     break;
                 }
-                Err(_) => { // I/O or timeout error
+                Err(_) => {
+                    // Launch, I/O, or timeout error
                     results.push(result);
                     println!("Task \"{}\" failed, stopping runner.", task.name);
                     break;
@@ -193,4 +861,571 @@ impl TaskRunner {
         }
         results
     }
+
+    /// Runs the tasks concurrently on `pool` and returns one `TaskResult`
+    /// per task, in the original order no matter which finished first.
+    ///
+    /// The tasks must be independent: nothing orders them beyond "started
+    /// in list order". A task counts as failed on the same terms as in
+    /// `run`: an error or a non-zero exit code.
+    ///
+    /// ## How it works
+    /// Each job gets a clone of a channel `Sender` and sends back
+    /// `(index, result)`. The calling thread keeps at most `max_parallel`
+    /// jobs submitted and only submits the next one when a result arrives,
+    /// which is also where `fail_fast` gets its chance to stop submitting.
+    ///
+    /// A job that panics (or is dropped unrun by a dying pool) still
+    /// reports, as `CommandError::JobLost`, via `Report`'s `Drop`. As a
+    /// second line of defence the runner hangs up its own `Sender` once it
+    /// has nothing left to submit, so if the outstanding jobs' senders all
+    /// vanish anyway, `recv` fails instead of blocking forever.
+    pub fn run_all_pooled(&self, pool: &ThreadPool) -> Vec<TaskResult> {
+        let limit = self.max_parallel.unwrap_or(pool.worker_count()).max(1);
+        let (tx, rx) = mpsc::channel();
+        let mut tx = Some(tx);
+        let mut results: Vec<Option<Result<CommandResult, CommandError>>> =
+            self.tasks.iter().map(|_| None).collect();
+        let mut next = 0;
+        let mut in_flight = 0;
+        let mut failed = false;
+
+        loop {
+            let stopped = failed && self.fail_fast;
+            while in_flight < limit && next < self.tasks.len() && !stopped {
+                let Some(tx) = &tx else { break };
+                let report = Report {
+                    index: next,
+                    tx: Some(tx.clone()),
+                };
+                let builder = self.tasks[next].builder.clone();
+                pool.execute(move || report.send(builder.run()));
+                next += 1;
+                in_flight += 1;
+            }
+            if next == self.tasks.len() || stopped {
+                tx = None;
+            }
+            if in_flight == 0 {
+                break;
+            }
+
+            let Ok((index, result)) = rx.recv() else {
+                // Every outstanding job is gone without a word
+                for result in results[..next].iter_mut().filter(|r| r.is_none()) {
+                    *result = Some(Err(CommandError::JobLost));
+                }
+                break;
+            };
+            in_flight -= 1;
+            if let Ok(res) = &result {
+                println!("Task \"{}\": {}", self.tasks[index].name, res.summary());
+            }
+            if !matches!(&result, Ok(res) if res.exit_code == 0) {
+                failed = true;
+            }
+            results[index] = Some(result);
+        }
+
+        self.tasks
+            .iter()
+            .zip(results)
+            .enumerate()
+            .map(|(index, (task, result))| TaskResult {
+                index,
+                name: task.name.clone(),
+                result,
+            })
+            .collect()
+    }
+}
+
+/// A pooled task's way back to the runner. Dropping it without calling
+/// `send` (the job panicked, or was never run) reports `JobLost` instead.
+struct Report {
+    index: usize,
+    tx: Option<mpsc::Sender<(usize, Result<CommandResult, CommandError>)>>,
+}
+
+impl Report {
+    fn send(mut self, result: Result<CommandResult, CommandError>) {
+        if let Some(tx) = self.tx.take() {
+            let _ = tx.send((self.index, result));
+        }
+    }
+}
+
+impl Drop for Report {
+    fn drop(&mut self) {
+        if let Some(tx) = self.tx.take() {
+            let _ = tx.send((self.index, Err(CommandError::JobLost)));
+        }
+    }
+}
+
+// ============================================================================
+// WATCH MODE
+// ============================================================================
+// A tiny `cargo watch`: re-run a command whenever watched files change.
+//
+// Real tools use OS notification APIs (inotify, FSEvents) via the `notify`
+// crate. We *poll* instead: every `poll_interval` we take a snapshot of
+// (path -> modified time, size) and diff it against the previous one. It
+// costs a directory walk per tick, but it is portable and needs nothing
+// beyond `std`.
+//
+// Two details make it pleasant to use:
+//
+// - **Debouncing.** Editors often write a file several times when saving
+//   (temp file, rename, touch). We collect changes and only fire once the
+//   files have been quiet for the `debounce` window, so one save = one run.
+// - **Cancel-previous.** If files change while the last run is still going,
+//   its result is already stale. We kill it before starting the new run.
+//
+// Time is injected everywhere (`poll_once(now)` and the `Clock` trait) so
+// the tests can drive the watcher through minutes of "time" without ever
+// calling `thread::sleep`.
+
+/// Source of time for `Watcher::run_loop`.
+pub trait Clock {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+/// The real clock: `Instant::now()` and `thread::sleep`.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// A fake clock for tests: `sleep` just advances the time instantly.
+///
+/// Clones share the same time (it lives behind an `Rc<Cell<_>>`), so a test
+/// can keep a handle and inspect how far the watcher advanced it.
+#[derive(Clone)]
+pub struct ManualClock {
+    now: Rc<Cell<Instant>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock {
+            now: Rc::new(Cell::new(Instant::now())),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+/// Result of a single poll.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// Files changed and the debounce window has passed. `paths` holds
+    /// every file created, modified, or deleted since the last event.
+    Changed { paths: Vec<PathBuf> },
+    /// Nothing to act on yet (no changes, or changes still settling).
+    Unchanged,
+}
+
+/// What `run_loop` reports to its callback.
+#[derive(Debug)]
+pub enum WatchRunEvent {
+    /// A change was detected and the command is about to start.
+    Triggered { paths: Vec<PathBuf> },
+    /// A run completed on its own.
+    Finished(CommandResult),
+    /// A run was killed because newer changes arrived.
+    Cancelled,
+    /// The command could not be spawned or collected.
+    Failed(CommandError),
+}
+
+/// What we remember about a file between polls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    size: u64,
+}
+
+/// Polls files and re-runs a command when they change.
+pub struct Watcher {
+    command: CommandBuilder,
+    paths: Vec<PathBuf>,
+    recursive: bool,
+    poll_interval: Duration,
+    debounce: Duration,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    clock: Box<dyn Clock>,
+    // `None` until the first poll records the baseline.
+    snapshot: Option<HashMap<PathBuf, FileStamp>>,
+    pending: BTreeSet<PathBuf>,
+    last_change: Option<Instant>,
+    // The current run, timed from its spawn in real time (not `clock`: the
+    // duration describes the process, not the watcher's schedule).
+    running: Option<Running>,
+}
+
+impl Watcher {
+    /// Creates a watcher for `command` with no paths yet, recursive walking,
+    /// a 500ms poll interval, and a 100ms debounce window.
+    pub fn new(command: CommandBuilder) -> Self {
+        Watcher {
+            command,
+            paths: Vec::new(),
+            recursive: true,
+            poll_interval: Duration::from_millis(500),
+            debounce: Duration::from_millis(100),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            clock: Box::new(SystemClock),
+            snapshot: None,
+            pending: BTreeSet::new(),
+            last_change: None,
+            running: None,
+        }
+    }
+
+    /// Watches a file or directory.
+    pub fn watch(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    /// Whether directories are walked recursively (default `true`).
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    pub fn debounce(mut self, window: Duration) -> Self {
+        self.debounce = window;
+        self
+    }
+
+    /// Only watch files matching this pattern. With no include patterns,
+    /// every file is included.
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    /// Ignore files matching this pattern. Excludes win over includes.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Replaces the clock used by `run_loop`.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Takes a snapshot and compares it with the previous one.
+    ///
+    /// The first call only records the baseline and returns `Unchanged`.
+    /// Changes are held back until `now` is at least `debounce` past the
+    /// most recent change; several quick changes are therefore reported
+    /// together as one `Changed` event.
+    pub fn poll_once(&mut self, now: Instant) -> WatchEvent {
+        let current = self.take_snapshot();
+
+        if let Some(previous) = self.snapshot.replace(current) {
+            let current = self.snapshot.as_ref().expect("just stored");
+            let changed = diff_snapshots(&previous, current);
+            if !changed.is_empty() {
+                self.pending.extend(changed);
+                self.last_change = Some(now);
+            }
+        }
+
+        match self.last_change {
+            Some(last) if now.saturating_duration_since(last) >= self.debounce => {
+                self.last_change = None;
+                let paths = std::mem::take(&mut self.pending).into_iter().collect();
+                WatchEvent::Changed { paths }
+            }
+            _ => WatchEvent::Unchanged,
+        }
+    }
+
+    /// Polls up to `max_iterations` times, re-running the command on change.
+    ///
+    /// Each iteration: report a run that finished on its own, poll, and on
+    /// `Changed` kill any still-running previous run before spawning the
+    /// next one. The clock sleeps `poll_interval` between iterations.
+    ///
+    /// When the iterations run out, the last run is waited for (not killed)
+    /// so its result is always reported.
+    pub fn run_loop<F>(&mut self, max_iterations: usize, mut on_event: F)
+    where
+        F: FnMut(&WatchRunEvent),
+    {
+        for iteration in 0..max_iterations {
+            if iteration > 0 {
+                self.clock.sleep(self.poll_interval);
+            }
+
+            self.reap_finished(&mut on_event);
+
+            let now = self.clock.now();
+            if let WatchEvent::Changed { paths } = self.poll_once(now) {
+                if let Some(mut run) = self.running.take() {
+                    // The child may have exited a moment ago; either way it
+                    // is gone after this, and `wait` reaps the zombie. The
+                    // output readers finish on their own once the pipes
+                    // close.
+                    let _ = run.child.kill();
+                    let _ = run.child.wait();
+                    on_event(&WatchRunEvent::Cancelled);
+                }
+
+                on_event(&WatchRunEvent::Triggered { paths });
+                let started = Instant::now();
+                match self.command.spawn() {
+                    Ok(child) => self.running = Some(Running::new(child, started)),
+                    Err(e) => on_event(&WatchRunEvent::Failed(e)),
+                }
+            }
+        }
+
+        if let Some(run) = self.running.take() {
+            on_event(&to_run_event(run.finish(None)));
+        }
+    }
+
+    fn reap_finished<F: FnMut(&WatchRunEvent)>(&mut self, on_event: &mut F) {
+        let exit = match self.running.as_mut().map(|run| try_reap(&mut run.child)) {
+            Some(Ok(Some(exit))) => exit,
+            Some(Err(e)) => {
+                self.running = None;
+                on_event(&WatchRunEvent::Failed(CommandError::IoError(e)));
+                return;
+            }
+            _ => return,
+        };
+        if let Some(run) = self.running.take() {
+            on_event(&to_run_event(run.finish(Some(exit))));
+        }
+    }
+
+    fn take_snapshot(&self) -> HashMap<PathBuf, FileStamp> {
+        let mut snapshot = HashMap::new();
+        for root in &self.paths {
+            if root.is_dir() {
+                self.walk(root, root, &mut snapshot);
+            } else if let Ok(meta) = fs::metadata(root) {
+                let name = root.file_name().map(PathBuf::from).unwrap_or_default();
+                if self.is_watched(&name) {
+                    snapshot.insert(root.clone(), stamp(&meta));
+                }
+            }
+        }
+        snapshot
+    }
+
+    fn walk(&self, root: &Path, dir: &Path, out: &mut HashMap<PathBuf, FileStamp>) {
+        // Files can vanish mid-walk; unreadable entries are simply skipped
+        // and show up as "deleted" if they were present before.
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else { continue };
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            if meta.is_dir() {
+                if self.recursive && !self.is_pruned(&relative) {
+                    self.walk(root, &path, out);
+                }
+            } else if self.is_watched(&relative) {
+                out.insert(path, stamp(&meta));
+            }
+        }
+    }
+
+    // Whether an exclude pattern covers everything under this directory, so
+    // walking it (think `target/**` on a big build tree) would be wasted
+    // work. Only `<dir pattern>/**` qualifies: `**` matches any rest of the
+    // path, so if `<dir pattern>` matches the directory, every file below
+    // it is excluded anyway.
+    fn is_pruned(&self, relative_dir: &Path) -> bool {
+        let dir: Vec<char> = relative_dir
+            .to_string_lossy()
+            .replace('\\', "/")
+            .chars()
+            .collect();
+        self.exclude.iter().any(|pattern| {
+            pattern.strip_suffix("/**").is_some_and(|prefix| {
+                let prefix: Vec<char> = prefix.chars().collect();
+                glob_match_chars(&prefix, &dir)
+            })
+        })
+    }
+
+    fn is_watched(&self, relative: &Path) -> bool {
+        let path = relative.to_string_lossy().replace('\\', "/");
+        let included = self.include.is_empty() || self.include.iter().any(|p| glob_match(p, &path));
+        included && !self.exclude.iter().any(|p| glob_match(p, &path))
+    }
+}
+
+fn stamp(meta: &fs::Metadata) -> FileStamp {
+    FileStamp {
+        modified: meta.modified().ok(),
+        size: meta.len(),
+    }
+}
+
+fn diff_snapshots(
+    previous: &HashMap<PathBuf, FileStamp>,
+    current: &HashMap<PathBuf, FileStamp>,
+) -> Vec<PathBuf> {
+    let modified_or_created = current
+        .iter()
+        .filter(|(path, stamp)| previous.get(*path) != Some(stamp))
+        .map(|(path, _)| path.clone());
+    let deleted = previous
+        .keys()
+        .filter(|path| !current.contains_key(*path))
+        .cloned();
+    modified_or_created.chain(deleted).collect()
+}
+
+fn to_run_event(result: Result<RawCommandResult, CommandError>) -> WatchRunEvent {
+    match result {
+        Ok(result) => WatchRunEvent::Finished(result.into_lossy()),
+        Err(e) => WatchRunEvent::Failed(e),
+    }
+}
+
+/// Glob-lite matching for watch patterns.
+///
+/// - `*` matches any run of characters except `/`
+/// - `**` matches any run of characters including `/`; `**/` may also
+///   match nothing, so `**/*.rs` matches `main.rs` and `src/bin/main.rs`
+/// - everything else matches literally
+///
+/// Like `.gitignore`, a pattern with no `/` is matched against the file
+/// name alone, so `*.rs` matches `src/main.rs`. Patterns containing `/`
+/// must match the whole relative path, so `target/**` only matches files
+/// under a top-level `target` directory.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let subject = if pattern.contains('/') {
+        path
+    } else {
+        path.rsplit('/').next().unwrap_or(path)
+    };
+    let p: Vec<char> = pattern.chars().collect();
+    let s: Vec<char> = subject.chars().collect();
+    glob_match_chars(&p, &s)
+}
+
+/// One step of a glob pattern.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum GlobToken {
+    Literal(char),
+    /// `*`: any run of characters without `/`.
+    Star,
+    /// `**` not followed by `/`: any run of characters.
+    AnyPath,
+    /// `**/`: nothing, or any run of characters ending in `/`.
+    AnyDirs,
+}
+
+// Runs the pattern as a small NFA: `states[i]` means "the first `i` tokens
+// can match what we've read so far". Each character advances every live
+// state at once, so the cost is O(pattern * path) with no recursion and no
+// backtracking, even for patterns like `**a**a**a**b`.
+fn glob_match_chars(p: &[char], s: &[char]) -> bool {
+    let mut tokens = Vec::with_capacity(p.len());
+    let mut i = 0;
+    while i < p.len() {
+        let token = match p[i..] {
+            ['*', '*', '/', ..] => GlobToken::AnyDirs,
+            ['*', '*', ..] => GlobToken::AnyPath,
+            ['*', ..] => GlobToken::Star,
+            [c, ..] => GlobToken::Literal(c),
+            [] => unreachable!(),
+        };
+        i += match token {
+            GlobToken::AnyDirs => 3,
+            GlobToken::AnyPath => 2,
+            _ => 1,
+        };
+        tokens.push(token);
+    }
+
+    // Every wildcard may match nothing, so a live state before one makes
+    // the state after it live too. Tokens only ever point forward, so one
+    // pass in order is enough.
+    let close = |states: &mut Vec<bool>| {
+        for (i, token) in tokens.iter().enumerate() {
+            if states[i] && !matches!(token, GlobToken::Literal(_)) {
+                states[i + 1] = true;
+            }
+        }
+    };
+
+    let mut states = vec![false; tokens.len() + 1];
+    states[0] = true;
+    close(&mut states);
+    for &c in s {
+        let mut next = vec![false; tokens.len() + 1];
+        for (i, token) in tokens.iter().enumerate() {
+            if !states[i] {
+                continue;
+            }
+            match *token {
+                GlobToken::Literal(l) if l == c => next[i + 1] = true,
+                GlobToken::Literal(_) => {}
+                GlobToken::Star if c != '/' => next[i] = true,
+                GlobToken::Star => {}
+                GlobToken::AnyPath => next[i] = true,
+                GlobToken::AnyDirs => {
+                    next[i] = true;
+                    if c == '/' {
+                        next[i + 1] = true;
+                    }
+                }
+            }
+        }
+        close(&mut next);
+        if !next.contains(&true) {
+            return false;
+        }
+        states = next;
+    }
+    states[tokens.len()]
 }
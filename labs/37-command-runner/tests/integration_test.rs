@@ -1,8 +1,14 @@
 //! Integration tests for Lab 37: Command Runner
 
-use command_runner::solution::{CommandBuilder, CommandError};
-use std::time::Duration;
+use command_runner::solution::{
+    glob_match, parse_command_line, pipe, run_line, Clock, CommandBuilder, CommandError,
+    CommandResult, ManualClock, ParseError, RawCommandResult, Task, TaskRunner, WatchEvent,
+    WatchRunEvent, Watcher,
+};
+use std::fs;
+use std::time::{Duration, Instant};
 use tempfile::tempdir;
+use thread_pool::solution::ThreadPool;
 
 #[test]
 fn test_run_echo() {
@@ -72,8 +78,11 @@ fn test_command_timeout_fails() {
         .run();
 
     match result {
-        Err(CommandError::Timeout(_)) => {
-            // This is the expected outcome
+        Err(CommandError::TimedOut {
+            command, timeout, ..
+        }) => {
+            assert_eq!(command, "sleep");
+            assert_eq!(timeout, Duration::from_millis(100));
         }
         _ => panic!("Expected a timeout error"),
     }
@@ -82,5 +91,920 @@ fn test_command_timeout_fails() {
 #[test]
 fn test_nonexistent_command() {
     let result = CommandBuilder::new("a_truly_nonexistent_command_123").run();
-    assert!(matches!(result, Err(CommandError::Io(_))));
-}
\ No newline at end of file
+    match result {
+        Err(CommandError::LaunchFailed { command, source }) => {
+            assert_eq!(command, "a_truly_nonexistent_command_123");
+            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        }
+        other => panic!("Expected LaunchFailed, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_launch_failure_exposes_source() {
+    use std::error::Error;
+
+    let err = CommandBuilder::new("a_truly_nonexistent_command_123")
+        .run()
+        .unwrap_err();
+    let source = err.source().expect("launch failures keep the io::Error");
+    let io = source.downcast_ref::<std::io::Error>().unwrap();
+    assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_timeout_keeps_partial_output() {
+    let result = CommandBuilder::new("sh")
+        .arg("-c")
+        .arg("echo started; exec sleep 2")
+        .timeout(Duration::from_millis(300))
+        .run();
+
+    match result {
+        Err(CommandError::TimedOut {
+            partial: Some(partial),
+            ..
+        }) => assert_eq!(partial.stdout.trim(), "started"),
+        other => panic!("Expected TimedOut with output, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_timeout_does_not_wait_for_background_children() {
+    // The backgrounded sleep inherits stdout and outlives the killed shell
+    let start = Instant::now();
+    let result = CommandBuilder::new("sh")
+        .arg("-c")
+        .arg("echo started; sleep 5 & wait")
+        .timeout(Duration::from_millis(200))
+        .run();
+
+    assert!(
+        start.elapsed() < Duration::from_secs(3),
+        "{:?}",
+        start.elapsed()
+    );
+    match result {
+        Err(CommandError::TimedOut {
+            partial: Some(partial),
+            ..
+        }) => assert_eq!(partial.stdout.trim(), "started"),
+        other => panic!("Expected TimedOut with output, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_timeout_drains_output_while_waiting() {
+    // Far more than a pipe buffer, then exit well inside the timeout
+    let result = CommandBuilder::new("sh")
+        .arg("-c")
+        .arg("head -c 300000 /dev/zero")
+        .timeout(Duration::from_secs(10))
+        .run_raw()
+        .unwrap();
+    assert_eq!(result.stdout.len(), 300000);
+}
+
+#[test]
+fn test_invalid_configuration_is_rejected_before_spawning() {
+    let cases = [
+        CommandBuilder::new(""),
+        CommandBuilder::new("echo").env("BAD=KEY", "x"),
+        CommandBuilder::new("echo").env("", "x"),
+        CommandBuilder::new("echo").timeout(Duration::ZERO),
+    ];
+    for builder in cases {
+        assert!(matches!(
+            builder.run(),
+            Err(CommandError::InvalidConfiguration(_))
+        ));
+    }
+}
+
+#[test]
+fn test_command_error_display_is_stable() {
+    let launch = CommandError::LaunchFailed {
+        command: "cargo".into(),
+        source: std::io::Error::new(std::io::ErrorKind::NotFound, "not found"),
+    };
+    assert_eq!(launch.to_string(), "failed to launch `cargo`: not found");
+
+    let timed_out = CommandError::TimedOut {
+        command: "sleep".into(),
+        timeout: Duration::from_millis(1500),
+        partial: None,
+    };
+    assert_eq!(timed_out.to_string(), "`sleep` timed out after 1.5s");
+
+    let io = CommandError::IoError(std::io::Error::new(
+        std::io::ErrorKind::BrokenPipe,
+        "pipe closed",
+    ));
+    assert_eq!(io.to_string(), "I/O error: pipe closed");
+
+    let config = CommandError::InvalidConfiguration("command is empty".into());
+    assert_eq!(
+        config.to_string(),
+        "invalid configuration: command is empty"
+    );
+}
+
+// ============================================================================
+// RAW OUTPUT AND PIPES
+// ============================================================================
+
+/// `sh -c "printf '<format>'"`: printf turns octal escapes into raw bytes.
+fn printf(format: &str) -> CommandBuilder {
+    CommandBuilder::new("sh")
+        .arg("-c")
+        .arg(format!("printf '{}'", format))
+}
+
+#[test]
+fn test_run_raw_keeps_invalid_utf8() {
+    let result = printf("a\\377b\\000c\\200").run_raw().unwrap();
+    assert_eq!(result.exit_code, 0);
+    assert_eq!(result.stdout, b"a\xffb\x00c\x80");
+    assert_eq!(result.stdout.len(), 6);
+    assert!(result.stderr.is_empty());
+
+    // The lossy view replaces each invalid byte with U+FFFD
+    assert_eq!(result.stdout_lossy(), "a\u{FFFD}b\u{0}c\u{FFFD}");
+    let lossy = printf("a\\377b\\000c\\200").run().unwrap();
+    assert_eq!(lossy.stdout, result.stdout_lossy());
+    assert_eq!(lossy.stdout.matches('\u{FFFD}').count(), 2);
+}
+
+#[test]
+fn test_raw_output_keeps_bytes_next_to_lossy_strings() {
+    let result = printf("a\\377b\\200").raw_output(true).run().unwrap();
+    assert_eq!(result.stdout_bytes.as_deref(), Some(&b"a\xffb\x80"[..]));
+    assert_eq!(result.stdout, "a\u{FFFD}b\u{FFFD}");
+    assert_eq!(result.stderr_bytes, Some(Vec::new()));
+
+    // Off by default
+    let plain = printf("a\\377b").run().unwrap();
+    assert_eq!(plain.stdout_bytes, None);
+    assert_eq!(plain.stderr_bytes, None);
+}
+
+#[test]
+fn test_raw_output_applies_to_timeout_partial() {
+    let result = CommandBuilder::new("sh")
+        .arg("-c")
+        .arg("printf '\\377'; exec sleep 2")
+        .raw_output(true)
+        .timeout(Duration::from_millis(300))
+        .run();
+
+    match result {
+        Err(CommandError::TimedOut {
+            partial: Some(partial),
+            ..
+        }) => assert_eq!(partial.stdout_bytes, Some(vec![0xFF])),
+        other => panic!("Expected TimedOut with output, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_raw_result_lossy_conversion() {
+    let raw = RawCommandResult {
+        exit_code: 3,
+        stdout: b"ok\n".to_vec(),
+        stderr: vec![0xC3],
+        duration: Duration::from_millis(5),
+        max_rss_kb: Some(1024),
+    };
+    assert_eq!(raw.stderr_lossy(), "\u{FFFD}");
+    let lossy = raw.into_lossy();
+    assert_eq!(lossy.exit_code, 3);
+    assert_eq!(lossy.stdout, "ok\n");
+    assert_eq!(lossy.stderr, "\u{FFFD}");
+    assert_eq!(lossy.duration, Duration::from_millis(5));
+    assert_eq!(lossy.max_rss_kb, Some(1024));
+}
+
+#[test]
+fn test_stdin_bytes_are_fed_to_the_command() {
+    let result = CommandBuilder::new("cat")
+        .stdin_bytes(vec![0u8, 159, 146, 150, 10])
+        .run_raw()
+        .unwrap();
+    assert_eq!(result.stdout, vec![0u8, 159, 146, 150, 10]);
+}
+
+#[test]
+fn test_pipe_preserves_binary_data() {
+    // Every byte value, through two `cat` stages
+    let all_bytes: Vec<u8> = (0..=255).collect();
+    let result = pipe(&[
+        CommandBuilder::new("cat").stdin_bytes(all_bytes.clone()),
+        CommandBuilder::new("cat"),
+        CommandBuilder::new("cat"),
+    ])
+    .unwrap();
+    assert_eq!(result.stdout, all_bytes);
+
+    let result = pipe(&[
+        printf("\\377\\376\\000\\001"),
+        CommandBuilder::new("wc").arg("-c"),
+    ])
+    .unwrap();
+    assert_eq!(result.stdout_lossy().trim(), "4");
+}
+
+#[test]
+fn test_pipe_large_output_does_not_deadlock() {
+    // Far more than a pipe buffer (64 KiB on Linux) in both directions
+    let big: Vec<u8> = (0..1_000_000u32).map(|i| (i % 251) as u8).collect();
+    let result = pipe(&[
+        CommandBuilder::new("cat").stdin_bytes(big.clone()),
+        CommandBuilder::new("cat"),
+    ])
+    .unwrap();
+    assert_eq!(result.stdout, big);
+}
+
+#[test]
+fn test_pipe_result_is_last_stage() {
+    let result = pipe(&[
+        CommandBuilder::new("false"),
+        CommandBuilder::new("echo").arg("after"),
+    ])
+    .unwrap();
+    assert_eq!(result.exit_code, 0);
+    assert_eq!(result.stdout, b"after\n");
+
+    assert!(matches!(
+        pipe(&[]),
+        Err(CommandError::InvalidConfiguration(_))
+    ));
+    assert!(matches!(
+        pipe(&[
+            CommandBuilder::new("echo"),
+            CommandBuilder::new("no_such_command_12345")
+        ]),
+        Err(CommandError::LaunchFailed { .. })
+    ));
+}
+
+// ============================================================================
+// WATCH MODE
+// ============================================================================
+
+fn quiet_watcher(dir: &std::path::Path) -> Watcher {
+    Watcher::new(CommandBuilder::new("true"))
+        .watch(dir)
+        .debounce(Duration::ZERO)
+}
+
+#[test]
+fn test_watch_detects_modify_create_delete() {
+    let dir = tempdir().unwrap();
+    let a = dir.path().join("a.txt");
+    let b = dir.path().join("b.txt");
+    fs::write(&a, "one").unwrap();
+
+    let mut watcher = quiet_watcher(dir.path());
+    let now = Instant::now();
+    assert_eq!(
+        watcher.poll_once(now),
+        WatchEvent::Unchanged,
+        "first poll is the baseline"
+    );
+    assert_eq!(watcher.poll_once(now), WatchEvent::Unchanged);
+
+    fs::write(&a, "one two").unwrap();
+    assert_eq!(
+        watcher.poll_once(now),
+        WatchEvent::Changed {
+            paths: vec![a.clone()]
+        }
+    );
+
+    fs::write(&b, "new").unwrap();
+    assert_eq!(
+        watcher.poll_once(now),
+        WatchEvent::Changed {
+            paths: vec![b.clone()]
+        }
+    );
+
+    fs::remove_file(&a).unwrap();
+    assert_eq!(
+        watcher.poll_once(now),
+        WatchEvent::Changed { paths: vec![a] }
+    );
+    assert_eq!(watcher.poll_once(now), WatchEvent::Unchanged);
+}
+
+#[test]
+fn test_watch_non_recursive_ignores_subdirectories() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+
+    let mut watcher = quiet_watcher(dir.path()).recursive(false);
+    let now = Instant::now();
+    watcher.poll_once(now);
+
+    fs::write(dir.path().join("sub").join("deep.txt"), "x").unwrap();
+    assert_eq!(watcher.poll_once(now), WatchEvent::Unchanged);
+
+    fs::write(dir.path().join("top.txt"), "x").unwrap();
+    assert!(matches!(watcher.poll_once(now), WatchEvent::Changed { .. }));
+}
+
+#[test]
+fn test_watch_debounce_coalesces_rapid_changes() {
+    let dir = tempdir().unwrap();
+    let a = dir.path().join("a.txt");
+    let b = dir.path().join("b.txt");
+
+    let mut watcher = Watcher::new(CommandBuilder::new("true"))
+        .watch(dir.path())
+        .debounce(Duration::from_millis(100));
+    let t0 = Instant::now();
+    watcher.poll_once(t0);
+
+    fs::write(&a, "first").unwrap();
+    assert_eq!(
+        watcher.poll_once(t0),
+        WatchEvent::Unchanged,
+        "still inside debounce window"
+    );
+
+    fs::write(&b, "second").unwrap();
+    let t1 = t0 + Duration::from_millis(10);
+    assert_eq!(
+        watcher.poll_once(t1),
+        WatchEvent::Unchanged,
+        "second change restarts the window"
+    );
+    assert_eq!(
+        watcher.poll_once(t1 + Duration::from_millis(99)),
+        WatchEvent::Unchanged
+    );
+
+    let fired = watcher.poll_once(t1 + Duration::from_millis(100));
+    assert_eq!(fired, WatchEvent::Changed { paths: vec![a, b] });
+    assert_eq!(
+        watcher.poll_once(t1 + Duration::from_secs(5)),
+        WatchEvent::Unchanged
+    );
+}
+
+#[test]
+fn test_watch_include_exclude_filters() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("src")).unwrap();
+    fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+
+    let mut watcher = quiet_watcher(dir.path())
+        .include("*.rs")
+        .exclude("target/**");
+    let now = Instant::now();
+    watcher.poll_once(now);
+
+    fs::write(dir.path().join("target/debug/build.rs"), "x").unwrap();
+    fs::write(dir.path().join("src/notes.md"), "x").unwrap();
+    assert_eq!(watcher.poll_once(now), WatchEvent::Unchanged);
+
+    let main = dir.path().join("src/main.rs");
+    fs::write(&main, "fn main() {}").unwrap();
+    assert_eq!(
+        watcher.poll_once(now),
+        WatchEvent::Changed { paths: vec![main] }
+    );
+}
+
+#[test]
+fn test_glob_match_table() {
+    let cases = [
+        ("*.rs", "main.rs", true),
+        ("*.rs", "src/main.rs", true),
+        ("*.rs", "main.rs.bak", false),
+        ("src/*.rs", "src/main.rs", true),
+        ("src/*.rs", "src/bin/main.rs", false),
+        ("src/**/*.rs", "src/main.rs", true),
+        ("src/**/*.rs", "src/bin/tools/main.rs", true),
+        ("target/**", "target/debug/app", true),
+        ("target/**", "src/target/app", false),
+        ("**/*.toml", "Cargo.toml", true),
+        ("**/*.toml", "labs/x/Cargo.toml", true),
+        ("Cargo.lock", "Cargo.lock", true),
+        ("Cargo.lock", "Cargo.toml", false),
+    ];
+    for (pattern, path, expected) in cases {
+        assert_eq!(
+            glob_match(pattern, path),
+            expected,
+            "glob_match({:?}, {:?})",
+            pattern,
+            path
+        );
+    }
+}
+
+#[test]
+fn test_glob_match_pathological_pattern_is_fast() {
+    let path = "a".repeat(200);
+    let started = Instant::now();
+    assert!(!glob_match("**a**a**a**a**a**a**a**a**b", &path));
+    assert!(!glob_match("*a*a*a*a*a*a*a*a*b", &path));
+    assert!(glob_match("**a**a**a**a**a**a**a**a", &path));
+    assert!(
+        started.elapsed() < Duration::from_secs(1),
+        "matching must not backtrack exponentially"
+    );
+}
+
+#[test]
+fn test_watch_exclude_prunes_only_whole_directories() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("build/deep")).unwrap();
+    fs::create_dir_all(dir.path().join("out/deep")).unwrap();
+
+    // `build/**` covers everything below build/; `out/*` only covers
+    // out/'s direct children, so out/deep/ must still be walked.
+    let mut watcher = quiet_watcher(dir.path())
+        .exclude("build/**")
+        .exclude("out/*");
+    let now = Instant::now();
+    watcher.poll_once(now);
+
+    fs::write(dir.path().join("build/deep/a.o"), "x").unwrap();
+    fs::write(dir.path().join("out/a.o"), "x").unwrap();
+    assert_eq!(watcher.poll_once(now), WatchEvent::Unchanged);
+
+    let nested = dir.path().join("out/deep/a.o");
+    fs::write(&nested, "x").unwrap();
+    assert_eq!(
+        watcher.poll_once(now),
+        WatchEvent::Changed {
+            paths: vec![nested]
+        }
+    );
+}
+
+#[test]
+fn test_watch_run_with_large_output_finishes_during_loop() {
+    let dir = tempdir().unwrap();
+    let trigger = dir.path().join("trigger.txt");
+    fs::write(&trigger, "").unwrap();
+
+    // Far more than a pipe buffer: the child can only exit once someone
+    // reads its output.
+    let command = CommandBuilder::new("sh")
+        .arg("-c")
+        .arg("head -c 200000 /dev/zero | tr '\\0' x");
+    let mut watcher = Watcher::new(command)
+        .watch(dir.path())
+        .poll_interval(Duration::from_millis(20))
+        .debounce(Duration::ZERO);
+    watcher.poll_once(Instant::now());
+    fs::write(&trigger, "1").unwrap();
+
+    let mut finished = Vec::new();
+    let mut triggers = 0;
+    watcher.run_loop(100, |event| match event {
+        WatchRunEvent::Triggered { .. } => triggers += 1,
+        WatchRunEvent::Finished(result) => {
+            finished.push(result.stdout.len());
+            if finished.len() == 1 {
+                // Only reachable before the loop ends if the first run
+                // exited on its own.
+                fs::write(&trigger, "12").unwrap();
+            }
+        }
+        other => panic!("unexpected {:?}", other),
+    });
+
+    assert_eq!(triggers, 2, "the first run must finish inside the loop");
+    assert_eq!(finished, vec![200_000, 200_000]);
+}
+
+#[test]
+fn test_watch_run_loop_cancels_still_running_previous_run() {
+    let dir = tempdir().unwrap();
+    let trigger = dir.path().join("trigger.txt");
+    fs::write(&trigger, "0").unwrap();
+
+    // Sleeps for a long time unless `done.flag` exists when it starts.
+    let command = CommandBuilder::new("sh")
+        .arg("-c")
+        .arg("if [ -f done.flag ]; then echo fast; else sleep 30; fi")
+        .current_dir(dir.path().to_str().unwrap());
+    let mut watcher = Watcher::new(command)
+        .watch(dir.path())
+        .include("*.txt")
+        .debounce(Duration::ZERO)
+        .with_clock(ManualClock::new());
+    watcher.poll_once(Instant::now());
+    fs::write(&trigger, "01").unwrap();
+
+    let mut events = Vec::new();
+    let mut triggers = 0;
+    let started = Instant::now();
+    watcher.run_loop(4, |event| {
+        if let WatchRunEvent::Triggered { .. } = event {
+            triggers += 1;
+            if triggers == 1 {
+                // Make the next poll see a change while run #1 sleeps.
+                fs::write(&trigger, "012").unwrap();
+            } else {
+                fs::write(dir.path().join("done.flag"), "").unwrap();
+            }
+        }
+        events.push(match event {
+            WatchRunEvent::Triggered { .. } => "triggered".to_string(),
+            WatchRunEvent::Cancelled => "cancelled".to_string(),
+            WatchRunEvent::Finished(result) => format!("finished:{}", result.stdout.trim()),
+            WatchRunEvent::Failed(e) => format!("failed:{}", e),
+        });
+    });
+
+    assert_eq!(
+        events,
+        vec!["triggered", "cancelled", "triggered", "finished:fast"]
+    );
+    assert!(
+        started.elapsed() < Duration::from_secs(10),
+        "the 30s sleeper must have been killed"
+    );
+}
+
+#[test]
+fn test_watch_run_loop_bounded_by_max_iterations() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("f.txt");
+    fs::write(&file, "").unwrap();
+
+    let clock = ManualClock::new();
+    let start = clock.now();
+    let mut watcher = quiet_watcher(dir.path())
+        .poll_interval(Duration::from_secs(1))
+        .with_clock(clock.clone());
+    watcher.poll_once(start);
+
+    // Every run touches the file again, so every iteration would fire
+    // forever without the bound.
+    let mut size = 0;
+    let mut triggers = 0;
+    fs::write(&file, "x").unwrap();
+    watcher.run_loop(5, |event| {
+        if let WatchRunEvent::Triggered { .. } = event {
+            triggers += 1;
+            size += 2;
+            fs::write(&file, "x".repeat(size)).unwrap();
+        }
+    });
+
+    assert_eq!(triggers, 5);
+    assert_eq!(
+        clock.now() - start,
+        Duration::from_secs(4),
+        "sleeps only between iterations"
+    );
+}
+
+#[test]
+fn test_watch_run_loop_reports_spawn_failure() {
+    let dir = tempdir().unwrap();
+    let mut watcher = Watcher::new(CommandBuilder::new("a_truly_nonexistent_command_123"))
+        .watch(dir.path())
+        .debounce(Duration::ZERO)
+        .with_clock(ManualClock::new());
+    watcher.poll_once(Instant::now());
+    fs::write(dir.path().join("a.txt"), "x").unwrap();
+
+    let mut failures = 0;
+    watcher.run_loop(1, |event| {
+        if let WatchRunEvent::Failed(CommandError::LaunchFailed { .. }) = event {
+            failures += 1;
+        }
+    });
+    assert_eq!(failures, 1);
+}
+
+// ============================================================================
+// DURATION AND RESOURCE USAGE TESTS
+// ============================================================================
+
+#[test]
+fn test_duration_covers_the_child_lifetime() {
+    let result = CommandBuilder::new("sleep").arg("0.2").run().unwrap();
+    assert_eq!(result.exit_code, 0);
+    assert!(
+        result.duration >= Duration::from_millis(200),
+        "{:?}",
+        result.duration
+    );
+
+    let timed = CommandBuilder::new("sleep")
+        .arg("0.2")
+        .timeout(Duration::from_secs(5))
+        .run()
+        .unwrap();
+    assert!(
+        timed.duration >= Duration::from_millis(200),
+        "{:?}",
+        timed.duration
+    );
+}
+
+#[test]
+fn test_pipe_duration_sums_stages() {
+    let result = pipe(&[
+        CommandBuilder::new("sleep").arg("0.1"),
+        CommandBuilder::new("sleep").arg("0.1"),
+    ])
+    .unwrap();
+    assert!(
+        result.duration >= Duration::from_millis(200),
+        "{:?}",
+        result.duration
+    );
+}
+
+#[test]
+fn test_summary_formatting() {
+    let mut result = CommandResult {
+        exit_code: 0,
+        stdout: String::new(),
+        stderr: String::new(),
+        stdout_bytes: None,
+        stderr_bytes: None,
+        duration: Duration::from_millis(1240),
+        max_rss_kb: Some(38 * 1024 + 100),
+    };
+    assert_eq!(result.summary(), "exit 0 in 1.24s, 38 MB peak");
+
+    result.exit_code = 2;
+    result.max_rss_kb = Some(512);
+    assert_eq!(result.summary(), "exit 2 in 1.24s, 512 KB peak");
+
+    result.duration = Duration::from_millis(5);
+    result.max_rss_kb = None;
+    assert_eq!(result.summary(), "exit 2 in 0.01s");
+}
+
+#[test]
+fn test_max_rss_is_reported_where_supported() {
+    let result = CommandBuilder::new("echo").arg("hi").run().unwrap();
+    if cfg!(unix) {
+        assert!(result.max_rss_kb.is_some_and(|kb| kb > 0));
+    } else {
+        assert_eq!(result.max_rss_kb, None);
+    }
+}
+
+// ============================================================================
+// POOLED TASK RUNNER TESTS
+// ============================================================================
+
+fn sh_task(name: &str, script: &str) -> Task {
+    Task::new(
+        name.to_string(),
+        CommandBuilder::new("sh").arg("-c").arg(script),
+    )
+}
+
+#[test]
+fn test_pooled_tasks_run_concurrently() {
+    let tasks = (0..4)
+        .map(|i| {
+            Task::new(
+                format!("sleep {}", i),
+                CommandBuilder::new("sleep").arg("0.2"),
+            )
+        })
+        .collect();
+    let pool = ThreadPool::new(4);
+
+    let started = Instant::now();
+    let results = TaskRunner::new(tasks).max_parallel(4).run_all_pooled(&pool);
+    let elapsed = started.elapsed();
+
+    assert_eq!(results.len(), 4);
+    assert!(results.iter().all(|r| r.succeeded()));
+    // Sequentially this takes 0.8s
+    assert!(elapsed < Duration::from_millis(600), "took {:?}", elapsed);
+}
+
+#[test]
+fn test_pooled_max_parallel_limits_concurrency() {
+    let tasks = (0..2)
+        .map(|i| {
+            Task::new(
+                format!("sleep {}", i),
+                CommandBuilder::new("sleep").arg("0.2"),
+            )
+        })
+        .collect();
+    let pool = ThreadPool::new(4);
+
+    let started = Instant::now();
+    let results = TaskRunner::new(tasks).max_parallel(1).run_all_pooled(&pool);
+    assert!(started.elapsed() >= Duration::from_millis(400));
+    assert!(results.iter().all(|r| r.succeeded()));
+}
+
+#[test]
+fn test_pooled_results_keep_task_order() {
+    // The first task finishes last
+    let tasks = vec![
+        sh_task("slow", "sleep 0.3; echo slow"),
+        sh_task("medium", "sleep 0.1; echo medium"),
+        sh_task("fast", "echo fast"),
+    ];
+    let results = TaskRunner::new(tasks).run_all_pooled(&ThreadPool::new(3));
+
+    let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["slow", "medium", "fast"]);
+    for (i, result) in results.iter().enumerate() {
+        assert_eq!(result.index, i);
+        let stdout = &result.result.as_ref().unwrap().as_ref().unwrap().stdout;
+        assert_eq!(stdout.trim(), result.name);
+    }
+}
+
+#[test]
+fn test_pooled_fail_fast_skips_later_tasks() {
+    let dir = tempdir().unwrap();
+    let touch = |name: &str| {
+        let path = dir.path().join(name);
+        sh_task(name, &format!("touch '{}'", path.display()))
+    };
+    let tasks = || {
+        vec![
+            touch("first"),
+            sh_task("broken", "exit 3"),
+            touch("third"),
+            touch("fourth"),
+        ]
+    };
+    let pool = ThreadPool::new(2);
+
+    let results = TaskRunner::new(tasks())
+        .max_parallel(1)
+        .fail_fast(true)
+        .run_all_pooled(&pool);
+    assert!(results[0].succeeded());
+    assert!(!results[1].succeeded() && !results[1].skipped());
+    assert!(results[2].skipped() && results[3].skipped());
+    assert!(dir.path().join("first").exists());
+    assert!(!dir.path().join("third").exists());
+    assert!(!dir.path().join("fourth").exists());
+
+    // Without fail_fast every task runs, failure or not
+    let results = TaskRunner::new(tasks())
+        .max_parallel(1)
+        .run_all_pooled(&pool);
+    assert!(results.iter().all(|r| !r.skipped()));
+    assert!(dir.path().join("fourth").exists());
+}
+
+#[test]
+fn test_pooled_lost_job_is_a_failure_not_a_hang() {
+    // The pool's only worker panics while the task's job waits in its queue;
+    // the dead worker takes the queue (and the unrun job) with it
+    let pool = ThreadPool::new(1);
+    pool.execute(|| {
+        std::thread::sleep(Duration::from_millis(200));
+        panic!("worker dies");
+    });
+
+    let results = TaskRunner::new(vec![sh_task("stranded", "true")]).run_all_pooled(&pool);
+    assert!(matches!(
+        results[0].result,
+        Some(Err(CommandError::JobLost))
+    ));
+    assert!(!results[0].succeeded() && !results[0].skipped());
+
+    // Dropping a pool whose workers are gone panics; this one is done
+    std::mem::forget(pool);
+}
+
+// ============================================================================
+// COMMAND LINE PARSING TESTS
+// ============================================================================
+
+fn words(line: &str) -> Vec<String> {
+    let (command, mut args) = parse_command_line(line).unwrap();
+    args.insert(0, command);
+    args
+}
+
+#[test]
+fn test_parse_splits_on_whitespace() {
+    assert_eq!(words("  ls   -la\t/tmp \n"), ["ls", "-la", "/tmp"]);
+    let (command, args) = parse_command_line("true").unwrap();
+    assert_eq!(command, "true");
+    assert!(args.is_empty());
+}
+
+#[test]
+fn test_parse_quoted_arguments_with_spaces() {
+    assert_eq!(
+        words("grep -r 'foo bar' src"),
+        ["grep", "-r", "foo bar", "src"]
+    );
+    assert_eq!(
+        words(r#"echo "hello   world" end"#),
+        ["echo", "hello   world", "end"]
+    );
+}
+
+#[test]
+fn test_parse_embedded_quotes() {
+    assert_eq!(
+        words(r#"echo "it's" 'say "hi"'"#),
+        ["echo", "it's", "say \"hi\""]
+    );
+    // Adjacent quoted and unquoted pieces form one word.
+    assert_eq!(words(r#"echo pre"fix"'ed'"#), ["echo", "prefixed"]);
+}
+
+#[test]
+fn test_parse_escapes() {
+    // Inside double quotes only \" \\ \$ \` are escapes.
+    assert_eq!(
+        words(r#"echo "a \"b\" \\ \$HOME \n""#),
+        ["echo", r#"a "b" \ $HOME \n"#]
+    );
+    // Single quotes keep backslashes as they are.
+    assert_eq!(words(r"echo 'a\b'"), ["echo", r"a\b"]);
+    // Outside quotes a backslash makes anything literal, even a space.
+    assert_eq!(words(r"touch my\ file \'x"), ["touch", "my file", "'x"]);
+}
+
+#[test]
+fn test_parse_empty_argument() {
+    assert_eq!(words(r#"printf "" ''"#), ["printf", "", ""]);
+    assert_eq!(words(r#""" x"#), ["", "x"]);
+}
+
+#[test]
+fn test_parse_shell_syntax_is_literal() {
+    assert_eq!(
+        words("echo $HOME *.rs | wc > out; rm -rf /"),
+        ["echo", "$HOME", "*.rs", "|", "wc", ">", "out;", "rm", "-rf", "/"]
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(parse_command_line(""), Err(ParseError::Empty));
+    assert_eq!(parse_command_line(" \t\n"), Err(ParseError::Empty));
+    assert_eq!(
+        parse_command_line("echo 'oops"),
+        Err(ParseError::UnterminatedQuote {
+            quote: '\'',
+            position: 5,
+        })
+    );
+    assert_eq!(
+        parse_command_line(r#"echo ok "still \" open"#),
+        Err(ParseError::UnterminatedQuote {
+            quote: '"',
+            position: 8,
+        })
+    );
+    assert_eq!(
+        parse_command_line(r"echo \"),
+        Err(ParseError::TrailingBackslash)
+    );
+    let err = parse_command_line("echo 'x").unwrap_err();
+    assert_eq!(err.to_string(), "unterminated ' quote starting at byte 5");
+}
+
+#[test]
+fn test_from_line_matches_manual_args() {
+    let from_line = CommandBuilder::from_line(r#"echo "hello  world" 'a"b' \$x"#)
+        .unwrap()
+        .run()
+        .unwrap();
+    let manual = CommandBuilder::new("echo")
+        .arg("hello  world")
+        .arg("a\"b")
+        .arg("$x")
+        .run()
+        .unwrap();
+    assert_eq!(from_line.stdout, manual.stdout);
+    assert_eq!(from_line.stdout, "hello  world a\"b $x\n");
+    assert_eq!(from_line.exit_code, manual.exit_code);
+}
+
+#[test]
+fn test_run_line() {
+    let result = run_line("echo one 'two three'").unwrap();
+    assert_eq!(result.stdout, "one two three\n");
+    assert!(matches!(
+        run_line("echo \"unclosed"),
+        Err(CommandError::Parse(ParseError::UnterminatedQuote { .. }))
+    ));
+    assert!(matches!(
+        run_line("   "),
+        Err(CommandError::Parse(ParseError::Empty))
+    ));
+}
@@ -20,11 +20,23 @@
 //! 4.  **`parallel_search_with_mmap()` (Stretch Goal)**: Combine the memory map
 //!     with `rayon` to perform the search in parallel.
 //!
+//! 5.  **`search_file_streaming()` / `search_file_lines_streaming()`**: Search
+//!     without ever holding more than one buffer (or one line) in memory.
+//!
+//! 6.  **`search_file_with_context()`**: grep-style output with lines of
+//!     context before and after each match.
+//!
+//! 7.  **`count_pattern()` / `parallel_count()`** and the `mmap` feature:
+//!     one shared counting core, a `MappedFile` wrapper (mind empty files!),
+//!     and `search_file_mmap()` / `parallel_search_file_mmap()` on top.
+//!     The memmap2 code is behind the opt-in `mmap` feature, so the default
+//!     build must compile without it.
+//!
 //! ## Running Your Code
 //!
 //! ```bash
-//! cargo test -p memmap-search
-//! cargo run -p memmap-search --release
+//! cargo test -p memmap-search --features mmap
+//! cargo run -p memmap-search --release --features mmap
 //! ```
 //!
 //! ## Stuck?
@@ -34,6 +46,7 @@
 use std::fs::File;
 use std::io::{self, BufReader, Read, Write};
 use std::path::Path;
+#[cfg(feature = "mmap")]
 use memmap2::Mmap;
 
 /// Creates a large test file with a given size in megabytes.
@@ -68,7 +81,70 @@ pub fn search_with_read(path: &Path, pattern: &str) -> io::Result<usize> {
     todo!("Implement search_with_read");
 }
 
+/// Counts (possibly overlapping) occurrences of `pattern` in `haystack`.
+pub fn count_pattern(haystack: &[u8], pattern: &[u8]) -> usize {
+    // TODO: Count the windows equal to `pattern`. Return 0 for an empty
+    // pattern instead of letting `windows(0)` panic.
+    let _ = (haystack, pattern);
+    todo!("Implement count_pattern");
+}
+
+/// `count_pattern` split across `threads` scoped threads.
+pub fn parallel_count(haystack: &[u8], pattern: &[u8], threads: usize) -> usize {
+    // TODO: Split the possible match *start* positions into `threads`
+    // ranges. Each thread scans its range plus `pattern.len() - 1` extra
+    // bytes so boundary matches are counted exactly once. Use
+    // `std::thread::scope` to borrow `haystack` without copying.
+    let _ = (haystack, pattern, threads);
+    todo!("Implement parallel_count");
+}
+
+/// A read-only memory map of a whole file.
+#[cfg(feature = "mmap")]
+pub struct MappedFile {
+    map: Option<Mmap>,
+}
+
+#[cfg(feature = "mmap")]
+impl MappedFile {
+    pub fn open(path: &Path) -> io::Result<MappedFile> {
+        // TODO: Open the file. A zero-length file can't be mapped portably,
+        // so keep `map: None` for it; otherwise `unsafe { Mmap::map(&file) }`.
+        let _ = path;
+        todo!("Implement MappedFile::open");
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        // TODO: The mapping as a slice, or `&[]` for an empty file.
+        let _ = &self.map;
+        todo!("Implement MappedFile::as_bytes");
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_bytes().is_empty()
+    }
+}
+
+/// `count_pattern` over a memory map of `path`.
+#[cfg(feature = "mmap")]
+pub fn search_file_mmap(path: &Path, pattern: &str) -> io::Result<usize> {
+    let _ = (path, pattern);
+    todo!("Implement search_file_mmap");
+}
+
+/// `parallel_count` over a memory map of `path`, on `threads` threads.
+#[cfg(feature = "mmap")]
+pub fn parallel_search_file_mmap(path: &Path, pattern: &str, threads: usize) -> io::Result<usize> {
+    let _ = (path, pattern, threads);
+    todo!("Implement parallel_search_file_mmap");
+}
+
 /// Searches for a pattern in a file using a memory map.
+#[cfg(feature = "mmap")]
 pub fn search_with_mmap(path: &Path, pattern: &str) -> io::Result<usize> {
     // TODO: Implement search with a memory map.
     // 1. Open the file.
@@ -83,6 +159,7 @@ pub fn search_with_mmap(path: &Path, pattern: &str) -> io::Result<usize> {
 }
 
 /// (Stretch Goal) Searches for a pattern in a memory-mapped file in parallel.
+#[cfg(feature = "mmap")]
 pub fn parallel_search_with_mmap(path: &Path, pattern: &str) -> io::Result<usize> {
     // TODO: Implement parallel search.
     // 1. Memory-map the file as in `search_with_mmap`.
@@ -91,6 +168,64 @@ pub fn parallel_search_with_mmap(path: &Path, pattern: &str) -> io::Result<usize
     todo!("Implement parallel_search_with_mmap");
 }
 
+/// Searches a file in fixed-size chunks, carrying over the last
+/// `pattern.len() - 1` bytes between chunks.
+pub fn search_file_streaming(path: &Path, pattern: &str, buffer_size: usize) -> io::Result<usize> {
+    // TODO: Implement chunked streaming search.
+    // 1. Return `InvalidInput` for an empty pattern or a `buffer_size`
+    //    smaller than the pattern.
+    // 2. Allocate one `buffer_size` buffer and `read` into the unused tail.
+    // 3. Count `.windows()` matches in the filled part of the buffer.
+    // 4. `copy_within` the last `pattern.len() - 1` bytes to the front so
+    //    matches spanning a chunk boundary are found on the next pass.
+    todo!("Implement search_file_streaming");
+}
+
+/// Returns the 1-based line numbers of lines containing `pattern`.
+pub fn search_file_lines_streaming(path: &Path, pattern: &str) -> io::Result<Vec<usize>> {
+    // TODO: Implement line-oriented streaming search.
+    // 1. Wrap the file in a `BufReader`.
+    // 2. Reuse one `String`: `clear()` it, then `read_line` into it.
+    // 3. Keep a running line counter and record lines that contain `pattern`.
+    todo!("Implement search_file_lines_streaming");
+}
+
+/// One match with `before`/`after` context lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchContext {
+    pub line_number: usize,
+    pub line: String,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// Returns every line containing `pattern` with up to `before` and `after`
+/// lines of context.
+pub fn search_file_with_context(
+    path: &Path,
+    pattern: &str,
+    before: usize,
+    after: usize,
+) -> io::Result<Vec<MatchContext>> {
+    // TODO: Implement context search.
+    // 1. Keep the last `before` lines in a `VecDeque`.
+    // 2. On a match, snapshot that window as the `before` context.
+    // 3. Keep matches "pending" until they have collected `after` lines.
+    // 4. At end of file, flush pending matches with shorter `after` context.
+    todo!("Implement search_file_with_context");
+}
+
+/// In-memory variant of `search_file_with_context`.
+pub fn search_str_with_context(
+    text: &str,
+    pattern: &str,
+    before: usize,
+    after: usize,
+) -> Vec<MatchContext> {
+    // TODO: Reuse the same logic over `text.lines()`.
+    todo!("Implement search_str_with_context");
+}
+
 
 // Re-export the solution module so people can compare
 #[doc(hidden)]
@@ -3,15 +3,21 @@
 //! This binary creates a large test file and then compares the performance
 //! of searching for a pattern using standard file I/O versus a memory map.
 //! 
-//! Run in release mode for accurate timings:
-//! cargo run -p memmap-search --release
+//! Run in release mode for accurate timings, with the `mmap` feature for
+//! the memory-mapped searches:
+//! cargo run -p memmap-search --release --features mmap
 
+use memmap_search::solution::{create_test_file, search_with_read};
+#[cfg(feature = "mmap")]
 use memmap_search::solution::{
-    create_test_file,
     search_with_mmap,
-    search_with_read,
-    parallel_search_with_mmap
+    parallel_search_with_mmap,
+    parallel_search_file_mmap
 };
+#[cfg(feature = "mmap")]
+use std::path::Path;
+#[cfg(feature = "mmap")]
+use std::time::Duration;
 use std::time::Instant;
 use tempfile::Builder;
 
@@ -44,12 +50,29 @@ fn main() -> std::io::Result<()> {
     println!("   -> Time taken: {:?}", duration_read);
     println!();
 
+    #[cfg(feature = "mmap")]
+    mmap_demos(&file_path, pattern, count_read, duration_read)?;
+    #[cfg(not(feature = "mmap"))]
+    println!("(Build with `--features mmap` to compare against the memory-mapped searches.)");
+
+    println!("\n=== Demo Complete! ===");
+    Ok(())
+}
+
+/// Demos 2-5: the memory-mapped searches, checked against the read-based count.
+#[cfg(feature = "mmap")]
+fn mmap_demos(
+    file_path: &Path,
+    pattern: &str,
+    count_read: usize,
+    duration_read: Duration,
+) -> std::io::Result<()> {
     // ============================================================================ 
     // DEMO 2: Search with Memory-Mapped File
     // ============================================================================ 
     println!("2. Searching with memory-mapping...");
     let start_mmap = Instant::now();
-    let count_mmap = search_with_mmap(&file_path, pattern).unwrap();
+    let count_mmap = search_with_mmap(file_path, pattern).unwrap();
     let duration_mmap = start_mmap.elapsed();
     println!("   -> Found {} occurrences.", count_mmap);
     println!("   -> Time taken: {:?}", duration_mmap);
@@ -60,20 +83,31 @@ fn main() -> std::io::Result<()> {
     // ============================================================================ 
     println!("3. Searching with memory-mapping and parallel processing (Rayon)...");
     let start_par_mmap = Instant::now();
-    let count_par_mmap = parallel_search_with_mmap(&file_path, pattern).unwrap();
+    let count_par_mmap = parallel_search_with_mmap(file_path, pattern).unwrap();
     let duration_par_mmap = start_par_mmap.elapsed();
     println!("   -> Found {} occurrences.", count_par_mmap);
     println!("   -> Time taken: {:?}", duration_par_mmap);
     println!();
 
+    // ============================================================================
+    // DEMO 4: Parallel Search with a Fixed Thread Count
+    // ============================================================================
+    println!("4. Searching with memory-mapping on 4 scoped threads...");
+    let start_threads = Instant::now();
+    let count_threads = parallel_search_file_mmap(file_path, pattern, 4).unwrap();
+    println!("   -> Found {} occurrences.", count_threads);
+    println!("   -> Time taken: {:?}", start_threads.elapsed());
+    println!();
+
 
     // ============================================================================ 
     // Comparison
     // ============================================================================ 
     assert_eq!(count_read, count_mmap);
     assert_eq!(count_mmap, count_par_mmap);
+    assert_eq!(count_mmap, count_threads);
 
-    println!("4. Comparison");
+    println!("5. Comparison");
     println!("   -----------");
     if duration_mmap < duration_read {
         let speedup = duration_read.as_secs_f64() / duration_mmap.as_secs_f64();
@@ -96,7 +130,5 @@ fn main() -> std::io::Result<()> {
     }
 
     println!("\n(Note: Performance can vary based on OS caching and file size.)");
-
-    println!("\n=== Demo Complete! ===");
     Ok(())
-}
\ No newline at end of file
+}
@@ -23,10 +23,19 @@
 //! leading to a crash. By using `unsafe`, we are telling the compiler that we
 //! understand this risk and are using the memory map in a context where the file
 //! is assumed to be static.
+//!
+//! ## The `mmap` Feature
+//!
+//! Everything that touches `memmap2` sits behind the opt-in `mmap` cargo
+//! feature. A plain `cargo build -p memmap-search` leaves only the
+//! read-based and streaming searches, with no `unsafe` mapping and no
+//! memmap2 dependency, which is what you'd ship to a target where mmap isn't
+//! available; `--features mmap` adds the mapped backend.
 
 use std::fs::File;
-use std::io::{self, BufReader, Read, Write};
+use std::io::{self, BufRead, BufReader, Read, Write};
 use std::path::Path;
+#[cfg(feature = "mmap")]
 use memmap2::Mmap;
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
@@ -73,51 +82,335 @@ pub fn search_with_read(path: &Path, pattern: &str) -> io::Result<usize> {
     let mut buffer = Vec::new();
     reader.read_to_end(&mut buffer)?;
 
-    let pattern_bytes = pattern.as_bytes();
-    let count = buffer
-        .windows(pattern_bytes.len())
-        .filter(|&window| window == pattern_bytes)
-        .count();
-    
-    Ok(count)
+    Ok(count_pattern(&buffer, pattern.as_bytes()))
+}
+
+/// Counts (possibly overlapping) occurrences of `pattern` in `haystack`.
+///
+/// Every search in this lab ends up here or in `parallel_count`; they only
+/// differ in how they get the bytes into memory. An empty pattern matches
+/// nothing (`windows(0)` would panic).
+pub fn count_pattern(haystack: &[u8], pattern: &[u8]) -> usize {
+    if pattern.is_empty() {
+        return 0;
+    }
+    haystack
+        .windows(pattern.len())
+        .filter(|&window| window == pattern)
+        .count()
+}
+
+/// `count_pattern` split across `threads` scoped threads.
+///
+/// Each thread owns the match *start positions* in one contiguous range
+/// and scans `pattern.len() - 1` bytes past its end, so a match straddling
+/// two ranges is counted exactly once: by the range it starts in. The
+/// threads borrow `haystack` directly (no copies), which is what makes this
+/// a good fit for a memory map. `threads` of 0 is treated as 1.
+pub fn parallel_count(haystack: &[u8], pattern: &[u8], threads: usize) -> usize {
+    if pattern.is_empty() || haystack.len() < pattern.len() {
+        return 0;
+    }
+    let starts = haystack.len() - pattern.len() + 1; // possible match starts
+    let threads = threads.clamp(1, starts);
+    let per_thread = starts.div_ceil(threads);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..starts)
+            .step_by(per_thread)
+            .map(|first| {
+                let last = (first + per_thread).min(starts); // exclusive
+                let slice = &haystack[first..last + pattern.len() - 1];
+                scope.spawn(move || count_pattern(slice, pattern))
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).sum()
+    })
+}
+
+/// A read-only memory map of a whole file.
+///
+/// `as_bytes` hands out the mapping itself, so searching a `MappedFile`
+/// never copies file data into the process; pages are faulted in by the
+/// OS as the search touches them.
+///
+/// Zero-length files are special: POSIX `mmap` rejects a length of 0, and
+/// whether `memmap2` papers over that depends on the version and platform.
+/// We skip the syscall entirely and expose an empty slice instead.
+#[cfg(feature = "mmap")]
+pub struct MappedFile {
+    map: Option<Mmap>,
+}
+
+#[cfg(feature = "mmap")]
+impl MappedFile {
+    /// Opens and maps `path`.
+    pub fn open(path: &Path) -> io::Result<MappedFile> {
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(MappedFile { map: None });
+        }
+        // `unsafe` is required because the file could be modified by another
+        // process, which would violate Rust's memory safety guarantees.
+        // We proceed assuming the file is static during the search.
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(MappedFile { map: Some(map) })
+    }
+
+    /// The file's contents, borrowed straight from the mapping.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or(&[])
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_bytes().is_empty()
+    }
+}
+
+/// `count_pattern` over a memory map of `path`.
+#[cfg(feature = "mmap")]
+pub fn search_file_mmap(path: &Path, pattern: &str) -> io::Result<usize> {
+    let mapped = MappedFile::open(path)?;
+    Ok(count_pattern(mapped.as_bytes(), pattern.as_bytes()))
+}
+
+/// `parallel_count` over a memory map of `path`, on `threads` threads.
+#[cfg(feature = "mmap")]
+pub fn parallel_search_file_mmap(path: &Path, pattern: &str, threads: usize) -> io::Result<usize> {
+    let mapped = MappedFile::open(path)?;
+    Ok(parallel_count(
+        mapped.as_bytes(),
+        pattern.as_bytes(),
+        threads,
+    ))
 }
 
 /// Searches for a pattern in a file using a memory map.
 ///
 /// This is generally much faster for large files as it avoids extra copies
 /// and leverages the OS's virtual memory system.
+#[cfg(feature = "mmap")]
 pub fn search_with_mmap(path: &Path, pattern: &str) -> io::Result<usize> {
-    let file = File::open(path)?;
-    // `unsafe` is required because the file could be modified by another
-    // process, which would violate Rust's memory safety guarantees.
-    // We proceed assuming the file is static during the search.
-    let mmap = unsafe { Mmap::map(&file)? };
-
-    // The memory-mapped file can be treated as a single, large byte slice.
-    let pattern_bytes = pattern.as_bytes();
-    let count = mmap
-        .windows(pattern_bytes.len())
-        .filter(|&window| window == pattern_bytes)
-        .count();
-
-    Ok(count)
+    search_file_mmap(path, pattern)
 }
 
 /// (Stretch Goal) Searches for a pattern in a memory-mapped file in parallel.
 ///
 /// This combines the benefits of memory-mapping with data parallelism from Rayon,
-/// often providing the best performance on multi-core systems.
+/// often providing the best performance on multi-core systems. Rayon picks
+/// the thread count; see `parallel_search_file_mmap` to choose it yourself.
+#[cfg(feature = "mmap")]
 pub fn parallel_search_with_mmap(path: &Path, pattern: &str) -> io::Result<usize> {
     use rayon::prelude::*;
-    
-    let file = File::open(path)?;
-    let mmap = unsafe { Mmap::map(&file)? };
 
+    let mapped = MappedFile::open(path)?;
     let pattern_bytes = pattern.as_bytes();
-    let count = mmap[..]
+    if pattern_bytes.is_empty() {
+        return Ok(0);
+    }
+    let count = mapped
+        .as_bytes()
         .par_windows(pattern_bytes.len()) // The parallel version of `.windows()`
         .filter(|window| *window == pattern_bytes)
         .count();
 
     Ok(count)
 }
+
+/// Searches a file in fixed-size chunks so memory use stays at one buffer.
+///
+/// `search_with_read` slurps the whole file, which is fine for tests but not
+/// for a multi-GB log. Here we keep a single `buffer_size` buffer and refill
+/// it in a loop. The trick is the *carry-over*: after scanning a chunk we
+/// move its last `pattern.len() - 1` bytes to the front of the buffer before
+/// reading more. A match that straddles two chunks therefore shows up whole
+/// in the next scan.
+///
+/// Why exactly `pattern.len() - 1`? Any window that fits entirely inside the
+/// carried bytes is shorter than the pattern, so it cannot be a match we
+/// already counted. That means no double counting, and (like the other
+/// search functions) overlapping matches are all counted.
+///
+/// The buffer is never grown: `buffer_size` must be at least `pattern.len()`,
+/// otherwise there would be no room for new data after the carry-over. Too
+/// small a buffer, or an empty pattern, returns `ErrorKind::InvalidInput`.
+pub fn search_file_streaming(path: &Path, pattern: &str, buffer_size: usize) -> io::Result<usize> {
+    let pattern_bytes = pattern.as_bytes();
+    if pattern_bytes.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "pattern must not be empty",
+        ));
+    }
+    if buffer_size < pattern_bytes.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "buffer_size ({}) must be at least the pattern length ({})",
+                buffer_size,
+                pattern_bytes.len()
+            ),
+        ));
+    }
+
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0u8; buffer_size];
+    let carry = pattern_bytes.len() - 1;
+    let mut filled = 0; // bytes of valid data at the front of `buffer`
+    let mut count = 0;
+
+    loop {
+        let read = file.read(&mut buffer[filled..])?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+
+        count += buffer[..filled]
+            .windows(pattern_bytes.len())
+            .filter(|&window| window == pattern_bytes)
+            .count();
+
+        // Slide the tail to the front; `copy_within` moves bytes inside the
+        // same allocation, so we never need a second buffer.
+        let keep = carry.min(filled);
+        buffer.copy_within(filled - keep..filled, 0);
+        filled = keep;
+    }
+
+    Ok(count)
+}
+
+/// Returns the 1-based numbers of lines that contain `pattern`.
+///
+/// `BufReader::read_line` appends into a `String` we reuse (and `clear`) for
+/// every line, so memory is bounded by the longest line rather than the file.
+/// Because `read_line` decodes UTF-8, non-text files fail with
+/// `ErrorKind::InvalidData`; use `search_file_streaming` for raw bytes.
+pub fn search_file_lines_streaming(path: &Path, pattern: &str) -> io::Result<Vec<usize>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut line = String::new();
+    let mut line_number = 0;
+    let mut matches = Vec::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+        if line.contains(pattern) {
+            matches.push(line_number);
+        }
+    }
+
+    Ok(matches)
+}
+
+/// One `grep -B/-A` style hit: the matching line plus its surrounding lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchContext {
+    /// 1-based line number of the match.
+    pub line_number: usize,
+    /// The matching line, without its trailing newline.
+    pub line: String,
+    /// Up to `before` lines preceding the match, oldest first.
+    pub before: Vec<String>,
+    /// Up to `after` lines following the match.
+    pub after: Vec<String>,
+}
+
+/// Like `search_file_lines_streaming`, but returns each match with context.
+///
+/// Memory stays bounded: we keep a sliding window of the last `before` lines
+/// (a `VecDeque` used as a ring buffer) plus the matches still waiting for
+/// their `after` lines.
+///
+/// Each match owns its context. When two matches are close together their
+/// context windows overlap, and the shared lines appear in both (a match can
+/// even show up in its neighbour's `before`/`after`). `grep` merges such
+/// regions for display; we leave that to the caller so every `MatchContext`
+/// stands on its own. Near the start or end of the file the context is
+/// simply shorter.
+pub fn search_file_with_context(
+    path: &Path,
+    pattern: &str,
+    before: usize,
+    after: usize,
+) -> io::Result<Vec<MatchContext>> {
+    let reader = BufReader::new(File::open(path)?);
+    collect_with_context(reader.lines(), pattern, before, after)
+}
+
+/// In-memory version of `search_file_with_context`; handy for tests.
+pub fn search_str_with_context(
+    text: &str,
+    pattern: &str,
+    before: usize,
+    after: usize,
+) -> Vec<MatchContext> {
+    let lines = text.lines().map(|line| Ok(line.to_string()));
+    // The iterator never yields an error, so this cannot fail.
+    collect_with_context(lines, pattern, before, after).unwrap_or_default()
+}
+
+/// Shared engine for both context searches: works on any line iterator.
+fn collect_with_context<I>(
+    lines: I,
+    pattern: &str,
+    before: usize,
+    after: usize,
+) -> io::Result<Vec<MatchContext>>
+where
+    I: Iterator<Item = io::Result<String>>,
+{
+    use std::collections::VecDeque;
+
+    let mut window: VecDeque<String> = VecDeque::with_capacity(before);
+    let mut done = Vec::new();
+    // Matches whose `after` context is not full yet.
+    let mut pending: Vec<MatchContext> = Vec::new();
+
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+
+        // Feed this line to every earlier match still collecting `after`.
+        for m in pending.iter_mut() {
+            m.after.push(line.clone());
+        }
+        let (complete, waiting): (Vec<_>, Vec<_>) =
+            pending.into_iter().partition(|m| m.after.len() >= after);
+        done.extend(complete);
+        pending = waiting;
+
+        if line.contains(pattern) {
+            let hit = MatchContext {
+                line_number: index + 1,
+                line: line.clone(),
+                before: window.iter().cloned().collect(),
+                after: Vec::new(),
+            };
+            if after == 0 {
+                done.push(hit);
+            } else {
+                pending.push(hit);
+            }
+        }
+
+        if before > 0 {
+            if window.len() == before {
+                window.pop_front();
+            }
+            window.push_back(line);
+        }
+    }
+
+    // End of input: whatever is still pending gets truncated `after` context.
+    done.extend(pending);
+    done.sort_by_key(|m| m.line_number);
+    Ok(done)
+}
@@ -1,14 +1,21 @@
 //! Integration tests for Lab 38: Memory-Mapped File Search
 
 use memmap_search::solution::{
-    create_test_file, search_with_read, search_with_mmap, parallel_search_with_mmap,
+    count_pattern, create_test_file, parallel_count, search_file_lines_streaming,
+    search_file_streaming, search_file_with_context, search_str_with_context, search_with_read,
+    MatchContext,
+};
+#[cfg(feature = "mmap")]
+use memmap_search::solution::{
+    parallel_search_file_mmap, parallel_search_with_mmap, search_file_mmap, search_with_mmap,
+    MappedFile,
 };
 use std::io;
-use tempfile::Builder;
+use testing_benchmarking::solution::TempWorkspace;
 
 fn run_search_test(file_size_mb: usize, pattern: &str) -> io::Result<()> {
-    let temp_dir = Builder::new().prefix("memmap_test").tempdir()?;
-    let file_path = temp_dir.path().join("test.txt");
+    let ws = TempWorkspace::new();
+    let file_path = ws.path("test.txt")?;
 
     create_test_file(&file_path, file_size_mb, pattern)?;
 
@@ -19,11 +26,14 @@ fn run_search_test(file_size_mb: usize, pattern: &str) -> io::Result<()> {
     let count_read = search_with_read(&file_path, pattern)?;
     assert_eq!(count_read, expected_count, "Read method failed");
 
-    let count_mmap = search_with_mmap(&file_path, pattern)?;
-    assert_eq!(count_mmap, expected_count, "Mmap method failed");
-    
-    let count_par_mmap = parallel_search_with_mmap(&file_path, pattern)?;
-    assert_eq!(count_par_mmap, expected_count, "Parallel Mmap method failed");
+    #[cfg(feature = "mmap")]
+    {
+        let count_mmap = search_with_mmap(&file_path, pattern)?;
+        assert_eq!(count_mmap, expected_count, "Mmap method failed");
+
+        let count_par_mmap = parallel_search_with_mmap(&file_path, pattern)?;
+        assert_eq!(count_par_mmap, expected_count, "Parallel Mmap method failed");
+    }
 
     Ok(())
 }
@@ -40,26 +50,277 @@ fn test_searches_on_medium_file() -> io::Result<()> {
 
 #[test]
 fn test_pattern_not_found() -> io::Result<()> {
-    let temp_dir = Builder::new().prefix("not_found").tempdir()?;
-    let file_path = temp_dir.path().join("test.txt");
+    let ws = TempWorkspace::new();
+    let file_path = ws.path("test.txt")?;
     create_test_file(&file_path, 1, "SOME_DATA")?;
 
     assert_eq!(search_with_read(&file_path, "NOT_HERE")?, 0);
-    assert_eq!(search_with_mmap(&file_path, "NOT_HERE")?, 0);
-    assert_eq!(parallel_search_with_mmap(&file_path, "NOT_HERE")?, 0);
+    #[cfg(feature = "mmap")]
+    {
+        assert_eq!(search_with_mmap(&file_path, "NOT_HERE")?, 0);
+        assert_eq!(parallel_search_with_mmap(&file_path, "NOT_HERE")?, 0);
+    }
 
     Ok(())
 }
 
 #[test]
 fn test_empty_file() -> io::Result<()> {
-    let temp_dir = Builder::new().prefix("empty_file").tempdir()?;
-    let file_path = temp_dir.path().join("test.txt");
-    std::fs::write(&file_path, "")?;
+    let ws = TempWorkspace::new();
+    let file_path = ws.file("test.txt", "");
 
     assert_eq!(search_with_read(&file_path, "a")?, 0);
-    assert_eq!(search_with_mmap(&file_path, "a")?, 0);
-    assert_eq!(parallel_search_with_mmap(&file_path, "a")?, 0);
+    #[cfg(feature = "mmap")]
+    {
+        assert_eq!(search_with_mmap(&file_path, "a")?, 0);
+        assert_eq!(parallel_search_with_mmap(&file_path, "a")?, 0);
+    }
+
+    Ok(())
+}
+#[test]
+fn test_count_pattern_counts_overlaps() {
+    assert_eq!(count_pattern(b"aaaa", b"aa"), 3);
+    assert_eq!(count_pattern(b"abcabc", b"abc"), 2);
+    assert_eq!(count_pattern(b"ab", b"abc"), 0);
+    assert_eq!(count_pattern(b"abc", b""), 0);
+}
+
+#[test]
+fn test_parallel_count_matches_sequential_at_every_split() {
+    // Every thread count puts chunk boundaries somewhere different,
+    // including through the middle of matches.
+    let haystack = b"xxabcabcxabcxxabxcabcabcx".repeat(7);
+    let expected = count_pattern(&haystack, b"abc");
+    for threads in 0..=haystack.len() + 1 {
+        assert_eq!(
+            parallel_count(&haystack, b"abc", threads),
+            expected,
+            "{} threads",
+            threads
+        );
+    }
+    assert_eq!(parallel_count(b"aaaa", b"aa", 3), 3);
+    assert_eq!(parallel_count(b"ab", b"abc", 4), 0);
+    assert_eq!(parallel_count(b"abc", b"", 4), 0);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_mmap_counts_equal_read_counts() -> io::Result<()> {
+    let ws = TempWorkspace::new();
+    let file_path = ws.path("test.bin")?;
+    create_test_file(&file_path, 2, "NEEDLE")?;
+
+    let expected = search_with_read(&file_path, "NEEDLE")?;
+    assert_eq!(expected, 10);
+    assert_eq!(search_file_mmap(&file_path, "NEEDLE")?, expected);
+    for threads in [1, 2, 3, 8] {
+        assert_eq!(
+            parallel_search_file_mmap(&file_path, "NEEDLE", threads)?,
+            expected
+        );
+    }
+
+    let mapped = MappedFile::open(&file_path)?;
+    assert_eq!(mapped.len(), 2 * 1024 * 1024);
+    assert_eq!(mapped.as_bytes(), std::fs::read(&file_path)?.as_slice());
+    Ok(())
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_mmap_empty_file() -> io::Result<()> {
+    let ws = TempWorkspace::new();
+    let file_path = ws.file("empty.bin", "");
+
+    let mapped = MappedFile::open(&file_path)?;
+    assert!(mapped.is_empty());
+    assert_eq!(mapped.as_bytes(), b"");
+    assert_eq!(search_file_mmap(&file_path, "a")?, 0);
+    assert_eq!(parallel_search_file_mmap(&file_path, "a", 4)?, 0);
+    Ok(())
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_mmap_missing_file_is_an_error() {
+    let ws = TempWorkspace::new();
+    let err = MappedFile::open(&ws.root().join("nope.bin")).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_parallel_mmap_consistent_on_larger_file() -> io::Result<()> {
+    let ws = TempWorkspace::new();
+    let file_path = ws.path("large.bin")?;
+    create_test_file(&file_path, 8, "NEEDLE")?;
+
+    // Also count a pattern that crosses the generated 1 MB chunks
+    let mapped = MappedFile::open(&file_path)?;
+    let boundary = &mapped.as_bytes()[1024 * 1024 - 3..1024 * 1024 + 3];
+    let boundary_count = count_pattern(mapped.as_bytes(), boundary);
+    assert!(boundary_count >= 1);
+
+    let expected = search_file_mmap(&file_path, "NEEDLE")?;
+    assert_eq!(expected, 5 * 8);
+    for threads in [2, 5, 8, 64] {
+        assert_eq!(
+            parallel_search_file_mmap(&file_path, "NEEDLE", threads)?,
+            expected
+        );
+        assert_eq!(
+            parallel_count(mapped.as_bytes(), boundary, threads),
+            boundary_count
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_streaming_finds_match_straddling_buffer_boundary() -> io::Result<()> {
+    let ws = TempWorkspace::new();
+    // With an 8-byte buffer, "RUST" occupies bytes 6..10 and crosses the
+    // boundary between the first and second chunk.
+    let file_path = ws.file("straddle.txt", "......RUST......RUST");
+
+    assert_eq!(search_file_streaming(&file_path, "RUST", 8)?, 2);
+    assert_eq!(search_with_read(&file_path, "RUST")?, 2);
+    Ok(())
+}
+
+#[test]
+fn test_streaming_matches_in_memory_count() -> io::Result<()> {
+    let ws = TempWorkspace::new();
+    let file_path = ws.path("large.bin")?;
+    create_test_file(&file_path, 1, "NEEDLE")?;
+
+    let expected = search_with_read(&file_path, "NEEDLE")?;
+    for buffer_size in [6, 7, 64, 4096, 1 << 20] {
+        assert_eq!(
+            search_file_streaming(&file_path, "NEEDLE", buffer_size)?,
+            expected,
+            "buffer_size {} disagreed with search_with_read",
+            buffer_size
+        );
+    }
+    Ok(())
+}
 
+#[test]
+fn test_streaming_counts_overlapping_matches_across_chunks() -> io::Result<()> {
+    let ws = TempWorkspace::new();
+    let file_path = ws.file("overlap.txt", "aaaaaaaaaa");
+
+    assert_eq!(search_file_streaming(&file_path, "aaa", 3)?, 8);
+    assert_eq!(search_file_streaming(&file_path, "aaa", 4)?, 8);
     Ok(())
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_streaming_rejects_buffer_smaller_than_pattern() {
+    let ws = TempWorkspace::new();
+    let file_path = ws.file("small.txt", "PATTERN");
+
+    let err = search_file_streaming(&file_path, "PATTERN", 4).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let err = search_file_streaming(&file_path, "", 4).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_streaming_empty_file() -> io::Result<()> {
+    let ws = TempWorkspace::new();
+    let file_path = ws.file("empty.txt", "");
+
+    assert_eq!(search_file_streaming(&file_path, "a", 16)?, 0);
+    assert!(search_file_lines_streaming(&file_path, "a")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_lines_streaming_reports_line_numbers() -> io::Result<()> {
+    let ws = TempWorkspace::new();
+    let file_path = ws.file(
+        "app.log",
+        "INFO start\nERROR disk full\nINFO retry\nERROR disk full again",
+    );
+
+    assert_eq!(
+        search_file_lines_streaming(&file_path, "ERROR")?,
+        vec![2, 4]
+    );
+    assert_eq!(
+        search_file_lines_streaming(&file_path, "WARN")?,
+        Vec::<usize>::new()
+    );
+    Ok(())
+}
+
+const LOG: &str = "ERROR boot\ninfo a\ninfo b\nERROR one\nERROR two\ninfo c\nERROR last";
+
+#[test]
+fn test_context_match_on_first_line_truncates_before() {
+    let hits = search_str_with_context(LOG, "boot", 2, 1);
+
+    assert_eq!(
+        hits,
+        vec![MatchContext {
+            line_number: 1,
+            line: "ERROR boot".to_string(),
+            before: vec![],
+            after: vec!["info a".to_string()],
+        }]
+    );
+}
+
+#[test]
+fn test_context_match_on_last_line_truncates_after() {
+    let hits = search_str_with_context(LOG, "last", 1, 3);
+
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].line_number, 7);
+    assert_eq!(hits[0].before, vec!["info c".to_string()]);
+    assert!(hits[0].after.is_empty());
+}
+
+#[test]
+fn test_context_adjacent_matches_keep_their_own_context() {
+    let hits = search_str_with_context(LOG, "ERROR", 1, 1);
+    let numbers: Vec<usize> = hits.iter().map(|m| m.line_number).collect();
+    assert_eq!(numbers, vec![1, 4, 5, 7]);
+
+    // Lines 4 and 5 are neighbours: each appears in the other's context.
+    assert_eq!(hits[1].before, vec!["info b".to_string()]);
+    assert_eq!(hits[1].after, vec!["ERROR two".to_string()]);
+    assert_eq!(hits[2].before, vec!["ERROR one".to_string()]);
+    assert_eq!(hits[2].after, vec!["info c".to_string()]);
+}
+
+#[test]
+fn test_context_zero_matches_line_search() -> io::Result<()> {
+    let ws = TempWorkspace::new();
+    let file_path = ws.file("app.log", LOG);
+
+    let hits = search_file_with_context(&file_path, "ERROR", 0, 0)?;
+    let numbers: Vec<usize> = hits.iter().map(|m| m.line_number).collect();
+    assert_eq!(numbers, search_file_lines_streaming(&file_path, "ERROR")?);
+    assert!(hits
+        .iter()
+        .all(|m| m.before.is_empty() && m.after.is_empty()));
+    Ok(())
+}
+
+#[test]
+fn test_context_file_and_str_variants_agree() -> io::Result<()> {
+    let ws = TempWorkspace::new();
+    let file_path = ws.file("app.log", LOG);
+
+    assert_eq!(
+        search_file_with_context(&file_path, "info", 2, 2)?,
+        search_str_with_context(LOG, "info", 2, 2)
+    );
+    Ok(())
+}
@@ -4,12 +4,27 @@
 //! The exercise teaches you how to design an in-memory domain model that can later
 //! be serialized to disk by the `main.rs` driver.
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily,
+    Weekly,
+    EveryNDays(u32),
+}
+
+impl Recurrence {
+    pub fn interval_days(&self) -> u32 {
+        todo!("Return the cycle length in days")
+    }
+}
+
 /// A single task in the todo list.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Task {
     id: usize,
     description: String,
     completed: bool,
+    tags: Vec<String>,
+    recurrence: Option<Recurrence>,
 }
 
 impl Task {
@@ -29,11 +44,30 @@ impl Task {
         todo!("Return completion state")
     }
 
+    pub fn tags(&self) -> &[String] {
+        todo!("Return the task's tags")
+    }
+
+    pub fn has_tag(&self, _tag: &str) -> bool {
+        todo!("Check for a tag after normalizing it")
+    }
+
+    pub fn recurrence(&self) -> Option<Recurrence> {
+        todo!("Return the recurrence, if any")
+    }
+
     pub fn display_string(&self) -> String {
-        todo!("Create a human-readable display string for the task")
+        todo!("Create a human-readable display string for the task, with #tag markers")
     }
 }
 
+pub fn normalize_tag(_tag: &str) -> String {
+    // TODO: Trim whitespace, drop a leading '#', and lowercase.
+    todo!("Normalize a tag")
+}
+
+pub const DEFAULT_HISTORY_LIMIT: usize = 50;
+
 /// In-memory todo list for adding, completing, and removing tasks.
 pub struct TodoList {
     _private: (),
@@ -48,22 +82,70 @@ impl TodoList {
         todo!("Build a TodoList from pre-existing tasks")
     }
 
+    pub fn with_history_limit(self, _limit: usize) -> Self {
+        todo!("Cap how many undo steps are kept")
+    }
+
+    pub fn history_limit(&self) -> usize {
+        todo!("Return the undo history cap")
+    }
+
     pub fn add_task(&mut self, _description: String) -> usize {
         todo!("Add a task and return its ID")
     }
 
-    pub fn complete_task(&mut self, _id: usize) -> Result<(), String> {
+    pub fn add_recurring_task(
+        &mut self,
+        _description: String,
+        _recurrence: Recurrence,
+    ) -> Result<usize, String> {
+        todo!("Add a task that respawns on completion")
+    }
+
+    pub fn complete_task(&mut self, _id: usize) -> Result<Option<usize>, String> {
+        // TODO: Mark the task complete. If it recurs, push a fresh pending
+        // copy with a new ID and return Some(new_id).
         todo!("Mark a task complete")
     }
 
+    pub fn remove_recurrence(&mut self, _id: usize) -> Result<(), String> {
+        todo!("Stop a task from recurring")
+    }
+
     pub fn remove_task(&mut self, _id: usize) -> Result<Task, String> {
         todo!("Remove a task by ID")
     }
 
+    pub fn add_tag(&mut self, _id: usize, _tag: &str) -> Result<(), String> {
+        todo!("Add a normalized tag to a task, ignoring duplicates")
+    }
+
+    pub fn remove_tag(&mut self, _id: usize, _tag: &str) -> Result<(), String> {
+        todo!("Remove a tag from a task")
+    }
+
     pub fn clear_all(&mut self) -> usize {
         todo!("Clear all tasks and return how many were removed")
     }
 
+    pub fn undo(&mut self) -> Result<String, String> {
+        // TODO: Pop the last inverse operation, apply it, and push the
+        // operation that reverses *that* onto the redo stack.
+        todo!("Undo the most recent mutation")
+    }
+
+    pub fn redo(&mut self) -> Result<String, String> {
+        todo!("Reapply the most recently undone mutation")
+    }
+
+    pub fn can_undo(&self) -> bool {
+        todo!("Report whether there is anything to undo")
+    }
+
+    pub fn can_redo(&self) -> bool {
+        todo!("Report whether there is anything to redo")
+    }
+
     pub fn get_tasks(&self) -> &[Task] {
         todo!("Return a slice of all tasks")
     }
@@ -76,6 +158,10 @@ impl TodoList {
         todo!("Return only completed tasks")
     }
 
+    pub fn recurring_tasks(&self) -> Vec<&Task> {
+        todo!("Return only recurring tasks")
+    }
+
     pub fn pending_count(&self) -> usize {
         todo!("Count pending tasks")
     }
@@ -96,6 +182,18 @@ impl TodoList {
         todo!("Find a task by ID")
     }
 
+    pub fn tasks_with_tag(&self, _tag: &str) -> Vec<&Task> {
+        todo!("Return tasks carrying a tag")
+    }
+
+    pub fn search(&self, _query: &str) -> Vec<&Task> {
+        todo!("Case-insensitive search over descriptions and tags")
+    }
+
+    pub fn all_tags(&self) -> Vec<(String, usize)> {
+        todo!("Count tag usage, most used first")
+    }
+
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         todo!("Serialize the task list to JSON")
     }
@@ -103,6 +201,64 @@ impl TodoList {
     pub fn from_json(_json: &str) -> Result<Self, serde_json::Error> {
         todo!("Deserialize the task list from JSON")
     }
+
+    pub fn to_markdown(&self) -> String {
+        // TODO: "- [ ] desc" or "- [x] desc" per task. Prefix a backslash when
+        // the description starts with '-', '[' or '\\'.
+        todo!("Export the list as a Markdown checklist")
+    }
+
+    pub fn from_markdown(_markdown: &str) -> Result<Self, MarkdownParseError> {
+        // TODO: Skip non-checklist lines, reject bad checkboxes like "[y]"
+        // with their 1-based line number, and number tasks from 1.
+        todo!("Import a Markdown checklist")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownParseError {
+    pub line: usize,
+    pub reason: String,
+}
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(std::io::Error),
+    Serde(serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadSource {
+    Primary,
+    Backup,
+    Fresh,
+}
+
+/// Crash-safe JSON persistence for `TodoList`.
+pub struct TodoStorage;
+
+impl TodoStorage {
+    pub fn save(_list: &TodoList, _path: &std::path::Path) -> Result<(), StorageError> {
+        // TODO: Write `<name>.tmp` in the same directory and sync it, copy the
+        // current file to `<name>.bak`, then rename the temp file over the target.
+        todo!("Atomically save the list")
+    }
+
+    pub fn load(_path: &std::path::Path) -> Result<TodoList, StorageError> {
+        todo!("Load the list, falling back to the backup")
+    }
+
+    pub fn load_with_source(
+        _path: &std::path::Path,
+    ) -> Result<(TodoList, LoadSource), StorageError> {
+        // TODO: Try the primary, then `<name>.bak`. Return a fresh list only
+        // when neither file exists.
+        todo!("Load the list and report which file was used")
+    }
+
+    pub fn backup_path(_path: &std::path::Path) -> std::path::PathBuf {
+        todo!("Append .bak to the file name")
+    }
 }
 
 #[doc(hidden)]
@@ -3,8 +3,7 @@
 //! Demonstrates a simple CLI for managing tasks on disk while keeping
 //! the business logic inside `cli_todo::solution` for testability.
 
-use cli_todo::solution::TodoList;
-use std::fs;
+use cli_todo::solution::{LoadSource, Recurrence, TodoList, TodoStorage};
 use std::path::{Path, PathBuf};
 
 fn main() {
@@ -30,6 +29,32 @@ fn main() {
             todo_list.add_task(description);
             save(&todo_list, &file_path);
         }
+        "repeat" => {
+            if args.len() < 4 {
+                eprintln!("Error: Please provide a schedule and a task description");
+                eprintln!(
+                    "Usage: {} repeat <daily|weekly|DAYS> <description>",
+                    args[0]
+                );
+                return;
+            }
+            let recurrence = match args[2].as_str() {
+                "daily" => Recurrence::Daily,
+                "weekly" => Recurrence::Weekly,
+                days => match days.parse::<u32>() {
+                    Ok(n) => Recurrence::EveryNDays(n),
+                    Err(_) => {
+                        eprintln!("Error: Invalid schedule '{}'", days);
+                        return;
+                    }
+                },
+            };
+            let description = args[3..].join(" ");
+            match todo_list.add_recurring_task(description, recurrence) {
+                Ok(_) => save(&todo_list, &file_path),
+                Err(err) => eprintln!("Error: {}", err),
+            }
+        }
         "list" => {
             list_tasks(&todo_list);
         }
@@ -40,13 +65,15 @@ fn main() {
                 return;
             }
             match args[2].parse::<usize>() {
-                Ok(id) => {
-                    if let Err(err) = todo_list.complete_task(id) {
-                        eprintln!("Error: {}", err);
-                    } else {
+                Ok(id) => match todo_list.complete_task(id) {
+                    Ok(next) => {
+                        if let Some(next_id) = next {
+                            println!("Task #{} repeats as #{}", id, next_id);
+                        }
                         save(&todo_list, &file_path);
                     }
-                }
+                    Err(err) => eprintln!("Error: {}", err),
+                },
                 Err(_) => eprintln!("Error: Invalid task ID"),
             }
         }
@@ -67,6 +94,73 @@ fn main() {
                 Err(_) => eprintln!("Error: Invalid task ID"),
             }
         }
+        "tag" | "untag" => {
+            if args.len() < 4 {
+                eprintln!("Error: Please provide a task ID and a tag");
+                eprintln!("Usage: {} {} <id> <tag>", args[0], args[1]);
+                return;
+            }
+            match args[2].parse::<usize>() {
+                Ok(id) => {
+                    let result = if args[1] == "tag" {
+                        todo_list.add_tag(id, &args[3])
+                    } else {
+                        todo_list.remove_tag(id, &args[3])
+                    };
+                    if let Err(err) = result {
+                        eprintln!("Error: {}", err);
+                    } else {
+                        save(&todo_list, &file_path);
+                    }
+                }
+                Err(_) => eprintln!("Error: Invalid task ID"),
+            }
+        }
+        "search" => {
+            let query = args[2..].join(" ");
+            let matches = todo_list.search(&query);
+            if matches.is_empty() {
+                println!("No tasks match '{}'.", query);
+            }
+            for task in matches {
+                println!("{}", task.display_string());
+            }
+        }
+        "tags" => {
+            for (tag, count) in todo_list.all_tags() {
+                println!("#{} ({})", tag, count);
+            }
+        }
+        "export" => {
+            print!("{}", todo_list.to_markdown());
+        }
+        "import" => {
+            if args.len() < 3 {
+                eprintln!("Error: Please provide a Markdown file");
+                eprintln!("Usage: {} import <file.md>", args[0]);
+                return;
+            }
+            let markdown = match std::fs::read_to_string(&args[2]) {
+                Ok(text) => text,
+                Err(err) => {
+                    eprintln!("Error: Failed to read {}: {}", args[2], err);
+                    return;
+                }
+            };
+            match TodoList::from_markdown(&markdown) {
+                Ok(imported) => {
+                    for task in imported.get_tasks() {
+                        let id = todo_list.add_task(task.description().to_string());
+                        if task.is_completed() {
+                            let _ = todo_list.complete_task(id);
+                        }
+                    }
+                    println!("Imported {} tasks.", imported.total_count());
+                    save(&todo_list, &file_path);
+                }
+                Err(err) => eprintln!("Error: {}: {}", args[2], err),
+            }
+        }
         "clear" => {
             todo_list.clear_all();
             save(&todo_list, &file_path);
@@ -86,33 +180,26 @@ fn todo_file_path() -> PathBuf {
 }
 
 fn load_or_new(path: &Path) -> TodoList {
-    if path.exists() {
-        match fs::read_to_string(path) {
-            Ok(contents) => match TodoList::from_json(&contents) {
-                Ok(list) => list,
-                Err(err) => {
-                    eprintln!("Warning: Failed to parse todo file: {}", err);
-                    TodoList::new()
-                }
-            },
-            Err(err) => {
-                eprintln!("Warning: Failed to read todo file: {}", err);
-                TodoList::new()
-            }
+    match TodoStorage::load_with_source(path) {
+        Ok((list, LoadSource::Backup)) => {
+            eprintln!(
+                "Warning: {} was missing or damaged; loaded {} instead",
+                path.display(),
+                TodoStorage::backup_path(path).display()
+            );
+            list
+        }
+        Ok((list, _)) => list,
+        Err(err) => {
+            eprintln!("Warning: Failed to load todo file: {}", err);
+            TodoList::new()
         }
-    } else {
-        TodoList::new()
     }
 }
 
 fn save(list: &TodoList, path: &Path) {
-    match list.to_json() {
-        Ok(json) => {
-            if let Err(err) = fs::write(path, json) {
-                eprintln!("Error: Failed to save todo list: {}", err);
-            }
-        }
-        Err(err) => eprintln!("Error: Failed to serialize tasks: {}", err),
+    if let Err(err) = TodoStorage::save(list, path) {
+        eprintln!("Error: Failed to save todo list: {}", err);
     }
 }
 
@@ -134,15 +221,26 @@ fn print_help() {
     println!();
     println!("COMMANDS:");
     println!("    add <description>    Add a new task");
+    println!("    repeat <when> <desc> Add a recurring task (daily, weekly, or N days)");
     println!("    list                 List all tasks");
     println!("    complete <id>        Mark task as complete");
     println!("    remove <id>          Remove a task");
+    println!("    tag <id> <tag>       Tag a task");
+    println!("    untag <id> <tag>     Remove a tag from a task");
+    println!("    search <query>       Search descriptions and tags");
+    println!("    tags                 List tags by usage");
+    println!("    export               Print tasks as a Markdown checklist");
+    println!("    import <file.md>     Add tasks from a Markdown checklist");
     println!("    clear                Clear all tasks");
     println!("    help                 Show this help message");
     println!();
     println!("EXAMPLES:");
     println!("    todo add \"Buy groceries\"");
+    println!("    todo repeat weekly \"Water plants\"");
     println!("    todo list");
     println!("    todo complete 1");
     println!("    todo remove 2");
+    println!("    todo tag 1 errands");
+    println!("    todo search grocer");
+    println!("    todo export > todo.md");
 }
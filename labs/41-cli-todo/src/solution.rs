@@ -20,9 +20,46 @@
 //! 2. **Mutations**: `add_task` pushes a new task (owned struct) onto the vector. `complete_task` borrows `self.tasks` mutably through `iter_mut()`, ensuring no other reads happen simultaneously.
 //! 3. **Queries**: Functions like `pending_tasks` and `total_count` iterate over shared borrows, producing views (`&Task`) without cloning.
 //! 4. **Serialization**: `to_json` borrows `self.tasks` immutably and lets serde decide whether to copy data. `from_json` returns a new vector, owning results from the JSON parser.
+//! 5. **Tags and search**: tags are normalized on the way in (trimmed, lowercased, deduplicated), so every query can compare plain strings. `#[serde(default)]` lets task files written before tags existed still load.
+//! 6. **Recurring tasks**: a `Recurrence` rides along on the task. Completing a recurring task leaves the finished one in place (history) and pushes a fresh copy with a new ID, so `complete_task` now reports that ID as `Ok(Some(id))`.
+//! 7. **Safe persistence**: `TodoStorage` never writes the real file in place. It writes a temp file next to it, flushes it to disk, then renames it over the target; a crash at any point leaves either the old file or the new one, never half of each.
+//! 8. **Undo/redo**: every mutation records the operation that reverses it. Undoing applies that operation and records *its* inverse on the redo stack, so one `apply` function serves both directions.
+//! 9. **Markdown checklists**: `to_markdown` writes `- [ ] task` / `- [x] task` lines and `from_markdown` reads them back, skipping prose. Only the description and completion state travel; ids are reassigned on import, and tags and recurrence stay in JSON.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// How often a recurring task comes back once completed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily,
+    Weekly,
+    EveryNDays(u32),
+}
+
+impl Recurrence {
+    /// Length of one cycle in days.
+    pub fn interval_days(&self) -> u32 {
+        match self {
+            Recurrence::Daily => 1,
+            Recurrence::Weekly => 7,
+            Recurrence::EveryNDays(n) => *n,
+        }
+    }
+}
+
+impl std::fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekly => write!(f, "weekly"),
+            Recurrence::EveryNDays(n) => write!(f, "every {} days", n),
+        }
+    }
+}
 
 /// A single CLI task with metadata.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -30,6 +67,10 @@ pub struct Task {
     id: usize,
     description: String,
     completed: bool,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    recurrence: Option<Recurrence>,
 }
 
 impl Task {
@@ -39,6 +80,8 @@ impl Task {
             id,
             description,
             completed: false,
+            tags: Vec::new(),
+            recurrence: None,
         }
     }
 
@@ -54,66 +97,375 @@ impl Task {
         self.completed
     }
 
+    /// Tags in the order they were added, already normalized.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = normalize_tag(tag);
+        self.tags.contains(&tag)
+    }
+
+    pub fn recurrence(&self) -> Option<Recurrence> {
+        self.recurrence
+    }
+
     pub fn display_string(&self) -> String {
         let mark = if self.completed { 'x' } else { ' ' };
-        format!("[{}] [{}] {}", self.id, mark, self.description)
+        let mut line = format!("[{}] [{}] {}", self.id, mark, self.description);
+        for tag in &self.tags {
+            line.push_str(" #");
+            line.push_str(tag);
+        }
+        if let Some(recurrence) = self.recurrence {
+            line.push_str(&format!(" (repeats {})", recurrence));
+        }
+        line
     }
 }
 
+/// Canonical form of a tag: surrounding whitespace and a leading `#` are
+/// dropped, and the rest is lowercased.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').trim().to_lowercase()
+}
+
+/// How many undo steps a list keeps unless told otherwise.
+pub const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// A journal entry: the operation that reverses one mutation.
+///
+/// The variants are symmetric -- applying one yields another `UndoOp` that
+/// puts things back -- which is what lets undo and redo share `apply`.
+#[derive(Debug, Clone)]
+enum UndoOp {
+    /// Remove the task with this id (reverses an add or a re-insert).
+    Remove { id: usize },
+    /// Put a task back at its original position (reverses a removal).
+    Insert { index: usize, task: Task },
+    /// Set a task's completed flag (reverses a completion).
+    SetCompleted { id: usize, completed: bool },
+    /// Replace the whole task list (reverses `clear_all`).
+    ReplaceAll { tasks: Vec<Task> },
+    /// Restore a task's tag list (reverses `add_tag`/`remove_tag`).
+    SetTags { id: usize, tags: Vec<String> },
+    /// Restore a task's recurrence (reverses `remove_recurrence`).
+    SetRecurrence {
+        id: usize,
+        recurrence: Option<Recurrence>,
+    },
+    /// Several ops that form one user action, applied last to first.
+    Batch(Vec<UndoOp>),
+}
+
 /// An in-memory todo list with ID allocation, lookup helpers, and persistence helpers.
 #[derive(Debug, Clone)]
 pub struct TodoList {
     tasks: Vec<Task>,
     next_id: usize,
+    undo_stack: Vec<UndoOp>,
+    redo_stack: Vec<UndoOp>,
+    history_limit: usize,
 }
 
 impl TodoList {
     pub fn new() -> Self {
-        Self {
-            tasks: Vec::new(),
-            next_id: 1,
-        }
+        Self::from_tasks(Vec::new())
     }
 
     pub fn from_tasks(tasks: Vec<Task>) -> Self {
         let next_id = tasks.iter().map(|t| t.id()).max().unwrap_or(0) + 1;
-        Self { tasks, next_id }
+        Self {
+            tasks,
+            next_id,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            history_limit: DEFAULT_HISTORY_LIMIT,
+        }
+    }
+
+    /// Keeps at most `limit` undo steps; older ones are forgotten first.
+    pub fn with_history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = limit;
+        self.trim_history();
+        self
+    }
+
+    pub fn history_limit(&self) -> usize {
+        self.history_limit
     }
 
     pub fn add_task(&mut self, description: String) -> usize {
         let id = self.next_id;
         self.tasks.push(Task::new(id, description));
         self.next_id += 1;
+        self.record(UndoOp::Remove { id });
         id
     }
 
-    pub fn complete_task(&mut self, id: usize) -> Result<(), String> {
-        if let Some(task) = self.tasks.iter_mut().find(|t| t.id() == id) {
-            if task.completed {
-                Err(format!("Task #{} is already completed", id))
-            } else {
-                task.completed = true;
-                Ok(())
-            }
-        } else {
-            Err(format!("Task #{} not found", id))
+    /// Adds a task that respawns every time it is completed.
+    pub fn add_recurring_task(
+        &mut self,
+        description: String,
+        recurrence: Recurrence,
+    ) -> Result<usize, String> {
+        if recurrence.interval_days() == 0 {
+            return Err("Recurrence interval must be at least one day".to_string());
         }
+        let id = self.next_id;
+        let mut task = Task::new(id, description);
+        task.recurrence = Some(recurrence);
+        self.tasks.push(task);
+        self.next_id += 1;
+        self.record(UndoOp::Remove { id });
+        Ok(id)
+    }
+
+    /// Marks a task complete. For a recurring task this also adds its
+    /// successor and returns the successor's ID.
+    pub fn complete_task(&mut self, id: usize) -> Result<Option<usize>, String> {
+        let task = self
+            .tasks
+            .iter_mut()
+            .find(|t| t.id() == id)
+            .ok_or_else(|| format!("Task #{} not found", id))?;
+        if task.completed {
+            return Err(format!("Task #{} is already completed", id));
+        }
+        task.completed = true;
+        let uncomplete = UndoOp::SetCompleted {
+            id,
+            completed: false,
+        };
+
+        if task.recurrence.is_none() {
+            self.record(uncomplete);
+            return Ok(None);
+        }
+
+        let mut next = task.clone();
+        next.id = self.next_id;
+        next.completed = false;
+        self.next_id += 1;
+        let next_id = next.id;
+        self.tasks.push(next);
+        // One undo step takes back both the completion and the respawn.
+        self.record(UndoOp::Batch(vec![
+            uncomplete,
+            UndoOp::Remove { id: next_id },
+        ]));
+        Ok(Some(next_id))
+    }
+
+    /// Stops a task from recurring. Already-spawned successors are kept.
+    pub fn remove_recurrence(&mut self, id: usize) -> Result<(), String> {
+        let task = self
+            .tasks
+            .iter_mut()
+            .find(|t| t.id() == id)
+            .ok_or_else(|| format!("Task #{} not found", id))?;
+        let previous = task
+            .recurrence
+            .take()
+            .ok_or_else(|| format!("Task #{} is not recurring", id))?;
+        self.record(UndoOp::SetRecurrence {
+            id,
+            recurrence: Some(previous),
+        });
+        Ok(())
     }
 
     pub fn remove_task(&mut self, id: usize) -> Result<Task, String> {
         if let Some(index) = self.tasks.iter().position(|t| t.id() == id) {
-            Ok(self.tasks.remove(index))
+            let task = self.tasks.remove(index);
+            self.record(UndoOp::Insert {
+                index,
+                task: task.clone(),
+            });
+            Ok(task)
         } else {
             Err(format!("Task #{} not found", id))
         }
     }
 
+    /// Tags a task. Adding a tag it already has is a no-op.
+    pub fn add_tag(&mut self, id: usize, tag: &str) -> Result<(), String> {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() {
+            return Err("Tag cannot be empty".to_string());
+        }
+        let task = self
+            .tasks
+            .iter_mut()
+            .find(|t| t.id() == id)
+            .ok_or_else(|| format!("Task #{} not found", id))?;
+        if task.tags.contains(&tag) {
+            return Ok(());
+        }
+        let previous = task.tags.clone();
+        task.tags.push(tag);
+        self.record(UndoOp::SetTags { id, tags: previous });
+        Ok(())
+    }
+
+    pub fn remove_tag(&mut self, id: usize, tag: &str) -> Result<(), String> {
+        let tag = normalize_tag(tag);
+        let task = self
+            .tasks
+            .iter_mut()
+            .find(|t| t.id() == id)
+            .ok_or_else(|| format!("Task #{} not found", id))?;
+        let index = task
+            .tags
+            .iter()
+            .position(|t| *t == tag)
+            .ok_or_else(|| format!("Task #{} has no tag #{}", id, tag))?;
+        let previous = task.tags.clone();
+        task.tags.remove(index);
+        self.record(UndoOp::SetTags { id, tags: previous });
+        Ok(())
+    }
+
     pub fn clear_all(&mut self) -> usize {
         let count = self.tasks.len();
-        self.tasks.clear();
+        // Clearing an empty list changes nothing, so it is not worth an undo step.
+        if count > 0 {
+            let tasks = std::mem::take(&mut self.tasks);
+            self.record(UndoOp::ReplaceAll { tasks });
+        }
         count
     }
 
+    /// Reverts the most recent mutation and describes what changed.
+    pub fn undo(&mut self) -> Result<String, String> {
+        let op = self.undo_stack.pop().ok_or("Nothing to undo")?;
+        let (inverse, description) = self.apply(op)?;
+        self.redo_stack.push(inverse);
+        Ok(format!("Undo: {}", description))
+    }
+
+    /// Reapplies the most recently undone mutation.
+    pub fn redo(&mut self) -> Result<String, String> {
+        let op = self.redo_stack.pop().ok_or("Nothing to redo")?;
+        let (inverse, description) = self.apply(op)?;
+        self.undo_stack.push(inverse);
+        self.trim_history();
+        Ok(format!("Redo: {}", description))
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Journals a fresh mutation. A new edit makes the redo stack stale:
+    /// redoing would replay changes on top of a state they never saw.
+    fn record(&mut self, op: UndoOp) {
+        self.undo_stack.push(op);
+        self.redo_stack.clear();
+        self.trim_history();
+    }
+
+    fn trim_history(&mut self) {
+        if self.undo_stack.len() > self.history_limit {
+            let excess = self.undo_stack.len() - self.history_limit;
+            self.undo_stack.drain(..excess);
+        }
+    }
+
+    /// Performs `op`, returning the operation that reverses it and a
+    /// human-readable description of what happened.
+    fn apply(&mut self, op: UndoOp) -> Result<(UndoOp, String), String> {
+        match op {
+            UndoOp::Remove { id } => {
+                let index = self
+                    .tasks
+                    .iter()
+                    .position(|t| t.id() == id)
+                    .ok_or_else(|| format!("Task #{} not found", id))?;
+                let task = self.tasks.remove(index);
+                let description = format!("removed task #{} \"{}\"", id, task.description);
+                Ok((UndoOp::Insert { index, task }, description))
+            }
+            UndoOp::Insert { index, task } => {
+                let id = task.id();
+                let description = format!("restored task #{} \"{}\"", id, task.description);
+                self.tasks.insert(index.min(self.tasks.len()), task);
+                Ok((UndoOp::Remove { id }, description))
+            }
+            UndoOp::SetCompleted { id, completed } => {
+                let task = self
+                    .tasks
+                    .iter_mut()
+                    .find(|t| t.id() == id)
+                    .ok_or_else(|| format!("Task #{} not found", id))?;
+                let previous = task.completed;
+                task.completed = completed;
+                let state = if completed { "completed" } else { "pending" };
+                let description = format!("marked task #{} as {}", id, state);
+                Ok((
+                    UndoOp::SetCompleted {
+                        id,
+                        completed: previous,
+                    },
+                    description,
+                ))
+            }
+            UndoOp::SetTags { id, tags } => {
+                let task = self
+                    .tasks
+                    .iter_mut()
+                    .find(|t| t.id() == id)
+                    .ok_or_else(|| format!("Task #{} not found", id))?;
+                let previous = std::mem::replace(&mut task.tags, tags);
+                let description = format!("restored tags on task #{}", id);
+                Ok((UndoOp::SetTags { id, tags: previous }, description))
+            }
+            UndoOp::SetRecurrence { id, recurrence } => {
+                let task = self
+                    .tasks
+                    .iter_mut()
+                    .find(|t| t.id() == id)
+                    .ok_or_else(|| format!("Task #{} not found", id))?;
+                let previous = std::mem::replace(&mut task.recurrence, recurrence);
+                let description = match recurrence {
+                    Some(r) => format!("task #{} repeats {} again", id, r),
+                    None => format!("task #{} no longer repeats", id),
+                };
+                Ok((
+                    UndoOp::SetRecurrence {
+                        id,
+                        recurrence: previous,
+                    },
+                    description,
+                ))
+            }
+            UndoOp::Batch(ops) => {
+                let mut inverses = Vec::with_capacity(ops.len());
+                let mut descriptions = Vec::with_capacity(ops.len());
+                for op in ops.into_iter().rev() {
+                    let (inverse, description) = self.apply(op)?;
+                    inverses.push(inverse);
+                    descriptions.push(description);
+                }
+                Ok((UndoOp::Batch(inverses), descriptions.join(", ")))
+            }
+            UndoOp::ReplaceAll { tasks } => {
+                let previous = std::mem::replace(&mut self.tasks, tasks);
+                let description = if self.tasks.is_empty() {
+                    format!("cleared {} task(s)", previous.len())
+                } else {
+                    format!("restored {} task(s)", self.tasks.len())
+                };
+                Ok((UndoOp::ReplaceAll { tasks: previous }, description))
+            }
+        }
+    }
+
     pub fn get_tasks(&self) -> &[Task] {
         &self.tasks
     }
@@ -126,6 +478,13 @@ impl TodoList {
         self.tasks.iter().filter(|t| t.completed).collect()
     }
 
+    pub fn recurring_tasks(&self) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|t| t.recurrence.is_some())
+            .collect()
+    }
+
     pub fn pending_count(&self) -> usize {
         self.tasks.iter().filter(|t| !t.completed).count()
     }
@@ -146,6 +505,41 @@ impl TodoList {
         self.tasks.iter().find(|t| t.id() == id)
     }
 
+    pub fn tasks_with_tag(&self, tag: &str) -> Vec<&Task> {
+        let tag = normalize_tag(tag);
+        self.tasks
+            .iter()
+            .filter(|t| t.tags.contains(&tag))
+            .collect()
+    }
+
+    /// Case-insensitive substring search over descriptions and tags.
+    pub fn search(&self, query: &str) -> Vec<&Task> {
+        let query = query.trim().to_lowercase();
+        self.tasks
+            .iter()
+            .filter(|t| {
+                t.description.to_lowercase().contains(&query)
+                    || t.tags.iter().any(|tag| tag.contains(&query))
+            })
+            .collect()
+    }
+
+    /// Every tag in use with how many tasks carry it, most used first
+    /// (ties broken alphabetically so the order is stable).
+    pub fn all_tags(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for tag in self.tasks.iter().flat_map(|t| &t.tags) {
+            *counts.entry(tag.as_str()).or_insert(0) += 1;
+        }
+        let mut tags: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(tag, count)| (tag.to_string(), count))
+            .collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        tags
+    }
+
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(&self.tasks)
     }
@@ -154,6 +548,96 @@ impl TodoList {
         let tasks: Vec<Task> = serde_json::from_str(json)?;
         Ok(Self::from_tasks(tasks))
     }
+
+    /// One `- [ ] description` line per task, `[x]` when completed.
+    ///
+    /// A description starting with `-`, `[` or `\` gets a leading
+    /// backslash so it cannot be mistaken for checklist syntax. Line breaks
+    /// inside a description become spaces.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        for task in &self.tasks {
+            let mark = if task.completed { 'x' } else { ' ' };
+            let description = task.description.replace(['\r', '\n'], " ");
+            out.push_str("- [");
+            out.push(mark);
+            out.push_str("] ");
+            if description.starts_with(['-', '[', '\\']) {
+                out.push('\\');
+            }
+            out.push_str(&description);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Reads the checklist items of a Markdown document.
+    ///
+    /// Lines that are not list items with a checkbox (headings, prose,
+    /// plain bullets, blank lines) are skipped. Tasks get ids 1, 2, 3, ...
+    /// in document order.
+    pub fn from_markdown(markdown: &str) -> Result<Self, MarkdownParseError> {
+        let mut tasks = Vec::new();
+        for (index, line) in markdown.lines().enumerate() {
+            let item = parse_checklist_line(line).map_err(|reason| MarkdownParseError {
+                line: index + 1,
+                reason,
+            })?;
+            if let Some((completed, description)) = item {
+                let mut task = Task::new(tasks.len() + 1, description);
+                task.completed = completed;
+                tasks.push(task);
+            }
+        }
+        Ok(Self::from_tasks(tasks))
+    }
+}
+
+/// Parses one Markdown line.
+///
+/// `Ok(None)` means "not a checklist item". A bullet followed by something
+/// shaped like a checkbox (`[]` or `[?]`) must be a valid one, or the line
+/// is an error; longer brackets such as `- [link](url)` are plain prose.
+fn parse_checklist_line(line: &str) -> Result<Option<(bool, String)>, String> {
+    let trimmed = line.trim_start();
+    let Some(rest) = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("+ "))
+    else {
+        return Ok(None);
+    };
+    let rest = rest.trim_start();
+    let Some(inner) = rest.strip_prefix('[') else {
+        return Ok(None);
+    };
+
+    let mut chars = inner.chars();
+    let (mark, after) = match (chars.next(), chars.next()) {
+        (Some(']'), _) => return Err("empty checkbox `[]`".to_string()),
+        (Some(mark), Some(']')) => (mark, chars.as_str()),
+        _ => return Ok(None),
+    };
+    let completed = match mark {
+        ' ' => false,
+        'x' | 'X' => true,
+        other => {
+            return Err(format!(
+                "invalid checkbox `[{}]`, expected `[ ]` or `[x]`",
+                other
+            ))
+        }
+    };
+
+    let description = if after.is_empty() {
+        ""
+    } else if let Some(text) = after.strip_prefix(' ') {
+        text.trim()
+    } else {
+        return Err("expected a space after the checkbox".to_string());
+    };
+    let description = description.strip_prefix('\\').unwrap_or(description);
+    Ok(Some((completed, description.to_string())))
 }
 
 impl Default for TodoList {
@@ -162,6 +646,158 @@ impl Default for TodoList {
     }
 }
 
+/// A checklist line in a Markdown document that could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownParseError {
+    /// 1-based line number.
+    pub line: usize,
+    pub reason: String,
+}
+
+impl std::fmt::Display for MarkdownParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for MarkdownParseError {}
+
+/// Errors from saving or loading a todo file.
+#[derive(Debug)]
+pub enum StorageError {
+    /// Reading, writing, or renaming a file failed.
+    Io(io::Error),
+    /// The file exists but is not a valid task list.
+    Serde(serde_json::Error),
+}
+
+impl From<io::Error> for StorageError {
+    fn from(err: io::Error) -> Self {
+        StorageError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(err: serde_json::Error) -> Self {
+        StorageError::Serde(err)
+    }
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Io(err) => write!(f, "I/O error: {}", err),
+            StorageError::Serde(err) => write!(f, "invalid todo file: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StorageError::Io(err) => Some(err),
+            StorageError::Serde(err) => Some(err),
+        }
+    }
+}
+
+/// Which file `TodoStorage::load_with_source` actually read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadSource {
+    /// The requested file.
+    Primary,
+    /// The `.bak` copy, because the primary was missing or unreadable.
+    Backup,
+    /// Neither file exists yet; the list is new and empty.
+    Fresh,
+}
+
+/// Crash-safe JSON persistence for `TodoList`.
+///
+/// Saving `todo.json` goes through `todo.json.tmp` and leaves the previous
+/// version in `todo.json.bak`.
+pub struct TodoStorage;
+
+impl TodoStorage {
+    /// Writes `list` to `path` atomically, keeping the old file as a backup.
+    pub fn save(list: &TodoList, path: &Path) -> Result<(), StorageError> {
+        let json = list.to_json()?;
+        let tmp = Self::temp_path(path);
+
+        // Write and fsync the temp file first. If anything fails here the
+        // target has not been touched yet.
+        let written = fs::File::create(&tmp).and_then(|mut file| {
+            file.write_all(json.as_bytes())?;
+            file.sync_all()
+        });
+        if let Err(err) = written {
+            let _ = fs::remove_file(&tmp);
+            return Err(err.into());
+        }
+
+        if path.exists() {
+            if let Err(err) = fs::copy(path, Self::backup_path(path)) {
+                let _ = fs::remove_file(&tmp);
+                return Err(err.into());
+            }
+        }
+
+        // rename() within one directory replaces the target in a single
+        // step: readers see the old file or the new one, nothing between.
+        fs::rename(&tmp, path).map_err(|err| {
+            let _ = fs::remove_file(&tmp);
+            StorageError::from(err)
+        })
+    }
+
+    /// Loads the list at `path`, falling back to its backup.
+    pub fn load(path: &Path) -> Result<TodoList, StorageError> {
+        Self::load_with_source(path).map(|(list, _)| list)
+    }
+
+    /// Like `load`, but also reports which file the list came from.
+    ///
+    /// If the primary is missing or fails to parse, the `.bak` file is
+    /// tried. Only when neither file exists is a fresh empty list returned;
+    /// a damaged file is reported rather than silently replaced, preferring
+    /// the primary's error since that is the file the caller asked for.
+    pub fn load_with_source(path: &Path) -> Result<(TodoList, LoadSource), StorageError> {
+        let primary_err = match Self::read(path) {
+            Ok(list) => return Ok((list, LoadSource::Primary)),
+            Err(err) => err,
+        };
+        let backup = Self::backup_path(path);
+        match Self::read(&backup) {
+            Ok(list) => Ok((list, LoadSource::Backup)),
+            Err(_) if !path.exists() && !backup.exists() => {
+                Ok((TodoList::new(), LoadSource::Fresh))
+            }
+            Err(backup_err) if !path.exists() => Err(backup_err),
+            Err(_) => Err(primary_err),
+        }
+    }
+
+    /// `todo.json` -> `todo.json.bak`, in the same directory.
+    pub fn backup_path(path: &Path) -> PathBuf {
+        Self::with_suffix(path, ".bak")
+    }
+
+    fn temp_path(path: &Path) -> PathBuf {
+        Self::with_suffix(path, ".tmp")
+    }
+
+    fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(suffix);
+        path.with_file_name(name)
+    }
+
+    fn read(path: &Path) -> Result<TodoList, StorageError> {
+        let contents = fs::read_to_string(path)?;
+        Ok(TodoList::from_json(&contents)?)
+    }
+}
+
 /// Retains extra helpers for CLI demos (deduplicate before writing file server).
 pub fn unique_descriptions(todo: &TodoList) -> HashSet<String> {
     todo.get_tasks().iter().map(|task| task.description().to_string()).collect()
@@ -1,9 +1,14 @@
 // Lab 41: CLI To-Do App - Integration Tests
 //
 // These tests verify the TodoList and Task types work correctly
-// in-memory without any file I/O. All tests are deterministic and fast.
+// in-memory. Only the storage tests touch disk, each in its own TempDir.
+// All tests are deterministic and fast.
 
-use cli_todo::solution::{Task, TodoList};
+use cli_todo::solution::{
+    normalize_tag, LoadSource, MarkdownParseError, Recurrence, StorageError, Task, TodoList,
+    TodoStorage,
+};
+use tempfile::TempDir;
 
 // ============================================================================
 // TASK CONSTRUCTION AND PROPERTIES
@@ -426,6 +431,105 @@ fn test_json_roundtrip_preserves_ids() {
     assert_eq!(new_id, 3);
 }
 
+// ============================================================================
+// FILE STORAGE
+// ============================================================================
+
+fn sample_list() -> TodoList {
+    let mut list = TodoList::new();
+    list.add_task("Buy milk".to_string());
+    let id = list.add_task("Pay rent".to_string());
+    list.complete_task(id).unwrap();
+    list.add_tag(id, "bills").unwrap();
+    list
+}
+
+#[test]
+fn test_storage_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("todo.json");
+    let list = sample_list();
+
+    TodoStorage::save(&list, &path).unwrap();
+    let (loaded, source) = TodoStorage::load_with_source(&path).unwrap();
+
+    assert_eq!(source, LoadSource::Primary);
+    assert_eq!(loaded.get_tasks(), list.get_tasks());
+    assert!(!dir.path().join("todo.json.tmp").exists());
+}
+
+#[test]
+fn test_storage_keeps_previous_version_as_backup() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("todo.json");
+    let mut list = sample_list();
+    TodoStorage::save(&list, &path).unwrap();
+    let first = list.get_tasks().to_vec();
+
+    list.add_task("Third".to_string());
+    TodoStorage::save(&list, &path).unwrap();
+
+    let backup = TodoStorage::backup_path(&path);
+    assert_eq!(backup, dir.path().join("todo.json.bak"));
+    assert_eq!(
+        TodoStorage::load(&backup).unwrap().get_tasks(),
+        first.as_slice()
+    );
+    assert_eq!(TodoStorage::load(&path).unwrap().total_count(), 3);
+}
+
+#[test]
+fn test_storage_recovers_from_corrupted_primary() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("todo.json");
+    let list = sample_list();
+    TodoStorage::save(&list, &path).unwrap();
+    // The second save moves the first version into the backup.
+    TodoStorage::save(&list, &path).unwrap();
+
+    std::fs::write(&path, "{ this is not json").unwrap();
+    let (loaded, source) = TodoStorage::load_with_source(&path).unwrap();
+
+    assert_eq!(source, LoadSource::Backup);
+    assert_eq!(loaded.get_tasks(), list.get_tasks());
+}
+
+#[test]
+fn test_storage_missing_files_give_fresh_list() {
+    let dir = TempDir::new().unwrap();
+    let (list, source) = TodoStorage::load_with_source(&dir.path().join("todo.json")).unwrap();
+    assert_eq!(source, LoadSource::Fresh);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn test_storage_reports_corruption_without_backup() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("todo.json");
+    std::fs::write(&path, "[{\"id\": \"oops\"}]").unwrap();
+
+    let err = TodoStorage::load(&path).unwrap_err();
+    assert!(matches!(err, StorageError::Serde(_)), "got: {:?}", err);
+}
+
+#[test]
+fn test_failed_save_leaves_original_intact() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("todo.json");
+    TodoStorage::save(&sample_list(), &path).unwrap();
+    let original = std::fs::read_to_string(&path).unwrap();
+
+    // A directory squatting on the temp file name makes the write fail.
+    std::fs::create_dir(dir.path().join("todo.json.tmp")).unwrap();
+    let mut changed = sample_list();
+    changed.clear_all();
+    let err = TodoStorage::save(&changed, &path).unwrap_err();
+
+    assert!(matches!(err, StorageError::Io(_)), "got: {:?}", err);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    assert_eq!(TodoStorage::load(&path).unwrap().total_count(), 2);
+}
+
 // ============================================================================
 // DISPLAY STRING
 // ============================================================================
@@ -440,6 +544,455 @@ fn test_task_display_string_completed() {
     assert_eq!(task.display_string(), format!("[{}] [x] Finished task", id));
 }
 
+// ============================================================================
+// TAGS AND SEARCH
+// ============================================================================
+
+#[test]
+fn test_json_without_tags_still_loads() {
+    let json = r#"[{"id": 1, "description": "Old task", "completed": false}]"#;
+    let list = TodoList::from_json(json).unwrap();
+    let task = list.find_task(1).unwrap();
+    assert!(task.tags().is_empty());
+    assert_eq!(task.display_string(), "[1] [ ] Old task");
+}
+
+#[test]
+fn test_tags_survive_json_roundtrip() {
+    let mut list = TodoList::new();
+    let id = list.add_task("Pay rent".to_string());
+    list.add_tag(id, "bills").unwrap();
+    let restored = TodoList::from_json(&list.to_json().unwrap()).unwrap();
+    assert_eq!(restored.find_task(id).unwrap().tags(), ["bills"]);
+}
+
+#[test]
+fn test_tags_are_normalized_and_deduplicated() {
+    let mut list = TodoList::new();
+    let id = list.add_task("Buy milk".to_string());
+    list.add_tag(id, "  Errands ").unwrap();
+    list.add_tag(id, "#errands").unwrap();
+    list.add_tag(id, "ERRANDS").unwrap();
+    list.add_tag(id, "Food").unwrap();
+
+    let task = list.find_task(id).unwrap();
+    assert_eq!(task.tags(), ["errands", "food"]);
+    assert!(task.has_tag("#Food"));
+    assert_eq!(task.display_string(), "[1] [ ] Buy milk #errands #food");
+    assert_eq!(normalize_tag(" #Work "), "work");
+}
+
+#[test]
+fn test_add_tag_errors() {
+    let mut list = TodoList::new();
+    let id = list.add_task("A".to_string());
+    assert!(list.add_tag(id, "   ").is_err());
+    assert_eq!(list.add_tag(99, "x"), Err("Task #99 not found".to_string()));
+}
+
+#[test]
+fn test_remove_tag() {
+    let mut list = TodoList::new();
+    let id = list.add_task("A".to_string());
+    list.add_tag(id, "one").unwrap();
+    list.add_tag(id, "two").unwrap();
+
+    list.remove_tag(id, "ONE").unwrap();
+    assert_eq!(list.find_task(id).unwrap().tags(), ["two"]);
+    assert!(list.remove_tag(id, "one").is_err());
+
+    list.undo().unwrap();
+    assert_eq!(list.find_task(id).unwrap().tags(), ["one", "two"]);
+}
+
+#[test]
+fn test_tasks_with_tag() {
+    let mut list = TodoList::new();
+    let a = list.add_task("A".to_string());
+    let b = list.add_task("B".to_string());
+    list.add_task("C".to_string());
+    list.add_tag(a, "work").unwrap();
+    list.add_tag(b, "Work").unwrap();
+
+    let ids: Vec<usize> = list.tasks_with_tag("WORK").iter().map(|t| t.id()).collect();
+    assert_eq!(ids, vec![a, b]);
+    assert!(list.tasks_with_tag("home").is_empty());
+}
+
+#[test]
+fn test_search_matches_descriptions_and_tags() {
+    let mut list = TodoList::new();
+    let groceries = list.add_task("Buy Groceries".to_string());
+    let report = list.add_task("Write report".to_string());
+    list.add_task("Call mom".to_string());
+    list.add_tag(report, "shopping-list").unwrap();
+
+    let ids: Vec<usize> = list.search("GROC").iter().map(|t| t.id()).collect();
+    assert_eq!(ids, vec![groceries]);
+    let ids: Vec<usize> = list.search("shop").iter().map(|t| t.id()).collect();
+    assert_eq!(ids, vec![report]);
+    assert!(list.search("dentist").is_empty());
+}
+
+#[test]
+fn test_all_tags_counts_sorted_by_usage() {
+    let mut list = TodoList::new();
+    for i in 0..3 {
+        let id = list.add_task(format!("Task {}", i));
+        list.add_tag(id, "work").unwrap();
+        if i > 0 {
+            list.add_tag(id, "urgent").unwrap();
+        }
+        if i == 2 {
+            list.add_tag(id, "home").unwrap();
+            list.add_tag(id, "admin").unwrap();
+        }
+    }
+
+    assert_eq!(
+        list.all_tags(),
+        vec![
+            ("work".to_string(), 3),
+            ("urgent".to_string(), 2),
+            ("admin".to_string(), 1),
+            ("home".to_string(), 1),
+        ]
+    );
+    assert!(TodoList::new().all_tags().is_empty());
+}
+
+// ============================================================================
+// RECURRING TASKS
+// ============================================================================
+
+#[test]
+fn test_completing_recurring_task_spawns_one_successor() {
+    let mut list = TodoList::new();
+    list.add_task("One-off".to_string());
+    let id = list
+        .add_recurring_task("Water plants".to_string(), Recurrence::Weekly)
+        .unwrap();
+    list.add_tag(id, "home").unwrap();
+
+    let next = list.complete_task(id).unwrap().expect("successor id");
+    assert_eq!(next, 3);
+    assert_eq!(list.total_count(), 3);
+    assert!(list.find_task(id).unwrap().is_completed());
+
+    let successor = list.find_task(next).unwrap();
+    assert!(!successor.is_completed());
+    assert_eq!(successor.description(), "Water plants");
+    assert_eq!(successor.tags(), ["home"]);
+    assert_eq!(successor.recurrence(), Some(Recurrence::Weekly));
+
+    // IDs keep counting past the successor.
+    assert_eq!(list.add_task("Later".to_string()), 4);
+    assert_eq!(list.recurring_tasks().len(), 2);
+}
+
+#[test]
+fn test_completing_plain_task_returns_none() {
+    let mut list = TodoList::new();
+    let id = list.add_task("Plain".to_string());
+    assert_eq!(list.complete_task(id), Ok(None));
+    assert_eq!(list.total_count(), 1);
+    assert!(list.recurring_tasks().is_empty());
+}
+
+#[test]
+fn test_recurring_task_validation_and_display() {
+    let mut list = TodoList::new();
+    assert!(list
+        .add_recurring_task("Never".to_string(), Recurrence::EveryNDays(0))
+        .is_err());
+    let id = list
+        .add_recurring_task("Backup".to_string(), Recurrence::EveryNDays(3))
+        .unwrap();
+    assert_eq!(
+        list.find_task(id).unwrap().display_string(),
+        "[1] [ ] Backup (repeats every 3 days)"
+    );
+    assert_eq!(Recurrence::Daily.interval_days(), 1);
+    assert_eq!(Recurrence::Weekly.interval_days(), 7);
+}
+
+#[test]
+fn test_remove_recurrence() {
+    let mut list = TodoList::new();
+    let id = list
+        .add_recurring_task("Gym".to_string(), Recurrence::Daily)
+        .unwrap();
+    list.remove_recurrence(id).unwrap();
+    assert!(list.remove_recurrence(id).is_err());
+    assert_eq!(list.complete_task(id), Ok(None));
+    assert_eq!(list.total_count(), 1);
+}
+
+#[test]
+fn test_undo_recurring_completion_is_one_step() {
+    let mut list = TodoList::new();
+    let id = list
+        .add_recurring_task("Gym".to_string(), Recurrence::Daily)
+        .unwrap();
+    let next = list.complete_task(id).unwrap().unwrap();
+
+    list.undo().unwrap();
+    assert_eq!(list.total_count(), 1);
+    assert!(!list.find_task(id).unwrap().is_completed());
+
+    list.redo().unwrap();
+    assert!(list.find_task(id).unwrap().is_completed());
+    assert!(list.find_task(next).is_some());
+}
+
+#[test]
+fn test_recurrence_json_roundtrip_each_variant() {
+    let mut list = TodoList::new();
+    for recurrence in [
+        Recurrence::Daily,
+        Recurrence::Weekly,
+        Recurrence::EveryNDays(10),
+    ] {
+        list.add_recurring_task(format!("{:?}", recurrence), recurrence)
+            .unwrap();
+    }
+    list.add_task("Plain".to_string());
+
+    let restored = TodoList::from_json(&list.to_json().unwrap()).unwrap();
+    assert_eq!(restored.get_tasks(), list.get_tasks());
+    assert_eq!(
+        restored.find_task(3).unwrap().recurrence(),
+        Some(Recurrence::EveryNDays(10))
+    );
+    assert_eq!(restored.find_task(4).unwrap().recurrence(), None);
+}
+
+// ============================================================================
+// UNDO / REDO
+// ============================================================================
+
+#[test]
+fn test_undo_remove_restores_exact_task_and_position() {
+    let mut list = TodoList::new();
+    list.add_task("First".to_string());
+    let id = list.add_task("Second".to_string());
+    list.add_task("Third".to_string());
+    list.complete_task(id).unwrap();
+    let before = list.get_tasks().to_vec();
+
+    list.remove_task(id).unwrap();
+    let message = list.undo().unwrap();
+
+    assert!(message.contains(&format!("#{}", id)), "got: {}", message);
+    assert_eq!(list.get_tasks(), before.as_slice());
+    let restored = list.find_task(id).unwrap();
+    assert_eq!(restored.description(), "Second");
+    assert!(restored.is_completed());
+}
+
+#[test]
+fn test_undo_clear_all_restores_every_task() {
+    let mut list = TodoList::new();
+    for i in 0..5 {
+        list.add_task(format!("Task {}", i));
+    }
+    list.complete_task(3).unwrap();
+    let before = list.get_tasks().to_vec();
+
+    assert_eq!(list.clear_all(), 5);
+    assert!(list.is_empty());
+    list.undo().unwrap();
+
+    assert_eq!(list.get_tasks(), before.as_slice());
+}
+
+#[test]
+fn test_undo_add_and_complete() {
+    let mut list = TodoList::new();
+    let id = list.add_task("Walk dog".to_string());
+    list.complete_task(id).unwrap();
+
+    list.undo().unwrap();
+    assert!(!list.find_task(id).unwrap().is_completed());
+    list.undo().unwrap();
+    assert!(list.is_empty());
+    assert_eq!(list.undo(), Err("Nothing to undo".to_string()));
+}
+
+#[test]
+fn test_redo_after_undo() {
+    let mut list = TodoList::new();
+    let id = list.add_task("Write report".to_string());
+    list.remove_task(id).unwrap();
+
+    list.undo().unwrap();
+    assert!(list.find_task(id).is_some());
+    assert!(list.can_redo());
+
+    let message = list.redo().unwrap();
+    assert!(message.starts_with("Redo:"), "got: {}", message);
+    assert!(list.find_task(id).is_none());
+    assert!(!list.can_redo());
+
+    // The redone removal is itself undoable.
+    list.undo().unwrap();
+    assert_eq!(list.find_task(id).unwrap().description(), "Write report");
+}
+
+#[test]
+fn test_fresh_mutation_invalidates_redo() {
+    let mut list = TodoList::new();
+    list.add_task("A".to_string());
+    list.undo().unwrap();
+    assert!(list.can_redo());
+
+    list.add_task("B".to_string());
+    assert!(!list.can_redo());
+    assert_eq!(list.redo(), Err("Nothing to redo".to_string()));
+}
+
+#[test]
+fn test_failed_mutation_keeps_redo() {
+    let mut list = TodoList::new();
+    list.add_task("A".to_string());
+    list.undo().unwrap();
+
+    assert!(list.remove_task(42).is_err());
+    assert!(list.complete_task(42).is_err());
+    assert!(list.can_redo());
+}
+
+#[test]
+fn test_history_limit_drops_oldest_steps() {
+    let mut list = TodoList::new().with_history_limit(2);
+    assert_eq!(list.history_limit(), 2);
+    for name in ["A", "B", "C"] {
+        list.add_task(name.to_string());
+    }
+
+    list.undo().unwrap();
+    list.undo().unwrap();
+    assert!(list.undo().is_err());
+    assert_eq!(list.total_count(), 1);
+    assert_eq!(list.get_tasks()[0].description(), "A");
+}
+
+#[test]
+fn test_undo_then_add_does_not_reuse_ids() {
+    let mut list = TodoList::new();
+    let first = list.add_task("A".to_string());
+    list.undo().unwrap();
+    let second = list.add_task("B".to_string());
+    assert_ne!(first, second);
+}
+
+// ============================================================================
+// MARKDOWN CHECKLISTS
+// ============================================================================
+
+/// (description, completed) pairs in list order, ignoring ids.
+fn checklist(list: &TodoList) -> Vec<(String, bool)> {
+    list.get_tasks()
+        .iter()
+        .map(|t| (t.description().to_string(), t.is_completed()))
+        .collect()
+}
+
+#[test]
+fn test_to_markdown_lines() {
+    let mut list = TodoList::new();
+    list.add_task("Buy milk".to_string());
+    let id = list.add_task("Call mom".to_string());
+    list.complete_task(id).unwrap();
+
+    assert_eq!(list.to_markdown(), "- [ ] Buy milk\n- [x] Call mom\n");
+    assert_eq!(TodoList::new().to_markdown(), "");
+}
+
+#[test]
+fn test_markdown_roundtrip_modulo_ids() {
+    let mut list = TodoList::new();
+    let first = list.add_task("Write report".to_string());
+    list.add_task("Review PR".to_string());
+    let third = list.add_task("Ship it".to_string());
+    list.remove_task(first).unwrap();
+    list.complete_task(third).unwrap();
+
+    let restored = TodoList::from_markdown(&list.to_markdown()).unwrap();
+    assert_eq!(checklist(&restored), checklist(&list));
+
+    // Fresh sequential ids, and new tasks continue after them
+    let ids: Vec<usize> = restored.get_tasks().iter().map(|t| t.id()).collect();
+    assert_eq!(ids, vec![1, 2]);
+    let mut restored = restored;
+    assert_eq!(restored.add_task("Next".to_string()), 3);
+}
+
+#[test]
+fn test_from_markdown_skips_prose_and_headings() {
+    let doc = "# Weekend\n\
+               \n\
+               Some notes about the weekend.\n\
+               - [ ] Buy milk\n\
+               - a plain bullet\n\
+               - [see the list](http://example.com)\n\
+               \n\
+               ## Done\n\
+               * [X] Call mom\n\
+               \x20 - [x] Indented item\n\
+               + [ ]\n";
+    let list = TodoList::from_markdown(doc).unwrap();
+    assert_eq!(
+        checklist(&list),
+        vec![
+            ("Buy milk".to_string(), false),
+            ("Call mom".to_string(), true),
+            ("Indented item".to_string(), true),
+            ("".to_string(), false),
+        ]
+    );
+}
+
+#[test]
+fn test_from_markdown_reports_malformed_checkbox_line() {
+    let doc = "# List\n- [ ] ok\n\n- [y] not a checkbox\n";
+    let err = TodoList::from_markdown(doc).unwrap_err();
+    assert_eq!(err.line, 4);
+    assert!(err.reason.contains("[y]"), "{}", err.reason);
+    assert!(err.to_string().starts_with("line 4: "));
+
+    let err = TodoList::from_markdown("- [x]no space").unwrap_err();
+    assert_eq!(err.line, 1);
+    assert!(matches!(
+        TodoList::from_markdown("text\n- [] empty"),
+        Err(MarkdownParseError { line: 2, .. })
+    ));
+}
+
+#[test]
+fn test_markdown_descriptions_with_brackets_and_dashes() {
+    let descriptions = [
+        "[urgent] file taxes",
+        "- looks like a bullet",
+        "[x] looks like a checkbox",
+        "array[0] = 1",
+        "\\ starts with a backslash",
+        "ends with [brackets]",
+    ];
+    let mut list = TodoList::new();
+    for d in descriptions {
+        list.add_task(d.to_string());
+    }
+
+    let markdown = list.to_markdown();
+    assert!(markdown.contains("- [ ] \\[urgent] file taxes\n"));
+    assert!(markdown.contains("- [ ] \\- looks like a bullet\n"));
+    assert!(markdown.contains("- [ ] array[0] = 1\n"));
+
+    let restored = TodoList::from_markdown(&markdown).unwrap();
+    assert_eq!(checklist(&restored), checklist(&list));
+}
+
 // ============================================================================
 // COMPLEX SCENARIOS
 // ============================================================================
@@ -17,6 +17,35 @@ impl Link {
 }
 
 pub struct Article;
+
+/// Title, description, canonical URL, Open Graph and Twitter tags, and language.
+#[derive(Default)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub canonical_url: Option<String>,
+    pub og: std::collections::HashMap<String, String>,
+    pub twitter: std::collections::HashMap<String, String>,
+    pub lang: Option<String>,
+}
+
+impl PageMetadata {
+    pub fn best_title(&self) -> Option<&str> {
+        todo!("Prefer og:title, then <title>, then twitter:title")
+    }
+}
+
+/// A table flattened to text rows.
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn to_csv(&self) -> String {
+        todo!("Render headers and rows as CSV")
+    }
+}
 pub struct Heading;
 
 pub fn extract_title(_html: &str) -> Option<String> {
@@ -27,6 +56,12 @@ pub fn extract_links(_html: &str, _limit: Option<usize>) -> Vec<Link> {
     todo!("Extract anchor tags")
 }
 
+pub fn resolve_links(_html: &str, _base_url: &str, _limit: Option<usize>) -> Vec<Link> {
+    // TODO: Honor <base href>, skip javascript:/mailto:/#fragment links,
+    // join the rest against the base with url::Url::join, and dedup.
+    todo!("Extract links as absolute URLs")
+}
+
 pub fn extract_headings(_html: &str, _level: u8) -> Vec<Heading> {
     todo!("Find headings of level")
 }
@@ -47,5 +82,172 @@ pub fn extract_attribute(_html: &str, _selector: &str, _attr: &str) -> Vec<Strin
     todo!("Extract attribute values")
 }
 
+pub fn extract_metadata(_html: &str) -> PageMetadata {
+    // TODO: Read <meta name|property=... content=...>, <link rel="canonical">,
+    // and <html lang>. Strip the og:/twitter: prefixes for the map keys.
+    todo!("Extract page metadata")
+}
+
+pub fn extract_tables(_html: &str) -> Vec<Table> {
+    // TODO: Take headers from the first all-<th> row (or the first row),
+    // collect trimmed cell text, and pad rows to the same width.
+    todo!("Extract HTML tables")
+}
+
+pub struct CrawledPage {
+    pub url: String,
+    pub depth: usize,
+    pub title: Option<String>,
+    pub link_count: usize,
+}
+
+#[derive(Default)]
+pub struct CrawlReport {
+    pub pages: Vec<CrawledPage>,
+    pub skipped_external: usize,
+    pub errors: Vec<(String, String)>,
+}
+
+pub struct Crawler<F>
+where
+    F: Fn(&str) -> Result<String, String>,
+{
+    fetcher: F,
+    start_url: String,
+    max_depth: usize,
+    max_pages: usize,
+}
+
+impl<F> Crawler<F>
+where
+    F: Fn(&str) -> Result<String, String>,
+{
+    pub fn new(
+        fetcher: F,
+        start_url: impl Into<String>,
+        max_depth: usize,
+        max_pages: usize,
+    ) -> Self {
+        Crawler {
+            fetcher,
+            start_url: start_url.into(),
+            max_depth,
+            max_pages,
+        }
+    }
+
+    pub fn crawl(&self) -> CrawlReport {
+        // TODO: Breadth-first over a VecDeque<(url, depth)>. Keep a visited
+        // set, resolve each page's links, follow only same-origin ones up to
+        // max_depth, count external ones, and record fetch errors.
+        todo!("Crawl the site")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RobotsRule {
+    allow: bool,
+    pattern: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct RobotsGroup {
+    agents: Vec<String>,
+    rules: Vec<RobotsRule>,
+    crawl_delay: Option<std::time::Duration>,
+}
+
+/// A parsed robots.txt file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsTxt {
+    groups: Vec<RobotsGroup>,
+}
+
+impl RobotsTxt {
+    pub fn parse(content: &str) -> RobotsTxt {
+        // TODO: Split into groups: consecutive User-agent lines start a group,
+        // Allow/Disallow/Crawl-delay lines attach to it. Strip `#` comments,
+        // skip unknown directives and empty Disallow values.
+        let _ = content;
+        todo!("Parse robots.txt")
+    }
+
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        // TODO: Pick the most specific matching agent group (or `*`), then
+        // the longest matching rule; Allow wins ties, no match means allowed.
+        let _ = (user_agent, path);
+        todo!("Check robots.txt permission")
+    }
+
+    pub fn crawl_delay(&self, user_agent: &str) -> Option<std::time::Duration> {
+        // TODO: Crawl-delay of the group this agent obeys.
+        let _ = user_agent;
+        todo!("Look up crawl delay")
+    }
+}
+
+/// The page's visible text, one line per block element.
+pub fn html_to_text(html: &str) -> String {
+    // TODO: Walk the DOM from the root element. Skip head/script/style,
+    // start a new line at block elements (p, div, li, headings), add a line
+    // break per <br>, and collapse whitespace inside text nodes.
+    let _ = html;
+    todo!("Render HTML as plain text")
+}
+
+/// The page as Markdown: headings, links, emphasis, lists, code blocks.
+pub fn html_to_markdown(html: &str) -> String {
+    // TODO: Same walk as html_to_text, but write `#` for headings,
+    // `[text](href)` for links, `**`/`*` for strong/em, `-` or `1.` list
+    // markers indented under their parent item, and ``` fences for <pre>.
+    let _ = html;
+    todo!("Render HTML as Markdown")
+}
+
+/// Collapse whitespace within lines and runs of blank lines.
+pub fn normalize_whitespace(text: &str) -> String {
+    // TODO: Join each line's words with single spaces, keep at most one blank
+    // line in a row, and drop blank lines at the start and end.
+    let _ = text;
+    todo!("Normalize whitespace")
+}
+
+/// An HTML `<form>` and the fields it would submit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Form {
+    pub action: String,
+    pub method: String,
+    pub fields: Vec<FormField>,
+}
+
+/// One `<input>`, `<textarea>`, or `<select>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormField {
+    pub name: String,
+    pub field_type: String,
+    pub value: Option<String>,
+    pub options: Vec<String>,
+    pub required: bool,
+}
+
+impl Form {
+    /// The default submission as an `application/x-www-form-urlencoded` body.
+    pub fn to_query_string(&self) -> String {
+        // TODO: Skip unnamed fields, buttons, and file inputs; send checkboxes
+        // and radios only when checked. `url::form_urlencoded::Serializer`
+        // does the percent-encoding.
+        todo!("Encode the form's default values")
+    }
+}
+
+/// Every `<form>` with its action, method (default "get"), and fields.
+pub fn extract_forms(html: &str) -> Vec<Form> {
+    // TODO: For each form, select `input, textarea, select` in document order.
+    // Hidden inputs count. A select's value is its `selected` option, else the
+    // first; a textarea's value is its text; `required` is just presence.
+    let _ = html;
+    todo!("Extract forms")
+}
+
 #[doc(hidden)]
 pub mod solution;
@@ -2,7 +2,11 @@
 
 use std::error::Error;
 use tokio::time::{sleep, Duration};
-use web_scraper::solution::{extract_articles, extract_headings, extract_links, extract_title};
+use web_scraper::solution::{
+    extract_articles, extract_forms, extract_headings, extract_links, extract_metadata,
+    extract_tables, extract_title, html_to_markdown, html_to_text, resolve_links, Crawler,
+    RobotsTxt,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -12,8 +16,56 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     println!("Title: {:?}", extract_title(html));
     println!("Links: {:?}", extract_links(html, None));
+    println!(
+        "Resolved: {:?}",
+        resolve_links(html, "https://example.com/blog/", None)
+    );
     println!("Headings: {:?}", extract_headings(html, 1));
     println!("Articles: {:?}", extract_articles(html));
+    println!("Best title: {:?}", extract_metadata(html).best_title());
+
+    let table_html = "<table><tr><th>Lab</th><th>Topic</th></tr><tr><td>44</td><td>Web <b>scraping</b></td></tr></table>";
+    for table in extract_tables(table_html) {
+        print!("Table as CSV:\n{}", table.to_csv());
+    }
+
+    // The crawler takes any fetch function; here it serves two canned pages.
+    let site = |url: &str| match url {
+        "https://demo.test/" => Ok(r#"<title>Home</title><a href="/docs">Docs</a>"#.to_string()),
+        "https://demo.test/docs" => Ok("<title>Docs</title>".to_string()),
+        _ => Err("not found".to_string()),
+    };
+    for page in Crawler::new(site, "https://demo.test/", 2, 10)
+        .crawl()
+        .pages
+    {
+        println!(
+            "Crawled {} (depth {}): {:?}",
+            page.url, page.depth, page.title
+        );
+    }
+
+    let robots = RobotsTxt::parse("User-agent: *\nDisallow: /private/\nCrawl-delay: 1\n");
+    println!(
+        "robots.txt: /private/x allowed? {}, crawl delay {:?}",
+        robots.is_allowed("rust-edu-bot", "/private/x"),
+        robots.crawl_delay("rust-edu-bot")
+    );
+
+    let page =
+        "<h2>Notes</h2><p>See <a href=\"/faq\">the FAQ</a>.</p><ul><li>one</li><li>two</li></ul>";
+    println!("As text:\n{}", html_to_text(page));
+    println!("As Markdown:\n{}", html_to_markdown(page));
+
+    let login = r#"<form action="/login" method="post"><input type="hidden" name="csrf" value="a+b/c"><input name="user" value="ferris"><input type="submit"></form>"#;
+    for form in extract_forms(login) {
+        println!(
+            "Form {} {} -> {}",
+            form.method.to_uppercase(),
+            form.action,
+            form.to_query_string()
+        );
+    }
 
     sleep(Duration::from_millis(10)).await;
     Ok(())
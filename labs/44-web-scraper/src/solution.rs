@@ -8,8 +8,12 @@
 // - HTML parsing with the `scraper` crate
 // - CSS selector-based data extraction
 // - Structured data models for scraped content
+// - Walking the DOM to render readable text and Markdown
 
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Node, Selector};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+use url::Url;
 
 // ============================================================================
 // DATA STRUCTURES
@@ -41,6 +45,71 @@ pub struct Heading {
     pub text: String,
 }
 
+/// Metadata from a page's `<head>`: what link previews and search engines
+/// use to describe the page.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageMetadata {
+    /// Text of the `<title>` element
+    pub title: Option<String>,
+    /// `<meta name="description">`
+    pub description: Option<String>,
+    /// `<link rel="canonical" href="...">`
+    pub canonical_url: Option<String>,
+    /// Open Graph properties with the `og:` prefix removed (`"title"`, `"image"`, ...)
+    pub og: HashMap<String, String>,
+    /// Twitter card tags with the `twitter:` prefix removed (`"card"`, `"site"`, ...)
+    pub twitter: HashMap<String, String>,
+    /// The `<html lang="...">` attribute
+    pub lang: Option<String>,
+}
+
+impl PageMetadata {
+    /// The title a link preview would show: `og:title`, then `<title>`,
+    /// then `twitter:title`.
+    pub fn best_title(&self) -> Option<&str> {
+        self.og
+            .get("title")
+            .map(String::as_str)
+            .or(self.title.as_deref())
+            .or(self.twitter.get("title").map(String::as_str))
+    }
+}
+
+/// An HTML table flattened to text.
+///
+/// Every row in `rows` has the same number of cells as `headers`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Render the table as CSV: a header row (if any), then one line per
+    /// row. Cells containing commas, quotes, or line breaks are quoted, with
+    /// inner quotes doubled, as RFC 4180 describes.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        let lines = std::iter::once(&self.headers)
+            .filter(|headers| !headers.is_empty())
+            .chain(&self.rows);
+        for cells in lines {
+            let line: Vec<String> = cells.iter().map(|cell| csv_field(cell)).collect();
+            out.push_str(&line.join(","));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn csv_field(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
 // ============================================================================
 // HTML PARSING FUNCTIONS
 // ============================================================================
@@ -85,6 +154,77 @@ pub fn extract_links(html: &str, limit: Option<usize>) -> Vec<Link> {
     }
 }
 
+/// Extract links and resolve them to absolute URLs.
+///
+/// Relative hrefs (`/about`, `../index.html`, `page?x=1`) are joined against
+/// the page's base URL using the `url` crate, which implements the same
+/// resolution rules as browsers, dot segments included. If the document has
+/// a `<base href="...">` tag, that (itself resolved against `base_url`)
+/// becomes the base instead.
+///
+/// `javascript:` and `mailto:` links and fragment-only links (`#top`) are
+/// skipped because they don't point at another page. The result is
+/// deduplicated by resolved URL, keeping the first link's text, and
+/// `limit` caps the number of links returned.
+///
+/// If `base_url` can't be parsed, absolute hrefs are still returned but
+/// relative ones are dropped, since there is nothing to resolve them against.
+pub fn resolve_links(html: &str, base_url: &str, limit: Option<usize>) -> Vec<Link> {
+    let document = Html::parse_document(html);
+    let page_base = Url::parse(base_url).ok();
+
+    let base = match Selector::parse("base[href]") {
+        Ok(selector) => document
+            .select(&selector)
+            .next()
+            .and_then(|element| element.value().attr("href"))
+            .and_then(|href| join_url(page_base.as_ref(), href))
+            .or(page_base),
+        Err(_) => page_base,
+    };
+
+    let selector = match Selector::parse("a[href]") {
+        Ok(s) => s,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut seen = HashSet::new();
+    let iter = document.select(&selector).filter_map(|element| {
+        let href = element.value().attr("href")?.trim();
+        if is_non_navigational(href) {
+            return None;
+        }
+        let resolved = join_url(base.as_ref(), href)?.to_string();
+        if !seen.insert(resolved.clone()) {
+            return None;
+        }
+        Some(Link {
+            href: resolved,
+            text: element.text().collect::<String>().trim().to_string(),
+        })
+    });
+
+    match limit {
+        Some(n) => iter.take(n).collect(),
+        None => iter.collect(),
+    }
+}
+
+/// Resolve `href` against `base`, or parse it on its own when there is no
+/// base (which only works for absolute URLs).
+fn join_url(base: Option<&Url>, href: &str) -> Option<Url> {
+    match base {
+        Some(base) => base.join(href).ok(),
+        None => Url::parse(href).ok(),
+    }
+}
+
+/// Links that run script, open a mail client, or jump within the page.
+fn is_non_navigational(href: &str) -> bool {
+    let lower = href.to_ascii_lowercase();
+    href.starts_with('#') || lower.starts_with("javascript:") || lower.starts_with("mailto:")
+}
+
 /// Extract all headings of a given level (h1-h6) from an HTML document.
 ///
 /// The `level` parameter must be between 1 and 6 (inclusive).
@@ -205,6 +345,976 @@ pub fn extract_attribute(html: &str, css_selector: &str, attr: &str) -> Vec<Stri
         .collect()
 }
 
+/// Extract the page's metadata from `<title>`, `<meta>`, `<link>`, and
+/// `<html lang>`.
+///
+/// `<meta>` tags are matched on either `name` or `property`, since sites
+/// use both for Open Graph and Twitter tags. Names are compared
+/// case-insensitively (the HTML parser already lowercases attribute
+/// *names*, so `Property=` works too). When a tag repeats, the first one
+/// wins. Anything missing is simply `None` or left out of the maps.
+pub fn extract_metadata(html: &str) -> PageMetadata {
+    let document = Html::parse_document(html);
+    let mut metadata = PageMetadata {
+        title: extract_title(html).filter(|t| !t.is_empty()),
+        lang: document
+            .root_element()
+            .value()
+            .attr("lang")
+            .map(str::to_string),
+        ..PageMetadata::default()
+    };
+
+    if let Ok(selector) = Selector::parse("meta[content]") {
+        for meta in document.select(&selector) {
+            let element = meta.value();
+            let Some(key) = element.attr("property").or(element.attr("name")) else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let content = element
+                .attr("content")
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+
+            if let Some(og) = key.strip_prefix("og:") {
+                metadata.og.entry(og.to_string()).or_insert(content);
+            } else if let Some(twitter) = key.strip_prefix("twitter:") {
+                metadata
+                    .twitter
+                    .entry(twitter.to_string())
+                    .or_insert(content);
+            } else if key == "description" && metadata.description.is_none() {
+                metadata.description = Some(content);
+            }
+        }
+    }
+
+    if let Ok(selector) = Selector::parse("link[rel][href]") {
+        metadata.canonical_url = document
+            .select(&selector)
+            .find(|link| {
+                link.value()
+                    .attr("rel")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .any(|rel| rel.eq_ignore_ascii_case("canonical"))
+            })
+            .and_then(|link| link.value().attr("href"))
+            .map(str::to_string);
+    }
+
+    metadata
+}
+
+/// Extract every `<table>` in the document as a rectangular `Table`.
+///
+/// - Headers come from the first row made up only of `<th>` cells. If
+///   there is no such row, the first row is used as the header.
+/// - Cell text is trimmed and inner whitespace collapsed, so markup such as
+///   `<b>` or `<a>` inside a cell just contributes its text.
+/// - Short rows are padded with empty strings to the widest row.
+/// - Simplification: `colspan`/`rowspan` are ignored, so a spanning cell
+///   counts as one cell.
+/// - A table nested inside another is returned as its own `Table`; its
+///   rows are not mixed into the outer one.
+pub fn extract_tables(html: &str) -> Vec<Table> {
+    let document = Html::parse_document(html);
+    let (table_selector, row_selector) = match (Selector::parse("table"), Selector::parse("tr")) {
+        (Ok(t), Ok(r)) => (t, r),
+        _ => return Vec::new(),
+    };
+
+    document
+        .select(&table_selector)
+        .map(|table| {
+            let mut rows: Vec<(bool, Vec<String>)> = table
+                .select(&row_selector)
+                .filter(|row| owning_table(*row).map(|t| t.id()) == Some(table.id()))
+                .map(row_cells)
+                .filter(|(_, cells)| !cells.is_empty())
+                .collect();
+
+            let header_index = rows.iter().position(|(all_th, _)| *all_th).unwrap_or(0);
+            let mut headers = if rows.is_empty() {
+                Vec::new()
+            } else {
+                rows.remove(header_index).1
+            };
+            let mut rows: Vec<Vec<String>> = rows.into_iter().map(|(_, cells)| cells).collect();
+
+            let width = rows
+                .iter()
+                .map(Vec::len)
+                .chain([headers.len()])
+                .max()
+                .unwrap_or(0);
+            headers.resize(width, String::new());
+            for row in &mut rows {
+                row.resize(width, String::new());
+            }
+
+            Table { headers, rows }
+        })
+        .collect()
+}
+
+/// The closest `<table>` ancestor of a row.
+fn owning_table(row: ElementRef) -> Option<ElementRef> {
+    row.ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|el| el.value().name() == "table")
+}
+
+/// The text of a row's `<td>`/`<th>` cells, and whether they were all `<th>`.
+fn row_cells(row: ElementRef) -> (bool, Vec<String>) {
+    let mut all_th = true;
+    let cells: Vec<String> = row
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+        .map(|cell| {
+            all_th &= cell.value().name() == "th";
+            let text: String = cell.text().collect();
+            text.split_whitespace().collect::<Vec<_>>().join(" ")
+        })
+        .collect();
+    (all_th, cells)
+}
+
+// ============================================================================
+// CRAWLING
+// ============================================================================
+// Crawling is mostly bookkeeping: which URLs are waiting (the "frontier"),
+// which were already seen, how deep we are, and when to stop. None of that
+// needs the network, so `Crawler` takes the fetcher as a closure. `main.rs`
+// can pass a closure that does real HTTP; tests pass one that looks pages
+// up in a HashMap.
+//
+// The frontier is a FIFO queue, which makes the crawl breadth-first: every
+// page at depth 1 is fetched before any page at depth 2. Links are queued
+// in document order, so the same site always produces the same report.
+
+/// One successfully fetched page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrawledPage {
+    pub url: String,
+    /// Link hops from the start URL (the start page is depth 0).
+    pub depth: usize,
+    pub title: Option<String>,
+    /// Number of distinct links on the page (internal and external).
+    pub link_count: usize,
+}
+
+/// Everything a crawl found.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CrawlReport {
+    /// Fetched pages in the order they were crawled.
+    pub pages: Vec<CrawledPage>,
+    /// Distinct links to other origins that were seen but not followed.
+    pub skipped_external: usize,
+    /// `(url, error)` for every fetch that failed.
+    pub errors: Vec<(String, String)>,
+}
+
+/// A breadth-first, same-origin crawler with an injectable fetcher.
+pub struct Crawler<F>
+where
+    F: Fn(&str) -> Result<String, String>,
+{
+    fetcher: F,
+    start_url: String,
+    max_depth: usize,
+    max_pages: usize,
+}
+
+impl<F> Crawler<F>
+where
+    F: Fn(&str) -> Result<String, String>,
+{
+    /// `max_depth` is how many link hops to follow from the start page;
+    /// `max_pages` caps the number of fetches, failed ones included.
+    pub fn new(
+        fetcher: F,
+        start_url: impl Into<String>,
+        max_depth: usize,
+        max_pages: usize,
+    ) -> Self {
+        Crawler {
+            fetcher,
+            start_url: start_url.into(),
+            max_depth,
+            max_pages,
+        }
+    }
+
+    /// Crawl from the start URL, following links that share its origin
+    /// (scheme, host, and port). Fragments are dropped, so `/a#x` and `/a`
+    /// are the same page.
+    pub fn crawl(&self) -> CrawlReport {
+        let mut report = CrawlReport::default();
+        let start = match Url::parse(&self.start_url) {
+            Ok(url) => url,
+            Err(e) => {
+                report
+                    .errors
+                    .push((self.start_url.clone(), format!("invalid URL: {}", e)));
+                return report;
+            }
+        };
+
+        let mut queued: HashSet<String> = HashSet::new();
+        let mut external: HashSet<String> = HashSet::new();
+        let mut frontier: VecDeque<(String, usize)> = VecDeque::new();
+        queued.insert(start.to_string());
+        frontier.push_back((start.to_string(), 0));
+        let mut fetches = 0;
+
+        while let Some((url, depth)) = frontier.pop_front() {
+            if fetches == self.max_pages {
+                break;
+            }
+            fetches += 1;
+
+            let html = match (self.fetcher)(&url) {
+                Ok(html) => html,
+                Err(e) => {
+                    report.errors.push((url, e));
+                    continue;
+                }
+            };
+
+            let links = resolve_links(&html, &url, None);
+            for link in &links {
+                let Ok(mut target) = Url::parse(&link.href) else {
+                    continue;
+                };
+                target.set_fragment(None);
+                if target.origin() != start.origin() {
+                    external.insert(target.to_string());
+                } else if depth < self.max_depth && queued.insert(target.to_string()) {
+                    frontier.push_back((target.to_string(), depth + 1));
+                }
+            }
+
+            report.pages.push(CrawledPage {
+                title: extract_title(&html).filter(|t| !t.is_empty()),
+                link_count: links.len(),
+                url,
+                depth,
+            });
+        }
+
+        report.skipped_external = external.len();
+        report
+    }
+}
+
+// ============================================================================
+// ROBOTS.TXT
+// ============================================================================
+// A polite scraper asks a site what it may fetch before fetching it. The
+// answer lives in `/robots.txt`, a line-based file of groups:
+//
+//     User-agent: examplebot      <- one or more agent lines start a group
+//     Disallow: /private/         <- rules for every agent in the group
+//     Allow: /private/press/
+//     Crawl-delay: 2
+//
+// The rules follow RFC 9309 (how Google and Bing read the file):
+// - A crawler obeys only ONE group: the one naming it most specifically,
+//   falling back to `*`. Groups naming the same agent are merged.
+// - Within that group, the rule with the LONGEST pattern matching the path
+//   wins; if an Allow and a Disallow tie, Allow wins.
+// - `*` in a pattern matches any run of characters and a trailing `$`
+//   anchors it to the end of the path. Without `$`, a pattern is a prefix.
+// - An empty `Disallow:` disallows nothing, and unknown lines are skipped.
+
+/// One `Allow` or `Disallow` line.
+#[derive(Debug, Clone, PartialEq)]
+struct RobotsRule {
+    allow: bool,
+    pattern: String,
+}
+
+/// Consecutive `User-agent` lines and the directives after them.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct RobotsGroup {
+    /// Lowercased agent names; `*` means every crawler.
+    agents: Vec<String>,
+    rules: Vec<RobotsRule>,
+    crawl_delay: Option<Duration>,
+}
+
+/// A parsed robots.txt file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsTxt {
+    groups: Vec<RobotsGroup>,
+}
+
+impl RobotsTxt {
+    /// Parse robots.txt content. Parsing never fails: lines that can't be
+    /// understood are ignored, as crawlers are expected to do.
+    pub fn parse(content: &str) -> RobotsTxt {
+        let mut groups: Vec<RobotsGroup> = Vec::new();
+        let mut current: Option<RobotsGroup> = None;
+        // True while we're still reading the agent lines of `current`
+        let mut collecting_agents = false;
+
+        for line in content.trim_start_matches('\u{feff}').lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if !collecting_agents {
+                        groups.extend(current.take());
+                        current = Some(RobotsGroup::default());
+                        collecting_agents = true;
+                    }
+                    if let Some(group) = current.as_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                directive @ ("allow" | "disallow") => {
+                    collecting_agents = false;
+                    // Rules before any User-agent line belong to no one
+                    if let (Some(group), false) = (current.as_mut(), value.is_empty()) {
+                        group.rules.push(RobotsRule {
+                            allow: directive == "allow",
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    collecting_agents = false;
+                    // Negative, NaN, infinite, or too large for a Duration
+                    // (`1e30`): ignored, like any other unparseable value.
+                    let delay = value
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+                    if let (Some(group), Some(delay)) = (current.as_mut(), delay) {
+                        group.crawl_delay = Some(delay);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        groups.extend(current);
+        RobotsTxt { groups }
+    }
+
+    /// May `user_agent` fetch `path`? `path` is everything after the host,
+    /// query string included (e.g. `/search?q=rust`).
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        let path = if path.is_empty() { "/" } else { path };
+        self.groups_for(user_agent)
+            .iter()
+            .flat_map(|group| &group.rules)
+            .filter(|rule| robots_pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .map_or(true, |rule| rule.allow)
+    }
+
+    /// How long `user_agent` should wait between requests, if the site says
+    pub fn crawl_delay(&self, user_agent: &str) -> Option<Duration> {
+        self.groups_for(user_agent)
+            .iter()
+            .find_map(|group| group.crawl_delay)
+    }
+
+    /// The groups `user_agent` must obey.
+    ///
+    /// The agent's product token (`ExampleBot` in `ExampleBot/2.1`) is
+    /// compared case-insensitively; the longest group name it starts with
+    /// wins, otherwise the `*` groups apply.
+    fn groups_for(&self, user_agent: &str) -> Vec<&RobotsGroup> {
+        let token = user_agent
+            .split('/')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+        let chosen = self
+            .groups
+            .iter()
+            .flat_map(|group| &group.agents)
+            .filter(|agent| agent.as_str() != "*" && !agent.is_empty())
+            .filter(|agent| token.starts_with(agent.as_str()))
+            .max_by_key(|agent| agent.len())
+            .map_or("*", |agent| agent.as_str());
+
+        self.groups
+            .iter()
+            .filter(|group| group.agents.iter().any(|agent| agent == chosen))
+            .collect()
+    }
+}
+
+/// Does a robots.txt pattern match `path`?
+///
+/// `*` matches any run of bytes and a trailing `$` means the path must end
+/// there; otherwise the pattern only has to match a prefix of the path.
+fn robots_pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(rest) => (rest, true),
+        None => (pattern, false),
+    };
+    let pattern = pattern.as_bytes();
+    let path = path.as_bytes();
+
+    // Classic wildcard matching with backtracking to the last `*`
+    let (mut p, mut t) = (0, 0);
+    let mut last_star: Option<(usize, usize)> = None;
+    while t < path.len() {
+        if p == pattern.len() && !anchored {
+            return true;
+        }
+        if p < pattern.len() && pattern[p] == b'*' {
+            last_star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == path[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = last_star {
+            // Let the last `*` swallow one more byte and try again
+            p = star_p + 1;
+            t = star_t + 1;
+            last_star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&b| b == b'*')
+}
+
+// ============================================================================
+// READABLE TEXT
+// ============================================================================
+// Selectors pull out known pieces of a page; sometimes you just want to read
+// it. Both converters below walk the parsed DOM once, writing into a small
+// builder that owns every whitespace decision:
+//
+// - Text nodes have their whitespace runs collapsed to single spaces, as a
+//   browser would render them.
+// - Block elements (p, div, li, headings, ...) don't write newlines
+//   directly; they *request* a line break (1) or a blank line (2). The
+//   request is only paid out when more content arrives, so nested blocks
+//   never stack up blank lines and nothing dangles at the end.
+// - Each `<br>` adds one line break, but breaks are capped at one blank
+//   line: `a<br><br><br>b` renders as "a", blank line, "b".
+//
+// Markdown adds a line prefix (`indent`) so that wrapped list items and
+// nested lists line up under their parent's text, which is what makes
+// CommonMark treat them as nested.
+
+/// Elements whose content is never shown.
+const HIDDEN_ELEMENTS: &[&str] = &["head", "script", "style", "noscript", "template"];
+
+/// Elements separated from their surroundings by a blank line.
+const PARAGRAPH_ELEMENTS: &[&str] = &[
+    "p",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "pre",
+    "blockquote",
+    "table",
+    "hr",
+    "ul",
+    "ol",
+];
+
+/// Elements that start on their own line.
+const LINE_ELEMENTS: &[&str] = &[
+    "div",
+    "li",
+    "tr",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "nav",
+    "main",
+    "aside",
+    "form",
+    "dl",
+    "dt",
+    "dd",
+    "figure",
+    "figcaption",
+    "address",
+];
+
+/// The page's visible text: scripts, styles, and `<head>` dropped, one
+/// line per block element, whitespace collapsed.
+pub fn html_to_text(html: &str) -> String {
+    normalize_whitespace(&render_readable(html, Flavor::Text))
+}
+
+/// The page as Markdown: `#` headings, `[text](href)` links, `**bold**`
+/// and `*italic*`, `-`/`1.` lists (nested by indentation), and fenced code
+/// blocks from `<pre>`.
+///
+/// Simplification: characters that mean something in Markdown (`*`, `_`,
+/// `[`) are not escaped in ordinary text.
+pub fn html_to_markdown(html: &str) -> String {
+    let markdown = render_readable(html, Flavor::Markdown);
+    let lines: Vec<&str> = markdown.lines().map(str::trim_end).collect();
+    lines.join("\n").trim().to_string()
+}
+
+/// Collapse spaces and tabs inside each line, trim every line, allow at most
+/// one blank line in a row, and drop blank lines at either end.
+///
+/// Idempotent: normalizing normalized text changes nothing.
+pub fn normalize_whitespace(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        let previous_blank = lines.last().map_or(true, |l: &String| l.is_empty());
+        if !line.is_empty() || !previous_blank {
+            lines.push(line);
+        }
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Flavor {
+    Text,
+    Markdown,
+}
+
+fn render_readable(html: &str, flavor: Flavor) -> String {
+    let document = Html::parse_document(html);
+    let mut renderer = Renderer::new(flavor);
+    renderer.element(document.root_element());
+    renderer.out.out
+}
+
+/// Accumulates output and decides where spaces and newlines go.
+#[derive(Default)]
+struct TextBuilder {
+    out: String,
+    /// Newlines owed before the next content (0-2).
+    pending_breaks: usize,
+    /// A space is owed before the next content on the same line.
+    pending_space: bool,
+    /// Written at the start of every new line (Markdown list nesting).
+    indent: String,
+}
+
+impl TextBuilder {
+    /// Content after this starts a new line (1) or paragraph (2); 0 is a
+    /// no-op so inline elements can share the block code path.
+    fn block(&mut self, breaks: usize) {
+        if breaks > 0 {
+            self.pending_breaks = self.pending_breaks.max(breaks);
+            self.pending_space = false;
+        }
+    }
+
+    /// A `<br>`: one more newline, capped at a blank line.
+    fn line_break(&mut self) {
+        self.pending_breaks = (self.pending_breaks + 1).min(2);
+        self.pending_space = false;
+    }
+
+    /// Pay out owed newlines, indentation, and spaces before new content.
+    fn start_content(&mut self) {
+        if !self.out.is_empty() && self.pending_breaks > 0 {
+            for _ in 0..self.pending_breaks {
+                self.out.push('\n');
+            }
+        } else if self.pending_space && !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push(' ');
+        }
+        if self.out.is_empty() || self.out.ends_with('\n') {
+            self.out.push_str(&self.indent);
+        }
+        self.pending_breaks = 0;
+        self.pending_space = false;
+    }
+
+    /// Write `s` as-is (no leading whitespace expected).
+    fn write(&mut self, s: &str) {
+        self.start_content();
+        self.out.push_str(s);
+    }
+
+    /// Write a text node, collapsing its whitespace.
+    fn text(&mut self, s: &str) {
+        if s.starts_with(char::is_whitespace) {
+            self.pending_space = true;
+        }
+        for (i, word) in s.split_whitespace().enumerate() {
+            if i > 0 {
+                self.pending_space = true;
+            }
+            self.write(word);
+        }
+        if s.ends_with(char::is_whitespace) {
+            self.pending_space = true;
+        }
+    }
+
+    /// Write preformatted lines, keeping their spacing.
+    fn write_lines(&mut self, text: &str) {
+        for (i, line) in text.lines().enumerate() {
+            if i > 0 {
+                self.out.push('\n');
+                self.out.push_str(&self.indent);
+            }
+            if i == 0 {
+                self.start_content();
+            }
+            self.out.push_str(line);
+        }
+    }
+}
+
+struct Renderer {
+    flavor: Flavor,
+    out: TextBuilder,
+    /// One entry per open list: `None` for `<ul>`, the next number for `<ol>`.
+    lists: Vec<Option<u64>>,
+}
+
+impl Renderer {
+    fn new(flavor: Flavor) -> Self {
+        Renderer {
+            flavor,
+            out: TextBuilder::default(),
+            lists: Vec::new(),
+        }
+    }
+
+    fn children(&mut self, el: ElementRef) {
+        for child in el.children() {
+            if let Some(child) = ElementRef::wrap(child) {
+                self.element(child);
+            } else if let Node::Text(text) = child.value() {
+                self.out.text(text);
+            }
+        }
+    }
+
+    fn element(&mut self, el: ElementRef) {
+        let name = el.value().name();
+        let markdown = self.flavor == Flavor::Markdown;
+
+        if HIDDEN_ELEMENTS.contains(&name) {
+            return;
+        }
+        match name {
+            "br" => self.out.line_break(),
+            "li" => self.list_item(el),
+            "ul" | "ol" => {
+                // Nested lists hang off their item without a blank line.
+                let breaks = if self.lists.is_empty() { 2 } else { 1 };
+                self.out.block(breaks);
+                let start = el.value().attr("start").and_then(|s| s.parse().ok());
+                self.lists.push((name == "ol").then(|| start.unwrap_or(1)));
+                self.children(el);
+                self.lists.pop();
+                self.out.block(breaks);
+            }
+            "pre" => {
+                self.out.block(2);
+                let code: String = el.text().collect();
+                let code = code.trim_end_matches('\n');
+                if markdown {
+                    let language = el
+                        .children()
+                        .filter_map(ElementRef::wrap)
+                        .find(|c| c.value().name() == "code")
+                        .and_then(|c| c.value().attr("class"))
+                        .and_then(|class| {
+                            class
+                                .split_whitespace()
+                                .find_map(|c| c.strip_prefix("language-"))
+                        })
+                        .unwrap_or("");
+                    self.out.write(&format!("```{}", language));
+                    self.out.block(1);
+                    self.out.write_lines(code);
+                    self.out.block(1);
+                    self.out.write("```");
+                } else {
+                    self.out.write_lines(code);
+                }
+                self.out.block(2);
+            }
+            "hr" if markdown => {
+                self.out.block(2);
+                self.out.write("---");
+                self.out.block(2);
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" if markdown => {
+                self.out.block(2);
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                self.out.write(&"#".repeat(level));
+                self.out.pending_space = true;
+                self.children(el);
+                self.out.block(2);
+            }
+            "a" if markdown => match el.value().attr("href") {
+                Some(href) => self.inline(el, "[", &format!("]({})", href)),
+                None => self.children(el),
+            },
+            "strong" | "b" if markdown => self.inline(el, "**", "**"),
+            "em" | "i" if markdown => self.inline(el, "*", "*"),
+            // `<pre>` renders its text directly, so any `<code>` reaching
+            // this arm is inline.
+            "code" if markdown => self.inline(el, "`", "`"),
+            "td" | "th" => {
+                self.out.pending_space = true;
+                self.children(el);
+                self.out.pending_space = true;
+            }
+            _ => {
+                let breaks = if PARAGRAPH_ELEMENTS.contains(&name) {
+                    2
+                } else if LINE_ELEMENTS.contains(&name) {
+                    1
+                } else {
+                    0
+                };
+                self.out.block(breaks);
+                self.children(el);
+                self.out.block(breaks);
+            }
+        }
+    }
+
+    /// `<li>`: a marker in Markdown, then the content indented under it.
+    fn list_item(&mut self, el: ElementRef) {
+        self.out.block(1);
+        if self.flavor == Flavor::Text {
+            self.children(el);
+            self.out.block(1);
+            return;
+        }
+
+        let marker = match self.lists.last_mut() {
+            Some(Some(number)) => {
+                let current = *number;
+                *number = number.saturating_add(1);
+                format!("{}.", current)
+            }
+            _ => "-".to_string(),
+        };
+        let parent_indent = self.out.indent.clone();
+        self.out.write(&marker);
+        self.out.pending_space = true;
+        self.out.indent = format!("{}{}", parent_indent, " ".repeat(marker.len() + 1));
+        self.children(el);
+        self.out.indent = parent_indent;
+        self.out.block(1);
+    }
+
+    /// Wrap an inline element's rendered content in `open`/`close`,
+    /// keeping the spacing around it. Empty content writes nothing.
+    fn inline(&mut self, el: ElementRef, open: &str, close: &str) {
+        let mut inner = Renderer::new(self.flavor);
+        inner.children(el);
+        let content = inner
+            .out
+            .out
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if content.is_empty() {
+            return;
+        }
+
+        let raw: String = el.text().collect();
+        if raw.starts_with(char::is_whitespace) {
+            self.out.pending_space = true;
+        }
+        self.out.write(&format!("{}{}{}", open, content, close));
+        if raw.ends_with(char::is_whitespace) {
+            self.out.pending_space = true;
+        }
+    }
+}
+
+// ============================================================================
+// FORMS
+// ============================================================================
+// Scripted logins and searches need to know what a form would send: where
+// (`action`), how (`method`), and which name/value pairs. Hidden inputs are
+// the easy ones to forget, and they usually carry a CSRF token the server
+// insists on seeing again.
+//
+// `to_query_string` mimics what a browser submits without user input:
+// - Buttons (`submit`, `reset`, `button`, `image`) and file pickers are
+//   skipped; a browser only sends a submit button that was clicked.
+// - Checkboxes and radio buttons are only sent when `checked`.
+// - A `<select>` sends its selected option, or its first one if none is
+//   marked (that's what the browser shows).
+
+/// An HTML `<form>` and the fields it would submit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Form {
+    /// The `action` attribute as written; empty means "the current page".
+    pub action: String,
+    /// `"get"` or `"post"`, lowercased; `"get"` when the attribute is missing.
+    pub method: String,
+    /// `<input>`, `<textarea>`, and `<select>` elements in document order.
+    pub fields: Vec<FormField>,
+}
+
+/// One form control.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormField {
+    pub name: String,
+    /// The input's `type` (lowercased, default `"text"`), or `"textarea"` /
+    /// `"select"` for those elements.
+    pub field_type: String,
+    /// The value submitted by default. `None` for an unchecked checkbox or
+    /// radio button, an input without a `value`, or an empty `<select>`.
+    pub value: Option<String>,
+    /// The `<option>` values of a `<select>`; empty for other fields.
+    pub options: Vec<String>,
+    /// Whether the `required` attribute is present.
+    pub required: bool,
+}
+
+impl Form {
+    /// The default submission as an `application/x-www-form-urlencoded`
+    /// body (or GET query string), e.g. `user=&token=a%2Bb`.
+    pub fn to_query_string(&self) -> String {
+        let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+        for field in &self.fields {
+            if field.name.is_empty() || is_unsubmitted_type(&field.field_type) {
+                continue;
+            }
+            match (&field.value, field.field_type.as_str()) {
+                (None, "checkbox" | "radio" | "select") => {}
+                (value, _) => {
+                    serializer.append_pair(&field.name, value.as_deref().unwrap_or(""));
+                }
+            }
+        }
+        serializer.finish()
+    }
+}
+
+fn is_unsubmitted_type(field_type: &str) -> bool {
+    matches!(field_type, "submit" | "reset" | "button" | "image" | "file")
+}
+
+/// Extract every `<form>` with its action, method, and fields.
+pub fn extract_forms(html: &str) -> Vec<Form> {
+    let document = Html::parse_document(html);
+    let (form_selector, field_selector, option_selector) = match (
+        Selector::parse("form"),
+        Selector::parse("input, textarea, select"),
+        Selector::parse("option"),
+    ) {
+        (Ok(f), Ok(i), Ok(o)) => (f, i, o),
+        _ => return Vec::new(),
+    };
+
+    document
+        .select(&form_selector)
+        .map(|form| {
+            let element = form.value();
+            let fields = form
+                .select(&field_selector)
+                .map(|field| form_field(field, &option_selector))
+                .collect();
+            Form {
+                action: element.attr("action").unwrap_or("").trim().to_string(),
+                method: element
+                    .attr("method")
+                    .map(|m| m.trim().to_ascii_lowercase())
+                    .filter(|m| !m.is_empty())
+                    .unwrap_or_else(|| "get".to_string()),
+                fields,
+            }
+        })
+        .collect()
+}
+
+fn form_field(field: ElementRef, option_selector: &Selector) -> FormField {
+    let element = field.value();
+    let name = element.attr("name").unwrap_or("").to_string();
+    let required = element.attr("required").is_some();
+
+    match element.name() {
+        "textarea" => FormField {
+            name,
+            field_type: "textarea".to_string(),
+            value: Some(field.text().collect()),
+            options: Vec::new(),
+            required,
+        },
+        "select" => {
+            // An <option> without a value attribute submits its text
+            let options: Vec<(String, bool)> = field
+                .select(option_selector)
+                .map(|option| {
+                    let value = match option.value().attr("value") {
+                        Some(value) => value.to_string(),
+                        None => {
+                            let text: String = option.text().collect();
+                            text.split_whitespace().collect::<Vec<_>>().join(" ")
+                        }
+                    };
+                    (value, option.value().attr("selected").is_some())
+                })
+                .collect();
+            let value = options
+                .iter()
+                .find(|(_, selected)| *selected)
+                .or(options.first())
+                .map(|(value, _)| value.clone());
+            FormField {
+                name,
+                field_type: "select".to_string(),
+                value,
+                options: options.into_iter().map(|(value, _)| value).collect(),
+                required,
+            }
+        }
+        _ => {
+            let field_type = element
+                .attr("type")
+                .map(|t| t.trim().to_ascii_lowercase())
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| "text".to_string());
+            let value = match field_type.as_str() {
+                "checkbox" | "radio" => element
+                    .attr("checked")
+                    .map(|_| element.attr("value").unwrap_or("on").to_string()),
+                _ => element.attr("value").map(str::to_string),
+            };
+            FormField {
+                name,
+                field_type,
+                value,
+                options: Vec::new(),
+                required,
+            }
+        }
+    }
+}
+
 // ============================================================================
 // WHAT RUST DOES UNDER THE HOOD
 // ============================================================================
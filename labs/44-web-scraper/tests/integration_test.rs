@@ -4,9 +4,12 @@
 // No network access required -- all tests use inline HTML content.
 
 use web_scraper::solution::{
-    Article, Heading, Link, extract_all_headings, extract_articles, extract_attribute,
-    extract_headings, extract_links, extract_text_by_selector, extract_title,
+    extract_all_headings, extract_articles, extract_attribute, extract_forms, extract_headings,
+    extract_links, extract_metadata, extract_tables, extract_text_by_selector, extract_title,
+    html_to_markdown, html_to_text, normalize_whitespace, resolve_links, Article, CrawlReport,
+    Crawler, Form, FormField, Heading, Link, PageMetadata, RobotsTxt, Table,
 };
+use std::time::Duration;
 
 // ============================================================================
 // TEST HTML FIXTURES
@@ -349,3 +352,722 @@ fn test_special_characters_in_text() {
     assert!(t.contains("Caf"));
     assert!(t.contains("Bar"));
 }
+
+// ============================================================================
+// RESOLVING LINKS
+// ============================================================================
+
+fn hrefs(links: &[Link]) -> Vec<&str> {
+    links.iter().map(|l| l.href.as_str()).collect()
+}
+
+#[test]
+fn test_resolve_links_relative_and_absolute() {
+    let links = resolve_links(SIMPLE_HTML, "https://site.test/docs/index.html", None);
+    assert_eq!(
+        hrefs(&links),
+        vec![
+            "https://example.com/",
+            "https://site.test/about",
+            "https://site.test/contact"
+        ]
+    );
+    assert_eq!(links[1].text, "About Us");
+}
+
+#[test]
+fn test_resolve_links_dot_segments() {
+    let html =
+        r#"<a href="../index.html">Up</a><a href="./next.html">Next</a><a href="img/a.png">A</a>"#;
+    let links = resolve_links(html, "https://site.test/guide/ch1/page.html", None);
+    assert_eq!(
+        hrefs(&links),
+        vec![
+            "https://site.test/guide/index.html",
+            "https://site.test/guide/ch1/next.html",
+            "https://site.test/guide/ch1/img/a.png",
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_links_base_tag_overrides_page_url() {
+    let html = r#"
+        <html><head><base href="/static/v2/"></head>
+        <body><a href="app.js">App</a><a href="/root">Root</a></body></html>
+    "#;
+    let links = resolve_links(html, "https://site.test/page/here", None);
+    assert_eq!(
+        hrefs(&links),
+        vec![
+            "https://site.test/static/v2/app.js",
+            "https://site.test/root"
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_links_skips_non_navigational() {
+    let html = r##"
+        <a href="#top">Top</a>
+        <a href="javascript:void(0)">Click</a>
+        <a href="MAILTO:someone@site.test">Mail</a>
+        <a href="/real">Real</a>
+    "##;
+    let links = resolve_links(html, "https://site.test/", None);
+    assert_eq!(hrefs(&links), vec!["https://site.test/real"]);
+}
+
+#[test]
+fn test_resolve_links_dedups_by_resolved_url() {
+    let html = r#"
+        <a href="/about">About</a>
+        <a href="https://site.test/about">About us (again)</a>
+        <a href="docs/../about">Sneaky</a>
+        <a href="/team">Team</a>
+    "#;
+    let links = resolve_links(html, "https://site.test/", None);
+    assert_eq!(links.len(), 2);
+    assert_eq!(
+        links[0],
+        Link {
+            href: "https://site.test/about".into(),
+            text: "About".into()
+        }
+    );
+    assert_eq!(links[1].href, "https://site.test/team");
+
+    assert_eq!(resolve_links(html, "https://site.test/", Some(1)).len(), 1);
+}
+
+#[test]
+fn test_resolve_links_invalid_base_keeps_absolute_only() {
+    let links = resolve_links(SIMPLE_HTML, "not a url", None);
+    assert_eq!(hrefs(&links), vec!["https://example.com/"]);
+}
+
+// ============================================================================
+// TABLES
+// ============================================================================
+
+fn strings(cells: &[&str]) -> Vec<String> {
+    cells.iter().map(|c| c.to_string()).collect()
+}
+
+#[test]
+fn test_extract_tables_thead_tbody() {
+    let html = r#"
+        <table>
+          <thead><tr><th>Name</th><th>Age</th></tr></thead>
+          <tbody>
+            <tr><td>Alice</td><td>30</td></tr>
+            <tr><td>Bob</td><td> 25 </td></tr>
+          </tbody>
+        </table>
+    "#;
+    let tables = extract_tables(html);
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].headers, strings(&["Name", "Age"]));
+    assert_eq!(
+        tables[0].rows,
+        vec![strings(&["Alice", "30"]), strings(&["Bob", "25"])]
+    );
+}
+
+#[test]
+fn test_extract_tables_headerless_uses_first_row() {
+    let html = "<table><tr><td>id</td><td>city</td></tr><tr><td>1</td><td>Oslo</td></tr></table>";
+    let table = &extract_tables(html)[0];
+    assert_eq!(table.headers, strings(&["id", "city"]));
+    assert_eq!(table.rows, vec![strings(&["1", "Oslo"])]);
+}
+
+#[test]
+fn test_extract_tables_nested_markup_collapses_to_text() {
+    let html = r#"
+        <table>
+          <tr><th>Who</th><th>Link</th></tr>
+          <tr><td><b>Al</b>ice
+              <i>Smith</i></td><td><a href="/a">profile <span>page</span></a></td></tr>
+        </table>
+    "#;
+    let table = &extract_tables(html)[0];
+    assert_eq!(table.rows[0], strings(&["Alice Smith", "profile page"]));
+}
+
+#[test]
+fn test_extract_tables_pads_missing_cells() {
+    let html = "<table><tr><th>a</th><th>b</th><th>c</th></tr><tr><td>1</td></tr><tr><td>1</td><td>2</td><td>3</td><td>4</td></tr></table>";
+    let table = &extract_tables(html)[0];
+    assert_eq!(table.headers, strings(&["a", "b", "c", ""]));
+    assert_eq!(
+        table.rows,
+        vec![strings(&["1", "", "", ""]), strings(&["1", "2", "3", "4"])]
+    );
+}
+
+#[test]
+fn test_extract_tables_colspan_and_nested_tables() {
+    let html = r#"
+        <table>
+          <tr><th colspan="2">Wide</th></tr>
+          <tr><td>x</td><td><table><tr><td>inner</td></tr></table></td></tr>
+        </table>
+    "#;
+    let tables = extract_tables(html);
+    assert_eq!(tables.len(), 2);
+    // colspan is ignored: the header row counts as a single cell.
+    assert_eq!(tables[0].headers, strings(&["Wide", ""]));
+    assert_eq!(tables[0].rows.len(), 1);
+    assert_eq!(tables[1].headers, strings(&["inner"]));
+}
+
+#[test]
+fn test_table_to_csv_quotes_special_cells() {
+    let table = Table {
+        headers: strings(&["name", "note"]),
+        rows: vec![
+            strings(&["Smith, Jo", "said \"hi\""]),
+            strings(&["Ann", "plain"]),
+        ],
+    };
+    assert_eq!(
+        table.to_csv(),
+        "name,note\n\"Smith, Jo\",\"said \"\"hi\"\"\"\nAnn,plain\n"
+    );
+}
+
+// ============================================================================
+// PAGE METADATA
+// ============================================================================
+
+#[test]
+fn test_extract_metadata_realistic_head() {
+    let html = r#"
+        <!DOCTYPE html>
+        <html lang="en-GB">
+        <head>
+          <title>Rust Labs | Home</title>
+          <meta name="description" content="  Learn Rust by building things. ">
+          <link rel="stylesheet" href="/style.css">
+          <link rel="canonical" href="https://labs.test/">
+          <meta property="og:title" content="Rust Labs">
+          <meta property="og:type" content="website">
+          <meta property="og:image" content="https://labs.test/card.png">
+          <meta property="og:image" content="https://labs.test/second.png">
+          <meta name="twitter:card" content="summary_large_image">
+          <meta name="twitter:site" content="@rustlabs">
+        </head>
+        <body><h1>Hi</h1></body>
+        </html>
+    "#;
+    let meta = extract_metadata(html);
+
+    assert_eq!(meta.title.as_deref(), Some("Rust Labs | Home"));
+    assert_eq!(
+        meta.description.as_deref(),
+        Some("Learn Rust by building things.")
+    );
+    assert_eq!(meta.canonical_url.as_deref(), Some("https://labs.test/"));
+    assert_eq!(meta.lang.as_deref(), Some("en-GB"));
+    assert_eq!(meta.og.len(), 3);
+    assert_eq!(meta.og["type"], "website");
+    assert_eq!(
+        meta.og["image"], "https://labs.test/card.png",
+        "first tag wins"
+    );
+    assert_eq!(meta.twitter["card"], "summary_large_image");
+    assert_eq!(meta.twitter["site"], "@rustlabs");
+    assert_eq!(meta.best_title(), Some("Rust Labs"));
+}
+
+#[test]
+fn test_extract_metadata_case_insensitive() {
+    let html = r#"
+        <html><head>
+          <META Property="OG:Title" CONTENT="Shouty">
+          <meta NAME="Description" content="Mixed case">
+          <link REL="Canonical" href="/c">
+        </head></html>
+    "#;
+    let meta = extract_metadata(html);
+    assert_eq!(meta.og.get("title").map(String::as_str), Some("Shouty"));
+    assert_eq!(meta.description.as_deref(), Some("Mixed case"));
+    assert_eq!(meta.canonical_url.as_deref(), Some("/c"));
+}
+
+#[test]
+fn test_extract_metadata_without_head() {
+    let meta = extract_metadata("<p>Just a paragraph</p>");
+    assert_eq!(meta, PageMetadata::default());
+    assert_eq!(meta.best_title(), None);
+}
+
+#[test]
+fn test_best_title_falls_back_to_title_then_twitter() {
+    let meta = extract_metadata("<title>Plain</title><meta name='twitter:title' content='Tweet'>");
+    assert_eq!(meta.best_title(), Some("Plain"));
+
+    let meta = extract_metadata("<meta name='twitter:title' content='Tweet'>");
+    assert_eq!(meta.best_title(), Some("Tweet"));
+}
+
+// ============================================================================
+// CRAWLING
+// ============================================================================
+
+/// Five pages with a cycle (/a -> /c -> /a), two external sites, and a
+/// link to a page the fetcher can't find.
+fn fake_fetch(url: &str) -> Result<String, String> {
+    let page = match url {
+        "https://site.test/" => {
+            r##"<title>Home</title>
+            <a href="/a">A</a><a href="/b">B</a><a href="#top">Top</a>
+            <a href="https://other.test/x">Elsewhere</a>"##
+        }
+        "https://site.test/a" => {
+            r#"<title>A</title><a href="/">Home</a><a href="c">C</a><a href="/b#part">B</a>"#
+        }
+        "https://site.test/b" => {
+            r#"<title>B</title><a href="/d">D</a>
+            <a href="https://other.test/x">Elsewhere again</a><a href="https://third.test/">Third</a>"#
+        }
+        "https://site.test/c" => {
+            r#"<title>C</title><a href="/a">back to A</a><a href="/missing">?</a>"#
+        }
+        "https://site.test/d" => r#"<p>no title</p><a href="http://site.test/">plain http</a>"#,
+        _ => return Err(format!("404 Not Found: {}", url)),
+    };
+    Ok(page.to_string())
+}
+
+fn crawled_urls(report: &CrawlReport) -> Vec<(&str, usize)> {
+    report
+        .pages
+        .iter()
+        .map(|p| (p.url.as_str(), p.depth))
+        .collect()
+}
+
+#[test]
+fn test_crawler_breadth_first_with_cycle() {
+    let report = Crawler::new(fake_fetch, "https://site.test/", 5, 100).crawl();
+
+    assert_eq!(
+        crawled_urls(&report),
+        vec![
+            ("https://site.test/", 0),
+            ("https://site.test/a", 1),
+            ("https://site.test/b", 1),
+            ("https://site.test/c", 2),
+            ("https://site.test/d", 2),
+        ]
+    );
+    assert_eq!(report.pages[0].title.as_deref(), Some("Home"));
+    assert_eq!(
+        report.pages[0].link_count, 3,
+        "fragment-only link is not counted"
+    );
+    assert_eq!(report.pages[4].title, None);
+}
+
+#[test]
+fn test_crawler_depth_limit() {
+    let report = Crawler::new(fake_fetch, "https://site.test/", 1, 100).crawl();
+    assert_eq!(report.pages.len(), 3);
+    assert!(report.pages.iter().all(|p| p.depth <= 1));
+    assert!(report.errors.is_empty());
+
+    let report = Crawler::new(fake_fetch, "https://site.test/", 0, 100).crawl();
+    assert_eq!(crawled_urls(&report), vec![("https://site.test/", 0)]);
+}
+
+#[test]
+fn test_crawler_counts_but_does_not_follow_external_links() {
+    let report = Crawler::new(fake_fetch, "https://site.test/", 5, 100).crawl();
+    // other.test (linked twice), third.test, and http://site.test/ (a
+    // different scheme is a different origin).
+    assert_eq!(report.skipped_external, 3);
+    assert!(report
+        .pages
+        .iter()
+        .all(|p| p.url.starts_with("https://site.test/")));
+}
+
+#[test]
+fn test_crawler_records_fetch_errors_and_continues() {
+    let report = Crawler::new(fake_fetch, "https://site.test/", 5, 100).crawl();
+    assert_eq!(
+        report.errors,
+        vec![(
+            "https://site.test/missing".to_string(),
+            "404 Not Found: https://site.test/missing".to_string()
+        )]
+    );
+    assert_eq!(report.pages.len(), 5);
+}
+
+#[test]
+fn test_crawler_max_pages_and_bad_start_url() {
+    let report = Crawler::new(fake_fetch, "https://site.test/", 5, 2).crawl();
+    assert_eq!(report.pages.len(), 2);
+
+    let report = Crawler::new(fake_fetch, "not a url", 5, 10).crawl();
+    assert!(report.pages.is_empty());
+    assert_eq!(report.errors.len(), 1);
+}
+
+// ============================================================================
+// ROBOTS.TXT
+// ============================================================================
+
+const NEWS_SITE_ROBOTS: &str = "\
+# robots.txt for news.test
+User-agent: *
+Disallow: /admin/
+Disallow: /search
+Allow: /search/about
+Disallow: /*.pdf$
+Disallow: /*?sessionid=
+Crawl-delay: 2.5
+
+User-agent: Googlebot
+User-agent: Bingbot
+Disallow: /drafts/
+
+Sitemap: https://news.test/sitemap.xml
+
+User-agent: BadBot
+Disallow: /
+";
+
+#[test]
+fn test_robots_longest_rule_wins() {
+    let robots = RobotsTxt::parse(NEWS_SITE_ROBOTS);
+    assert!(!robots.is_allowed("examplebot", "/search?q=rust"));
+    assert!(robots.is_allowed("examplebot", "/search/about"));
+    assert!(robots.is_allowed("examplebot", "/news/today"));
+    assert!(!robots.is_allowed("examplebot", "/admin/users"));
+    assert!(robots.is_allowed("examplebot", "/admin"));
+}
+
+#[test]
+fn test_robots_allow_wins_equal_length_tie() {
+    let robots = RobotsTxt::parse("User-agent: *\nDisallow: /page\nAllow: /page\n");
+    assert!(robots.is_allowed("examplebot", "/page"));
+}
+
+#[test]
+fn test_robots_allow_carves_out_of_disallow() {
+    let robots = RobotsTxt::parse(
+        "User-agent: *\nDisallow: /private/\nAllow: /private/press/\nAllow: /private/press/*.html$\n",
+    );
+    assert!(!robots.is_allowed("examplebot", "/private/payroll"));
+    assert!(robots.is_allowed("examplebot", "/private/press/2024.html"));
+    assert!(robots.is_allowed("examplebot", "/private/press/"));
+}
+
+#[test]
+fn test_robots_specific_agent_overrides_star() {
+    let robots = RobotsTxt::parse(NEWS_SITE_ROBOTS);
+
+    // Googlebot obeys only its own group, not the `*` rules
+    assert!(robots.is_allowed("Googlebot/2.1", "/admin/users"));
+    assert!(!robots.is_allowed("Googlebot/2.1", "/drafts/next-week"));
+    assert!(!robots.is_allowed("bingbot", "/drafts/next-week"));
+    assert_eq!(robots.crawl_delay("Googlebot"), None);
+
+    assert!(!robots.is_allowed("BadBot", "/"));
+    assert!(!robots.is_allowed("badbot/1.0", "/news/today"));
+}
+
+#[test]
+fn test_robots_same_agent_groups_are_merged() {
+    let robots = RobotsTxt::parse(
+        "User-agent: examplebot\nDisallow: /a\n\nUser-agent: *\nDisallow: /\n\nUser-agent: examplebot\nDisallow: /b\n",
+    );
+    assert!(!robots.is_allowed("examplebot", "/a"));
+    assert!(!robots.is_allowed("examplebot", "/b"));
+    assert!(robots.is_allowed("examplebot", "/c"));
+    assert!(!robots.is_allowed("otherbot", "/c"));
+}
+
+#[test]
+fn test_robots_wildcards_and_anchors() {
+    let robots = RobotsTxt::parse(NEWS_SITE_ROBOTS);
+    assert!(!robots.is_allowed("examplebot", "/reports/2024/annual.pdf"));
+    assert!(robots.is_allowed("examplebot", "/reports/2024/annual.pdf.html"));
+    assert!(robots.is_allowed("examplebot", "/reports/2024/annual.pdf?download=1"));
+    assert!(!robots.is_allowed("examplebot", "/story?sessionid=abc&id=4"));
+    assert!(robots.is_allowed("examplebot", "/story?id=4"));
+
+    let robots = RobotsTxt::parse("User-agent: *\nDisallow: /fish*shop$\n");
+    assert!(!robots.is_allowed("examplebot", "/fish/tackle/shop"));
+    assert!(robots.is_allowed("examplebot", "/fish/shop/open"));
+}
+
+#[test]
+fn test_robots_crawl_delay() {
+    let robots = RobotsTxt::parse(NEWS_SITE_ROBOTS);
+    assert_eq!(
+        robots.crawl_delay("examplebot"),
+        Some(Duration::from_millis(2500))
+    );
+
+    let robots = RobotsTxt::parse("User-agent: *\nCrawl-delay: soon\n");
+    assert_eq!(robots.crawl_delay("examplebot"), None);
+}
+
+#[test]
+fn test_robots_crawl_delay_out_of_range_is_ignored() {
+    for value in ["1e30", "-1", "inf", "NaN"] {
+        let robots = RobotsTxt::parse(&format!("User-agent: *\nCrawl-delay: {}\n", value));
+        assert_eq!(robots.crawl_delay("examplebot"), None, "{}", value);
+    }
+}
+
+#[test]
+fn test_robots_empty_file_and_empty_disallow_allow_everything() {
+    let empty = RobotsTxt::parse("");
+    assert!(empty.is_allowed("examplebot", "/anything"));
+    assert!(empty.is_allowed("examplebot", ""));
+    assert_eq!(empty.crawl_delay("examplebot"), None);
+
+    let robots = RobotsTxt::parse("User-agent: *\nDisallow:\n");
+    assert!(robots.is_allowed("examplebot", "/admin/"));
+}
+
+#[test]
+fn test_robots_ignores_unknown_lines_and_comments() {
+    let robots = RobotsTxt::parse(
+        "Disallow: /orphan\nuser-agent: * # everyone\nNoindex: /x\nnot a directive\nDISALLOW: /tmp # scratch\n",
+    );
+    assert!(robots.is_allowed("examplebot", "/orphan"));
+    assert!(robots.is_allowed("examplebot", "/x"));
+    assert!(!robots.is_allowed("examplebot", "/tmp/file"));
+}
+
+// ============================================================================
+// READABLE TEXT TESTS
+// ============================================================================
+
+const ARTICLE_PAGE: &str = r#"
+<html>
+<head><title>Ignored</title><style>p { color: red; }</style></head>
+<body>
+    <h1>Release   notes</h1>
+    <script>var tracking = "do not show";</script>
+    <p>Read the <a href="/guide">setup <em>guide</em></a> first,
+       then <strong>upgrade</strong>.</p>
+    <div>Second block</div>
+</body>
+</html>
+"#;
+
+#[test]
+fn test_html_to_text_drops_hidden_elements() {
+    let text = html_to_text(ARTICLE_PAGE);
+    assert_eq!(
+        text,
+        "Release notes\n\nRead the setup guide first, then upgrade.\n\nSecond block"
+    );
+    assert!(!text.contains("tracking"));
+    assert!(!text.contains("color"));
+    assert!(!text.contains("Ignored"));
+}
+
+#[test]
+fn test_html_to_text_br_and_blocks() {
+    assert_eq!(html_to_text("<p>a<br>b</p>"), "a\nb");
+    assert_eq!(html_to_text("<p>a<br><br><br><br>b</p>"), "a\n\nb");
+    assert_eq!(
+        html_to_text("<div><div>one</div><div>two</div></div>"),
+        "one\ntwo"
+    );
+    assert_eq!(html_to_text("<ul><li>x</li><li>y</li></ul>"), "x\ny");
+    assert_eq!(html_to_text(""), "");
+}
+
+#[test]
+fn test_html_to_markdown_inline_formatting() {
+    assert_eq!(
+        html_to_markdown(ARTICLE_PAGE),
+        "# Release notes\n\nRead the [setup *guide*](/guide) first, then **upgrade**.\n\nSecond block"
+    );
+    assert_eq!(
+        html_to_markdown("<p>Run <code>cargo test</code>, or <a>nothing</a>.</p>"),
+        "Run `cargo test`, or nothing."
+    );
+    assert_eq!(html_to_markdown("<h3>Deep</h3>"), "### Deep");
+}
+
+#[test]
+fn test_html_to_markdown_nested_lists() {
+    let html = "<ul><li>Fruit<ul><li>Apple</li><li>Pear</li></ul></li><li>Veg</li></ul>";
+    assert_eq!(
+        html_to_markdown(html),
+        "- Fruit\n  - Apple\n  - Pear\n- Veg"
+    );
+
+    let html = r#"<ol start="9"><li>Nine<ol><li>Inner</li></ol></li><li>Ten</li></ol>"#;
+    assert_eq!(html_to_markdown(html), "9. Nine\n   1. Inner\n10. Ten");
+
+    // Numbering stops at u64::MAX instead of overflowing
+    let html = r#"<ol start="18446744073709551615"><li>a</li><li>b</li></ol>"#;
+    assert_eq!(
+        html_to_markdown(html),
+        "18446744073709551615. a\n18446744073709551615. b"
+    );
+}
+
+#[test]
+fn test_html_to_markdown_code_block() {
+    let html = "<p>Example:</p><pre><code class=\"language-rust\">fn main() {\n    run();\n}\n</code></pre>";
+    assert_eq!(
+        html_to_markdown(html),
+        "Example:\n\n```rust\nfn main() {\n    run();\n}\n```"
+    );
+}
+
+#[test]
+fn test_normalize_whitespace_is_idempotent() {
+    let messy = "\n\n  lots   of\tspace  \n\n\n\nnext   line \n\n";
+    let once = normalize_whitespace(messy);
+    assert_eq!(once, "lots of space\n\nnext line");
+    assert_eq!(normalize_whitespace(&once), once);
+
+    let text = html_to_text(ARTICLE_PAGE);
+    assert_eq!(normalize_whitespace(&text), text);
+}
+
+// ============================================================================
+// FORM EXTRACTION TESTS
+// ============================================================================
+
+const LOGIN_PAGE: &str = r#"
+<html><body>
+  <form action="/session" method="POST">
+    <input type="hidden" name="csrf_token" value="abc123">
+    <input type="text" name="username" required>
+    <input type="password" name="password" required>
+    <input type="checkbox" name="remember" checked>
+    <input type="checkbox" name="newsletter" value="yes">
+    <input type="submit" value="Log in">
+  </form>
+</body></html>
+"#;
+
+fn field<'a>(form: &'a Form, name: &str) -> &'a FormField {
+    form.fields
+        .iter()
+        .find(|f| f.name == name)
+        .unwrap_or_else(|| panic!("no field named {}", name))
+}
+
+#[test]
+fn test_extract_login_form_with_csrf_token() {
+    let forms = extract_forms(LOGIN_PAGE);
+    assert_eq!(forms.len(), 1);
+    let form = &forms[0];
+    assert_eq!(form.action, "/session");
+    assert_eq!(form.method, "post");
+    assert_eq!(form.fields.len(), 6);
+
+    let token = field(form, "csrf_token");
+    assert_eq!(token.field_type, "hidden");
+    assert_eq!(token.value.as_deref(), Some("abc123"));
+
+    assert_eq!(field(form, "remember").value.as_deref(), Some("on"));
+    assert_eq!(field(form, "newsletter").value, None);
+    assert_eq!(
+        form.to_query_string(),
+        "csrf_token=abc123&username=&password=&remember=on"
+    );
+}
+
+#[test]
+fn test_form_defaults_method_to_get() {
+    let forms = extract_forms(r#"<form><input name="q"></form><form method=""></form>"#);
+    assert_eq!(forms.len(), 2);
+    assert!(forms
+        .iter()
+        .all(|f| f.method == "get" && f.action.is_empty()));
+    assert_eq!(forms[0].fields[0].field_type, "text");
+    assert!(extract_forms("<p>no forms here</p>").is_empty());
+}
+
+#[test]
+fn test_select_and_textarea_values() {
+    let html = r#"
+        <form action="/search">
+          <select name="sort">
+            <option value="new">Newest</option>
+            <option value="top" selected>Top rated</option>
+          </select>
+          <select name="lang"><option>Rust</option><option>Go</option></select>
+          <select name="empty"></select>
+          <textarea name="notes">hello
+world</textarea>
+        </form>
+    "#;
+    let form = &extract_forms(html)[0];
+
+    let sort = field(form, "sort");
+    assert_eq!(sort.field_type, "select");
+    assert_eq!(sort.options, ["new", "top"]);
+    assert_eq!(sort.value.as_deref(), Some("top"));
+
+    // No `selected`: the first option is what a browser would send
+    let lang = field(form, "lang");
+    assert_eq!(lang.options, ["Rust", "Go"]);
+    assert_eq!(lang.value.as_deref(), Some("Rust"));
+    assert_eq!(field(form, "empty").value, None);
+
+    assert_eq!(field(form, "notes").field_type, "textarea");
+    assert_eq!(field(form, "notes").value.as_deref(), Some("hello\nworld"));
+    assert_eq!(
+        form.to_query_string(),
+        "sort=top&lang=Rust&notes=hello%0Aworld"
+    );
+}
+
+#[test]
+fn test_required_detection() {
+    let html = r#"<form>
+        <input name="a" required>
+        <input name="b" required="required">
+        <input name="c">
+        <textarea name="d" required></textarea>
+        <select name="e" required><option>x</option></select>
+    </form>"#;
+    let required: Vec<(String, bool)> = extract_forms(html)[0]
+        .fields
+        .iter()
+        .map(|f| (f.name.clone(), f.required))
+        .collect();
+    assert_eq!(
+        required,
+        [
+            ("a".to_string(), true),
+            ("b".to_string(), true),
+            ("c".to_string(), false),
+            ("d".to_string(), true),
+            ("e".to_string(), true),
+        ]
+    );
+}
+
+#[test]
+fn test_query_string_percent_encodes_values() {
+    let html = r#"<form method="post">
+        <input type="hidden" name="redirect" value="/a b?x=1&amp;y=2">
+        <input name="email" value="me+you@example.com">
+        <input name="caf&eacute;" value="100% &quot;sure&quot;">
+        <input value="unnamed">
+    </form>"#;
+    let form = &extract_forms(html)[0];
+    assert_eq!(
+        form.to_query_string(),
+        "redirect=%2Fa+b%3Fx%3D1%26y%3D2&email=me%2Byou%40example.com&caf%C3%A9=100%25+%22sure%22"
+    );
+}
@@ -1,7 +1,10 @@
 //! # CSV to JSON - Student API
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::error::Error;
+use std::io::{Read, Write};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct Person {
@@ -33,6 +36,8 @@ pub struct Product {
     pub name: String,
     pub price: f64,
     pub quantity: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -60,6 +65,66 @@ pub fn parse_csv_to_persons(_csv_data: &str) -> Result<Vec<Person>, Box<dyn Erro
     todo!("Parse CSV records into Person values")
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub has_headers: bool,
+    pub quote: u8,
+    pub trim: bool,
+    pub columns: Option<Vec<String>>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        todo!("Comma delimiter, double quotes, header row, no trimming, all columns")
+    }
+}
+
+impl CsvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn delimiter(self, _delimiter: u8) -> Self {
+        todo!("Set the field delimiter")
+    }
+
+    pub fn has_headers(self, _has_headers: bool) -> Self {
+        todo!("Set whether the first row is a header row")
+    }
+
+    pub fn quote(self, _quote: u8) -> Self {
+        todo!("Set the quote character")
+    }
+
+    pub fn trim(self, _trim: bool) -> Self {
+        todo!("Set whether whitespace around fields is trimmed")
+    }
+
+    pub fn columns<I, S>(self, _columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        todo!("Keep only these columns, in this order")
+    }
+}
+
+pub fn parse_csv_to_persons_with(
+    _csv_data: &str,
+    _options: &CsvOptions,
+) -> Result<Vec<Person>, Box<dyn Error>> {
+    // TODO: Configure a csv::ReaderBuilder from the options. Resolve
+    // `columns` to indices (names need the header row, otherwise parse them
+    // as numbers), project every record, and deserialize. Without headers,
+    // serde maps the projected fields to Person by position.
+    todo!("Parse CSV records into Person values using the options")
+}
+
+pub fn csv_to_json_with(_csv_data: &str, _options: &CsvOptions) -> Result<String, Box<dyn Error>> {
+    todo!("Perform one-step CSV to JSON conversion using the options")
+}
+
 pub fn persons_to_json(_people: &[Person]) -> Result<String, Box<dyn Error>> {
     todo!("Serialize people to pretty JSON")
 }
@@ -92,5 +157,281 @@ pub fn students_to_json(_students: &[Student]) -> Result<String, Box<dyn Error>>
     todo!("Serialize students to JSON")
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowError {
+    pub row_number: u64,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub valid_count: usize,
+    pub errors: Vec<RowError>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        todo!("Report whether there were no errors")
+    }
+
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        todo!("Serialize the report to pretty JSON")
+    }
+}
+
+pub fn validate_csv<T: DeserializeOwned>(_csv_data: &str) -> ValidationReport {
+    // TODO: Deserialize every row, collecting a RowError for each failure.
+    // csv::Error::position() gives the record number; for
+    // ErrorKind::Deserialize, err.field() is the column index to look up
+    // in the header row.
+    todo!("Validate CSV rows and report per-field errors")
+}
+
+pub fn csv_to_json_generic(_csv_data: &str) -> Result<String, Box<dyn Error>> {
+    todo!("Convert any CSV into a JSON array of objects with type inference")
+}
+
+pub fn csv_to_json_generic_typed(_csv_data: &str, _infer: bool) -> Result<String, Box<dyn Error>> {
+    todo!("Convert any CSV to JSON, optionally keeping every cell as a string")
+}
+
+pub fn csv_to_values(_csv_data: &str, _infer: bool) -> Result<Vec<Value>, Box<dyn Error>> {
+    // TODO: Read the header row, then build one serde_json::Map per record.
+    todo!("Parse CSV rows into serde_json::Value objects")
+}
+
+pub fn infer_value(_cell: &str) -> Value {
+    // TODO: empty -> null, i64, finite f64, "true"/"false", else string.
+    todo!("Infer a JSON value from a CSV cell")
+}
+
+pub fn dedupe_headers<'a>(_headers: impl Iterator<Item = &'a str>) -> Vec<String> {
+    // TODO: Suffix repeated names with _2, _3, ... deterministically.
+    todo!("Make header names unique")
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConversionStats {
+    pub records: usize,
+    pub errors: usize,
+    pub bytes_written: u64,
+}
+
+pub fn convert_stream<R: Read, W: Write>(
+    _reader: R,
+    _writer: W,
+    _skip_errors: bool,
+) -> Result<ConversionStats, Box<dyn Error>> {
+    // TODO: Reuse one csv::StringRecord with `read_record`, write `[`, `,`
+    // and `]` yourself, and serialize one object at a time.
+    todo!("Stream CSV records to a JSON array")
+}
+
+pub fn json_to_csv(_json_data: &str) -> Result<String, Box<dyn Error>> {
+    // TODO: Flatten nested objects with dots, union the keys in first-seen
+    // order for the header, and leave null/missing cells empty.
+    todo!("Convert a JSON array of objects to CSV")
+}
+
+pub const SNIFF_SAMPLE_RECORDS: usize = 20;
+
+pub const SNIFF_DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColumnType {
+    Int,
+    Float,
+    Bool,
+    String,
+    Empty,
+}
+
+impl ColumnType {
+    pub fn of_cell(_cell: &str) -> ColumnType {
+        // TODO: Same rules as infer_value: empty, i64, finite f64, true/false, else string.
+        todo!("Classify a cell")
+    }
+
+    pub fn merge(self, _other: ColumnType) -> ColumnType {
+        // TODO: Empty yields to anything, Int + Float -> Float, other mixes -> String.
+        todo!("Combine two column types")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub kind: ColumnType,
+    pub nullable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SniffResult {
+    pub delimiter: u8,
+    pub has_headers: bool,
+    pub column_count: usize,
+    pub columns: Vec<ColumnSchema>,
+    pub notes: Vec<String>,
+}
+
+impl SniffResult {
+    pub fn is_confident(&self) -> bool {
+        todo!("No notes means a clear-cut guess")
+    }
+
+    pub fn options(&self) -> CsvOptions {
+        todo!("CsvOptions matching the sniffed delimiter and header row")
+    }
+}
+
+pub fn sniff(_csv_sample: &str) -> SniffResult {
+    // TODO: Split the first SNIFF_SAMPLE_RECORDS records with each candidate
+    // delimiter and keep the one whose most common column count (> 1) covers
+    // the most rows. The first row is a header if it has no numeric cells
+    // while later rows do. Add a note whenever the guess is uncertain.
+    todo!("Guess delimiter, header row, and column types")
+}
+
+/// Records bucketed by key, in first-appearance order.
+#[derive(Debug, Clone)]
+pub struct GroupBy<K, T> {
+    groups: Vec<(K, Vec<T>)>,
+    index: std::collections::HashMap<K, usize>,
+}
+
+impl<K: Eq + std::hash::Hash + Clone, T> GroupBy<K, T> {
+    pub fn new<I, F>(_records: I, _key_fn: F) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        F: FnMut(&T) -> K,
+    {
+        // TODO: Keep groups in a Vec so they come out in first-appearance
+        // order; a HashMap from key to Vec slot finds each record's group.
+        todo!("Group records by key")
+    }
+
+    pub fn get(&self, _key: &K) -> Option<&[T]> {
+        let _ = (&self.groups, &self.index);
+        todo!("Records for one key")
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &[T])> {
+        self.groups
+            .iter()
+            .map(|(key, records)| (key, records.as_slice()))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.groups.iter().map(|(key, _)| key)
+    }
+
+    pub fn len(&self) -> usize {
+        todo!("Number of groups")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        todo!("No groups at all")
+    }
+
+    pub fn into_groups(self) -> Vec<(K, Vec<T>)> {
+        todo!("Hand over the groups")
+    }
+
+    pub fn counts(&self) -> Vec<(K, usize)> {
+        todo!("Records per group")
+    }
+
+    pub fn sum<F: FnMut(&T) -> f64>(&self, _value_fn: F) -> Vec<(K, f64)> {
+        todo!("Sum per group")
+    }
+
+    pub fn avg<F: FnMut(&T) -> f64>(&self, _value_fn: F) -> Vec<(K, f64)> {
+        todo!("Mean per group")
+    }
+
+    pub fn min_max<F: FnMut(&T) -> f64>(&self, _value_fn: F) -> Vec<(K, (f64, f64))> {
+        todo!("Min and max per group, skipping NaN")
+    }
+}
+
+pub fn group_by_key<K, T, I, F>(_records: I, _key_fn: F) -> GroupBy<K, T>
+where
+    K: Eq + std::hash::Hash + Clone,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T) -> K,
+{
+    todo!("Shorthand for GroupBy::new")
+}
+
+pub fn count_by<K, T, I, F>(_records: I, _key_fn: F) -> Vec<(K, usize)>
+where
+    K: Eq + std::hash::Hash + Clone,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T) -> K,
+{
+    todo!("Count per key")
+}
+
+pub fn sum_by<K, T, I, F, V>(_records: I, _key_fn: F, _value_fn: V) -> Vec<(K, f64)>
+where
+    K: Eq + std::hash::Hash + Clone,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T) -> K,
+    V: FnMut(&T) -> f64,
+{
+    todo!("Sum per key")
+}
+
+pub fn avg_by<K, T, I, F, V>(_records: I, _key_fn: F, _value_fn: V) -> Vec<(K, f64)>
+where
+    K: Eq + std::hash::Hash + Clone,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T) -> K,
+    V: FnMut(&T) -> f64,
+{
+    todo!("Mean per key")
+}
+
+pub fn min_max_by<K, T, I, F, V>(_records: I, _key_fn: F, _value_fn: V) -> Vec<(K, (f64, f64))>
+where
+    K: Eq + std::hash::Hash + Clone,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T) -> K,
+    V: FnMut(&T) -> f64,
+{
+    todo!("Min and max per key")
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductSummary {
+    pub group: String,
+    pub count: usize,
+    pub total_quantity: u64,
+    pub total_value: f64,
+    pub average_price: f64,
+}
+
+pub fn product_group(_product: &Product) -> String {
+    todo!("Category if set and non-blank, else the name's first word")
+}
+
+pub fn summarize_products(_products: &[Product]) -> Vec<ProductSummary> {
+    todo!("Summarize by product_group")
+}
+
+pub fn summarize_products_by<F>(_products: &[Product], _key_fn: F) -> Vec<ProductSummary>
+where
+    F: FnMut(&Product) -> String,
+{
+    // TODO: count, total quantity, total value (price * quantity), and the
+    // unweighted mean price for each group.
+    todo!("Summarize by any key")
+}
+
+pub fn summaries_to_json(_summaries: &[ProductSummary]) -> Result<String, Box<dyn Error>> {
+    todo!("Serialize summaries to JSON")
+}
+
 #[doc(hidden)]
 pub mod solution;
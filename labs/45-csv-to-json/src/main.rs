@@ -3,8 +3,9 @@
 use std::error::Error;
 
 use csv_to_json::solution::{
-    csv_to_json, parse_csv_to_employees, parse_nested_csv, persons_to_json_compact,
-    students_to_json,
+    convert_stream, csv_to_json, csv_to_json_generic, json_to_csv, parse_csv_to_employees,
+    parse_csv_to_products_tolerant, parse_nested_csv, persons_to_json_compact, sniff,
+    students_to_json, summaries_to_json, summarize_products,
 };
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -23,7 +24,40 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let student_csv = "id,name,contact.email,contact.phone,grades.math,grades.english,grades.science\n1,Alice,alice@school.edu,555-0001,95,88,92";
     let students = parse_nested_csv(student_csv)?;
-    println!("Students JSON:\n{}", students_to_json(&students)?);
+    let students_json = students_to_json(&students)?;
+    println!("Students JSON:\n{}", students_json);
+    println!("\nBack to CSV:\n{}", json_to_csv(&students_json)?);
+
+    let any_csv = "city,population,coastal,note\nLisbon,545923,true,\nMadrid,3305408,false,capital";
+    println!("\nGeneric JSON:\n{}", csv_to_json_generic(any_csv)?);
+
+    let log_csv = "timestamp,level,message\n09:00,INFO,started\n09:01,WARN,disk 91% full\n";
+    println!("\nStreamed log JSON:");
+    let stats = convert_stream(log_csv.as_bytes(), std::io::stdout(), false)?;
+    println!("({} records, {} bytes)", stats.records, stats.bytes_written);
+
+    let mystery = "sku;weight;qty;fragile\nA-1;1,5;4;true\nB-7;0,25;10;false\n";
+    let guess = sniff(mystery);
+    println!(
+        "\nSniffed: delimiter {:?}, header {}, columns {:?}",
+        guess.delimiter as char,
+        guess.has_headers,
+        guess
+            .columns
+            .iter()
+            .map(|c| format!("{}: {:?}", c.name, c.kind))
+            .collect::<Vec<_>>()
+    );
+    for note in &guess.notes {
+        println!("  note: {}", note);
+    }
+
+    let stock = "id,name,price,quantity,category\n1,Steel Bolt,0.50,200,\n2,Oak Table,120.00,2,furniture\n3,Steel Nut,0.25,400,\n";
+    let (products, _) = parse_csv_to_products_tolerant(stock);
+    println!(
+        "\nStock summary:\n{}",
+        summaries_to_json(&summarize_products(&products))?
+    );
 
     Ok(())
 }
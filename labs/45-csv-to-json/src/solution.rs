@@ -10,10 +10,17 @@
 // - JSON serialization with `serde_json`
 // - Field mapping with serde attributes
 // - Streaming vs. in-memory conversion
+// - Sniffing the delimiter, header row, and column types of unknown files
+// - Grouping and aggregating records before serializing a summary
 
 use csv;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::hash::Hash;
+use std::io::{self, BufWriter, Read, Write};
 
 // ============================================================================
 // DATA STRUCTURES
@@ -72,6 +79,9 @@ pub struct Product {
     pub name: String,
     pub price: f64,
     pub quantity: u32,
+    /// Optional grouping label; files without a `category` column still parse.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
 }
 
 /// A log entry for streaming demonstrations.
@@ -230,6 +240,1064 @@ pub fn students_to_json(students: &[Student]) -> Result<String, Box<dyn Error>>
     Ok(json)
 }
 
+// ============================================================================
+// PARSING OPTIONS
+// ============================================================================
+// Real-world "CSV" files vary a lot: TSV exports, semicolon-separated files
+// from locales that use `,` as the decimal separator, files with no header
+// row, stray spaces around fields. `csv::ReaderBuilder` handles all of that.
+// `CsvOptions` collects the settings so they can be passed around as one
+// value.
+//
+// `columns` projects the input before deserializing: it picks columns by
+// name (or by 0-based index when there is no header row) and puts them in
+// the given order. Without headers, serde maps fields *by position*, so
+// projection is also how a headerless file is lined up with a struct's
+// field order.
+
+/// Settings for reading CSV, built with chained setters, e.g.
+/// `CsvOptions::new().delimiter(b'\t').trim(true)` for trimmed TSV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub has_headers: bool,
+    pub quote: u8,
+    pub trim: bool,
+    /// Column names (or 0-based indices without headers) to keep, in order.
+    pub columns: Option<Vec<String>>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            has_headers: true,
+            quote: b'"',
+            trim: false,
+            columns: None,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Standard CSV: comma-delimited, double quotes, header row, no trimming.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn has_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    pub fn quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Strip leading and trailing whitespace from headers and fields.
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    pub fn columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    fn reader<'a>(&self, csv_data: &'a str) -> csv::Reader<&'a [u8]> {
+        csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(self.has_headers)
+            .quote(self.quote)
+            .trim(if self.trim {
+                csv::Trim::All
+            } else {
+                csv::Trim::None
+            })
+            .from_reader(csv_data.as_bytes())
+    }
+}
+
+/// Like `parse_csv_to_persons`, but reads the CSV according to `options`.
+///
+/// With headers, columns are matched to `Person` fields by name. Without
+/// headers they are matched by position (name, age, city), after any
+/// `columns` projection.
+pub fn parse_csv_to_persons_with(
+    csv_data: &str,
+    options: &CsvOptions,
+) -> Result<Vec<Person>, Box<dyn Error>> {
+    let mut reader = options.reader(csv_data);
+    let headers = if options.has_headers {
+        Some(reader.headers()?.clone())
+    } else {
+        None
+    };
+
+    let projection = match &options.columns {
+        Some(columns) => Some(resolve_columns(columns, headers.as_ref())?),
+        None => None,
+    };
+    let headers = match (&headers, &projection) {
+        (Some(headers), Some(indices)) => Some(project_record(headers, indices)?),
+        _ => headers,
+    };
+
+    let mut people = Vec::new();
+    for result in reader.records() {
+        let record = result?;
+        let record = match &projection {
+            Some(indices) => project_record(&record, indices)?,
+            None => record,
+        };
+        let person: Person = record.deserialize(headers.as_ref())?;
+        people.push(person);
+    }
+
+    Ok(people)
+}
+
+/// Like `csv_to_json`, but reads the CSV according to `options`.
+pub fn csv_to_json_with(csv_data: &str, options: &CsvOptions) -> Result<String, Box<dyn Error>> {
+    let people = parse_csv_to_persons_with(csv_data, options)?;
+    persons_to_json(&people)
+}
+
+/// Turn projected column names (or indices, without headers) into indices.
+fn resolve_columns(
+    columns: &[String],
+    headers: Option<&csv::StringRecord>,
+) -> Result<Vec<usize>, Box<dyn Error>> {
+    columns
+        .iter()
+        .map(|column| match headers {
+            Some(headers) => headers
+                .iter()
+                .position(|h| h == column)
+                .ok_or_else(|| format!("column '{}' not found in header row", column).into()),
+            None => column.parse::<usize>().map_err(|_| {
+                format!(
+                    "column '{}' is not a 0-based index (the CSV has no header row)",
+                    column
+                )
+                .into()
+            }),
+        })
+        .collect()
+}
+
+/// Pick `indices` out of `record`, in that order.
+fn project_record(
+    record: &csv::StringRecord,
+    indices: &[usize],
+) -> Result<csv::StringRecord, Box<dyn Error>> {
+    let mut projected = csv::StringRecord::new();
+    for &index in indices {
+        let field = record.get(index).ok_or_else(|| {
+            format!(
+                "column {} not found: the row only has {} fields",
+                index,
+                record.len()
+            )
+        })?;
+        projected.push_field(field);
+    }
+    Ok(projected)
+}
+
+// ============================================================================
+// VALIDATION REPORTS
+// ============================================================================
+// `parse_csv_to_products_tolerant` only counts bad rows. To tell a user
+// *what* to fix we need the details, and the csv crate already has them:
+// every `csv::Error` knows which record it came from, and a serde
+// deserialization error also knows the index of the field that failed.
+// We turn the index into a header name so the report says `price`, not
+// "field 2".
+
+/// One problem found while validating a CSV file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowError {
+    /// 1-based data row number (the header row is not counted).
+    pub row_number: u64,
+    /// Column name of the failing field, when the error points at one.
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Outcome of `validate_csv`: how many rows parsed, and what went wrong in
+/// the rest.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub valid_count: usize,
+    pub errors: Vec<RowError>,
+}
+
+impl ValidationReport {
+    /// True when every row deserialized successfully.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Pretty-printed JSON, ready to send to a frontend.
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        Ok(json)
+    }
+}
+
+/// Try to deserialize every row of `csv_data` as `T` and report each
+/// failure instead of stopping at the first one.
+///
+/// A header row that can't be read at all is reported as row 0.
+pub fn validate_csv<T: DeserializeOwned>(csv_data: &str) -> ValidationReport {
+    let mut reader = csv::Reader::from_reader(csv_data.as_bytes());
+    let mut report = ValidationReport::default();
+
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(e) => {
+            report.errors.push(RowError {
+                row_number: 0,
+                field: None,
+                message: e.to_string(),
+            });
+            return report;
+        }
+    };
+
+    for (index, result) in reader.deserialize::<T>().enumerate() {
+        match result {
+            Ok(_) => report.valid_count += 1,
+            Err(e) => report
+                .errors
+                .push(row_error(&e, &headers, index as u64 + 1)),
+        }
+    }
+
+    report
+}
+
+/// Pull the row number, field name, and a short message out of a csv error.
+fn row_error(error: &csv::Error, headers: &csv::StringRecord, fallback_row: u64) -> RowError {
+    // With a header row, the header is record 0, so a data row's record
+    // index is already its 1-based row number.
+    let row_number = error.position().map_or(fallback_row, |pos| pos.record());
+
+    match error.kind() {
+        csv::ErrorKind::Deserialize { err, .. } => RowError {
+            row_number,
+            field: err
+                .field()
+                .and_then(|i| headers.get(i as usize))
+                .map(str::to_string),
+            message: err.kind().to_string(),
+        },
+        csv::ErrorKind::UnequalLengths {
+            expected_len, len, ..
+        } => RowError {
+            row_number,
+            field: None,
+            message: format!("expected {} fields, found {}", expected_len, len),
+        },
+        _ => RowError {
+            row_number,
+            field: None,
+            message: error.to_string(),
+        },
+    }
+}
+
+// ============================================================================
+// SCHEMA-LESS CONVERSION
+// ============================================================================
+// Every function above needs a struct that matches the CSV's columns. That
+// is great when you know the data ahead of time, but a general converter has
+// to handle *any* CSV. The trick is to skip typed structs and build
+// `serde_json::Value` trees directly: the header row supplies the keys and
+// each cell becomes a JSON value.
+//
+// Column order: `serde_json::Map` is a BTreeMap by default, which would sort
+// the keys alphabetically. This crate enables serde_json's `preserve_order`
+// feature so objects keep the CSV's column order.
+
+/// Convert any CSV with a header row into a pretty-printed JSON array of
+/// objects, inferring a JSON type for each cell.
+///
+/// See `infer_value` for the inference rules.
+pub fn csv_to_json_generic(csv_data: &str) -> Result<String, Box<dyn Error>> {
+    csv_to_json_generic_typed(csv_data, true)
+}
+
+/// Like `csv_to_json_generic`, but `infer = false` keeps every cell as a
+/// JSON string (empty cells stay `""`).
+pub fn csv_to_json_generic_typed(csv_data: &str, infer: bool) -> Result<String, Box<dyn Error>> {
+    let rows = csv_to_values(csv_data, infer)?;
+    let json = serde_json::to_string_pretty(&rows)?;
+    Ok(json)
+}
+
+/// Parse any CSV into one JSON object per data row.
+///
+/// This is the building block behind the generic converters; it returns
+/// `Value`s rather than a string so callers can inspect or transform them.
+pub fn csv_to_values(csv_data: &str, infer: bool) -> Result<Vec<Value>, Box<dyn Error>> {
+    let mut reader = csv::Reader::from_reader(csv_data.as_bytes());
+    let headers = dedupe_headers(reader.headers()?.iter());
+    let mut rows = Vec::new();
+
+    for result in reader.records() {
+        let record = result?;
+        let mut object = Map::new();
+        // The csv reader already rejects rows whose length differs from the
+        // header row, so zipping cannot silently drop cells.
+        for (header, cell) in headers.iter().zip(record.iter()) {
+            let value = if infer {
+                infer_value(cell)
+            } else {
+                Value::String(cell.to_string())
+            };
+            object.insert(header.clone(), value);
+        }
+        rows.push(Value::Object(object));
+    }
+
+    Ok(rows)
+}
+
+/// Guess the JSON type of a single CSV cell.
+///
+/// Rules, tried in order:
+/// - empty cell -> `null`
+/// - parses as `i64` -> number (so `"007"` becomes `7`)
+/// - parses as a finite `f64` -> number (`"NaN"`/`"inf"` stay strings,
+///   because JSON has no representation for them)
+/// - exactly `"true"` / `"false"` -> bool
+/// - anything else -> string
+pub fn infer_value(cell: &str) -> Value {
+    if cell.is_empty() {
+        return Value::Null;
+    }
+    if let Ok(int) = cell.parse::<i64>() {
+        return Value::from(int);
+    }
+    if let Ok(float) = cell.parse::<f64>() {
+        if float.is_finite() {
+            return Value::from(float);
+        }
+    }
+    match cell {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::String(cell.to_string()),
+    }
+}
+
+/// Make header names unique so no column silently overwrites another.
+///
+/// The first occurrence keeps its name; later duplicates get `_2`, `_3`, ...
+/// If a suffixed name is itself taken, we keep counting, so the result is
+/// deterministic for any input.
+pub fn dedupe_headers<'a>(headers: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut unique = Vec::new();
+
+    for header in headers {
+        let mut candidate = header.to_string();
+        let mut n = 2;
+        while seen.contains(&candidate) {
+            candidate = format!("{}_{}", header, n);
+            n += 1;
+        }
+        seen.insert(candidate.clone());
+        unique.push(candidate);
+    }
+
+    unique
+}
+
+// ============================================================================
+// STREAMING CONVERSION
+// ============================================================================
+// Everything above builds a complete `Vec` before serializing it, so memory
+// grows with the size of the file. For large inputs (log files, exports) we
+// want constant memory instead: read one record, write one JSON element,
+// repeat.
+//
+// `serde_json` can only serialize a whole value at once, so we write the
+// array punctuation ourselves: `[` first, a `,` before every element after
+// the first, and `]` at the end. Each element is still written by serde, so
+// escaping stays correct.
+//
+// The reader reuses a single `StringRecord` buffer, and a small wrapper
+// counts the bytes passing through to the writer for the stats.
+
+/// Summary of a `convert_stream` run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConversionStats {
+    /// Records written to the JSON array.
+    pub records: usize,
+    /// Malformed records that were skipped (only when `skip_errors` is set).
+    pub errors: usize,
+    /// Total bytes written to the output.
+    pub bytes_written: u64,
+}
+
+/// Stream CSV from `reader` to a JSON array on `writer`, one record at a
+/// time.
+///
+/// Cells are typed with `infer_value` and headers are made unique with
+/// `dedupe_headers`, as in `csv_to_json_generic`. The output is one
+/// compact JSON object per line inside the array brackets.
+///
+/// With `skip_errors`, malformed records (wrong number of fields, invalid
+/// UTF-8) are counted in `errors` and skipped, like
+/// `parse_csv_to_products_tolerant`. Without it, the first bad record
+/// aborts the conversion. I/O errors from `reader` always abort. Output
+/// written before that point is not rolled back.
+pub fn convert_stream<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    skip_errors: bool,
+) -> Result<ConversionStats, Box<dyn Error>> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = dedupe_headers(reader.headers()?.iter());
+    let mut out = CountingWriter {
+        inner: BufWriter::new(writer),
+        count: 0,
+    };
+    let mut stats = ConversionStats::default();
+    let mut record = csv::StringRecord::new();
+
+    out.write_all(b"[")?;
+    loop {
+        match reader.read_record(&mut record) {
+            Ok(true) => {}
+            Ok(false) => break,
+            // Only a bad record is skippable. An I/O error is about the
+            // reader, not the record, and a reader that keeps failing would
+            // keep this loop spinning forever.
+            Err(e) if skip_errors && is_malformed_record(&e) => {
+                stats.errors += 1;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        let mut object = Map::new();
+        for (header, cell) in headers.iter().zip(record.iter()) {
+            object.insert(header.clone(), infer_value(cell));
+        }
+
+        out.write_all(if stats.records == 0 { b"\n" } else { b",\n" })?;
+        serde_json::to_writer(&mut out, &object)?;
+        stats.records += 1;
+    }
+    out.write_all(if stats.records == 0 { b"]\n" } else { b"\n]\n" })?;
+    out.flush()?;
+
+    stats.bytes_written = out.count;
+    Ok(stats)
+}
+
+/// Errors caused by the record's contents, as opposed to the reader.
+fn is_malformed_record(err: &csv::Error) -> bool {
+    matches!(
+        err.kind(),
+        csv::ErrorKind::UnequalLengths { .. } | csv::ErrorKind::Utf8 { .. }
+    )
+}
+
+/// A `Write` adapter that counts the bytes it forwards.
+struct CountingWriter<W: Write> {
+    inner: W,
+    count: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// ============================================================================
+// JSON -> CSV
+// ============================================================================
+// Going back the other way is the mirror image of the schema-less reader:
+// walk each JSON object, flatten nested objects into dot-notation columns
+// (`{"contact": {"email": ..}}` becomes `contact.email`, the same naming
+// `parse_nested_csv` reads), and let the csv writer handle quoting.
+//
+// JSON objects don't all have to share the same keys, so the header row is
+// the union of every object's (flattened) keys, in the order they are first
+// seen. An object that lacks a column simply gets an empty cell.
+
+/// Convert a JSON array of objects into CSV text with a header row.
+///
+/// - nested objects are flattened with dots (`contact.email`)
+/// - `null` and missing keys become empty cells
+/// - arrays are written as compact JSON (`[1,2,3]`) in a single cell
+/// - an empty array produces an empty string (there is no header to write)
+///
+/// Fails if the input isn't valid JSON, isn't an array, or contains
+/// anything other than objects.
+pub fn json_to_csv(json_data: &str) -> Result<String, Box<dyn Error>> {
+    let value: Value = serde_json::from_str(json_data)?;
+    let items = match value {
+        Value::Array(items) => items,
+        _ => return Err("expected a JSON array of objects".into()),
+    };
+
+    let mut rows = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let object = item
+            .as_object()
+            .ok_or_else(|| format!("element {} is not a JSON object", index))?;
+        let mut row = Map::new();
+        flatten_into("", object, &mut row);
+        rows.push(row);
+    }
+
+    let mut headers: Vec<String> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for row in &rows {
+        for key in row.keys() {
+            if seen.insert(key.clone()) {
+                headers.push(key.clone());
+            }
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    if !headers.is_empty() {
+        writer.write_record(&headers)?;
+    }
+    for row in &rows {
+        let cells: Vec<String> = headers
+            .iter()
+            .map(|header| row.get(header).map(cell_text).unwrap_or_default())
+            .collect();
+        writer.write_record(&cells)?;
+    }
+
+    let bytes = writer.into_inner()?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+/// Recursively copy `object` into `out`, joining nested keys with dots.
+fn flatten_into(prefix: &str, object: &Map<String, Value>, out: &mut Map<String, Value>) {
+    for (key, value) in object {
+        let name = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Object(nested) => flatten_into(&name, nested, out),
+            other => {
+                out.insert(name, other.clone());
+            }
+        }
+    }
+}
+
+/// Render one flattened JSON value as CSV cell text.
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        // `to_string` on a Value::String would keep the JSON quotes.
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+// ============================================================================
+// SNIFFING
+// ============================================================================
+// Files with no documentation still give themselves away. `sniff` reads the
+// first few records and guesses how the file was written:
+//
+// - Delimiter: split the sample with each candidate. The right one gives
+//   (nearly) every row the same number of columns, and more than one; a
+//   wrong one gives one column, or a count that jumps around.
+// - Header row: a header is words ("price"), data is often numbers ("4.50").
+//   If no cell in the first row is a number but later rows have numbers,
+//   the first row is a header.
+// - Column types: each cell is classified the same way `infer_value` would
+//   convert it, and the column takes the narrowest type that fits every
+//   cell (Int and Float mix to Float; anything else mixed becomes String).
+//
+// These are guesses, so the result carries `notes` whenever the sample
+// left room for doubt. `SniffResult::options` turns the guess into
+// `CsvOptions` for the real read.
+
+/// How many records `sniff` looks at.
+pub const SNIFF_SAMPLE_RECORDS: usize = 20;
+
+/// Delimiters `sniff` tries, in order of preference on ties.
+pub const SNIFF_DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// The inferred type of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColumnType {
+    Int,
+    Float,
+    Bool,
+    String,
+    /// Every sampled cell was empty.
+    Empty,
+}
+
+impl ColumnType {
+    /// Classify one cell with the same rules as `infer_value`.
+    pub fn of_cell(cell: &str) -> ColumnType {
+        match infer_value(cell) {
+            Value::Null => ColumnType::Empty,
+            Value::Number(n) if n.is_i64() => ColumnType::Int,
+            Value::Number(_) => ColumnType::Float,
+            Value::Bool(_) => ColumnType::Bool,
+            _ => ColumnType::String,
+        }
+    }
+
+    /// The narrowest type that holds values of both `self` and `other`.
+    pub fn merge(self, other: ColumnType) -> ColumnType {
+        use ColumnType::*;
+        match (self, other) {
+            (Empty, t) | (t, Empty) => t,
+            (a, b) if a == b => a,
+            (Int, Float) | (Float, Int) => Float,
+            _ => String,
+        }
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(self, ColumnType::Int | ColumnType::Float)
+    }
+}
+
+/// One column as `sniff` sees it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnSchema {
+    /// Header name, or `col_0`, `col_1`, ... without a header row.
+    pub name: String,
+    pub kind: ColumnType,
+    /// Some sampled row had this cell empty or missing.
+    pub nullable: bool,
+}
+
+/// What `sniff` worked out about a CSV sample.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SniffResult {
+    pub delimiter: u8,
+    pub has_headers: bool,
+    pub column_count: usize,
+    pub columns: Vec<ColumnSchema>,
+    /// Why the guess might be wrong; empty when detection was clear-cut.
+    pub notes: Vec<String>,
+}
+
+impl SniffResult {
+    pub fn is_confident(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    /// `CsvOptions` for reading the whole file the way it was sniffed.
+    pub fn options(&self) -> CsvOptions {
+        CsvOptions::new()
+            .delimiter(self.delimiter)
+            .has_headers(self.has_headers)
+            .trim(true)
+    }
+}
+
+/// Guess the delimiter, header row, and column types of a CSV sample.
+///
+/// Only the first `SNIFF_SAMPLE_RECORDS` records are examined. Never
+/// panics: an empty or single-column sample gives a result with notes.
+pub fn sniff(csv_sample: &str) -> SniffResult {
+    let mut notes = Vec::new();
+
+    // Score every candidate: (share of rows with the most common width, that width)
+    let mut scored: Vec<(u8, f64, usize, Vec<Vec<String>>)> = SNIFF_DELIMITERS
+        .iter()
+        .map(|&delimiter| {
+            let rows = sample_rows(csv_sample, delimiter);
+            let (width, share) = modal_width(&rows);
+            (delimiter, share, width, rows)
+        })
+        .filter(|(_, _, width, _)| *width > 1)
+        .collect();
+    // Most consistent first, then widest; the sort is stable so earlier
+    // candidates win exact ties.
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.2.cmp(&a.2)));
+
+    let (delimiter, column_count, rows) = match scored.first() {
+        Some((delimiter, share, width, rows)) => {
+            if let Some(runner_up) = scored.get(1).filter(|r| r.1 == *share && r.2 == *width) {
+                notes.push(format!(
+                    "ambiguous delimiter: {:?} and {:?} both split rows evenly",
+                    *delimiter as char, runner_up.0 as char
+                ));
+            }
+            if *share < 1.0 {
+                notes.push(format!(
+                    "rows disagree on column count; {:.0}% have {} columns",
+                    share * 100.0,
+                    width
+                ));
+            }
+            (*delimiter, *width, rows.clone())
+        }
+        None => {
+            let rows = sample_rows(csv_sample, b',');
+            let width = usize::from(!rows.is_empty());
+            if rows.is_empty() {
+                notes.push("sample is empty".to_string());
+            } else {
+                notes.push("no delimiter found; treating each line as one column".to_string());
+            }
+            (b',', width, rows)
+        }
+    };
+
+    let has_headers = match rows.split_first() {
+        Some((first, rest)) if !rest.is_empty() => {
+            let first_has_numbers = first.iter().any(|c| ColumnType::of_cell(c).is_numeric());
+            let rest_has_numbers = rest
+                .iter()
+                .flatten()
+                .any(|c| ColumnType::of_cell(c).is_numeric());
+            if !first_has_numbers && !rest_has_numbers {
+                notes.push(
+                    "no numeric data to tell a header from data; assuming a header row".to_string(),
+                );
+                true
+            } else {
+                !first_has_numbers && rest_has_numbers
+            }
+        }
+        Some(_) => {
+            notes.push("only one row; assuming it is data".to_string());
+            false
+        }
+        None => false,
+    };
+
+    let (header, data) = if has_headers {
+        (rows.first().cloned(), &rows[1..])
+    } else {
+        (None, &rows[..])
+    };
+
+    let raw_names: Vec<String> = (0..column_count)
+        .map(|i| match header.as_ref().and_then(|h| h.get(i)) {
+            Some(name) if !name.is_empty() => name.clone(),
+            _ => format!("col_{}", i),
+        })
+        .collect();
+    let names = dedupe_headers(raw_names.iter().map(String::as_str));
+
+    let columns = names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let mut kind = ColumnType::Empty;
+            let mut nullable = data.is_empty();
+            for row in data {
+                let cell_kind = row
+                    .get(i)
+                    .map_or(ColumnType::Empty, |c| ColumnType::of_cell(c));
+                nullable |= cell_kind == ColumnType::Empty;
+                kind = kind.merge(cell_kind);
+            }
+            ColumnSchema {
+                name,
+                kind,
+                nullable,
+            }
+        })
+        .collect();
+
+    SniffResult {
+        delimiter,
+        has_headers,
+        column_count,
+        columns,
+        notes,
+    }
+}
+
+/// The first `SNIFF_SAMPLE_RECORDS` records split on `delimiter`, trimmed.
+/// Quoting is honoured, so a quoted `"a,b"` stays one cell.
+fn sample_rows(csv_sample: &str, delimiter: u8) -> Vec<Vec<String>> {
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(csv_sample.as_bytes())
+        .records()
+        .take(SNIFF_SAMPLE_RECORDS)
+        .map_while(Result::ok)
+        .map(|record| record.iter().map(str::to_string).collect())
+        .collect()
+}
+
+/// The most common row width and the share of rows that have it. Ties go
+/// to the wider width.
+fn modal_width(rows: &[Vec<String>]) -> (usize, f64) {
+    let mut counts: Vec<(usize, usize)> = Vec::new();
+    for row in rows {
+        match counts.iter_mut().find(|(width, _)| *width == row.len()) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((row.len(), 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(width, n)| (n, width))
+        .map_or((0, 0.0), |(width, n)| (width, n as f64 / rows.len() as f64))
+}
+
+// ============================================================================
+// GROUP-BY AND AGGREGATES
+// ============================================================================
+// Row-per-record JSON is not always what a report wants; often it's "how
+// many, how much, on average" per category. `GroupBy` buckets any records by
+// a key function, and the aggregates run one value function over each bucket.
+//
+// Group order: a `HashMap` alone would return groups in an arbitrary order
+// that changes between runs, which makes JSON output impossible to diff.
+// `GroupBy` keeps the groups in a `Vec` in order of each key's first
+// appearance and uses the map only to find a key's slot.
+
+/// Records bucketed by key, in first-appearance order.
+#[derive(Debug, Clone)]
+pub struct GroupBy<K, T> {
+    groups: Vec<(K, Vec<T>)>,
+    index: HashMap<K, usize>,
+}
+
+impl<K: Eq + Hash + Clone, T> GroupBy<K, T> {
+    /// Put each record in the group named by `key_fn`.
+    pub fn new<I, F>(records: I, mut key_fn: F) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        F: FnMut(&T) -> K,
+    {
+        let mut grouped = GroupBy {
+            groups: Vec::new(),
+            index: HashMap::new(),
+        };
+        for record in records {
+            let key = key_fn(&record);
+            match grouped.index.get(&key) {
+                Some(&slot) => grouped.groups[slot].1.push(record),
+                None => {
+                    grouped.index.insert(key.clone(), grouped.groups.len());
+                    grouped.groups.push((key, vec![record]));
+                }
+            }
+        }
+        grouped
+    }
+
+    /// The records with key `key`, in input order.
+    pub fn get(&self, key: &K) -> Option<&[T]> {
+        self.index
+            .get(key)
+            .map(|&slot| self.groups[slot].1.as_slice())
+    }
+
+    /// `(key, records)` pairs in first-appearance order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &[T])> {
+        self.groups
+            .iter()
+            .map(|(key, records)| (key, records.as_slice()))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.groups.iter().map(|(key, _)| key)
+    }
+
+    /// Number of groups.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub fn into_groups(self) -> Vec<(K, Vec<T>)> {
+        self.groups
+    }
+
+    /// Records per group.
+    pub fn counts(&self) -> Vec<(K, usize)> {
+        self.aggregate(|records| records.len())
+    }
+
+    /// Sum of `value_fn` over each group.
+    pub fn sum<F: FnMut(&T) -> f64>(&self, mut value_fn: F) -> Vec<(K, f64)> {
+        self.aggregate(|records| records.iter().map(&mut value_fn).sum())
+    }
+
+    /// Mean of `value_fn` over each group. Groups are never empty, so there
+    /// is no division by zero.
+    pub fn avg<F: FnMut(&T) -> f64>(&self, mut value_fn: F) -> Vec<(K, f64)> {
+        self.aggregate(|records| {
+            records.iter().map(&mut value_fn).sum::<f64>() / records.len() as f64
+        })
+    }
+
+    /// `(min, max)` of `value_fn` over each group. NaN values are skipped;
+    /// a group of nothing but NaN reports `(NaN, NaN)`.
+    pub fn min_max<F: FnMut(&T) -> f64>(&self, mut value_fn: F) -> Vec<(K, (f64, f64))> {
+        self.aggregate(|records| {
+            records
+                .iter()
+                .map(&mut value_fn)
+                .fold((f64::NAN, f64::NAN), |(lo, hi), v| (lo.min(v), hi.max(v)))
+        })
+    }
+
+    fn aggregate<V, F: FnMut(&[T]) -> V>(&self, mut f: F) -> Vec<(K, V)> {
+        self.groups
+            .iter()
+            .map(|(key, records)| (key.clone(), f(records)))
+            .collect()
+    }
+}
+
+/// Shorthand for `GroupBy::new`.
+pub fn group_by_key<K, T, I, F>(records: I, key_fn: F) -> GroupBy<K, T>
+where
+    K: Eq + Hash + Clone,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T) -> K,
+{
+    GroupBy::new(records, key_fn)
+}
+
+/// Records per key, in first-appearance order.
+pub fn count_by<K, T, I, F>(records: I, key_fn: F) -> Vec<(K, usize)>
+where
+    K: Eq + Hash + Clone,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T) -> K,
+{
+    GroupBy::new(records, key_fn).counts()
+}
+
+/// Sum of `value_fn` per key, in first-appearance order.
+pub fn sum_by<K, T, I, F, V>(records: I, key_fn: F, value_fn: V) -> Vec<(K, f64)>
+where
+    K: Eq + Hash + Clone,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T) -> K,
+    V: FnMut(&T) -> f64,
+{
+    GroupBy::new(records, key_fn).sum(value_fn)
+}
+
+/// Mean of `value_fn` per key, in first-appearance order.
+pub fn avg_by<K, T, I, F, V>(records: I, key_fn: F, value_fn: V) -> Vec<(K, f64)>
+where
+    K: Eq + Hash + Clone,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T) -> K,
+    V: FnMut(&T) -> f64,
+{
+    GroupBy::new(records, key_fn).avg(value_fn)
+}
+
+/// `(min, max)` of `value_fn` per key, in first-appearance order.
+pub fn min_max_by<K, T, I, F, V>(records: I, key_fn: F, value_fn: V) -> Vec<(K, (f64, f64))>
+where
+    K: Eq + Hash + Clone,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T) -> K,
+    V: FnMut(&T) -> f64,
+{
+    GroupBy::new(records, key_fn).min_max(value_fn)
+}
+
+/// Per-group totals for a list of products.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductSummary {
+    pub group: String,
+    /// Number of product rows in the group.
+    pub count: usize,
+    /// Sum of `quantity`.
+    pub total_quantity: u64,
+    /// Sum of `price * quantity`: what the stock is worth.
+    pub total_value: f64,
+    /// Mean of `price` over the rows (not weighted by quantity).
+    pub average_price: f64,
+}
+
+/// The group a product is summarized under: its `category` if set and
+/// non-blank, otherwise the first word of its name.
+pub fn product_group(product: &Product) -> String {
+    match product.category.as_deref().map(str::trim) {
+        Some(category) if !category.is_empty() => category.to_string(),
+        _ => product
+            .name
+            .split_whitespace()
+            .next()
+            .unwrap_or("")
+            .to_string(),
+    }
+}
+
+/// Summarize products grouped by `product_group`.
+pub fn summarize_products(products: &[Product]) -> Vec<ProductSummary> {
+    summarize_products_by(products, product_group)
+}
+
+/// Summarize products grouped by any key.
+pub fn summarize_products_by<F>(products: &[Product], mut key_fn: F) -> Vec<ProductSummary>
+where
+    F: FnMut(&Product) -> String,
+{
+    GroupBy::new(products, |product| key_fn(product))
+        .into_groups()
+        .into_iter()
+        .map(|(group, items)| ProductSummary {
+            group,
+            count: items.len(),
+            total_quantity: items.iter().map(|p| u64::from(p.quantity)).sum(),
+            total_value: items.iter().map(|p| p.price * f64::from(p.quantity)).sum(),
+            average_price: items.iter().map(|p| p.price).sum::<f64>() / items.len() as f64,
+        })
+        .collect()
+}
+
+/// Pretty-printed JSON array of summaries, in group order.
+pub fn summaries_to_json(summaries: &[ProductSummary]) -> Result<String, Box<dyn Error>> {
+    let json = serde_json::to_string_pretty(summaries)?;
+    Ok(json)
+}
+
 // ============================================================================
 // WHAT RUST DOES UNDER THE HOOD
 // ============================================================================
@@ -4,6 +4,7 @@
 // error handling, and edge cases.
 
 use csv_to_json::solution::*;
+use serde_json::{json, Value};
 
 // ============================================================================
 // CSV TEST DATA
@@ -329,3 +330,638 @@ fn test_invalid_csv_wrong_type() {
     let result = parse_csv_to_persons(csv);
     assert!(result.is_err());
 }
+
+// ============================================================================
+// SCHEMA-LESS CONVERSION TESTS
+// ============================================================================
+
+fn parse_generic(csv: &str, infer: bool) -> Value {
+    let text = csv_to_json_generic_typed(csv, infer).unwrap();
+    serde_json::from_str(&text).unwrap()
+}
+
+#[test]
+fn test_generic_mixed_type_columns() {
+    let csv = "sku,qty,price,active,note\nA1,3,9.99,true,fragile\nB2,-4,10,false,12abc";
+    let value: Value = serde_json::from_str(&csv_to_json_generic(csv).unwrap()).unwrap();
+
+    assert_eq!(
+        value,
+        json!([
+            {"sku": "A1", "qty": 3, "price": 9.99, "active": true, "note": "fragile"},
+            {"sku": "B2", "qty": -4, "price": 10, "active": false, "note": "12abc"}
+        ])
+    );
+}
+
+#[test]
+fn test_generic_preserves_column_order() {
+    let text = csv_to_json_generic("zeta,alpha,mid\n1,2,3").unwrap();
+    let zeta = text.find("zeta").unwrap();
+    let alpha = text.find("alpha").unwrap();
+    let mid = text.find("mid").unwrap();
+    assert!(
+        zeta < alpha && alpha < mid,
+        "keys should follow CSV order:\n{}",
+        text
+    );
+}
+
+#[test]
+fn test_generic_empty_cells_become_null() {
+    let value = parse_generic("a,b,c\n1,,x\n,,", true);
+    assert_eq!(
+        value,
+        json!([{"a": 1, "b": null, "c": "x"}, {"a": null, "b": null, "c": null}])
+    );
+}
+
+#[test]
+fn test_generic_without_inference_keeps_strings() {
+    let value = parse_generic("a,b,c\n1,,true", false);
+    assert_eq!(value, json!([{"a": "1", "b": "", "c": "true"}]));
+}
+
+#[test]
+fn test_generic_duplicate_headers_get_suffix() {
+    let value = parse_generic("name,name,id,name\nAda,Lovelace,1,Countess", true);
+    assert_eq!(
+        value,
+        json!([{"name": "Ada", "name_2": "Lovelace", "id": 1, "name_3": "Countess"}])
+    );
+}
+
+#[test]
+fn test_dedupe_headers_skips_taken_suffix() {
+    let headers = dedupe_headers(["a", "a", "a_2"].into_iter());
+    assert_eq!(headers, vec!["a", "a_2", "a_2_2"]);
+}
+
+#[test]
+fn test_generic_headers_only_yields_empty_array() {
+    let value = parse_generic("name,age,city\n", true);
+    assert_eq!(value, json!([]));
+}
+
+#[test]
+fn test_generic_ragged_row_is_error() {
+    assert!(csv_to_json_generic("a,b\n1,2,3").is_err());
+}
+
+#[test]
+fn test_infer_value_rules() {
+    assert_eq!(infer_value(""), Value::Null);
+    assert_eq!(infer_value("42"), json!(42));
+    assert_eq!(infer_value("-1.5"), json!(-1.5));
+    assert_eq!(infer_value("true"), json!(true));
+    assert_eq!(infer_value("True"), json!("True"));
+    assert_eq!(infer_value("NaN"), json!("NaN"));
+    assert_eq!(infer_value("hello"), json!("hello"));
+}
+
+// ============================================================================
+// JSON TO CSV
+// ============================================================================
+
+#[test]
+fn test_json_to_csv_students_roundtrip() {
+    let students = parse_nested_csv(STUDENT_CSV).unwrap();
+    let json = students_to_json(&students).unwrap();
+    let csv = json_to_csv(&json).unwrap();
+
+    assert!(csv.starts_with(
+        "id,name,contact.email,contact.phone,grades.math,grades.english,grades.science\n"
+    ));
+    assert_eq!(parse_nested_csv(&csv).unwrap(), students);
+}
+
+#[test]
+fn test_json_to_csv_union_of_keys_in_first_seen_order() {
+    let json = r#"[{"a": 1, "b": 2}, {"c": 3, "a": 4}, {"b": null}]"#;
+    let csv = json_to_csv(json).unwrap();
+    assert_eq!(csv, "a,b,c\n1,2,\n4,,3\n,,\n");
+}
+
+#[test]
+fn test_json_to_csv_quotes_special_characters() {
+    let json = r#"[{"name": "Smith, Jo", "quote": "say \"hi\"", "note": "two\nlines"}]"#;
+    let csv = json_to_csv(json).unwrap();
+    assert_eq!(
+        csv,
+        "name,quote,note\n\"Smith, Jo\",\"say \"\"hi\"\"\",\"two\nlines\"\n"
+    );
+
+    let back = parse_generic(&csv, false);
+    assert_eq!(
+        back,
+        json!([{"name": "Smith, Jo", "quote": "say \"hi\"", "note": "two\nlines"}])
+    );
+}
+
+#[test]
+fn test_json_to_csv_arrays_and_deep_nesting() {
+    let json = r#"[{"tags": ["x", "y"], "meta": {"owner": {"id": 7}, "ok": true}}]"#;
+    let csv = json_to_csv(json).unwrap();
+    assert_eq!(
+        csv,
+        "tags,meta.owner.id,meta.ok\n\"[\"\"x\"\",\"\"y\"\"]\",7,true\n"
+    );
+}
+
+#[test]
+fn test_json_to_csv_empty_array() {
+    assert_eq!(json_to_csv("[]").unwrap(), "");
+}
+
+#[test]
+fn test_json_to_csv_rejects_non_array_input() {
+    assert!(json_to_csv(r#"{"a": 1}"#).is_err());
+    assert!(json_to_csv("[1, 2]").is_err());
+    assert!(json_to_csv("not json").is_err());
+}
+
+// ============================================================================
+// STREAMING CONVERSION
+// ============================================================================
+
+#[test]
+fn test_convert_stream_large_input() {
+    let rows = 50_000;
+    let mut csv = String::from("id,level,message\n");
+    for i in 0..rows {
+        csv.push_str(&format!("{},INFO,\"event {}, ok\"\n", i, i));
+    }
+
+    let mut out = Vec::new();
+    let stats = convert_stream(std::io::Cursor::new(csv), &mut out, false).unwrap();
+
+    assert_eq!(stats.records, rows);
+    assert_eq!(stats.errors, 0);
+    assert_eq!(stats.bytes_written, out.len() as u64);
+
+    let value: Value = serde_json::from_slice(&out).unwrap();
+    let items = value.as_array().unwrap();
+    assert_eq!(items.len(), rows);
+    assert_eq!(
+        items[0],
+        json!({"id": 0, "level": "INFO", "message": "event 0, ok"})
+    );
+    assert_eq!(items[rows - 1]["id"], json!(rows - 1));
+}
+
+#[test]
+fn test_convert_stream_skips_malformed_row_when_tolerant() {
+    let csv = "a,b\n1,2\n3,4,5\n6,7\n";
+    let mut out = Vec::new();
+    let stats = convert_stream(csv.as_bytes(), &mut out, true).unwrap();
+
+    assert_eq!(stats.records, 2);
+    assert_eq!(stats.errors, 1);
+    let value: Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(value, json!([{"a": 1, "b": 2}, {"a": 6, "b": 7}]));
+}
+
+#[test]
+fn test_convert_stream_aborts_on_malformed_row_when_strict() {
+    let csv = "a,b\n1,2\n3,4,5\n6,7\n";
+    let mut out = Vec::new();
+    assert!(convert_stream(csv.as_bytes(), &mut out, false).is_err());
+}
+
+/// Yields `data`, then fails on every read after it.
+struct FailingReader {
+    data: std::io::Cursor<Vec<u8>>,
+    failures: usize,
+}
+
+impl std::io::Read for FailingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.data.read(buf)?;
+        if n > 0 {
+            return Ok(n);
+        }
+        self.failures += 1;
+        assert!(self.failures < 100, "reader retried forever");
+        Err(std::io::Error::other("disk went away"))
+    }
+}
+
+#[test]
+fn test_convert_stream_propagates_io_errors_when_tolerant() {
+    let mut reader = FailingReader {
+        data: std::io::Cursor::new(b"a,b\n1,2\n".to_vec()),
+        failures: 0,
+    };
+    let mut out = Vec::new();
+    let err = convert_stream(&mut reader, &mut out, true).unwrap_err();
+    assert!(err.to_string().contains("disk went away"), "{}", err);
+    assert_eq!(reader.failures, 1);
+}
+
+#[test]
+fn test_convert_stream_headers_only() {
+    let mut out = Vec::new();
+    let stats = convert_stream("a,b\n".as_bytes(), &mut out, false).unwrap();
+    assert_eq!(stats.records, 0);
+    assert_eq!(serde_json::from_slice::<Value>(&out).unwrap(), json!([]));
+}
+
+// ============================================================================
+// PARSING OPTIONS
+// ============================================================================
+
+fn alice() -> Person {
+    Person {
+        name: "Alice".to_string(),
+        age: 30,
+        city: "New York".to_string(),
+    }
+}
+
+#[test]
+fn test_options_default_matches_plain_parser() {
+    let plain = parse_csv_to_persons(PERSON_CSV).unwrap();
+    let with = parse_csv_to_persons_with(PERSON_CSV, &CsvOptions::new()).unwrap();
+    assert_eq!(plain, with);
+}
+
+#[test]
+fn test_options_tsv() {
+    let tsv = "name\tage\tcity\nAlice\t30\tNew York\nBob\t25\tSan Francisco, CA";
+    let people = parse_csv_to_persons_with(tsv, &CsvOptions::new().delimiter(b'\t')).unwrap();
+    assert_eq!(people.len(), 2);
+    assert_eq!(people[0], alice());
+    assert_eq!(people[1].city, "San Francisco, CA");
+}
+
+#[test]
+fn test_options_semicolon_european_csv() {
+    let data = "name ; age ; city\nAlice ; 30 ; New York\n'Dupont; Marie' ; 41 ; Paris";
+    let options = CsvOptions::new().delimiter(b';').quote(b'\'').trim(true);
+    let people = parse_csv_to_persons_with(data, &options).unwrap();
+    assert_eq!(people[0], alice());
+    assert_eq!(people[1].name, "Dupont; Marie");
+    assert_eq!(people[1].age, 41);
+}
+
+#[test]
+fn test_options_headerless_positional() {
+    let data = "Alice,30,New York\nBob,25,Boston";
+    let people = parse_csv_to_persons_with(data, &CsvOptions::new().has_headers(false)).unwrap();
+    assert_eq!(people.len(), 2);
+    assert_eq!(people[0], alice());
+}
+
+#[test]
+fn test_options_projection_reorders_columns() {
+    // Headerless export in city, id, name, age order.
+    let data = "New York,1,Alice,30\nBoston,2,Bob,25";
+    let options = CsvOptions::new()
+        .has_headers(false)
+        .columns(["2", "3", "0"]);
+    let people = parse_csv_to_persons_with(data, &options).unwrap();
+    assert_eq!(people[0], alice());
+    assert_eq!(people[1].name, "Bob");
+
+    // With headers, extra columns are dropped by projecting by name.
+    let data = "id,city,name,age\n1,New York,Alice,30";
+    let options = CsvOptions::new().columns(["name", "age", "city"]);
+    let json = csv_to_json_with(data, &options).unwrap();
+    let value: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        value,
+        json!([{"name": "Alice", "age": 30, "city": "New York"}])
+    );
+}
+
+#[test]
+fn test_options_missing_projected_column_is_named() {
+    let options = CsvOptions::new().columns(["name", "country"]);
+    let err = parse_csv_to_persons_with(PERSON_CSV, &options).unwrap_err();
+    assert!(
+        err.to_string().contains("country"),
+        "unexpected error: {}",
+        err
+    );
+
+    let options = CsvOptions::new().has_headers(false).columns(["0", "7"]);
+    let err = parse_csv_to_persons_with("Alice,30,New York", &options).unwrap_err();
+    assert!(err.to_string().contains('7'), "unexpected error: {}", err);
+
+    let options = CsvOptions::new().has_headers(false).columns(["name"]);
+    let err = parse_csv_to_persons_with("Alice,30,New York", &options).unwrap_err();
+    assert!(
+        err.to_string().contains("name"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+// ============================================================================
+// VALIDATION REPORTS
+// ============================================================================
+
+#[test]
+fn test_validate_csv_reports_row_and_field() {
+    let csv = "\
+id,name,price,quantity
+1,Widget,9.99,10
+2,Gadget,19.99,5
+3,Gizmo,not-a-number,7
+4,Doohickey,4.50,1
+5,Thingamajig,2.00";
+
+    let report = validate_csv::<Product>(csv);
+    assert_eq!(report.valid_count, 3);
+    assert_eq!(report.errors.len(), 2);
+
+    assert_eq!(report.errors[0].row_number, 3);
+    assert_eq!(report.errors[0].field.as_deref(), Some("price"));
+    assert!(
+        report.errors[0].message.contains("float"),
+        "{}",
+        report.errors[0].message
+    );
+
+    assert_eq!(report.errors[1].row_number, 5);
+    assert_eq!(report.errors[1].field, None);
+    assert_eq!(report.errors[1].message, "expected 4 fields, found 3");
+    assert!(!report.is_valid());
+}
+
+#[test]
+fn test_validate_csv_fully_valid_file() {
+    let report = validate_csv::<Person>(PERSON_CSV);
+    assert_eq!(report.valid_count, 4);
+    assert!(report.errors.is_empty());
+    assert!(report.is_valid());
+}
+
+#[test]
+fn test_validation_report_to_json() {
+    let report = validate_csv::<Person>("name,age,city\nAlice,old,Paris");
+    let value: Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    assert_eq!(value["valid_count"], json!(0));
+    assert_eq!(value["errors"][0]["row_number"], json!(1));
+    assert_eq!(value["errors"][0]["field"], json!("age"));
+}
+
+// ============================================================================
+// SNIFFING
+// ============================================================================
+
+fn kinds(result: &SniffResult) -> Vec<ColumnType> {
+    result.columns.iter().map(|c| c.kind).collect()
+}
+
+#[test]
+fn test_sniff_semicolon_file() {
+    // Decimal commas would fool a comma-only reader.
+    let sample =
+        "name;price;qty;in_stock\nTea;4,50;12;true\nCoffee;7,25;3;false\nCocoa;3,00;0;true\n";
+    let result = sniff(sample);
+
+    assert_eq!(result.delimiter, b';');
+    assert!(result.has_headers);
+    assert_eq!(result.column_count, 4);
+    let names: Vec<&str> = result.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["name", "price", "qty", "in_stock"]);
+    assert_eq!(
+        kinds(&result),
+        vec![
+            ColumnType::String,
+            ColumnType::String,
+            ColumnType::Int,
+            ColumnType::Bool
+        ]
+    );
+    assert!(result.is_confident(), "{:?}", result.notes);
+}
+
+#[test]
+fn test_sniff_tsv_with_nullable_column() {
+    let sample = "id\tcity\tpopulation\n1\tLisbon\t545923\n2\tPorto\t\n3\tFaro\t64560\n";
+    let result = sniff(sample);
+
+    assert_eq!(result.delimiter, b'\t');
+    assert!(result.has_headers);
+    assert_eq!(
+        result.columns[2],
+        ColumnSchema {
+            name: "population".to_string(),
+            kind: ColumnType::Int,
+            nullable: true
+        }
+    );
+    assert!(!result.columns[0].nullable);
+
+    let options = result.options();
+    assert_eq!(options.delimiter, b'\t');
+    assert!(options.has_headers);
+}
+
+#[test]
+fn test_sniff_headerless_numeric_file() {
+    let result = sniff("1|2.5|7\n4|5|8\n9|1e3|-2\n");
+
+    assert_eq!(result.delimiter, b'|');
+    assert!(!result.has_headers);
+    let names: Vec<&str> = result.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["col_0", "col_1", "col_2"]);
+    assert_eq!(
+        kinds(&result),
+        vec![ColumnType::Int, ColumnType::Float, ColumnType::Int]
+    );
+}
+
+#[test]
+fn test_sniff_ints_and_floats_merge_to_float() {
+    let result = sniff("x,y\n1,a\n2.5,b\n3,c\n");
+    assert_eq!(result.columns[0].kind, ColumnType::Float);
+
+    assert_eq!(ColumnType::Int.merge(ColumnType::Float), ColumnType::Float);
+    assert_eq!(ColumnType::Empty.merge(ColumnType::Bool), ColumnType::Bool);
+    assert_eq!(ColumnType::Int.merge(ColumnType::Bool), ColumnType::String);
+}
+
+#[test]
+fn test_sniff_quoted_delimiters_do_not_count() {
+    let sample = "name,quote\n\"Smith; J\",\"a; b; c\"\n\"Doe; A\",\"x; y\"\n";
+    let result = sniff(sample);
+    assert_eq!(result.delimiter, b',');
+    assert_eq!(result.column_count, 2);
+}
+
+#[test]
+fn test_sniff_ambiguous_inputs_do_not_panic() {
+    let single = sniff("name\nAlice\nBob\n");
+    assert_eq!(single.column_count, 1);
+    assert_eq!(single.columns.len(), 1);
+    assert!(!single.is_confident());
+
+    let empty = sniff("");
+    assert_eq!(empty.column_count, 0);
+    assert!(empty.columns.is_empty());
+    assert!(!empty.is_confident());
+
+    let one_row = sniff("a,b,c");
+    assert!(!one_row.has_headers);
+    assert_eq!(one_row.column_count, 3);
+    assert!(!one_row.is_confident());
+
+    // Both ',' and ';' split this evenly.
+    let tie = sniff("a,b;c\nd,e;f\n");
+    assert_eq!(tie.delimiter, b',');
+    assert!(tie.notes.iter().any(|n| n.contains("ambiguous")));
+}
+
+#[test]
+fn test_sniff_only_reads_the_sample_window() {
+    let mut sample = String::from("n\tv\n");
+    for i in 0..(SNIFF_SAMPLE_RECORDS * 3) {
+        sample.push_str(&format!("{}\t{}\n", i, i));
+    }
+    // Rows past the window would turn column v into a string.
+    sample.push_str("x\thello\n");
+    assert_eq!(sniff(&sample).columns[1].kind, ColumnType::Int);
+}
+
+// ============================================================================
+// GROUP-BY AND AGGREGATE TESTS
+// ============================================================================
+
+const INVENTORY_CSV: &str = "\
+id,name,price,quantity,category
+1,Steel Bolt,0.50,200,
+2,Oak Table,120.00,2,furniture
+3,Steel Nut,0.25,400,
+4,Pine Shelf,45.00,4,furniture
+5,Brass Hinge,3.00,10,
+6,Steel Washer,0.10,1000,";
+
+fn inventory() -> Vec<Product> {
+    let (products, errors) = parse_csv_to_products_tolerant(INVENTORY_CSV);
+    assert_eq!(errors, 0);
+    products
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn test_category_column_is_optional() {
+    let products = inventory();
+    assert_eq!(products[0].category, None);
+    assert_eq!(products[1].category.as_deref(), Some("furniture"));
+
+    // The original four-column files still parse, and still serialize
+    // without a category key
+    let (products, errors) = parse_csv_to_products_tolerant(PRODUCT_CSV_GOOD);
+    assert_eq!(errors, 0);
+    assert!(products.iter().all(|p| p.category.is_none()));
+    assert!(!products_to_json(&products).unwrap().contains("category"));
+}
+
+#[test]
+fn test_summarize_products_hand_checked() {
+    let summaries = summarize_products(&inventory());
+    let groups: Vec<&str> = summaries.iter().map(|s| s.group.as_str()).collect();
+    assert_eq!(groups, ["Steel", "furniture", "Brass"]);
+
+    let steel = &summaries[0];
+    assert_eq!(steel.count, 3);
+    assert_eq!(steel.total_quantity, 1600);
+    // 0.50 * 200 + 0.25 * 400 + 0.10 * 1000
+    assert_close(steel.total_value, 300.0);
+    assert_close(steel.average_price, (0.50 + 0.25 + 0.10) / 3.0);
+
+    let furniture = &summaries[1];
+    assert_eq!((furniture.count, furniture.total_quantity), (2, 6));
+    assert_close(furniture.total_value, 240.0 + 180.0);
+    assert_close(furniture.average_price, 82.5);
+
+    let json: Value = serde_json::from_str(&summaries_to_json(&summaries).unwrap()).unwrap();
+    assert_eq!(json[2]["group"], "Brass");
+    assert_eq!(json[2]["total_quantity"], 10);
+}
+
+#[test]
+fn test_aggregate_helpers() {
+    let products = inventory();
+    assert_eq!(
+        count_by(&products, |p| product_group(p)),
+        [
+            ("Steel".to_string(), 3),
+            ("furniture".to_string(), 2),
+            ("Brass".to_string(), 1)
+        ]
+    );
+
+    let sums = sum_by(&products, |p| product_group(p), |p| f64::from(p.quantity));
+    assert_eq!(sums[0], ("Steel".to_string(), 1600.0));
+
+    let averages = avg_by(&products, |p| product_group(p), |p| p.price);
+    assert_close(averages[1].1, 82.5);
+
+    let ranges = min_max_by(&products, |p| product_group(p), |p| p.price);
+    assert_eq!(ranges[0].1, (0.10, 0.50));
+    assert_eq!(ranges[2].1, (3.0, 3.0));
+}
+
+#[test]
+fn test_group_by_works_on_any_records() {
+    let words = ["apple", "bob", "avocado", "cat", "banana", "apricot"];
+    let groups = group_by_key(words, |w| w.chars().next().unwrap());
+
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups.keys().copied().collect::<String>(), "abc");
+    assert_eq!(groups.get(&'a'), Some(&["apple", "avocado", "apricot"][..]));
+    assert_eq!(groups.get(&'z'), None);
+    assert_eq!(groups.counts(), [('a', 3), ('b', 2), ('c', 1)]);
+
+    let lengths = groups.sum(|w| w.len() as f64);
+    assert_eq!(lengths[1], ('b', 9.0));
+
+    let owned = groups.into_groups();
+    assert_eq!(owned[2], ('c', vec!["cat"]));
+}
+
+#[test]
+fn test_group_order_is_first_appearance() {
+    // Same data, shuffled: group order follows the input, not the keys
+    let mut products = inventory();
+    products.reverse();
+    let groups: Vec<String> = summarize_products(&products)
+        .into_iter()
+        .map(|s| s.group)
+        .collect();
+    assert_eq!(groups, ["Steel", "Brass", "furniture"]);
+
+    let by_id = summarize_products_by(&products, |p| (p.id % 2).to_string());
+    assert_eq!(by_id[0].group, "0");
+    assert_eq!(by_id[0].count, 3);
+}
+
+#[test]
+fn test_group_by_empty_input() {
+    let groups = group_by_key(Vec::<Product>::new(), product_group);
+    assert!(groups.is_empty());
+    assert!(groups.counts().is_empty());
+    assert!(summarize_products(&[]).is_empty());
+    assert_eq!(summaries_to_json(&[]).unwrap(), "[]");
+    assert!(min_max_by(Vec::<f64>::new(), |_| 0, |x| *x).is_empty());
+}
+
+#[test]
+fn test_min_max_skips_nan() {
+    let values = [(1, f64::NAN), (1, 2.0), (1, -1.0), (2, f64::NAN)];
+    let ranges = min_max_by(values, |(k, _)| *k, |(_, v)| *v);
+    assert_eq!(ranges[0].1, (-1.0, 2.0));
+    assert!(ranges[1].1 .0.is_nan() && ranges[1].1 .1.is_nan());
+}
@@ -3,6 +3,8 @@
 //! Implement declarative macros in `src/solution.rs` and compare your expansions
 //! against the reference behavior in the integration tests.
 
+use std::time::Duration;
+
 pub fn calculate_add(_a: i32, _b: i32) -> i32 {
     todo!("Use calculate! macro to add two integers")
 }
@@ -15,5 +17,50 @@ pub fn calculate_power(_base: f64, _exp: f64) -> f64 {
     todo!("Use calculate! macro to compute power")
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DurationStats {
+    pub samples: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub std_dev: Duration,
+}
+
+pub fn compute_stats(_samples: &[Duration]) -> DurationStats {
+    todo!("Mean, median, min, max, and population std-dev of the samples")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    pub label: String,
+    pub stats: DurationStats,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchComparison {
+    pub baseline: BenchResult,
+    pub candidate: BenchResult,
+}
+
+impl BenchComparison {
+    pub fn new(
+        _baseline_label: impl Into<String>,
+        _baseline_samples: &[Duration],
+        _candidate_label: impl Into<String>,
+        _candidate_samples: &[Duration],
+    ) -> Self {
+        todo!("Compute stats for both sides")
+    }
+
+    pub fn speedup(&self) -> f64 {
+        todo!("Baseline mean divided by candidate mean")
+    }
+
+    pub fn stats(&self, _label: &str) -> Option<&DurationStats> {
+        todo!("Look up a side by label")
+    }
+}
+
 #[doc(hidden)]
 pub mod solution;
@@ -2,7 +2,8 @@
 
 use declarative_macros::solution::{calculate_add, calculate_multiply, calculate_power};
 use declarative_macros::{
-    assert_between, calculate, count_tokens, fancy_sum, greet, hashmap, make_vec, sum, time_it,
+    assert_between, bench_compare, calculate, config, count_tokens, fancy_sum, greet, hashmap,
+    make_vec, string_enum, sum, time_it,
 };
 
 fn main() {
@@ -28,6 +29,22 @@ fn main() {
     assert_between!(50, 0, 100);
     println!("count_tokens!: {}", count_tokens!(alpha beta gamma));
 
+    string_enum! {
+        Level {
+            Debug => "debug",
+            Info => "info",
+            Warning => "warn",
+            Error,
+        }
+    }
+    let names: Vec<String> = Level::iter().map(|l| l.to_string()).collect();
+    println!(
+        "string_enum!: {} levels {:?}",
+        Level::variant_count(),
+        names
+    );
+    println!("parse 'WARN': {:?}", Level::from_str_ignore_case("WARN"));
+
     let (result, elapsed) = time_it!({
         let mut total = 0;
         for i in 0..10_000 {
@@ -37,6 +54,35 @@ fn main() {
     });
     println!("time_it! result={} elapsed={:?}", result, elapsed);
 
+    let numbers: Vec<u64> = (0..100_000).collect();
+    let cmp = bench_compare!(50,
+        "for loop" => {
+            let mut total = 0u64;
+            for n in &numbers {
+                total += n;
+            }
+            total
+        },
+        "iter().sum()" => { numbers.iter().sum::<u64>() },
+    );
+    for side in [&cmp.baseline, &cmp.candidate] {
+        println!(
+            "bench_compare! {}: mean={:?} median={:?} sd={:?}",
+            side.label, side.stats.mean, side.stats.median, side.stats.std_dev
+        );
+    }
+    println!("speedup: {:.2}x", cmp.speedup());
+
+    config! {
+        DemoConfig {
+            host: String = "localhost".to_string(),
+            port: u16 = 8080,
+        }
+        validate(cfg) { cfg.port > 0 }
+    }
+    let cfg = DemoConfig::from_env("DEMO").with_host("0.0.0.0".to_string());
+    println!("config!: {:?} valid={}", cfg, cfg.validate());
+
     // Helper functions from the solution module.
     println!("helper add: {}", calculate_add(10, 20));
     println!("helper multiply: {}", calculate_multiply(6, 7));
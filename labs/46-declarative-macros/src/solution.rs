@@ -11,6 +11,8 @@
 // - Internal rules with @ prefix
 // - Zero-cost compile-time code generation
 
+use std::time::Duration;
+
 // ============================================================================
 // SIMPLE MACROS
 // ============================================================================
@@ -158,6 +160,11 @@ macro_rules! fancy_sum {
 
 /// Generate an enum with automatic `to_str()`, `from_str()`, and `all_variants()` methods.
 ///
+/// Each variant may give its own display string with `Variant => "text"`;
+/// bare variants use their own name. Both forms can be mixed. The enum also
+/// gets `from_str_ignore_case()`, `variant_count()`, `iter()`, and a
+/// `Display` impl, and derives `Hash` so it can key a `HashMap`.
+///
 /// Usage:
 /// ```
 /// use declarative_macros::string_enum;
@@ -165,52 +172,80 @@ macro_rules! fancy_sum {
 /// string_enum! {
 ///     Color {
 ///         Red,
-///         Green,
+///         Green => "green",
 ///         Blue,
 ///     }
 /// }
 ///
 /// let c = Color::Red;
 /// assert_eq!(c.to_str(), "Red");
-/// assert_eq!(Color::from_str("Green"), Some(Color::Green));
+/// assert_eq!(Color::from_str("green"), Some(Color::Green));
+/// assert_eq!(Color::from_str_ignore_case("BLUE"), Some(Color::Blue));
+/// assert_eq!(Color::variant_count(), 3);
 /// ```
 #[macro_export]
 macro_rules! string_enum {
+    // Internal rule: the string for one variant. An explicit display
+    // string wins; otherwise fall back to the variant's name.
+    (@str $variant:ident) => {
+        stringify!($variant)
+    };
+    (@str $variant:ident $display:literal) => {
+        $display
+    };
+
     (
         $name:ident {
-            $($variant:ident),* $(,)?
+            $($variant:ident $(=> $display:literal)?),* $(,)?
         }
     ) => {
-        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
         pub enum $name {
             $($variant),*
         }
 
         impl $name {
+            const ALL: &'static [$name] = &[$($name::$variant),*];
+
             pub fn to_str(&self) -> &'static str {
                 match self {
                     $(
-                        $name::$variant => stringify!($variant),
+                        $name::$variant => $crate::string_enum!(@str $variant $($display)?),
                     )*
                 }
             }
 
             pub fn from_str(s: &str) -> Option<Self> {
-                match s {
-                    $(
-                        stringify!($variant) => Some($name::$variant),
-                    )*
-                    _ => None,
-                }
+                Self::iter().find(|v| v.to_str() == s)
+            }
+
+            /// Like `from_str`, but ignores ASCII case.
+            pub fn from_str_ignore_case(s: &str) -> Option<Self> {
+                Self::iter().find(|v| v.to_str().eq_ignore_ascii_case(s))
             }
 
             pub fn all_variants() -> &'static [&'static str] {
                 &[
                     $(
-                        stringify!($variant),
+                        $crate::string_enum!(@str $variant $($display)?),
                     )*
                 ]
             }
+
+            pub const fn variant_count() -> usize {
+                Self::ALL.len()
+            }
+
+            /// Every variant, in declaration order.
+            pub fn iter() -> impl Iterator<Item = Self> {
+                Self::ALL.iter().copied()
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.to_str())
+            }
         }
     };
 }
@@ -237,13 +272,203 @@ macro_rules! time_it {
     }};
 }
 
+// ============================================================================
+// BENCHMARK COMPARISON MACRO
+// ============================================================================
+
+/// Summary statistics for a set of timing samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DurationStats {
+    pub samples: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    /// Population standard deviation.
+    pub std_dev: Duration,
+}
+
+/// Compute mean, median, min, max, and standard deviation of `samples`.
+///
+/// An empty slice gives all-zero stats. The median of an even number of
+/// samples is the mean of the two middle values.
+pub fn compute_stats(samples: &[Duration]) -> DurationStats {
+    if samples.is_empty() {
+        return DurationStats::default();
+    }
+
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+    let n = sorted.len();
+
+    // Work in nanoseconds: u128 for exact sums, f64 for the variance.
+    let total: u128 = sorted.iter().map(|d| d.as_nanos()).sum();
+    let mean_nanos = total / n as u128;
+    let median = if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+    };
+
+    let mean_f = total as f64 / n as f64;
+    let variance = sorted
+        .iter()
+        .map(|d| {
+            let diff = d.as_nanos() as f64 - mean_f;
+            diff * diff
+        })
+        .sum::<f64>()
+        / n as f64;
+
+    DurationStats {
+        samples: n,
+        mean: Duration::from_nanos(mean_nanos as u64),
+        median,
+        min: sorted[0],
+        max: sorted[n - 1],
+        std_dev: Duration::from_nanos(variance.sqrt().round() as u64),
+    }
+}
+
+/// One labeled side of a `bench_compare!` run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    pub label: String,
+    pub stats: DurationStats,
+}
+
+/// The result of `bench_compare!`: stats for a baseline and a candidate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchComparison {
+    pub baseline: BenchResult,
+    pub candidate: BenchResult,
+}
+
+impl BenchComparison {
+    /// Build a comparison from the raw per-iteration samples of each side.
+    pub fn new(
+        baseline_label: impl Into<String>,
+        baseline_samples: &[Duration],
+        candidate_label: impl Into<String>,
+        candidate_samples: &[Duration],
+    ) -> Self {
+        BenchComparison {
+            baseline: BenchResult {
+                label: baseline_label.into(),
+                stats: compute_stats(baseline_samples),
+            },
+            candidate: BenchResult {
+                label: candidate_label.into(),
+                stats: compute_stats(candidate_samples),
+            },
+        }
+    }
+
+    /// How many times faster the candidate is than the baseline, by mean.
+    ///
+    /// Above 1.0 means the candidate won. If the candidate's mean is zero
+    /// the result is infinite (or NaN when both are zero).
+    pub fn speedup(&self) -> f64 {
+        self.baseline.stats.mean.as_secs_f64() / self.candidate.stats.mean.as_secs_f64()
+    }
+
+    /// Stats for the side with the given label.
+    pub fn stats(&self, label: &str) -> Option<&DurationStats> {
+        [&self.baseline, &self.candidate]
+            .into_iter()
+            .find(|side| side.label == label)
+            .map(|side| &side.stats)
+    }
+}
+
+/// Run two labeled blocks `n` times each and compare their timings.
+///
+/// Returns a `BenchComparison` with the first block as the baseline. Each
+/// block's value goes through `std::hint::black_box` so the optimizer
+/// can't throw the work away.
+///
+/// Usage:
+/// ```
+/// use declarative_macros::bench_compare;
+///
+/// let data: Vec<u64> = (0..1_000).collect();
+/// let cmp = bench_compare!(10,
+///     "loop" => { let mut t = 0; for x in &data { t += x; } t },
+///     "iter" => { data.iter().sum::<u64>() },
+/// );
+/// assert_eq!(cmp.baseline.stats.samples, 10);
+/// assert!(cmp.stats("iter").is_some());
+/// ```
+#[macro_export]
+macro_rules! bench_compare {
+    (
+        $iterations:expr,
+        $label_a:expr => $block_a:block,
+        $label_b:expr => $block_b:block $(,)?
+    ) => {{
+        let iterations: usize = $iterations;
+        let mut samples_a = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let start = std::time::Instant::now();
+            let _ = std::hint::black_box($block_a);
+            samples_a.push(start.elapsed());
+        }
+        let mut samples_b = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let start = std::time::Instant::now();
+            let _ = std::hint::black_box($block_b);
+            samples_b.push(start.elapsed());
+        }
+        $crate::solution::BenchComparison::new($label_a, &samples_a, $label_b, &samples_b)
+    }};
+}
+
 // ============================================================================
 // CONFIG DSL MACRO
 // ============================================================================
 
+// `paste` builds identifiers like `with_port` from `port`, which plain
+// `macro_rules!` can't do. Re-exported so `config!` works in other crates.
+#[doc(hidden)]
+pub use paste;
+
+/// Error from a `from_env_strict` constructor generated by `config!`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The variable was set but didn't parse as the field's type.
+    InvalidValue { var: String, value: String },
+    /// The variable was set but isn't valid Unicode.
+    NotUnicode { var: String },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::InvalidValue { var, value } => {
+                write!(f, "{} has invalid value {:?}", var, value)
+            }
+            ConfigError::NotUnicode { var } => write!(f, "{} is not valid unicode", var),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// The environment variable `config!` reads for `field`: `PREFIX_FIELD`.
+pub fn env_var_name(prefix: &str, field: &str) -> String {
+    format!("{}_{}", prefix, field).to_uppercase()
+}
+
 /// A DSL for defining configuration structs with default values.
 ///
-/// Generates a struct with a `Default` impl and a `new()` constructor.
+/// Generates a struct with a `Default` impl, a `new()` constructor, and a
+/// chainable `with_<field>()` setter per field. `from_env(prefix)` starts
+/// from the defaults and overrides any field whose `PREFIX_FIELD` variable
+/// parses with `FromStr`; `from_env_strict(prefix)` reports bad values as a
+/// `ConfigError` instead of ignoring them. An optional trailing
+/// `validate(cfg) { ... }` block becomes a `validate() -> bool` method.
+///
+/// Every field type must implement `FromStr`.
 ///
 /// Usage:
 /// ```
@@ -254,10 +479,14 @@ macro_rules! time_it {
 ///         host: String = "localhost".to_string(),
 ///         port: u16 = 8080,
 ///     }
+///     validate(cfg) { cfg.port > 0 }
 /// }
 ///
 /// let cfg = AppConfig::new();
 /// assert_eq!(cfg.port, 8080);
+///
+/// let cfg = AppConfig::new().with_port(0);
+/// assert!(!cfg.validate());
 /// ```
 #[macro_export]
 macro_rules! config {
@@ -267,6 +496,7 @@ macro_rules! config {
                 $field:ident : $field_type:ty = $default:expr
             ),* $(,)?
         }
+        $(validate($cfg:ident) $validate:block)?
     ) => {
         #[derive(Debug, PartialEq)]
         pub struct $struct_name {
@@ -285,9 +515,65 @@ macro_rules! config {
             }
         }
 
-        impl $struct_name {
-            pub fn new() -> Self {
-                Self::default()
+        $crate::solution::paste::paste! {
+            impl $struct_name {
+                pub fn new() -> Self {
+                    Self::default()
+                }
+
+                $(
+                    pub fn [<with_ $field>](mut self, value: $field_type) -> Self {
+                        self.$field = value;
+                        self
+                    }
+                )*
+
+                /// Defaults, overridden by any `PREFIX_FIELD` variable that parses.
+                pub fn from_env(prefix: &str) -> Self {
+                    let mut cfg = Self::default();
+                    $(
+                        let var = $crate::solution::env_var_name(prefix, stringify!($field));
+                        if let Ok(raw) = ::std::env::var(&var) {
+                            if let Ok(value) = raw.parse::<$field_type>() {
+                                cfg.$field = value;
+                            }
+                        }
+                    )*
+                    cfg
+                }
+
+                /// Like `from_env`, but a set variable that doesn't parse is an error.
+                pub fn from_env_strict(
+                    prefix: &str,
+                ) -> ::std::result::Result<Self, $crate::solution::ConfigError> {
+                    let mut cfg = Self::default();
+                    $(
+                        let var = $crate::solution::env_var_name(prefix, stringify!($field));
+                        match ::std::env::var(&var) {
+                            Ok(raw) => match raw.parse::<$field_type>() {
+                                Ok(value) => cfg.$field = value,
+                                Err(_) => {
+                                    return Err($crate::solution::ConfigError::InvalidValue {
+                                        var,
+                                        value: raw,
+                                    })
+                                }
+                            },
+                            Err(::std::env::VarError::NotPresent) => {}
+                            Err(::std::env::VarError::NotUnicode(_)) => {
+                                return Err($crate::solution::ConfigError::NotUnicode { var })
+                            }
+                        }
+                    )*
+                    Ok(cfg)
+                }
+
+                $(
+                    pub fn validate(&self) -> bool {
+                        let $cfg = self;
+                        $validate
+                    }
+                )?
             }
         }
     };
@@ -4,7 +4,11 @@
 // Macros must be #[macro_export] to be used in integration tests.
 
 use declarative_macros::*;
-use declarative_macros::solution::{calculate_add, calculate_multiply, calculate_power};
+use std::time::Duration;
+use declarative_macros::solution::{
+    calculate_add, calculate_multiply, calculate_power, compute_stats, BenchComparison,
+    ConfigError, DurationStats,
+};
 
 // ============================================================================
 // GREET MACRO TESTS
@@ -333,6 +337,95 @@ fn test_string_enum_clone() {
     assert_eq!(a, b);
 }
 
+string_enum! {
+    Level {
+        Debug => "debug",
+        Info => "info",
+        Warning => "warn",
+    }
+}
+
+string_enum! {
+    Suit {
+        Hearts,
+        Spades => "spades",
+        Clubs,
+        Diamonds => "diamonds"
+    }
+}
+
+#[test]
+fn test_string_enum_display_strings() {
+    assert_eq!(Level::Warning.to_str(), "warn");
+    assert_eq!(Level::from_str("warn"), Some(Level::Warning));
+    assert_eq!(Level::from_str("Warning"), None);
+    assert_eq!(Level::all_variants(), &["debug", "info", "warn"]);
+    assert_eq!(Level::Info.to_string(), "info");
+}
+
+#[test]
+fn test_string_enum_mixed_syntax() {
+    assert_eq!(Suit::Hearts.to_str(), "Hearts");
+    assert_eq!(Suit::Spades.to_str(), "spades");
+    assert_eq!(Suit::Clubs.to_str(), "Clubs");
+    assert_eq!(Suit::Diamonds.to_str(), "diamonds");
+    assert_eq!(
+        Suit::all_variants(),
+        &["Hearts", "spades", "Clubs", "diamonds"]
+    );
+}
+
+#[test]
+fn test_string_enum_ignore_case_round_trip() {
+    for color in Color::iter() {
+        let upper = color.to_str().to_uppercase();
+        assert_eq!(Color::from_str_ignore_case(&upper), Some(color));
+    }
+    for suit in Suit::iter() {
+        let lower = suit.to_str().to_lowercase();
+        assert_eq!(Suit::from_str_ignore_case(&lower), Some(suit));
+    }
+    assert_eq!(Color::from_str_ignore_case("red"), Some(Color::Red));
+    assert_eq!(Color::from_str_ignore_case("purple"), None);
+}
+
+#[test]
+fn test_string_enum_variant_count_and_iter() {
+    const COLORS: usize = Color::variant_count();
+    assert_eq!(COLORS, 4);
+    assert_eq!(Suit::variant_count(), 4);
+    assert_eq!(
+        Color::iter().collect::<Vec<_>>(),
+        vec![Color::Red, Color::Green, Color::Blue, Color::Yellow]
+    );
+    assert_eq!(Level::iter().count(), Level::all_variants().len());
+}
+
+#[test]
+fn test_string_enum_as_hashmap_key() {
+    let mut counts = std::collections::HashMap::new();
+    for suit in [Suit::Hearts, Suit::Clubs, Suit::Hearts] {
+        *counts.entry(suit).or_insert(0) += 1;
+    }
+    assert_eq!(counts[&Suit::Hearts], 2);
+    assert_eq!(counts[&Suit::Clubs], 1);
+    assert!(!counts.contains_key(&Suit::Spades));
+}
+
+#[test]
+fn test_string_enum_keeps_derived_traits() {
+    fn assert_traits<T>()
+    where
+        T: std::fmt::Debug + std::fmt::Display + Clone + Copy + Eq + std::hash::Hash,
+    {
+    }
+
+    assert_traits::<Color>();
+    assert_traits::<Level>();
+    assert_traits::<Suit>();
+    assert_eq!(format!("{:?}", Level::Warning), "Warning");
+}
+
 // ============================================================================
 // TIME_IT MACRO TESTS
 // ============================================================================
@@ -365,6 +458,95 @@ fn test_time_it_with_computation() {
     assert!(duration.as_secs() == 0);
 }
 
+// ============================================================================
+// BENCH_COMPARE MACRO TESTS
+// ============================================================================
+
+fn ms(values: &[u64]) -> Vec<Duration> {
+    values.iter().map(|&v| Duration::from_millis(v)).collect()
+}
+
+#[test]
+fn test_compute_stats_odd_count() {
+    let stats = compute_stats(&ms(&[30, 10, 20]));
+    assert_eq!(stats.samples, 3);
+    assert_eq!(stats.mean, Duration::from_millis(20));
+    assert_eq!(stats.median, Duration::from_millis(20));
+    assert_eq!(stats.min, Duration::from_millis(10));
+    assert_eq!(stats.max, Duration::from_millis(30));
+}
+
+#[test]
+fn test_compute_stats_even_count_and_std_dev() {
+    // Classic example: mean 5, population std-dev exactly 2.
+    let stats = compute_stats(&ms(&[2, 4, 4, 4, 5, 5, 7, 9]));
+    assert_eq!(stats.mean, Duration::from_millis(5));
+    assert_eq!(
+        stats.median,
+        Duration::from_millis(4) + Duration::from_micros(500)
+    );
+    assert_eq!(stats.std_dev, Duration::from_millis(2));
+}
+
+#[test]
+fn test_compute_stats_constant_and_empty() {
+    let stats = compute_stats(&ms(&[7, 7, 7]));
+    assert_eq!(stats.std_dev, Duration::ZERO);
+    assert_eq!(stats.min, stats.max);
+
+    assert_eq!(compute_stats(&[]), DurationStats::default());
+}
+
+#[test]
+fn test_bench_comparison_speedup_and_lookup() {
+    let cmp = BenchComparison::new("slow", &ms(&[40, 40]), "fast", &ms(&[10, 10]));
+    assert!((cmp.speedup() - 4.0).abs() < 1e-9);
+    assert_eq!(cmp.stats("slow").unwrap().mean, Duration::from_millis(40));
+    assert_eq!(cmp.stats("fast").unwrap().mean, Duration::from_millis(10));
+    assert!(cmp.stats("medium").is_none());
+}
+
+#[test]
+fn test_bench_compare_runs_each_block_n_times() {
+    let mut seq_runs = 0;
+    let mut par_runs = 0;
+
+    let cmp = bench_compare!(25,
+        "seq" => { seq_runs += 1; },
+        "par" => { par_runs += 1; }
+    );
+
+    assert_eq!(seq_runs, 25);
+    assert_eq!(par_runs, 25);
+    assert_eq!(cmp.baseline.label, "seq");
+    assert_eq!(cmp.candidate.label, "par");
+    assert_eq!(cmp.baseline.stats.samples, 25);
+    assert_eq!(cmp.candidate.stats.samples, 25);
+}
+
+#[test]
+fn test_bench_compare_captures_outer_variables() {
+    let data: Vec<u64> = (1..=100).collect();
+    let label = String::from("iter");
+    let mut last_sum = 0;
+
+    let cmp = bench_compare!(5,
+        "loop" => {
+            let mut total = 0;
+            for x in &data {
+                total += x;
+            }
+            last_sum = total;
+            total
+        },
+        label.clone() => { data.iter().sum::<u64>() },
+    );
+
+    assert_eq!(last_sum, 5050);
+    assert!(cmp.stats(&label).is_some());
+    assert!(cmp.baseline.stats.min <= cmp.baseline.stats.max);
+}
+
 // ============================================================================
 // CONFIG DSL MACRO TESTS
 // ============================================================================
@@ -421,3 +603,72 @@ fn test_config_single_field() {
     let cfg = MinimalConfig::new();
     assert!(!cfg.debug);
 }
+
+config! {
+    ServerConfig {
+        host: String = "127.0.0.1".to_string(),
+        port: u16 = 8080,
+        workers: usize = 4,
+        verbose: bool = false,
+    }
+    validate(cfg) { cfg.port > 0 && cfg.workers > 0 }
+}
+
+#[test]
+fn test_config_setter_chaining() {
+    let cfg = ServerConfig::new()
+        .with_host("0.0.0.0".to_string())
+        .with_port(3000)
+        .with_verbose(true);
+    assert_eq!(cfg.host, "0.0.0.0");
+    assert_eq!(cfg.port, 3000);
+    assert_eq!(cfg.workers, 4);
+    assert!(cfg.verbose);
+}
+
+#[test]
+fn test_config_from_env_overrides() {
+    std::env::set_var("CFGTEST_OVERRIDE_PORT", "9000");
+    std::env::set_var("CFGTEST_OVERRIDE_VERBOSE", "true");
+    std::env::set_var("CFGTEST_OVERRIDE_WORKERS", "lots");
+
+    let cfg = ServerConfig::from_env("cfgtest_override");
+    assert_eq!(cfg.port, 9000);
+    assert!(cfg.verbose);
+    // Unparseable values fall back to the default.
+    assert_eq!(cfg.workers, 4);
+    assert_eq!(cfg.host, "127.0.0.1");
+}
+
+#[test]
+fn test_config_from_env_without_vars_is_default() {
+    let cfg = ServerConfig::from_env("CFGTEST_NOTHING_SET");
+    assert_eq!(cfg, ServerConfig::default());
+    assert_eq!(
+        ServerConfig::from_env_strict("CFGTEST_NOTHING_SET"),
+        Ok(ServerConfig::default())
+    );
+}
+
+#[test]
+fn test_config_from_env_strict_names_bad_variable() {
+    std::env::set_var("CFGTEST_STRICT_HOST", "example.com");
+    std::env::set_var("CFGTEST_STRICT_PORT", "not-a-port");
+
+    let err = ServerConfig::from_env_strict("CFGTEST_STRICT").unwrap_err();
+    assert_eq!(
+        err,
+        ConfigError::InvalidValue {
+            var: "CFGTEST_STRICT_PORT".to_string(),
+            value: "not-a-port".to_string(),
+        }
+    );
+    assert!(err.to_string().contains("CFGTEST_STRICT_PORT"));
+}
+
+#[test]
+fn test_config_validate_hook() {
+    assert!(ServerConfig::new().validate());
+    assert!(!ServerConfig::new().with_port(0).validate());
+    assert!(!ServerConfig::new().with_workers(0).validate());
+}
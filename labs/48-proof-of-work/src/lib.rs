@@ -1,8 +1,12 @@
 //! # Proof of Work - Student API
 
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use std::time::Duration;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Block {
     pub index: u64,
     pub timestamp: u64,
@@ -40,6 +44,23 @@ impl Block {
         todo!("Brute-force nonce search")
     }
 
+    pub fn mine_with_control(
+        &mut self,
+        _cancel: &AtomicBool,
+        _progress: impl FnMut(MiningProgress),
+    ) -> Result<MiningResult, MiningCancelled> {
+        todo!("Cancellable mining with default progress interval")
+    }
+
+    pub fn mine_with_control_every(
+        &mut self,
+        _interval: u64,
+        _cancel: &AtomicBool,
+        _progress: impl FnMut(MiningProgress),
+    ) -> Result<MiningResult, MiningCancelled> {
+        todo!("Check cancel flag each attempt; report progress every interval; leave block untouched on cancel")
+    }
+
     pub fn is_valid(&self) -> bool {
         todo!("Validate block hash and difficulty")
     }
@@ -54,6 +75,28 @@ pub struct MiningResult {
     pub hash: String,
 }
 
+pub const DEFAULT_PROGRESS_INTERVAL: u64 = 100_000;
+
+#[derive(Debug, Clone)]
+pub struct MiningProgress {
+    pub attempts: u64,
+    pub elapsed: Duration,
+    pub current_best_hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MiningCancelled {
+    pub attempts_so_far: u64,
+}
+
+impl std::fmt::Display for MiningCancelled {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        todo!("Describe cancellation")
+    }
+}
+
+impl std::error::Error for MiningCancelled {}
+
 pub fn sha256_hex(_data: &[u8]) -> String {
     todo!("Compute SHA-256 hash as hex")
 }
@@ -62,6 +105,7 @@ pub fn meets_difficulty(_hash: &str, _difficulty: usize) -> bool {
     todo!("Check leading-zero difficulty")
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Blockchain {
     pub chain: Vec<Block>,
     pub difficulty: usize,
@@ -92,6 +136,157 @@ impl Blockchain {
     pub fn latest_block(&self) -> &Block {
         todo!("Return latest block")
     }
+
+    pub fn save_to_file(&self, _path: impl AsRef<Path>) -> io::Result<()> {
+        todo!("Serialize chain to JSON and write it")
+    }
+
+    pub fn load_from_file(
+        _path: impl AsRef<Path>,
+        _min_difficulty: usize,
+    ) -> Result<Blockchain, LoadError> {
+        todo!("Read, parse, and re-validate a saved chain")
+    }
+
+    pub fn export_block(&self, _index: usize) -> Option<String> {
+        todo!("Serialize one block to JSON")
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    InvalidChain,
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        todo!("Describe load failure")
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainStats {
+    pub total_blocks: usize,
+    pub avg_block_time: Option<f64>,
+    pub median_block_time: Option<f64>,
+    pub avg_nonce: f64,
+    pub min_data_len: usize,
+    pub max_data_len: usize,
+    pub estimated_hashes: u128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockFault {
+    HashMismatch,
+    DifficultyNotMet,
+    BrokenLink,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainError {
+    InvalidRange { from: usize, to: usize, len: usize },
+    InvalidBlock { index: usize, fault: BlockFault },
+}
+
+impl std::fmt::Display for ChainError {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        todo!("Describe chain error")
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+impl Blockchain {
+    pub fn stats(&self) -> ChainStats {
+        // TODO: Gaps between consecutive timestamps give the mean and median
+        // block time; estimated_hashes sums 16^difficulty per block.
+        todo!("Compute chain statistics")
+    }
+
+    pub fn verify_range(&self, _from: usize, _to: usize) -> Result<(), ChainError> {
+        // TODO: Reject from > to or to > len. For each block in from..to check
+        // its hash, then (except genesis) difficulty and link to the previous block.
+        todo!("Validate a slice of the chain")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Share {
+    pub index: u64,
+    pub timestamp: u64,
+    pub data: String,
+    pub previous_hash: String,
+    pub nonce: u64,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareRejection {
+    HashMismatch,
+    BelowShareDifficulty,
+    Duplicate,
+}
+
+impl std::fmt::Display for ShareRejection {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        todo!("Describe share rejection")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareResult {
+    Invalid(ShareRejection),
+    ValidShare(Share),
+    ValidBlock(Share),
+}
+
+#[derive(Debug, Clone)]
+pub struct SharePool {
+    network_difficulty: usize,
+    share_difficulty: usize,
+}
+
+impl SharePool {
+    pub fn new(_network_difficulty: usize, _share_difficulty: usize) -> SharePool {
+        todo!("Create pool; cap share difficulty at network difficulty")
+    }
+
+    pub fn network_difficulty(&self) -> usize {
+        self.network_difficulty
+    }
+
+    pub fn share_difficulty(&self) -> usize {
+        self.share_difficulty
+    }
+
+    pub fn submit_share(&mut self, _miner: &str, _block: &Block, _nonce: u64) -> ShareResult {
+        // TODO: Recompute the hash with `nonce`; reject if it differs from
+        // block.hash, misses the share difficulty, or was seen before.
+        // Otherwise credit the miner and check the network difficulty.
+        todo!("Validate and credit a share")
+    }
+
+    pub fn share_count(&self, _miner: &str) -> u64 {
+        todo!("Accepted shares for one miner")
+    }
+
+    pub fn total_shares(&self) -> u64 {
+        todo!("Accepted shares for all miners")
+    }
+
+    pub fn blocks_found(&self) -> &[Share] {
+        todo!("Shares that met network difficulty")
+    }
+
+    pub fn payouts(&self, _total_reward: u64) -> Vec<(String, u64)> {
+        // TODO: Floor of reward * count / total per miner (use u128), then
+        // hand leftover units to the largest remainders so the sum is exact.
+        todo!("Proportional payout split")
+    }
 }
 
 #[doc(hidden)]
@@ -1,6 +1,12 @@
 //! # Proof of Work - Demo
 
-use proof_of_work::solution::{meets_difficulty, sha256_hex, Block, Blockchain};
+use proof_of_work::solution::{
+    meets_difficulty, sha256_hex, Block, Blockchain, SharePool, ShareResult,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 fn main() {
     println!("=== Proof of Work Demo ===");
@@ -17,4 +23,68 @@ fn main() {
     chain.add_block("Alice -> Bob: 10".to_string());
     chain.add_block("Bob -> Carol: 3".to_string());
     println!("chain len={} valid={}", chain.len(), chain.is_valid());
+    println!("stats: {:?}", chain.stats());
+    match chain.verify_range(1, chain.len()) {
+        Ok(()) => println!("blocks 1.. verified"),
+        Err(err) => println!("spot check failed: {}", err),
+    }
+
+    let path = std::env::temp_dir().join("proof_of_work_demo_chain.json");
+    match chain.save_to_file(&path) {
+        Ok(()) => match Blockchain::load_from_file(&path, 2) {
+            Ok(loaded) => println!("reloaded {} blocks from {}", loaded.len(), path.display()),
+            Err(err) => println!("reload failed: {}", err),
+        },
+        Err(err) => println!("save failed: {}", err),
+    }
+    let _ = std::fs::remove_file(&path);
+    if let Some(json) = chain.export_block(1) {
+        println!("block 1 as JSON:\n{}", json);
+    }
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancel);
+    let miner = thread::spawn(move || {
+        let mut hard = Block::new(2, "too hard".to_string(), "prev_hash".to_string(), 12);
+        let outcome = hard.mine_with_control_every(50_000, &flag, |p| {
+            println!(
+                "  {} attempts in {:?}, best {}",
+                p.attempts,
+                p.elapsed,
+                &p.current_best_hash[..12]
+            );
+        });
+        (hard.is_valid(), outcome)
+    });
+    thread::sleep(Duration::from_millis(200));
+    cancel.store(true, Ordering::Relaxed);
+    match miner.join().expect("miner thread panicked") {
+        (valid, Err(err)) => println!("{} (block valid? {})", err, valid),
+        (valid, Ok(result)) => println!(
+            "mined anyway after {} attempts (valid? {})",
+            result.attempts, valid
+        ),
+    }
+
+    // Pool mining: shares at a lower difficulty earn a cut of the reward.
+    let mut pool = SharePool::new(3, 1);
+    let template = Block::new(3, "pool block".to_string(), "prev_hash".to_string(), 3);
+    for nonce in 0..5_000u64 {
+        let miner = if nonce % 3 == 0 { "alice" } else { "bob" };
+        let mut claimed = template.clone();
+        claimed.nonce = nonce;
+        claimed.hash = claimed.calculate_hash();
+        if let ShareResult::ValidBlock(share) = pool.submit_share(miner, &claimed, nonce) {
+            println!(
+                "{} found a block at nonce {}: {}",
+                miner, share.nonce, share.hash
+            );
+        }
+    }
+    println!(
+        "pool shares: alice {}, bob {}; payouts of 625: {:?}",
+        pool.share_count("alice"),
+        pool.share_count("bob"),
+        pool.payouts(625)
+    );
 }
@@ -10,8 +10,16 @@
 // - Difficulty scaling (leading zeros)
 // - Block validation
 // - Mining result reporting
+// - Chain statistics and partial (range) verification
 
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 // ============================================================================
@@ -19,7 +27,7 @@ use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 // ============================================================================
 
 /// A block in the blockchain with proof-of-work mining support.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Block {
     pub index: u64,
     pub timestamp: u64,
@@ -96,16 +104,72 @@ impl Block {
     ///
     /// Returns a `MiningResult` with statistics about the mining process.
     pub fn mine(&mut self) -> MiningResult {
+        // A flag nobody else can see is never set, so this can't be cancelled.
+        let never = AtomicBool::new(false);
+        match self.mine_with_control(&never, |_| {}) {
+            Ok(result) => result,
+            Err(_) => unreachable!("mining without a shared cancel flag cannot be cancelled"),
+        }
+    }
+
+    /// Mine like `mine`, but stop early if `cancel` becomes true and call
+    /// `progress` every `DEFAULT_PROGRESS_INTERVAL` attempts.
+    ///
+    /// On cancellation the block is left exactly as it was.
+    pub fn mine_with_control(
+        &mut self,
+        cancel: &AtomicBool,
+        progress: impl FnMut(MiningProgress),
+    ) -> Result<MiningResult, MiningCancelled> {
+        self.mine_with_control_every(DEFAULT_PROGRESS_INTERVAL, cancel, progress)
+    }
+
+    /// `mine_with_control` with a custom progress interval (0 means never).
+    ///
+    /// The search works on a local nonce and only writes `nonce` and `hash`
+    /// back into the block once a valid hash is found. The cancel flag is
+    /// checked before every attempt: an atomic load is far cheaper than a
+    /// SHA-256 hash, so cancellation is effectively immediate.
+    pub fn mine_with_control_every(
+        &mut self,
+        interval: u64,
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(MiningProgress),
+    ) -> Result<MiningResult, MiningCancelled> {
         let start = Instant::now();
         let target = "0".repeat(self.difficulty);
+        let mut candidate = self.clone();
         let mut attempts = 0u64;
+        let mut best_hash: Option<String> = None;
 
         loop {
-            self.nonce += 1;
-            self.hash = self.calculate_hash();
+            // Relaxed is enough: we only need to see the flag eventually,
+            // and it doesn't guard any other data.
+            if cancel.load(Ordering::Relaxed) {
+                return Err(MiningCancelled {
+                    attempts_so_far: attempts,
+                });
+            }
+
+            candidate.nonce += 1;
+            let hash = candidate.calculate_hash();
             attempts += 1;
 
-            if self.hash.starts_with(&target) {
+            // Hex strings of equal length compare like the numbers they encode.
+            if best_hash.as_ref().map_or(true, |best| hash < *best) {
+                best_hash = Some(hash.clone());
+            }
+            if interval > 0 && attempts % interval == 0 {
+                progress(MiningProgress {
+                    attempts,
+                    elapsed: start.elapsed(),
+                    current_best_hash: best_hash.clone().unwrap_or_default(),
+                });
+            }
+
+            if hash.starts_with(&target) {
+                self.nonce = candidate.nonce;
+                self.hash = hash;
                 break;
             }
         }
@@ -117,13 +181,13 @@ impl Block {
             0.0
         };
 
-        MiningResult {
+        Ok(MiningResult {
             nonce: self.nonce,
             attempts,
             duration,
             hash_rate,
             hash: self.hash.clone(),
-        }
+        })
     }
 
     /// Validate this block's hash.
@@ -155,6 +219,36 @@ pub struct MiningResult {
     pub hash: String,
 }
 
+/// How often `mine_with_control` reports progress, in attempts.
+pub const DEFAULT_PROGRESS_INTERVAL: u64 = 100_000;
+
+/// A snapshot passed to the progress callback while mining.
+#[derive(Debug, Clone)]
+pub struct MiningProgress {
+    pub attempts: u64,
+    pub elapsed: Duration,
+    /// The numerically smallest hash seen so far (closest to the target).
+    pub current_best_hash: String,
+}
+
+/// Returned when mining is stopped through the cancel flag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MiningCancelled {
+    pub attempts_so_far: u64,
+}
+
+impl fmt::Display for MiningCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "mining cancelled after {} attempts",
+            self.attempts_so_far
+        )
+    }
+}
+
+impl std::error::Error for MiningCancelled {}
+
 // ============================================================================
 // HASH UTILITIES
 // ============================================================================
@@ -178,6 +272,7 @@ pub fn meets_difficulty(hash: &str, difficulty: usize) -> bool {
 // ============================================================================
 
 /// A simple blockchain with difficulty adjustment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Blockchain {
     pub chain: Vec<Block>,
     pub difficulty: usize,
@@ -247,6 +342,423 @@ impl Blockchain {
     pub fn latest_block(&self) -> &Block {
         self.chain.last().expect("Chain is empty")
     }
+
+    /// Write the whole chain to `path` as pretty-printed JSON.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+    }
+
+    /// Read a chain saved by `save_to_file` and re-validate it.
+    ///
+    /// The file is untrusted input: anyone could have edited a block's data
+    /// without re-mining it, so every hash is recomputed before the chain is
+    /// handed back. A block only has to meet its *own* difficulty, and that
+    /// difficulty is read from the same file, so the caller supplies the
+    /// floor: every mined block (all but genesis) must claim at least
+    /// `min_difficulty`, otherwise `"difficulty": 0` makes forging free.
+    /// Indices must also count up from 0 without gaps.
+    pub fn load_from_file(
+        path: impl AsRef<Path>,
+        min_difficulty: usize,
+    ) -> Result<Blockchain, LoadError> {
+        let json = fs::read_to_string(path)?;
+        let chain: Blockchain = serde_json::from_str(&json)?;
+
+        // `is_valid` skips genesis, so check its hash here as well.
+        let genesis_ok = chain
+            .chain
+            .first()
+            .is_some_and(|genesis| genesis.hash == genesis.calculate_hash());
+        let blocks_ok = chain.chain.iter().enumerate().all(|(i, block)| {
+            block.index == i as u64 && (i == 0 || block.difficulty >= min_difficulty)
+        });
+        if !genesis_ok || !blocks_ok || !chain.is_valid() {
+            return Err(LoadError::InvalidChain);
+        }
+        Ok(chain)
+    }
+
+    /// Pretty-printed JSON for the block at `index`, if there is one.
+    pub fn export_block(&self, index: usize) -> Option<String> {
+        let block = self.chain.get(index)?;
+        serde_json::to_string_pretty(block).ok()
+    }
+}
+
+/// Errors from `Blockchain::load_from_file`.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file couldn't be read.
+    Io(io::Error),
+    /// The file isn't a JSON blockchain (malformed or truncated).
+    Json(serde_json::Error),
+    /// The file parsed, but its hashes or links don't check out.
+    InvalidChain,
+}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> Self {
+        LoadError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for LoadError {
+    fn from(err: serde_json::Error) -> Self {
+        LoadError::Json(err)
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "I/O error: {}", err),
+            LoadError::Json(err) => write!(f, "invalid blockchain file: {}", err),
+            LoadError::InvalidChain => write!(f, "blockchain failed validation"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(err) => Some(err),
+            LoadError::Json(err) => Some(err),
+            LoadError::InvalidChain => None,
+        }
+    }
+}
+
+// ============================================================================
+// CHAIN ANALYTICS AND SPOT CHECKS
+// ============================================================================
+// `is_valid` re-hashes every block, which is fine for a demo chain and slow
+// for a long one. `verify_range` checks just a window -- each block's hash,
+// its difficulty, and its link to the block before it (even when that block
+// is outside the window) -- so a large chain can be spot-checked piece by
+// piece. Failures say which block and why instead of a bare `false`.
+//
+// `stats` summarizes a chain without mining anything. The work estimate uses
+// the fact that a hex hash meets difficulty `d` with probability 1/16^d, so
+// a block at difficulty `d` took about 16^d attempts on average.
+
+/// Summary numbers for a chain, from `Blockchain::stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainStats {
+    pub total_blocks: usize,
+    /// Mean seconds between consecutive timestamps; `None` below two blocks.
+    pub avg_block_time: Option<f64>,
+    /// Median seconds between consecutive timestamps; `None` below two blocks.
+    pub median_block_time: Option<f64>,
+    /// Mean nonce over all blocks, genesis included.
+    pub avg_nonce: f64,
+    pub min_data_len: usize,
+    pub max_data_len: usize,
+    /// Sum of 16^difficulty over all blocks (saturating).
+    pub estimated_hashes: u128,
+}
+
+/// Why a block failed `verify_range`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockFault {
+    /// The stored hash is not the hash of the block's contents.
+    HashMismatch,
+    /// The hash has fewer leading zeros than the block's difficulty.
+    DifficultyNotMet,
+    /// `previous_hash` does not match the preceding block's hash.
+    BrokenLink,
+}
+
+impl fmt::Display for BlockFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockFault::HashMismatch => write!(f, "stored hash does not match contents"),
+            BlockFault::DifficultyNotMet => write!(f, "hash does not meet difficulty"),
+            BlockFault::BrokenLink => write!(f, "previous_hash does not match prior block"),
+        }
+    }
+}
+
+/// Errors from `Blockchain::verify_range`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainError {
+    /// `from..to` is reversed or runs past the end of the chain.
+    InvalidRange { from: usize, to: usize, len: usize },
+    /// The block at `index` failed a check.
+    InvalidBlock { index: usize, fault: BlockFault },
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainError::InvalidRange { from, to, len } => write!(
+                f,
+                "invalid range {}..{} for a chain of {} blocks",
+                from, to, len
+            ),
+            ChainError::InvalidBlock { index, fault } => {
+                write!(f, "block {} is invalid: {}", index, fault)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+impl Blockchain {
+    /// Timing, nonce, size, and work statistics for the whole chain.
+    pub fn stats(&self) -> ChainStats {
+        let blocks = &self.chain;
+        let mut gaps: Vec<f64> = blocks
+            .windows(2)
+            .map(|pair| pair[1].timestamp as f64 - pair[0].timestamp as f64)
+            .collect();
+        gaps.sort_by(|a, b| a.total_cmp(b));
+
+        let avg_block_time = if gaps.is_empty() {
+            None
+        } else {
+            Some(gaps.iter().sum::<f64>() / gaps.len() as f64)
+        };
+        let median_block_time = match gaps.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(gaps[n / 2]),
+            n => Some((gaps[n / 2 - 1] + gaps[n / 2]) / 2.0),
+        };
+
+        let avg_nonce = if blocks.is_empty() {
+            0.0
+        } else {
+            blocks.iter().map(|b| b.nonce as f64).sum::<f64>() / blocks.len() as f64
+        };
+
+        let estimated_hashes = blocks.iter().fold(0u128, |total, b| {
+            let work = u32::try_from(b.difficulty)
+                .ok()
+                .and_then(|d| 16u128.checked_pow(d))
+                .unwrap_or(u128::MAX);
+            total.saturating_add(work)
+        });
+
+        ChainStats {
+            total_blocks: blocks.len(),
+            avg_block_time,
+            median_block_time,
+            avg_nonce,
+            min_data_len: blocks.iter().map(|b| b.data.len()).min().unwrap_or(0),
+            max_data_len: blocks.iter().map(|b| b.data.len()).max().unwrap_or(0),
+            estimated_hashes,
+        }
+    }
+
+    /// Validate blocks `from..to` (half-open, like a slice range).
+    ///
+    /// Genesis (index 0) is only checked against its own contents: it has
+    /// no predecessor and is not mined. Every other block must hash
+    /// correctly, meet its difficulty, and link to the block before it.
+    pub fn verify_range(&self, from: usize, to: usize) -> Result<(), ChainError> {
+        let len = self.chain.len();
+        if from > to || to > len {
+            return Err(ChainError::InvalidRange { from, to, len });
+        }
+
+        for index in from..to {
+            let block = &self.chain[index];
+            let fault = if block.hash != block.calculate_hash() {
+                Some(BlockFault::HashMismatch)
+            } else if index == 0 {
+                None
+            } else if !meets_difficulty(&block.hash, block.difficulty) {
+                Some(BlockFault::DifficultyNotMet)
+            } else if block.previous_hash != self.chain[index - 1].hash {
+                Some(BlockFault::BrokenLink)
+            } else {
+                None
+            };
+            if let Some(fault) = fault {
+                return Err(ChainError::InvalidBlock { index, fault });
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// MINING POOL SHARES
+// ============================================================================
+// A lone miner at network difficulty might go months between blocks. A pool
+// smooths that out: everyone mines the same kind of block, and the pool also
+// accepts "shares" - hashes that meet a much LOWER share difficulty. Shares
+// are useless to the network, but they are cheap proof of how much hashing
+// each miner did, so the reward for a found block can be split by share count.
+//
+//   share difficulty 2:  00a41f...  -> ValidShare (credit, no block)
+//   network difficulty 4: 0000c9... -> ValidBlock (credit, and a block!)
+//
+// The pool never trusts the miner's hash: it rebuilds the block with the
+// submitted nonce and hashes it again. A lying miner gets `Invalid`, and a
+// hash it already submitted can't be counted twice.
+//
+// Payouts divide an integer reward, so the shares rarely split evenly.
+// Everyone gets their floor first; the leftover satoshis go one each to the
+// largest fractional remainders. The total paid is always exactly the reward.
+
+/// The header fields of a block plus the nonce and hash a miner found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Share {
+    pub index: u64,
+    pub timestamp: u64,
+    pub data: String,
+    pub previous_hash: String,
+    pub nonce: u64,
+    pub hash: String,
+}
+
+/// Why `SharePool::submit_share` rejected a submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareRejection {
+    /// The claimed hash is not the hash of the block with that nonce.
+    HashMismatch,
+    /// The hash is genuine but doesn't meet the share difficulty.
+    BelowShareDifficulty,
+    /// This exact hash was already accepted.
+    Duplicate,
+}
+
+impl fmt::Display for ShareRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareRejection::HashMismatch => write!(f, "hash does not match recomputed hash"),
+            ShareRejection::BelowShareDifficulty => {
+                write!(f, "hash does not meet share difficulty")
+            }
+            ShareRejection::Duplicate => write!(f, "share already submitted"),
+        }
+    }
+}
+
+/// Outcome of one `SharePool::submit_share` call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareResult {
+    Invalid(ShareRejection),
+    /// Meets the share difficulty only; counts toward the miner's credit.
+    ValidShare(Share),
+    /// Meets the network difficulty: a real block. Also counts as a share.
+    ValidBlock(Share),
+}
+
+/// Collects shares from miners and splits block rewards by share count.
+#[derive(Debug, Clone)]
+pub struct SharePool {
+    network_difficulty: usize,
+    share_difficulty: usize,
+    /// Accepted shares per miner. A BTreeMap keeps payouts in name order.
+    credits: BTreeMap<String, u64>,
+    seen: HashSet<String>,
+    blocks: Vec<Share>,
+}
+
+impl SharePool {
+    /// Create a pool. A share difficulty above the network difficulty would
+    /// reject blocks, so it is capped at the network difficulty.
+    pub fn new(network_difficulty: usize, share_difficulty: usize) -> SharePool {
+        SharePool {
+            network_difficulty,
+            share_difficulty: share_difficulty.min(network_difficulty),
+            credits: BTreeMap::new(),
+            seen: HashSet::new(),
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn network_difficulty(&self) -> usize {
+        self.network_difficulty
+    }
+
+    pub fn share_difficulty(&self) -> usize {
+        self.share_difficulty
+    }
+
+    /// Check `block` mined with `nonce`, where `block.hash` is the hash the
+    /// miner claims. The block's own `nonce` field is ignored.
+    pub fn submit_share(&mut self, miner: &str, block: &Block, nonce: u64) -> ShareResult {
+        let mut candidate = block.clone();
+        candidate.nonce = nonce;
+        let hash = candidate.calculate_hash();
+
+        if block.hash != hash {
+            return ShareResult::Invalid(ShareRejection::HashMismatch);
+        }
+        if !meets_difficulty(&hash, self.share_difficulty) {
+            return ShareResult::Invalid(ShareRejection::BelowShareDifficulty);
+        }
+        if !self.seen.insert(hash.clone()) {
+            return ShareResult::Invalid(ShareRejection::Duplicate);
+        }
+
+        *self.credits.entry(miner.to_string()).or_insert(0) += 1;
+        let share = Share {
+            index: candidate.index,
+            timestamp: candidate.timestamp,
+            data: candidate.data,
+            previous_hash: candidate.previous_hash,
+            nonce,
+            hash,
+        };
+        if meets_difficulty(&share.hash, self.network_difficulty) {
+            self.blocks.push(share.clone());
+            ShareResult::ValidBlock(share)
+        } else {
+            ShareResult::ValidShare(share)
+        }
+    }
+
+    /// Accepted shares (blocks included) from `miner`.
+    pub fn share_count(&self, miner: &str) -> u64 {
+        self.credits.get(miner).copied().unwrap_or(0)
+    }
+
+    pub fn total_shares(&self) -> u64 {
+        self.credits.values().sum()
+    }
+
+    /// Every submission that met the network difficulty, in order.
+    pub fn blocks_found(&self) -> &[Share] {
+        &self.blocks
+    }
+
+    /// Split `total_reward` in proportion to share counts, sorted by miner.
+    ///
+    /// Amounts always add up to exactly `total_reward`. Leftover units from
+    /// rounding down go to the largest remainders (ties: earlier name). With
+    /// no shares there is nobody to pay, so the result is empty.
+    pub fn payouts(&self, total_reward: u64) -> Vec<(String, u64)> {
+        let total = u128::from(self.total_shares());
+        if total == 0 {
+            return Vec::new();
+        }
+
+        // reward * count fits in u128 for any u64 inputs.
+        let mut payouts = Vec::with_capacity(self.credits.len());
+        let mut remainders = Vec::with_capacity(self.credits.len());
+        let mut paid: u128 = 0;
+        for (i, (miner, &count)) in self.credits.iter().enumerate() {
+            let scaled = u128::from(total_reward) * u128::from(count);
+            let amount = scaled / total;
+            paid += amount;
+            payouts.push((miner.clone(), amount as u64));
+            remainders.push((scaled % total, i));
+        }
+
+        // Fewer leftover units than miners: each remainder is < total, so the
+        // floors miss the reward by less than one unit per miner.
+        let leftover = (u128::from(total_reward) - paid) as usize;
+        remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for &(_, i) in remainders.iter().take(leftover) {
+            payouts[i].1 += 1;
+        }
+        payouts
+    }
 }
 
 // ============================================================================
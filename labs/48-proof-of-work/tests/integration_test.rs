@@ -4,6 +4,10 @@
 // difficulty checking, and blockchain integrity.
 
 use proof_of_work::solution::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 // ============================================================================
 // SHA-256 HASH UTILITY TESTS
@@ -442,3 +446,546 @@ fn test_difficulty_2_requires_more_attempts_on_average() {
     // Use a very loose bound to avoid flaky tests
     assert!(total_d2 > total_d1);
 }
+
+// ============================================================================
+// CANCELLABLE MINING TESTS
+// ============================================================================
+
+#[test]
+fn test_mine_with_control_cancels_promptly_and_leaves_block_unmined() {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancel);
+
+    // 64 leading zeros will never be found.
+    let handle = thread::spawn(move || {
+        let mut block = Block::with_timestamp(1, "hard".to_string(), "0".repeat(64), 64, 100);
+        let original = block.clone();
+        let result = block.mine_with_control(&flag, |_| {});
+        (block, original, result)
+    });
+
+    thread::sleep(Duration::from_millis(50));
+    let cancelled_at = Instant::now();
+    cancel.store(true, Ordering::Relaxed);
+    let (block, original, result) = handle.join().unwrap();
+
+    assert!(cancelled_at.elapsed() < Duration::from_secs(2));
+    let err = result.unwrap_err();
+    assert!(err.attempts_so_far > 0);
+    assert!(!block.is_valid());
+    assert_eq!(block.nonce, original.nonce);
+    assert_eq!(block.hash, original.hash);
+}
+
+#[test]
+fn test_mine_with_control_already_cancelled_makes_no_attempts() {
+    let cancel = AtomicBool::new(true);
+    let mut block = Block::with_timestamp(1, "x".to_string(), "0".repeat(64), 1, 100);
+
+    let err = block.mine_with_control(&cancel, |_| {}).unwrap_err();
+    assert_eq!(err, MiningCancelled { attempts_so_far: 0 });
+    assert_eq!(err.to_string(), "mining cancelled after 0 attempts");
+}
+
+#[test]
+fn test_mine_with_control_reports_monotonic_progress() {
+    let cancel = AtomicBool::new(false);
+    let mut block = Block::with_timestamp(1, "easy".to_string(), "0".repeat(64), 1, 100);
+    let mut reports = Vec::new();
+
+    let result = block
+        .mine_with_control_every(1, &cancel, |p| reports.push(p))
+        .unwrap();
+
+    assert!(!reports.is_empty());
+    assert!(reports.windows(2).all(|w| w[0].attempts < w[1].attempts));
+    assert_eq!(reports.last().unwrap().attempts, result.attempts);
+    assert!(reports.last().unwrap().current_best_hash.starts_with('0'));
+    assert!(block.is_valid());
+}
+
+#[test]
+fn test_mine_with_control_matches_mine() {
+    let cancel = AtomicBool::new(false);
+    let mut a = Block::with_timestamp(1, "same".to_string(), "0".repeat(64), 2, 100);
+    let mut b = a.clone();
+
+    let plain = a.mine();
+    let controlled = b.mine_with_control(&cancel, |_| {}).unwrap();
+
+    assert_eq!(plain.nonce, controlled.nonce);
+    assert_eq!(a.hash, b.hash);
+}
+
+#[test]
+fn test_default_progress_interval_skips_short_searches() {
+    let cancel = AtomicBool::new(false);
+    let mut block = Block::with_timestamp(1, "quick".to_string(), "0".repeat(64), 1, 100);
+    let mut calls = 0;
+
+    block.mine_with_control(&cancel, |_| calls += 1).unwrap();
+
+    assert_eq!(DEFAULT_PROGRESS_INTERVAL, 100_000);
+    assert_eq!(calls, 0);
+}
+
+// ============================================================================
+// PERSISTENCE TESTS
+// ============================================================================
+
+fn small_chain() -> Blockchain {
+    let mut bc = Blockchain::new(1, 1);
+    bc.add_block("Alice -> Bob: 5".to_string());
+    bc.add_block("Bob -> Carol: 2".to_string());
+    bc
+}
+
+#[test]
+fn test_save_and_load_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.json");
+    let bc = small_chain();
+
+    bc.save_to_file(&path).unwrap();
+    let loaded = Blockchain::load_from_file(&path, 1).unwrap();
+
+    assert_eq!(loaded.len(), 3);
+    assert!(loaded.is_valid());
+    assert_eq!(loaded.difficulty, bc.difficulty);
+    assert_eq!(loaded.target_block_time, bc.target_block_time);
+    for (a, b) in loaded.chain.iter().zip(&bc.chain) {
+        assert_eq!(a.hash, b.hash);
+        assert_eq!(a.nonce, b.nonce);
+        assert_eq!(a.data, b.data);
+    }
+}
+
+#[test]
+fn test_load_rejects_tampered_block_data() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.json");
+    small_chain().save_to_file(&path).unwrap();
+
+    let json = std::fs::read_to_string(&path).unwrap();
+    let tampered = json.replace("Alice -> Bob: 5", "Alice -> Bob: 500");
+    assert_ne!(json, tampered);
+    std::fs::write(&path, tampered).unwrap();
+
+    let err = Blockchain::load_from_file(&path, 1).unwrap_err();
+    assert!(matches!(err, LoadError::InvalidChain));
+}
+
+#[test]
+fn test_load_rejects_tampered_genesis() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.json");
+    small_chain().save_to_file(&path).unwrap();
+
+    let json = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, json.replace("Genesis Block", "Genesis Blob")).unwrap();
+
+    let err = Blockchain::load_from_file(&path, 1).unwrap_err();
+    assert!(matches!(err, LoadError::InvalidChain));
+}
+
+#[test]
+fn test_load_rejects_blocks_below_min_difficulty() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.json");
+
+    // A chain "mined" at difficulty 0 throughout is self-consistent but
+    // took no work at all
+    let mut forged = Blockchain::new(0, 1);
+    forged.add_block("Alice -> Mallory: 1000".to_string());
+    assert!(forged.is_valid());
+    forged.save_to_file(&path).unwrap();
+
+    let err = Blockchain::load_from_file(&path, 1).unwrap_err();
+    assert!(matches!(err, LoadError::InvalidChain));
+    assert!(Blockchain::load_from_file(&path, 0).is_ok());
+}
+
+#[test]
+fn test_load_rejects_chain_relabelled_to_lower_difficulty() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.json");
+    let mut bc = Blockchain::new(2, 1);
+    bc.add_block("Alice -> Bob: 5".to_string());
+
+    // Rewrite the block at difficulty 0, as an editor of the file could
+    bc.difficulty = 0;
+    for block in &mut bc.chain {
+        block.difficulty = 0;
+    }
+    bc.chain[1].data = "Alice -> Mallory: 5000".to_string();
+    bc.chain[1].hash = bc.chain[1].calculate_hash();
+    assert!(bc.is_valid());
+    bc.save_to_file(&path).unwrap();
+
+    let err = Blockchain::load_from_file(&path, 2).unwrap_err();
+    assert!(matches!(err, LoadError::InvalidChain));
+}
+
+#[test]
+fn test_load_rejects_non_consecutive_indices() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.json");
+
+    let mut bc = Blockchain::new(1, 1);
+    let mut skipped = Block::new(
+        5,
+        "skip ahead".to_string(),
+        bc.latest_block().hash.clone(),
+        1,
+    );
+    skipped.mine();
+    bc.chain.push(skipped);
+    assert!(bc.is_valid());
+    bc.save_to_file(&path).unwrap();
+
+    let err = Blockchain::load_from_file(&path, 1).unwrap_err();
+    assert!(matches!(err, LoadError::InvalidChain));
+}
+
+#[test]
+fn test_load_truncated_file_is_json_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.json");
+    small_chain().save_to_file(&path).unwrap();
+
+    let json = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, &json[..json.len() / 2]).unwrap();
+
+    let err = Blockchain::load_from_file(&path, 1).unwrap_err();
+    assert!(matches!(err, LoadError::Json(_)));
+}
+
+#[test]
+fn test_load_missing_file_is_io_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = Blockchain::load_from_file(dir.path().join("missing.json"), 1).unwrap_err();
+    assert!(matches!(err, LoadError::Io(_)));
+}
+
+#[test]
+fn test_export_block() {
+    let bc = small_chain();
+
+    let json = bc.export_block(1).unwrap();
+    let block: Block = serde_json::from_str(&json).unwrap();
+    assert_eq!(block.hash, bc.chain[1].hash);
+    assert!(block.is_valid());
+
+    assert!(bc.export_block(3).is_none());
+}
+
+// ============================================================================
+// CHAIN STATS AND RANGE VERIFICATION TESTS
+// ============================================================================
+
+/// A mined difficulty-1 chain with the given block timestamps, built by hand
+/// so the timing is known.
+fn chain_with_timestamps(timestamps: &[u64]) -> Blockchain {
+    let mut genesis =
+        Block::with_timestamp(0, "Genesis".to_string(), "0".to_string(), 1, timestamps[0]);
+    genesis.hash = genesis.calculate_hash();
+    let mut chain = vec![genesis];
+    for (i, &ts) in timestamps.iter().enumerate().skip(1) {
+        let data = "x".repeat(i * 3);
+        let prev = chain.last().unwrap().hash.clone();
+        let mut block = Block::with_timestamp(i as u64, data, prev, 1, ts);
+        block.mine();
+        chain.push(block);
+    }
+    Blockchain {
+        chain,
+        difficulty: 1,
+        target_block_time: 10,
+    }
+}
+
+#[test]
+fn test_stats_known_timestamps() {
+    let bc = chain_with_timestamps(&[1000, 1010, 1030, 1100]);
+    let stats = bc.stats();
+
+    assert_eq!(stats.total_blocks, 4);
+    // Gaps 10, 20, 70
+    assert!((stats.avg_block_time.unwrap() - 100.0 / 3.0).abs() < 1e-9);
+    assert_eq!(stats.median_block_time, Some(20.0));
+    // "Genesis" is 7 bytes; blocks 1..=3 carry 3, 6 and 9
+    assert_eq!(stats.min_data_len, 3);
+    assert_eq!(stats.max_data_len, 9);
+    assert_eq!(stats.estimated_hashes, 4 * 16);
+
+    let nonce_sum: u64 = bc.chain.iter().map(|b| b.nonce).sum();
+    assert!((stats.avg_nonce - nonce_sum as f64 / 4.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_stats_even_gap_count_and_single_block() {
+    let stats = chain_with_timestamps(&[0, 5, 6, 20, 21]).stats();
+    // Gaps sorted: 1, 1, 5, 14
+    assert_eq!(stats.median_block_time, Some(3.0));
+    assert_eq!(stats.avg_block_time, Some(21.0 / 4.0));
+
+    let single = Blockchain::new(3, 10).stats();
+    assert_eq!(single.total_blocks, 1);
+    assert_eq!(single.avg_block_time, None);
+    assert_eq!(single.median_block_time, None);
+    assert_eq!(single.estimated_hashes, 16u128.pow(3));
+}
+
+#[test]
+fn test_verify_range_catches_tampered_middle_block() {
+    let mut bc = chain_with_timestamps(&[0, 10, 20, 30, 40, 50]);
+    assert_eq!(bc.verify_range(0, bc.len()), Ok(()));
+
+    bc.chain[3].data = "rewritten history".to_string();
+    assert_eq!(
+        bc.verify_range(2, 5),
+        Err(ChainError::InvalidBlock {
+            index: 3,
+            fault: BlockFault::HashMismatch
+        })
+    );
+    // Windows that avoid block 3 still pass
+    assert_eq!(bc.verify_range(0, 3), Ok(()));
+    assert_eq!(bc.verify_range(5, 6), Ok(()));
+
+    // Re-hashing without re-mining is caught by the difficulty check ...
+    bc.chain[3].hash = bc.chain[3].calculate_hash();
+    if !meets_difficulty(&bc.chain[3].hash, 1) {
+        assert_eq!(
+            bc.verify_range(3, 4),
+            Err(ChainError::InvalidBlock {
+                index: 3,
+                fault: BlockFault::DifficultyNotMet
+            })
+        );
+    }
+    // ... and re-mining breaks the next block's link
+    bc.chain[3].mine();
+    assert_eq!(bc.verify_range(3, 4), Ok(()));
+    assert_eq!(
+        bc.verify_range(3, 6),
+        Err(ChainError::InvalidBlock {
+            index: 4,
+            fault: BlockFault::BrokenLink
+        })
+    );
+}
+
+#[test]
+fn test_verify_range_out_of_bounds_is_an_error() {
+    let bc = chain_with_timestamps(&[0, 10, 20]);
+    assert_eq!(
+        bc.verify_range(1, 4),
+        Err(ChainError::InvalidRange {
+            from: 1,
+            to: 4,
+            len: 3
+        })
+    );
+    let reversed = bc.verify_range(2, 1).unwrap_err();
+    assert!(matches!(reversed, ChainError::InvalidRange { .. }));
+    assert_eq!(
+        reversed.to_string(),
+        "invalid range 2..1 for a chain of 3 blocks"
+    );
+    // An empty range at the end is fine
+    assert_eq!(bc.verify_range(3, 3), Ok(()));
+}
+
+// ============================================================================
+// MINING POOL SHARE TESTS
+// ============================================================================
+
+fn pool_template() -> Block {
+    Block::with_timestamp(
+        1,
+        "pool work".to_string(),
+        "prev".to_string(),
+        2,
+        1_700_000_000,
+    )
+}
+
+/// Hash of `template` with `nonce`, as an honest miner would claim it.
+fn claim(template: &Block, nonce: u64) -> Block {
+    let mut block = template.clone();
+    block.nonce = nonce;
+    block.hash = block.calculate_hash();
+    block
+}
+
+/// First nonce whose hash has exactly `zeros` leading hex zeros.
+fn nonce_with_zeros(template: &Block, zeros: usize) -> u64 {
+    (0..)
+        .find(|&nonce| {
+            let hash = claim(template, nonce).hash;
+            meets_difficulty(&hash, zeros) && !meets_difficulty(&hash, zeros + 1)
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_share_vs_block_classification() {
+    let template = pool_template();
+    let mut pool = SharePool::new(2, 1);
+
+    let weak = nonce_with_zeros(&template, 0);
+    assert_eq!(
+        pool.submit_share("alice", &claim(&template, weak), weak),
+        ShareResult::Invalid(ShareRejection::BelowShareDifficulty)
+    );
+
+    let share = nonce_with_zeros(&template, 1);
+    match pool.submit_share("alice", &claim(&template, share), share) {
+        ShareResult::ValidShare(s) => {
+            assert_eq!(s.nonce, share);
+            assert_eq!(s.hash, claim(&template, share).hash);
+            assert_eq!(s.data, "pool work");
+        }
+        other => panic!("expected ValidShare, got {:?}", other),
+    }
+
+    let block = nonce_with_zeros(&template, 2);
+    assert!(matches!(
+        pool.submit_share("bob", &claim(&template, block), block),
+        ShareResult::ValidBlock(_)
+    ));
+
+    assert_eq!(pool.share_count("alice"), 1);
+    assert_eq!(pool.share_count("bob"), 1, "a block is also a share");
+    assert_eq!(pool.share_count("carol"), 0);
+    assert_eq!(pool.total_shares(), 2);
+    assert_eq!(pool.blocks_found().len(), 1);
+    assert_eq!(pool.blocks_found()[0].nonce, block);
+}
+
+#[test]
+fn test_share_difficulty_zero_accepts_any_honest_hash() {
+    let template = pool_template();
+    let mut pool = SharePool::new(3, 0);
+    let nonce = nonce_with_zeros(&template, 0);
+    assert!(matches!(
+        pool.submit_share("alice", &claim(&template, nonce), nonce),
+        ShareResult::ValidShare(_)
+    ));
+}
+
+#[test]
+fn test_share_difficulty_capped_at_network() {
+    let pool = SharePool::new(2, 5);
+    assert_eq!(pool.share_difficulty(), 2);
+    assert_eq!(pool.network_difficulty(), 2);
+}
+
+#[test]
+fn test_share_hash_mismatch_rejected() {
+    let template = pool_template();
+    let mut pool = SharePool::new(2, 0);
+    let good = nonce_with_zeros(&template, 1);
+    let claimed = claim(&template, good);
+
+    // Right hash, wrong nonce.
+    assert_eq!(
+        pool.submit_share("mallory", &claimed, good + 1),
+        ShareResult::Invalid(ShareRejection::HashMismatch)
+    );
+
+    // A forged hash that "meets" the network difficulty.
+    let mut forged = claimed.clone();
+    forged.hash = format!("00{}", &claimed.hash[2..]);
+    assert_eq!(
+        pool.submit_share("mallory", &forged, good),
+        ShareResult::Invalid(ShareRejection::HashMismatch)
+    );
+    assert_eq!(pool.share_count("mallory"), 0);
+}
+
+#[test]
+fn test_duplicate_share_not_credited_twice() {
+    let template = pool_template();
+    let mut pool = SharePool::new(2, 1);
+    let nonce = nonce_with_zeros(&template, 1);
+    let claimed = claim(&template, nonce);
+    assert!(matches!(
+        pool.submit_share("alice", &claimed, nonce),
+        ShareResult::ValidShare(_)
+    ));
+    assert_eq!(
+        pool.submit_share("bob", &claimed, nonce),
+        ShareResult::Invalid(ShareRejection::Duplicate)
+    );
+    assert_eq!(pool.total_shares(), 1);
+}
+
+/// A pool with the given number of accepted shares per miner.
+fn pool_with_counts(counts: &[(&str, usize)]) -> SharePool {
+    let template = Block::with_timestamp(7, "payout".to_string(), "prev".to_string(), 0, 1);
+    let mut pool = SharePool::new(64, 0);
+    let mut nonce = 0;
+    for &(miner, count) in counts {
+        for _ in 0..count {
+            let result = pool.submit_share(miner, &claim(&template, nonce), nonce);
+            assert!(matches!(result, ShareResult::ValidShare(_)));
+            nonce += 1;
+        }
+    }
+    pool
+}
+
+#[test]
+fn test_payouts_proportional_and_exact() {
+    let pool = pool_with_counts(&[("alice", 1), ("bob", 1), ("carol", 1)]);
+    let payouts = pool.payouts(100);
+    assert_eq!(
+        payouts,
+        vec![
+            ("alice".to_string(), 34),
+            ("bob".to_string(), 33),
+            ("carol".to_string(), 33),
+        ]
+    );
+
+    let pool = pool_with_counts(&[("bob", 3), ("alice", 1)]);
+    assert_eq!(
+        pool.payouts(1_000),
+        vec![("alice".to_string(), 250), ("bob".to_string(), 750)]
+    );
+}
+
+#[test]
+fn test_payouts_sum_equals_reward() {
+    let pool = pool_with_counts(&[("a", 7), ("b", 3), ("c", 11), ("d", 1), ("e", 5)]);
+    for reward in [0, 1, 2, 26, 27, 100, 625_000_000, 1_000_003, u64::MAX] {
+        let payouts = pool.payouts(reward);
+        let sum: u128 = payouts.iter().map(|(_, amount)| u128::from(*amount)).sum();
+        assert_eq!(sum, u128::from(reward), "reward {}", reward);
+    }
+
+    // Everyone's floor of 1 unit is 0, so the single unit goes to the
+    // largest remainder: c, with 11 of the 27 shares.
+    let payouts = pool.payouts(1);
+    let winners: Vec<_> = payouts.iter().filter(|(_, amount)| *amount > 0).collect();
+    assert_eq!(winners, [&("c".to_string(), 1)]);
+}
+
+#[test]
+fn test_payouts_empty_pool() {
+    assert!(SharePool::new(2, 1).payouts(50).is_empty());
+}
+
+#[test]
+fn test_share_rejection_display() {
+    assert_eq!(
+        ShareRejection::HashMismatch.to_string(),
+        "hash does not match recomputed hash"
+    );
+    assert_eq!(
+        ShareRejection::Duplicate.to_string(),
+        "share already submitted"
+    );
+}
@@ -3,7 +3,8 @@
 //! Implement core blockchain primitives and validation logic.
 //! See `src/solution.rs` for the complete reference implementation.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::mpsc::{Receiver, Sender};
 
 #[derive(Clone, Debug)]
 pub struct Block {
@@ -35,6 +36,73 @@ impl Block {
         let _ = self;
         todo!("Recompute and compare merkle root")
     }
+
+    pub fn header(&self) -> BlockHeader {
+        let _ = self;
+        todo!("Copy header fields, dropping transactions")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockHeader {
+    pub index: u64,
+    pub timestamp: u64,
+    pub merkle_root: String,
+    pub previous_hash: String,
+    pub hash: String,
+    pub nonce: u64,
+}
+
+impl BlockHeader {
+    pub fn calculate_hash(&self) -> String {
+        let _ = self;
+        todo!("Hash the same fields as Block::calculate_hash")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HeaderChain {
+    pub headers: Vec<BlockHeader>,
+    pub difficulty: usize,
+}
+
+impl HeaderChain {
+    pub fn new(_difficulty: usize) -> Self {
+        todo!("Create empty header chain")
+    }
+
+    pub fn from_headers(
+        _headers: impl IntoIterator<Item = BlockHeader>,
+        _difficulty: usize,
+    ) -> Result<Self, ValidationError> {
+        todo!("Push every header, stopping at the first invalid one")
+    }
+
+    pub fn push(&mut self, _header: BlockHeader) -> Result<(), ValidationError> {
+        let _ = self;
+        // TODO: check link to previous header, hash, and PoW (except the first header).
+        todo!("Validate and append header")
+    }
+
+    pub fn headers(&self) -> &[BlockHeader] {
+        let _ = self;
+        todo!("Return accepted headers")
+    }
+
+    pub fn tip(&self) -> Option<&BlockHeader> {
+        let _ = self;
+        todo!("Return latest header")
+    }
+
+    pub fn len(&self) -> usize {
+        let _ = self;
+        todo!("Return header count")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let _ = self;
+        todo!("Return whether no headers were accepted")
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -77,21 +145,38 @@ pub struct TxInput {
     pub signature: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TxOutput {
     pub address: String,
     pub amount: u64,
 }
 
+#[derive(Clone, Debug)]
 pub struct UTXOSet {
     utxos: HashMap<String, UTXO>,
+    by_address: HashMap<String, HashSet<String>>,
+    balances: HashMap<String, u64>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct UTXO {
     pub txid: String,
     pub vout: usize,
     pub output: TxOutput,
+    pub height: u64,
+    pub is_coinbase: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UtxoDiff {
+    pub added: Vec<UTXO>,
+    pub removed: Vec<UTXO>,
+}
+
+impl UtxoDiff {
+    pub fn is_empty(&self) -> bool {
+        todo!("No added and no removed UTXOs")
+    }
 }
 
 impl UTXOSet {
@@ -100,12 +185,26 @@ impl UTXOSet {
     }
 
     pub fn add_utxo(&mut self, _txid: String, _vout: usize, _output: TxOutput) {
-        let _ = self;
+        let _ = (&self.utxos, &self.by_address, &self.balances);
+        // TODO: Also record the key under the address and add to its balance.
         todo!("Insert UTXO")
     }
 
+    pub fn add_utxo_at(
+        &mut self,
+        _txid: String,
+        _vout: usize,
+        _output: TxOutput,
+        _height: u64,
+        _is_coinbase: bool,
+    ) {
+        let _ = self;
+        todo!("Add UTXO with its creation height and coinbase flag")
+    }
+
     pub fn remove_utxo(&mut self, _txid: &str, _vout: usize) {
         let _ = self;
+        // TODO: Keep the indexes in step; drop an address once it has no UTXOs.
         todo!("Remove spent UTXO")
     }
 
@@ -116,7 +215,7 @@ impl UTXOSet {
 
     pub fn get_balance(&self, _address: &str) -> u64 {
         let _ = self;
-        todo!("Sum UTXOs by address")
+        todo!("Look up cached balance")
     }
 
     pub fn count(&self) -> usize {
@@ -126,12 +225,49 @@ impl UTXOSet {
 
     pub fn get_utxos_for_address(&self, _address: &str) -> Vec<&UTXO> {
         let _ = self;
-        todo!("Collect UTXOs by address")
+        todo!("Collect UTXOs via the address index")
+    }
+
+    pub fn richest_addresses(&self, _n: usize) -> Vec<(String, u64)> {
+        let _ = self;
+        todo!("Top n balances, ties broken by address")
+    }
+
+    pub fn total_supply(&self) -> u64 {
+        let _ = self;
+        todo!("Sum all balances")
+    }
+
+    pub fn debug_index_consistent(&self) -> bool {
+        let _ = self;
+        todo!("Rebuild indexes from utxos and compare")
+    }
+
+    pub fn state_hash(&self) -> String {
+        let _ = self;
+        // TODO: Sort the outpoint keys, then SHA-256 key || address || amount
+        // for each UTXO in that order.
+        todo!("Hash the UTXO set deterministically")
+    }
+
+    pub fn diff(&self, _other: &UTXOSet) -> UtxoDiff {
+        let _ = self;
+        // TODO: Entries only in (or different in) other are added; entries
+        // only in (or different in) self are removed. Sort both by outpoint.
+        todo!("Diff two UTXO sets")
     }
 }
 
 pub struct Mempool {
     transactions: HashMap<String, Transaction>,
+    added_at: HashMap<String, u64>,
+    spends: HashMap<String, HashSet<String>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MempoolError {
+    AlreadyPooled(String),
+    InsufficientFee { fee: u64, required: u64 },
 }
 
 impl Mempool {
@@ -144,6 +280,38 @@ impl Mempool {
         todo!("Add transaction to mempool")
     }
 
+    pub fn add_transaction_at(&mut self, _tx: Transaction, _added_at: u64) {
+        // TODO: Record the arrival time and index every "txid:vout" the
+        // transaction spends in `spends` (outpoint -> spending txids).
+        let _ = (&self.added_at, &self.spends);
+        todo!("Add transaction with an arrival time")
+    }
+
+    pub fn add_transaction_rbf(
+        &mut self,
+        _tx: Transaction,
+        _utxo_set: &UTXOSet,
+        _min_fee_bump: u64,
+    ) -> Result<Vec<String>, MempoolError> {
+        // TODO: Find pooled transactions spending the same outpoints, plus
+        // their descendants. Reject unless the new fee is at least their
+        // combined fee + min_fee_bump; otherwise evict them and add `tx`.
+        let _ = self;
+        todo!("Replace conflicting transactions by fee")
+    }
+
+    pub fn expire_older_than(&mut self, _cutoff: u64) -> Vec<String> {
+        // TODO: Remove (and return, sorted) txids added before `cutoff`,
+        // keeping the outpoint index in sync.
+        let _ = self;
+        todo!("Expire old transactions")
+    }
+
+    pub fn added_at(&self, _txid: &str) -> Option<u64> {
+        let _ = self;
+        todo!("Look up arrival time")
+    }
+
     pub fn remove_transaction(&mut self, _txid: &str) {
         let _ = self;
         todo!("Remove transaction from mempool")
@@ -163,11 +331,24 @@ impl Mempool {
         let _ = self;
         todo!("Check if txid exists in mempool")
     }
+
+    pub fn get(&self, _txid: &str) -> Option<&Transaction> {
+        let _ = self;
+        todo!("Look up a pooled transaction")
+    }
+
+    pub fn debug_index_consistent(&self) -> bool {
+        let _ = self;
+        todo!("Rebuild the outpoint index and compare")
+    }
 }
 
 pub struct Blockchain {
     pub blocks: Vec<Block>,
     pub difficulty: usize,
+    checkpoints: BTreeMap<u64, String>,
+    pub params: ConsensusParams,
+    pub index: ChainIndex,
 }
 
 impl Blockchain {
@@ -175,11 +356,30 @@ impl Blockchain {
         todo!("Create blockchain with genesis block")
     }
 
+    pub fn with_params(
+        _difficulty: usize,
+        _genesis_timestamp: u64,
+        _params: ConsensusParams,
+    ) -> Self {
+        todo!("Create blockchain that enforces the given consensus params")
+    }
+
     pub fn add_block(&mut self, _block: Block) {
         let _ = self;
         todo!("Append block to chain")
     }
 
+    pub fn add_block_validated(
+        &mut self,
+        _block: Block,
+        _utxo_set: &mut UTXOSet,
+    ) -> Result<(), BlockError> {
+        let _ = self;
+        // TODO: validate_block against the tip; only on success append the
+        // block and apply it to the UTXO set.
+        todo!("Validate, append, and apply block")
+    }
+
     pub fn get_latest_block(&self) -> Option<&Block> {
         let _ = self;
         todo!("Return tip block")
@@ -195,20 +395,498 @@ impl Blockchain {
         todo!("Get block by height")
     }
 
+    pub fn get_block_by_hash(&self, _hash: &str) -> Option<&Block> {
+        let _ = self;
+        todo!("Look up block height in the index")
+    }
+
+    pub fn get_transaction(&self, _txid: &str) -> Option<(&Transaction, u64)> {
+        let _ = self;
+        todo!("Look up (height, position) in the index")
+    }
+
+    pub fn get_transactions_for_address(&self, _address: &str) -> Vec<(String, u64)> {
+        let _ = self;
+        todo!("Return the address history, oldest first")
+    }
+
+    pub fn index(&self) -> &ChainIndex {
+        let _ = self;
+        todo!("Return the chain index")
+    }
+
+    pub fn reindex(&mut self) {
+        let _ = self;
+        // TODO: Start from an empty index and connect every block in order.
+        todo!("Rebuild the index from the blocks")
+    }
+
     pub fn is_valid(&self) -> bool {
         let _ = self;
         todo!("Validate block links, hashes, and PoW")
     }
+
+    pub fn set_checkpoints(&mut self, _checkpoints: Vec<(u64, String)>) {
+        let _ = self;
+        todo!("Store trusted height -> hash checkpoints")
+    }
+
+    pub fn validate(&self, _mode: ValidationMode) -> Result<(), ValidationError> {
+        let _ = &self.checkpoints;
+        // TODO: Full = check every block. Fast = up to the highest checkpoint
+        // the chain reaches (ignore any above the tip), only check
+        // previous_hash links and exact checkpoint hashes.
+        todo!("Validate chain with the selected mode")
+    }
+
+    pub fn spot_check(&self, _sample_every: usize) -> Result<(), ValidationError> {
+        let _ = self;
+        todo!("Fully validate a random sample of sub-checkpoint blocks")
+    }
+
+    pub fn spot_check_with_seed(
+        &self,
+        _sample_every: usize,
+        _seed: u64,
+    ) -> Result<(), ValidationError> {
+        let _ = self;
+        todo!("Seeded spot check: one random block per window of sample_every")
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Block> {
+        let _ = self;
+        todo!("Iterate over blocks, genesis first")
+    }
+
+    pub fn headers(&self) -> Vec<BlockHeader> {
+        let _ = self;
+        todo!("Collect headers for every block")
+    }
+
+    pub fn locator(&self) -> Vec<String> {
+        let _ = self;
+        // TODO: tip, tip-1, tip-2, tip-4, ... and always genesis last.
+        todo!("Build exponentially spaced block locator")
+    }
+
+    pub fn find_fork_point(&self, _locator: &[String]) -> Option<usize> {
+        let _ = self;
+        todo!("Height of the first locator hash found in our chain")
+    }
+}
+
+impl<'a> IntoIterator for &'a Blockchain {
+    type Item = &'a Block;
+    type IntoIter = std::slice::Iter<'a, Block>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationMode {
+    Full,
+    Fast,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    BrokenLink {
+        height: u64,
+    },
+    HashMismatch {
+        height: u64,
+    },
+    InsufficientWork {
+        height: u64,
+    },
+    MerkleRootMismatch {
+        height: u64,
+    },
+    CheckpointMismatch {
+        height: u64,
+        expected: String,
+        actual: String,
+    },
+}
+
+pub const COIN: u64 = 100_000_000;
+pub const INITIAL_SUBSIDY: u64 = 50 * COIN;
+pub const SUBSIDY_HALVING_INTERVAL: u64 = 210;
+pub const COINBASE_MATURITY: u64 = 10;
+
+pub fn block_subsidy(_height: u64) -> u64 {
+    todo!("Halve INITIAL_SUBSIDY every SUBSIDY_HALVING_INTERVAL blocks")
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsensusParams {
+    pub max_block_txs: usize,
+    pub max_tx_inputs: usize,
+    pub max_tx_outputs: usize,
+    pub max_money: u64,
+    pub strict_addresses: bool,
+}
+
+impl Default for ConsensusParams {
+    fn default() -> Self {
+        todo!("Pick default consensus limits")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlockError {
+    TooManyTransactions {
+        count: usize,
+        max: usize,
+    },
+    HashMismatch,
+    InsufficientWork,
+    MerkleRootMismatch,
+    PreviousHashMismatch {
+        expected: String,
+        actual: String,
+    },
+    IndexMismatch {
+        expected: u64,
+        actual: u64,
+    },
+    MissingCoinbase,
+    MultipleCoinbase {
+        index: usize,
+    },
+    TooManyInputs {
+        txid: String,
+        count: usize,
+        max: usize,
+    },
+    TooManyOutputs {
+        txid: String,
+        count: usize,
+        max: usize,
+    },
+    OutputExceedsMaxMoney {
+        txid: String,
+        amount: u64,
+        max: u64,
+    },
+    InvalidAddress {
+        txid: String,
+        address: String,
+        reason: address::AddressError,
+    },
+    InvalidTransaction {
+        txid: String,
+        reason: String,
+    },
+    CoinbaseOverclaim {
+        claimed: u64,
+        allowed: u64,
+    },
+}
+
+pub fn check_transaction_limits(
+    _tx: &Transaction,
+    _params: &ConsensusParams,
+) -> Result<(), BlockError> {
+    // TODO: input/output counts, max_money per output and (if strict) output addresses.
+    todo!("Enforce the per-transaction consensus limits")
+}
+
+pub fn validate_block(
+    _block: &Block,
+    _prev_block: &Block,
+    _utxo_set: &UTXOSet,
+    _params: &ConsensusParams,
+    _difficulty: usize,
+) -> Result<(), BlockError> {
+    // TODO: size limits first, then hash/PoW/link/index/merkle, coinbase placement,
+    // check_transaction_limits on every transaction, and finally validate_transaction
+    // against a scratch UTXO set updated as each transaction is applied.
+    todo!("Enforce every consensus rule on the block")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxLocation {
+    pub height: u64,
+    pub position: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainIndex {
+    blocks: HashMap<String, u64>,
+    transactions: HashMap<String, TxLocation>,
+    addresses: HashMap<String, Vec<(String, u64)>>,
+}
+
+impl ChainIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn block_height(&self, _hash: &str) -> Option<u64> {
+        let _ = self;
+        todo!("Look up block hash")
+    }
+
+    pub fn locate_transaction(&self, _txid: &str) -> Option<TxLocation> {
+        let _ = self;
+        todo!("Look up txid")
+    }
+
+    pub fn address_history(&self, _address: &str) -> &[(String, u64)] {
+        let _ = self;
+        todo!("Look up address history")
+    }
+
+    pub fn transaction_count(&self) -> usize {
+        let _ = self;
+        todo!("Count indexed transactions")
+    }
+
+    // TODO: A private `connect(&mut self, chain: &[Block], height)` that
+    // indexes chain[height]: its hash, each txid's location, and every
+    // address paid by an output or owning an output spent by an input.
+}
+
+pub const MAX_ORPHANS: usize = 100;
+
+#[derive(Clone, Debug)]
+pub enum NetMessage {
+    Inv(Vec<String>),
+    GetData(Vec<String>),
+    BlockMsg(Block),
+    TxMsg(Transaction),
+    GetHeaders { locator: Vec<String> },
+    Headers(Vec<BlockHeader>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Peer(usize),
+    Broadcast,
+}
+
+pub struct SimNode {
+    id: usize,
+    chain: Blockchain,
+    utxo_set: UTXOSet,
+    mempool: Mempool,
+    orphans: HashMap<String, Block>,
+}
+
+impl SimNode {
+    pub fn new(_id: usize, _difficulty: usize, _genesis_timestamp: u64) -> Self {
+        // TODO: A fresh Blockchain, with its genesis applied to the UTXO set.
+        todo!("Create a node at genesis")
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn chain(&self) -> &Blockchain {
+        &self.chain
+    }
+
+    pub fn utxo_set(&self) -> &UTXOSet {
+        &self.utxo_set
+    }
+
+    pub fn mempool(&self) -> &Mempool {
+        &self.mempool
+    }
+
+    pub fn tip_hash(&self) -> &str {
+        let _ = self;
+        todo!("Hash of the last block")
+    }
+
+    pub fn knows(&self, _hash: &str) -> bool {
+        // TODO: Connected or orphaned block, pooled or confirmed transaction.
+        let _ = &self.orphans;
+        todo!("Check whether we have this block or transaction")
+    }
+
+    pub fn mine_block(&mut self, _miner: &str, _timestamp: u64) -> Block {
+        // TODO: Take pooled transactions oldest first while they validate,
+        // pay subsidy + fees to `miner`, mine, and connect the block.
+        let _ = self;
+        todo!("Mine a block on our tip")
+    }
+
+    pub fn announce(&self) -> NetMessage {
+        let _ = self;
+        todo!("Inv of our tip and pooled txids")
+    }
+
+    pub fn handle(&mut self, _from: usize, _message: NetMessage) -> Vec<(Target, NetMessage)> {
+        // TODO:
+        // - Inv: GetData for unknown hashes; GetHeaders if one is an orphan.
+        // - GetData: reply with the blocks and pooled transactions we have.
+        // - BlockMsg: connect it if it extends our tip (then any orphans
+        //   waiting on it) and broadcast an Inv of the new tip. An unknown
+        //   parent makes it an orphan and asks the sender for headers.
+        // - TxMsg: pool it if valid and broadcast an Inv.
+        // - GetHeaders: headers after the locator's fork point.
+        // - Headers: GetData for the ones we don't know.
+        let _ = self;
+        todo!("Process a message from a peer")
+    }
+}
+
+pub struct Network {
+    nodes: Vec<SimNode>,
+    outboxes: Vec<Sender<(usize, NetMessage)>>,
+    inboxes: Vec<Receiver<(usize, NetMessage)>>,
+    loss_percent: u8,
+    rng_state: u64,
+    sent: usize,
+    dropped: usize,
+}
+
+impl Network {
+    pub fn new(_size: usize, _difficulty: usize, _genesis_timestamp: u64) -> Self {
+        // TODO: One SimNode and one mpsc channel per node.
+        todo!("Create a fully connected network")
+    }
+
+    pub fn with_message_loss(self, _percent: u8, _seed: u64) -> Self {
+        let _ = (self.loss_percent, self.rng_state);
+        todo!("Drop a seeded share of messages")
+    }
+
+    pub fn node(&self, id: usize) -> &SimNode {
+        &self.nodes[id]
+    }
+
+    pub fn nodes(&self) -> &[SimNode] {
+        &self.nodes
+    }
+
+    pub fn messages_sent(&self) -> usize {
+        self.sent
+    }
+
+    pub fn messages_dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub fn mine_block(&mut self, _id: usize, _miner: &str, _timestamp: u64) -> String {
+        let _ = &self.outboxes;
+        todo!("Mine on one node and broadcast an Inv")
+    }
+
+    pub fn submit_transaction(&mut self, _id: usize, _tx: Transaction) {
+        let _ = self;
+        todo!("Hand a transaction to one node")
+    }
+
+    pub fn step(&mut self) -> usize {
+        // TODO: Drain every inbox first, then let each node handle its
+        // messages; replies are delivered next round. Count deliveries.
+        let _ = &self.inboxes;
+        todo!("Deliver one round of messages")
+    }
+
+    pub fn rebroadcast(&mut self) {
+        let _ = self;
+        todo!("Every node re-announces")
+    }
+
+    pub fn is_converged(&self) -> bool {
+        let _ = self;
+        todo!("Compare tip hashes")
+    }
+
+    pub fn run_until_converged(&mut self, _max_rounds: usize) -> Option<usize> {
+        // TODO: Step until converged, rebroadcasting after a quiet round.
+        let _ = self;
+        todo!("Step until every node agrees")
+    }
 }
 
 pub fn calculate_merkle_root(_transactions: &[Transaction]) -> String {
     todo!("Compute merkle root from transaction list")
 }
 
-pub fn validate_transaction(_tx: &Transaction, _utxo_set: &UTXOSet, _is_coinbase: bool) -> Result<(), String> {
+pub fn validate_transaction(
+    _tx: &Transaction,
+    _utxo_set: &UTXOSet,
+    _min_fee: u64,
+    _current_height: u64,
+) -> Result<(), String> {
+    // TODO: also reject coinbase outputs younger than COINBASE_MATURITY and
+    // inputs that list the same outpoint twice.
     todo!("Validate transaction against UTXO set and invariants")
 }
 
+pub fn validate_transaction_with(
+    _tx: &Transaction,
+    _utxo_set: &UTXOSet,
+    _min_fee: u64,
+    _current_height: u64,
+    _params: &ConsensusParams,
+) -> Result<(), String> {
+    // TODO: validate_transaction, then if params.strict_addresses check
+    // every output address with address::validate_address.
+    todo!("Validate transaction with optional strict address checks")
+}
+
+pub mod address {
+    pub const ADDRESS_VERSION: u8 = 0x00;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum AddressError {
+        InvalidCharacter { ch: char, position: usize },
+        InvalidLength { len: usize },
+        ChecksumMismatch,
+        WrongVersion { expected: u8, found: u8 },
+    }
+
+    impl std::fmt::Display for AddressError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    impl std::error::Error for AddressError {}
+
+    pub fn encode_address(_pubkey_hash: &[u8; 20], _version: u8) -> String {
+        // TODO: version || hash || first 4 bytes of sha256(sha256(...)),
+        // then base58_encode.
+        todo!("Encode a Base58Check address")
+    }
+
+    pub fn decode_address(_address: &str) -> Result<(u8, [u8; 20]), AddressError> {
+        // TODO: base58_decode, require 25 bytes, verify the checksum.
+        todo!("Decode and verify a Base58Check address")
+    }
+
+    pub fn validate_address(
+        _address: &str,
+        _expected_version: u8,
+    ) -> Result<[u8; 20], AddressError> {
+        todo!("Decode and check the version byte")
+    }
+
+    pub fn derive_address_from_pubkey(_pubkey: &str) -> String {
+        // TODO: SHA-256 the key, keep the first 20 bytes, encode with
+        // ADDRESS_VERSION.
+        todo!("Derive an address from a public key")
+    }
+
+    pub fn base58_encode(_bytes: &[u8]) -> String {
+        // TODO: each leading zero byte becomes a '1'.
+        todo!("Encode bytes in base58")
+    }
+
+    pub fn base58_decode(_input: &str) -> Result<Vec<u8>, AddressError> {
+        todo!("Decode base58 into bytes")
+    }
+}
+
 pub fn validate_proof_of_work(_block: &Block, _difficulty: usize) -> bool {
     todo!("Check block hash against difficulty target")
 }
@@ -217,6 +895,22 @@ pub fn apply_block_to_utxo_set(_block: &Block, _utxo_set: &mut UTXOSet) {
     todo!("Spend inputs and create outputs in UTXO set")
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockUndo {
+    pub spent: Vec<UTXO>,
+}
+
+pub fn apply_block_with_undo(_block: &Block, _utxo_set: &mut UTXOSet) -> BlockUndo {
+    // TODO: Same as apply_block_to_utxo_set, but record each UTXO before
+    // removing it.
+    todo!("Apply block and record spent UTXOs")
+}
+
+pub fn revert_block_from_utxo_set(_block: &Block, _undo: &BlockUndo, _utxo_set: &mut UTXOSet) {
+    // TODO: Remove the block's outputs, then re-add the spent UTXOs.
+    todo!("Revert block from UTXO set")
+}
+
 pub fn format_coins(_satoshis: u64) -> String {
     todo!("Format satoshis into decimal coin string")
 }
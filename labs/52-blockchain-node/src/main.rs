@@ -1,7 +1,9 @@
 //! # Blockchain Node Demo
 
 use blockchain_node::solution::{
-    format_coins, validate_proof_of_work, Block, Blockchain, Transaction,
+    address::{decode_address, derive_address_from_pubkey},
+    apply_block_with_undo, format_coins, revert_block_from_utxo_set, validate_proof_of_work, Block,
+    Blockchain, HeaderChain, Network, Transaction, UTXOSet, ValidationMode,
 };
 
 fn main() {
@@ -23,4 +25,69 @@ fn main() {
     chain.add_block(block);
     println!("new height={} reward={}", chain.height(), format_coins(5_000_000_000));
     println!("chain valid={}", chain.is_valid());
+    if let Some((tx, height)) = chain.get_transaction("coinbase-1") {
+        println!(
+            "coinbase-1 found at height {} paying {}",
+            height, tx.outputs[0].address
+        );
+    }
+    println!(
+        "miner1 history={:?}",
+        chain.get_transactions_for_address("miner1")
+    );
+
+    let tip_hash = chain
+        .get_latest_block()
+        .map(|b| b.hash.clone())
+        .unwrap_or_default();
+    chain.set_checkpoints(vec![(1, tip_hash)]);
+    println!("fast validation={:?}", chain.validate(ValidationMode::Fast));
+    println!("spot check={:?}", chain.spot_check(1));
+
+    match HeaderChain::from_headers(chain.headers(), chain.difficulty) {
+        Ok(headers) => println!("header-only sync accepted {} headers", headers.len()),
+        Err(err) => println!("header-only sync failed: {}", err),
+    }
+    let locator = chain.locator();
+    println!(
+        "locator has {} hashes, fork point with ourselves={:?}",
+        locator.len(),
+        chain.find_fork_point(&locator)
+    );
+
+    let mut utxo_set = UTXOSet::new();
+    let before = utxo_set.state_hash();
+    if let Some(tip) = chain.get_latest_block() {
+        let undo = apply_block_with_undo(tip, &mut utxo_set);
+        println!("utxo state after tip={}", utxo_set.state_hash());
+        revert_block_from_utxo_set(tip, &undo, &mut utxo_set);
+        println!(
+            "state restored by revert={}",
+            utxo_set.state_hash() == before
+        );
+    }
+
+    let mut network = Network::new(4, 2, 1_700_000_000).with_message_loss(25, 1);
+    network.mine_block(0, "miner1", 1_700_000_100);
+    let rounds = network.run_until_converged(100);
+    println!(
+        "gossip: converged after {:?} rounds, {} of {} messages dropped",
+        rounds,
+        network.messages_dropped(),
+        network.messages_sent()
+    );
+
+    let addr = derive_address_from_pubkey("miner1-public-key");
+    println!(
+        "derived address={} decoded={:?}",
+        addr,
+        decode_address(&addr).is_ok()
+    );
+    let mut typo: Vec<char> = addr.chars().collect();
+    typo[5] = if typo[5] == 'x' { 'y' } else { 'x' };
+    let typo: String = typo.into_iter().collect();
+    match decode_address(&typo) {
+        Ok(_) => println!("typo {} slipped through", typo),
+        Err(err) => println!("typo {} rejected: {}", typo, err),
+    }
 }
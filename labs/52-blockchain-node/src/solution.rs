@@ -11,9 +11,13 @@
 // - Mempool for pending transactions
 // - Block validation (PoW, merkle root, transaction validity)
 // - Coinbase transactions (block reward + fees)
+// - Indexing blocks, transactions, and addresses for O(1) lookups
+// - Gossiping blocks and transactions between simulated peers
 
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
 
 // ============================================================================
 // BLOCK
@@ -50,14 +54,13 @@ impl Block {
 
     /// Compute the SHA-256 hash of the block header.
     pub fn calculate_hash(&self) -> String {
-        let contents = format!(
-            "{}{}{}{}{}",
-            self.index, self.timestamp, self.merkle_root, self.previous_hash, self.nonce
-        );
-        let mut hasher = Sha256::new();
-        hasher.update(contents.as_bytes());
-        let result = hasher.finalize();
-        result.iter().map(|b| format!("{:02x}", b)).collect()
+        hash_header_fields(
+            self.index,
+            self.timestamp,
+            &self.merkle_root,
+            &self.previous_hash,
+            self.nonce,
+        )
     }
 
     /// Mine the block by finding a nonce that produces a hash starting
@@ -75,6 +78,146 @@ impl Block {
         let computed = calculate_merkle_root(&self.transactions);
         self.merkle_root == computed
     }
+
+    /// The block without its transactions.
+    pub fn header(&self) -> BlockHeader {
+        BlockHeader {
+            index: self.index,
+            timestamp: self.timestamp,
+            merkle_root: self.merkle_root.clone(),
+            previous_hash: self.previous_hash.clone(),
+            hash: self.hash.clone(),
+            nonce: self.nonce,
+        }
+    }
+}
+
+/// The block hash covers only these fields, so both `Block` and
+/// `BlockHeader` hash through here and always agree.
+fn hash_header_fields(
+    index: u64,
+    timestamp: u64,
+    merkle_root: &str,
+    previous_hash: &str,
+    nonce: u64,
+) -> String {
+    let contents = format!(
+        "{}{}{}{}{}",
+        index, timestamp, merkle_root, previous_hash, nonce
+    );
+    let mut hasher = Sha256::new();
+    hasher.update(contents.as_bytes());
+    let result = hasher.finalize();
+    result.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// ============================================================================
+// BLOCK HEADERS (LIGHTWEIGHT CLIENTS)
+// ============================================================================
+
+/// Everything a block commits to, minus the transactions.
+///
+/// The merkle root stands in for the transactions, so a header is enough to
+/// check the chain's links and proof-of-work. A lightweight client downloads
+/// only headers and asks for a merkle proof when it cares about one
+/// transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockHeader {
+    pub index: u64,
+    pub timestamp: u64,
+    pub merkle_root: String,
+    pub previous_hash: String,
+    pub hash: String,
+    pub nonce: u64,
+}
+
+impl BlockHeader {
+    /// Same hash as `Block::calculate_hash` for the block this came from.
+    pub fn calculate_hash(&self) -> String {
+        hash_header_fields(
+            self.index,
+            self.timestamp,
+            &self.merkle_root,
+            &self.previous_hash,
+            self.nonce,
+        )
+    }
+}
+
+/// A header-only chain, validated as headers arrive.
+#[derive(Clone, Debug)]
+pub struct HeaderChain {
+    headers: Vec<BlockHeader>,
+    difficulty: usize,
+}
+
+impl HeaderChain {
+    /// An empty header chain expecting `difficulty` leading zeros.
+    pub fn new(difficulty: usize) -> Self {
+        HeaderChain {
+            headers: Vec::new(),
+            difficulty,
+        }
+    }
+
+    /// Validate `headers` in order and collect them into a chain.
+    pub fn from_headers(
+        headers: impl IntoIterator<Item = BlockHeader>,
+        difficulty: usize,
+    ) -> Result<Self, ValidationError> {
+        let mut chain = HeaderChain::new(difficulty);
+        for header in headers {
+            chain.push(header)?;
+        }
+        Ok(chain)
+    }
+
+    /// Append `header` if it extends the chain.
+    ///
+    /// The first header is the anchor: like genesis in
+    /// `Blockchain::validate`, it has no parent to link to and no PoW
+    /// requirement, but its hash must still match its fields. Every later
+    /// header must link to the previous one, hash correctly, and meet the
+    /// difficulty. Errors use the header's position as its height, and a
+    /// rejected header leaves the chain unchanged.
+    pub fn push(&mut self, header: BlockHeader) -> Result<(), ValidationError> {
+        let height = self.headers.len() as u64;
+
+        if let Some(prev) = self.headers.last() {
+            if header.previous_hash != prev.hash {
+                return Err(ValidationError::BrokenLink { height });
+            }
+        }
+        if header.hash != header.calculate_hash() {
+            return Err(ValidationError::HashMismatch { height });
+        }
+        if height > 0 && !header.hash.starts_with(&"0".repeat(self.difficulty)) {
+            return Err(ValidationError::InsufficientWork { height });
+        }
+
+        self.headers.push(header);
+        Ok(())
+    }
+
+    /// All accepted headers, anchor first.
+    pub fn headers(&self) -> &[BlockHeader] {
+        &self.headers
+    }
+
+    /// The most recent header, if any.
+    pub fn tip(&self) -> Option<&BlockHeader> {
+        self.headers.last()
+    }
+
+    /// Number of headers in the chain.
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Whether no headers have been accepted yet.
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
 }
 
 // ============================================================================
@@ -165,7 +308,7 @@ pub struct TxInput {
 }
 
 /// A transaction output assigning value to an address.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TxOutput {
     pub address: String,
     pub amount: u64,
@@ -176,16 +319,49 @@ pub struct TxOutput {
 // ============================================================================
 
 /// The set of all unspent transaction outputs, keyed by "txid:vout".
+///
+/// Two secondary indexes make wallet queries cheap: `by_address` maps each
+/// address to the keys of its UTXOs, and `balances` caches each address's
+/// total. `add_utxo` and `remove_utxo` are the only writers, so keeping the
+/// indexes in step there keeps them consistent everywhere, including in
+/// `apply_block_to_utxo_set`. An address whose last UTXO is spent is removed
+/// from both, so the indexes never hold empty entries.
+#[derive(Clone, Debug)]
 pub struct UTXOSet {
     utxos: HashMap<String, UTXO>,
+    by_address: HashMap<String, HashSet<String>>,
+    balances: HashMap<String, u64>,
 }
 
 /// A single unspent transaction output.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct UTXO {
     pub txid: String,
     pub vout: usize,
     pub output: TxOutput,
+    /// Height of the block that created this output.
+    pub height: u64,
+    /// Whether it was created by a coinbase (and so must mature first).
+    pub is_coinbase: bool,
+}
+
+/// How one UTXO set differs from another, both lists sorted by outpoint.
+///
+/// An outpoint present in both sets with different contents shows up in
+/// both lists: the old entry as removed, the new one as added.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UtxoDiff {
+    /// In the other set but not in this one.
+    pub added: Vec<UTXO>,
+    /// In this set but not in the other one.
+    pub removed: Vec<UTXO>,
+}
+
+impl UtxoDiff {
+    /// True when the two sets hold exactly the same UTXOs.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
 }
 
 impl UTXOSet {
@@ -193,19 +369,69 @@ impl UTXOSet {
     pub fn new() -> Self {
         UTXOSet {
             utxos: HashMap::new(),
+            by_address: HashMap::new(),
+            balances: HashMap::new(),
         }
     }
 
-    /// Add a UTXO to the set.
+    /// Add a non-coinbase UTXO created at height 0.
     pub fn add_utxo(&mut self, txid: String, vout: usize, output: TxOutput) {
+        self.add_utxo_at(txid, vout, output, 0, false);
+    }
+
+    /// Add a UTXO, recording where it came from so coinbase maturity can be
+    /// enforced when it is spent.
+    pub fn add_utxo_at(
+        &mut self,
+        txid: String,
+        vout: usize,
+        output: TxOutput,
+        height: u64,
+        is_coinbase: bool,
+    ) {
         let key = format!("{}:{}", txid, vout);
-        self.utxos.insert(key, UTXO { txid, vout, output });
+        // Re-adding an outpoint replaces it; unindex the old one first.
+        if let Some(old) = self.utxos.remove(&key) {
+            self.unindex(&key, &old);
+        }
+        self.by_address
+            .entry(output.address.clone())
+            .or_default()
+            .insert(key.clone());
+        *self.balances.entry(output.address.clone()).or_insert(0) += output.amount;
+        self.utxos.insert(
+            key,
+            UTXO {
+                txid,
+                vout,
+                output,
+                height,
+                is_coinbase,
+            },
+        );
     }
 
     /// Remove a UTXO (mark it as spent).
     pub fn remove_utxo(&mut self, txid: &str, vout: usize) {
         let key = format!("{}:{}", txid, vout);
-        self.utxos.remove(&key);
+        if let Some(utxo) = self.utxos.remove(&key) {
+            self.unindex(&key, &utxo);
+        }
+    }
+
+    fn unindex(&mut self, key: &str, utxo: &UTXO) {
+        let address = &utxo.output.address;
+        if let Some(keys) = self.by_address.get_mut(address) {
+            keys.remove(key);
+            if keys.is_empty() {
+                self.by_address.remove(address);
+                self.balances.remove(address);
+                return;
+            }
+        }
+        if let Some(balance) = self.balances.get_mut(address) {
+            *balance -= utxo.output.amount;
+        }
     }
 
     /// Look up a UTXO by txid and output index.
@@ -214,13 +440,9 @@ impl UTXOSet {
         self.utxos.get(&key)
     }
 
-    /// Get the total balance for a given address.
+    /// Get the total balance for a given address. O(1).
     pub fn get_balance(&self, address: &str) -> u64 {
-        self.utxos
-            .values()
-            .filter(|utxo| utxo.output.address == address)
-            .map(|utxo| utxo.output.amount)
-            .sum()
+        self.balances.get(address).copied().unwrap_or(0)
     }
 
     /// Count all UTXOs.
@@ -228,12 +450,95 @@ impl UTXOSet {
         self.utxos.len()
     }
 
-    /// Get all UTXOs belonging to an address.
+    /// Get all UTXOs belonging to an address, in no particular order.
+    /// O(number of UTXOs the address owns).
     pub fn get_utxos_for_address(&self, address: &str) -> Vec<&UTXO> {
-        self.utxos
-            .values()
-            .filter(|utxo| utxo.output.address == address)
-            .collect()
+        self.by_address
+            .get(address)
+            .map(|keys| keys.iter().filter_map(|key| self.utxos.get(key)).collect())
+            .unwrap_or_default()
+    }
+
+    /// The `n` addresses with the highest balances, richest first. Ties are
+    /// broken by address so the order is deterministic.
+    pub fn richest_addresses(&self, n: usize) -> Vec<(String, u64)> {
+        let mut balances: Vec<(String, u64)> = self
+            .balances
+            .iter()
+            .map(|(address, &amount)| (address.clone(), amount))
+            .collect();
+        balances.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        balances.truncate(n);
+        balances
+    }
+
+    /// Sum of every unspent output.
+    pub fn total_supply(&self) -> u64 {
+        self.balances.values().sum()
+    }
+
+    /// Rebuild both indexes from scratch and compare them with the live ones.
+    ///
+    /// O(total UTXOs): meant for tests and debugging, not hot paths.
+    pub fn debug_index_consistent(&self) -> bool {
+        let mut by_address: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut balances: HashMap<String, u64> = HashMap::new();
+        for (key, utxo) in &self.utxos {
+            let address = &utxo.output.address;
+            by_address
+                .entry(address.clone())
+                .or_default()
+                .insert(key.clone());
+            *balances.entry(address.clone()).or_insert(0) += utxo.output.amount;
+        }
+        by_address == self.by_address && balances == self.balances
+    }
+
+    /// A SHA-256 commitment to the whole set, as hex.
+    ///
+    /// Two nodes with the same UTXOs get the same hash no matter what order
+    /// they inserted them in: `HashMap` iteration order is random, so the
+    /// outpoint keys are sorted first. Each entry contributes
+    /// `key || address || amount`, with a zero byte after each string so
+    /// `("ab", "c")` and `("a", "bc")` can't collide, and the amount as
+    /// 8 big-endian bytes.
+    pub fn state_hash(&self) -> String {
+        let mut keys: Vec<&String> = self.utxos.keys().collect();
+        keys.sort();
+
+        let mut hasher = Sha256::new();
+        for key in keys {
+            let output = &self.utxos[key].output;
+            hasher.update(key.as_bytes());
+            hasher.update([0u8]);
+            hasher.update(output.address.as_bytes());
+            hasher.update([0u8]);
+            hasher.update(output.amount.to_be_bytes());
+        }
+        let result = hasher.finalize();
+        result.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// What would turn `self` into `other`. Handy when two nodes report
+    /// different `state_hash`es and you need to know why.
+    pub fn diff(&self, other: &UTXOSet) -> UtxoDiff {
+        let mut added: Vec<(&String, &UTXO)> = other
+            .utxos
+            .iter()
+            .filter(|(key, utxo)| self.utxos.get(*key) != Some(*utxo))
+            .collect();
+        let mut removed: Vec<(&String, &UTXO)> = self
+            .utxos
+            .iter()
+            .filter(|(key, utxo)| other.utxos.get(*key) != Some(*utxo))
+            .collect();
+        added.sort_by(|a, b| a.0.cmp(b.0));
+        removed.sort_by(|a, b| a.0.cmp(b.0));
+
+        UtxoDiff {
+            added: added.into_iter().map(|(_, utxo)| utxo.clone()).collect(),
+            removed: removed.into_iter().map(|(_, utxo)| utxo.clone()).collect(),
+        }
     }
 }
 
@@ -248,26 +553,159 @@ impl Default for UTXOSet {
 // ============================================================================
 
 /// A mempool holding unconfirmed transactions.
+///
+/// Besides the transactions themselves, the pool records when each one
+/// arrived (for expiry) and keeps an outpoint index: every "txid:vout" a
+/// pooled transaction spends maps to the pooled txids spending it. That
+/// index is what lets `add_transaction_rbf` find conflicts without scanning
+/// the pool. Plain `add_transaction` still accepts conflicting spends, so
+/// an outpoint can have more than one spender.
 pub struct Mempool {
     transactions: HashMap<String, Transaction>,
+    added_at: HashMap<String, u64>,
+    spends: HashMap<String, HashSet<String>>,
+}
+
+/// Why `Mempool::add_transaction_rbf` refused a transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MempoolError {
+    /// A transaction with this txid is already pooled.
+    AlreadyPooled(String),
+    /// The replacement doesn't pay enough to evict what it conflicts with.
+    InsufficientFee { fee: u64, required: u64 },
+}
+
+impl fmt::Display for MempoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MempoolError::AlreadyPooled(txid) => {
+                write!(f, "transaction {} is already in the mempool", txid)
+            }
+            MempoolError::InsufficientFee { fee, required } => write!(
+                f,
+                "replacement pays a fee of {}, at least {} is required",
+                fee, required
+            ),
+        }
+    }
 }
 
+impl std::error::Error for MempoolError {}
+
 impl Mempool {
     /// Create an empty mempool.
     pub fn new() -> Self {
         Mempool {
             transactions: HashMap::new(),
+            added_at: HashMap::new(),
+            spends: HashMap::new(),
         }
     }
 
-    /// Add a transaction to the mempool.
+    /// Add a transaction to the mempool, stamped with the current time.
     pub fn add_transaction(&mut self, tx: Transaction) {
+        self.add_transaction_at(tx, unix_now());
+    }
+
+    /// Add a transaction with an explicit arrival time (seconds since the
+    /// Unix epoch), so expiry can be tested without sleeping.
+    ///
+    /// Re-adding a pooled txid replaces the entry and its timestamp.
+    pub fn add_transaction_at(&mut self, tx: Transaction, added_at: u64) {
+        self.remove_transaction(&tx.txid);
+        for input in &tx.inputs {
+            self.spends
+                .entry(format!("{}:{}", input.txid, input.vout))
+                .or_default()
+                .insert(tx.txid.clone());
+        }
+        self.added_at.insert(tx.txid.clone(), added_at);
         self.transactions.insert(tx.txid.clone(), tx);
     }
 
+    /// Add `tx`, replacing any pooled transactions that spend the same
+    /// outputs (replace-by-fee).
+    ///
+    /// Evicting a transaction also evicts its descendants: pooled
+    /// transactions spending its outputs would otherwise be left spending
+    /// coins that no longer exist. The replacement must pay at least
+    /// `min_fee_bump` more than everything it evicts combined, so miners
+    /// are never worse off and a peer can't churn the pool for free.
+    ///
+    /// Returns the evicted txids (empty when nothing conflicted), sorted.
+    /// Like `add_transaction`, this doesn't run `validate_transaction`;
+    /// fees are computed against `utxo_set` plus outputs in the pool.
+    pub fn add_transaction_rbf(
+        &mut self,
+        tx: Transaction,
+        utxo_set: &UTXOSet,
+        min_fee_bump: u64,
+    ) -> Result<Vec<String>, MempoolError> {
+        if self.contains(&tx.txid) {
+            return Err(MempoolError::AlreadyPooled(tx.txid));
+        }
+
+        let mut evicted = self.conflicts_with(&tx);
+        self.add_descendants(&mut evicted);
+        let mut evicted: Vec<String> = evicted.into_iter().collect();
+        evicted.sort();
+
+        if !evicted.is_empty() {
+            let replaced_fees: u64 = evicted
+                .iter()
+                .filter_map(|txid| self.transactions.get(txid))
+                .map(|pooled| self.pooled_fee(pooled, utxo_set))
+                .sum();
+            let required = replaced_fees.saturating_add(min_fee_bump);
+            let fee = self.pooled_fee(&tx, utxo_set);
+            if fee < required {
+                return Err(MempoolError::InsufficientFee { fee, required });
+            }
+        }
+
+        for txid in &evicted {
+            self.remove_transaction(txid);
+        }
+        self.add_transaction(tx);
+        Ok(evicted)
+    }
+
+    /// Remove every transaction added before `cutoff` and return their
+    /// txids, sorted. Entries added at exactly `cutoff` stay.
+    pub fn expire_older_than(&mut self, cutoff: u64) -> Vec<String> {
+        let mut expired: Vec<String> = self
+            .added_at
+            .iter()
+            .filter(|(_, &added_at)| added_at < cutoff)
+            .map(|(txid, _)| txid.clone())
+            .collect();
+        expired.sort();
+        for txid in &expired {
+            self.remove_transaction(txid);
+        }
+        expired
+    }
+
+    /// When `txid` entered the pool, if it's pooled.
+    pub fn added_at(&self, txid: &str) -> Option<u64> {
+        self.added_at.get(txid).copied()
+    }
+
     /// Remove a transaction from the mempool.
     pub fn remove_transaction(&mut self, txid: &str) {
-        self.transactions.remove(txid);
+        let Some(tx) = self.transactions.remove(txid) else {
+            return;
+        };
+        self.added_at.remove(txid);
+        for input in &tx.inputs {
+            let key = format!("{}:{}", input.txid, input.vout);
+            if let Some(spenders) = self.spends.get_mut(&key) {
+                spenders.remove(txid);
+                if spenders.is_empty() {
+                    self.spends.remove(&key);
+                }
+            }
+        }
     }
 
     /// Select transactions for inclusion in a block.
@@ -284,6 +722,90 @@ impl Mempool {
     pub fn contains(&self, txid: &str) -> bool {
         self.transactions.contains_key(txid)
     }
+
+    /// Look up a pooled transaction.
+    pub fn get(&self, txid: &str) -> Option<&Transaction> {
+        self.transactions.get(txid)
+    }
+
+    /// Rebuild the outpoint index from scratch and compare it with the live
+    /// one; also checks every pooled transaction has a timestamp.
+    ///
+    /// O(pooled inputs): meant for tests and debugging, not hot paths.
+    pub fn debug_index_consistent(&self) -> bool {
+        let mut spends: HashMap<String, HashSet<String>> = HashMap::new();
+        for tx in self.transactions.values() {
+            for input in &tx.inputs {
+                spends
+                    .entry(format!("{}:{}", input.txid, input.vout))
+                    .or_default()
+                    .insert(tx.txid.clone());
+            }
+        }
+        spends == self.spends
+            && self.added_at.len() == self.transactions.len()
+            && self
+                .added_at
+                .keys()
+                .all(|txid| self.transactions.contains_key(txid))
+    }
+
+    /// Pooled txids spending any outpoint `tx` spends.
+    fn conflicts_with(&self, tx: &Transaction) -> HashSet<String> {
+        tx.inputs
+            .iter()
+            .filter_map(|input| self.spends.get(&format!("{}:{}", input.txid, input.vout)))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Grow `txids` with every pooled transaction that spends, directly or
+    /// through a chain, an output of one of them.
+    fn add_descendants(&self, txids: &mut HashSet<String>) {
+        let mut queue: Vec<String> = txids.iter().cloned().collect();
+        while let Some(txid) = queue.pop() {
+            let Some(tx) = self.transactions.get(&txid) else {
+                continue;
+            };
+            for vout in 0..tx.outputs.len() {
+                if let Some(children) = self.spends.get(&format!("{}:{}", txid, vout)) {
+                    for child in children {
+                        if txids.insert(child.clone()) {
+                            queue.push(child.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Like `Transaction::calculate_fee`, but inputs may also come from
+    /// outputs of pooled transactions (a child spending its parent).
+    fn pooled_fee(&self, tx: &Transaction, utxo_set: &UTXOSet) -> u64 {
+        let input_total: u64 = tx
+            .inputs
+            .iter()
+            .filter_map(|input| match utxo_set.get_utxo(&input.txid, input.vout) {
+                Some(utxo) => Some(utxo.output.amount),
+                None => self
+                    .transactions
+                    .get(&input.txid)
+                    .and_then(|parent| parent.outputs.get(input.vout))
+                    .map(|output| output.amount),
+            })
+            .sum();
+        let output_total: u64 = tx.outputs.iter().map(|o| o.amount).sum();
+        input_total.saturating_sub(output_total)
+    }
+}
+
+/// Seconds since the Unix epoch, for mempool arrival times.
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 impl Default for Mempool {
@@ -300,6 +822,13 @@ impl Default for Mempool {
 pub struct Blockchain {
     chain: Vec<Block>,
     pub difficulty: usize,
+    // height -> expected block hash. A BTreeMap keeps heights sorted so the
+    // highest checkpoint is simply the last key.
+    checkpoints: BTreeMap<u64, String>,
+    /// Limits enforced by `add_block_validated`.
+    pub params: ConsensusParams,
+    /// Hash, txid, and address lookups; see `ChainIndex`.
+    index: ChainIndex,
 }
 
 impl Blockchain {
@@ -315,15 +844,45 @@ impl Blockchain {
         let mut genesis = Block::new(0, genesis_timestamp, vec![genesis_tx], "0".to_string());
         genesis.mine(difficulty);
 
-        Blockchain {
+        let mut chain = Blockchain {
             chain: vec![genesis],
             difficulty,
-        }
+            checkpoints: BTreeMap::new(),
+            params: ConsensusParams::default(),
+            index: ChainIndex::new(),
+        };
+        chain.index.connect(&chain.chain, 0);
+        chain
+    }
+
+    /// Create a new blockchain that enforces `params` in
+    /// `add_block_validated`.
+    pub fn with_params(difficulty: usize, genesis_timestamp: u64, params: ConsensusParams) -> Self {
+        let mut chain = Self::new(difficulty, genesis_timestamp);
+        chain.params = params;
+        chain
     }
 
     /// Add a pre-mined block to the chain.
     pub fn add_block(&mut self, block: Block) {
         self.chain.push(block);
+        self.index.connect(&self.chain, self.chain.len() - 1);
+    }
+
+    /// Check `block` against every consensus rule and, if it passes, append
+    /// it and apply it to `utxo_set`.
+    ///
+    /// On error neither the chain nor the UTXO set is changed.
+    pub fn add_block_validated(
+        &mut self,
+        block: Block,
+        utxo_set: &mut UTXOSet,
+    ) -> Result<(), BlockError> {
+        let tip = self.chain.last().expect("chain always has genesis");
+        validate_block(&block, tip, utxo_set, &self.params, self.difficulty)?;
+        apply_block_to_utxo_set(&block, utxo_set);
+        self.add_block(block);
+        Ok(())
     }
 
     /// Return the latest block.
@@ -341,36 +900,413 @@ impl Blockchain {
         self.chain.get(height)
     }
 
+    /// Find a block by its hash. O(1).
+    pub fn get_block_by_hash(&self, hash: &str) -> Option<&Block> {
+        self.index
+            .block_height(hash)
+            .map(|height| &self.chain[height as usize])
+    }
+
+    /// Find a confirmed transaction and the height of its block. O(1).
+    pub fn get_transaction(&self, txid: &str) -> Option<(&Transaction, u64)> {
+        let location = self.index.locate_transaction(txid)?;
+        let block = &self.chain[location.height as usize];
+        Some((&block.transactions[location.position], location.height))
+    }
+
+    /// Every transaction that paid `address` or spent one of its outputs,
+    /// as `(txid, height)`, oldest first. A transaction that touches the
+    /// address more than once is listed once.
+    pub fn get_transactions_for_address(&self, address: &str) -> Vec<(String, u64)> {
+        self.index.address_history(address).to_vec()
+    }
+
+    /// The lookup index kept in step with the chain.
+    pub fn index(&self) -> &ChainIndex {
+        &self.index
+    }
+
+    /// Throw the index away and rebuild it from the blocks. The result is
+    /// identical to what `add_block` built incrementally.
+    pub fn reindex(&mut self) {
+        self.index = ChainIndex::new();
+        for height in 0..self.chain.len() {
+            self.index.connect(&self.chain, height);
+        }
+    }
+
     /// Validate the entire chain: check hashes, previous_hash links, and PoW.
     pub fn is_valid(&self) -> bool {
-        let target = "0".repeat(self.difficulty);
+        self.validate(ValidationMode::Full).is_ok()
+    }
+
+    /// Replace the trusted checkpoints (height -> expected block hash).
+    pub fn set_checkpoints(&mut self, checkpoints: Vec<(u64, String)>) {
+        self.checkpoints = checkpoints.into_iter().collect();
+    }
+
+    /// Validate the chain, reporting *why* it is invalid.
+    ///
+    /// - `Full` re-checks every block: link, hash, PoW, merkle root.
+    ///   Checkpoints are ignored, exactly like `is_valid`.
+    /// - `Fast` trusts the checkpoints. Up to the highest checkpoint it only
+    ///   follows the `previous_hash` links and requires every checkpointed
+    ///   block to carry exactly the expected hash. Blocks above the highest
+    ///   checkpoint still get the full treatment.
+    ///
+    /// ## The Fast-Mode Tradeoff
+    ///
+    /// Links only prove that each block *claims* the right parent hash. A
+    /// malicious peer can ship a chain whose checkpointed blocks are genuine
+    /// but whose blocks in between contain garbage (wrong merkle root, no
+    /// real proof-of-work) with the `hash` fields left untouched. Fast mode
+    /// will accept that chain. We accept the risk because the checkpoints
+    /// come from a source we already trust (e.g. shipped with the binary),
+    /// and any later operation that re-reads those blocks (applying them to
+    /// a UTXO set, serving them to peers) must still validate what it
+    /// touches. `spot_check` is the middle ground: full validation of a
+    /// random sample of the skipped blocks.
+    pub fn validate(&self, mode: ValidationMode) -> Result<(), ValidationError> {
+        let trusted_up_to = match mode {
+            ValidationMode::Full => None,
+            ValidationMode::Fast => self.highest_reached_checkpoint(),
+        };
+
+        // Genesis has no parent to link to, but may still be checkpointed.
+        if trusted_up_to.is_some() {
+            if let Some(genesis) = self.chain.first() {
+                self.check_checkpoint(0, genesis)?;
+            }
+        }
 
         for i in 1..self.chain.len() {
             let block = &self.chain[i];
             let prev = &self.chain[i - 1];
 
-            // Check previous hash link
             if block.previous_hash != prev.hash {
-                return false;
+                return Err(ValidationError::BrokenLink { height: i as u64 });
             }
 
-            // Verify hash matches contents
-            if block.hash != block.calculate_hash() {
-                return false;
+            match trusted_up_to {
+                Some(top) if (i as u64) <= top => self.check_checkpoint(i as u64, block)?,
+                _ => self.validate_block_at(i)?,
             }
+        }
 
-            // Check proof-of-work
-            if !block.hash.starts_with(&target) {
-                return false;
-            }
+        Ok(())
+    }
+
+    /// Fully validate a random sample of the blocks fast mode skipped.
+    ///
+    /// The sub-checkpoint range is split into windows of `sample_every`
+    /// blocks and one random block per window is checked, so roughly
+    /// `1 / sample_every` of the skipped work is redone. `sample_every == 1`
+    /// checks everything.
+    pub fn spot_check(&self, sample_every: usize) -> Result<(), ValidationError> {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        self.spot_check_with_seed(sample_every, seed)
+    }
+
+    /// `spot_check` with a caller-chosen seed, so tests are reproducible.
+    pub fn spot_check_with_seed(
+        &self,
+        sample_every: usize,
+        seed: u64,
+    ) -> Result<(), ValidationError> {
+        let sample_every = sample_every.max(1);
+        let top = match self.highest_reached_checkpoint() {
+            Some(top) => top as usize,
+            None => return Ok(()), // nothing was skipped
+        };
+
+        // xorshift64: a tiny, dependency-free PRNG. Fine for sampling, not
+        // for cryptography. A zero state would stay zero forever, so nudge it.
+        let mut state = seed | 1;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        // Genesis (height 0) has no PoW requirement in this lab, so start at 1.
+        let mut window_start = 1;
+        while window_start <= top {
+            let window_len = sample_every.min(top - window_start + 1);
+            let pick = window_start + (next() % window_len as u64) as usize;
+            self.validate_block_at(pick)?;
+            window_start += sample_every;
+        }
+
+        Ok(())
+    }
+
+    /// Iterate over the blocks, genesis first.
+    pub fn iter(&self) -> std::slice::Iter<'_, Block> {
+        self.chain.iter()
+    }
+
+    /// Headers for every block, genesis first.
+    pub fn headers(&self) -> Vec<BlockHeader> {
+        self.iter().map(Block::header).collect()
+    }
+
+    /// Hashes a peer can use to find where our chains diverge.
+    ///
+    /// Like Bitcoin's block locator: the tip, then blocks 1, 2, 4, 8, ...
+    /// below it, always ending with genesis. The list stays O(log n) long
+    /// but is dense near the tip, where forks usually happen.
+    pub fn locator(&self) -> Vec<String> {
+        let tip = match self.chain.len().checked_sub(1) {
+            Some(tip) => tip,
+            None => return Vec::new(),
+        };
+
+        let mut hashes = vec![self.chain[tip].hash.clone()];
+        let mut step = 1;
+        while step <= tip {
+            hashes.push(self.chain[tip - step].hash.clone());
+            step *= 2;
+        }
+        if tip > 0 && step / 2 != tip {
+            hashes.push(self.chain[0].hash.clone());
+        }
+        hashes
+    }
+
+    /// Height of the first hash in `locator` that is also in our chain.
+    ///
+    /// Locators list the newest hashes first, so this is the highest block
+    /// both chains share (to the locator's resolution). `None` means the
+    /// chains don't even share a genesis block.
+    pub fn find_fork_point(&self, locator: &[String]) -> Option<usize> {
+        let heights: HashMap<&str, usize> = self
+            .chain
+            .iter()
+            .enumerate()
+            .map(|(height, block)| (block.hash.as_str(), height))
+            .collect();
+        locator
+            .iter()
+            .find_map(|hash| heights.get(hash.as_str()).copied())
+    }
+
+    // The highest checkpoint at or below our tip. A checkpoint above the tip
+    // vouches for blocks we don't have; trusting it would let a chain that
+    // is simply too short skip every check. Fast mode still requires each
+    // trusted checkpoint's hash to match, via `check_checkpoint`.
+    fn highest_reached_checkpoint(&self) -> Option<u64> {
+        self.checkpoints
+            .range(..self.chain.len() as u64)
+            .next_back()
+            .map(|(&height, _)| height)
+    }
+
+    // Heights are chain positions, not `block.index`: a forged block could
+    // claim any index it likes.
+    fn check_checkpoint(&self, height: u64, block: &Block) -> Result<(), ValidationError> {
+        match self.checkpoints.get(&height) {
+            Some(expected) if *expected != block.hash => Err(ValidationError::CheckpointMismatch {
+                height,
+                expected: expected.clone(),
+                actual: block.hash.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Everything except the link check: hash, PoW, and merkle root.
+    fn validate_block_at(&self, height: usize) -> Result<(), ValidationError> {
+        let block = &self.chain[height];
+        let height = height as u64;
+
+        if block.hash != block.calculate_hash() {
+            return Err(ValidationError::HashMismatch { height });
+        }
+        if !validate_proof_of_work(block, self.difficulty) {
+            return Err(ValidationError::InsufficientWork { height });
+        }
+        if !block.verify_merkle_root() {
+            return Err(ValidationError::MerkleRootMismatch { height });
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a Blockchain {
+    type Item = &'a Block;
+    type IntoIter = std::slice::Iter<'a, Block>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// How much work `Blockchain::validate` should do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Re-verify every block (the behavior of `is_valid`).
+    Full,
+    /// Trust checkpoints; only link-check blocks at or below the highest one
+    /// the chain reaches.
+    Fast,
+}
+
+/// Why a chain failed validation. Every variant names the offending height.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    BrokenLink {
+        height: u64,
+    },
+    HashMismatch {
+        height: u64,
+    },
+    InsufficientWork {
+        height: u64,
+    },
+    MerkleRootMismatch {
+        height: u64,
+    },
+    CheckpointMismatch {
+        height: u64,
+        expected: String,
+        actual: String,
+    },
+}
 
-            // Check merkle root
-            if !block.verify_merkle_root() {
-                return false;
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::BrokenLink { height } => {
+                write!(f, "block {} does not link to its parent", height)
+            }
+            ValidationError::HashMismatch { height } => {
+                write!(f, "block {} hash does not match its contents", height)
+            }
+            ValidationError::InsufficientWork { height } => {
+                write!(f, "block {} does not meet the proof-of-work target", height)
+            }
+            ValidationError::MerkleRootMismatch { height } => {
+                write!(
+                    f,
+                    "block {} merkle root does not match its transactions",
+                    height
+                )
             }
+            ValidationError::CheckpointMismatch {
+                height,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "checkpoint mismatch at height {}: expected {}, found {}",
+                height, expected, actual
+            ),
         }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+// ============================================================================
+// CHAIN INDEX
+// ============================================================================
+// Without an index, "which block holds transaction X?" means walking every
+// block. Real nodes keep a txindex on disk; ours lives in memory beside the
+// chain and is updated by `Blockchain::add_block` (which every append goes
+// through), so lookups by block hash, txid, or address are hash-map hits.
+//
+// The index stores *positions* (height, transaction number), never copies
+// of blocks. Heights are chain positions, like everywhere else in
+// `Blockchain`, not the `index` field a block claims for itself.
+//
+// Address history covers both sides of a payment: a transaction belongs to
+// an address if one of its outputs pays it, or one of its inputs spends an
+// output that paid it. Finding the owner of a spent output is itself an
+// index lookup (the input's txid), which is why blocks must be connected in
+// order. A chain that could switch forks would also need a `disconnect`
+// that undoes `connect` for each block it drops, newest first.
+
+/// Where a confirmed transaction lives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxLocation {
+    /// Chain position of the containing block.
+    pub height: u64,
+    /// Position of the transaction inside that block.
+    pub position: usize,
+}
+
+/// Lookup tables over a `Blockchain`, maintained as blocks are added.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainIndex {
+    blocks: HashMap<String, u64>,
+    transactions: HashMap<String, TxLocation>,
+    // address -> (txid, height), in the order the chain confirmed them.
+    addresses: HashMap<String, Vec<(String, u64)>>,
+}
+
+impl ChainIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Height of the block with this hash.
+    pub fn block_height(&self, hash: &str) -> Option<u64> {
+        self.blocks.get(hash).copied()
+    }
 
-        true
+    /// Where the transaction with this txid was confirmed.
+    pub fn locate_transaction(&self, txid: &str) -> Option<TxLocation> {
+        self.transactions.get(txid).copied()
+    }
+
+    /// `(txid, height)` of every transaction touching `address`, oldest first.
+    pub fn address_history(&self, address: &str) -> &[(String, u64)] {
+        self.addresses.get(address).map_or(&[], Vec::as_slice)
+    }
+
+    /// Number of indexed transactions.
+    pub fn transaction_count(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Index `chain[height]`. Every block below it must already be indexed,
+    /// so inputs can be traced back to the outputs they spend.
+    fn connect(&mut self, chain: &[Block], height: usize) {
+        let block = &chain[height];
+        let height = height as u64;
+        self.blocks.insert(block.hash.clone(), height);
+
+        for (position, tx) in block.transactions.iter().enumerate() {
+            let mut touched: Vec<&str> = Vec::new();
+            for input in &tx.inputs {
+                let spent = self.locate_transaction(&input.txid).and_then(|at| {
+                    chain[at.height as usize].transactions[at.position]
+                        .outputs
+                        .get(input.vout)
+                });
+                if let Some(spent) = spent {
+                    touched.push(&spent.address);
+                }
+            }
+            touched.extend(tx.outputs.iter().map(|o| o.address.as_str()));
+
+            let mut seen = HashSet::new();
+            for address in touched {
+                if seen.insert(address) {
+                    self.addresses
+                        .entry(address.to_string())
+                        .or_default()
+                        .push((tx.txid.clone(), height));
+                }
+            }
+            self.transactions
+                .insert(tx.txid.clone(), TxLocation { height, position });
+        }
     }
 }
 
@@ -410,29 +1346,234 @@ pub fn calculate_merkle_root(transactions: &[Transaction]) -> String {
 }
 
 // ============================================================================
-// VALIDATION HELPERS
+// ADDRESSES
 // ============================================================================
-
-/// Validate a transaction against a UTXO set.
-///
+// Output addresses are plain strings, so "bbo" instead of "bob" sends coins
+// to an address nobody owns, and nothing notices. Bitcoin's fix is
+// Base58Check: the address carries a checksum of its own contents.
+//
+//   version (1 byte) | pubkey hash (20 bytes) | checksum (4 bytes)
+//                                               = sha256(sha256(first 21))[..4]
+//   then the 25 bytes are written in base58
+//
+// A typo changes the decoded bytes, the checksum stops matching, and the
+// address is rejected before any coins move. Base58 is base64 minus the
+// look-alikes `0 O I l` and the symbols `+ /`, so addresses are easy to
+// read aloud and double-click.
+//
+// Existing code and tests use names like "alice" as addresses, so checking
+// is opt-in: see `ConsensusParams::strict_addresses`.
+
+/// Base58Check addresses: encoding, decoding, and checksum validation.
+pub mod address {
+    use sha2::{Digest, Sha256};
+    use std::fmt;
+
+    /// Version byte for addresses on this chain (Bitcoin mainnet P2PKH is 0).
+    pub const ADDRESS_VERSION: u8 = 0x00;
+
+    const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    /// version + 20-byte hash + 4-byte checksum.
+    const PAYLOAD_LEN: usize = 1 + 20 + 4;
+
+    /// Why a string is not a valid address.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum AddressError {
+        /// A character outside the base58 alphabet (`0`, `O`, `I`, `l`, ...).
+        InvalidCharacter { ch: char, position: usize },
+        /// The decoded bytes are not version + 20-byte hash + checksum.
+        InvalidLength { len: usize },
+        /// The checksum does not match: most likely a typo.
+        ChecksumMismatch,
+        /// A well-formed address for a different network or address type.
+        WrongVersion { expected: u8, found: u8 },
+    }
+
+    impl fmt::Display for AddressError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                AddressError::InvalidCharacter { ch, position } => {
+                    write!(f, "invalid base58 character {:?} at {}", ch, position)
+                }
+                AddressError::InvalidLength { len } => {
+                    write!(
+                        f,
+                        "decoded address is {} bytes, expected {}",
+                        len, PAYLOAD_LEN
+                    )
+                }
+                AddressError::ChecksumMismatch => write!(f, "address checksum does not match"),
+                AddressError::WrongVersion { expected, found } => write!(
+                    f,
+                    "address version is {:#04x}, expected {:#04x}",
+                    found, expected
+                ),
+            }
+        }
+    }
+
+    impl std::error::Error for AddressError {}
+
+    /// Encode a public-key hash as a Base58Check address.
+    pub fn encode_address(pubkey_hash: &[u8; 20], version: u8) -> String {
+        let mut payload = Vec::with_capacity(PAYLOAD_LEN);
+        payload.push(version);
+        payload.extend_from_slice(pubkey_hash);
+        let check = checksum(&payload);
+        payload.extend_from_slice(&check);
+        base58_encode(&payload)
+    }
+
+    /// Decode an address into its version byte and public-key hash,
+    /// rejecting bad characters, wrong lengths, and bad checksums.
+    pub fn decode_address(address: &str) -> Result<(u8, [u8; 20]), AddressError> {
+        let payload = base58_decode(address)?;
+        if payload.len() != PAYLOAD_LEN {
+            return Err(AddressError::InvalidLength { len: payload.len() });
+        }
+        let (body, check) = payload.split_at(PAYLOAD_LEN - 4);
+        if checksum(body) != check {
+            return Err(AddressError::ChecksumMismatch);
+        }
+        let mut hash = [0u8; 20];
+        hash.copy_from_slice(&body[1..]);
+        Ok((body[0], hash))
+    }
+
+    /// `decode_address`, plus a check that the version is `expected_version`.
+    pub fn validate_address(address: &str, expected_version: u8) -> Result<[u8; 20], AddressError> {
+        let (version, hash) = decode_address(address)?;
+        if version != expected_version {
+            return Err(AddressError::WrongVersion {
+                expected: expected_version,
+                found: version,
+            });
+        }
+        Ok(hash)
+    }
+
+    /// Derive an `ADDRESS_VERSION` address from a public key string.
+    ///
+    /// Educational shortcut: the hash is SHA-256 truncated to 20 bytes.
+    /// Bitcoin uses RIPEMD-160(SHA-256(pubkey)), which is also 20 bytes but
+    /// needs another dependency; the address format is the same either way.
+    pub fn derive_address_from_pubkey(pubkey: &str) -> String {
+        let digest = Sha256::digest(pubkey.as_bytes());
+        let mut hash = [0u8; 20];
+        hash.copy_from_slice(&digest[..20]);
+        encode_address(&hash, ADDRESS_VERSION)
+    }
+
+    /// First four bytes of SHA-256(SHA-256(data)).
+    fn checksum(data: &[u8]) -> [u8; 4] {
+        let twice = Sha256::digest(Sha256::digest(data));
+        [twice[0], twice[1], twice[2], twice[3]]
+    }
+
+    /// Write `bytes` as a base-58 number.
+    ///
+    /// A big-endian number has no room for leading zero bytes - 0x0001 and
+    /// 0x01 are the same value - so each one is written as a `1` (the
+    /// alphabet's zero digit) up front. That's why version-0 addresses
+    /// always start with `1`.
+    pub fn base58_encode(bytes: &[u8]) -> String {
+        let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+
+        // Base-58 digits, least significant first. Each input byte does
+        // `number = number * 256 + byte` on the digit array.
+        let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+        for &byte in &bytes[zeros..] {
+            let mut carry = byte as u32;
+            for digit in digits.iter_mut() {
+                carry += (*digit as u32) << 8;
+                *digit = (carry % 58) as u8;
+                carry /= 58;
+            }
+            while carry > 0 {
+                digits.push((carry % 58) as u8);
+                carry /= 58;
+            }
+        }
+
+        let mut out = String::with_capacity(zeros + digits.len());
+        out.extend(std::iter::repeat('1').take(zeros));
+        out.extend(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char));
+        out
+    }
+
+    /// Reverse `base58_encode`: each leading `1` becomes a zero byte.
+    pub fn base58_decode(input: &str) -> Result<Vec<u8>, AddressError> {
+        let zeros = input.chars().take_while(|&c| c == '1').count();
+
+        // Bytes, least significant first: `number = number * 58 + digit`.
+        let mut bytes: Vec<u8> = Vec::with_capacity(input.len());
+        for (position, ch) in input.chars().enumerate().skip(zeros) {
+            let value = ALPHABET
+                .iter()
+                .position(|&a| a as char == ch)
+                .ok_or(AddressError::InvalidCharacter { ch, position })?;
+            let mut carry = value as u32;
+            for byte in bytes.iter_mut() {
+                carry += *byte as u32 * 58;
+                *byte = carry as u8;
+                carry >>= 8;
+            }
+            while carry > 0 {
+                bytes.push(carry as u8);
+                carry >>= 8;
+            }
+        }
+
+        let mut out = vec![0u8; zeros];
+        out.extend(bytes.iter().rev());
+        Ok(out)
+    }
+}
+
+// ============================================================================
+// VALIDATION HELPERS
+// ============================================================================
+
+/// Validate a transaction against a UTXO set.
+///
 /// Checks:
 /// - All inputs reference existing UTXOs
+/// - No input is listed twice
 /// - All inputs have non-empty signatures
+/// - Coinbase outputs being spent are at least `COINBASE_MATURITY` blocks
+///   old at `current_height` (the height of the block that would include
+///   this transaction)
 /// - Total outputs do not exceed total inputs
 /// - Fee meets minimum threshold
 pub fn validate_transaction(
     tx: &Transaction,
     utxo_set: &UTXOSet,
     min_fee: u64,
+    current_height: u64,
 ) -> Result<(), String> {
     let mut input_total = 0u64;
+    let mut seen = HashSet::new();
 
     for input in &tx.inputs {
+        // Listing an outpoint twice would count its value twice.
+        if !seen.insert((input.txid.as_str(), input.vout)) {
+            return Err(format!("Duplicate input: {}:{}", input.txid, input.vout));
+        }
         match utxo_set.get_utxo(&input.txid, input.vout) {
             Some(utxo) => {
                 if input.signature.is_empty() {
                     return Err("Invalid signature".to_string());
                 }
+                if utxo.is_coinbase && current_height < utxo.height + COINBASE_MATURITY {
+                    return Err(format!(
+                        "Immature coinbase spend: {}:{} created at height {}, spendable at {}",
+                        input.txid,
+                        input.vout,
+                        utxo.height,
+                        utxo.height + COINBASE_MATURITY
+                    ));
+                }
                 input_total += utxo.output.amount;
             }
             None => {
@@ -455,26 +1596,901 @@ pub fn validate_transaction(
     Ok(())
 }
 
+/// `validate_transaction`, plus the optional rules in `params`.
+///
+/// With `params.strict_addresses`, every output address must be a valid
+/// `address::ADDRESS_VERSION` Base58Check address.
+pub fn validate_transaction_with(
+    tx: &Transaction,
+    utxo_set: &UTXOSet,
+    min_fee: u64,
+    current_height: u64,
+    params: &ConsensusParams,
+) -> Result<(), String> {
+    validate_transaction(tx, utxo_set, min_fee, current_height)?;
+    if params.strict_addresses {
+        if let Some((output, err)) = first_bad_address(tx) {
+            return Err(format!("Invalid address {}: {}", output.address, err));
+        }
+    }
+    Ok(())
+}
+
+/// The first output whose address doesn't validate, with the reason.
+fn first_bad_address(tx: &Transaction) -> Option<(&TxOutput, address::AddressError)> {
+    tx.outputs.iter().find_map(|output| {
+        address::validate_address(&output.address, address::ADDRESS_VERSION)
+            .err()
+            .map(|err| (output, err))
+    })
+}
+
 /// Validate a block's proof-of-work.
 pub fn validate_proof_of_work(block: &Block, difficulty: usize) -> bool {
     let target = "0".repeat(difficulty);
     block.hash.starts_with(&target)
 }
 
-/// Update a UTXO set given a newly confirmed block.
+/// Update a UTXO set given a newly confirmed block. New outputs are tagged
+/// with `block.index` as their creation height.
 pub fn apply_block_to_utxo_set(block: &Block, utxo_set: &mut UTXOSet) {
-    // Remove spent UTXOs
+    apply_block_with_undo(block, utxo_set);
+}
+
+/// What `revert_block_from_utxo_set` needs to undo a block: the UTXOs it
+/// spent. A block only names the outpoints it spends, not their amounts or
+/// owners, so without this record they would be gone for good.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockUndo {
+    /// UTXOs the block removed, in the order it removed them.
+    pub spent: Vec<UTXO>,
+}
+
+/// Like `apply_block_to_utxo_set`, but also returns the undo record.
+///
+/// Transactions are applied one at a time, inputs then outputs, the same
+/// way `validate_block` checks them. Removing every input of the block
+/// first would leave an output that is created and spent within the block
+/// in the set afterwards, spendable a second time.
+pub fn apply_block_with_undo(block: &Block, utxo_set: &mut UTXOSet) -> BlockUndo {
+    let mut undo = BlockUndo::default();
+
     for tx in &block.transactions {
         for input in &tx.inputs {
+            if let Some(utxo) = utxo_set.get_utxo(&input.txid, input.vout) {
+                undo.spent.push(utxo.clone());
+            }
             utxo_set.remove_utxo(&input.txid, input.vout);
         }
+        for (idx, output) in tx.outputs.iter().enumerate() {
+            utxo_set.add_utxo_at(
+                tx.txid.clone(),
+                idx,
+                output.clone(),
+                block.index,
+                tx.is_coinbase(),
+            );
+        }
     }
+    undo
+}
 
-    // Add new UTXOs
+/// Undo `apply_block_with_undo`: drop the block's outputs and restore what
+/// it spent. Blocks must be reverted newest first, each with its own undo
+/// record.
+///
+/// Outputs the block both created and spent are in `undo.spent` too; they
+/// didn't exist before the block, so they are not restored.
+pub fn revert_block_from_utxo_set(block: &Block, undo: &BlockUndo, utxo_set: &mut UTXOSet) {
+    let created: HashSet<&str> = block
+        .transactions
+        .iter()
+        .map(|tx| tx.txid.as_str())
+        .collect();
     for tx in &block.transactions {
-        for (idx, output) in tx.outputs.iter().enumerate() {
-            utxo_set.add_utxo(tx.txid.clone(), idx, output.clone());
+        for idx in 0..tx.outputs.len() {
+            utxo_set.remove_utxo(&tx.txid, idx);
+        }
+    }
+    for utxo in &undo.spent {
+        if created.contains(utxo.txid.as_str()) {
+            continue;
+        }
+        utxo_set.add_utxo_at(
+            utxo.txid.clone(),
+            utxo.vout,
+            utxo.output.clone(),
+            utxo.height,
+            utxo.is_coinbase,
+        );
+    }
+}
+
+// ============================================================================
+// CONSENSUS RULES
+// ============================================================================
+// `validate_transaction` checks one transaction in isolation. A node
+// receiving a whole block from a peer must also check the block itself:
+// that it was mined, that it builds on our tip, that the coinbase is where
+// it belongs, and that it stays within size limits. Without limits, a
+// single (validly mined!) block with a million transactions or outputs
+// would be enough to exhaust every node's memory.
+
+/// Base units per coin.
+pub const COIN: u64 = 100_000_000;
+
+/// Subsidy of the first block, before any halving.
+pub const INITIAL_SUBSIDY: u64 = 50 * COIN;
+
+/// Blocks between subsidy halvings (210,000 in Bitcoin; small here so the
+/// schedule is visible in a demo).
+pub const SUBSIDY_HALVING_INTERVAL: u64 = 210;
+
+/// Blocks a coinbase output must wait before it can be spent. A reorg can
+/// orphan the block that created it; waiting keeps such coins from having
+/// already been passed on.
+pub const COINBASE_MATURITY: u64 = 10;
+
+/// New coins a block at `height` may create: `INITIAL_SUBSIDY`, halved
+/// every `SUBSIDY_HALVING_INTERVAL` blocks, reaching zero after 64
+/// halvings.
+pub fn block_subsidy(height: u64) -> u64 {
+    let halvings = height / SUBSIDY_HALVING_INTERVAL;
+    if halvings >= 64 {
+        return 0;
+    }
+    INITIAL_SUBSIDY >> halvings
+}
+
+/// Limits every node agrees to enforce on incoming blocks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsensusParams {
+    /// Most transactions a block may hold, coinbase included.
+    pub max_block_txs: usize,
+    /// Most inputs a single transaction may spend.
+    pub max_tx_inputs: usize,
+    /// Most outputs a single transaction may create.
+    pub max_tx_outputs: usize,
+    /// Largest amount any single output may carry.
+    pub max_money: u64,
+    /// Require every output address, coinbase included, to be a valid
+    /// Base58Check address (see the `address` module). Off by default so
+    /// chains that use plain names as addresses keep working.
+    pub strict_addresses: bool,
+}
+
+impl Default for ConsensusParams {
+    fn default() -> Self {
+        ConsensusParams {
+            max_block_txs: 1_000,
+            max_tx_inputs: 100,
+            max_tx_outputs: 100,
+            max_money: 21_000_000 * 100_000_000,
+            strict_addresses: false,
+        }
+    }
+}
+
+/// The consensus rule a block broke.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlockError {
+    TooManyTransactions {
+        count: usize,
+        max: usize,
+    },
+    HashMismatch,
+    InsufficientWork,
+    MerkleRootMismatch,
+    PreviousHashMismatch {
+        expected: String,
+        actual: String,
+    },
+    /// The block's index is not one past its parent's. The subsidy and
+    /// coinbase maturity are both computed from the index, so it can't be
+    /// taken on trust.
+    IndexMismatch {
+        expected: u64,
+        actual: u64,
+    },
+    /// The block is empty or its first transaction has inputs.
+    MissingCoinbase,
+    /// A transaction other than the first has no inputs.
+    MultipleCoinbase {
+        index: usize,
+    },
+    TooManyInputs {
+        txid: String,
+        count: usize,
+        max: usize,
+    },
+    TooManyOutputs {
+        txid: String,
+        count: usize,
+        max: usize,
+    },
+    OutputExceedsMaxMoney {
+        txid: String,
+        amount: u64,
+        max: u64,
+    },
+    /// With `strict_addresses`, an output address failed to decode.
+    InvalidAddress {
+        txid: String,
+        address: String,
+        reason: address::AddressError,
+    },
+    /// `validate_transaction` rejected a non-coinbase transaction.
+    InvalidTransaction {
+        txid: String,
+        reason: String,
+    },
+    /// The coinbase pays out more than the subsidy plus the block's fees.
+    CoinbaseOverclaim {
+        claimed: u64,
+        allowed: u64,
+    },
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockError::TooManyTransactions { count, max } => {
+                write!(f, "block has {} transactions, limit is {}", count, max)
+            }
+            BlockError::HashMismatch => write!(f, "block hash does not match its contents"),
+            BlockError::InsufficientWork => {
+                write!(f, "block does not meet the proof-of-work target")
+            }
+            BlockError::MerkleRootMismatch => {
+                write!(f, "block merkle root does not match its transactions")
+            }
+            BlockError::PreviousHashMismatch { expected, actual } => {
+                write!(f, "block builds on {}, but the tip is {}", actual, expected)
+            }
+            BlockError::IndexMismatch { expected, actual } => {
+                write!(f, "block claims index {}, expected {}", actual, expected)
+            }
+            BlockError::MissingCoinbase => {
+                write!(f, "first transaction in the block is not a coinbase")
+            }
+            BlockError::MultipleCoinbase { index } => {
+                write!(f, "transaction {} is a second coinbase", index)
+            }
+            BlockError::TooManyInputs { txid, count, max } => write!(
+                f,
+                "transaction {} has {} inputs, limit is {}",
+                txid, count, max
+            ),
+            BlockError::TooManyOutputs { txid, count, max } => write!(
+                f,
+                "transaction {} has {} outputs, limit is {}",
+                txid, count, max
+            ),
+            BlockError::OutputExceedsMaxMoney { txid, amount, max } => write!(
+                f,
+                "transaction {} has an output of {}, limit is {}",
+                txid, amount, max
+            ),
+            BlockError::InvalidAddress {
+                txid,
+                address,
+                reason,
+            } => write!(
+                f,
+                "transaction {} pays to invalid address {}: {}",
+                txid, address, reason
+            ),
+            BlockError::InvalidTransaction { txid, reason } => {
+                write!(f, "transaction {} is invalid: {}", txid, reason)
+            }
+            BlockError::CoinbaseOverclaim { claimed, allowed } => write!(
+                f,
+                "coinbase claims {}, but subsidy plus fees is {}",
+                claimed, allowed
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BlockError {}
+
+/// Check the per-transaction limits in `params`: input and output counts,
+/// the largest output, and with `strict_addresses` the output addresses.
+///
+/// These need no UTXO set, so nodes run them before pooling a transaction
+/// as well as on every transaction in a block.
+pub fn check_transaction_limits(
+    tx: &Transaction,
+    params: &ConsensusParams,
+) -> Result<(), BlockError> {
+    if tx.inputs.len() > params.max_tx_inputs {
+        return Err(BlockError::TooManyInputs {
+            txid: tx.txid.clone(),
+            count: tx.inputs.len(),
+            max: params.max_tx_inputs,
+        });
+    }
+    if tx.outputs.len() > params.max_tx_outputs {
+        return Err(BlockError::TooManyOutputs {
+            txid: tx.txid.clone(),
+            count: tx.outputs.len(),
+            max: params.max_tx_outputs,
+        });
+    }
+    if let Some(output) = tx.outputs.iter().find(|o| o.amount > params.max_money) {
+        return Err(BlockError::OutputExceedsMaxMoney {
+            txid: tx.txid.clone(),
+            amount: output.amount,
+            max: params.max_money,
+        });
+    }
+    if params.strict_addresses {
+        if let Some((output, reason)) = first_bad_address(tx) {
+            return Err(BlockError::InvalidAddress {
+                txid: tx.txid.clone(),
+                address: output.address.clone(),
+                reason,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Check every consensus rule for `block` as the successor of `prev_block`.
+///
+/// Cheap structural checks run first so an oversized block is rejected
+/// before any hashing. Transactions are validated in order against a
+/// scratch copy of `utxo_set` that is updated as we go, so a transaction may
+/// spend an output created earlier in the same block, and two transactions
+/// spending the same output are caught. Fees are policy, not consensus, so
+/// no minimum fee is required here. The fees collected along the way cap
+/// what the coinbase may claim: `block_subsidy(block.index)` plus fees.
+pub fn validate_block(
+    block: &Block,
+    prev_block: &Block,
+    utxo_set: &UTXOSet,
+    params: &ConsensusParams,
+    difficulty: usize,
+) -> Result<(), BlockError> {
+    if block.transactions.len() > params.max_block_txs {
+        return Err(BlockError::TooManyTransactions {
+            count: block.transactions.len(),
+            max: params.max_block_txs,
+        });
+    }
+
+    if block.hash != block.calculate_hash() {
+        return Err(BlockError::HashMismatch);
+    }
+    if !validate_proof_of_work(block, difficulty) {
+        return Err(BlockError::InsufficientWork);
+    }
+    if block.previous_hash != prev_block.hash {
+        return Err(BlockError::PreviousHashMismatch {
+            expected: prev_block.hash.clone(),
+            actual: block.previous_hash.clone(),
+        });
+    }
+    if block.index != prev_block.index + 1 {
+        return Err(BlockError::IndexMismatch {
+            expected: prev_block.index + 1,
+            actual: block.index,
+        });
+    }
+    if !block.verify_merkle_root() {
+        return Err(BlockError::MerkleRootMismatch);
+    }
+
+    match block.transactions.first() {
+        Some(tx) if tx.is_coinbase() => {}
+        _ => return Err(BlockError::MissingCoinbase),
+    }
+    if let Some(index) = block
+        .transactions
+        .iter()
+        .skip(1)
+        .position(Transaction::is_coinbase)
+    {
+        return Err(BlockError::MultipleCoinbase { index: index + 1 });
+    }
+
+    for tx in &block.transactions {
+        check_transaction_limits(tx, params)?;
+    }
+
+    let mut scratch = utxo_set.clone();
+    let mut fees = 0u64;
+    for tx in &block.transactions {
+        if !tx.is_coinbase() {
+            validate_transaction(tx, &scratch, 0, block.index).map_err(|reason| {
+                BlockError::InvalidTransaction {
+                    txid: tx.txid.clone(),
+                    reason,
+                }
+            })?;
+            fees += tx.calculate_fee(&scratch);
+        }
+        for input in &tx.inputs {
+            scratch.remove_utxo(&input.txid, input.vout);
+        }
+        for (vout, output) in tx.outputs.iter().enumerate() {
+            scratch.add_utxo_at(
+                tx.txid.clone(),
+                vout,
+                output.clone(),
+                block.index,
+                tx.is_coinbase(),
+            );
+        }
+    }
+
+    let claimed: u64 = block.transactions[0].outputs.iter().map(|o| o.amount).sum();
+    let allowed = block_subsidy(block.index) + fees;
+    if claimed > allowed {
+        return Err(BlockError::CoinbaseOverclaim { claimed, allowed });
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// P2P GOSSIP
+// ============================================================================
+// Nodes don't push whole blocks at each other. They announce what they have
+// (`Inv`) and a peer that lacks it asks for it (`GetData`), so each block
+// crosses each link about once. A block whose parent we've never seen is
+// parked as an orphan while we send the announcer our locator
+// (`GetHeaders`); the headers it returns name every block we're missing.
+//
+// Announcements are cheap, so a node that hears nothing new just announces
+// its tip again. That repetition is the only retransmission there is: a
+// lost `GetData` or `BlockMsg` is repaired by the next `Inv`.
+//
+// The simulation is synchronous and deterministic. `Network::step` delivers
+// the messages sent during the previous round, and message loss comes from
+// a seeded PRNG, so a test sees the same losses every run. There is no fork
+// choice: a node only accepts blocks on its own tip, so the network
+// converges as long as blocks are mined one at a time.
+
+/// Most orphan blocks a node keeps while it fetches their ancestors.
+pub const MAX_ORPHANS: usize = 100;
+
+/// A message between two simulated nodes.
+#[derive(Clone, Debug)]
+pub enum NetMessage {
+    /// "I have these" (block hashes or txids).
+    Inv(Vec<String>),
+    /// "Send me these."
+    GetData(Vec<String>),
+    BlockMsg(Block),
+    TxMsg(Transaction),
+    /// "Send the headers after the newest of these blocks you also have."
+    GetHeaders {
+        locator: Vec<String>,
+    },
+    Headers(Vec<BlockHeader>),
+}
+
+/// Who an outgoing message is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Peer(usize),
+    /// Every node except the sender.
+    Broadcast,
+}
+
+/// One node: a chain, the UTXO set it implies, and a mempool.
+pub struct SimNode {
+    id: usize,
+    chain: Blockchain,
+    utxo_set: UTXOSet,
+    mempool: Mempool,
+    // Blocks whose parent we don't have yet, by hash.
+    orphans: HashMap<String, Block>,
+}
+
+impl SimNode {
+    /// A node at genesis. Nodes built with the same difficulty and genesis
+    /// timestamp share a genesis block.
+    pub fn new(id: usize, difficulty: usize, genesis_timestamp: u64) -> Self {
+        let chain = Blockchain::new(difficulty, genesis_timestamp);
+        let mut utxo_set = UTXOSet::new();
+        for block in &chain {
+            apply_block_to_utxo_set(block, &mut utxo_set);
+        }
+        SimNode {
+            id,
+            chain,
+            utxo_set,
+            mempool: Mempool::new(),
+            orphans: HashMap::new(),
+        }
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn chain(&self) -> &Blockchain {
+        &self.chain
+    }
+
+    pub fn utxo_set(&self) -> &UTXOSet {
+        &self.utxo_set
+    }
+
+    pub fn mempool(&self) -> &Mempool {
+        &self.mempool
+    }
+
+    /// Hash of the last block on our chain.
+    pub fn tip_hash(&self) -> &str {
+        &self.tip().hash
+    }
+
+    /// Whether `hash` is a block we have (connected or orphaned) or a
+    /// transaction we have (pooled or confirmed).
+    pub fn knows(&self, hash: &str) -> bool {
+        self.chain.get_block_by_hash(hash).is_some()
+            || self.orphans.contains_key(hash)
+            || self.mempool.contains(hash)
+            || self.chain.get_transaction(hash).is_some()
+    }
+
+    /// Mine a block on our tip and connect it.
+    ///
+    /// Pooled transactions are taken oldest first while they stay valid
+    /// against the outputs taken so far; the coinbase pays `miner` the
+    /// subsidy plus their fees. A pooled transaction that breaks a
+    /// consensus limit could never be mined, so it is evicted.
+    pub fn mine_block(&mut self, miner: &str, timestamp: u64) -> Block {
+        let height = self.chain.height() as u64;
+        let mut pooled = self.mempool.select_transactions();
+        pooled.sort_by(|a, b| (a.timestamp, &a.txid).cmp(&(b.timestamp, &b.txid)));
+
+        let mut scratch = self.utxo_set.clone();
+        let mut fees = 0;
+        let mut transactions = Vec::new();
+        for tx in pooled {
+            if transactions.len() + 1 >= self.chain.params.max_block_txs {
+                break;
+            }
+            if check_transaction_limits(&tx, &self.chain.params).is_err() {
+                self.mempool.remove_transaction(&tx.txid);
+                continue;
+            }
+            if validate_transaction(&tx, &scratch, 0, height).is_err() {
+                continue;
+            }
+            fees += tx.calculate_fee(&scratch);
+            for input in &tx.inputs {
+                scratch.remove_utxo(&input.txid, input.vout);
+            }
+            for (vout, output) in tx.outputs.iter().enumerate() {
+                scratch.add_utxo_at(tx.txid.clone(), vout, output.clone(), height, false);
+            }
+            transactions.push(tx);
+        }
+
+        let coinbase = Transaction::coinbase(
+            miner.to_string(),
+            block_subsidy(height) + fees,
+            timestamp,
+            format!("coinbase-{}-{}", height, miner),
+        );
+        transactions.insert(0, coinbase);
+
+        let mut block = Block::new(height, timestamp, transactions, self.tip().hash.clone());
+        block.mine(self.chain.difficulty);
+        self.connect(block.clone())
+            .expect("a block we assembled ourselves is valid");
+        block
+    }
+
+    /// Our tip and pooled txids, for re-announcing to peers.
+    pub fn announce(&self) -> NetMessage {
+        let mut txids: Vec<String> = self
+            .mempool
+            .select_transactions()
+            .into_iter()
+            .map(|tx| tx.txid)
+            .collect();
+        txids.sort();
+        txids.insert(0, self.tip_hash().to_string());
+        NetMessage::Inv(txids)
+    }
+
+    /// Process one message from node `from` and return what to send in
+    /// response. Invalid blocks and transactions are dropped silently.
+    pub fn handle(&mut self, from: usize, message: NetMessage) -> Vec<(Target, NetMessage)> {
+        let reply = |message| vec![(Target::Peer(from), message)];
+        match message {
+            NetMessage::Inv(hashes) => {
+                let mut out = Vec::new();
+                // We have the block but are still missing its ancestors.
+                if hashes.iter().any(|hash| self.orphans.contains_key(hash)) {
+                    out.extend(reply(self.get_headers()));
+                }
+                let wanted = self.unknown(hashes);
+                if !wanted.is_empty() {
+                    out.extend(reply(NetMessage::GetData(wanted)));
+                }
+                out
+            }
+            NetMessage::GetData(hashes) => hashes
+                .iter()
+                .filter_map(|hash| {
+                    if let Some(block) = self.chain.get_block_by_hash(hash) {
+                        Some(NetMessage::BlockMsg(block.clone()))
+                    } else {
+                        self.mempool.get(hash).cloned().map(NetMessage::TxMsg)
+                    }
+                })
+                .map(|message| (Target::Peer(from), message))
+                .collect(),
+            NetMessage::BlockMsg(block) => self.receive_block(from, block),
+            NetMessage::TxMsg(tx) => self.receive_transaction(tx),
+            NetMessage::GetHeaders { locator } => {
+                let headers = match self.chain.find_fork_point(&locator) {
+                    Some(fork) => self
+                        .chain
+                        .iter()
+                        .skip(fork + 1)
+                        .map(Block::header)
+                        .collect(),
+                    None => Vec::new(),
+                };
+                if headers.is_empty() {
+                    Vec::new()
+                } else {
+                    reply(NetMessage::Headers(headers))
+                }
+            }
+            NetMessage::Headers(headers) => {
+                let wanted = self.unknown(headers.into_iter().map(|header| header.hash).collect());
+                if wanted.is_empty() {
+                    Vec::new()
+                } else {
+                    reply(NetMessage::GetData(wanted))
+                }
+            }
+        }
+    }
+
+    fn tip(&self) -> &Block {
+        self.chain
+            .get_latest_block()
+            .expect("chain always has genesis")
+    }
+
+    fn get_headers(&self) -> NetMessage {
+        NetMessage::GetHeaders {
+            locator: self.chain.locator(),
+        }
+    }
+
+    fn unknown(&self, hashes: Vec<String>) -> Vec<String> {
+        hashes
+            .into_iter()
+            .filter(|hash| !self.knows(hash))
+            .collect()
+    }
+
+    fn receive_block(&mut self, from: usize, block: Block) -> Vec<(Target, NetMessage)> {
+        if self.knows(&block.hash) {
+            return Vec::new();
+        }
+        if block.previous_hash != self.tip().hash {
+            // A block on an older part of our chain is a fork we don't follow.
+            if self.chain.get_block_by_hash(&block.previous_hash).is_some()
+                || self.orphans.len() >= MAX_ORPHANS
+            {
+                return Vec::new();
+            }
+            self.orphans.insert(block.hash.clone(), block);
+            return vec![(Target::Peer(from), self.get_headers())];
+        }
+
+        if self.connect(block).is_err() {
+            return Vec::new();
+        }
+        // The new tip may be the parent an orphan was waiting for.
+        while let Some(hash) = self
+            .orphans
+            .iter()
+            .find(|(_, orphan)| orphan.previous_hash == self.tip().hash)
+            .map(|(hash, _)| hash.clone())
+        {
+            if let Some(orphan) = self.orphans.remove(&hash) {
+                if self.connect(orphan).is_err() {
+                    break;
+                }
+            }
+        }
+        vec![(
+            Target::Broadcast,
+            NetMessage::Inv(vec![self.tip_hash().to_string()]),
+        )]
+    }
+
+    /// Pool transactions that spend confirmed outputs and fit the consensus
+    /// limits. One spending another pooled transaction is rejected; it'll
+    /// be announced again.
+    fn receive_transaction(&mut self, tx: Transaction) -> Vec<(Target, NetMessage)> {
+        let height = self.chain.height() as u64;
+        if self.knows(&tx.txid)
+            || check_transaction_limits(&tx, &self.chain.params).is_err()
+            || validate_transaction(&tx, &self.utxo_set, 0, height).is_err()
+        {
+            return Vec::new();
+        }
+        let txid = tx.txid.clone();
+        self.mempool.add_transaction(tx);
+        vec![(Target::Broadcast, NetMessage::Inv(vec![txid]))]
+    }
+
+    fn connect(&mut self, block: Block) -> Result<(), BlockError> {
+        let txids: Vec<String> = block
+            .transactions
+            .iter()
+            .map(|tx| tx.txid.clone())
+            .collect();
+        self.chain.add_block_validated(block, &mut self.utxo_set)?;
+        for txid in &txids {
+            self.mempool.remove_transaction(txid);
+        }
+        Ok(())
+    }
+}
+
+/// N `SimNode`s, fully connected by mpsc channels, with message delivery
+/// stepped round by round.
+pub struct Network {
+    nodes: Vec<SimNode>,
+    // One channel per node carrying (sender id, message).
+    outboxes: Vec<Sender<(usize, NetMessage)>>,
+    inboxes: Vec<Receiver<(usize, NetMessage)>>,
+    loss_percent: u8,
+    rng_state: u64,
+    sent: usize,
+    dropped: usize,
+}
+
+impl Network {
+    /// `size` nodes sharing a genesis block, with no message loss.
+    pub fn new(size: usize, difficulty: usize, genesis_timestamp: u64) -> Self {
+        let (outboxes, inboxes) = (0..size).map(|_| mpsc::channel()).unzip();
+        Network {
+            nodes: (0..size)
+                .map(|id| SimNode::new(id, difficulty, genesis_timestamp))
+                .collect(),
+            outboxes,
+            inboxes,
+            loss_percent: 0,
+            rng_state: 1,
+            sent: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Drop `percent`% of messages (capped at 100), chosen by a PRNG seeded
+    /// with `seed`.
+    pub fn with_message_loss(mut self, percent: u8, seed: u64) -> Self {
+        self.loss_percent = percent.min(100);
+        // Same xorshift64 as `Blockchain::spot_check_with_seed`.
+        self.rng_state = seed | 1;
+        self
+    }
+
+    pub fn node(&self, id: usize) -> &SimNode {
+        &self.nodes[id]
+    }
+
+    pub fn nodes(&self) -> &[SimNode] {
+        &self.nodes
+    }
+
+    /// Messages handed to the network so far, including dropped ones.
+    pub fn messages_sent(&self) -> usize {
+        self.sent
+    }
+
+    pub fn messages_dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Mine a block on node `id` and announce it. Returns its hash.
+    pub fn mine_block(&mut self, id: usize, miner: &str, timestamp: u64) -> String {
+        let block = self.nodes[id].mine_block(miner, timestamp);
+        self.dispatch(
+            id,
+            vec![(Target::Broadcast, NetMessage::Inv(vec![block.hash.clone()]))],
+        );
+        block.hash
+    }
+
+    /// Hand `tx` to node `id` as if a wallet had submitted it there.
+    pub fn submit_transaction(&mut self, id: usize, tx: Transaction) {
+        let out = self.nodes[id].handle(id, NetMessage::TxMsg(tx));
+        self.dispatch(id, out);
+    }
+
+    /// Deliver every message sent before this call, node by node in order.
+    /// Replies go out for the next round. Returns how many were delivered.
+    pub fn step(&mut self) -> usize {
+        let pending: Vec<Vec<(usize, NetMessage)>> = self
+            .inboxes
+            .iter()
+            .map(|inbox| inbox.try_iter().collect())
+            .collect();
+        let mut delivered = 0;
+        for (id, messages) in pending.into_iter().enumerate() {
+            for (from, message) in messages {
+                delivered += 1;
+                let out = self.nodes[id].handle(from, message);
+                self.dispatch(id, out);
+            }
+        }
+        delivered
+    }
+
+    /// Every node re-announces its tip and mempool to every peer.
+    pub fn rebroadcast(&mut self) {
+        for id in 0..self.nodes.len() {
+            let announcement = self.nodes[id].announce();
+            self.dispatch(id, vec![(Target::Broadcast, announcement)]);
+        }
+    }
+
+    /// Whether every node has the same tip.
+    pub fn is_converged(&self) -> bool {
+        self.nodes
+            .windows(2)
+            .all(|pair| pair[0].tip_hash() == pair[1].tip_hash())
+    }
+
+    /// Step until every node has the same tip, rebroadcasting whenever a
+    /// round delivers nothing. Returns the rounds taken, or `None` if the
+    /// nodes still disagree after `max_rounds`.
+    pub fn run_until_converged(&mut self, max_rounds: usize) -> Option<usize> {
+        for round in 0..=max_rounds {
+            if self.is_converged() {
+                return Some(round);
+            }
+            if round < max_rounds && self.step() == 0 {
+                self.rebroadcast();
+            }
+        }
+        None
+    }
+
+    fn dispatch(&mut self, from: usize, out: Vec<(Target, NetMessage)>) {
+        for (target, message) in out {
+            let recipients: Vec<usize> = match target {
+                Target::Peer(to) => vec![to],
+                Target::Broadcast => (0..self.nodes.len()).filter(|&to| to != from).collect(),
+            };
+            for to in recipients {
+                self.sent += 1;
+                if self.should_drop() {
+                    self.dropped += 1;
+                    continue;
+                }
+                self.outboxes[to]
+                    .send((from, message.clone()))
+                    .expect("inboxes live as long as the network");
+            }
+        }
+    }
+
+    fn should_drop(&mut self) -> bool {
+        if self.loss_percent == 0 {
+            return false;
         }
+        self.rng_state ^= self.rng_state << 13;
+        self.rng_state ^= self.rng_state >> 7;
+        self.rng_state ^= self.rng_state << 17;
+        self.rng_state % 100 < self.loss_percent as u64
     }
 }
 
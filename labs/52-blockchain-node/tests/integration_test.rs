@@ -286,6 +286,191 @@ fn test_mempool_select_transactions() {
     assert_eq!(selected.len(), 5);
 }
 
+// ============================================================================
+// MEMPOOL REPLACE-BY-FEE AND EXPIRY TESTS
+// ============================================================================
+
+/// Alice owns two 1000-unit outputs of "funding".
+fn rbf_utxos() -> UTXOSet {
+    let mut set = UTXOSet::new();
+    set.add_utxo("funding".into(), 0, output("Alice", 1000));
+    set.add_utxo("funding".into(), 1, output("Alice", 1000));
+    set
+}
+
+/// Spends `funding:vout` for each vout, paying `fee` in total.
+fn pay(vouts: &[usize], to: &str, fee: u64, timestamp: u64) -> Transaction {
+    let inputs = vouts
+        .iter()
+        .map(|&vout| TxInput {
+            txid: "funding".into(),
+            vout,
+            signature: "sig".into(),
+        })
+        .collect();
+    let total = 1000 * vouts.len() as u64;
+    Transaction::new(inputs, vec![output(to, total - fee)], timestamp)
+}
+
+#[test]
+fn test_rbf_without_conflict_just_adds() {
+    let utxos = rbf_utxos();
+    let mut mempool = Mempool::new();
+    let tx = pay(&[0], "Bob", 10, 1);
+    let txid = tx.txid.clone();
+
+    assert_eq!(
+        mempool.add_transaction_rbf(tx.clone(), &utxos, 50),
+        Ok(vec![])
+    );
+    assert!(mempool.contains(&txid));
+    assert_eq!(
+        mempool.add_transaction_rbf(tx, &utxos, 50),
+        Err(MempoolError::AlreadyPooled(txid))
+    );
+    assert!(mempool.debug_index_consistent());
+}
+
+#[test]
+fn test_rbf_evicts_two_conflicting_transactions() {
+    let utxos = rbf_utxos();
+    let mut mempool = Mempool::new();
+    let first = pay(&[0], "Bob", 10, 1);
+    let second = pay(&[1], "Carol", 20, 2);
+    mempool.add_transaction(first.clone());
+    mempool.add_transaction(second.clone());
+
+    // Spends both outpoints: must beat 10 + 20 by the bump of 50
+    let replacement = pay(&[0, 1], "Dave", 80, 3);
+    let mut expected = vec![first.txid.clone(), second.txid.clone()];
+    expected.sort();
+
+    assert_eq!(
+        mempool.add_transaction_rbf(replacement.clone(), &utxos, 50),
+        Ok(expected)
+    );
+    assert_eq!(mempool.size(), 1);
+    assert!(mempool.contains(&replacement.txid));
+    assert!(!mempool.contains(&first.txid) && !mempool.contains(&second.txid));
+    assert!(mempool.debug_index_consistent());
+}
+
+#[test]
+fn test_rbf_insufficient_bump_rejected() {
+    let utxos = rbf_utxos();
+    let mut mempool = Mempool::new();
+    let original = pay(&[0], "Bob", 10, 1);
+    mempool.add_transaction(original.clone());
+
+    // Higher fee, but not by the full bump
+    let replacement = pay(&[0], "Bob", 59, 2);
+    assert_eq!(
+        mempool.add_transaction_rbf(replacement.clone(), &utxos, 50),
+        Err(MempoolError::InsufficientFee {
+            fee: 59,
+            required: 60
+        })
+    );
+    assert_eq!(mempool.size(), 1);
+    assert!(mempool.contains(&original.txid));
+    assert!(!mempool.contains(&replacement.txid));
+    assert!(mempool.debug_index_consistent());
+
+    // Exactly the bump is enough
+    let replacement = pay(&[0], "Bob", 60, 3);
+    assert_eq!(
+        mempool.add_transaction_rbf(replacement, &utxos, 50),
+        Ok(vec![original.txid])
+    );
+}
+
+#[test]
+fn test_rbf_evicts_descendants_and_counts_their_fees() {
+    let utxos = rbf_utxos();
+    let mut mempool = Mempool::new();
+    let parent = pay(&[0], "Bob", 10, 1); // Bob gets 990
+    let child = Transaction::new(
+        vec![TxInput {
+            txid: parent.txid.clone(),
+            vout: 0,
+            signature: "sig".into(),
+        }],
+        vec![output("Carol", 960)], // fee 30, funded by the pooled parent
+        2,
+    );
+    mempool.add_transaction(parent.clone());
+    mempool.add_transaction(child.clone());
+
+    // Evicting the parent orphans the child, so both fees count: 10 + 30 + 5
+    assert_eq!(
+        mempool.add_transaction_rbf(pay(&[0], "Alice", 44, 3), &utxos, 5),
+        Err(MempoolError::InsufficientFee {
+            fee: 44,
+            required: 45
+        })
+    );
+
+    let mut expected = vec![parent.txid.clone(), child.txid.clone()];
+    expected.sort();
+    assert_eq!(
+        mempool.add_transaction_rbf(pay(&[0], "Alice", 45, 4), &utxos, 5),
+        Ok(expected)
+    );
+    assert_eq!(mempool.size(), 1);
+    assert!(!mempool.contains(&child.txid));
+    assert!(mempool.debug_index_consistent());
+}
+
+#[test]
+fn test_expire_older_than_keeps_newer_transactions() {
+    let mut mempool = Mempool::new();
+    let old_a = pay(&[0], "Bob", 10, 1);
+    let old_b = pay(&[1], "Carol", 10, 2);
+    let fresh = pay(&[0], "Dave", 20, 3);
+    mempool.add_transaction_at(old_a.clone(), 100);
+    mempool.add_transaction_at(old_b.clone(), 150);
+    mempool.add_transaction_at(fresh.clone(), 200);
+
+    let mut expected = vec![old_a.txid.clone(), old_b.txid.clone()];
+    expected.sort();
+    assert_eq!(mempool.expire_older_than(200), expected);
+
+    assert_eq!(mempool.size(), 1);
+    assert!(mempool.contains(&fresh.txid));
+    assert_eq!(mempool.added_at(&fresh.txid), Some(200));
+    assert_eq!(mempool.added_at(&old_a.txid), None);
+    assert!(mempool.debug_index_consistent());
+
+    assert!(mempool.expire_older_than(200).is_empty());
+    assert_eq!(mempool.expire_older_than(201), vec![fresh.txid]);
+    assert_eq!(mempool.size(), 0);
+    assert!(mempool.debug_index_consistent());
+}
+
+#[test]
+fn test_expired_transaction_no_longer_conflicts() {
+    let utxos = rbf_utxos();
+    let mut mempool = Mempool::new();
+    mempool.add_transaction_at(pay(&[0], "Bob", 500, 1), 100);
+    mempool.expire_older_than(150);
+
+    // Nothing left to out-bid, so even a low fee gets in
+    assert_eq!(
+        mempool.add_transaction_rbf(pay(&[0], "Carol", 1, 2), &utxos, 50),
+        Ok(vec![])
+    );
+    assert!(mempool.debug_index_consistent());
+}
+
+#[test]
+fn test_mempool_add_records_arrival_time() {
+    let mut mempool = Mempool::new();
+    let tx = pay(&[0], "Bob", 10, 1);
+    mempool.add_transaction(tx.clone());
+    // Stamped with the wall clock, so well past any fixed test timestamp
+    assert!(mempool.added_at(&tx.txid).unwrap() > 1_600_000_000);
+}
+
 // ============================================================================
 // MERKLE ROOT TESTS
 // ============================================================================
@@ -429,7 +614,7 @@ fn test_validate_transaction_success() {
         1000,
     );
 
-    assert!(validate_transaction(&tx, &utxo_set, 1000).is_ok());
+    assert!(validate_transaction(&tx, &utxo_set, 1000, 1).is_ok());
 }
 
 #[test]
@@ -446,7 +631,7 @@ fn test_validate_transaction_missing_utxo() {
         1000,
     );
 
-    let result = validate_transaction(&tx, &utxo_set, 0);
+    let result = validate_transaction(&tx, &utxo_set, 0, 1);
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("UTXO not found"));
 }
@@ -469,7 +654,7 @@ fn test_validate_transaction_empty_signature() {
         1000,
     );
 
-    let result = validate_transaction(&tx, &utxo_set, 0);
+    let result = validate_transaction(&tx, &utxo_set, 0, 1);
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("Invalid signature"));
 }
@@ -492,7 +677,7 @@ fn test_validate_transaction_outputs_exceed_inputs() {
         1000,
     );
 
-    let result = validate_transaction(&tx, &utxo_set, 0);
+    let result = validate_transaction(&tx, &utxo_set, 0, 1);
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("Outputs exceed inputs"));
 }
@@ -515,7 +700,7 @@ fn test_validate_transaction_fee_too_low() {
         1000,
     );
 
-    let result = validate_transaction(&tx, &utxo_set, 1000); // min_fee = 1000
+    let result = validate_transaction(&tx, &utxo_set, 1000, 1); // min_fee = 1000
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("Fee too low"));
 }
@@ -605,6 +790,133 @@ fn test_apply_block_full_flow() {
     assert_eq!(utxo_set.get_balance("genesis"), 49_99000000);
 }
 
+// ============================================================================
+// UTXO STATE HASH AND DIFF TESTS
+// ============================================================================
+
+fn out(address: &str, amount: u64) -> TxOutput {
+    TxOutput {
+        address: address.into(),
+        amount,
+    }
+}
+
+#[test]
+fn test_state_hash_ignores_insertion_order() {
+    let entries = [
+        ("tx_a", 0, out("Alice", 10)),
+        ("tx_a", 1, out("Bob", 20)),
+        ("tx_b", 0, out("Carol", 30)),
+        ("tx_c", 2, out("Alice", 40)),
+    ];
+
+    let mut forward = UTXOSet::new();
+    for (txid, vout, output) in entries.iter().cloned() {
+        forward.add_utxo(txid.into(), vout, output);
+    }
+    let mut backward = UTXOSet::new();
+    for (txid, vout, output) in entries.iter().rev().cloned() {
+        backward.add_utxo(txid.into(), vout, output);
+    }
+
+    assert_eq!(forward.state_hash(), backward.state_hash());
+    assert_eq!(forward.state_hash().len(), 64);
+    assert_eq!(forward.state_hash(), forward.clone().state_hash());
+    assert_ne!(forward.state_hash(), UTXOSet::new().state_hash());
+}
+
+#[test]
+fn test_state_hash_changes_with_any_single_amount() {
+    let mut base = UTXOSet::new();
+    base.add_utxo("tx_a".into(), 0, out("Alice", 10));
+    base.add_utxo("tx_a".into(), 1, out("Bob", 20));
+    base.add_utxo("tx_b".into(), 0, out("Carol", 30));
+    let original = base.state_hash();
+
+    for (txid, vout, address, amount) in [
+        ("tx_a", 0, "Alice", 10),
+        ("tx_a", 1, "Bob", 20),
+        ("tx_b", 0, "Carol", 30),
+    ] {
+        let mut changed = base.clone();
+        changed.add_utxo(txid.into(), vout, out(address, amount + 1));
+        assert_ne!(changed.state_hash(), original, "{}:{}", txid, vout);
+    }
+
+    // Same amount, different owner
+    let mut moved = base.clone();
+    moved.add_utxo("tx_a".into(), 0, out("Mallory", 10));
+    assert_ne!(moved.state_hash(), original);
+}
+
+#[test]
+fn test_state_hash_restored_after_revert() {
+    let mut utxo_set = UTXOSet::new();
+    let genesis_tx = Transaction::coinbase("miner".into(), 100, 0, "gen_tx".into());
+    let genesis = Block::new(0, 0, vec![genesis_tx], "0".into());
+    apply_block_to_utxo_set(&genesis, &mut utxo_set);
+    let before = utxo_set.state_hash();
+    let snapshot = utxo_set.clone();
+
+    let spend = Transaction::new(
+        vec![TxInput {
+            txid: "gen_tx".into(),
+            vout: 0,
+            signature: "sig".into(),
+        }],
+        vec![out("Alice", 60), out("miner", 39)],
+        1000,
+    );
+    let coinbase = Transaction::coinbase("miner".into(), 51, 1000, "cb_1".into());
+    let block = Block::new(1, 1000, vec![coinbase, spend], genesis.hash.clone());
+
+    let undo = apply_block_with_undo(&block, &mut utxo_set);
+    assert_ne!(utxo_set.state_hash(), before);
+    assert_eq!(undo.spent.len(), 1);
+    assert!(undo.spent[0].is_coinbase);
+
+    revert_block_from_utxo_set(&block, &undo, &mut utxo_set);
+    assert_eq!(utxo_set.state_hash(), before);
+    assert!(snapshot.diff(&utxo_set).is_empty());
+    assert!(utxo_set.debug_index_consistent());
+    assert_eq!(utxo_set.get_balance("miner"), 100);
+}
+
+#[test]
+fn test_utxo_diff_on_constructed_sets() {
+    let mut left = UTXOSet::new();
+    left.add_utxo("tx_a".into(), 0, out("Alice", 10));
+    left.add_utxo("tx_b".into(), 0, out("Bob", 20));
+    left.add_utxo("tx_c".into(), 0, out("Carol", 30));
+
+    let mut right = left.clone();
+    right.remove_utxo("tx_a", 0);
+    right.add_utxo("tx_d".into(), 0, out("Dave", 40));
+    right.add_utxo("tx_c".into(), 0, out("Carol", 31));
+
+    let diff = left.diff(&right);
+    let keys = |utxos: &[UTXO]| -> Vec<(String, u64)> {
+        utxos
+            .iter()
+            .map(|u| (format!("{}:{}", u.txid, u.vout), u.output.amount))
+            .collect()
+    };
+    assert_eq!(
+        keys(&diff.added),
+        vec![("tx_c:0".to_string(), 31), ("tx_d:0".to_string(), 40)]
+    );
+    assert_eq!(
+        keys(&diff.removed),
+        vec![("tx_a:0".to_string(), 10), ("tx_c:0".to_string(), 30)]
+    );
+
+    // Diffing the other way swaps the lists
+    let back = right.diff(&left);
+    assert_eq!(back.added, diff.removed);
+    assert_eq!(back.removed, diff.added);
+    assert!(left.diff(&left.clone()).is_empty());
+}
+
 // ============================================================================
 // FORMAT UTILITY TESTS
 // ============================================================================
@@ -643,3 +955,1557 @@ fn test_three_block_chain_valid() {
     assert_eq!(chain.height(), 3);
     assert!(chain.is_valid());
 }
+
+// ============================================================================
+// CHECKPOINTED VALIDATION TESTS
+// ============================================================================
+
+/// Build a chain of `blocks` mined blocks on top of genesis. The block at
+/// `tamper_at` gets its transactions swapped after mining, so its links still
+/// look fine but its merkle root no longer matches.
+fn build_chain(blocks: u64, txs_per_block: u64, tamper_at: Option<u64>) -> Blockchain {
+    let mut chain = Blockchain::new(1, 0);
+    for i in 1..=blocks {
+        let prev_hash = chain.get_latest_block().unwrap().hash.clone();
+        let txs = (0..txs_per_block)
+            .map(|t| {
+                Transaction::coinbase("miner".into(), 50, i * 1000 + t, format!("cb_{}_{}", i, t))
+            })
+            .collect();
+        let mut block = Block::new(i, i * 1000, txs, prev_hash);
+        block.mine(1);
+        if tamper_at == Some(i) {
+            block.transactions = vec![Transaction::coinbase(
+                "thief".into(),
+                999,
+                0,
+                "forged".into(),
+            )];
+        }
+        chain.add_block(block);
+    }
+    chain
+}
+
+fn checkpoint(chain: &Blockchain, height: u64) -> (u64, String) {
+    (
+        height,
+        chain.get_block(height as usize).unwrap().hash.clone(),
+    )
+}
+
+#[test]
+fn test_fast_mode_accepts_valid_chain() {
+    let mut chain = build_chain(12, 2, None);
+    let checkpoints = vec![checkpoint(&chain, 5), checkpoint(&chain, 10)];
+    chain.set_checkpoints(checkpoints);
+
+    assert_eq!(chain.validate(ValidationMode::Fast), Ok(()));
+    assert_eq!(chain.validate(ValidationMode::Full), Ok(()));
+}
+
+#[test]
+fn test_fast_mode_detects_checkpoint_mismatch() {
+    let mut chain = build_chain(6, 1, None);
+    let actual = chain.get_block(4).unwrap().hash.clone();
+    chain.set_checkpoints(vec![(4, "00deadbeef".into())]);
+
+    let err = chain.validate(ValidationMode::Fast).unwrap_err();
+    assert_eq!(
+        err,
+        ValidationError::CheckpointMismatch {
+            height: 4,
+            expected: "00deadbeef".into(),
+            actual: actual.clone(),
+        }
+    );
+    let message = err.to_string();
+    assert!(
+        message.contains("height 4"),
+        "message should name the height: {}",
+        message
+    );
+    assert!(
+        message.contains(&actual),
+        "message should name the found hash: {}",
+        message
+    );
+}
+
+#[test]
+fn test_full_mode_ignores_checkpoints() {
+    let mut chain = build_chain(3, 1, None);
+    chain.set_checkpoints(vec![(2, "wrong".into())]);
+
+    assert!(chain.is_valid());
+    assert_eq!(chain.validate(ValidationMode::Full), Ok(()));
+}
+
+#[test]
+fn test_fast_mode_skips_tampering_between_checkpoints() {
+    let mut chain = build_chain(10, 1, Some(4));
+    let checkpoints = vec![checkpoint(&chain, 2), checkpoint(&chain, 8)];
+    chain.set_checkpoints(checkpoints);
+
+    // This is the documented tradeoff: fast mode only follows links here.
+    assert_eq!(chain.validate(ValidationMode::Fast), Ok(()));
+    assert_eq!(
+        chain.validate(ValidationMode::Full),
+        Err(ValidationError::MerkleRootMismatch { height: 4 })
+    );
+}
+
+#[test]
+fn test_spot_check_catches_tampering_between_checkpoints() {
+    let mut chain = build_chain(10, 1, Some(4));
+    let checkpoints = vec![checkpoint(&chain, 8)];
+    chain.set_checkpoints(checkpoints);
+
+    // Sampling every block is exhaustive.
+    assert_eq!(
+        chain.spot_check_with_seed(1, 7),
+        Err(ValidationError::MerkleRootMismatch { height: 4 })
+    );
+
+    // A seeded sample is reproducible, and some seed lands on block 4.
+    let first = chain.spot_check_with_seed(4, 42);
+    assert_eq!(chain.spot_check_with_seed(4, 42), first);
+    let caught = (0..64).any(|seed| chain.spot_check_with_seed(4, seed).is_err());
+    assert!(caught, "no seed sampled the tampered block");
+}
+
+#[test]
+fn test_spot_check_passes_on_valid_chain() {
+    let mut chain = build_chain(10, 1, None);
+    let checkpoints = vec![checkpoint(&chain, 9)];
+    chain.set_checkpoints(checkpoints);
+
+    for seed in 0..16 {
+        assert_eq!(chain.spot_check_with_seed(3, seed), Ok(()));
+    }
+    assert_eq!(chain.spot_check(3), Ok(()));
+}
+
+#[test]
+fn test_fast_mode_fully_validates_blocks_above_checkpoint() {
+    let mut chain = build_chain(8, 1, Some(7));
+    let checkpoints = vec![checkpoint(&chain, 5)];
+    chain.set_checkpoints(checkpoints);
+
+    assert_eq!(
+        chain.validate(ValidationMode::Fast),
+        Err(ValidationError::MerkleRootMismatch { height: 7 })
+    );
+}
+
+#[test]
+fn test_fast_mode_ignores_checkpoints_above_the_tip() {
+    let honest = build_chain(10, 1, None);
+    let checkpoints = vec![checkpoint(&honest, 2), checkpoint(&honest, 9)];
+
+    // A forged short chain that never reaches height 9 must not be trusted
+    // up to it.
+    let mut short = build_chain(5, 1, Some(4));
+    short.set_checkpoints(checkpoints.clone());
+    assert_eq!(
+        short.validate(ValidationMode::Fast),
+        Err(ValidationError::MerkleRootMismatch { height: 4 })
+    );
+    assert_eq!(
+        short.spot_check_with_seed(1, 0),
+        Ok(()),
+        "only heights up to checkpoint 2 were skipped"
+    );
+
+    let mut full = build_chain(10, 1, None);
+    full.set_checkpoints(checkpoints);
+    assert_eq!(full.validate(ValidationMode::Fast), Ok(()));
+}
+
+#[test]
+fn test_fast_mode_still_checks_links() {
+    let mut chain = Blockchain::new(1, 0);
+    let mut block = Block::new(1, 1000, vec![], "not_the_parent".into());
+    block.mine(1);
+    let hash = block.hash.clone();
+    chain.add_block(block);
+    chain.set_checkpoints(vec![(1, hash)]);
+
+    assert_eq!(
+        chain.validate(ValidationMode::Fast),
+        Err(ValidationError::BrokenLink { height: 1 })
+    );
+}
+
+#[test]
+#[ignore] // timing-sensitive; run with `cargo test -- --ignored`
+fn test_fast_mode_is_faster_on_large_chain() {
+    use std::time::Instant;
+
+    let mut chain = build_chain(2_000, 16, None);
+    let tip = checkpoint(&chain, 2_000);
+    chain.set_checkpoints(vec![tip]);
+
+    let start = Instant::now();
+    chain.validate(ValidationMode::Full).unwrap();
+    let full = start.elapsed();
+
+    let start = Instant::now();
+    chain.validate(ValidationMode::Fast).unwrap();
+    let fast = start.elapsed();
+
+    assert!(
+        fast * 5 < full,
+        "fast {:?} should be well under full {:?}",
+        fast,
+        full
+    );
+}
+
+// ============================================================================
+// HEADER-ONLY SYNC TESTS
+// ============================================================================
+
+fn extend_chain(chain: &mut Blockchain, label: &str, blocks: u64) {
+    for _ in 0..blocks {
+        let tip = chain.get_latest_block().unwrap();
+        let (index, prev_hash) = (tip.index + 1, tip.hash.clone());
+        let tx = Transaction::coinbase("miner".into(), 50, index, format!("{}_{}", label, index));
+        let mut block = Block::new(index, index * 1000, vec![tx], prev_hash);
+        block.mine(chain.difficulty);
+        chain.add_block(block);
+    }
+}
+
+#[test]
+fn test_chain_iterator_walks_genesis_to_tip() {
+    let chain = build_chain(4, 1, None);
+    let indices: Vec<u64> = chain.iter().map(|b| b.index).collect();
+    assert_eq!(indices, vec![0, 1, 2, 3, 4]);
+
+    let mut count = 0;
+    for block in &chain {
+        assert_eq!(block.hash, block.calculate_hash());
+        count += 1;
+    }
+    assert_eq!(count, chain.height());
+    assert_eq!(chain.iter().next_back().unwrap().index, 4);
+}
+
+#[test]
+fn test_block_header_matches_block() {
+    let chain = build_chain(2, 2, None);
+    let block = chain.get_block(2).unwrap();
+    let header = block.header();
+
+    assert_eq!(header.hash, block.hash);
+    assert_eq!(header.merkle_root, block.merkle_root);
+    assert_eq!(header.calculate_hash(), block.calculate_hash());
+    assert_eq!(chain.headers().len(), chain.height());
+}
+
+#[test]
+fn test_locator_spacing_for_20_block_chain() {
+    let chain = build_chain(19, 1, None);
+    assert_eq!(chain.height(), 20);
+
+    let expected: Vec<String> = [19, 18, 17, 15, 11, 3, 0]
+        .iter()
+        .map(|&h| chain.get_block(h).unwrap().hash.clone())
+        .collect();
+    assert_eq!(chain.locator(), expected);
+}
+
+#[test]
+fn test_locator_does_not_repeat_genesis() {
+    // Tip at height 16: offsets 1, 2, 4, 8, 16 already reach genesis.
+    let chain = build_chain(16, 1, None);
+    let locator = chain.locator();
+    assert_eq!(locator.len(), 6);
+    assert_eq!(locator.last(), Some(&chain.get_block(0).unwrap().hash));
+
+    let genesis_only = Blockchain::new(1, 0);
+    assert_eq!(genesis_only.locator().len(), 1);
+}
+
+#[test]
+fn test_find_fork_point_between_chains_sharing_prefix() {
+    let mut ours = build_chain(10, 1, None);
+    let mut theirs = build_chain(10, 1, None);
+    assert_eq!(
+        ours.get_latest_block().unwrap().hash,
+        theirs.get_latest_block().unwrap().hash
+    );
+
+    extend_chain(&mut ours, "ours", 1);
+    extend_chain(&mut theirs, "theirs", 7);
+
+    // Our locator (tip 11): 11, 10, 9, 7, 3, 0. Block 10 is the real fork.
+    assert_eq!(theirs.find_fork_point(&ours.locator()), Some(10));
+    // Their locator (tip 17): 17, 16, 15, 13, 9, 1, 0. The first of those we
+    // also have is block 9: the locator's resolution, not the exact fork.
+    assert_eq!(ours.find_fork_point(&theirs.locator()), Some(9));
+}
+
+#[test]
+fn test_find_fork_point_unrelated_chains() {
+    let ours = Blockchain::new(1, 0);
+    let theirs = Blockchain::new(1, 12345);
+    assert_eq!(ours.find_fork_point(&theirs.locator()), None);
+    assert_eq!(ours.find_fork_point(&[]), None);
+}
+
+#[test]
+fn test_header_chain_validates_synced_headers() {
+    let chain = build_chain(5, 3, None);
+    let headers = HeaderChain::from_headers(chain.headers(), chain.difficulty).unwrap();
+
+    assert_eq!(headers.len(), 6);
+    assert_eq!(
+        headers.tip().unwrap().hash,
+        chain.get_latest_block().unwrap().hash
+    );
+}
+
+#[test]
+fn test_header_chain_catches_broken_link() {
+    let chain = build_chain(5, 1, None);
+    let mut headers = chain.headers();
+    headers.remove(3);
+
+    let err = HeaderChain::from_headers(headers, chain.difficulty).unwrap_err();
+    assert_eq!(err, ValidationError::BrokenLink { height: 3 });
+}
+
+#[test]
+fn test_header_chain_catches_bad_hash_and_work() {
+    let chain = build_chain(3, 1, None);
+
+    let mut tampered = chain.headers();
+    tampered[2].merkle_root = "forged".to_string();
+    let err = HeaderChain::from_headers(tampered, chain.difficulty).unwrap_err();
+    assert_eq!(err, ValidationError::HashMismatch { height: 2 });
+
+    // Requiring more work than was done fails on the first non-anchor header.
+    let err = HeaderChain::from_headers(chain.headers(), 64).unwrap_err();
+    assert_eq!(err, ValidationError::InsufficientWork { height: 1 });
+}
+
+#[test]
+fn test_header_chain_push_rejects_without_changing_state() {
+    let chain = build_chain(2, 1, None);
+    let headers = chain.headers();
+    let mut light = HeaderChain::new(chain.difficulty);
+
+    light.push(headers[0].clone()).unwrap();
+    assert!(light.push(headers[2].clone()).is_err());
+    assert_eq!(light.len(), 1);
+    light.push(headers[1].clone()).unwrap();
+    light.push(headers[2].clone()).unwrap();
+    assert_eq!(light.headers(), &headers[..]);
+}
+
+// ============================================================================
+// UTXO ADDRESS INDEX TESTS
+// ============================================================================
+
+fn output(address: &str, amount: u64) -> TxOutput {
+    TxOutput {
+        address: address.into(),
+        amount,
+    }
+}
+
+#[test]
+fn test_utxo_index_matches_brute_force() {
+    let addresses = ["alice", "bob", "carol", "dave", "erin"];
+    let mut set = UTXOSet::new();
+    // Our own list of live outpoints, to recompute balances by brute force.
+    let mut live: Vec<(String, usize, &str, u64)> = Vec::new();
+
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for step in 0..2_000 {
+        let roll = next();
+        if live.is_empty() || roll % 3 != 0 {
+            let address = addresses[(roll % addresses.len() as u64) as usize];
+            let amount = next() % 1_000;
+            let txid = format!("tx{}", step);
+            set.add_utxo(txid.clone(), 0, output(address, amount));
+            live.push((txid, 0, address, amount));
+        } else {
+            let (txid, vout, _, _) = live.swap_remove((roll as usize / 3) % live.len());
+            set.remove_utxo(&txid, vout);
+        }
+
+        if step % 97 == 0 {
+            assert!(set.debug_index_consistent());
+        }
+    }
+
+    for address in addresses {
+        let expected: u64 = live.iter().filter(|u| u.2 == address).map(|u| u.3).sum();
+        let count = live.iter().filter(|u| u.2 == address).count();
+        assert_eq!(set.get_balance(address), expected, "{}", address);
+        assert_eq!(
+            set.get_utxos_for_address(address).len(),
+            count,
+            "{}",
+            address
+        );
+    }
+    assert_eq!(set.total_supply(), live.iter().map(|u| u.3).sum::<u64>());
+    assert_eq!(set.count(), live.len());
+    assert!(set.debug_index_consistent());
+}
+
+#[test]
+fn test_utxo_readding_outpoint_replaces_it() {
+    let mut set = UTXOSet::new();
+    set.add_utxo("tx".into(), 0, output("alice", 100));
+    set.add_utxo("tx".into(), 0, output("bob", 40));
+
+    assert_eq!(set.get_balance("alice"), 0);
+    assert_eq!(set.get_balance("bob"), 40);
+    assert_eq!(set.total_supply(), 40);
+    assert!(set.debug_index_consistent());
+
+    // Removing something that isn't there changes nothing.
+    set.remove_utxo("missing", 3);
+    assert_eq!(set.count(), 1);
+    assert!(set.debug_index_consistent());
+}
+
+#[test]
+fn test_richest_addresses_ordering_and_ties() {
+    let mut set = UTXOSet::new();
+    set.add_utxo("a1".into(), 0, output("alice", 300));
+    set.add_utxo("a2".into(), 0, output("alice", 200));
+    set.add_utxo("b1".into(), 0, output("bob", 500));
+    set.add_utxo("c1".into(), 0, output("carol", 100));
+    set.add_utxo("z1".into(), 0, output("zed", 500));
+
+    assert_eq!(
+        set.richest_addresses(3),
+        vec![
+            ("alice".to_string(), 500),
+            ("bob".to_string(), 500),
+            ("zed".to_string(), 500),
+        ]
+    );
+    assert_eq!(set.richest_addresses(10).len(), 4);
+    assert!(set.richest_addresses(0).is_empty());
+    assert_eq!(set.total_supply(), 1_600);
+}
+
+#[test]
+fn test_apply_block_cleans_up_spent_address() {
+    let mut set = UTXOSet::new();
+    set.add_utxo("prev_tx".into(), 0, output("alice", 100));
+
+    let tx = Transaction::new(
+        vec![TxInput {
+            txid: "prev_tx".into(),
+            vout: 0,
+            signature: "sig".into(),
+        }],
+        vec![output("bob", 60), output("carol", 30)],
+        1000,
+    );
+    let block = Block::new(1, 2000, vec![tx], "prev_hash".into());
+    apply_block_to_utxo_set(&block, &mut set);
+
+    assert!(set.get_utxos_for_address("alice").is_empty());
+    assert_eq!(
+        set.richest_addresses(10),
+        vec![("bob".to_string(), 60), ("carol".to_string(), 30)]
+    );
+    assert_eq!(set.total_supply(), 90);
+    assert!(set.debug_index_consistent());
+}
+
+// ============================================================================
+// CONSENSUS RULE TESTS
+// ============================================================================
+
+fn small_params() -> ConsensusParams {
+    ConsensusParams {
+        max_block_txs: 3,
+        max_tx_inputs: 2,
+        max_tx_outputs: 2,
+        max_money: 1_000_000,
+        strict_addresses: false,
+    }
+}
+
+/// A difficulty-1 chain plus a UTXO set where alice owns funding:0 (1000)
+/// and funding:1 (500).
+fn consensus_fixture() -> (Blockchain, UTXOSet) {
+    let chain = Blockchain::with_params(1, 1000, small_params());
+    let mut utxos = UTXOSet::new();
+    utxos.add_utxo("funding".into(), 0, output("alice", 1000));
+    utxos.add_utxo("funding".into(), 1, output("alice", 500));
+    (chain, utxos)
+}
+
+fn spend(vout: usize, outputs: Vec<TxOutput>) -> Transaction {
+    Transaction::new(
+        vec![TxInput {
+            txid: "funding".into(),
+            vout,
+            signature: "sig".into(),
+        }],
+        outputs,
+        2000 + vout as u64,
+    )
+}
+
+fn reward(amount: u64) -> Transaction {
+    Transaction::coinbase("miner".into(), amount, 2000, "reward".into())
+}
+
+fn mined_on(chain: &Blockchain, transactions: Vec<Transaction>) -> Block {
+    let tip = chain.get_latest_block().unwrap();
+    let mut block = Block::new(tip.index + 1, 3000, transactions, tip.hash.clone());
+    block.mine(chain.difficulty);
+    block
+}
+
+fn check(chain: &Blockchain, utxos: &UTXOSet, block: &Block) -> Result<(), BlockError> {
+    let tip = chain.get_latest_block().unwrap();
+    validate_block(block, tip, utxos, &chain.params, chain.difficulty)
+}
+
+#[test]
+fn test_valid_block_is_added_and_applied() {
+    let (mut chain, mut utxos) = consensus_fixture();
+    let block = mined_on(
+        &chain,
+        vec![
+            reward(50),
+            spend(0, vec![output("bob", 600), output("alice", 390)]),
+        ],
+    );
+
+    chain.add_block_validated(block, &mut utxos).unwrap();
+    assert_eq!(chain.height(), 2);
+    assert!(chain.is_valid());
+    assert_eq!(utxos.get_balance("bob"), 600);
+    assert_eq!(utxos.get_balance("alice"), 890);
+    assert_eq!(utxos.get_balance("miner"), 50);
+}
+
+#[test]
+fn test_block_with_too_many_transactions() {
+    let (chain, utxos) = consensus_fixture();
+    let block = mined_on(
+        &chain,
+        vec![
+            reward(50),
+            spend(0, vec![output("bob", 1000)]),
+            spend(1, vec![output("bob", 500)]),
+            Transaction::new(vec![], vec![output("carol", 1)], 9),
+        ],
+    );
+    assert_eq!(
+        check(&chain, &utxos, &block),
+        Err(BlockError::TooManyTransactions { count: 4, max: 3 })
+    );
+}
+
+#[test]
+fn test_transaction_with_too_many_inputs() {
+    let (chain, mut utxos) = consensus_fixture();
+    utxos.add_utxo("funding".into(), 2, output("alice", 1));
+    let inputs = (0..3)
+        .map(|vout| TxInput {
+            txid: "funding".into(),
+            vout,
+            signature: "sig".into(),
+        })
+        .collect();
+    let tx = Transaction::new(inputs, vec![output("bob", 1501)], 2000);
+    let txid = tx.txid.clone();
+    let block = mined_on(&chain, vec![reward(50), tx]);
+
+    assert_eq!(
+        check(&chain, &utxos, &block),
+        Err(BlockError::TooManyInputs {
+            txid,
+            count: 3,
+            max: 2
+        })
+    );
+}
+
+#[test]
+fn test_transaction_with_too_many_outputs() {
+    let (chain, utxos) = consensus_fixture();
+    let tx = spend(
+        0,
+        vec![output("bob", 1), output("carol", 1), output("dave", 1)],
+    );
+    let txid = tx.txid.clone();
+    let block = mined_on(&chain, vec![reward(50), tx]);
+
+    assert_eq!(
+        check(&chain, &utxos, &block),
+        Err(BlockError::TooManyOutputs {
+            txid,
+            count: 3,
+            max: 2
+        })
+    );
+}
+
+#[test]
+fn test_output_above_max_money() {
+    let (chain, utxos) = consensus_fixture();
+    let block = mined_on(&chain, vec![reward(1_000_001)]);
+
+    assert_eq!(
+        check(&chain, &utxos, &block),
+        Err(BlockError::OutputExceedsMaxMoney {
+            txid: "reward".into(),
+            amount: 1_000_001,
+            max: 1_000_000
+        })
+    );
+    let at_limit = mined_on(&chain, vec![reward(1_000_000)]);
+    assert_eq!(check(&chain, &utxos, &at_limit), Ok(()));
+}
+
+#[test]
+fn test_block_without_coinbase() {
+    let (chain, utxos) = consensus_fixture();
+    let empty = mined_on(&chain, vec![]);
+    assert_eq!(
+        check(&chain, &utxos, &empty),
+        Err(BlockError::MissingCoinbase)
+    );
+
+    let spend_first = mined_on(&chain, vec![spend(0, vec![output("bob", 1000)])]);
+    assert_eq!(
+        check(&chain, &utxos, &spend_first),
+        Err(BlockError::MissingCoinbase)
+    );
+}
+
+#[test]
+fn test_block_with_second_coinbase() {
+    let (chain, utxos) = consensus_fixture();
+    let extra = Transaction::coinbase("miner".into(), 50, 2000, "reward-2".into());
+    let block = mined_on(&chain, vec![reward(50), extra]);
+
+    assert_eq!(
+        check(&chain, &utxos, &block),
+        Err(BlockError::MultipleCoinbase { index: 1 })
+    );
+}
+
+#[test]
+fn test_block_with_bad_header_fields() {
+    let (chain, utxos) = consensus_fixture();
+    let tip_hash = chain.get_latest_block().unwrap().hash.clone();
+
+    let mut tampered = mined_on(&chain, vec![reward(50)]);
+    tampered.nonce += 1;
+    assert_eq!(
+        check(&chain, &utxos, &tampered),
+        Err(BlockError::HashMismatch)
+    );
+
+    let mut unmined = Block::new(1, 3000, vec![reward(50)], tip_hash.clone());
+    while unmined.hash.starts_with('0') {
+        unmined.timestamp += 1;
+        unmined.hash = unmined.calculate_hash();
+    }
+    assert_eq!(
+        check(&chain, &utxos, &unmined),
+        Err(BlockError::InsufficientWork)
+    );
+
+    let mut orphan = Block::new(1, 3000, vec![reward(50)], "elsewhere".into());
+    orphan.mine(chain.difficulty);
+    assert_eq!(
+        check(&chain, &utxos, &orphan),
+        Err(BlockError::PreviousHashMismatch {
+            expected: tip_hash,
+            actual: "elsewhere".into()
+        })
+    );
+
+    let mut stuffed = mined_on(&chain, vec![reward(50)]);
+    stuffed
+        .transactions
+        .push(spend(0, vec![output("bob", 1000)]));
+    assert_eq!(
+        check(&chain, &utxos, &stuffed),
+        Err(BlockError::MerkleRootMismatch)
+    );
+}
+
+#[test]
+fn test_block_with_invalid_transaction() {
+    let (chain, utxos) = consensus_fixture();
+    let overspend = spend(0, vec![output("bob", 1001)]);
+    let txid = overspend.txid.clone();
+    let block = mined_on(&chain, vec![reward(50), overspend]);
+
+    assert_eq!(
+        check(&chain, &utxos, &block),
+        Err(BlockError::InvalidTransaction {
+            txid,
+            reason: "Outputs exceed inputs".into()
+        })
+    );
+}
+
+#[test]
+fn test_double_spend_within_block_is_rejected() {
+    let (chain, utxos) = consensus_fixture();
+    let first = spend(0, vec![output("bob", 1000)]);
+    let mut second = spend(0, vec![output("carol", 1000)]);
+    second.timestamp += 100;
+    second.txid = second.calculate_txid();
+    let block = mined_on(&chain, vec![reward(50), first, second]);
+
+    assert!(matches!(
+        check(&chain, &utxos, &block),
+        Err(BlockError::InvalidTransaction { .. })
+    ));
+}
+
+#[test]
+fn test_spending_output_created_in_same_block() {
+    let (chain, utxos) = consensus_fixture();
+    let to_bob = spend(0, vec![output("bob", 1000)]);
+    let from_bob = Transaction::new(
+        vec![TxInput {
+            txid: to_bob.txid.clone(),
+            vout: 0,
+            signature: "bob-sig".into(),
+        }],
+        vec![output("carol", 1000)],
+        2500,
+    );
+    let block = mined_on(&chain, vec![reward(50), to_bob, from_bob]);
+
+    assert_eq!(check(&chain, &utxos, &block), Ok(()));
+}
+
+#[test]
+fn test_output_spent_in_same_block_is_not_left_spendable() {
+    let (mut chain, mut utxos) = consensus_fixture();
+    let to_bob = spend(0, vec![output("bob", 1000)]);
+    let bob_txid = to_bob.txid.clone();
+    let from_bob = Transaction::new(
+        vec![TxInput {
+            txid: bob_txid.clone(),
+            vout: 0,
+            signature: "bob-sig".into(),
+        }],
+        vec![output("carol", 1000)],
+        2500,
+    );
+    let block = mined_on(&chain, vec![reward(50), to_bob, from_bob]);
+    let before = utxos.state_hash();
+
+    let mut reverted = utxos.clone();
+    let undo = apply_block_with_undo(&block, &mut reverted);
+    revert_block_from_utxo_set(&block, &undo, &mut reverted);
+    assert_eq!(reverted.state_hash(), before);
+
+    chain.add_block_validated(block, &mut utxos).unwrap();
+    assert!(utxos.get_utxo(&bob_txid, 0).is_none());
+    assert_eq!(utxos.get_balance("bob"), 0);
+    assert_eq!(utxos.get_balance("carol"), 1000);
+    assert_eq!(utxos.total_supply(), 500 + 1000 + 50);
+
+    // Bob's output is gone, so spending it again in the next block fails.
+    let again = Transaction::new(
+        vec![TxInput {
+            txid: bob_txid,
+            vout: 0,
+            signature: "bob-sig".into(),
+        }],
+        vec![output("dave", 1000)],
+        2600,
+    );
+    let replay = mined_on(&chain, vec![reward(50), again]);
+    assert!(matches!(
+        chain.add_block_validated(replay, &mut utxos),
+        Err(BlockError::InvalidTransaction { .. })
+    ));
+}
+
+#[test]
+fn test_transaction_listing_an_input_twice_is_rejected() {
+    let (chain, utxos) = consensus_fixture();
+    let input = TxInput {
+        txid: "funding".into(),
+        vout: 1,
+        signature: "sig".into(),
+    };
+    let tx = Transaction::new(vec![input.clone(), input], vec![output("bob", 1000)], 2000);
+    assert_eq!(
+        validate_transaction(&tx, &utxos, 0, 1),
+        Err("Duplicate input: funding:1".to_string())
+    );
+    let block = mined_on(&chain, vec![reward(50), tx]);
+    assert!(matches!(
+        check(&chain, &utxos, &block),
+        Err(BlockError::InvalidTransaction { .. })
+    ));
+}
+
+#[test]
+fn test_rejected_block_changes_nothing() {
+    let (mut chain, mut utxos) = consensus_fixture();
+    let block = mined_on(&chain, vec![reward(2_000_000)]);
+
+    let err = chain.add_block_validated(block, &mut utxos).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "transaction reward has an output of 2000000, limit is 1000000"
+    );
+    assert_eq!(chain.height(), 1);
+    assert_eq!(utxos.count(), 2);
+    assert_eq!(utxos.get_balance("miner"), 0);
+}
+
+// ============================================================================
+// BLOCK REWARD AND COINBASE MATURITY TESTS
+// ============================================================================
+
+#[test]
+fn test_block_subsidy_halving_boundaries() {
+    assert_eq!(block_subsidy(0), 50 * COIN);
+    assert_eq!(block_subsidy(SUBSIDY_HALVING_INTERVAL - 1), 50 * COIN);
+    assert_eq!(block_subsidy(SUBSIDY_HALVING_INTERVAL), 25 * COIN);
+    assert_eq!(block_subsidy(2 * SUBSIDY_HALVING_INTERVAL - 1), 25 * COIN);
+    assert_eq!(block_subsidy(2 * SUBSIDY_HALVING_INTERVAL), 12_50000000);
+    assert_eq!(block_subsidy(64 * SUBSIDY_HALVING_INTERVAL), 0);
+    assert_eq!(block_subsidy(u64::MAX), 0);
+}
+
+#[test]
+fn test_coinbase_overclaim_rejected() {
+    let chain = Blockchain::with_params(1, 1000, ConsensusParams::default());
+    let utxos = UTXOSet::new();
+    let block = mined_on(&chain, vec![reward(block_subsidy(1) + 1)]);
+
+    assert_eq!(
+        check(&chain, &utxos, &block),
+        Err(BlockError::CoinbaseOverclaim {
+            claimed: block_subsidy(1) + 1,
+            allowed: block_subsidy(1),
+        })
+    );
+}
+
+#[test]
+fn test_coinbase_may_claim_exactly_subsidy_plus_fees() {
+    let mut params = small_params();
+    params.max_money = u64::MAX;
+    let mut chain = Blockchain::with_params(1, 1000, params);
+    let mut utxos = UTXOSet::new();
+    utxos.add_utxo("funding".into(), 0, output("alice", 1000));
+
+    // Alice pays a fee of 10.
+    let tx = spend(0, vec![output("bob", 990)]);
+    let allowed = block_subsidy(1) + 10;
+
+    let greedy = mined_on(&chain, vec![reward(allowed + 1), tx.clone()]);
+    assert_eq!(
+        check(&chain, &utxos, &greedy),
+        Err(BlockError::CoinbaseOverclaim {
+            claimed: allowed + 1,
+            allowed
+        })
+    );
+
+    let exact = mined_on(&chain, vec![reward(allowed), tx]);
+    chain.add_block_validated(exact, &mut utxos).unwrap();
+    assert_eq!(utxos.get_balance("miner"), allowed);
+}
+
+#[test]
+fn test_block_index_cannot_be_rewound_for_a_larger_subsidy() {
+    let params = ConsensusParams::default();
+    let utxos = UTXOSet::new();
+    let mut prev = Block::new(SUBSIDY_HALVING_INTERVAL, 1000, vec![], "0".into());
+    prev.mine(1);
+
+    // Index 0 would always earn the full, unhalved subsidy.
+    let mut liar = Block::new(0, 2000, vec![reward(block_subsidy(0))], prev.hash.clone());
+    liar.mine(1);
+    assert_eq!(
+        validate_block(&liar, &prev, &utxos, &params, 1),
+        Err(BlockError::IndexMismatch {
+            expected: SUBSIDY_HALVING_INTERVAL + 1,
+            actual: 0,
+        })
+    );
+
+    let height = SUBSIDY_HALVING_INTERVAL + 1;
+    let mut honest = Block::new(
+        height,
+        2000,
+        vec![reward(block_subsidy(0))],
+        prev.hash.clone(),
+    );
+    honest.mine(1);
+    assert_eq!(
+        validate_block(&honest, &prev, &utxos, &params, 1),
+        Err(BlockError::CoinbaseOverclaim {
+            claimed: block_subsidy(0),
+            allowed: block_subsidy(height),
+        })
+    );
+}
+
+#[test]
+fn test_block_index_cannot_be_advanced_to_mature_a_coinbase() {
+    let (chain, mut utxos) = consensus_fixture();
+    utxos.add_utxo_at("cb".into(), 0, output("miner", 50), 1, true);
+    let tx = Transaction::new(
+        vec![TxInput {
+            txid: "cb".into(),
+            vout: 0,
+            signature: "sig".into(),
+        }],
+        vec![output("bob", 50)],
+        2000,
+    );
+    let tip = chain.get_latest_block().unwrap();
+
+    let far = 1 + COINBASE_MATURITY;
+    let mut liar = Block::new(far, 3000, vec![reward(0), tx.clone()], tip.hash.clone());
+    liar.mine(chain.difficulty);
+    assert_eq!(
+        check(&chain, &utxos, &liar),
+        Err(BlockError::IndexMismatch {
+            expected: tip.index + 1,
+            actual: far,
+        })
+    );
+
+    let honest = mined_on(&chain, vec![reward(0), tx]);
+    assert!(matches!(
+        check(&chain, &utxos, &honest),
+        Err(BlockError::InvalidTransaction { reason, .. }) if reason.contains("Immature")
+    ));
+}
+
+#[test]
+fn test_validate_transaction_coinbase_maturity() {
+    let mut utxo_set = UTXOSet::new();
+    utxo_set.add_utxo_at("cb".into(), 0, output("miner", 5000), 5, true);
+    let tx = Transaction::new(
+        vec![TxInput {
+            txid: "cb".into(),
+            vout: 0,
+            signature: "sig".into(),
+        }],
+        vec![output("bob", 5000)],
+        1000,
+    );
+
+    let early = validate_transaction(&tx, &utxo_set, 0, 5 + COINBASE_MATURITY - 1);
+    assert!(early.unwrap_err().contains("Immature coinbase spend"));
+    assert!(validate_transaction(&tx, &utxo_set, 0, 5 + COINBASE_MATURITY).is_ok());
+
+    // Ordinary outputs can be spent right away.
+    utxo_set.add_utxo_at("cb".into(), 0, output("miner", 5000), 5, false);
+    assert!(validate_transaction(&tx, &utxo_set, 0, 5).is_ok());
+}
+
+#[test]
+fn test_apply_block_records_origin_of_outputs() {
+    let mut utxo_set = UTXOSet::new();
+    utxo_set.add_utxo("prev".into(), 0, output("alice", 100));
+    let block = Block::new(
+        7,
+        0,
+        vec![
+            Transaction::coinbase("miner".into(), 50, 0, "cb7".into()),
+            Transaction::new(
+                vec![TxInput {
+                    txid: "prev".into(),
+                    vout: 0,
+                    signature: "sig".into(),
+                }],
+                vec![output("bob", 100)],
+                0,
+            ),
+        ],
+        "prev_hash".into(),
+    );
+    apply_block_to_utxo_set(&block, &mut utxo_set);
+
+    let coinbase = utxo_set.get_utxo("cb7", 0).unwrap();
+    assert_eq!((coinbase.height, coinbase.is_coinbase), (7, true));
+    let payment = utxo_set.get_utxos_for_address("bob")[0];
+    assert_eq!((payment.height, payment.is_coinbase), (7, false));
+}
+
+#[test]
+fn test_block_spending_immature_coinbase_rejected_until_mature() {
+    let mut chain = Blockchain::with_params(1, 1000, ConsensusParams::default());
+    let mut utxos = UTXOSet::new();
+
+    let first = mined_on(
+        &chain,
+        vec![Transaction::coinbase(
+            "miner".into(),
+            1000,
+            1,
+            "cb-1".into(),
+        )],
+    );
+    chain.add_block_validated(first, &mut utxos).unwrap();
+
+    let spend_reward = Transaction::new(
+        vec![TxInput {
+            txid: "cb-1".into(),
+            vout: 0,
+            signature: "sig".into(),
+        }],
+        vec![output("bob", 1000)],
+        5,
+    );
+
+    // Fill the chain up to one block short of maturity, trying to spend the
+    // reward at every height along the way.
+    for height in 2..1 + COINBASE_MATURITY {
+        let label = format!("cb-{}", height);
+        let attempt = mined_on(
+            &chain,
+            vec![
+                Transaction::coinbase("miner".into(), 0, height, label.clone()),
+                spend_reward.clone(),
+            ],
+        );
+        assert!(
+            matches!(
+                check(&chain, &utxos, &attempt),
+                Err(BlockError::InvalidTransaction { .. })
+            ),
+            "spend at height {} should be immature",
+            height
+        );
+
+        let filler = mined_on(
+            &chain,
+            vec![Transaction::coinbase("miner".into(), 0, height, label)],
+        );
+        chain.add_block_validated(filler, &mut utxos).unwrap();
+    }
+
+    let mature = mined_on(
+        &chain,
+        vec![
+            Transaction::coinbase("miner".into(), 0, 99, "cb-mature".into()),
+            spend_reward,
+        ],
+    );
+    assert_eq!(mature.index, 1 + COINBASE_MATURITY);
+    chain.add_block_validated(mature, &mut utxos).unwrap();
+    assert_eq!(utxos.get_balance("bob"), 1000);
+}
+
+// ============================================================================
+// CHAIN INDEX TESTS
+// ============================================================================
+
+fn payment(from: &[(&str, usize)], to: Vec<TxOutput>, timestamp: u64) -> Transaction {
+    let inputs = from
+        .iter()
+        .map(|(txid, vout)| TxInput {
+            txid: txid.to_string(),
+            vout: *vout,
+            signature: "sig".into(),
+        })
+        .collect();
+    Transaction::new(inputs, to, timestamp)
+}
+
+/// Genesis plus five blocks. Returns the chain and the txids of the
+/// non-coinbase payments, in order.
+fn indexed_chain() -> (Blockchain, Vec<String>) {
+    let mut chain = Blockchain::new(1, 1000);
+    let mut txids = Vec::new();
+
+    // Height 1: alice is paid twice by one coinbase-funded split.
+    let split = payment(
+        &[("genesis_tx", 0)],
+        vec![output("alice", 60), output("alice", 30), output("bob", 10)],
+        1,
+    );
+    // Height 2: alice spends one output to carol, change back to herself.
+    let to_carol = payment(
+        &[(&split.txid, 0)],
+        vec![output("carol", 50), output("alice", 10)],
+        2,
+    );
+    // Height 3: nothing but a coinbase for dave.
+    // Height 4: bob and alice merge into carol.
+    let merge = payment(
+        &[(&split.txid, 2), (&split.txid, 1)],
+        vec![output("carol", 40)],
+        4,
+    );
+    // Height 5: carol spends both of her outputs to erin.
+    let to_erin = payment(
+        &[(&to_carol.txid, 0), (&merge.txid, 0)],
+        vec![output("erin", 90)],
+        5,
+    );
+
+    let blocks = vec![
+        vec![split],
+        vec![to_carol],
+        vec![],
+        vec![merge],
+        vec![to_erin],
+    ];
+    for (i, payments) in blocks.into_iter().enumerate() {
+        let height = i as u64 + 1;
+        let miner = if height == 3 { "dave" } else { "miner" };
+        let mut transactions = vec![Transaction::coinbase(
+            miner.into(),
+            50,
+            height,
+            format!("cb-{}", height),
+        )];
+        txids.extend(payments.iter().map(|tx| tx.txid.clone()));
+        transactions.extend(payments);
+
+        let tip = chain.get_latest_block().unwrap();
+        let mut block = Block::new(height, 1000 + height, transactions, tip.hash.clone());
+        block.mine(chain.difficulty);
+        chain.add_block(block);
+    }
+    (chain, txids)
+}
+
+#[test]
+fn test_index_finds_blocks_by_hash() {
+    let (chain, _) = indexed_chain();
+    for (height, block) in chain.iter().enumerate() {
+        let found = chain.get_block_by_hash(&block.hash).unwrap();
+        assert_eq!(found.index, height as u64);
+        assert_eq!(chain.index().block_height(&block.hash), Some(height as u64));
+    }
+    assert!(chain.get_block_by_hash("no-such-hash").is_none());
+}
+
+#[test]
+fn test_index_finds_transactions() {
+    let (chain, txids) = indexed_chain();
+    let heights = [1, 2, 4, 5];
+    for (txid, height) in txids.iter().zip(heights) {
+        let (tx, found_height) = chain.get_transaction(txid).unwrap();
+        assert_eq!(&tx.txid, txid);
+        assert_eq!(found_height, height);
+    }
+
+    let (coinbase, height) = chain.get_transaction("cb-3").unwrap();
+    assert!(coinbase.is_coinbase());
+    assert_eq!(height, 3);
+    let (genesis, height) = chain.get_transaction("genesis_tx").unwrap();
+    assert_eq!(genesis.outputs[0].address, "genesis_address");
+    assert_eq!(height, 0);
+
+    // Genesis + 5 coinbases + 4 payments.
+    assert_eq!(chain.index().transaction_count(), 10);
+    assert_eq!(
+        chain.index().locate_transaction(&txids[3]),
+        Some(TxLocation {
+            height: 5,
+            position: 1
+        })
+    );
+}
+
+#[test]
+fn test_index_missing_txid_is_none() {
+    let (chain, _) = indexed_chain();
+    assert!(chain.get_transaction("not-a-txid").is_none());
+    assert!(chain.index().locate_transaction("").is_none());
+    assert!(chain.get_transactions_for_address("nobody").is_empty());
+}
+
+#[test]
+fn test_address_history_ordered_by_height() {
+    let (chain, txids) = indexed_chain();
+    let history = |address: &str| chain.get_transactions_for_address(address);
+
+    // alice: paid twice by the split (listed once), pays and gets change in
+    // to_carol, spends her remaining split output in merge.
+    assert_eq!(
+        history("alice"),
+        vec![
+            (txids[0].clone(), 1),
+            (txids[1].clone(), 2),
+            (txids[2].clone(), 4)
+        ]
+    );
+    // carol: paid at 2 and 4, spends both at 5.
+    assert_eq!(
+        history("carol"),
+        vec![
+            (txids[1].clone(), 2),
+            (txids[2].clone(), 4),
+            (txids[3].clone(), 5)
+        ]
+    );
+    // bob only shows up through the output merge spent.
+    assert_eq!(
+        history("bob"),
+        vec![(txids[0].clone(), 1), (txids[2].clone(), 4)]
+    );
+    assert_eq!(history("dave"), vec![("cb-3".to_string(), 3)]);
+    assert_eq!(history("erin"), vec![(txids[3].clone(), 5)]);
+    // The genesis output is spent by the split.
+    assert_eq!(
+        history("genesis_address"),
+        vec![("genesis_tx".to_string(), 0), (txids[0].clone(), 1)]
+    );
+
+    let miner_heights: Vec<u64> = history("miner").iter().map(|(_, h)| *h).collect();
+    assert_eq!(miner_heights, vec![1, 2, 4, 5]);
+}
+
+#[test]
+fn test_reindex_matches_incremental_index() {
+    let (mut chain, _) = indexed_chain();
+    let incremental = chain.index().clone();
+    chain.reindex();
+    assert_eq!(chain.index(), &incremental);
+    assert_eq!(chain.index().transaction_count(), 10);
+}
+
+#[test]
+fn test_validated_blocks_are_indexed() {
+    let (mut chain, mut utxos) = consensus_fixture();
+    let block = mined_on(&chain, vec![reward(1000)]);
+    let hash = block.hash.clone();
+    chain.add_block_validated(block, &mut utxos).unwrap();
+
+    assert_eq!(chain.get_block_by_hash(&hash).unwrap().index, 1);
+    assert_eq!(chain.get_transactions_for_address("miner").len(), 1);
+}
+
+// ============================================================================
+// P2P GOSSIP TESTS
+// ============================================================================
+
+fn assert_all_at(network: &Network, tip: &str, height: usize) {
+    for node in network.nodes() {
+        assert_eq!(node.tip_hash(), tip, "node {}", node.id());
+        assert_eq!(node.chain().height(), height, "node {}", node.id());
+        assert!(node.chain().is_valid());
+    }
+}
+
+#[test]
+fn test_gossip_block_reaches_every_node() {
+    let mut network = Network::new(4, 1, 1000);
+    assert!(network.is_converged());
+
+    let tip = network.mine_block(0, "miner", 1001);
+    assert!(!network.is_converged());
+    assert!(network.run_until_converged(10).is_some());
+    assert_all_at(&network, &tip, 2);
+    assert_eq!(network.messages_dropped(), 0);
+    for node in network.nodes() {
+        assert_eq!(node.utxo_set().get_balance("miner"), block_subsidy(1));
+    }
+}
+
+#[test]
+fn test_gossip_catches_up_through_headers() {
+    // Only the last block is announced; the others are found via the
+    // locator after the tip arrives as an orphan.
+    let mut network = Network::new(3, 1, 1000);
+    for i in 1..=5 {
+        network.mine_block(0, "miner", 1000 + i);
+    }
+    let tip = network.node(0).tip_hash().to_string();
+    assert!(network.run_until_converged(20).is_some());
+    assert_all_at(&network, &tip, 6);
+}
+
+#[test]
+fn test_gossip_converges_despite_message_loss() {
+    let mut network = Network::new(5, 1, 1000).with_message_loss(40, 7);
+    for i in 1..=4 {
+        network.mine_block(0, "miner", 1000 + i);
+        assert!(network.run_until_converged(200).is_some(), "block {}", i);
+    }
+    let tip = network.node(0).tip_hash().to_string();
+    assert_all_at(&network, &tip, 5);
+    assert!(network.messages_dropped() > 0);
+}
+
+#[test]
+fn test_gossip_message_loss_is_deterministic() {
+    let run = || {
+        let mut network = Network::new(4, 1, 1000).with_message_loss(30, 42);
+        network.mine_block(0, "miner", 1001);
+        let rounds = network.run_until_converged(200);
+        (rounds, network.messages_sent(), network.messages_dropped())
+    };
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn test_gossip_transactions_reach_every_mempool_and_the_next_block() {
+    let mut network = Network::new(3, 1, 1000);
+    // Let the genesis coinbase mature before spending it.
+    for i in 1..=COINBASE_MATURITY {
+        network.mine_block(0, "miner", 1000 + i);
+    }
+    assert!(network.run_until_converged(50).is_some());
+
+    let tx = payment(&[("genesis_tx", 0)], vec![output("alice", 90)], 1);
+    let txid = tx.txid.clone();
+    network.submit_transaction(2, tx);
+    // Inv, then GetData, then the transaction itself.
+    for _ in 0..3 {
+        network.step();
+    }
+    for node in network.nodes() {
+        assert!(node.mempool().contains(&txid), "node {}", node.id());
+    }
+
+    // A different node mines it, collecting the fee.
+    network.mine_block(1, "other", 2000);
+    assert!(network.run_until_converged(10).is_some());
+    for node in network.nodes() {
+        assert!(!node.mempool().contains(&txid), "node {}", node.id());
+        assert!(node.chain().get_transaction(&txid).is_some());
+        assert_eq!(node.utxo_set().get_balance("alice"), 90);
+        assert_eq!(
+            node.utxo_set().get_balance("other"),
+            block_subsidy(COINBASE_MATURITY + 1) + 100_00000000 - 90
+        );
+    }
+}
+
+#[test]
+fn test_sim_node_refuses_transactions_over_consensus_limits() {
+    let mut network = Network::new(2, 1, 1000);
+    for i in 1..=COINBASE_MATURITY {
+        network.mine_block(0, "miner", 1000 + i);
+    }
+    assert!(network.run_until_converged(50).is_some());
+
+    let max_outputs = network.node(0).chain().params.max_tx_outputs;
+    let outputs = (0..=max_outputs).map(|_| output("dust", 1)).collect();
+    let tx = payment(&[("genesis_tx", 0)], outputs, 1);
+    let txid = tx.txid.clone();
+    assert!(matches!(
+        check_transaction_limits(&tx, &network.node(0).chain().params),
+        Err(BlockError::TooManyOutputs { .. })
+    ));
+
+    network.submit_transaction(0, tx);
+    assert!(!network.node(0).mempool().contains(&txid));
+
+    // Mining carries on without it instead of producing an invalid block.
+    network.mine_block(0, "miner", 2000);
+    assert!(network.run_until_converged(10).is_some());
+    assert!(network.node(0).chain().get_transaction(&txid).is_none());
+}
+
+#[test]
+fn test_sim_node_requests_only_unknown_data() {
+    let mut node = SimNode::new(0, 1, 1000);
+    let genesis = node.tip_hash().to_string();
+
+    let out = node.handle(3, NetMessage::Inv(vec![genesis.clone(), "unknown".into()]));
+    assert_eq!(out.len(), 1);
+    match &out[0] {
+        (Target::Peer(3), NetMessage::GetData(hashes)) => assert_eq!(hashes, &["unknown"]),
+        other => panic!("unexpected {:?}", other),
+    }
+
+    assert!(node
+        .handle(3, NetMessage::Inv(vec![genesis.clone()]))
+        .is_empty());
+    let out = node.handle(3, NetMessage::GetData(vec![genesis, "unknown".into()]));
+    assert!(matches!(
+        &out[..],
+        [(Target::Peer(3), NetMessage::BlockMsg(_))]
+    ));
+}
+
+#[test]
+fn test_sim_node_rejects_invalid_blocks() {
+    let mut node = SimNode::new(0, 1, 1000);
+    let genesis = node.tip_hash().to_string();
+    // Claims far more than the subsidy.
+    let coinbase = Transaction::coinbase("thief".into(), block_subsidy(1) * 2, 1, "cb".into());
+    let mut block = Block::new(1, 1001, vec![coinbase], genesis.clone());
+    block.mine(1);
+
+    assert!(node.handle(1, NetMessage::BlockMsg(block)).is_empty());
+    assert_eq!(node.tip_hash(), genesis);
+}
+
+// ============================================================================
+// ADDRESS TESTS
+// ============================================================================
+
+use blockchain_node::solution::address::{
+    base58_decode, base58_encode, decode_address, derive_address_from_pubkey, encode_address,
+    validate_address, AddressError, ADDRESS_VERSION,
+};
+
+#[test]
+fn test_address_round_trip() {
+    let hash: [u8; 20] = std::array::from_fn(|i| (i as u8).wrapping_mul(37).wrapping_add(5));
+    for version in [0x00, 0x05, 0x6f, 0xff] {
+        let address = encode_address(&hash, version);
+        assert_eq!(decode_address(&address), Ok((version, hash)));
+    }
+}
+
+#[test]
+fn test_address_known_vector() {
+    // The all-zero hash at version 0 is Bitcoin's well-known burn address.
+    assert_eq!(encode_address(&[0u8; 20], 0), "1111111111111111111114oLvT2");
+}
+
+#[test]
+fn test_flipped_character_fails_checksum() {
+    let address = derive_address_from_pubkey("alice-pubkey");
+    assert!(decode_address(&address).is_ok());
+
+    let mut rejected = 0;
+    for i in 1..address.len() {
+        let mut chars: Vec<char> = address.chars().collect();
+        chars[i] = if chars[i] == 'z' { 'y' } else { 'z' };
+        let typo: String = chars.into_iter().collect();
+        match decode_address(&typo) {
+            Err(AddressError::ChecksumMismatch) | Err(AddressError::InvalidLength { .. }) => {
+                rejected += 1
+            }
+            other => panic!("typo at {} accepted: {:?}", i, other),
+        }
+    }
+    assert_eq!(rejected, address.len() - 1);
+}
+
+#[test]
+fn test_invalid_base58_character() {
+    let address = derive_address_from_pubkey("alice-pubkey");
+    let typo = format!("{}0{}", &address[..3], &address[4..]);
+    assert_eq!(
+        decode_address(&typo),
+        Err(AddressError::InvalidCharacter {
+            ch: '0',
+            position: 3
+        })
+    );
+}
+
+#[test]
+fn test_wrong_version_is_surfaced() {
+    let hash = [7u8; 20];
+    let testnet = encode_address(&hash, 0x6f);
+    assert_eq!(decode_address(&testnet), Ok((0x6f, hash)));
+    assert_eq!(
+        validate_address(&testnet, ADDRESS_VERSION),
+        Err(AddressError::WrongVersion {
+            expected: ADDRESS_VERSION,
+            found: 0x6f
+        })
+    );
+    assert_eq!(validate_address(&testnet, 0x6f), Ok(hash));
+}
+
+#[test]
+fn test_base58_leading_zero_bytes() {
+    assert_eq!(base58_encode(&[]), "");
+    assert_eq!(base58_encode(&[0]), "1");
+    assert_eq!(base58_encode(&[0, 0, 1]), "112");
+    assert_eq!(base58_decode("112"), Ok(vec![0, 0, 1]));
+    assert_eq!(base58_encode(&[0, 0, 0x3a]), "1121");
+
+    // Version 0 always yields a leading '1'; a zero-prefixed hash adds more.
+    let mut hash = [0xabu8; 20];
+    hash[0] = 0;
+    hash[1] = 0;
+    let address = encode_address(&hash, 0);
+    assert!(address.starts_with("111"));
+    assert_eq!(decode_address(&address), Ok((0, hash)));
+}
+
+#[test]
+fn test_decode_rejects_wrong_length() {
+    let short = base58_encode(&[0u8; 10]);
+    assert_eq!(
+        decode_address(&short),
+        Err(AddressError::InvalidLength { len: 10 })
+    );
+}
+
+#[test]
+fn test_strict_addresses_off_by_default() {
+    assert!(!ConsensusParams::default().strict_addresses);
+    let (chain, utxos) = consensus_fixture();
+    let block = mined_on(&chain, vec![reward(50), spend(0, vec![output("bob", 990)])]);
+    assert_eq!(check(&chain, &utxos, &block), Ok(()));
+}
+
+#[test]
+fn test_strict_addresses_reject_plain_names() {
+    let (mut chain, utxos) = consensus_fixture();
+    chain.params.strict_addresses = true;
+    let miner = derive_address_from_pubkey("miner-pubkey");
+    let coinbase = Transaction::coinbase(miner, 50, 2000, "reward".into());
+    let tx = spend(0, vec![output("bob", 990)]);
+    let txid = tx.txid.clone();
+    let block = mined_on(&chain, vec![coinbase, tx.clone()]);
+
+    assert_eq!(
+        check(&chain, &utxos, &block),
+        Err(BlockError::InvalidAddress {
+            txid,
+            address: "bob".into(),
+            reason: AddressError::InvalidLength { len: 3 },
+        })
+    );
+    let err = validate_transaction_with(&tx, &utxos, 0, 1, &chain.params).unwrap_err();
+    assert!(err.contains("bob"), "{}", err);
+    assert_eq!(
+        validate_transaction_with(&tx, &utxos, 0, 1, &small_params()),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_strict_addresses_accept_derived_addresses() {
+    let (mut chain, utxos) = consensus_fixture();
+    chain.params.strict_addresses = true;
+    let miner = derive_address_from_pubkey("miner-pubkey");
+    let bob = derive_address_from_pubkey("bob-pubkey");
+    let coinbase = Transaction::coinbase(miner, 50, 2000, "reward".into());
+    let tx = spend(0, vec![output(&bob, 990)]);
+    assert_eq!(
+        validate_transaction_with(&tx, &utxos, 0, 1, &chain.params),
+        Ok(())
+    );
+    let block = mined_on(&chain, vec![coinbase, tx]);
+    assert_eq!(check(&chain, &utxos, &block), Ok(()));
+}
@@ -85,5 +85,201 @@ pub fn divide_and_round(_a: f64, _b: f64) -> Result<i64, MathError> {
     todo!("Safe divide and round to nearest i64")
 }
 
+#[derive(Debug)]
+pub enum CalcError {
+    Parse(ParseError),
+    Math(MathError),
+    UnknownOperator(String),
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        todo!("Describe this layer only; the inner error is the source")
+    }
+}
+
+impl std::error::Error for CalcError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        todo!("Return the wrapped ParseError or MathError")
+    }
+}
+
+impl From<ParseError> for CalcError {
+    fn from(e: ParseError) -> Self {
+        CalcError::Parse(e)
+    }
+}
+
+impl From<MathError> for CalcError {
+    fn from(e: MathError) -> Self {
+        CalcError::Math(e)
+    }
+}
+
+pub fn calculate_from_strings(_a: &str, _b: &str, _op: &str) -> Result<f64, CalcError> {
+    todo!("Parse both operands with ? and apply +, -, * or /")
+}
+
+#[derive(Debug)]
+pub struct ContextError {
+    pub msg: String,
+    pub source: Box<dyn std::error::Error + 'static>,
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        todo!("Display the context message")
+    }
+}
+
+impl std::error::Error for ContextError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        todo!("Return the boxed error")
+    }
+}
+
+pub trait Context<T> {
+    fn context<M: Into<String>>(self, msg: M) -> Result<T, ContextError>;
+    fn with_context<M: Into<String>, F: FnOnce() -> M>(self, f: F) -> Result<T, ContextError>;
+}
+
+impl<T, E: std::error::Error + 'static> Context<T> for Result<T, E> {
+    fn context<M: Into<String>>(self, _msg: M) -> Result<T, ContextError> {
+        todo!("Wrap the error in a ContextError")
+    }
+
+    fn with_context<M: Into<String>, F: FnOnce() -> M>(self, _f: F) -> Result<T, ContextError> {
+        todo!("Wrap the error, building the message lazily")
+    }
+}
+
+pub fn error_chain(_err: &dyn std::error::Error) -> Vec<String> {
+    todo!("Collect Display strings down the source() chain")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RetryError<E> {
+    Exhausted(Vec<E>),
+}
+
+pub fn retry<T, E>(_attempts: u32, _f: impl FnMut() -> Result<T, E>) -> Result<T, RetryError<E>> {
+    // TODO: Call `f` up to `attempts` times, collecting every error in order.
+    todo!("Retry a fallible operation")
+}
+
+pub fn fallback_chain<'a, T, E>(
+    _ops: Vec<Box<dyn FnMut() -> Result<T, E> + 'a>>,
+) -> Result<T, Vec<E>> {
+    todo!("Try each operation until one succeeds")
+}
+
+pub fn with_default<T: Default, E>(_r: Result<T, E>) -> T {
+    todo!("Fall back to T::default() on error")
+}
+
+pub fn parse_first_valid(_inputs: &[&str]) -> Result<i32, Vec<ParseError>> {
+    // TODO: Box one `parse_positive_bounded` closure per input and hand
+    // them to `fallback_chain`.
+    todo!("Parse the first valid input")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    UserInput,
+    NotFound,
+    Conflict,
+    Internal,
+    Unavailable,
+}
+
+pub trait Categorize {
+    fn category(&self) -> ErrorCategory;
+}
+
+impl Categorize for MathError {
+    fn category(&self) -> ErrorCategory {
+        // TODO: Bad operands are UserInput; Overflow is Internal.
+        todo!("Categorize MathError")
+    }
+}
+
+impl Categorize for ParseError {
+    fn category(&self) -> ErrorCategory {
+        todo!("Categorize ParseError")
+    }
+}
+
+impl Categorize for CalcError {
+    fn category(&self) -> ErrorCategory {
+        // TODO: Delegate to the wrapped error.
+        todo!("Categorize CalcError")
+    }
+}
+
+impl Categorize for std::io::Error {
+    fn category(&self) -> ErrorCategory {
+        // TODO: Map self.kind() onto a category.
+        todo!("Categorize io::Error")
+    }
+}
+
+impl<E> Categorize for RetryError<E> {
+    fn category(&self) -> ErrorCategory {
+        todo!("Categorize RetryError")
+    }
+}
+
+impl<T: Categorize + ?Sized> Categorize for Box<T> {
+    fn category(&self) -> ErrorCategory {
+        todo!("Categorize the boxed value")
+    }
+}
+
+#[derive(Debug)]
+pub enum AppError {
+    BadRequest(String),
+    NotFound(u64),
+    AlreadyExists(u64),
+    Storage(std::io::Error),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        todo!("Display AppError")
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Categorize for AppError {
+    fn category(&self) -> ErrorCategory {
+        todo!("Categorize AppError")
+    }
+}
+
+pub fn exit_code_for(_category: ErrorCategory) -> i32 {
+    // TODO: sysexits codes: 65, 66, 69, 70, 73.
+    todo!("Map category to exit code")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorReport {
+    pub message: String,
+    pub category: ErrorCategory,
+    pub chain: Vec<String>,
+    pub exit_code: i32,
+}
+
+pub fn categorize(_err: &(dyn std::error::Error + 'static)) -> ErrorCategory {
+    // TODO: Walk source(), downcast_ref to each known error type, and let
+    // the first hit decide. Default to Internal.
+    todo!("Categorize a dyn Error")
+}
+
+pub fn report(_err: &(dyn std::error::Error + 'static)) -> ErrorReport {
+    // TODO: Message, category, the source() chain below it, and exit code.
+    todo!("Build an error report")
+}
+
 #[doc(hidden)]
 pub mod solution;
@@ -1,6 +1,9 @@
 //! # Error Handling Demo
 
-use error_handling_alternative::solution::{divide, safe_add, safe_divide, safe_sqrt};
+use error_handling_alternative::solution::{
+    calculate_from_strings, divide, error_chain, parse_first_valid, report, safe_add, safe_divide,
+    safe_sqrt, Context,
+};
 
 fn main() {
     println!("=== Error Handling Demo ===");
@@ -12,4 +15,20 @@ fn main() {
     println!("safe_divide(10,0)={:?}", safe_divide(10.0, 0.0));
     println!("safe_sqrt(9)={:?}", safe_sqrt(9.0));
     println!("safe_add(i32::MAX,1)={:?}", safe_add(i32::MAX, 1));
+
+    if let Err(err) = calculate_from_strings("12", "abc", "/").context("evaluating row 3") {
+        println!("error chain: {}", error_chain(&err).join(": "));
+
+        let report = report(&err);
+        println!("{}", report);
+        println!(
+            "category {:?} -> exit code {}",
+            report.category, report.exit_code
+        );
+    }
+
+    println!(
+        "parse_first_valid([\"oops\", \"-3\", \"17\"])={:?}",
+        parse_first_valid(&["oops", "-3", "17"])
+    );
 }
@@ -9,8 +9,12 @@
 //! - The ? operator propagates errors by returning early with Err
 //! - Custom error enums let you define domain-specific error types
 //! - `Option<T>` handles the absence of a value without null pointers
+//! - `source()` links an error to the error that caused it, forming a chain
+//! - Retry/fallback combinators take closures, so they own no I/O themselves
+//! - `report` only borrows the error; downcasting needs `'static` error types
 
 use std::fmt;
+use std::io;
 use std::num::ParseIntError;
 
 // ============================================================================
@@ -152,6 +156,11 @@ impl fmt::Display for ParseError {
     }
 }
 
+/// `InvalidNumber` already prints the `ParseIntError` in its Display, so
+/// `source()` keeps the default `None`; returning it as well would make a
+/// chain reporter print the same text twice.
+impl std::error::Error for ParseError {}
+
 impl From<ParseIntError> for ParseError {
     fn from(e: ParseIntError) -> Self {
         ParseError::InvalidNumber(e)
@@ -194,6 +203,431 @@ pub fn divide_and_round(a: f64, b: f64) -> Result<i64, MathError> {
     safe_divide(a, b).map(|result| result.round() as i64)
 }
 
+// ============================================================================
+// ERROR CHAINING: source() AND CONTEXT
+// ============================================================================
+
+/// Top-level error for `calculate_from_strings`, wrapping the lower layers.
+///
+/// # Teaching Note
+/// Display describes only *this* layer; the wrapped error is reachable via
+/// `source()`. Printing both would repeat the inner message once per level
+/// when a reporter walks the chain.
+#[derive(Debug)]
+pub enum CalcError {
+    Parse(ParseError),
+    Math(MathError),
+    UnknownOperator(String),
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcError::Parse(_) => write!(f, "could not parse operand"),
+            CalcError::Math(_) => write!(f, "calculation failed"),
+            CalcError::UnknownOperator(op) => write!(f, "unknown operator '{}'", op),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CalcError::Parse(e) => Some(e),
+            CalcError::Math(e) => Some(e),
+            CalcError::UnknownOperator(_) => None,
+        }
+    }
+}
+
+impl From<ParseError> for CalcError {
+    fn from(e: ParseError) -> Self {
+        CalcError::Parse(e)
+    }
+}
+
+impl From<MathError> for CalcError {
+    fn from(e: MathError) -> Self {
+        CalcError::Math(e)
+    }
+}
+
+/// Parses both operands and applies `op` ("+", "-", "*" or "/").
+///
+/// Each `?` converts the lower-level error into `CalcError` through the
+/// `From` impls above, keeping the original as the source.
+pub fn calculate_from_strings(a: &str, b: &str, op: &str) -> Result<f64, CalcError> {
+    let a = parse_positive_bounded(a)?;
+    let b = parse_positive_bounded(b)?;
+    match op {
+        "+" => Ok(safe_add(a, b)? as f64),
+        "-" => Ok((a - b) as f64),
+        "*" => Ok(safe_multiply(a, b)? as f64),
+        "/" => Ok(safe_divide(a as f64, b as f64)?),
+        other => Err(CalcError::UnknownOperator(other.to_string())),
+    }
+}
+
+/// An error with a human-readable note about what was being attempted.
+///
+/// # Memory Model
+/// The wrapped error is boxed, so any error type fits and `ContextError`
+/// stays two words plus the String no matter how deep the chain is.
+#[derive(Debug)]
+pub struct ContextError {
+    pub msg: String,
+    pub source: Box<dyn std::error::Error + 'static>,
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.msg)
+    }
+}
+
+impl std::error::Error for ContextError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Adds `.context()` / `.with_context()` to any `Result` whose error is a
+/// std error -- a dependency-free take on what `anyhow` provides.
+pub trait Context<T> {
+    /// Wraps the error with a fixed message.
+    fn context<M: Into<String>>(self, msg: M) -> Result<T, ContextError>;
+
+    /// Like `context`, but only builds the message if there is an error.
+    fn with_context<M: Into<String>, F: FnOnce() -> M>(self, f: F) -> Result<T, ContextError>;
+}
+
+impl<T, E: std::error::Error + 'static> Context<T> for Result<T, E> {
+    fn context<M: Into<String>>(self, msg: M) -> Result<T, ContextError> {
+        self.map_err(|e| ContextError {
+            msg: msg.into(),
+            source: Box::new(e),
+        })
+    }
+
+    fn with_context<M: Into<String>, F: FnOnce() -> M>(self, f: F) -> Result<T, ContextError> {
+        self.map_err(|e| ContextError {
+            msg: f().into(),
+            source: Box::new(e),
+        })
+    }
+}
+
+/// Collects the Display text of `err` and every error below it.
+pub fn error_chain(err: &dyn std::error::Error) -> Vec<String> {
+    let mut chain = vec![err.to_string()];
+    let mut current = err.source();
+    while let Some(e) = current {
+        chain.push(e.to_string());
+        current = e.source();
+    }
+    chain
+}
+
+// ============================================================================
+// RESILIENCE: RETRY AND FALLBACK
+// ============================================================================
+
+/// Why `retry` gave up.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RetryError<E> {
+    /// Every attempt failed; the errors are in the order they happened.
+    Exhausted(Vec<E>),
+}
+
+impl<E: fmt::Display> fmt::Display for RetryError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetryError::Exhausted(errors) => match errors.last() {
+                Some(last) => write!(
+                    f,
+                    "all {} attempts failed; last error: {}",
+                    errors.len(),
+                    last
+                ),
+                None => write!(f, "no attempts were made"),
+            },
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for RetryError<E> {}
+
+/// Calls `f` up to `attempts` times, returning the first success.
+///
+/// # Teaching Note
+/// `FnMut` (not `Fn`) lets the closure mutate what it captures -- a counter,
+/// an iterator of inputs, a connection it re-opens. Errors are kept rather
+/// than dropped so the caller can see *why* each attempt failed.
+pub fn retry<T, E>(attempts: u32, mut f: impl FnMut() -> Result<T, E>) -> Result<T, RetryError<E>> {
+    let mut errors = Vec::new();
+    for _ in 0..attempts {
+        match f() {
+            Ok(value) => return Ok(value),
+            Err(e) => errors.push(e),
+        }
+    }
+    Err(RetryError::Exhausted(errors))
+}
+
+/// Tries each operation in order and returns the first success.
+///
+/// Later operations never run once one succeeds. If all fail, every error
+/// is returned in order. The `'a` bound lets the closures borrow local data
+/// instead of requiring `'static` captures.
+pub fn fallback_chain<'a, T, E>(
+    ops: Vec<Box<dyn FnMut() -> Result<T, E> + 'a>>,
+) -> Result<T, Vec<E>> {
+    let mut errors = Vec::new();
+    for mut op in ops {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) => errors.push(e),
+        }
+    }
+    Err(errors)
+}
+
+/// Unwraps a result, using `T::default()` for any error.
+pub fn with_default<T: Default, E>(r: Result<T, E>) -> T {
+    r.unwrap_or_default()
+}
+
+/// Returns the first input that parses as a bounded positive number.
+///
+/// Builds one fallback operation per input on top of `parse_positive_bounded`.
+pub fn parse_first_valid(inputs: &[&str]) -> Result<i32, Vec<ParseError>> {
+    let ops: Vec<Box<dyn FnMut() -> Result<i32, ParseError> + '_>> = inputs
+        .iter()
+        .map(|&input| {
+            Box::new(move || parse_positive_bounded(input))
+                as Box<dyn FnMut() -> Result<i32, ParseError>>
+        })
+        .collect();
+    fallback_chain(ops)
+}
+
+// ============================================================================
+// CLI REPORTING: CATEGORIES AND EXIT CODES
+// ============================================================================
+// A library returns precise error types; a CLI `main` has to turn them into
+// two things: a message for a human and an exit code for a script. Matching
+// every concrete error type in `main` doesn't scale, so each error sorts
+// itself into a small set of categories (like `std::io::ErrorKind` does for
+// I/O) and the exit code is derived from the category alone.
+
+/// Coarse buckets an error can fall into, from the caller's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The input was bad; running again with the same input fails again.
+    UserInput,
+    /// Something the user referred to doesn't exist.
+    NotFound,
+    /// The operation clashes with existing state (e.g. a duplicate id).
+    Conflict,
+    /// A bug or an unexpected failure inside the program.
+    Internal,
+    /// A resource is temporarily out of reach; retrying later may work.
+    Unavailable,
+}
+
+/// Errors that know which `ErrorCategory` they belong to.
+pub trait Categorize {
+    fn category(&self) -> ErrorCategory;
+}
+
+impl Categorize for MathError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            MathError::DivisionByZero | MathError::NegativeSquareRoot => ErrorCategory::UserInput,
+            // The inputs were valid numbers; i32 just can't hold the answer
+            MathError::Overflow => ErrorCategory::Internal,
+        }
+    }
+}
+
+impl Categorize for ParseError {
+    fn category(&self) -> ErrorCategory {
+        ErrorCategory::UserInput
+    }
+}
+
+impl Categorize for CalcError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            // A wrapper reports the category of what it wraps
+            CalcError::Parse(e) => e.category(),
+            CalcError::Math(e) => e.category(),
+            CalcError::UnknownOperator(_) => ErrorCategory::UserInput,
+        }
+    }
+}
+
+impl Categorize for io::Error {
+    fn category(&self) -> ErrorCategory {
+        match self.kind() {
+            io::ErrorKind::NotFound => ErrorCategory::NotFound,
+            io::ErrorKind::AlreadyExists => ErrorCategory::Conflict,
+            io::ErrorKind::InvalidInput | io::ErrorKind::InvalidData => ErrorCategory::UserInput,
+            io::ErrorKind::TimedOut
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset => ErrorCategory::Unavailable,
+            _ => ErrorCategory::Internal,
+        }
+    }
+}
+
+impl<E> Categorize for RetryError<E> {
+    fn category(&self) -> ErrorCategory {
+        ErrorCategory::Unavailable
+    }
+}
+
+/// Boxed errors categorize like the value inside.
+impl<T: Categorize + ?Sized> Categorize for Box<T> {
+    fn category(&self) -> ErrorCategory {
+        (**self).category()
+    }
+}
+
+/// The kind of application error a todo or chat app defines, shown here to
+/// illustrate how an app's own errors slot into the same categories.
+#[derive(Debug)]
+pub enum AppError {
+    BadRequest(String),
+    NotFound(u64),
+    AlreadyExists(u64),
+    Storage(io::Error),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::BadRequest(msg) => write!(f, "bad request: {}", msg),
+            AppError::NotFound(id) => write!(f, "item {} not found", id),
+            AppError::AlreadyExists(id) => write!(f, "item {} already exists", id),
+            AppError::Storage(_) => write!(f, "storage failure"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::Storage(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> Self {
+        AppError::Storage(e)
+    }
+}
+
+impl Categorize for AppError {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            AppError::BadRequest(_) => ErrorCategory::UserInput,
+            AppError::NotFound(_) => ErrorCategory::NotFound,
+            AppError::AlreadyExists(_) => ErrorCategory::Conflict,
+            AppError::Storage(e) => e.category(),
+        }
+    }
+}
+
+/// Process exit code for a category, taken from BSD `sysexits.h` so shell
+/// scripts can tell failures apart. These values are part of the CLI's
+/// interface: don't renumber them.
+///
+/// | category    | code | sysexits name  |
+/// |-------------|------|----------------|
+/// | UserInput   | 65   | EX_DATAERR     |
+/// | NotFound    | 66   | EX_NOINPUT     |
+/// | Unavailable | 69   | EX_UNAVAILABLE |
+/// | Internal    | 70   | EX_SOFTWARE    |
+/// | Conflict    | 73   | EX_CANTCREAT   |
+pub fn exit_code_for(category: ErrorCategory) -> i32 {
+    match category {
+        ErrorCategory::UserInput => 65,
+        ErrorCategory::NotFound => 66,
+        ErrorCategory::Unavailable => 69,
+        ErrorCategory::Internal => 70,
+        ErrorCategory::Conflict => 73,
+    }
+}
+
+/// Everything a CLI `main` needs to print an error and exit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorReport {
+    /// Display text of the top-level error.
+    pub message: String,
+    pub category: ErrorCategory,
+    /// Display text of each `source()` below the top-level error, outermost
+    /// first. Empty when the error has no source.
+    pub chain: Vec<String>,
+    pub exit_code: i32,
+}
+
+impl fmt::Display for ErrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error: {}", self.message)?;
+        for cause in &self.chain {
+            write!(f, "\n  caused by: {}", cause)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds the category of an arbitrary error.
+///
+/// # Teaching Note
+/// `&dyn Error` has forgotten its concrete type, and a trait object can't
+/// be asked "do you implement `Categorize`?". What it *can* do is
+/// `downcast_ref::<T>()` to each type we know about. We walk down the chain
+/// and let the outermost known error decide, so wrappers like `ContextError`
+/// are looked through. Unknown errors count as `Internal`.
+pub fn categorize(err: &(dyn std::error::Error + 'static)) -> ErrorCategory {
+    let mut current = Some(err);
+    while let Some(e) = current {
+        let known = e
+            .downcast_ref::<AppError>()
+            .map(Categorize::category)
+            .or_else(|| e.downcast_ref::<CalcError>().map(Categorize::category))
+            .or_else(|| e.downcast_ref::<ParseError>().map(Categorize::category))
+            .or_else(|| e.downcast_ref::<MathError>().map(Categorize::category))
+            .or_else(|| e.downcast_ref::<io::Error>().map(Categorize::category));
+        if let Some(category) = known {
+            return category;
+        }
+        current = e.source();
+    }
+    ErrorCategory::Internal
+}
+
+/// Builds the report for `err`.
+///
+/// The error must be `'static` (own its data) because `categorize`
+/// downcasts; every error in this lab qualifies.
+pub fn report(err: &(dyn std::error::Error + 'static)) -> ErrorReport {
+    let category = categorize(err);
+    let mut chain = error_chain(err);
+    let message = chain.remove(0);
+    ErrorReport {
+        message,
+        category,
+        chain,
+        exit_code: exit_code_for(category),
+    }
+}
+
 // ============================================================================
 // UNIT TESTS
 // ============================================================================
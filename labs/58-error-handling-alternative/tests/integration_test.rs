@@ -4,6 +4,7 @@
 //! with ?, parse error handling, and combinator patterns.
 
 use error_handling_alternative::solution::*;
+use std::error::Error;
 
 // ============================================================================
 // OPTION TESTS (divide, safe_get, first_even)
@@ -321,3 +322,339 @@ fn test_parse_error_display_negative() {
         panic!("expected error");
     }
 }
+
+// ============================================================================
+// ERROR CHAIN AND CONTEXT TESTS
+// ============================================================================
+
+#[test]
+fn test_calculate_from_strings_ok() {
+    assert_eq!(calculate_from_strings("6", "3", "+").unwrap(), 9.0);
+    assert_eq!(calculate_from_strings("6", "3", "-").unwrap(), 3.0);
+    assert_eq!(calculate_from_strings("6", "3", "*").unwrap(), 18.0);
+    assert_eq!(calculate_from_strings("6", "3", "/").unwrap(), 2.0);
+}
+
+#[test]
+fn test_calculate_from_strings_error_variants() {
+    assert!(matches!(
+        calculate_from_strings("x", "3", "+"),
+        Err(CalcError::Parse(ParseError::InvalidNumber(_)))
+    ));
+    assert!(matches!(
+        calculate_from_strings("5000", "3", "+"),
+        Err(CalcError::Parse(ParseError::NumberTooLarge))
+    ));
+    assert!(matches!(
+        calculate_from_strings("1", "0", "/"),
+        Err(CalcError::Math(MathError::DivisionByZero))
+    ));
+    let err = calculate_from_strings("1", "2", "%").unwrap_err();
+    assert_eq!(err.to_string(), "unknown operator '%'");
+    assert!(err.source().is_none());
+}
+
+#[test]
+fn test_calc_error_source_walks_two_levels() {
+    let err = calculate_from_strings("abc", "1", "+")
+        .context("reading input")
+        .unwrap_err();
+    assert_eq!(err.to_string(), "reading input");
+
+    let calc = err.source().expect("ContextError wraps a CalcError");
+    assert_eq!(calc.to_string(), "could not parse operand");
+
+    let parse = calc.source().expect("CalcError wraps a ParseError");
+    assert_eq!(
+        parse.to_string(),
+        "invalid number: invalid digit found in string"
+    );
+    assert!(parse.source().is_none());
+}
+
+#[test]
+fn test_math_error_is_source_of_calc_error() {
+    let err = calculate_from_strings("4", "0", "/").unwrap_err();
+    assert_eq!(err.to_string(), "calculation failed");
+    let inner = err.source().unwrap();
+    assert_eq!(inner.to_string(), "division by zero");
+    assert!(inner.source().is_none());
+}
+
+#[test]
+fn test_context_wraps_error_and_keeps_chain() {
+    let err = calculate_from_strings("1", "0", "/")
+        .context("computing average")
+        .unwrap_err();
+
+    assert_eq!(err.to_string(), "computing average");
+    assert_eq!(
+        error_chain(&err),
+        vec![
+            "computing average",
+            "calculation failed",
+            "division by zero"
+        ]
+    );
+}
+
+#[test]
+fn test_with_context_is_lazy_and_stacks() {
+    let mut called = false;
+    let ok: Result<i32, ParseError> = Ok(5);
+    let value = ok
+        .with_context(|| {
+            called = true;
+            "never built"
+        })
+        .unwrap();
+    assert_eq!(value, 5);
+    assert!(!called);
+
+    let err = parse_positive_bounded("-4")
+        .with_context(|| format!("reading field {}", "age"))
+        .context("loading profile")
+        .unwrap_err();
+    assert_eq!(
+        error_chain(&err),
+        vec![
+            "loading profile",
+            "reading field age",
+            "negative numbers not allowed"
+        ]
+    );
+}
+
+// ============================================================================
+// RETRY AND FALLBACK TESTS
+// ============================================================================
+
+#[test]
+fn test_retry_succeeds_on_nth_attempt() {
+    let mut calls = 0;
+    let result = retry(5, || {
+        calls += 1;
+        if calls < 3 {
+            Err(format!("attempt {} failed", calls))
+        } else {
+            Ok(calls * 10)
+        }
+    });
+    assert_eq!(result, Ok(30));
+    assert_eq!(calls, 3);
+}
+
+#[test]
+fn test_retry_exhaustion_keeps_errors_in_order() {
+    let mut calls = 0;
+    let result: Result<(), _> = retry(3, || {
+        calls += 1;
+        safe_divide(calls as f64, 0.0).map(|_| ())
+    });
+    assert_eq!(
+        result,
+        Err(RetryError::Exhausted(vec![MathError::DivisionByZero; 3]))
+    );
+
+    let mut inputs = ["x", "-1", "5000"].into_iter();
+    let err = retry(3, || parse_positive_bounded(inputs.next().unwrap())).unwrap_err();
+    let RetryError::Exhausted(errors) = &err;
+    assert!(matches!(
+        errors.as_slice(),
+        [
+            ParseError::InvalidNumber(_),
+            ParseError::NegativeNumber,
+            ParseError::NumberTooLarge
+        ]
+    ));
+    assert_eq!(
+        err.to_string(),
+        "all 3 attempts failed; last error: number too large (max 1000)"
+    );
+}
+
+#[test]
+fn test_retry_zero_attempts() {
+    let result: Result<i32, RetryError<String>> = retry(0, || Ok(1));
+    assert_eq!(result, Err(RetryError::Exhausted(vec![])));
+}
+
+#[test]
+fn test_fallback_chain_short_circuits() {
+    use std::cell::Cell;
+
+    let third_called = Cell::new(false);
+    let ops: Vec<Box<dyn FnMut() -> Result<f64, MathError> + '_>> = vec![
+        Box::new(|| safe_divide(1.0, 0.0)),
+        Box::new(|| safe_sqrt(16.0)),
+        Box::new(|| {
+            third_called.set(true);
+            Ok(0.0)
+        }),
+    ];
+    assert_eq!(fallback_chain(ops), Ok(4.0));
+    assert!(!third_called.get());
+}
+
+#[test]
+fn test_fallback_chain_collects_all_errors() {
+    let ops: Vec<Box<dyn FnMut() -> Result<f64, MathError>>> = vec![
+        Box::new(|| safe_divide(1.0, 0.0)),
+        Box::new(|| safe_sqrt(-1.0)),
+    ];
+    assert_eq!(
+        fallback_chain(ops),
+        Err(vec![
+            MathError::DivisionByZero,
+            MathError::NegativeSquareRoot
+        ])
+    );
+    assert_eq!(fallback_chain::<i32, MathError>(Vec::new()), Err(vec![]));
+}
+
+#[test]
+fn test_with_default() {
+    assert_eq!(with_default(safe_add(2, 3)), 5);
+    assert_eq!(with_default(safe_add(i32::MAX, 1)), 0);
+    assert_eq!(with_default::<String, ()>(Err(())), "");
+}
+
+#[test]
+fn test_parse_first_valid() {
+    assert_eq!(parse_first_valid(&["abc", "-7", "42", "9"]).unwrap(), 42);
+
+    let errors = parse_first_valid(&["", "2000"]).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors[0], ParseError::InvalidNumber(_)));
+    assert!(matches!(errors[1], ParseError::NumberTooLarge));
+}
+
+// ============================================================================
+// CATEGORY AND EXIT CODE TESTS
+// ============================================================================
+
+#[test]
+fn test_math_error_categories() {
+    assert_eq!(
+        MathError::DivisionByZero.category(),
+        ErrorCategory::UserInput
+    );
+    assert_eq!(
+        MathError::NegativeSquareRoot.category(),
+        ErrorCategory::UserInput
+    );
+    assert_eq!(MathError::Overflow.category(), ErrorCategory::Internal);
+}
+
+#[test]
+fn test_parse_and_calc_error_categories() {
+    let parse_err = parse_positive_bounded("abc").unwrap_err();
+    assert_eq!(parse_err.category(), ErrorCategory::UserInput);
+    assert_eq!(
+        ParseError::NumberTooLarge.category(),
+        ErrorCategory::UserInput
+    );
+
+    // CalcError takes the category of what it wraps
+    assert_eq!(
+        calculate_from_strings("4", "0", "/")
+            .unwrap_err()
+            .category(),
+        ErrorCategory::UserInput
+    );
+    assert_eq!(
+        CalcError::Math(MathError::Overflow).category(),
+        ErrorCategory::Internal
+    );
+    assert_eq!(
+        CalcError::UnknownOperator("%".to_string()).category(),
+        ErrorCategory::UserInput
+    );
+}
+
+#[test]
+fn test_app_error_categories() {
+    use std::io;
+
+    assert_eq!(
+        AppError::BadRequest("empty title".to_string()).category(),
+        ErrorCategory::UserInput
+    );
+    assert_eq!(AppError::NotFound(7).category(), ErrorCategory::NotFound);
+    assert_eq!(
+        AppError::AlreadyExists(7).category(),
+        ErrorCategory::Conflict
+    );
+    assert_eq!(
+        AppError::from(io::Error::from(io::ErrorKind::TimedOut)).category(),
+        ErrorCategory::Unavailable
+    );
+    assert_eq!(
+        AppError::from(io::Error::from(io::ErrorKind::PermissionDenied)).category(),
+        ErrorCategory::Internal
+    );
+
+    let boxed: Box<AppError> = Box::new(AppError::NotFound(1));
+    assert_eq!(boxed.category(), ErrorCategory::NotFound);
+
+    let exhausted: RetryError<AppError> = RetryError::Exhausted(vec![]);
+    assert_eq!(exhausted.category(), ErrorCategory::Unavailable);
+}
+
+#[test]
+fn test_exit_codes_are_stable() {
+    assert_eq!(exit_code_for(ErrorCategory::UserInput), 65);
+    assert_eq!(exit_code_for(ErrorCategory::NotFound), 66);
+    assert_eq!(exit_code_for(ErrorCategory::Unavailable), 69);
+    assert_eq!(exit_code_for(ErrorCategory::Internal), 70);
+    assert_eq!(exit_code_for(ErrorCategory::Conflict), 73);
+}
+
+#[test]
+fn test_report_single_error_has_empty_chain() {
+    let report = report(&AppError::AlreadyExists(3));
+    assert_eq!(report.message, "item 3 already exists");
+    assert_eq!(report.category, ErrorCategory::Conflict);
+    assert!(report.chain.is_empty());
+    assert_eq!(report.exit_code, 73);
+    assert_eq!(report.to_string(), "error: item 3 already exists");
+}
+
+#[test]
+fn test_report_walks_chain_through_context() {
+    let err = calculate_from_strings("12", "abc", "/")
+        .context("evaluating row 3")
+        .unwrap_err();
+    let report = report(&err);
+
+    assert_eq!(report.message, "evaluating row 3");
+    // ContextError -> CalcError -> ParseError (which has no source)
+    assert_eq!(report.chain.len(), 2);
+    assert_eq!(report.chain[0], "could not parse operand");
+    // ContextError is unknown, so the CalcError below it decides
+    assert_eq!(report.category, ErrorCategory::UserInput);
+    assert_eq!(report.exit_code, 65);
+    assert_eq!(report.to_string().lines().count(), 3);
+}
+
+#[test]
+fn test_report_unknown_error_is_internal() {
+    let err = "x".parse::<u8>().unwrap_err();
+    let report = report(&err);
+    assert_eq!(report.category, ErrorCategory::Internal);
+    assert_eq!(report.exit_code, 70);
+}
+
+#[test]
+fn test_report_io_error_inside_context() {
+    let io_err = std::fs::read("/definitely/not/here.txt").unwrap_err();
+    let err = Err::<(), _>(AppError::from(io_err))
+        .context("loading todo list")
+        .unwrap_err();
+    let report = report(&err);
+
+    assert_eq!(report.chain.len(), 2);
+    assert_eq!(report.chain[0], "storage failure");
+    assert_eq!(report.category, ErrorCategory::NotFound);
+    assert_eq!(categorize(&err), ErrorCategory::NotFound);
+}
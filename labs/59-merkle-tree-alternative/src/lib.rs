@@ -15,18 +15,161 @@ pub fn hash_pair(_left: &str, _right: &str) -> String {
     todo!("Hash concatenated child hashes")
 }
 
+pub trait Hasher {
+    fn hash(&self, data: &[u8]) -> String;
+
+    fn hash_pair(&self, l: &str, r: &str) -> String {
+        self.hash(format!("{}{}", l, r).as_bytes())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StdHasher;
+
+impl Hasher for StdHasher {
+    fn hash(&self, _data: &[u8]) -> String {
+        todo!("Delegate to hash_bytes")
+    }
+}
+
+#[cfg(feature = "sha256")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sha256Hasher;
+
+#[cfg(feature = "sha256")]
+impl Hasher for Sha256Hasher {
+    fn hash(&self, _data: &[u8]) -> String {
+        todo!("Hex-encode sha2::Sha256::digest(data)")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum Position {
+    // TODO: Serialize as "L" / "R" to keep proofs compact.
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProofStep {
+    pub hash: String,
+    pub position: Position,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Proof {
+    pub leaf_index: usize,
+    pub steps: Vec<ProofStep>,
+}
+
+impl Proof {
+    pub fn from_tuples(_leaf_index: usize, _proof: &[(String, bool)]) -> Self {
+        todo!("`true` means the sibling is on the right")
+    }
+
+    pub fn to_tuples(&self) -> Vec<(String, bool)> {
+        todo!("Convert steps back to (hash, is_right)")
+    }
+
+    pub fn verify(&self, _root: &str, _data: &str) -> bool {
+        todo!("Verify with StdHasher")
+    }
+
+    pub fn verify_with<H: Hasher>(&self, _hasher: &H, _root: &str, _data: &str) -> bool {
+        todo!("Hash up the path, placing each sibling on its side")
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        todo!("serde_json::to_string")
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json(_json: &str) -> Result<Self, String> {
+        todo!("serde_json::from_str")
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MultiProof {
+    pub hashes: Vec<String>,
+}
+
+impl MultiProof {
+    pub fn len(&self) -> usize {
+        todo!("Return the number of proof hashes")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        todo!("Return true when no hashes are needed")
+    }
+}
+
 #[derive(Debug, Clone)]
-pub struct MerkleTree {
-    root: String,
-    leaves: Vec<String>,
-    nodes: Vec<String>,
+pub struct MerkleTree<H: Hasher = StdHasher> {
+    levels: Vec<Vec<String>>,
+    hasher: H,
 }
 
-impl MerkleTree {
+impl MerkleTree<StdHasher> {
     pub fn new(_data: &[&str]) -> Self {
+        todo!("Build a StdHasher tree via with_hasher")
+    }
+
+    pub fn verify_proof(_root: &str, _data: &str, _proof: &[(String, bool)]) -> bool {
+        todo!("Verify Merkle inclusion proof with StdHasher")
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json(_json: &str) -> Result<Self, String> {
+        todo!("Load a StdHasher tree via from_json_with")
+    }
+
+    pub fn verify_multi_proof(
+        _root: &str,
+        _items: &[(usize, &str)],
+        _proof: &MultiProof,
+        _leaf_count: usize,
+    ) -> bool {
+        todo!("Verify a multi-leaf proof with StdHasher")
+    }
+}
+
+impl<H: Hasher> MerkleTree<H> {
+    pub fn with_hasher(_data: &[&str], _hasher: H) -> Self {
         todo!("Build Merkle tree bottom-up from leaves")
     }
 
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        // TODO: Save only the leaf hashes and the root as `expected_root`.
+        todo!("Serialize the tree")
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json_with(_json: &str, _hasher: H) -> Result<Self, String> {
+        // TODO: Rebuild from the leaf hashes and reject a root that doesn't
+        // match `expected_root`.
+        todo!("Load and cross-check a saved tree")
+    }
+
+    pub fn hasher(&self) -> &H {
+        todo!("Return the hashing strategy")
+    }
+
+    pub fn append(&mut self, _data: &str) {
+        // TODO: Push the leaf hash, then rehash only its ancestors
+        // (parent index = index / 2 at every level).
+        todo!("Append a leaf in O(log n)")
+    }
+
+    pub fn update_leaf(&mut self, _index: usize, _new_data: &str) -> Result<(), String> {
+        todo!("Replace a leaf and rehash its path")
+    }
+
     pub fn root(&self) -> &str {
         let _ = self;
         todo!("Return root hash")
@@ -53,11 +196,39 @@ impl MerkleTree {
     }
 
     pub fn generate_proof(&self, _leaf_index: usize) -> Option<Vec<(String, bool)>> {
+        let _ = self;
+        todo!("Adapt `prove` to the tuple form")
+    }
+
+    pub fn prove(&self, _leaf_index: usize) -> Option<Proof> {
         let _ = self;
         todo!("Generate sibling path proof for leaf")
     }
 
-    pub fn verify_proof(_root: &str, _data: &str, _proof: &[(String, bool)]) -> bool {
+    pub fn generate_multi_proof(&self, _leaf_indices: &[usize]) -> Option<MultiProof> {
+        // TODO: Sort/dedup the indices, then walk up the levels. Record a
+        // sibling hash only when the sibling is not itself a known node.
+        todo!("Generate one proof for several leaves")
+    }
+
+    pub fn verify_multi_proof_with(
+        _hasher: &H,
+        _root: &str,
+        _items: &[(usize, &str)],
+        _proof: &MultiProof,
+        _leaf_count: usize,
+    ) -> bool {
+        // TODO: Rebuild the root level by level, taking siblings from the
+        // known nodes first and from the proof otherwise.
+        todo!("Verify a multi-leaf proof")
+    }
+
+    pub fn verify_proof_with(
+        _hasher: &H,
+        _root: &str,
+        _data: &str,
+        _proof: &[(String, bool)],
+    ) -> bool {
         todo!("Verify Merkle inclusion proof")
     }
 }
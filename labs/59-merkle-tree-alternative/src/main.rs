@@ -1,12 +1,14 @@
 //! # Merkle Tree Demo
 
 use merkle_tree_alternative::solution::MerkleTree;
+#[cfg(feature = "sha256")]
+use merkle_tree_alternative::solution::Sha256Hasher;
 
 fn main() {
     println!("=== Merkle Tree Demo ===");
 
     let data = vec!["tx1", "tx2", "tx3", "tx4"];
-    let tree = MerkleTree::new(&data);
+    let mut tree = MerkleTree::new(&data);
     println!("leaf_count={} node_count={}", tree.leaf_count(), tree.node_count());
     println!("root={}", tree.root());
 
@@ -14,4 +16,44 @@ fn main() {
         let ok = MerkleTree::verify_proof(tree.root(), data[0], &proof);
         println!("proof for tx1 valid={}", ok);
     }
+
+    tree.append("tx5");
+    let rebuilt = MerkleTree::new(&["tx1", "tx2", "tx3", "tx4", "tx5"]);
+    println!(
+        "after append: root={} matches rebuild={}",
+        tree.root(),
+        tree.root() == rebuilt.root()
+    );
+
+    if let Some(multi) = tree.generate_multi_proof(&[0, 1, 4]) {
+        let items = [(0, "tx1"), (1, "tx2"), (4, "tx5")];
+        let ok = MerkleTree::verify_multi_proof(tree.root(), &items, &multi, tree.leaf_count());
+        println!(
+            "multi proof for 3 leaves uses {} hashes, valid={}",
+            multi.len(),
+            ok
+        );
+    }
+
+    #[cfg(feature = "json")]
+    if let Some(proof) = tree.prove(4) {
+        let json = proof.to_json();
+        let received = merkle_tree_alternative::solution::Proof::from_json(&json);
+        println!(
+            "proof as JSON ({} bytes) verifies after reload={}",
+            json.len(),
+            received.is_ok_and(|p| p.verify(tree.root(), "tx5"))
+        );
+        let reloaded = MerkleTree::from_json(&tree.to_json());
+        println!(
+            "tree reloaded from JSON, same root={}",
+            reloaded.is_ok_and(|t| t.root() == tree.root())
+        );
+    }
+
+    #[cfg(feature = "sha256")]
+    {
+        let sha_tree = MerkleTree::with_hasher(&data, Sha256Hasher);
+        println!("sha256 root={}", sha_tree.root());
+    }
 }
@@ -5,7 +5,7 @@
 //! educational purposes, keeping the workspace dependency-free.
 //!
 //! ## Classroom Narrative
-//! 1. **Data layout**: Each tree node is a `String` owning 32 bytes of hash. `MerkleTree` stores the root, leaves, and all nodes in `Vec<String>`, so the heap owns every hash and the struct only stores pointers+lengths on the stack.
+//! 1. **Data layout**: Each tree node is a `String` owning 32 bytes of hash. `MerkleTree` stores the tree level by level in `Vec<Vec<String>>` (leaves first, root last), so the heap owns every hash and the struct only stores pointers+lengths on the stack.
 //! 2. **Building the tree**: We clone leaf hashes to build parent levels; clones are value copies (heap bytes duplicated) so each level owns its data. The borrow checker sees no overlapping mutable borrows because we only mutate local vectors until the final tree is assembled.
 //! 3. **Proofs & verification**: Proof generation clones sibling hashes into a `Vec<(String, bool)>`. These owned tuples stay valid even after the tree is dropped because they own their bytes. Verification borrows the root string immutably (`&str`), avoiding extra allocations.
 //!
@@ -16,13 +16,19 @@
 //!
 //! ## Step-by-step Teaching Breakdown
 //! 1. **Leaf hashing**: `hash_string` and `hash_bytes` convert raw data into owned `String` hashes. Each hash is heap data; the stack holds the `Vec<String>` handles while we build levels.
-//! 2. **Level reduction**: `new` iterates pairs of nodes, hashing them into parent level strings and pushing them into the `nodes` Vec. Odd nodes are promoted via clones (value copies) to keep tree balance.
+//! 2. **Level reduction**: `new` iterates pairs of nodes, hashing them into parent level strings and pushing each finished level onto `levels`. Odd nodes are promoted via clones (value copies) to keep tree balance.
 //! 3. **Proof generation**: `generate_proof` replays the level-by-level reduction, pushing sibling hashes into a proof vector along with booleans indicating left/right positions.
-//! 4. **Proof verification**: (Solution not shown) would take a borrowed root and recompute the hash path using owned hashes from the proof, ensuring the leaf belongs to the root without reading the entire tree.
+//! 4. **Incremental updates**: because every level is kept, `append` and `update_leaf` only rehash the one node per level above the changed leaf -- O(log n) hashes instead of rebuilding all n.
+//! 5. **Batch proofs**: `generate_multi_proof` walks the levels with the whole set of requested indices at once. When both children of a parent are already known, neither needs a proof hash, so proving neighbours together is cheaper than proving each alone.
+//! 6. **Pluggable hashing**: `MerkleTree<H: Hasher = StdHasher>` takes its hash function as a type parameter, the same trick `HashMap<K, V, S = RandomState>` uses. `new` keeps building std-hashed trees; `with_hasher` accepts any other strategy, such as `Sha256Hasher` behind the `sha256` feature.
+//! 7. **Portable proofs**: `prove` returns a `Proof` whose steps say outright which side each sibling is on, and (with the `json` feature) proofs and trees round-trip through JSON so a verifier in another process can check them. The older `Vec<(String, bool)>` API is a thin adapter over `Proof`.
+//! 8. **Proof verification**: `Proof::verify` (and `MerkleTree::verify_proof` for tuple proofs) takes a borrowed root, rehashes the leaf data, and folds in each owned sibling hash on its recorded side. Only the proof and the root are needed, never the tree itself.
 //!
 
 use std::collections::hash_map::DefaultHasher;
-use std::hash::Hasher;
+// Imported anonymously: we only need its methods, and the name `Hasher`
+// belongs to our own trait below.
+use std::hash::Hasher as _;
 
 // ============================================================================
 // HASHING UTILITIES
@@ -35,7 +41,8 @@ use std::hash::Hasher;
 /// which we extend to a 32-byte hash for pedagogical similarity to SHA-256.
 /// The returned String is heap-allocated and owned by the caller.
 ///
-/// NOTE: This is NOT cryptographically secure. In production, use sha2 crate.
+/// NOTE: This is NOT cryptographically secure. In production, use sha2 crate
+/// (see `Sha256Hasher` behind the `sha256` feature).
 pub fn hash_bytes(data: &[u8]) -> String {
     let mut hasher = DefaultHasher::new();
     hasher.write(data);
@@ -67,26 +74,210 @@ pub fn hash_pair(left: &str, right: &str) -> String {
     hash_bytes(combined.as_bytes())
 }
 
+// ============================================================================
+// HASHING STRATEGIES
+// ============================================================================
+
+/// A hash function a `MerkleTree` can be built with.
+///
+/// Implementors only have to provide `hash`; pairing defaults to hashing the
+/// two hex strings concatenated, which is what both built-in hashers use.
+pub trait Hasher {
+    /// Hashes raw bytes into a hex string.
+    fn hash(&self, data: &[u8]) -> String;
+
+    /// Combines a left and right child hash into their parent.
+    fn hash_pair(&self, l: &str, r: &str) -> String {
+        self.hash(format!("{}{}", l, r).as_bytes())
+    }
+}
+
+/// The original lab hasher built on std's `DefaultHasher`.
+///
+/// Fast and dependency-free, but NOT cryptographically secure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StdHasher;
+
+impl Hasher for StdHasher {
+    fn hash(&self, data: &[u8]) -> String {
+        hash_bytes(data)
+    }
+}
+
+/// SHA-256, as used by lab 52. Only compiled with the `sha256` feature,
+/// so the default build pulls in no hashing crates.
+#[cfg(feature = "sha256")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sha256Hasher;
+
+#[cfg(feature = "sha256")]
+impl Hasher for Sha256Hasher {
+    fn hash(&self, data: &[u8]) -> String {
+        use sha2::{Digest, Sha256};
+        bytes_to_hex(&Sha256::digest(data))
+    }
+}
+
+// ============================================================================
+// PROOFS
+// ============================================================================
+
+/// Which side of the running hash a proof sibling sits on.
+///
+/// Serialized as `"L"` / `"R"`: a proof is mostly hashes already, so the
+/// positions shouldn't add more than a byte each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum Position {
+    #[cfg_attr(feature = "json", serde(rename = "L"))]
+    Left,
+    #[cfg_attr(feature = "json", serde(rename = "R"))]
+    Right,
+}
+
+/// One sibling hash on the path from a leaf to the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProofStep {
+    pub hash: String,
+    pub position: Position,
+}
+
+/// An inclusion proof for the leaf at `leaf_index`, leaf level first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Proof {
+    pub leaf_index: usize,
+    pub steps: Vec<ProofStep>,
+}
+
+impl Proof {
+    /// Wraps a tuple proof from `generate_proof`; `true` means the sibling
+    /// is on the right.
+    pub fn from_tuples(leaf_index: usize, proof: &[(String, bool)]) -> Self {
+        let steps = proof
+            .iter()
+            .map(|(hash, is_right)| ProofStep {
+                hash: hash.clone(),
+                position: if *is_right {
+                    Position::Right
+                } else {
+                    Position::Left
+                },
+            })
+            .collect();
+        Proof { leaf_index, steps }
+    }
+
+    /// The steps in the tuple form `verify_proof` takes.
+    pub fn to_tuples(&self) -> Vec<(String, bool)> {
+        self.steps
+            .iter()
+            .map(|step| (step.hash.clone(), step.position == Position::Right))
+            .collect()
+    }
+
+    /// Whether `data` hashes up to `root` along this proof, using `StdHasher`.
+    pub fn verify(&self, root: &str, data: &str) -> bool {
+        self.verify_with(&StdHasher, root, data)
+    }
+
+    /// Like `verify`, hashing with `hasher`.
+    pub fn verify_with<H: Hasher>(&self, hasher: &H, root: &str, data: &str) -> bool {
+        let mut current_hash = hasher.hash(data.as_bytes());
+        for step in &self.steps {
+            current_hash = match step.position {
+                Position::Right => hasher.hash_pair(&current_hash, &step.hash),
+                Position::Left => hasher.hash_pair(&step.hash, &current_hash),
+            };
+        }
+        current_hash == root
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a proof is always serializable")
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("invalid proof JSON: {}", e))
+    }
+}
+
 // ============================================================================
 // MERKLE TREE
 // ============================================================================
 
-/// A Merkle tree that stores hashes at each level.
+/// A proof that several leaves belong to the same tree.
+///
+/// Holds only the sibling hashes the verifier cannot compute itself, in the
+/// order it will need them: level by level from the leaves up, left to
+/// right within a level.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MultiProof {
+    pub hashes: Vec<String>,
+}
+
+impl MultiProof {
+    /// Number of hashes carried by the proof.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+}
+
+/// A Merkle tree that stores hashes at each level, hashed with `H`.
 ///
 /// # Memory Model
-/// - `root`: Owned String on the heap (the root hash)
-/// - `leaves`: Vec<String> owning all leaf hashes
-/// - `nodes`: Vec<String> owning ALL nodes (leaves + internal + root)
+/// - `levels[0]`: the leaf hashes
+/// - `levels[k + 1]`: the parents of `levels[k]`, half as many (rounded up)
+/// - `levels.last()`: a single-element level holding the root
 ///
-/// When the MerkleTree is dropped, all Strings and Vecs are freed automatically.
+/// Keeping every level (instead of one flat `Vec` of nodes) means a parent's
+/// position is just `index / 2`, which is what makes O(log n) updates
+/// possible. `hasher` is usually a zero-sized unit struct, so carrying it
+/// costs nothing. When the MerkleTree is dropped, all Strings and Vecs are
+/// freed automatically.
 #[derive(Debug, Clone)]
-pub struct MerkleTree {
-    root: String,
-    leaves: Vec<String>,
-    nodes: Vec<String>,
+pub struct MerkleTree<H: Hasher = StdHasher> {
+    levels: Vec<Vec<String>>,
+    hasher: H,
+}
+
+impl MerkleTree<StdHasher> {
+    /// Creates a new Merkle tree from a slice of string data, using `StdHasher`.
+    pub fn new(data: &[&str]) -> Self {
+        Self::with_hasher(data, StdHasher)
+    }
+
+    /// Verifies a `StdHasher` Merkle proof for a given data item.
+    /// Returns true if the proof is valid (the data belongs to the tree).
+    pub fn verify_proof(root: &str, data: &str, proof: &[(String, bool)]) -> bool {
+        Self::verify_proof_with(&StdHasher, root, data, proof)
+    }
+
+    /// Loads a `StdHasher` tree saved with `to_json`; see `from_json_with`.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        Self::from_json_with(json, StdHasher)
+    }
+
+    /// Verifies a `StdHasher` multi-leaf proof; see `verify_multi_proof_with`.
+    pub fn verify_multi_proof(
+        root: &str,
+        items: &[(usize, &str)],
+        proof: &MultiProof,
+        leaf_count: usize,
+    ) -> bool {
+        Self::verify_multi_proof_with(&StdHasher, root, items, proof, leaf_count)
+    }
 }
 
-impl MerkleTree {
+impl<H: Hasher> MerkleTree<H> {
     /// Creates a new Merkle tree from a slice of string data.
     ///
     /// # Algorithm
@@ -94,72 +285,164 @@ impl MerkleTree {
     /// 2. Pair adjacent nodes and hash them together
     /// 3. Repeat until only one node remains (the root)
     /// 4. If a level has an odd number of nodes, the last node is promoted
-    pub fn new(data: &[&str]) -> Self {
-        if data.is_empty() {
+    pub fn with_hasher(data: &[&str], hasher: H) -> Self {
+        // Step 1: Hash all data items (leaf nodes)
+        let leaves: Vec<String> = data.iter().map(|d| hasher.hash(d.as_bytes())).collect();
+        Self::from_leaf_hashes(leaves, hasher)
+    }
+
+    /// Builds the levels above already-hashed leaves.
+    fn from_leaf_hashes(leaves: Vec<String>, hasher: H) -> Self {
+        if leaves.is_empty() {
             return MerkleTree {
-                root: String::new(),
-                leaves: vec![],
-                nodes: vec![],
+                levels: vec![],
+                hasher,
             };
         }
-
-        // Step 1: Hash all data items (leaf nodes)
-        let leaves: Vec<String> = data.iter().map(|d| hash_string(d)).collect();
-        let mut nodes = leaves.clone();
+        let mut levels = vec![leaves];
 
         // Step 2: Build tree bottom-up
-        let mut current_level = leaves.clone();
-
-        while current_level.len() > 1 {
-            let mut next_level = Vec::new();
+        while levels[levels.len() - 1].len() > 1 {
+            let current_level = &levels[levels.len() - 1];
+            let mut next_level = Vec::with_capacity(current_level.len().div_ceil(2));
 
             for i in (0..current_level.len()).step_by(2) {
                 if i + 1 < current_level.len() {
                     // Hash pair of nodes
-                    let parent = hash_pair(&current_level[i], &current_level[i + 1]);
-                    next_level.push(parent.clone());
-                    nodes.push(parent);
+                    next_level.push(hasher.hash_pair(&current_level[i], &current_level[i + 1]));
                 } else {
                     // Odd node: promote (duplicate) it
-                    let promoted = current_level[i].clone();
-                    next_level.push(promoted.clone());
-                    nodes.push(promoted);
+                    next_level.push(current_level[i].clone());
                 }
             }
 
-            current_level = next_level;
+            levels.push(next_level);
         }
 
-        MerkleTree {
-            root: current_level[0].clone(),
-            leaves,
-            nodes,
+        MerkleTree { levels, hasher }
+    }
+
+    /// Saves the tree as JSON: the leaf hashes plus the root they should
+    /// rebuild to. Inner levels are left out; `from_json_with` recomputes
+    /// them.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        let saved = SavedTree {
+            leaves: self.leaves().to_vec(),
+            expected_root: self.root().to_string(),
+        };
+        serde_json::to_string(&saved).expect("a tree is always serializable")
+    }
+
+    /// Loads a tree saved with `to_json`, rebuilding it with `hasher`.
+    ///
+    /// Fails if the JSON is malformed or the rebuilt root differs from the
+    /// stored `expected_root` (a tampered file, or the wrong hasher).
+    #[cfg(feature = "json")]
+    pub fn from_json_with(json: &str, hasher: H) -> Result<Self, String> {
+        let saved: SavedTree =
+            serde_json::from_str(json).map_err(|e| format!("invalid tree JSON: {}", e))?;
+        let tree = Self::from_leaf_hashes(saved.leaves, hasher);
+        if tree.root() != saved.expected_root {
+            return Err(format!(
+                "root mismatch: expected {}, rebuilt {}",
+                saved.expected_root,
+                tree.root()
+            ));
+        }
+        Ok(tree)
+    }
+
+    /// The hashing strategy this tree was built with.
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Adds a leaf to the right edge of the tree.
+    ///
+    /// Only the rightmost path changes, so this rehashes one node per level.
+    /// The resulting root equals `MerkleTree::new` over the same data.
+    pub fn append(&mut self, data: &str) {
+        if self.levels.is_empty() {
+            self.levels.push(Vec::new());
+        }
+        let leaf = self.hasher.hash(data.as_bytes());
+        self.levels[0].push(leaf);
+        let index = self.levels[0].len() - 1;
+        self.recompute_path(index);
+    }
+
+    /// Replaces the data of the leaf at `index` and rehashes its path.
+    pub fn update_leaf(&mut self, index: usize, new_data: &str) -> Result<(), String> {
+        if index >= self.leaf_count() {
+            return Err(format!(
+                "leaf index {} out of bounds (tree has {} leaves)",
+                index,
+                self.leaf_count()
+            ));
+        }
+        self.levels[0][index] = self.hasher.hash(new_data.as_bytes());
+        self.recompute_path(index);
+        Ok(())
+    }
+
+    /// Recomputes every ancestor of the leaf at `leaf_index`.
+    ///
+    /// A parent that does not exist yet (the leaf was just appended) is
+    /// pushed; an existing one is overwritten. A new level is added on top
+    /// whenever the old root gains a sibling.
+    fn recompute_path(&mut self, leaf_index: usize) {
+        let mut level = 0;
+        let mut index = leaf_index;
+
+        while self.levels[level].len() > 1 {
+            let nodes = &self.levels[level];
+            let left = index & !1;
+            let parent = match nodes.get(left + 1) {
+                Some(right) => self.hasher.hash_pair(&nodes[left], right),
+                None => nodes[left].clone(),
+            };
+
+            index /= 2;
+            if level + 1 == self.levels.len() {
+                self.levels.push(Vec::new());
+            }
+            let parents = &mut self.levels[level + 1];
+            if index < parents.len() {
+                parents[index] = parent;
+            } else {
+                parents.push(parent);
+            }
+            level += 1;
         }
     }
 
     /// Returns the Merkle root hash.
     pub fn root(&self) -> &str {
-        &self.root
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .map_or("", |root| root.as_str())
     }
 
     /// Returns all leaf hashes.
     pub fn leaves(&self) -> &[String] {
-        &self.leaves
+        self.levels.first().map_or(&[], |leaves| leaves.as_slice())
     }
 
     /// Returns the total number of nodes (leaves + internal).
     pub fn node_count(&self) -> usize {
-        self.nodes.len()
+        self.levels.iter().map(Vec::len).sum()
     }
 
     /// Returns the number of leaf nodes.
     pub fn leaf_count(&self) -> usize {
-        self.leaves.len()
+        self.leaves().len()
     }
 
     /// Returns true if the tree is empty (no data).
     pub fn is_empty(&self) -> bool {
-        self.leaves.is_empty()
+        self.leaves().is_empty()
     }
 
     /// Generates a Merkle proof (list of sibling hashes) for the leaf at the given index.
@@ -168,61 +451,156 @@ impl MerkleTree {
     /// # Teaching Note
     /// A Merkle proof allows verification that a specific data item is part of the tree
     /// without needing all the data -- only O(log n) hashes are required.
+    /// With every level stored, the sibling at each level is simply `index ^ 1`.
     pub fn generate_proof(&self, leaf_index: usize) -> Option<Vec<(String, bool)>> {
-        if leaf_index >= self.leaves.len() {
+        self.prove(leaf_index).map(|proof| proof.to_tuples())
+    }
+
+    /// Like `generate_proof`, but returns a self-describing `Proof`.
+    pub fn prove(&self, leaf_index: usize) -> Option<Proof> {
+        if leaf_index >= self.leaf_count() {
             return None;
         }
 
-        let mut proof = Vec::new();
-        let mut current_level: Vec<String> = self.leaves.clone();
+        let mut steps = Vec::new();
         let mut index = leaf_index;
 
-        while current_level.len() > 1 {
-            let mut next_level = Vec::new();
+        // Every level except the root contributes at most one sibling.
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            if let Some(hash) = level.get(sibling) {
+                steps.push(ProofStep {
+                    hash: hash.clone(),
+                    position: if sibling > index {
+                        Position::Right
+                    } else {
+                        Position::Left
+                    },
+                });
+            }
+            // Odd node with no sibling: it was promoted, nothing to record.
+            index /= 2;
+        }
 
-            for i in (0..current_level.len()).step_by(2) {
-                if i + 1 < current_level.len() {
-                    let parent = hash_pair(&current_level[i], &current_level[i + 1]);
-                    next_level.push(parent);
-
-                    // If this pair contains our index, record the sibling
-                    if i == index {
-                        // Sibling is on the right
-                        proof.push((current_level[i + 1].clone(), true));
-                    } else if i + 1 == index {
-                        // Sibling is on the left
-                        proof.push((current_level[i].clone(), false));
-                    }
-                } else {
-                    // Odd node: no sibling to record
-                    next_level.push(current_level[i].clone());
+        Some(Proof { leaf_index, steps })
+    }
+
+    /// Generates one proof covering every leaf in `leaf_indices`.
+    ///
+    /// Indices may come in any order and may repeat. Returns None if the
+    /// list is empty or any index is out of bounds.
+    pub fn generate_multi_proof(&self, leaf_indices: &[usize]) -> Option<MultiProof> {
+        let mut known: Vec<usize> = leaf_indices.to_vec();
+        known.sort_unstable();
+        known.dedup();
+        if known.is_empty() || known[known.len() - 1] >= self.leaf_count() {
+            return None;
+        }
+
+        let mut hashes = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            let mut parents = Vec::with_capacity(known.len());
+            let mut i = 0;
+            while i < known.len() {
+                let index = known[i];
+                let sibling = index ^ 1;
+                if known.get(i + 1) == Some(&sibling) {
+                    // Both children are known: the parent costs nothing.
+                    i += 1;
+                } else if let Some(hash) = level.get(sibling) {
+                    hashes.push(hash.clone());
                 }
+                parents.push(index / 2);
+                i += 1;
             }
-
-            index /= 2;
-            current_level = next_level;
+            known = parents;
         }
 
-        Some(proof)
+        Some(MultiProof { hashes })
     }
 
-    /// Verifies a Merkle proof for a given data item.
-    /// Returns true if the proof is valid (the data belongs to the tree).
-    pub fn verify_proof(root: &str, data: &str, proof: &[(String, bool)]) -> bool {
-        let mut current_hash = hash_string(data);
+    /// Verifies, using `hasher`, that every `(index, data)` pair belongs to
+    /// the tree with this `root` and `leaf_count` leaves.
+    ///
+    /// Rebuilds the root level by level, taking a sibling from the known
+    /// nodes when it is there and from the proof otherwise. The proof must
+    /// be used up exactly -- leftover hashes mean it was built for a
+    /// different set of leaves.
+    pub fn verify_multi_proof_with(
+        hasher: &H,
+        root: &str,
+        items: &[(usize, &str)],
+        proof: &MultiProof,
+        leaf_count: usize,
+    ) -> bool {
+        let mut known: Vec<(usize, String)> = items
+            .iter()
+            .map(|(index, data)| (*index, hasher.hash(data.as_bytes())))
+            .collect();
+        known.sort_by_key(|(index, _)| *index);
+        // The same index twice is fine if the data agrees.
+        for pair in known.windows(2) {
+            if pair[0].0 == pair[1].0 && pair[0].1 != pair[1].1 {
+                return false;
+            }
+        }
+        known.dedup_by_key(|(index, _)| *index);
+        if known.is_empty() || known[known.len() - 1].0 >= leaf_count {
+            return false;
+        }
 
-        for (sibling_hash, is_right) in proof {
-            if *is_right {
-                current_hash = hash_pair(&current_hash, sibling_hash);
-            } else {
-                current_hash = hash_pair(sibling_hash, &current_hash);
+        let mut proof_hashes = proof.hashes.iter();
+        let mut level_len = leaf_count;
+        while level_len > 1 {
+            let mut parents = Vec::with_capacity(known.len());
+            let mut nodes = known.into_iter().peekable();
+            while let Some((index, hash)) = nodes.next() {
+                let sibling = index ^ 1;
+                let parent = if sibling >= level_len {
+                    // Last node of an odd level: promoted unchanged, as in `new`.
+                    hash
+                } else {
+                    let sibling_hash = match nodes.next_if(|(i, _)| *i == sibling) {
+                        Some((_, known_hash)) => known_hash,
+                        None => match proof_hashes.next() {
+                            Some(proof_hash) => proof_hash.clone(),
+                            None => return false,
+                        },
+                    };
+                    if index % 2 == 0 {
+                        hasher.hash_pair(&hash, &sibling_hash)
+                    } else {
+                        hasher.hash_pair(&sibling_hash, &hash)
+                    }
+                };
+                parents.push((index / 2, parent));
             }
+            known = parents;
+            level_len = level_len.div_ceil(2);
         }
 
-        current_hash == root
+        proof_hashes.next().is_none() && known.len() == 1 && known[0].1 == root
+    }
+
+    /// Verifies a Merkle proof for a given data item, hashing with `hasher`.
+    /// Returns true if the proof is valid (the data belongs to the tree).
+    ///
+    /// The hasher must match the one the tree was built with; a proof
+    /// never verifies under a different hash function.
+    pub fn verify_proof_with(hasher: &H, root: &str, data: &str, proof: &[(String, bool)]) -> bool {
+        // The tuple form carries no leaf index, and verification needs none.
+        Proof::from_tuples(0, proof).verify_with(hasher, root, data)
     }
 }
 
+/// What `MerkleTree::to_json` writes.
+#[cfg(feature = "json")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SavedTree {
+    leaves: Vec<String>,
+    expected_root: String,
+}
+
 // ============================================================================
 // UNIT TESTS
 // ============================================================================
@@ -282,3 +282,425 @@ fn test_tree_eight_leaves() {
         assert!(MerkleTree::verify_proof(tree.root(), item, &proof));
     }
 }
+
+// ============================================================================
+// INCREMENTAL UPDATES (append / update_leaf)
+// ============================================================================
+
+fn items(n: usize) -> Vec<String> {
+    (0..n).map(|i| format!("tx{}", i)).collect()
+}
+
+fn build(data: &[String]) -> MerkleTree {
+    let refs: Vec<&str> = data.iter().map(String::as_str).collect();
+    MerkleTree::new(&refs)
+}
+
+#[test]
+fn test_append_matches_full_rebuild() {
+    let data = items(33);
+    let mut tree = MerkleTree::new(&[]);
+    for (i, item) in data.iter().enumerate() {
+        tree.append(item);
+        let rebuilt = build(&data[..=i]);
+        assert_eq!(
+            tree.root(),
+            rebuilt.root(),
+            "root differs after {} appends",
+            i + 1
+        );
+        assert_eq!(tree.node_count(), rebuilt.node_count());
+        assert_eq!(tree.leaves(), rebuilt.leaves());
+    }
+}
+
+#[test]
+fn test_append_onto_existing_tree() {
+    let mut tree = MerkleTree::new(&["a", "b", "c"]);
+    tree.append("d");
+    tree.append("e");
+    assert_eq!(
+        tree.root(),
+        MerkleTree::new(&["a", "b", "c", "d", "e"]).root()
+    );
+    assert_eq!(tree.leaf_count(), 5);
+}
+
+#[test]
+fn test_proofs_after_appends() {
+    let data = items(11);
+    let mut tree = MerkleTree::new(&[]);
+    for item in &data {
+        tree.append(item);
+    }
+    let rebuilt = build(&data);
+    for (i, item) in data.iter().enumerate() {
+        let proof = tree.generate_proof(i).unwrap();
+        assert_eq!(Some(proof.clone()), rebuilt.generate_proof(i));
+        assert!(MerkleTree::verify_proof(tree.root(), item, &proof));
+        assert!(!MerkleTree::verify_proof(tree.root(), "forged", &proof));
+    }
+}
+
+#[test]
+fn test_update_leaf_matches_rebuild() {
+    let mut data = items(7);
+    let mut tree = build(&data);
+
+    tree.update_leaf(4, "patched").unwrap();
+    data[4] = "patched".to_string();
+    assert_eq!(tree.root(), build(&data).root());
+
+    // The promoted last leaf has no sibling; updating it must still
+    // propagate all the way up.
+    tree.update_leaf(6, "tail").unwrap();
+    data[6] = "tail".to_string();
+    assert_eq!(tree.root(), build(&data).root());
+
+    let proof = tree.generate_proof(4).unwrap();
+    assert!(MerkleTree::verify_proof(tree.root(), "patched", &proof));
+    assert!(!MerkleTree::verify_proof(tree.root(), "tx4", &proof));
+}
+
+#[test]
+fn test_update_leaf_out_of_bounds() {
+    let mut tree = MerkleTree::new(&["a", "b"]);
+    let before = tree.root().to_string();
+    assert!(tree.update_leaf(2, "c").is_err());
+    assert!(MerkleTree::new(&[]).update_leaf(0, "x").is_err());
+    assert_eq!(tree.root(), before);
+}
+
+#[test]
+fn test_update_single_leaf_tree() {
+    let mut tree = MerkleTree::new(&["only"]);
+    tree.update_leaf(0, "changed").unwrap();
+    assert_eq!(tree.root(), hash_string("changed"));
+    assert_eq!(tree.node_count(), 1);
+}
+
+// ============================================================================
+// MULTI-LEAF PROOFS
+// ============================================================================
+
+fn with_data<'a>(data: &'a [String], indices: &[usize]) -> Vec<(usize, &'a str)> {
+    indices.iter().map(|&i| (i, data[i].as_str())).collect()
+}
+
+#[test]
+fn test_multi_proof_adjacent_leaves_share_parent() {
+    let data = items(8);
+    let tree = build(&data);
+
+    let proof = tree.generate_multi_proof(&[2, 3]).unwrap();
+    let single_total =
+        tree.generate_proof(2).unwrap().len() + tree.generate_proof(3).unwrap().len();
+    assert_eq!(proof.len(), 2);
+    assert!(proof.len() < single_total);
+    assert!(MerkleTree::verify_multi_proof(
+        tree.root(),
+        &with_data(&data, &[2, 3]),
+        &proof,
+        8
+    ));
+}
+
+#[test]
+fn test_multi_proof_non_adjacent_leaves() {
+    let data = items(16);
+    let tree = build(&data);
+
+    // Unsorted with a duplicate: both are normalized.
+    let proof = tree.generate_multi_proof(&[13, 1, 6, 1]).unwrap();
+    assert_eq!(proof, tree.generate_multi_proof(&[1, 6, 13]).unwrap());
+    assert!(MerkleTree::verify_multi_proof(
+        tree.root(),
+        &with_data(&data, &[6, 13, 1]),
+        &proof,
+        16
+    ));
+}
+
+#[test]
+fn test_multi_proof_all_leaves_is_empty() {
+    let data = items(6);
+    let tree = build(&data);
+    let all: Vec<usize> = (0..6).collect();
+
+    let proof = tree.generate_multi_proof(&all).unwrap();
+    assert!(proof.is_empty());
+    assert!(MerkleTree::verify_multi_proof(
+        tree.root(),
+        &with_data(&data, &all),
+        &proof,
+        6
+    ));
+}
+
+#[test]
+fn test_multi_proof_tampered_leaf_fails() {
+    let data = items(8);
+    let tree = build(&data);
+    let proof = tree.generate_multi_proof(&[0, 5]).unwrap();
+
+    assert!(!MerkleTree::verify_multi_proof(
+        tree.root(),
+        &[(0, "tx0"), (5, "tx5-forged")],
+        &proof,
+        8
+    ));
+    // Right data, wrong positions.
+    assert!(!MerkleTree::verify_multi_proof(
+        tree.root(),
+        &[(1, "tx0"), (5, "tx5")],
+        &proof,
+        8
+    ));
+    // A proof for other leaves does not transfer.
+    assert!(!MerkleTree::verify_multi_proof(
+        tree.root(),
+        &[(0, "tx0")],
+        &proof,
+        8
+    ));
+}
+
+#[test]
+fn test_multi_proof_odd_leaf_promotion_matches_new() {
+    for n in 1..=9 {
+        let data = items(n);
+        let tree = build(&data);
+        let last = n - 1;
+        let indices = if n > 1 { vec![0, last] } else { vec![0] };
+
+        let proof = tree.generate_multi_proof(&indices).unwrap();
+        assert!(
+            MerkleTree::verify_multi_proof(tree.root(), &with_data(&data, &indices), &proof, n),
+            "multi proof failed for {} leaves",
+            n
+        );
+    }
+}
+
+#[test]
+fn test_multi_proof_out_of_range() {
+    let tree = build(&items(4));
+    assert!(tree.generate_multi_proof(&[1, 4]).is_none());
+    assert!(tree.generate_multi_proof(&[]).is_none());
+    assert!(MerkleTree::new(&[]).generate_multi_proof(&[0]).is_none());
+
+    let proof = tree.generate_multi_proof(&[1]).unwrap();
+    assert!(!MerkleTree::verify_multi_proof(
+        tree.root(),
+        &[(4, "tx4")],
+        &proof,
+        4
+    ));
+    assert!(!MerkleTree::verify_multi_proof(tree.root(), &[], &proof, 4));
+}
+
+#[test]
+fn test_multi_proof_after_append() {
+    let data = items(10);
+    let mut tree = build(&data[..7]);
+    for item in &data[7..] {
+        tree.append(item);
+    }
+    let proof = tree.generate_multi_proof(&[3, 8, 9]).unwrap();
+    assert!(MerkleTree::verify_multi_proof(
+        tree.root(),
+        &with_data(&data, &[3, 8, 9]),
+        &proof,
+        10
+    ));
+}
+
+// ============================================================================
+// PLUGGABLE HASHERS
+// ============================================================================
+
+/// A toy strategy: keeps the tests independent of any hashing crate while
+/// still proving the tree only talks to `H`.
+#[derive(Debug, Clone, Copy, Default)]
+struct ReverseHasher;
+
+impl Hasher for ReverseHasher {
+    fn hash(&self, data: &[u8]) -> String {
+        hash_bytes(&data.iter().rev().copied().collect::<Vec<u8>>())
+    }
+}
+
+#[test]
+fn test_new_uses_std_hasher() {
+    let data = ["a", "b", "c"];
+    let tree = MerkleTree::new(&data);
+    let explicit = MerkleTree::with_hasher(&data, StdHasher);
+    assert_eq!(tree.root(), explicit.root());
+    assert_eq!(StdHasher.hash(b"a"), hash_string("a"));
+    assert_eq!(StdHasher.hash_pair("x", "y"), hash_pair("x", "y"));
+}
+
+#[test]
+fn test_different_hashers_give_different_roots() {
+    let data = ["tx1", "tx2", "tx3", "tx4"];
+    let std_tree = MerkleTree::new(&data);
+    let other = MerkleTree::with_hasher(&data, ReverseHasher);
+    assert_ne!(std_tree.root(), other.root());
+    assert_eq!(std_tree.node_count(), other.node_count());
+}
+
+#[test]
+fn test_proofs_verify_under_their_own_hasher_only() {
+    let data = ["tx1", "tx2", "tx3", "tx4", "tx5"];
+    let tree = MerkleTree::with_hasher(&data, ReverseHasher);
+
+    for (i, item) in data.iter().enumerate() {
+        let proof = tree.generate_proof(i).unwrap();
+        assert!(MerkleTree::verify_proof_with(
+            tree.hasher(),
+            tree.root(),
+            item,
+            &proof
+        ));
+        assert!(!MerkleTree::verify_proof(tree.root(), item, &proof));
+    }
+
+    let multi = tree.generate_multi_proof(&[0, 4]).unwrap();
+    assert!(MerkleTree::verify_multi_proof_with(
+        &ReverseHasher,
+        tree.root(),
+        &[(0, "tx1"), (4, "tx5")],
+        &multi,
+        5
+    ));
+}
+
+#[test]
+fn test_append_with_custom_hasher_matches_rebuild() {
+    let mut tree = MerkleTree::with_hasher(&[], ReverseHasher);
+    for item in ["a", "b", "c"] {
+        tree.append(item);
+    }
+    assert_eq!(
+        tree.root(),
+        MerkleTree::with_hasher(&["a", "b", "c"], ReverseHasher).root()
+    );
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn test_sha256_two_leaf_root_matches_hand_computed() {
+    // sha256("alice->bob:5"), sha256("bob->carol:2"), and the SHA-256 of
+    // those two hex strings concatenated -- computed independently.
+    let left = "18a2ab4d82f90f0c38c8b3c911a9f7bced4c386a453782eea46d52c2845d555e";
+    let right = "7b5c3411fe81abb2852052757f22a4cb91a7a9a3658407312f2a77bbbf3e9f08";
+    let root = "4f22d51d401b88013ee1138942fc7d3568e7332650e811a8f0329964924565ce";
+
+    let tree = MerkleTree::with_hasher(&["alice->bob:5", "bob->carol:2"], Sha256Hasher);
+    assert_eq!(tree.leaves(), [left, right]);
+    assert_eq!(tree.root(), root);
+    assert_ne!(
+        tree.root(),
+        MerkleTree::new(&["alice->bob:5", "bob->carol:2"]).root()
+    );
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn test_sha256_proofs_verify() {
+    let data = ["tx1", "tx2", "tx3", "tx4", "tx5", "tx6"];
+    let tree = MerkleTree::with_hasher(&data, Sha256Hasher);
+    for (i, item) in data.iter().enumerate() {
+        let proof = tree.generate_proof(i).unwrap();
+        assert!(MerkleTree::verify_proof_with(
+            &Sha256Hasher,
+            tree.root(),
+            item,
+            &proof
+        ));
+    }
+}
+
+// ============================================================================
+// PORTABLE PROOFS AND JSON
+// ============================================================================
+
+#[test]
+fn test_prove_matches_tuple_proof() {
+    let data = ["tx1", "tx2", "tx3", "tx4", "tx5"];
+    let tree = MerkleTree::new(&data);
+    for (i, item) in data.iter().enumerate() {
+        let proof = tree.prove(i).unwrap();
+        let tuples = tree.generate_proof(i).unwrap();
+        assert_eq!(proof.leaf_index, i);
+        assert_eq!(proof.to_tuples(), tuples);
+        assert_eq!(Proof::from_tuples(i, &tuples), proof);
+        assert!(proof.verify(tree.root(), item));
+        assert!(MerkleTree::verify_proof(tree.root(), item, &tuples));
+    }
+    assert!(tree.prove(data.len()).is_none());
+}
+
+#[test]
+fn test_proof_positions() {
+    let tree = MerkleTree::new(&["a", "b", "c"]);
+    let positions = |i| -> Vec<Position> {
+        tree.prove(i)
+            .unwrap()
+            .steps
+            .iter()
+            .map(|step| step.position)
+            .collect()
+    };
+    assert_eq!(positions(0), [Position::Right, Position::Right]);
+    assert_eq!(positions(1), [Position::Left, Position::Right]);
+    // "c" is promoted past the first level, so it has only one sibling.
+    assert_eq!(positions(2), [Position::Left]);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_proof_json_round_trip() {
+    let tree = MerkleTree::new(&["tx1", "tx2", "tx3", "tx4"]);
+    let proof = tree.prove(2).unwrap();
+    let json = proof.to_json();
+    assert!(json.contains(r#""position":"L""#));
+    assert!(json.contains(r#""position":"R""#));
+
+    let loaded = Proof::from_json(&json).unwrap();
+    assert_eq!(loaded, proof);
+    assert!(loaded.verify(tree.root(), "tx3"));
+    assert!(!loaded.verify(tree.root(), "tx4"));
+
+    assert!(Proof::from_json("{not json").is_err());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_tree_json_round_trip() {
+    let tree = MerkleTree::new(&["tx1", "tx2", "tx3", "tx4", "tx5"]);
+    let loaded = MerkleTree::from_json(&tree.to_json()).unwrap();
+    assert_eq!(loaded.root(), tree.root());
+    assert_eq!(loaded.leaves(), tree.leaves());
+    assert_eq!(loaded.node_count(), tree.node_count());
+
+    let empty = MerkleTree::new(&[]);
+    assert!(MerkleTree::from_json(&empty.to_json()).unwrap().is_empty());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_tree_json_detects_tampering() {
+    let tree = MerkleTree::new(&["tx1", "tx2", "tx3"]);
+    let json = tree.to_json();
+
+    let tampered_root = json.replace(tree.root(), &hash_string("forged"));
+    let err = MerkleTree::from_json(&tampered_root).unwrap_err();
+    assert!(err.contains("root mismatch"), "{}", err);
+
+    let tampered_leaf = json.replacen(&tree.leaves()[1], &hash_string("forged"), 1);
+    assert!(MerkleTree::from_json(&tampered_leaf).is_err());
+
+    // Loading with the wrong hasher rebuilds a different root.
+    assert!(MerkleTree::from_json_with(&json, ReverseHasher).is_err());
+}